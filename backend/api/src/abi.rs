@@ -0,0 +1,988 @@
+//! Contract interfaces extracted from published WASM.
+//!
+//! Soroban contracts embed their interface in a `contractspecv0` custom
+//! section: a stream of XDR-encoded `ScSpecEntry` values. On publish the
+//! section is read with `wasmparser`, the function and user-defined type
+//! entries are decoded, and the result is stored per version in `contract_abis`. WASM without a
+//! readable spec stores an empty ABI with `parsed: false` and a note; it
+//! never fails the publish.
+//!
+//! Contracts can be searched by the functions they export, by bare name or
+//! full signature, through a GIN index on `abi -> 'functions'`.
+
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use shared::Contract;
+use sqlx::{types::Json as DbJson, PgExecutor, PgPool, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::{db_internal_error, fetch_visible_contract, push_visibility_filter},
+    pagination::{Paginated, PaginatedFunctionMatches, DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT},
+    share_tokens::PresentedShareToken,
+    state::AppState,
+};
+
+/// Custom section holding the contract spec
+const SPEC_SECTION: &str = "contractspecv0";
+/// Deepest type nesting decoded (`Option<Vec<Map<..>>>`, ...)
+const MAX_TYPE_DEPTH: usize = 16;
+
+/// Interface of one contract version
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct ContractAbi {
+    /// Whether a contract spec was found and decoded
+    pub parsed: bool,
+    /// Why `functions` is empty when `parsed` is false
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    pub functions: Vec<AbiFunction>,
+    /// User-defined types the functions can refer to by name. Absent from
+    /// ABIs stored before types were decoded.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<AbiType>,
+}
+
+/// An exported contract function
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AbiFunction {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub doc: Option<String>,
+    pub inputs: Vec<AbiParam>,
+    /// Return types, rendered like the inputs' (`void` functions have none)
+    pub outputs: Vec<String>,
+}
+
+/// A function argument
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AbiParam {
+    pub name: String,
+    /// Soroban type as written in Rust, e.g. `Address`, `Option<i128>`,
+    /// `BytesN<32>` or a user-defined type's name
+    #[serde(rename = "type")]
+    pub type_name: String,
+}
+
+/// A user-defined type from the spec. Docs are left out, so only changes
+/// that reach the wire make two definitions differ.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AbiType {
+    pub name: String,
+    pub kind: AbiTypeKind,
+    /// Struct fields, in declaration order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fields: Vec<AbiParam>,
+    /// Union, enum or error enum cases, in declaration order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cases: Vec<AbiCase>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum AbiTypeKind {
+    Struct,
+    Union,
+    Enum,
+    ErrorEnum,
+}
+
+/// A case of a union (with its payload types) or of an enum (with its value)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct AbiCase {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub types: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<u32>,
+}
+
+impl AbiType {
+    /// Every type the definition mentions: field types and case payloads
+    pub fn referenced_types(&self) -> impl Iterator<Item = &str> {
+        self.fields
+            .iter()
+            .map(|field| field.type_name.as_str())
+            .chain(
+                self.cases
+                    .iter()
+                    .flat_map(|case| case.types.iter().map(String::as_str)),
+            )
+    }
+}
+
+impl AbiFunction {
+    /// `name(T1, T2) -> R`, the form returned by function search
+    pub fn signature(&self) -> String {
+        let inputs: Vec<&str> = self.inputs.iter().map(|p| p.type_name.as_str()).collect();
+        let mut signature = format!("{}({})", self.name, inputs.join(", "));
+        if !self.outputs.is_empty() {
+            signature.push_str(" -> ");
+            signature.push_str(&self.outputs.join(", "));
+        }
+        signature
+    }
+}
+
+impl ContractAbi {
+    fn unparsed(note: String) -> Self {
+        Self {
+            parsed: false,
+            note: Some(note),
+            functions: Vec::new(),
+            types: Vec::new(),
+        }
+    }
+
+    /// Extract the interface of `wasm`. Never fails: problems are recorded in
+    /// `note` with `parsed: false`.
+    pub fn from_wasm(wasm: &[u8]) -> Self {
+        let spec = match spec_section(wasm) {
+            Ok(Some(spec)) => spec,
+            Ok(None) => {
+                return Self::unparsed(format!("WASM has no {} section", SPEC_SECTION));
+            }
+            Err(err) => return Self::unparsed(format!("WASM could not be parsed: {}", err)),
+        };
+        match decode_spec(&spec) {
+            Ok((functions, types)) => Self {
+                parsed: true,
+                note: None,
+                functions,
+                types,
+            },
+            Err(err) => Self::unparsed(format!("Contract spec could not be decoded: {}", err)),
+        }
+    }
+}
+
+/// The concatenated `contractspecv0` sections, if any
+fn spec_section(wasm: &[u8]) -> Result<Option<Vec<u8>>, wasmparser::BinaryReaderError> {
+    let mut spec: Option<Vec<u8>> = None;
+    for payload in wasmparser::Parser::new(0).parse_all(wasm) {
+        if let wasmparser::Payload::CustomSection(reader) = payload? {
+            if reader.name() == SPEC_SECTION {
+                spec.get_or_insert_with(Vec::new)
+                    .extend_from_slice(reader.data());
+            }
+        }
+    }
+    Ok(spec)
+}
+
+/// Minimal XDR reader for `ScSpecEntry` streams
+struct Xdr<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Xdr<'a> {
+    fn remaining(&self) -> usize {
+        self.bytes.len() - self.pos
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], String> {
+        if len > self.remaining() {
+            return Err(format!("truncated at byte {}", self.pos));
+        }
+        let slice = &self.bytes[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, String> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Variable-length opaque data padded to a multiple of four bytes
+    fn string(&mut self) -> Result<String, String> {
+        let len = self.u32()? as usize;
+        let bytes = self.take(len)?;
+        self.take((4 - len % 4) % 4)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|_| format!("invalid UTF-8 before byte {}", self.pos))
+    }
+
+    fn vec<T>(
+        &mut self,
+        mut item: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        let len = self.u32()? as usize;
+        // Every element takes at least four bytes
+        if len > self.remaining() / 4 {
+            return Err(format!("array length {} exceeds the input", len));
+        }
+        (0..len).map(|_| item(self)).collect()
+    }
+
+    /// `ScSpecTypeDef`, rendered as its Rust spelling
+    fn type_def(&mut self, depth: usize) -> Result<String, String> {
+        if depth > MAX_TYPE_DEPTH {
+            return Err("type nesting too deep".to_string());
+        }
+        let inner = |xdr: &mut Self| xdr.type_def(depth + 1);
+        let name = match self.u32()? {
+            0 => "Val".to_string(),
+            1 => "bool".to_string(),
+            2 => "void".to_string(),
+            3 => "Error".to_string(),
+            4 => "u32".to_string(),
+            5 => "i32".to_string(),
+            6 => "u64".to_string(),
+            7 => "i64".to_string(),
+            8 => "Timepoint".to_string(),
+            9 => "Duration".to_string(),
+            10 => "u128".to_string(),
+            11 => "i128".to_string(),
+            12 => "u256".to_string(),
+            13 => "i256".to_string(),
+            14 => "Bytes".to_string(),
+            16 => "String".to_string(),
+            17 => "Symbol".to_string(),
+            19 => "Address".to_string(),
+            20 => "MuxedAddress".to_string(),
+            1000 => format!("Option<{}>", inner(self)?),
+            1001 => {
+                let ok = inner(self)?;
+                format!("Result<{}, {}>", ok, inner(self)?)
+            }
+            1002 => format!("Vec<{}>", inner(self)?),
+            1004 => {
+                let key = inner(self)?;
+                format!("Map<{}, {}>", key, inner(self)?)
+            }
+            1005 => format!("({})", self.vec(inner)?.join(", ")),
+            1006 => format!("BytesN<{}>", self.u32()?),
+            2000 => self.string()?,
+            other => return Err(format!("unknown spec type {}", other)),
+        };
+        Ok(name)
+    }
+}
+
+/// Decode every entry, keeping the functions and user-defined types. Events
+/// are read only to step over them.
+fn decode_spec(spec: &[u8]) -> Result<(Vec<AbiFunction>, Vec<AbiType>), String> {
+    let mut xdr = Xdr {
+        bytes: spec,
+        pos: 0,
+    };
+    let mut functions = Vec::new();
+    let mut types = Vec::new();
+    while xdr.remaining() > 0 {
+        match xdr.u32()? {
+            // FunctionV0 { doc, name, inputs, outputs }
+            0 => {
+                let doc = xdr.string()?;
+                let name = xdr.string()?;
+                let inputs = xdr.vec(|xdr| {
+                    xdr.string()?;
+                    Ok(AbiParam {
+                        name: xdr.string()?,
+                        type_name: xdr.type_def(0)?,
+                    })
+                })?;
+                let outputs = xdr.vec(|xdr| xdr.type_def(0))?;
+                functions.push(AbiFunction {
+                    name,
+                    doc: Some(doc).filter(|doc| !doc.is_empty()),
+                    inputs,
+                    outputs,
+                });
+            }
+            // UdtStructV0 { doc, lib, name, fields { doc, name, type } }
+            1 => {
+                let name = udt_name(&mut xdr)?;
+                let fields = xdr.vec(|xdr| {
+                    xdr.string()?;
+                    Ok(AbiParam {
+                        name: xdr.string()?,
+                        type_name: xdr.type_def(0)?,
+                    })
+                })?;
+                types.push(AbiType {
+                    name,
+                    kind: AbiTypeKind::Struct,
+                    fields,
+                    cases: Vec::new(),
+                });
+            }
+            // UdtUnionV0 { doc, lib, name, cases: Void { doc, name } | Tuple { doc, name, types } }
+            2 => {
+                let name = udt_name(&mut xdr)?;
+                let cases = xdr.vec(|xdr| {
+                    let kind = xdr.u32()?;
+                    xdr.string()?;
+                    let name = xdr.string()?;
+                    let types = match kind {
+                        0 => Vec::new(),
+                        1 => xdr.vec(|xdr| xdr.type_def(0))?,
+                        other => return Err(format!("unknown union case kind {}", other)),
+                    };
+                    Ok(AbiCase {
+                        name,
+                        types,
+                        value: None,
+                    })
+                })?;
+                types.push(AbiType {
+                    name,
+                    kind: AbiTypeKind::Union,
+                    fields: Vec::new(),
+                    cases,
+                });
+            }
+            // UdtEnumV0 / UdtErrorEnumV0 { doc, lib, name, cases { doc, name, value } }
+            kind @ (3 | 4) => {
+                let name = udt_name(&mut xdr)?;
+                let cases = xdr.vec(|xdr| {
+                    xdr.string()?;
+                    Ok(AbiCase {
+                        name: xdr.string()?,
+                        types: Vec::new(),
+                        value: Some(xdr.u32()?),
+                    })
+                })?;
+                types.push(AbiType {
+                    name,
+                    kind: if kind == 3 {
+                        AbiTypeKind::Enum
+                    } else {
+                        AbiTypeKind::ErrorEnum
+                    },
+                    fields: Vec::new(),
+                    cases,
+                });
+            }
+            // EventV0 { doc, lib, name, prefix_topics, params { doc, name, type, location }, data_format }
+            5 => {
+                skip_strings(&mut xdr, 3)?;
+                xdr.vec(|xdr| xdr.string())?;
+                xdr.vec(|xdr| {
+                    skip_strings(xdr, 2)?;
+                    xdr.type_def(0)?;
+                    xdr.u32()
+                })?;
+                xdr.u32()?;
+            }
+            other => return Err(format!("unknown spec entry kind {}", other)),
+        }
+    }
+    Ok((functions, types))
+}
+
+/// The name from a UDT's `{ doc, lib, name }` header
+fn udt_name(xdr: &mut Xdr<'_>) -> Result<String, String> {
+    skip_strings(xdr, 2)?;
+    xdr.string()
+}
+
+fn skip_strings(xdr: &mut Xdr<'_>, count: usize) -> Result<(), String> {
+    for _ in 0..count {
+        xdr.string()?;
+    }
+    Ok(())
+}
+
+/// Store the ABI of a newly published version
+pub async fn store<'e>(
+    executor: impl PgExecutor<'e>,
+    contract_id: Uuid,
+    version: &str,
+    abi: &ContractAbi,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO contract_abis (contract_id, version, abi)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (contract_id, version) DO UPDATE SET abi = EXCLUDED.abi",
+    )
+    .bind(contract_id)
+    .bind(version)
+    .bind(DbJson(abi))
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// Functions exported by a version, as extracted from its WASM on publish
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/versions/{version}/abi",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("version" = String, Path, description = "Contract version"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "The version's ABI; `parsed` is false when the WASM had no readable spec", body = ContractAbi),
+        (status = 404, description = "Unknown or private contract, unknown version, or the version was published without WASM"),
+    ),
+)]
+pub async fn get_version_abi(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path((id, version)): Path<(Uuid, String)>,
+) -> ApiResult<Json<ContractAbi>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    fetch_version_abi(&state.db, id, &version)
+        .await?
+        .map(Json)
+        .ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::AbiNotFound,
+                format!(
+                    "Version {} of contract {} was published without WASM",
+                    version, id
+                ),
+            )
+        })
+}
+
+/// The stored ABI of a version; `None` when it was published without WASM
+pub(crate) async fn fetch_version_abi(
+    pool: &PgPool,
+    contract_id: Uuid,
+    version: &str,
+) -> ApiResult<Option<ContractAbi>> {
+    let row: Option<(Option<DbJson<ContractAbi>>,)> = sqlx::query_as(
+        "SELECT a.abi
+         FROM contract_versions v
+         LEFT JOIN contract_abis a ON a.contract_id = v.contract_id AND a.version = v.version
+         WHERE v.contract_id = $1 AND v.version = $2",
+    )
+    .bind(contract_id)
+    .bind(version)
+    .fetch_optional(pool)
+    .await
+    .map_err(|err| db_internal_error("get version abi", err))?;
+
+    match row {
+        None => Err(ApiError::not_found(
+            ErrorCode::VersionNotFound,
+            format!("Version {} not found for contract {}", version, contract_id),
+        )),
+        Some((abi,)) => Ok(abi.map(|DbJson(abi)| abi)),
+    }
+}
+
+/// A function search: a bare name, or a name with its argument types
+#[derive(Debug, PartialEq)]
+struct FunctionQuery {
+    name: String,
+    /// Argument types with whitespace removed, when a signature was given
+    input_types: Option<Vec<String>>,
+}
+
+/// Parse `transfer` or `transfer(Address, Address, i128)`
+fn parse_function_query(raw: &str) -> Result<FunctionQuery, String> {
+    let raw = raw.trim();
+    let (name, args) = match raw.split_once('(') {
+        None => (raw, None),
+        Some((name, rest)) => match rest.trim_end().strip_suffix(')') {
+            Some(args) => (name.trim(), Some(args)),
+            None => return Err("signature must end with ')'".to_string()),
+        },
+    };
+    if name.is_empty() || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(format!("'{}' is not a valid function name", name));
+    }
+
+    let input_types = args
+        .map(|args| {
+            let args: String = args.chars().filter(|c| !c.is_whitespace()).collect();
+            if args.is_empty() {
+                return Ok(Vec::new());
+            }
+            // Split on commas outside `<..>` and `(..)`
+            let mut types = Vec::new();
+            let (mut depth, mut start) = (0i32, 0);
+            for (i, c) in args.char_indices() {
+                match c {
+                    '<' | '(' => depth += 1,
+                    '>' | ')' => depth -= 1,
+                    ',' if depth == 0 => {
+                        types.push(args[start..i].to_string());
+                        start = i + 1;
+                    }
+                    _ => {}
+                }
+                if depth < 0 {
+                    return Err("unbalanced brackets in signature".to_string());
+                }
+            }
+            types.push(args[start..].to_string());
+            if depth != 0 || types.iter().any(String::is_empty) {
+                return Err("malformed argument list in signature".to_string());
+            }
+            Ok(types)
+        })
+        .transpose()?;
+
+    Ok(FunctionQuery {
+        name: name.to_string(),
+        input_types,
+    })
+}
+
+/// Query parameters for `GET /api/contracts/search/by-function`
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct FunctionSearchParams {
+    /// Function name (`transfer`) or full signature
+    /// (`transfer(Address,Address,i128)`)
+    pub name: String,
+    /// Only functions taking exactly this many arguments
+    pub arity: Option<i32>,
+    /// Page size, 1–100 (default 20)
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+/// A contract exporting the searched function
+#[derive(Debug, Serialize, ToSchema)]
+pub struct FunctionMatch {
+    #[serde(flatten)]
+    pub contract: Contract,
+    /// The contract's newest version, whose ABI has the function
+    pub version: String,
+    /// The matching function as `name(T1, T2) -> R`
+    pub signature: String,
+    pub function: AbiFunction,
+}
+
+#[derive(sqlx::FromRow)]
+struct FunctionMatchRow {
+    #[sqlx(flatten)]
+    contract: Contract,
+    abi_version: String,
+    abi_function: DbJson<AbiFunction>,
+}
+
+/// One row per contract whose newest version's ABI has a matching function.
+/// Functions only older versions exported don't match: the contract no
+/// longer offers them.
+fn push_function_matches(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    query: &FunctionQuery,
+    arity: Option<i32>,
+    caller: Option<&Caller>,
+) {
+    builder
+        .push(
+            "SELECT DISTINCT ON (c.id) c.*, a.version AS abi_version, f.value AS abi_function
+             FROM contract_abis a
+             JOIN contracts c ON c.id = a.contract_id
+             CROSS JOIN LATERAL jsonb_array_elements(a.abi -> 'functions') AS f(value)
+             WHERE a.version = (
+                 SELECT v.version FROM contract_versions v
+                 WHERE v.contract_id = a.contract_id
+                 ORDER BY v.created_at DESC LIMIT 1
+             )
+             AND a.abi -> 'functions' @> ",
+        )
+        .push_bind(DbJson(serde_json::json!([{ "name": query.name }])))
+        .push(" AND f.value ->> 'name' = ")
+        .push_bind(query.name.clone());
+    if let Some(arity) = arity {
+        builder
+            .push(" AND jsonb_array_length(f.value -> 'inputs') = ")
+            .push_bind(arity);
+    }
+    if let Some(types) = &query.input_types {
+        builder
+            .push(
+                " AND ARRAY(SELECT regexp_replace(i.value ->> 'type', '\\s', '', 'g')
+                   FROM jsonb_array_elements(f.value -> 'inputs') WITH ORDINALITY AS i(value, n)
+                   ORDER BY i.n)::text[] = ",
+            )
+            .push_bind(types.clone());
+    }
+    push_visibility_filter(builder, caller);
+    builder.push(" ORDER BY c.id");
+}
+
+/// Contracts exporting a function, by bare name or full signature
+#[utoipa::path(
+    get,
+    path = "/api/contracts/search/by-function",
+    tag = "contracts",
+    params(FunctionSearchParams),
+    responses(
+        (status = 200, description = "Matching contracts with the matched function's signature", body = PaginatedFunctionMatches),
+        (status = 400, description = "Malformed name or signature, or an arity that contradicts it"),
+    ),
+)]
+pub async fn search_by_function(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Query(params): Query<FunctionSearchParams>,
+) -> ApiResult<Json<Paginated<FunctionMatch>>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    let query = parse_function_query(&params.name)
+        .map_err(|reason| ApiError::bad_request(ErrorCode::InvalidFunctionSignature, reason))?;
+    if let (Some(arity), Some(types)) = (params.arity, &query.input_types) {
+        if arity as usize != types.len() {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidFunctionSignature,
+                format!(
+                    "arity {} contradicts the {} argument(s) in the signature",
+                    arity,
+                    types.len()
+                ),
+            ));
+        }
+    }
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_PAGE_LIMIT)
+        .clamp(1, MAX_PAGE_LIMIT);
+    let offset = params.offset.unwrap_or(0).max(0);
+
+    let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM (");
+    push_function_matches(&mut builder, &query, params.arity, caller.as_ref());
+    builder
+        .push(") m ORDER BY m.name, m.id LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+    let rows: Vec<FunctionMatchRow> = builder
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("search contracts by function", err))?;
+
+    let mut count = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM (");
+    push_function_matches(&mut count, &query, params.arity, caller.as_ref());
+    count.push(") m");
+    let total: i64 = count
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("count contracts by function", err))?;
+
+    let items = rows
+        .into_iter()
+        .map(|row| {
+            let DbJson(function) = row.abi_function;
+            FunctionMatch {
+                contract: row.contract,
+                version: row.abi_version,
+                signature: function.signature(),
+                function,
+            }
+        })
+        .collect();
+    Ok(Json(Paginated::new(items, total, limit, offset)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn u32_xdr(out: &mut Vec<u8>, value: u32) {
+        out.extend_from_slice(&value.to_be_bytes());
+    }
+
+    fn string_xdr(out: &mut Vec<u8>, value: &str) {
+        u32_xdr(out, value.len() as u32);
+        out.extend_from_slice(value.as_bytes());
+        out.resize(out.len() + (4 - value.len() % 4) % 4, 0);
+    }
+
+    /// A module with nothing but the given custom section
+    fn wasm_with_section(name: &str, data: &[u8]) -> Vec<u8> {
+        let mut section = vec![name.len() as u8];
+        section.extend_from_slice(name.as_bytes());
+        section.extend_from_slice(data);
+        let mut wasm = b"\0asm\x01\0\0\0".to_vec();
+        wasm.push(0);
+        // Section size as unsigned LEB128
+        let mut size = section.len();
+        loop {
+            let byte = (size & 0x7f) as u8;
+            size >>= 7;
+            if size == 0 {
+                wasm.push(byte);
+                break;
+            }
+            wasm.push(byte | 0x80);
+        }
+        wasm.extend_from_slice(&section);
+        wasm
+    }
+
+    /// `fn transfer(from: Address, to: Address, amount: i128)`,
+    /// `enum Status { Active = 0 }` and
+    /// `fn balance(id: Address) -> Option<BytesN<32>>`
+    fn token_spec() -> Vec<u8> {
+        let mut spec = Vec::new();
+        u32_xdr(&mut spec, 0);
+        string_xdr(&mut spec, "Move tokens");
+        string_xdr(&mut spec, "transfer");
+        u32_xdr(&mut spec, 3);
+        for (name, ty) in [("from", 19), ("to", 19), ("amount", 11)] {
+            string_xdr(&mut spec, "");
+            string_xdr(&mut spec, name);
+            u32_xdr(&mut spec, ty);
+        }
+        u32_xdr(&mut spec, 0);
+
+        u32_xdr(&mut spec, 3);
+        for s in ["", "", "Status"] {
+            string_xdr(&mut spec, s);
+        }
+        u32_xdr(&mut spec, 1);
+        string_xdr(&mut spec, "");
+        string_xdr(&mut spec, "Active");
+        u32_xdr(&mut spec, 0);
+
+        u32_xdr(&mut spec, 0);
+        string_xdr(&mut spec, "");
+        string_xdr(&mut spec, "balance");
+        u32_xdr(&mut spec, 1);
+        string_xdr(&mut spec, "");
+        string_xdr(&mut spec, "id");
+        u32_xdr(&mut spec, 19);
+        u32_xdr(&mut spec, 1);
+        u32_xdr(&mut spec, 1000);
+        u32_xdr(&mut spec, 1006);
+        u32_xdr(&mut spec, 32);
+        spec
+    }
+
+    #[test]
+    fn functions_are_read_from_the_spec_section() {
+        let abi = ContractAbi::from_wasm(&wasm_with_section(SPEC_SECTION, &token_spec()));
+        assert!(abi.parsed);
+        assert_eq!(abi.note, None);
+        assert_eq!(abi.functions.len(), 2);
+
+        let transfer = &abi.functions[0];
+        assert_eq!(transfer.name, "transfer");
+        assert_eq!(transfer.doc.as_deref(), Some("Move tokens"));
+        let inputs: Vec<(&str, &str)> = transfer
+            .inputs
+            .iter()
+            .map(|p| (p.name.as_str(), p.type_name.as_str()))
+            .collect();
+        assert_eq!(
+            inputs,
+            [("from", "Address"), ("to", "Address"), ("amount", "i128")]
+        );
+        assert!(transfer.outputs.is_empty());
+
+        let balance = &abi.functions[1];
+        assert_eq!(balance.doc, None);
+        assert_eq!(balance.outputs, ["Option<BytesN<32>>"]);
+
+        let json = serde_json::to_value(&abi).unwrap();
+        assert_eq!(json["functions"][1]["inputs"][0]["type"], "Address");
+
+        assert_eq!(
+            abi.types,
+            [AbiType {
+                name: "Status".to_string(),
+                kind: AbiTypeKind::Enum,
+                fields: vec![],
+                cases: vec![AbiCase {
+                    name: "Active".to_string(),
+                    types: vec![],
+                    value: Some(0),
+                }],
+            }]
+        );
+    }
+
+    #[test]
+    fn struct_and_union_definitions_are_decoded() {
+        let mut spec = Vec::new();
+        // struct Position { owner: Address, amount: i128 }
+        u32_xdr(&mut spec, 1);
+        for s in ["A holding", "", "Position"] {
+            string_xdr(&mut spec, s);
+        }
+        u32_xdr(&mut spec, 2);
+        for (name, ty) in [("owner", 19), ("amount", 11)] {
+            string_xdr(&mut spec, "");
+            string_xdr(&mut spec, name);
+            u32_xdr(&mut spec, ty);
+        }
+        // enum DataKey { Admin, Position(Address) }
+        u32_xdr(&mut spec, 2);
+        for s in ["", "", "DataKey"] {
+            string_xdr(&mut spec, s);
+        }
+        u32_xdr(&mut spec, 2);
+        u32_xdr(&mut spec, 0);
+        string_xdr(&mut spec, "");
+        string_xdr(&mut spec, "Admin");
+        u32_xdr(&mut spec, 1);
+        string_xdr(&mut spec, "");
+        string_xdr(&mut spec, "Position");
+        u32_xdr(&mut spec, 1);
+        u32_xdr(&mut spec, 19);
+
+        let abi = ContractAbi::from_wasm(&wasm_with_section(SPEC_SECTION, &spec));
+        assert!(abi.parsed);
+        let position = &abi.types[0];
+        assert_eq!(position.kind, AbiTypeKind::Struct);
+        let fields: Vec<(&str, &str)> = position
+            .fields
+            .iter()
+            .map(|f| (f.name.as_str(), f.type_name.as_str()))
+            .collect();
+        assert_eq!(fields, [("owner", "Address"), ("amount", "i128")]);
+
+        let key = &abi.types[1];
+        assert_eq!(key.kind, AbiTypeKind::Union);
+        assert_eq!(key.cases[0].name, "Admin");
+        assert!(key.cases[0].types.is_empty());
+        assert_eq!(key.cases[1].types, ["Address"]);
+        assert_eq!(key.referenced_types().collect::<Vec<_>>(), ["Address"]);
+    }
+
+    #[test]
+    fn wasm_without_a_readable_spec_is_stored_unparsed() {
+        let abi = ContractAbi::from_wasm(&wasm_with_section("name", b"x"));
+        assert!(!abi.parsed);
+        assert!(abi.functions.is_empty());
+        assert!(abi.note.unwrap().contains(SPEC_SECTION));
+
+        let abi = ContractAbi::from_wasm(b"not wasm");
+        assert!(!abi.parsed);
+        assert!(abi.note.unwrap().starts_with("WASM could not be parsed"));
+
+        let mut truncated = token_spec();
+        truncated.truncate(truncated.len() - 2);
+        let abi = ContractAbi::from_wasm(&wasm_with_section(SPEC_SECTION, &truncated));
+        assert!(!abi.parsed);
+        assert!(abi.functions.is_empty());
+        assert!(abi
+            .note
+            .unwrap()
+            .starts_with("Contract spec could not be decoded"));
+    }
+
+    #[test]
+    fn signatures_render_like_the_search_syntax() {
+        let abi = ContractAbi::from_wasm(&wasm_with_section(SPEC_SECTION, &token_spec()));
+        assert_eq!(
+            abi.functions[0].signature(),
+            "transfer(Address, Address, i128)"
+        );
+        assert_eq!(
+            abi.functions[1].signature(),
+            "balance(Address) -> Option<BytesN<32>>"
+        );
+    }
+
+    #[test]
+    fn function_queries_take_names_or_signatures() {
+        assert_eq!(
+            parse_function_query(" transfer ").unwrap(),
+            FunctionQuery {
+                name: "transfer".to_string(),
+                input_types: None,
+            }
+        );
+        assert_eq!(
+            parse_function_query("transfer(Address, Address,i128)")
+                .unwrap()
+                .input_types,
+            Some(vec![
+                "Address".to_string(),
+                "Address".to_string(),
+                "i128".to_string()
+            ])
+        );
+        assert_eq!(
+            parse_function_query("swap(Map<Address, i128>, (u32, bool))")
+                .unwrap()
+                .input_types,
+            Some(vec![
+                "Map<Address,i128>".to_string(),
+                "(u32,bool)".to_string()
+            ])
+        );
+        assert_eq!(
+            parse_function_query("init()").unwrap().input_types,
+            Some(vec![])
+        );
+
+        for bad in [
+            "",
+            "trans fer",
+            "transfer(Address",
+            "transfer(,)",
+            "f(Vec<u32)",
+        ] {
+            assert!(parse_function_query(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn signature_searches_compare_argument_types_in_order() {
+        let query = parse_function_query("transfer(Address,Address,i128)").unwrap();
+        let mut builder = QueryBuilder::<Postgres>::new("");
+        push_function_matches(&mut builder, &query, Some(3), None);
+        let sql = builder.sql();
+        assert!(sql.contains("a.abi -> 'functions' @> $1"));
+        assert!(sql.contains("jsonb_array_length(f.value -> 'inputs') = $3"));
+        assert!(sql.contains("ORDER BY i.n)::text[] = $4"));
+        assert!(sql.contains("visibility = 'public'"));
+        assert!(sql.contains("ORDER BY v.created_at DESC LIMIT 1"));
+    }
+
+    #[tokio::test]
+    async fn functions_dropped_by_the_newest_version_no_longer_match() {
+        use crate::state::{json_body, test_request};
+        use axum::http::StatusCode;
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let publisher = state.insert_publisher().await;
+        let contract = state.insert_contract(publisher, None, "public").await;
+        let suffix = Uuid::new_v4().simple().to_string();
+        let (legacy, current) = (format!("legacy_{}", suffix), format!("current_{}", suffix));
+        for (version, function) in [("1.0.0", &legacy), ("2.0.0", &current)] {
+            state.insert_version(contract, version).await;
+            let abi = serde_json::json!({
+                "parsed": true,
+                "functions": [{ "name": function, "inputs": [], "outputs": [] }],
+            });
+            sqlx::query(
+                "INSERT INTO contract_abis (contract_id, version, abi) VALUES ($1, $2, $3)",
+            )
+            .bind(contract)
+            .bind(version)
+            .bind(abi)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        }
+
+        let search = |name: &str| {
+            let uri = format!("/api/contracts/search/by-function?name={}", name);
+            test_request("GET", &uri, None, None)
+        };
+        let response = state.send(search(&legacy)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json_body(response).await["total"], 0);
+
+        let matches = json_body(state.send(search(&current)).await).await;
+        assert_eq!(matches["total"], 1);
+        assert_eq!(matches["items"][0]["version"], "2.0.0");
+    }
+}
@@ -0,0 +1,726 @@
+//! Interface compatibility between two versions of a contract, from the
+//! ABIs extracted on publish (see `abi`).
+//!
+//! Functions are matched by name, so a renamed function shows up as one
+//! removal and one addition. Removing a function or changing its argument
+//! or return types breaks callers; adding one does not; renaming arguments
+//! or editing docs changes nothing on the wire. User-defined types are
+//! compared by definition, not just by name: a function whose signature still
+//! reads `deposit(Position)` breaks when a field of `Position`, or of a type
+//! nested in it, changes.
+//!
+//! The same comparison backs two endpoints: `abi-compat` classifies each
+//! change and recommends a semver bump, and `abi-diff` spells out which
+//! arguments and return types changed, for rendering in a UI.
+
+use std::collections::{BTreeMap, BTreeSet, HashSet};
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    abi::{fetch_version_abi, AbiFunction, AbiParam, AbiType, ContractAbi},
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::fetch_visible_contract,
+    share_tokens::PresentedShareToken,
+    state::AppState,
+};
+
+/// How a change affects existing callers, least to most severe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum AbiChangeKind {
+    None,
+    Additive,
+    Breaking,
+}
+
+/// Version bump the changes call for under semver
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SemverBump {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl From<AbiChangeKind> for SemverBump {
+    fn from(kind: AbiChangeKind) -> Self {
+        match kind {
+            AbiChangeKind::None => SemverBump::Patch,
+            AbiChangeKind::Additive => SemverBump::Minor,
+            AbiChangeKind::Breaking => SemverBump::Major,
+        }
+    }
+}
+
+/// A function that differs between the two versions
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum FunctionChange<'a> {
+    Added(&'a AbiFunction),
+    Removed(&'a AbiFunction),
+    Modified {
+        before: &'a AbiFunction,
+        after: &'a AbiFunction,
+        /// User-defined types the function uses whose definition changed
+        changed_types: Vec<String>,
+    },
+}
+
+impl FunctionChange<'_> {
+    fn name(&self) -> &str {
+        match self {
+            FunctionChange::Added(function) | FunctionChange::Removed(function) => &function.name,
+            FunctionChange::Modified { after, .. } => &after.name,
+        }
+    }
+
+    fn classify(&self) -> (AbiChangeKind, String) {
+        match self {
+            FunctionChange::Added(function) => (
+                AbiChangeKind::Additive,
+                format!("added {}", function.signature()),
+            ),
+            FunctionChange::Removed(function) => (
+                AbiChangeKind::Breaking,
+                format!("removed {}", function.signature()),
+            ),
+            FunctionChange::Modified { before, after, .. } if types_differ(before, after) => (
+                AbiChangeKind::Breaking,
+                format!(
+                    "signature changed from {} to {}",
+                    before.signature(),
+                    after.signature()
+                ),
+            ),
+            FunctionChange::Modified { changed_types, .. } if !changed_types.is_empty() => (
+                AbiChangeKind::Breaking,
+                format!("definition of {} changed", changed_types.join(", ")),
+            ),
+            FunctionChange::Modified { .. } => (
+                AbiChangeKind::None,
+                "argument names or docs changed".to_string(),
+            ),
+        }
+    }
+}
+
+fn types_differ(before: &AbiFunction, after: &AbiFunction) -> bool {
+    before.outputs != after.outputs
+        || before.inputs.len() != after.inputs.len()
+        || before
+            .inputs
+            .iter()
+            .zip(&after.inputs)
+            .any(|(b, a)| b.type_name != a.type_name)
+}
+
+/// Identifiers in a rendered type, e.g. `Vec` and `Position` in `Vec<Position>`
+fn type_idents(type_name: &str) -> impl Iterator<Item = &str> {
+    type_name
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .filter(|ident| !ident.is_empty())
+}
+
+/// User-defined types as each version declares them
+struct TypeDefs<'a> {
+    before: BTreeMap<&'a str, &'a AbiType>,
+    after: BTreeMap<&'a str, &'a AbiType>,
+}
+
+impl<'a> TypeDefs<'a> {
+    fn new(from: &'a ContractAbi, to: &'a ContractAbi) -> Self {
+        let by_name = |abi: &'a ContractAbi| {
+            abi.types
+                .iter()
+                .map(|ty| (ty.name.as_str(), ty))
+                .collect::<BTreeMap<_, _>>()
+        };
+        Self {
+            before: by_name(from),
+            after: by_name(to),
+        }
+    }
+
+    fn is_udt(&self, name: &str) -> bool {
+        self.before.contains_key(name) || self.after.contains_key(name)
+    }
+
+    /// Whether `name` is defined differently, itself or through a type its
+    /// fields or cases mention. `seen` stops recursive types.
+    fn differs(&self, name: &str, seen: &mut HashSet<String>) -> bool {
+        if !seen.insert(name.to_string()) {
+            return false;
+        }
+        match (self.before.get(name), self.after.get(name)) {
+            (Some(before), Some(after)) if before == after => before
+                .referenced_types()
+                .flat_map(type_idents)
+                .any(|nested| self.is_udt(nested) && self.differs(nested, seen)),
+            (None, None) => false,
+            _ => true,
+        }
+    }
+
+    /// Types `before` or `after` mention whose definition changed, by name
+    fn changed_in(&self, before: &AbiFunction, after: &AbiFunction) -> Vec<String> {
+        let mentioned: BTreeSet<&str> = [before, after]
+            .into_iter()
+            .flat_map(|function| {
+                function
+                    .inputs
+                    .iter()
+                    .map(|param| param.type_name.as_str())
+                    .chain(function.outputs.iter().map(String::as_str))
+            })
+            .flat_map(type_idents)
+            .filter(|ident| self.is_udt(ident))
+            .collect();
+        mentioned
+            .into_iter()
+            .filter(|name| self.differs(name, &mut HashSet::new()))
+            .map(str::to_string)
+            .collect()
+    }
+}
+
+/// Functions added, removed or edited from `from` to `to`, by name
+pub(crate) fn function_changes<'a>(
+    from: &'a ContractAbi,
+    to: &'a ContractAbi,
+) -> Vec<FunctionChange<'a>> {
+    let types = TypeDefs::new(from, to);
+    let before: BTreeMap<&str, &AbiFunction> = from
+        .functions
+        .iter()
+        .map(|f| (f.name.as_str(), f))
+        .collect();
+    let after: BTreeMap<&str, &AbiFunction> =
+        to.functions.iter().map(|f| (f.name.as_str(), f)).collect();
+
+    let mut changes = Vec::new();
+    for (name, old) in &before {
+        let Some(new) = after.get(name) else {
+            changes.push(FunctionChange::Removed(*old));
+            continue;
+        };
+        let changed_types = types.changed_in(old, new);
+        if old != new || !changed_types.is_empty() {
+            changes.push(FunctionChange::Modified {
+                before: *old,
+                after: *new,
+                changed_types,
+            });
+        }
+    }
+    for (name, new) in &after {
+        if !before.contains_key(name) {
+            changes.push(FunctionChange::Added(*new));
+        }
+    }
+    changes.sort_by(|a, b| a.name().cmp(b.name()));
+    changes
+}
+
+/// One classified change
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AbiChange {
+    pub function: String,
+    pub kind: AbiChangeKind,
+    pub description: String,
+}
+
+/// `GET /api/contracts/:id/abi-compat` body
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AbiCompatReport {
+    pub from: String,
+    pub to: String,
+    /// The most severe change; `none` when nothing callers see changed
+    pub compatibility: AbiChangeKind,
+    pub recommendation: SemverBump,
+    /// Changed functions by name; unchanged ones are left out
+    pub changes: Vec<AbiChange>,
+}
+
+fn compat_report(
+    from: &str,
+    to: &str,
+    before: &ContractAbi,
+    after: &ContractAbi,
+) -> AbiCompatReport {
+    let changes: Vec<AbiChange> = function_changes(before, after)
+        .iter()
+        .map(|change| {
+            let (kind, description) = change.classify();
+            AbiChange {
+                function: change.name().to_string(),
+                kind,
+                description,
+            }
+        })
+        .collect();
+    let compatibility = changes
+        .iter()
+        .map(|change| change.kind)
+        .max()
+        .unwrap_or(AbiChangeKind::None);
+    AbiCompatReport {
+        from: from.to_string(),
+        to: to.to_string(),
+        compatibility,
+        recommendation: compatibility.into(),
+        changes,
+    }
+}
+
+/// An argument that differs at one position of a function's parameter list
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum ParamChange {
+    Added {
+        position: usize,
+        param: AbiParam,
+    },
+    Removed {
+        position: usize,
+        param: AbiParam,
+    },
+    /// Renamed, retyped or both
+    Modified {
+        position: usize,
+        before: AbiParam,
+        after: AbiParam,
+    },
+}
+
+/// Return types before and after
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+pub struct ReturnChange {
+    pub before: Vec<String>,
+    pub after: Vec<String>,
+}
+
+/// One function of the diff; unchanged functions never appear
+#[derive(Debug, PartialEq, Serialize, ToSchema)]
+#[serde(tag = "change", rename_all = "lowercase")]
+pub enum FunctionDiff {
+    Added {
+        function: AbiFunction,
+    },
+    Removed {
+        function: AbiFunction,
+    },
+    Modified {
+        name: String,
+        /// Whether existing callers break (argument or return types, or the
+        /// definition of a type they use, changed)
+        breaking: bool,
+        /// User-defined types the function uses whose definition changed
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        changed_types: Vec<String>,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        inputs: Vec<ParamChange>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        outputs: Option<ReturnChange>,
+        #[serde(skip_serializing_if = "std::ops::Not::not")]
+        doc_changed: bool,
+    },
+}
+
+/// Position-by-position comparison of two parameter lists
+fn param_changes(before: &[AbiParam], after: &[AbiParam]) -> Vec<ParamChange> {
+    (0..before.len().max(after.len()))
+        .filter_map(
+            |position| match (before.get(position), after.get(position)) {
+                (Some(old), Some(new)) if old != new => Some(ParamChange::Modified {
+                    position,
+                    before: old.clone(),
+                    after: new.clone(),
+                }),
+                (Some(old), None) => Some(ParamChange::Removed {
+                    position,
+                    param: old.clone(),
+                }),
+                (None, Some(new)) => Some(ParamChange::Added {
+                    position,
+                    param: new.clone(),
+                }),
+                _ => None,
+            },
+        )
+        .collect()
+}
+
+impl From<&FunctionChange<'_>> for FunctionDiff {
+    fn from(change: &FunctionChange<'_>) -> Self {
+        match change {
+            FunctionChange::Added(function) => FunctionDiff::Added {
+                function: (*function).clone(),
+            },
+            FunctionChange::Removed(function) => FunctionDiff::Removed {
+                function: (*function).clone(),
+            },
+            FunctionChange::Modified {
+                before,
+                after,
+                changed_types,
+            } => FunctionDiff::Modified {
+                name: after.name.clone(),
+                breaking: types_differ(before, after) || !changed_types.is_empty(),
+                changed_types: changed_types.clone(),
+                inputs: param_changes(&before.inputs, &after.inputs),
+                outputs: (before.outputs != after.outputs).then(|| ReturnChange {
+                    before: before.outputs.clone(),
+                    after: after.outputs.clone(),
+                }),
+                doc_changed: before.doc != after.doc,
+            },
+        }
+    }
+}
+
+/// `GET /api/contracts/:id/versions/:version/abi-diff` body
+#[derive(Debug, Serialize, ToSchema)]
+pub struct AbiDiff {
+    pub version: String,
+    pub against: String,
+    /// Changed functions by name
+    pub functions: Vec<FunctionDiff>,
+}
+
+/// Parsed ABIs of two versions of one contract, or 409 naming each version
+/// that has none
+pub(crate) async fn fetch_abi_pair(
+    state: &AppState,
+    contract_id: Uuid,
+    from: &str,
+    to: &str,
+) -> ApiResult<(ContractAbi, ContractAbi)> {
+    let before = fetch_version_abi(&state.db, contract_id, from).await?;
+    let after = fetch_version_abi(&state.db, contract_id, to).await?;
+    match (before, after) {
+        (Some(before), Some(after)) if before.parsed && after.parsed => Ok((before, after)),
+        (before, after) => {
+            let unparsed: Vec<&str> = [(from, before), (to, after)]
+                .into_iter()
+                .filter(|(_, abi)| !abi.as_ref().is_some_and(|abi| abi.parsed))
+                .map(|(version, _)| version)
+                .collect();
+            Err(ApiError::new(
+                StatusCode::CONFLICT,
+                ErrorCode::AbiNotParsed,
+                format!("No parsed ABI for version(s) {}", unparsed.join(", ")),
+            )
+            .with_details(serde_json::json!({ "versions": unparsed })))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AbiCompatParams {
+    /// Version being upgraded from
+    pub from: String,
+    /// Version being upgraded to
+    pub to: String,
+}
+
+/// Whether upgrading between two versions breaks the contract's interface
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/abi-compat",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        AbiCompatParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Classified changes and the semver bump they call for", body = AbiCompatReport),
+        (status = 404, description = "Unknown or private contract, or unknown version"),
+        (status = 409, description = "A version has no parsed ABI; `details.versions` names it"),
+    ),
+)]
+pub async fn get_abi_compat(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+    Query(params): Query<AbiCompatParams>,
+) -> ApiResult<Json<AbiCompatReport>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let (before, after) = fetch_abi_pair(&state, id, &params.from, &params.to).await?;
+    Ok(Json(compat_report(
+        &params.from,
+        &params.to,
+        &before,
+        &after,
+    )))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AbiDiffParams {
+    /// Version to compare against, usually an older one
+    pub against: String,
+}
+
+/// Function-by-function changes from `against` to `version`
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/versions/{version}/abi-diff",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("version" = String, Path, description = "Version whose changes are listed"),
+        AbiDiffParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Added, removed and modified functions", body = AbiDiff),
+        (status = 404, description = "Unknown or private contract, or unknown version"),
+        (status = 409, description = "A version has no parsed ABI; `details.versions` names it"),
+    ),
+)]
+pub async fn get_abi_diff(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path((id, version)): Path<(Uuid, String)>,
+    Query(params): Query<AbiDiffParams>,
+) -> ApiResult<Json<AbiDiff>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let (before, after) = fetch_abi_pair(&state, id, &params.against, &version).await?;
+    let functions = function_changes(&before, &after)
+        .iter()
+        .map(FunctionDiff::from)
+        .collect();
+    Ok(Json(AbiDiff {
+        version,
+        against: params.against,
+        functions,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn function(name: &str, inputs: &[(&str, &str)], outputs: &[&str]) -> AbiFunction {
+        AbiFunction {
+            name: name.to_string(),
+            doc: None,
+            inputs: inputs
+                .iter()
+                .map(|(name, ty)| AbiParam {
+                    name: name.to_string(),
+                    type_name: ty.to_string(),
+                })
+                .collect(),
+            outputs: outputs.iter().map(|ty| ty.to_string()).collect(),
+        }
+    }
+
+    fn abi(functions: Vec<AbiFunction>) -> ContractAbi {
+        ContractAbi {
+            parsed: true,
+            note: None,
+            functions,
+            types: vec![],
+        }
+    }
+
+    fn record(name: &str, fields: &[(&str, &str)]) -> AbiType {
+        AbiType {
+            name: name.to_string(),
+            kind: crate::abi::AbiTypeKind::Struct,
+            fields: fields
+                .iter()
+                .map(|(name, ty)| AbiParam {
+                    name: name.to_string(),
+                    type_name: ty.to_string(),
+                })
+                .collect(),
+            cases: vec![],
+        }
+    }
+
+    /// `deposit(Vec<Position>)`, where `Position` holds an `Amount`
+    fn vault() -> ContractAbi {
+        let mut vault = abi(vec![function(
+            "deposit",
+            &[("positions", "Vec<Position>")],
+            &[],
+        )]);
+        vault.types = vec![
+            record("Position", &[("owner", "Address"), ("amount", "Amount")]),
+            record("Amount", &[("value", "i128")]),
+        ];
+        vault
+    }
+
+    fn token() -> ContractAbi {
+        abi(vec![
+            function(
+                "transfer",
+                &[("from", "Address"), ("to", "Address"), ("amount", "i128")],
+                &[],
+            ),
+            function("balance", &[("id", "Address")], &["i128"]),
+        ])
+    }
+
+    #[test]
+    fn removing_a_function_is_breaking() {
+        let mut next = token();
+        next.functions.retain(|f| f.name != "balance");
+        let report = compat_report("1.0.0", "2.0.0", &token(), &next);
+        assert_eq!(report.compatibility, AbiChangeKind::Breaking);
+        assert_eq!(report.recommendation, SemverBump::Major);
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.changes[0].function, "balance");
+        assert_eq!(report.changes[0].kind, AbiChangeKind::Breaking);
+    }
+
+    #[test]
+    fn adding_a_function_is_additive() {
+        let mut next = token();
+        next.functions.push(function("decimals", &[], &["u32"]));
+        let report = compat_report("1.0.0", "1.1.0", &token(), &next);
+        assert_eq!(report.compatibility, AbiChangeKind::Additive);
+        assert_eq!(report.recommendation, SemverBump::Minor);
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.changes[0].description, "added decimals() -> u32");
+    }
+
+    #[test]
+    fn type_changes_break_but_renamed_arguments_do_not() {
+        let mut retyped = token();
+        retyped.functions[1].outputs = vec!["u64".to_string()];
+        let report = compat_report("1.0.0", "2.0.0", &token(), &retyped);
+        assert_eq!(report.compatibility, AbiChangeKind::Breaking);
+
+        let mut renamed = token();
+        renamed.functions[0].inputs[2].name = "value".to_string();
+        let report = compat_report("1.0.0", "1.0.1", &token(), &renamed);
+        assert_eq!(report.compatibility, AbiChangeKind::None);
+        assert_eq!(report.recommendation, SemverBump::Patch);
+        assert_eq!(report.changes.len(), 1);
+
+        let report = compat_report("1.0.0", "1.0.0", &token(), &token());
+        assert_eq!(report.compatibility, AbiChangeKind::None);
+        assert!(report.changes.is_empty());
+    }
+
+    #[test]
+    fn diffs_name_the_changed_arguments_and_returns() {
+        let mut next = token();
+        next.functions[0].inputs[2].type_name = "u64".to_string();
+        next.functions[0].inputs.push(AbiParam {
+            name: "memo".to_string(),
+            type_name: "Option<String>".to_string(),
+        });
+        next.functions[1].doc = Some("Balance of `id`".to_string());
+        next.functions.push(function("decimals", &[], &["u32"]));
+
+        let before = token();
+        let diff: Vec<FunctionDiff> = function_changes(&before, &next)
+            .iter()
+            .map(FunctionDiff::from)
+            .collect();
+        assert_eq!(diff.len(), 3);
+        assert!(
+            matches!(&diff[1], FunctionDiff::Added { function } if function.name == "decimals")
+        );
+        assert_eq!(
+            diff[0],
+            FunctionDiff::Modified {
+                name: "balance".to_string(),
+                breaking: false,
+                changed_types: vec![],
+                inputs: vec![],
+                outputs: None,
+                doc_changed: true,
+            }
+        );
+        let FunctionDiff::Modified {
+            breaking, inputs, ..
+        } = &diff[2]
+        else {
+            panic!("transfer should be modified: {:?}", diff[2]);
+        };
+        assert!(breaking);
+        assert!(matches!(
+            &inputs[..],
+            [
+                ParamChange::Modified { position: 2, before, after },
+                ParamChange::Added { position: 3, .. },
+            ] if before.type_name == "i128" && after.type_name == "u64"
+        ));
+
+        let json = serde_json::to_value(&diff[0]).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "change": "modified",
+                "name": "balance",
+                "breaking": false,
+                "doc_changed": true,
+            })
+        );
+    }
+
+    #[test]
+    fn changing_a_struct_field_breaks_functions_that_use_it() {
+        let mut retyped = vault();
+        retyped.types[0].fields[1].type_name = "u64".to_string();
+        let report = compat_report("1.0.0", "2.0.0", &vault(), &retyped);
+        assert_eq!(report.compatibility, AbiChangeKind::Breaking);
+        assert_eq!(report.changes.len(), 1);
+        assert_eq!(report.changes[0].function, "deposit");
+        assert_eq!(
+            report.changes[0].description,
+            "definition of Position changed"
+        );
+
+        // A change two levels down still reaches the function
+        let mut nested = vault();
+        nested.types[1].fields[0].name = "units".to_string();
+        let diff: Vec<FunctionDiff> = function_changes(&vault(), &nested)
+            .iter()
+            .map(FunctionDiff::from)
+            .collect();
+        assert!(matches!(
+            &diff[..],
+            [FunctionDiff::Modified { breaking: true, changed_types, .. }]
+                if changed_types == &["Position"]
+        ));
+
+        // Adding a type no function uses changes nothing for callers
+        let mut unused = vault();
+        unused.types.push(record("Unused", &[("x", "u32")]));
+        let report = compat_report("1.0.0", "1.0.1", &vault(), &unused);
+        assert_eq!(report.compatibility, AbiChangeKind::None);
+        assert!(report.changes.is_empty());
+    }
+
+    #[test]
+    fn recursive_types_are_compared_once() {
+        let mut tree = abi(vec![function("insert", &[("node", "Node")], &[])]);
+        tree.types = vec![record("Node", &[("children", "Vec<Node>")])];
+        assert!(compat_report("1.0.0", "1.0.0", &tree, &tree)
+            .changes
+            .is_empty());
+    }
+}
@@ -0,0 +1,269 @@
+//! Trail of privileged actions on the registry itself (`admin_audit_log`):
+//! ownership transfers, account deactivation, configuration changes and
+//! credential minting. Unrelated to security audits of contracts, which live
+//! in `audit_handlers`.
+//!
+//! The log is append-only: there is no endpoint to edit or remove entries,
+//! and a trigger rejects `UPDATE`, `DELETE` and `TRUNCATE` on the table.
+
+use axum::{
+    extract::{Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::{PgExecutor, PgPool, Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::{
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::db_internal_error,
+    observability,
+    pagination::{Paginated, PaginatedAdminAuditEntries},
+    state::AppState,
+};
+
+pub const PUBLISHER_DEACTIVATED: &str = "publisher.deactivated";
+pub const PUBLISHER_REACTIVATED: &str = "publisher.reactivated";
+pub const TRANSFER_INITIATED: &str = "contract.transfer_initiated";
+pub const TRANSFER_ACCEPTED: &str = "contract.transfer_accepted";
+pub const CONFIG_CHANGED: &str = "config.changed";
+pub const LOG_LEVEL_CHANGED: &str = "admin.log_level_changed";
+pub const API_KEY_CREATED: &str = "api_key.created";
+pub const SHARE_TOKEN_CREATED: &str = "share_token.created";
+pub const SHARE_TOKEN_REVOKED: &str = "share_token.revoked";
+
+/// Default page size for `GET /api/admin/audit-log`
+const DEFAULT_AUDIT_LOG_LIMIT: i64 = 50;
+/// Hard upper bound for the page size of `GET /api/admin/audit-log`
+const MAX_AUDIT_LOG_LIMIT: i64 = 200;
+
+/// Append one entry, tagged with the id of the request being served. Pass
+/// the transaction making the change so the entry lands only if the change
+/// does.
+pub async fn record<'e>(
+    executor: impl PgExecutor<'e>,
+    actor: &Caller,
+    action: &str,
+    target_type: &str,
+    target_id: &str,
+    details: Option<serde_json::Value>,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO admin_audit_log (actor, action, target_type, target_id, details, request_id)
+         VALUES ($1, $2, $3, $4, $5, $6)",
+    )
+    .bind(actor.audit_name())
+    .bind(action)
+    .bind(target_type)
+    .bind(target_id)
+    .bind(details)
+    .bind(observability::current_request_id())
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+/// [`record`] for a change that has no transaction to join, such as one
+/// already written through a helper or not stored in the database at all.
+/// The change stands whatever happens here, so a failed write is logged
+/// instead of failing the request.
+pub async fn record_applied(
+    pool: &PgPool,
+    actor: &Caller,
+    action: &str,
+    target_type: &str,
+    target_id: &str,
+    details: Option<serde_json::Value>,
+) {
+    if let Err(err) = record(pool, actor, action, target_type, target_id, details).await {
+        tracing::error!(action, target_type, target_id, error = ?err, "failed to record admin audit entry");
+    }
+}
+
+/// One entry of the admin audit log
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct AdminAuditEntry {
+    pub id: Uuid,
+    /// `admin`, or the acting publisher's id
+    pub actor: String,
+    pub action: String,
+    pub target_type: String,
+    pub target_id: String,
+    #[schema(value_type = Option<Object>)]
+    pub details: Option<serde_json::Value>,
+    /// `X-Request-Id` of the request that made the change
+    pub request_id: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Query parameters for `GET /api/admin/audit-log`
+#[derive(Debug, Default, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct AuditLogParams {
+    /// `admin` or a publisher id
+    pub actor: Option<String>,
+    /// Exact action name, e.g. `publisher.deactivated`
+    pub action: Option<String>,
+    /// Only entries at or after this time
+    pub from: Option<DateTime<Utc>>,
+    /// Only entries before this time
+    pub to: Option<DateTime<Utc>>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+impl AuditLogParams {
+    fn clamped_limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_AUDIT_LOG_LIMIT)
+            .clamp(1, MAX_AUDIT_LOG_LIMIT)
+    }
+
+    fn clamped_offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+}
+
+fn push_audit_log_filters(builder: &mut QueryBuilder<'_, Postgres>, params: &AuditLogParams) {
+    builder.push(" WHERE 1=1");
+    if let Some(actor) = params
+        .actor
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        builder.push(" AND actor = ").push_bind(actor.to_string());
+    }
+    if let Some(action) = params
+        .action
+        .as_deref()
+        .map(str::trim)
+        .filter(|a| !a.is_empty())
+    {
+        builder.push(" AND action = ").push_bind(action.to_string());
+    }
+    if let Some(from) = params.from {
+        builder.push(" AND created_at >= ").push_bind(from);
+    }
+    if let Some(to) = params.to {
+        builder.push(" AND created_at < ").push_bind(to);
+    }
+}
+
+/// Privileged actions on the registry, newest first
+#[utoipa::path(
+    get,
+    path = "/api/admin/audit-log",
+    tag = "admin",
+    params(AuditLogParams),
+    responses(
+        (status = 200, description = "A page of audit entries", body = PaginatedAdminAuditEntries),
+        (status = 400, description = "`from` is after `to`"),
+        (status = 403, description = "Caller is not an admin"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn list_audit_log(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Query(params): Query<AuditLogParams>,
+) -> ApiResult<Json<Paginated<AdminAuditEntry>>> {
+    caller.require_admin()?;
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidRange,
+                "`from` must not be after `to`",
+            ));
+        }
+    }
+    let limit = params.clamped_limit();
+    let offset = params.clamped_offset();
+
+    let mut query = QueryBuilder::<Postgres>::new("SELECT * FROM admin_audit_log");
+    push_audit_log_filters(&mut query, &params);
+    query
+        .push(" ORDER BY created_at DESC, id DESC LIMIT ")
+        .push_bind(limit)
+        .push(" OFFSET ")
+        .push_bind(offset);
+    let items: Vec<AdminAuditEntry> = query
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("list admin audit log", err))?;
+
+    let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM admin_audit_log");
+    push_audit_log_filters(&mut count_query, &params);
+    let total: i64 = count_query
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("count admin audit log", err))?;
+
+    Ok(Json(Paginated::new(items, total, limit, offset)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    fn params(query: &str) -> AuditLogParams {
+        let uri: axum::http::Uri = format!("/api/admin/audit-log?{}", query).parse().unwrap();
+        Query::<AuditLogParams>::try_from_uri(&uri).unwrap().0
+    }
+
+    #[test]
+    fn filters_bind_only_what_was_given() {
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM admin_audit_log");
+        push_audit_log_filters(&mut builder, &params(""));
+        assert_eq!(builder.sql(), "SELECT * FROM admin_audit_log WHERE 1=1");
+
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM admin_audit_log");
+        push_audit_log_filters(
+            &mut builder,
+            &params("actor=admin&action=config.changed&from=2026-01-01T00:00:00Z&to=2026-02-01T00:00:00Z"),
+        );
+        assert_eq!(
+            builder.sql(),
+            "SELECT * FROM admin_audit_log WHERE 1=1 AND actor = $1 AND action = $2 \
+             AND created_at >= $3 AND created_at < $4"
+        );
+    }
+
+    #[test]
+    fn page_size_is_clamped() {
+        assert_eq!(params("").clamped_limit(), DEFAULT_AUDIT_LOG_LIMIT);
+        assert_eq!(params("limit=0").clamped_limit(), 1);
+        assert_eq!(params("limit=5000").clamped_limit(), MAX_AUDIT_LOG_LIMIT);
+        assert_eq!(params("offset=-3").clamped_offset(), 0);
+    }
+
+    #[tokio::test]
+    async fn non_admins_and_inverted_ranges_are_refused() {
+        // Refused before any query
+        let state = AppState::for_tests();
+
+        let err = list_audit_log(
+            State(state.clone()),
+            Extension(Caller::Publisher(Uuid::new_v4())),
+            Query(AuditLogParams::default()),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+
+        let err = list_audit_log(
+            State(state),
+            Extension(Caller::Admin),
+            Query(params("from=2026-02-01T00:00:00Z&to=2026-01-01T00:00:00Z")),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+}
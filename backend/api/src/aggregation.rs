@@ -1,34 +1,114 @@
+use serde::Serialize;
 use sqlx::PgPool;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+
+pub const INTERVAL_ENV: &str = "AGGREGATION_INTERVAL_SECS";
+pub const DEFAULT_INTERVAL_SECS: u64 = 3600;
+
+/// Serializes scheduled and operator-triggered runs
+static RUN_LOCK: Mutex<()> = Mutex::const_new(());
+
+/// Read `AGGREGATION_INTERVAL_SECS`, defaulting to hourly. Zero or a
+/// non-integer is a startup error rather than a silent fallback.
+pub fn interval_from_env() -> Result<Duration, String> {
+    match std::env::var(INTERVAL_ENV) {
+        Ok(raw) => parse_interval(&raw),
+        Err(_) => Ok(Duration::from_secs(DEFAULT_INTERVAL_SECS)),
+    }
+}
+
+fn parse_interval(raw: &str) -> Result<Duration, String> {
+    match raw.trim().parse::<u64>() {
+        Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+        _ => Err(format!(
+            "{} must be a positive number of seconds (got '{}')",
+            INTERVAL_ENV, raw
+        )),
+    }
+}
+
+/// Outcome of one aggregation pass
+#[derive(Debug, Serialize)]
+pub struct AggregationRun {
+    pub rows_upserted: u64,
+    pub events_deleted: u64,
+    pub duration_ms: u128,
+}
 
 /// Spawn the background aggregation task.
 ///
-/// Runs every hour:
-///   1. Aggregate raw events into daily summaries (yesterday + today).
+/// Runs every `interval`:
+///   1. Aggregate raw events into daily summaries (yesterday + today),
+///      contract analytics and template instantiations alike.
 ///   2. Delete raw events older than 90 days.
-pub fn spawn_aggregation_task(pool: PgPool) {
+///   3. Refresh the registry-wide total gauges.
+///
+/// Stops once `shutdown` is cancelled, letting a run already in progress
+/// finish; await the handle to drain it.
+pub fn spawn_aggregation_task(
+    pool: PgPool,
+    interval: Duration,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
     tokio::spawn(async move {
-        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        let mut ticker = tokio::time::interval(interval);
 
         loop {
-            interval.tick().await;
-            tracing::info!("aggregation: starting hourly run");
+            tokio::select! {
+                _ = ticker.tick() => {}
+                _ = shutdown.cancelled() => {
+                    tracing::info!("aggregation: stopped");
+                    return;
+                }
+            }
+            tracing::info!(interval_secs = interval.as_secs(), "aggregation: starting scheduled run");
 
-            if let Err(err) = run_aggregation(&pool).await {
+            if let Err(err) = run_once(&pool).await {
                 tracing::error!(error = ?err, "aggregation: run failed");
             }
+        }
+    })
+}
 
-            if let Err(err) = cleanup_old_events(&pool).await {
-                tracing::error!(error = ?err, "aggregation: retention cleanup failed");
-            }
+/// Run one full aggregation + retention pass now
+pub async fn run_once(pool: &PgPool) -> Result<AggregationRun, sqlx::Error> {
+    let _guard = RUN_LOCK.lock().await;
+    let started = Instant::now();
+
+    let rows_upserted = run_aggregation(pool).await? + run_template_rollup(pool).await?;
+    let events_deleted = match cleanup_old_events(pool).await {
+        Ok(deleted) => deleted,
+        Err(err) => {
+            tracing::error!(error = ?err, "aggregation: retention cleanup failed");
+            0
         }
-    });
+    };
+
+    if let Err(err) = refresh_registry_totals(pool).await {
+        tracing::warn!(error = ?err, "aggregation: refreshing registry totals failed");
+    }
+
+    let run = AggregationRun {
+        rows_upserted,
+        events_deleted,
+        duration_ms: started.elapsed().as_millis(),
+    };
+    tracing::info!(
+        rows_upserted = run.rows_upserted,
+        events_deleted = run.events_deleted,
+        duration_ms = run.duration_ms as u64,
+        "aggregation: run complete"
+    );
+    Ok(run)
 }
 
 /// Build daily aggregates from raw `analytics_events`.
 ///
 /// Uses `ON CONFLICT … DO UPDATE` so re-running is idempotent.
-async fn run_aggregation(pool: &PgPool) -> Result<(), sqlx::Error> {
+async fn run_aggregation(pool: &PgPool) -> Result<u64, sqlx::Error> {
     // Aggregate events from the last 2 days (yesterday + partial today)
     // to ensure we always capture the freshest data.
     let rows_affected = sqlx::query(
@@ -115,24 +195,76 @@ async fn run_aggregation(pool: &PgPool) -> Result<(), sqlx::Error> {
     .await?
     .rows_affected();
 
-    tracing::info!(
+    tracing::debug!(
         rows = rows_affected,
         "aggregation: daily summaries upserted"
     );
+    Ok(rows_affected)
+}
+
+/// Roll flushed template instantiations up into daily counts.
+///
+/// Recomputes yesterday and today from the raw events, so re-running is
+/// idempotent like [`run_aggregation`].
+async fn run_template_rollup(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let rows_affected = sqlx::query(
+        "INSERT INTO template_instantiation_daily (template_id, date, count)
+         SELECT template_id, DATE(created_at), SUM(count)::bigint
+         FROM template_instantiation_events
+         WHERE created_at >= CURRENT_DATE - INTERVAL '1 day'
+         GROUP BY template_id, DATE(created_at)
+         ON CONFLICT (template_id, date) DO UPDATE SET count = EXCLUDED.count",
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
+
+    tracing::debug!(rows = rows_affected, "aggregation: template usage upserted");
+    Ok(rows_affected)
+}
+
+/// Set the `contracts_total` and `publishers_total` gauges, so scrapes never
+/// hit the database.
+async fn refresh_registry_totals(pool: &PgPool) -> Result<(), sqlx::Error> {
+    let (contracts, publishers): (i64, i64) = sqlx::query_as(
+        "SELECT (SELECT COUNT(*) FROM contracts), (SELECT COUNT(*) FROM publishers)",
+    )
+    .fetch_one(pool)
+    .await?;
+    crate::metrics::set_registry_totals(contracts, publishers);
     Ok(())
 }
 
-/// Delete raw analytics events older than 90 days.
-async fn cleanup_old_events(pool: &PgPool) -> Result<(), sqlx::Error> {
-    let deleted =
+/// Delete raw analytics and template usage events older than 90 days.
+async fn cleanup_old_events(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    let mut deleted =
         sqlx::query("DELETE FROM analytics_events WHERE created_at < NOW() - INTERVAL '90 days'")
             .execute(pool)
             .await?
             .rows_affected();
+    deleted += sqlx::query(
+        "DELETE FROM template_instantiation_events WHERE created_at < NOW() - INTERVAL '90 days'",
+    )
+    .execute(pool)
+    .await?
+    .rows_affected();
 
     if deleted > 0 {
         tracing::info!(deleted, "aggregation: cleaned up old raw events");
     }
 
-    Ok(())
+    Ok(deleted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn interval_must_be_positive_seconds() {
+        assert_eq!(parse_interval("900").unwrap(), Duration::from_secs(900));
+        assert_eq!(parse_interval(" 60 ").unwrap(), Duration::from_secs(60));
+        assert!(parse_interval("0").is_err());
+        assert!(parse_interval("1h").is_err());
+    }
 }
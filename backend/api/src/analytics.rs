@@ -1,3 +1,7 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, Months, TimeZone, Timelike, Utc};
+use serde::Serialize;
 use shared::{AnalyticsEventType, Network};
 use sqlx::PgPool;
 use uuid::Uuid;
@@ -36,3 +40,227 @@ pub async fn record_event(
 
     Ok(())
 }
+
+/// Most buckets a single timeseries request may span, so a wide window at a
+/// fine interval can't turn into an expensive scan
+pub const MAX_TIMESERIES_BUCKETS: usize = 1000;
+
+/// Registry-wide counters that can be charted over time
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimeseriesMetric {
+    /// Contracts published
+    Publishes,
+    /// Source scans submitted
+    Scans,
+    /// Publishers registered
+    NewPublishers,
+}
+
+impl TimeseriesMetric {
+    pub const ALL: [TimeseriesMetric; 3] = [Self::Publishes, Self::Scans, Self::NewPublishers];
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "publishes" => Some(Self::Publishes),
+            "scans" => Some(Self::Scans),
+            "new_publishers" => Some(Self::NewPublishers),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Publishes => "publishes",
+            Self::Scans => "scans",
+            Self::NewPublishers => "new_publishers",
+        }
+    }
+
+    /// Table whose `created_at` is counted. Raw `analytics_events` are pruned
+    /// after 90 days, so the source tables are used instead, and the daily
+    /// rollups are too coarse for hourly buckets.
+    fn table(&self) -> &'static str {
+        match self {
+            Self::Publishes => "contracts",
+            Self::Scans => "scan_jobs",
+            Self::NewPublishers => "publishers",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BucketInterval {
+    Hour,
+    Day,
+    Week,
+    Month,
+}
+
+impl BucketInterval {
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw {
+            "hour" => Some(Self::Hour),
+            "day" => Some(Self::Day),
+            "week" => Some(Self::Week),
+            "month" => Some(Self::Month),
+            _ => None,
+        }
+    }
+
+    /// `date_trunc` field name; only ever one of the allowlisted values
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Week => "week",
+            Self::Month => "month",
+        }
+    }
+
+    /// Start of the bucket containing `at`, matching `date_trunc(.., 'UTC')`
+    /// (weeks start on Monday)
+    pub fn truncate(&self, at: DateTime<Utc>) -> DateTime<Utc> {
+        let day = Utc
+            .with_ymd_and_hms(at.year(), at.month(), at.day(), 0, 0, 0)
+            .unwrap();
+        match self {
+            Self::Hour => day + Duration::hours(at.hour() as i64),
+            Self::Day => day,
+            Self::Week => day - Duration::days(at.weekday().num_days_from_monday() as i64),
+            Self::Month => Utc
+                .with_ymd_and_hms(at.year(), at.month(), 1, 0, 0, 0)
+                .unwrap(),
+        }
+    }
+
+    fn next(&self, bucket: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::Hour => bucket + Duration::hours(1),
+            Self::Day => bucket + Duration::days(1),
+            Self::Week => bucket + Duration::weeks(1),
+            Self::Month => bucket + Months::new(1),
+        }
+    }
+}
+
+/// Every bucket start covering `[from, to]`, or `None` when that would exceed
+/// [`MAX_TIMESERIES_BUCKETS`]
+pub fn bucket_starts(
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    interval: BucketInterval,
+) -> Option<Vec<DateTime<Utc>>> {
+    let mut buckets = Vec::new();
+    let mut bucket = interval.truncate(from);
+    while bucket <= to {
+        if buckets.len() == MAX_TIMESERIES_BUCKETS {
+            return None;
+        }
+        buckets.push(bucket);
+        bucket = interval.next(bucket);
+    }
+    Some(buckets)
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TimeseriesPoint {
+    pub bucket: DateTime<Utc>,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TimeseriesResponse {
+    pub metric: TimeseriesMetric,
+    pub interval: BucketInterval,
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+    pub points: Vec<TimeseriesPoint>,
+}
+
+/// Pair each bucket with its count, zero when nothing happened in it
+pub fn fill_buckets(
+    buckets: &[DateTime<Utc>],
+    counts: &HashMap<DateTime<Utc>, i64>,
+) -> Vec<TimeseriesPoint> {
+    buckets
+        .iter()
+        .map(|bucket| TimeseriesPoint {
+            bucket: *bucket,
+            count: counts.get(bucket).copied().unwrap_or(0),
+        })
+        .collect()
+}
+
+/// Count `metric` per bucket over `[from, to]`; callers validate the range
+/// with [`bucket_starts`] first.
+pub async fn timeseries(
+    pool: &PgPool,
+    metric: TimeseriesMetric,
+    interval: BucketInterval,
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    buckets: &[DateTime<Utc>],
+) -> Result<Vec<TimeseriesPoint>, sqlx::Error> {
+    let sql = format!(
+        "SELECT date_trunc($1, created_at, 'UTC') AS bucket, COUNT(*) AS count
+         FROM {}
+         WHERE created_at >= $2 AND created_at <= $3
+         GROUP BY bucket",
+        metric.table()
+    );
+    let rows: Vec<(DateTime<Utc>, i64)> = sqlx::query_as(&sql)
+        .bind(interval.as_str())
+        .bind(from)
+        .bind(to)
+        .fetch_all(pool)
+        .await?;
+
+    let counts: HashMap<DateTime<Utc>, i64> = rows.into_iter().collect();
+    Ok(fill_buckets(buckets, &counts))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(y: i32, m: u32, d: u32, h: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, m, d, h, 30, 0).unwrap()
+    }
+
+    #[test]
+    fn truncates_like_date_trunc() {
+        // 2026-03-05 is a Thursday
+        let t = at(2026, 3, 5, 14);
+        assert_eq!(BucketInterval::Hour.truncate(t), Utc.with_ymd_and_hms(2026, 3, 5, 14, 0, 0).unwrap());
+        assert_eq!(BucketInterval::Day.truncate(t), Utc.with_ymd_and_hms(2026, 3, 5, 0, 0, 0).unwrap());
+        assert_eq!(BucketInterval::Week.truncate(t), Utc.with_ymd_and_hms(2026, 3, 2, 0, 0, 0).unwrap());
+        assert_eq!(BucketInterval::Month.truncate(t), Utc.with_ymd_and_hms(2026, 3, 1, 0, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn empty_buckets_are_zero_filled() {
+        let buckets = bucket_starts(at(2026, 1, 30, 0), at(2026, 2, 2, 0), BucketInterval::Day).unwrap();
+        assert_eq!(buckets.len(), 4);
+
+        let counts = HashMap::from([(buckets[1], 3)]);
+        let counts: Vec<i64> = fill_buckets(&buckets, &counts).iter().map(|p| p.count).collect();
+        assert_eq!(counts, vec![0, 3, 0, 0]);
+    }
+
+    #[test]
+    fn months_step_by_calendar_month() {
+        let buckets = bucket_starts(at(2026, 1, 31, 0), at(2026, 4, 1, 0), BucketInterval::Month).unwrap();
+        let months: Vec<u32> = buckets.iter().map(|b| b.month()).collect();
+        assert_eq!(months, vec![1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn oversized_ranges_are_rejected() {
+        let from = at(2025, 1, 1, 0);
+        let to = from + Duration::hours(MAX_TIMESERIES_BUCKETS as i64);
+        assert!(bucket_starts(from, to, BucketInterval::Hour).is_none());
+        assert!(bucket_starts(from, to, BucketInterval::Day).is_some());
+    }
+}
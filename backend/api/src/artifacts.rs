@@ -0,0 +1,551 @@
+//! Stored WASM artifacts (`contract_artifacts`) and the download endpoint.
+//!
+//! Artifacts live in a `BYTEA` column. Downloads read it back in fixed-size
+//! `substring` slices so a large module is never held in memory at once, and
+//! honor a single `Range: bytes=` so interrupted downloads can resume.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Request, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension,
+};
+use futures::StreamExt;
+use once_cell::sync::Lazy;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use crate::{
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::{db_internal_error, fetch_visible_contract},
+    share_tokens::PresentedShareToken,
+    state::AppState,
+};
+
+/// Bytes fetched per query while streaming a download
+const DOWNLOAD_CHUNK_BYTES: i64 = 64 * 1024;
+
+/// Build a safe `<name>-<version>.wasm` filename for Content-Disposition
+pub fn artifact_filename(name: &str, version: &str) -> String {
+    format!(
+        "{}-{}.wasm",
+        sanitize_filename_part(name, "contract"),
+        sanitize_filename_part(version, "")
+    )
+}
+
+/// Reduce `s` to `[A-Za-z0-9._-]` so it is safe in a Content-Disposition
+/// filename, using `fallback` when nothing is left
+pub fn sanitize_filename_part(s: &str, fallback: &str) -> String {
+    let cleaned: String = s
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let cleaned = cleaned.trim_matches('_');
+    if cleaned.is_empty() {
+        fallback.to_string()
+    } else {
+        cleaned.to_string()
+    }
+}
+
+/// Inclusive, zero-based byte span requested by a `Range` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ByteRange {
+    pub start: i64,
+    pub end: i64,
+}
+
+/// Parse a `Range` header against an artifact of `size` bytes. Anything but
+/// a single `bytes=` range is ignored (`Ok(None)`), per RFC 9110, and the
+/// whole artifact is served; a range that starts past the end is an `Err`.
+pub fn parse_byte_range(header: &str, size: i64) -> Result<Option<ByteRange>, ()> {
+    let Some(spec) = header.trim().strip_prefix("bytes=") else {
+        return Ok(None);
+    };
+    if spec.contains(',') {
+        return Ok(None);
+    }
+    let Some((first, last)) = spec.trim().split_once('-') else {
+        return Ok(None);
+    };
+    let (first, last) = (first.trim(), last.trim());
+    let parse = |n: &str| n.parse::<i64>().ok().filter(|n| *n >= 0);
+    let range = match (first.is_empty(), last.is_empty()) {
+        // bytes=-N: the last N bytes
+        (true, false) => match parse(last) {
+            Some(0) => return Err(()),
+            Some(suffix) => ByteRange {
+                start: (size - suffix).max(0),
+                end: size - 1,
+            },
+            None => return Ok(None),
+        },
+        (false, _) => {
+            let Some(start) = parse(first) else {
+                return Ok(None);
+            };
+            let end = if last.is_empty() {
+                size - 1
+            } else {
+                match parse(last) {
+                    Some(end) if end >= start => end.min(size - 1),
+                    _ => return Ok(None),
+                }
+            };
+            ByteRange { start, end }
+        }
+        (true, true) => return Ok(None),
+    };
+    if range.start >= size {
+        return Err(());
+    }
+    Ok(Some(range))
+}
+
+/// Hex sha256 of a WASM module
+pub fn wasm_sha256(wasm: &[u8]) -> String {
+    use sha2::{Digest, Sha256};
+    hex::encode(Sha256::digest(wasm))
+}
+
+/// Env var capping the size of an uploaded WASM artifact, in bytes
+pub const MAX_ARTIFACT_BYTES_ENV: &str = "MAX_ARTIFACT_BYTES";
+pub const DEFAULT_MAX_ARTIFACT_BYTES: usize = 5 * 1024 * 1024;
+
+/// Room in a publish body for the metadata that travels with the artifact:
+/// the largest README and changelog, plus headroom for every other field
+pub const METADATA_ALLOWANCE_BYTES: usize = crate::readme::MAX_README_BYTES
+    + crate::validation::requests::MAX_CHANGELOG_BYTES
+    + METADATA_HEADROOM_BYTES;
+
+/// Room for the publish fields that have no size cap of their own
+const METADATA_HEADROOM_BYTES: usize = 64 * 1024;
+
+static MAX_ARTIFACT_BYTES: Lazy<usize> =
+    Lazy::new(|| parse_max_artifact_bytes(std::env::var(MAX_ARTIFACT_BYTES_ENV).ok().as_deref()));
+
+/// Configured artifact limit; unset, zero or unparsable values use the default
+pub fn max_artifact_bytes() -> usize {
+    *MAX_ARTIFACT_BYTES
+}
+
+fn parse_max_artifact_bytes(raw: Option<&str>) -> usize {
+    raw.and_then(|value| value.trim().parse().ok())
+        .filter(|bytes| *bytes > 0)
+        .unwrap_or(DEFAULT_MAX_ARTIFACT_BYTES)
+}
+
+/// 413 `artifact.too_large`, with both sizes in `details`
+pub fn artifact_too_large(limit: usize, actual: usize) -> ApiError {
+    ApiError::new(
+        StatusCode::PAYLOAD_TOO_LARGE,
+        ErrorCode::ArtifactTooLarge,
+        format!("Artifact is {} bytes; the limit is {} bytes", actual, limit),
+    )
+    .with_details(serde_json::json!({ "limit": limit, "actual": actual }))
+}
+
+/// Exact check on a decoded artifact
+pub fn check_artifact_size(wasm: &[u8], limit: usize) -> ApiResult<()> {
+    if wasm.len() > limit {
+        return Err(artifact_too_large(limit, wasm.len()));
+    }
+    Ok(())
+}
+
+/// Largest publish body that can still carry an artifact within `limit`:
+/// the artifact base64-encoded, plus the surrounding metadata
+pub fn publish_body_cap(limit: usize) -> usize {
+    limit.div_ceil(3) * 4 + METADATA_ALLOWANCE_BYTES
+}
+
+/// Middleware for upload routes: rejects bodies that cannot fit within
+/// `MAX_ARTIFACT_BYTES` while they stream in, rather than after buffering.
+pub async fn limit_artifact_upload(req: Request, next: Next) -> Response {
+    enforce_upload_limit(max_artifact_bytes(), req, next).await
+}
+
+async fn enforce_upload_limit(limit: usize, req: Request, next: Next) -> Response {
+    let cap = publish_body_cap(limit);
+    let (parts, body) = req.into_parts();
+
+    // A declared length over the cap is refused before reading anything
+    if let Some(declared) = content_length(&parts.headers) {
+        if declared > cap {
+            return artifact_too_large(limit, declared).into_response();
+        }
+    }
+
+    match read_capped(body, limit, cap).await {
+        Ok(bytes) => next.run(Request::from_parts(parts, Body::from(bytes))).await,
+        Err(err) => err.into_response(),
+    }
+}
+
+fn content_length(headers: &HeaderMap) -> Option<usize> {
+    headers
+        .get(header::CONTENT_LENGTH)?
+        .to_str()
+        .ok()?
+        .parse()
+        .ok()
+}
+
+/// Read `body` frame by frame, stopping as soon as more than `cap` bytes have
+/// arrived. `actual` in the error is the number of bytes received by then.
+async fn read_capped(body: Body, limit: usize, cap: usize) -> ApiResult<Bytes> {
+    let mut stream = body.into_data_stream();
+    let mut buffer = Vec::new();
+    while let Some(frame) = stream.next().await {
+        let frame = frame.map_err(|_| {
+            ApiError::bad_request(ErrorCode::InvalidRequest, "Failed to read request body")
+        })?;
+        if buffer.len() + frame.len() > cap {
+            return Err(artifact_too_large(limit, buffer.len() + frame.len()));
+        }
+        buffer.extend_from_slice(&frame);
+    }
+    Ok(Bytes::from(buffer))
+}
+
+/// Load the full artifact for a version, if one is stored.
+pub async fn load_artifact(pool: &PgPool, version_id: Uuid) -> Result<Option<Vec<u8>>, sqlx::Error> {
+    sqlx::query_scalar("SELECT wasm FROM contract_artifacts WHERE version_id = $1")
+        .bind(version_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Store (or replace) the artifact for a version
+pub async fn store_artifact<'e, E>(executor: E, version_id: Uuid, wasm: &[u8]) -> Result<(), sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query(
+        "INSERT INTO contract_artifacts (version_id, wasm, size_bytes, sha256)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (version_id) DO UPDATE
+         SET wasm = EXCLUDED.wasm, size_bytes = EXCLUDED.size_bytes,
+             sha256 = EXCLUDED.sha256, created_at = NOW()",
+    )
+    .bind(version_id)
+    .bind(wasm)
+    .bind(wasm.len() as i64)
+    .bind(wasm_sha256(wasm))
+    .execute(executor)
+    .await?;
+    Ok(())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/versions/{version}/download",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("version" = String, Path, description = "Version to download"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "WASM bytecode", content_type = "application/wasm"),
+        (status = 206, description = "The byte range asked for in `Range`", content_type = "application/wasm"),
+        (status = 404, description = "Unknown or private contract, unknown version, or the version has no stored artifact"),
+        (status = 416, description = "The range starts past the end of the artifact"),
+    ),
+)]
+pub async fn download_version_wasm(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path((id, version)): Path<(Uuid, String)>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let row: Option<(String, Uuid, Option<i64>)> = sqlx::query_as(
+        "SELECT c.name, v.id, a.size_bytes
+         FROM contract_versions v
+         JOIN contracts c ON c.id = v.contract_id
+         LEFT JOIN contract_artifacts a ON a.version_id = v.id
+         WHERE v.contract_id = $1 AND v.version = $2",
+    )
+    .bind(id)
+    .bind(&version)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("look up artifact", err))?;
+
+    let Some((name, version_id, size)) = row else {
+        return Err(ApiError::not_found(
+            ErrorCode::VersionNotFound,
+            format!("Version {} not found for contract {}", version, id),
+        ));
+    };
+    let Some(size) = size else {
+        return Err(ApiError::not_found(
+            ErrorCode::ArtifactNotFound,
+            format!("Version {} of contract {} has no stored WASM artifact", version, id),
+        ));
+    };
+    let requested = headers.get(header::RANGE).and_then(|value| value.to_str().ok());
+    let range = match requested.map(|raw| (raw, parse_byte_range(raw, size))) {
+        Some((_, Ok(range))) => range,
+        Some((raw, Err(()))) => {
+            let mut response = ApiError::new(
+                StatusCode::RANGE_NOT_SATISFIABLE,
+                ErrorCode::RangeNotSatisfiable,
+                format!("Range '{}' is outside the {}-byte artifact", raw, size),
+            )
+            .into_response();
+            response.headers_mut().insert(
+                header::CONTENT_RANGE,
+                HeaderValue::from_str(&format!("bytes */{}", size)).expect("digits are a valid header"),
+            );
+            return Ok(response);
+        }
+        None => None,
+    };
+    // A resumed download is still one download
+    if range.map_or(true, |range| range.start == 0) {
+        state.downloads.record(version_id);
+    }
+    let span = range.unwrap_or(ByteRange {
+        start: 0,
+        end: size - 1,
+    });
+
+    let pool = state.db.clone();
+    // Next 1-based offset; `substring` takes int4 positions, hence the casts
+    let last = span.end + 1;
+    let chunks = futures::stream::unfold(span.start + 1, move |offset| {
+        let pool = pool.clone();
+        async move {
+            if offset > last {
+                return None;
+            }
+            let length = DOWNLOAD_CHUNK_BYTES.min(last - offset + 1);
+            let chunk: Result<Vec<u8>, sqlx::Error> = sqlx::query_scalar(
+                "SELECT substring(wasm FROM $2::int4 FOR $3::int4)
+                 FROM contract_artifacts WHERE version_id = $1",
+            )
+            .bind(version_id)
+            .bind(offset)
+            .bind(length)
+            .fetch_one(&pool)
+            .await;
+
+            match chunk {
+                Ok(bytes) if bytes.is_empty() => None,
+                Ok(bytes) => Some((Ok::<_, sqlx::Error>(bytes), offset + length)),
+                // surface the error to the body stream, then stop
+                Err(err) => {
+                    tracing::error!(error = ?err, "artifact stream failed");
+                    Some((Err(err), last + 1))
+                }
+            }
+        }
+    });
+
+    let status = if range.is_some() {
+        StatusCode::PARTIAL_CONTENT
+    } else {
+        StatusCode::OK
+    };
+    let disposition = format!("attachment; filename=\"{}\"", artifact_filename(&name, &version));
+    let mut response = (status, Body::from_stream(chunks)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/wasm"));
+    headers.insert(header::ACCEPT_RANGES, HeaderValue::from_static("bytes"));
+    headers.insert(
+        header::CONTENT_LENGTH,
+        HeaderValue::from((span.end - span.start + 1) as u64),
+    );
+    if range.is_some() {
+        let content_range = format!("bytes {}-{}/{}", span.start, span.end, size);
+        if let Ok(value) = HeaderValue::from_str(&content_range) {
+            headers.insert(header::CONTENT_RANGE, value);
+        }
+    }
+    if let Ok(value) = HeaderValue::from_str(&disposition) {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+
+    Ok(response)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filename_is_header_safe() {
+        assert_eq!(artifact_filename("Token Swap", "1.2.0"), "Token_Swap-1.2.0.wasm");
+        assert_eq!(artifact_filename("\"evil\"\r\n", "1.0.0"), "evil-1.0.0.wasm");
+        assert_eq!(artifact_filename("***", "v2"), "contract-v2.wasm");
+    }
+
+    #[test]
+    fn byte_ranges_are_clamped_to_the_artifact() {
+        let range = |start, end| Ok(Some(ByteRange { start, end }));
+        assert_eq!(parse_byte_range("bytes=0-99", 1000), range(0, 99));
+        assert_eq!(parse_byte_range("bytes=900-", 1000), range(900, 999));
+        assert_eq!(parse_byte_range("bytes=900-5000", 1000), range(900, 999));
+        assert_eq!(parse_byte_range("bytes=-100", 1000), range(900, 999));
+        assert_eq!(parse_byte_range("bytes=-5000", 1000), range(0, 999));
+
+        assert_eq!(parse_byte_range("bytes=1000-", 1000), Err(()));
+        assert_eq!(parse_byte_range("bytes=-0", 1000), Err(()));
+        // Unsupported forms fall back to the whole artifact
+        assert_eq!(parse_byte_range("bytes=0-1,5-9", 1000), Ok(None));
+        assert_eq!(parse_byte_range("items=0-9", 1000), Ok(None));
+        assert_eq!(parse_byte_range("bytes=9-0", 1000), Ok(None));
+    }
+
+    #[tokio::test]
+    async fn ranged_downloads_return_the_requested_slice() {
+        use crate::state::test_request;
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let publisher = state.insert_publisher().await;
+        let contract = state.insert_contract(publisher, None, "public").await;
+        let version = state.insert_version(contract, "1.0.0").await;
+        // Longer than one chunk, so the slice spans two substring reads
+        let wasm: Vec<u8> = (0..DOWNLOAD_CHUNK_BYTES * 2).map(|i| (i % 251) as u8).collect();
+        store_artifact(&state.db, version, &wasm).await.unwrap();
+        let uri = format!("/api/contracts/{}/versions/1.0.0/download", contract);
+
+        let mut request = test_request("GET", &uri, None, None);
+        let (start, end) = (DOWNLOAD_CHUNK_BYTES - 10, DOWNLOAD_CHUNK_BYTES + 9);
+        let range = HeaderValue::from_str(&format!("bytes={}-{}", start, end)).unwrap();
+        request.headers_mut().insert(header::RANGE, range);
+        let response = state.send(request).await;
+        assert_eq!(response.status(), StatusCode::PARTIAL_CONTENT);
+        let content_range = format!("bytes {}-{}/{}", start, end, wasm.len());
+        assert_eq!(response.headers()[header::CONTENT_RANGE], content_range.as_str());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], &wasm[start as usize..=end as usize]);
+
+        let response = state.send(test_request("GET", &uri, None, None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], &wasm[..]);
+
+        let mut request = test_request("GET", &uri, None, None);
+        request
+            .headers_mut()
+            .insert(header::RANGE, HeaderValue::from_static("bytes=999999-"));
+        let response = state.send(request).await;
+        assert_eq!(response.status(), StatusCode::RANGE_NOT_SATISFIABLE);
+        let unsatisfied = format!("bytes */{}", wasm.len());
+        assert_eq!(response.headers()[header::CONTENT_RANGE], unsatisfied.as_str());
+    }
+
+    #[test]
+    fn artifact_limit_defaults_when_unset_or_invalid() {
+        assert_eq!(parse_max_artifact_bytes(None), DEFAULT_MAX_ARTIFACT_BYTES);
+        assert_eq!(parse_max_artifact_bytes(Some("0")), DEFAULT_MAX_ARTIFACT_BYTES);
+        assert_eq!(parse_max_artifact_bytes(Some("lots")), DEFAULT_MAX_ARTIFACT_BYTES);
+        assert_eq!(parse_max_artifact_bytes(Some(" 1024 ")), 1024);
+    }
+
+    #[test]
+    fn decoded_artifact_over_the_limit_is_rejected() {
+        assert!(check_artifact_size(&[0; 100], 100).is_ok());
+        let err = check_artifact_size(&[0; 101], 100).unwrap_err();
+        assert_eq!(err.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(err.code().as_str(), "artifact.too_large");
+    }
+
+    fn limited_app(limit: usize) -> axum::Router {
+        use axum::{extract::DefaultBodyLimit, middleware, routing::post};
+
+        axum::Router::new()
+            .route("/upload", post(|body: Bytes| async move { body.len().to_string() }))
+            .layer(middleware::from_fn(move |req: Request, next: Next| {
+                enforce_upload_limit(limit, req, next)
+            }))
+            .layer(DefaultBodyLimit::disable())
+    }
+
+    async fn upload(limit: usize, len: usize, declare_length: bool) -> Response {
+        use tower::ServiceExt;
+
+        let mut request = axum::http::Request::post("/upload");
+        if declare_length {
+            request = request.header(header::CONTENT_LENGTH, len);
+        }
+        // Several frames, so the cap is hit part-way through the stream
+        let frames = futures::stream::iter(
+            vec![0u8; len]
+                .chunks(1024)
+                .map(|chunk| Ok::<_, std::io::Error>(Bytes::copy_from_slice(chunk)))
+                .collect::<Vec<_>>(),
+        );
+        limited_app(limit)
+            .oneshot(request.body(Body::from_stream(frames)).unwrap())
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn body_just_under_the_cap_is_accepted() {
+        let cap = publish_body_cap(4096);
+        let response = upload(4096, cap, false).await;
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn body_just_over_the_cap_is_rejected_while_streaming() {
+        let cap = publish_body_cap(4096);
+        let response = upload(4096, cap + 1, false).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(json["code"], "artifact.too_large");
+        assert_eq!(json["details"]["limit"], 4096);
+        assert_eq!(json["details"]["actual"], cap + 1);
+    }
+
+    #[tokio::test]
+    async fn declared_length_over_the_cap_is_rejected_up_front() {
+        let cap = publish_body_cap(4096);
+        let response = upload(4096, cap + 1, true).await;
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[test]
+    fn largest_readme_and_changelog_fit_the_metadata_allowance() {
+        let metadata = serde_json::json!({
+            "contract_id": format!("C{}", "A".repeat(55)),
+            "name": "token",
+            "publisher_address": format!("G{}", "A".repeat(55)),
+            "network": "testnet",
+            "tags": vec!["t".repeat(50); 10],
+            "readme": "#".repeat(crate::readme::MAX_README_BYTES),
+            "changelog": "-".repeat(crate::validation::requests::MAX_CHANGELOG_BYTES),
+        });
+        assert!(metadata.to_string().len() <= METADATA_ALLOWANCE_BYTES);
+    }
+
+    #[test]
+    fn sha256_is_hex_encoded() {
+        assert_eq!(
+            wasm_sha256(b""),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+    }
+}
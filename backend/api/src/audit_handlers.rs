@@ -5,7 +5,7 @@ use axum::{
     extract::{Path, Query, State},
     http::header,
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
 use uuid::Uuid;
 
@@ -15,32 +15,64 @@ use crate::models::{
     UpdateCheckRequest,
 };
 use crate::{
+    artifacts::sanitize_filename_part,
+    audit_pdf::{render_audit_pdf, AuditReport},
+    auth::Caller,
     checklist::all_checks,
-    detector::detect_all,
-    error::{ApiError, ApiResult},
+    detector::{self, detect_all},
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::fetch_visible_contract,
+    metrics,
     models::{
-        AuditCheckRow, AuditRecord, AuditResponse, CheckStatus, CheckWithStatus, ChecklistItem,
+        AuditCheckRow, AuditRecord, AuditResponse, AuditSeveritySummary, CheckStatus, CheckWithStatus, ChecklistItem,
         ContractSecuritySummary, CreateAuditRequest, DetectionMethod, ExportRequest,
         UpdateCheckRequest,
     },
+    pagination::{PageParams, Paginated},
+    scanner_service::SeverityCounts,
     scoring::{build_markdown_report, calculate_scores, score_badge},
+    share_tokens::PresentedShareToken,
     state::AppState,
 };
 
 // ─────────────────────────────────────────────────────────
 // GET /api/contracts/:id/security-audit
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/security-audit",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Most recent audit"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_security_audit(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<Uuid>,
 ) -> ApiResult<Json<AuditResponse>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    )
+    .await?;
+
     let audit: AuditRecord = sqlx::query_as(
         "SELECT * FROM security_audits WHERE contract_id = $1 ORDER BY audit_date DESC LIMIT 1",
     )
     .bind(contract_id)
     .fetch_one(&state.db)
     .await
-    .map_err(|_| ApiError::not_found("AuditNotFound", format!("No security audit found for contract: {}", contract_id)))?;
+    .map_err(|_| ApiError::not_found(ErrorCode::AuditNotFound, format!("No security audit found for contract: {}", contract_id)))?;
 
     build_audit_response(&state, audit).await
 }
@@ -48,17 +80,42 @@ pub async fn get_security_audit(
 // ─────────────────────────────────────────────────────────
 // GET /api/contracts/:id/security-audit/:audit_id
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/security-audit/{audit_id}",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("audit_id" = Uuid, Path, description = "Audit UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Audit details"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_security_audit_by_id(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path((contract_id, audit_id)): Path<(Uuid, Uuid)>,
 ) -> ApiResult<Json<AuditResponse>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    )
+    .await?;
+
     let audit: AuditRecord =
         sqlx::query_as("SELECT * FROM security_audits WHERE id = $1 AND contract_id = $2")
             .bind(audit_id)
             .bind(contract_id)
             .fetch_one(&state.db)
             .await
-            .map_err(|_| ApiError::not_found("AuditNotFound", format!("No audit found with ID: {}", audit_id)))?;
+            .map_err(|_| ApiError::not_found(ErrorCode::AuditNotFound, format!("No audit found with ID: {}", audit_id)))?;
 
     build_audit_response(&state, audit).await
 }
@@ -66,24 +123,76 @@ pub async fn get_security_audit_by_id(
 // ─────────────────────────────────────────────────────────
 // GET /api/contracts/:id/security-audits
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/security-audits",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        PageParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Audits for the contract, newest first"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn list_security_audits(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<Uuid>,
-) -> ApiResult<Json<Vec<AuditRecord>>> {
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<AuditRecord>>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    )
+    .await?;
+
     let audits: Vec<AuditRecord> = sqlx::query_as(
-        "SELECT * FROM security_audits WHERE contract_id = $1 ORDER BY audit_date DESC",
+        "SELECT * FROM security_audits WHERE contract_id = $1
+         ORDER BY audit_date DESC, id DESC
+         LIMIT $2 OFFSET $3",
     )
     .bind(contract_id)
+    .bind(page.limit())
+    .bind(page.offset())
     .fetch_all(&state.db)
     .await
     .map_err(|_| ApiError::db_error("Failed to fetch security audits"))?;
-
-    Ok(Json(audits))
+    let total: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM security_audits WHERE contract_id = $1")
+            .bind(contract_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|_| ApiError::db_error("Failed to count security audits"))?;
+
+    Ok(Json(Paginated::new(
+        audits,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
 }
 
 // ─────────────────────────────────────────────────────────
 // POST /api/contracts/:id/security-audit
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/security-audit",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "Audit created"),
+    ),
+)]
 pub async fn create_security_audit(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
@@ -94,9 +203,12 @@ pub async fn create_security_audit(
         .bind(contract_id)
         .fetch_one(&state.db)
         .await
-        .map_err(|_| ApiError::not_found("ContractNotFound", format!("No contract found with ID: {}", contract_id)))?;
+        .map_err(|_| ApiError::not_found(ErrorCode::ContractNotFound, format!("No contract found with ID: {}", contract_id)))?;
 
     // Run auto-detection if source provided
+    if req.source_code.is_some() {
+        detector::refresh_dynamic_rules(&state.db).await;
+    }
     let auto_results = req
         .source_code
         .as_deref()
@@ -120,7 +232,7 @@ pub async fn create_security_audit(
     // Seed all check rows
     let all = all_checks();
     for item in &all {
-        let (status, evidence, auto_detected) = match auto_results.get(item.id) {
+        let (status, evidence, auto_detected) = match auto_results.get(item.id.as_ref()) {
             Some(result) => (result.status.clone(), result.evidence.clone(), true),
             None => (CheckStatus::Pending, None, false),
         };
@@ -131,7 +243,7 @@ pub async fn create_security_audit(
                VALUES ($1, $2, $3, $4, $5)"#,
         )
         .bind(audit.id)
-        .bind(&item.id)
+        .bind(item.id.as_ref())
         .bind(&status)
         .bind(auto_detected)
         .bind(&evidence)
@@ -162,6 +274,7 @@ pub async fn create_security_audit(
         auto_detected = auto_results.len(),
         "New security audit created"
     );
+    metrics::AUDITS_COMPLETED.inc();
 
     build_audit_response(&state, audit).await
 }
@@ -169,6 +282,19 @@ pub async fn create_security_audit(
 // ─────────────────────────────────────────────────────────
 // PATCH /api/contracts/:id/security-audit/:audit_id/checks/:check_id
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    patch,
+    path = "/api/contracts/{id}/security-audit/{audit_id}/checks/{check_id}",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("audit_id" = Uuid, Path, description = "Audit UUID"),
+        ("check_id" = String, Path, description = "Checklist item id"),
+    ),
+    responses(
+        (status = 200, description = "Audit with the check updated"),
+    ),
+)]
 pub async fn update_check(
     State(state): State<AppState>,
     Path((_contract_id, audit_id, check_id)): Path<(Uuid, Uuid, String)>,
@@ -178,7 +304,7 @@ pub async fn update_check(
     let all = all_checks();
     if !all.iter().any(|c| c.id == check_id) {
         return Err(ApiError::bad_request(
-            "InvalidCheckId",
+            ErrorCode::InvalidCheckId,
             format!("Check ID '{}' does not exist in the audit checklist", check_id),
         ));
     }
@@ -199,7 +325,7 @@ pub async fn update_check(
 
     if rows_affected == 0 {
         return Err(ApiError::not_found(
-            "CheckNotFound",
+            ErrorCode::CheckNotFound,
             format!("No check found with ID '{}' for audit: {}", check_id, audit_id),
         ));
     }
@@ -226,6 +352,18 @@ pub async fn update_check(
 // ─────────────────────────────────────────────────────────
 // POST /api/contracts/:id/security-audit/:audit_id/run-autocheck
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/security-audit/{audit_id}/run-autocheck",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("audit_id" = Uuid, Path, description = "Audit UUID"),
+    ),
+    responses(
+        (status = 200, description = "Audit with auto-detected results"),
+    ),
+)]
 pub async fn run_autocheck(
     State(state): State<AppState>,
     Path((_contract_id, audit_id)): Path<(Uuid, Uuid)>,
@@ -234,16 +372,17 @@ pub async fn run_autocheck(
         .bind(audit_id)
         .fetch_one(&state.db)
         .await
-        .map_err(|_| ApiError::not_found("AuditNotFound", format!("No audit found with ID: {}", audit_id)))?;
+        .map_err(|_| ApiError::not_found(ErrorCode::AuditNotFound, format!("No audit found with ID: {}", audit_id)))?;
 
     let source = audit.contract_source.as_deref().ok_or_else(|| {
         tracing::warn!(audit_id = %audit_id, "No source code stored for auto-check");
         ApiError::unprocessable(
-            "NoSourceCode",
+            ErrorCode::NoSourceCode,
             "No source code is stored for this audit. Upload source code first.",
         )
     })?;
 
+    detector::refresh_dynamic_rules(&state.db).await;
     let auto_results = detect_all(source);
 
     for (check_id, result) in &auto_results {
@@ -285,24 +424,49 @@ pub async fn run_autocheck(
 // ─────────────────────────────────────────────────────────
 // GET /api/contracts/:id/security-audit/:audit_id/export
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/security-audit/{audit_id}/export",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("audit_id" = Uuid, Path, description = "Audit UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Audit report download"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn export_audit_markdown(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path((contract_id, audit_id)): Path<(Uuid, Uuid)>,
     Query(params): Query<ExportRequest>,
 ) -> ApiResult<Response> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    )
+    .await?;
+
     let audit: AuditRecord =
         sqlx::query_as("SELECT * FROM security_audits WHERE id = $1 AND contract_id = $2")
             .bind(audit_id)
             .bind(contract_id)
             .fetch_one(&state.db)
             .await
-            .map_err(|_| ApiError::not_found("AuditNotFound", format!("No audit found with ID: {}", audit_id)))?;
+            .map_err(|_| ApiError::not_found(ErrorCode::AuditNotFound, format!("No audit found with ID: {}", audit_id)))?;
 
     let (contract_name,): (String,) = sqlx::query_as("SELECT name FROM contracts WHERE id = $1")
         .bind(contract_id)
         .fetch_one(&state.db)
         .await
-        .map_err(|_| ApiError::not_found("ContractNotFound", format!("No contract found with ID: {}", contract_id)))?;
+        .map_err(|_| ApiError::not_found(ErrorCode::ContractNotFound, format!("No contract found with ID: {}", contract_id)))?;
 
     let checks = fetch_check_rows(&state, audit_id).await?;
     let (_, category_scores) = calculate_scores(&checks);
@@ -340,13 +504,124 @@ pub async fn export_audit_markdown(
         .into_response())
 }
 
+// ─────────────────────────────────────────────────────────
+// GET /api/audits/:id/report.pdf
+// ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/audits/{id}/report.pdf",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Audit UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Audit report as PDF", content_type = "application/pdf"),
+        (status = 404, description = "Audit not found, or its contract is private"),
+        (status = 409, description = "Audit still has pending checks"),
+    ),
+)]
+pub async fn export_audit_pdf(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(audit_id): Path<Uuid>,
+) -> ApiResult<Response> {
+    let audit: AuditRecord = sqlx::query_as("SELECT * FROM security_audits WHERE id = $1")
+        .bind(audit_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|_| ApiError::db_error("Failed to fetch audit"))?
+        .ok_or_else(|| ApiError::not_found(ErrorCode::AuditNotFound, format!("No audit found with ID: {}", audit_id)))?;
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        audit.contract_id,
+    )
+    .await?;
+
+    let checks = fetch_check_rows(&state, audit_id).await?;
+    let pending = checks.iter().filter(|r| r.status == CheckStatus::Pending).count();
+    if pending > 0 {
+        return Err(ApiError::new(
+            axum::http::StatusCode::CONFLICT,
+            ErrorCode::AuditIncomplete,
+            format!("Audit {} still has {} pending checks", audit_id, pending),
+        )
+        .with_details(serde_json::json!({ "pending_checks": pending })));
+    }
+
+    let (contract_name,): (String,) = sqlx::query_as("SELECT name FROM contracts WHERE id = $1")
+        .bind(audit.contract_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|_| ApiError::not_found(ErrorCode::ContractNotFound, format!("No contract found with ID: {}", audit.contract_id)))?;
+
+    let (_, category_scores) = calculate_scores(&checks);
+    let pdf = render_audit_pdf(&AuditReport {
+        contract_name: &contract_name,
+        audit: &audit,
+        checks: &checks,
+        category_scores: &category_scores,
+    })
+    .map_err(|err| {
+        tracing::error!(audit_id = %audit_id, error = %err, "failed to render audit PDF");
+        ApiError::internal("Failed to render audit report")
+    })?;
+
+    let filename = format!(
+        "security-audit-{}-{}.pdf",
+        sanitize_filename_part(&contract_name.to_lowercase().replace(' ', "-"), "contract"),
+        audit.audit_date.format("%Y%m%d")
+    );
+
+    Ok((
+        axum::http::StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, "application/pdf"),
+            (
+                header::CONTENT_DISPOSITION,
+                &format!("attachment; filename=\"{}\"", filename),
+            ),
+        ],
+        pdf,
+    )
+        .into_response())
+}
+
 // ─────────────────────────────────────────────────────────
 // GET /api/contracts/:id/security-score
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/security-score",
+    tag = "security-audit",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Latest security score summary"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_security_score(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<Uuid>,
 ) -> ApiResult<Json<ContractSecuritySummary>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    )
+    .await?;
+
     let summary: ContractSecuritySummary = sqlx::query_as(
         r#"SELECT
                id          AS audit_id,
@@ -362,7 +637,7 @@ pub async fn get_security_score(
     .bind(contract_id)
     .fetch_one(&state.db)
     .await
-    .map_err(|_| ApiError::not_found("AuditNotFound", format!("No security audit found for contract: {}", contract_id)))?;
+    .map_err(|_| ApiError::not_found(ErrorCode::AuditNotFound, format!("No security audit found for contract: {}", contract_id)))?;
 
     Ok(Json(ContractSecuritySummary {
         score_badge: score_badge(summary.overall_score).to_string(),
@@ -370,9 +645,57 @@ pub async fn get_security_score(
     }))
 }
 
+// ─────────────────────────────────────────────────────────
+// GET /api/audits/summary
+// ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/audits/summary",
+    tag = "security-audit",
+    responses(
+        (status = 200, description = "Failed checks per severity across all audits"),
+    ),
+)]
+pub async fn get_audit_summary(
+    State(state): State<AppState>,
+) -> ApiResult<Json<AuditSeveritySummary>> {
+    let (audits, audits_with_findings): (i64, i64) = sqlx::query_as(
+        "SELECT
+             (SELECT COUNT(*) FROM security_audits),
+             (SELECT COUNT(DISTINCT audit_id) FROM audit_checks WHERE status = 'failed')",
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|_| ApiError::db_error("Failed to count security audits"))?;
+
+    let failed: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT check_id, COUNT(*) FROM audit_checks WHERE status = 'failed' GROUP BY check_id",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|_| ApiError::db_error("Failed to aggregate audit findings"))?;
+
+    Ok(Json(AuditSeveritySummary {
+        audits,
+        audits_with_findings,
+        severity_summary: severity_summary(
+            failed.iter().map(|(check_id, n)| (check_id.as_str(), *n)),
+            &all_checks(),
+        ),
+    }))
+}
+
 // ─────────────────────────────────────────────────────────
 // GET /api/security-audit/checklist
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/security-audit/checklist",
+    tag = "security-audit",
+    responses(
+        (status = 200, description = "Static security checklist"),
+    ),
+)]
 pub async fn get_checklist_definition() -> Json<serde_json::Value> {
     let checks = all_checks();
     let items: Vec<serde_json::Value> = checks
@@ -418,6 +741,26 @@ async fn fetch_check_rows(
         .map_err(|_| ApiError::db_error("Failed to fetch audit check rows"))
 }
 
+/// Count failed checks by the severity of their checklist item, using the
+/// same taxonomy as scan findings. Checks no longer in the checklist are
+/// skipped.
+pub fn severity_summary<'a>(
+    failed: impl IntoIterator<Item = (&'a str, i64)>,
+    checks: &[ChecklistItem],
+) -> SeverityCounts {
+    let severities: std::collections::HashMap<&str, &crate::models::Severity> = checks
+        .iter()
+        .map(|c| (c.id.as_ref(), &c.severity))
+        .collect();
+    let mut counts = SeverityCounts::default();
+    for (check_id, n) in failed {
+        if let Some(severity) = severities.get(check_id) {
+            counts.add(severity, n);
+        }
+    }
+    counts
+}
+
 async fn build_audit_response(
     state: &AppState,
     audit: AuditRecord,
@@ -430,25 +773,32 @@ async fn build_audit_response(
         check_rows.iter().map(|r| (r.check_id.clone(), r)).collect();
 
     let auto_detected_count = check_rows.iter().filter(|r| r.auto_detected).count();
+    let severity_summary = severity_summary(
+        check_rows
+            .iter()
+            .filter(|r| r.status == CheckStatus::Failed)
+            .map(|r| (r.check_id.as_str(), 1)),
+        &all,
+    );
 
     let checks_with_status: Vec<CheckWithStatus> = all
         .iter()
         .map(|item| {
-            let row = status_map.get(item.id);
+            let row = status_map.get(item.id.as_ref());
             let (detection_type, auto_patterns): (&'static str, Vec<String>) = match &item.detection {
                 DetectionMethod::Automatic { patterns } => ("automatic", patterns.clone()),
                 DetectionMethod::SemiAutomatic { patterns } => ("semi_automatic", patterns.clone()),
                 DetectionMethod::Manual => ("manual", vec![]),
             };
             CheckWithStatus {
-                id: item.id,
+                id: item.id.clone(),
                 category: item.category.to_string(),
-                title: item.title,
-                description: item.description,
+                title: item.title.clone(),
+                description: item.description.clone(),
                 severity: format!("{:?}", item.severity),
                 detection_type,
                 auto_patterns,
-                remediation: item.remediation,
+                remediation: item.remediation.clone(),
                 references: item.references.clone(),
                 status: row.map(|r| r.status.clone()).unwrap_or_default(),
                 notes: row.and_then(|r| r.notes.clone()),
@@ -463,5 +813,53 @@ async fn build_audit_response(
         checks: checks_with_status,
         category_scores,
         auto_detected_count,
+        severity_summary,
     }))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn severity_summary_counts_failed_checks_by_checklist_severity() {
+        let checks = all_checks();
+        assert_eq!(
+            severity_summary(std::iter::empty(), &checks),
+            SeverityCounts::default()
+        );
+
+        let critical = checks
+            .iter()
+            .find(|c| c.severity == crate::models::Severity::Critical)
+            .unwrap();
+        let counts = severity_summary([(critical.id.as_ref(), 3), ("no-such-check", 5)], &checks);
+        assert_eq!(
+            counts,
+            SeverityCounts {
+                critical: 3,
+                ..SeverityCounts::default()
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn private_contract_audits_are_hidden_from_anonymous_callers() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let id = state.insert_private_contract().await;
+        let audit = Uuid::new_v4();
+
+        for uri in [
+            format!("/api/contracts/{}/security-score", id),
+            format!("/api/contracts/{}/security-audits", id),
+            format!("/api/contracts/{}/security-audit", id),
+            format!("/api/contracts/{}/security-audit/{}", id, audit),
+            format!("/api/contracts/{}/security-audit/{}/export", id, audit),
+        ] {
+            let status = state.anonymous_get(&uri).await;
+            assert_eq!(status, axum::http::StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+}
@@ -0,0 +1,343 @@
+// api/src/audit_pdf.rs
+// Renders a security audit as a shareable PDF.
+// Simple typographic layout with the built-in Helvetica faces; findings are
+// grouped by severity, each group in its own color.
+
+use std::collections::HashMap;
+
+use printpdf::{
+    BuiltinFont, Color, IndirectFontRef, Mm, PdfDocument, PdfDocumentReference,
+    PdfLayerReference, Rgb,
+};
+
+use crate::{
+    checklist::all_checks,
+    models::{AuditCheckRow, AuditRecord, CategoryScore, CheckStatus, ChecklistItem, Severity},
+};
+
+const PAGE_WIDTH: f32 = 210.0;
+const PAGE_HEIGHT: f32 = 297.0;
+const MARGIN: f32 = 18.0;
+/// Rough characters per line at 10pt Helvetica across the text block
+const WRAP_AT: usize = 95;
+
+pub const SEVERITY_ORDER: [Severity; 5] = [
+    Severity::Critical,
+    Severity::High,
+    Severity::Medium,
+    Severity::Low,
+    Severity::Info,
+];
+
+/// Heading color per severity (RGB, 0..1)
+pub fn severity_color(severity: &Severity) -> (f32, f32, f32) {
+    match severity {
+        Severity::Critical => (0.70, 0.05, 0.10),
+        Severity::High => (0.90, 0.40, 0.00),
+        Severity::Medium => (0.75, 0.60, 0.00),
+        Severity::Low => (0.10, 0.35, 0.80),
+        Severity::Info => (0.40, 0.40, 0.40),
+    }
+}
+
+/// Everything the report shows about one audit
+pub struct AuditReport<'a> {
+    pub contract_name: &'a str,
+    pub audit: &'a AuditRecord,
+    pub checks: &'a [AuditCheckRow],
+    pub category_scores: &'a [CategoryScore],
+}
+
+/// Failed checks grouped by severity, most severe first; empty groups omitted
+pub fn group_findings<'a>(
+    checks: &'a [AuditCheckRow],
+    meta: &'a HashMap<String, ChecklistItem>,
+) -> Vec<(Severity, Vec<(&'a ChecklistItem, &'a AuditCheckRow)>)> {
+    SEVERITY_ORDER
+        .iter()
+        .filter_map(|severity| {
+            let mut items: Vec<_> = checks
+                .iter()
+                .filter(|row| row.status == CheckStatus::Failed)
+                .filter_map(|row| meta.get(row.check_id.as_str()).map(|item| (item, row)))
+                .filter(|(item, _)| item.severity == *severity)
+                .collect();
+            if items.is_empty() {
+                return None;
+            }
+            items.sort_by(|a, b| a.0.id.cmp(&b.0.id));
+            Some((severity.clone(), items))
+        })
+        .collect()
+}
+
+/// Built-in PDF fonts only cover WinAnsi; keep text printable ASCII
+fn pdf_text(text: &str) -> String {
+    text.chars()
+        .map(|c| match c {
+            '—' | '–' => '-',
+            '‘' | '’' => '\'',
+            '“' | '”' => '"',
+            c if c.is_ascii() && !c.is_ascii_control() => c,
+            _ => '?',
+        })
+        .collect()
+}
+
+fn wrap(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+    for paragraph in text.lines() {
+        let mut line = String::new();
+        for word in paragraph.split_whitespace() {
+            if !line.is_empty() && line.len() + 1 + word.len() > width {
+                lines.push(std::mem::take(&mut line));
+            }
+            if !line.is_empty() {
+                line.push(' ');
+            }
+            line.push_str(word);
+        }
+        lines.push(line);
+    }
+    lines
+}
+
+/// Writes lines top to bottom, starting a new page when the current one fills
+struct PageWriter {
+    doc: PdfDocumentReference,
+    layer: PdfLayerReference,
+    regular: IndirectFontRef,
+    bold: IndirectFontRef,
+    y: f32,
+}
+
+impl PageWriter {
+    fn line(&mut self, text: &str, size: f32, bold: bool, color: (f32, f32, f32)) {
+        let height = size * 0.45;
+        if self.y - height < MARGIN {
+            let (page, layer) = self.doc.add_page(Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+            self.layer = self.doc.get_page(page).get_layer(layer);
+            self.y = PAGE_HEIGHT - MARGIN;
+        }
+        self.y -= height;
+        self.layer
+            .set_fill_color(Color::Rgb(Rgb::new(color.0, color.1, color.2, None)));
+        let font = if bold { &self.bold } else { &self.regular };
+        self.layer
+            .use_text(pdf_text(text), size, Mm(MARGIN), Mm(self.y), font);
+    }
+
+    fn paragraph(&mut self, text: &str, size: f32, color: (f32, f32, f32)) {
+        for line in wrap(text, WRAP_AT) {
+            self.line(&line, size, false, color);
+        }
+    }
+
+    fn gap(&mut self, mm: f32) {
+        self.y -= mm;
+    }
+}
+
+const BLACK: (f32, f32, f32) = (0.0, 0.0, 0.0);
+const GREY: (f32, f32, f32) = (0.35, 0.35, 0.35);
+
+pub fn render_audit_pdf(report: &AuditReport<'_>) -> Result<Vec<u8>, String> {
+    let title = format!("Security Audit - {}", report.contract_name);
+    let (doc, page, layer) =
+        PdfDocument::new(pdf_text(&title), Mm(PAGE_WIDTH), Mm(PAGE_HEIGHT), "Layer 1");
+    let regular = doc
+        .add_builtin_font(BuiltinFont::Helvetica)
+        .map_err(|e| e.to_string())?;
+    let bold = doc
+        .add_builtin_font(BuiltinFont::HelveticaBold)
+        .map_err(|e| e.to_string())?;
+    let layer = doc.get_page(page).get_layer(layer);
+    let mut out = PageWriter {
+        doc,
+        layer,
+        regular,
+        bold,
+        y: PAGE_HEIGHT - MARGIN,
+    };
+
+    let audit = report.audit;
+    out.line(&title, 18.0, true, BLACK);
+    out.gap(3.0);
+    out.line(&format!("Contract ID: {}", audit.contract_id), 10.0, false, GREY);
+    out.line(&format!("Auditor: {}", audit.auditor), 10.0, false, GREY);
+    out.line(
+        &format!("Audit date: {}", audit.audit_date.format("%Y-%m-%d %H:%M UTC")),
+        10.0,
+        false,
+        GREY,
+    );
+    out.line(
+        &format!(
+            "Created: {}   Last updated: {}",
+            audit.created_at.format("%Y-%m-%d %H:%M UTC"),
+            audit.updated_at.format("%Y-%m-%d %H:%M UTC")
+        ),
+        10.0,
+        false,
+        GREY,
+    );
+    out.gap(3.0);
+    out.line(
+        &format!("Overall score: {:.1}%", audit.overall_score),
+        14.0,
+        true,
+        BLACK,
+    );
+
+    out.gap(4.0);
+    out.line("Summary", 13.0, true, BLACK);
+    out.paragraph(
+        audit.summary.as_deref().unwrap_or("No summary provided."),
+        10.0,
+        BLACK,
+    );
+
+    out.gap(4.0);
+    out.line("Category scores", 13.0, true, BLACK);
+    for cs in report.category_scores {
+        out.line(
+            &format!(
+                "{}: {:.0}%  ({} of {} passed, {} critical / {} high failures)",
+                cs.category, cs.score, cs.passed, cs.total, cs.failed_critical, cs.failed_high
+            ),
+            10.0,
+            false,
+            BLACK,
+        );
+    }
+
+    let meta: HashMap<String, ChecklistItem> = all_checks()
+        .into_iter()
+        .map(|c| (c.id.to_string(), c))
+        .collect();
+    let groups = group_findings(report.checks, &meta);
+
+    out.gap(4.0);
+    out.line("Findings", 13.0, true, BLACK);
+    if groups.is_empty() {
+        out.line("No failed checks.", 10.0, false, BLACK);
+    }
+    for (severity, items) in &groups {
+        let color = severity_color(severity);
+        out.gap(2.0);
+        out.line(
+            &format!("{} severity ({})", severity, items.len()),
+            12.0,
+            true,
+            color,
+        );
+        for (item, row) in items {
+            out.line(&format!("[{}] {}", item.id, item.title), 10.0, true, color);
+            out.paragraph(&format!("Category: {}", item.category), 9.0, GREY);
+            out.paragraph(&item.description, 9.0, BLACK);
+            if let Some(evidence) = &row.evidence {
+                out.paragraph(&format!("Evidence: {}", evidence), 9.0, GREY);
+            }
+            if let Some(notes) = &row.notes {
+                out.paragraph(&format!("Auditor notes: {}", notes), 9.0, GREY);
+            }
+            out.paragraph(&format!("Remediation: {}", item.remediation), 9.0, BLACK);
+            out.gap(1.5);
+        }
+    }
+
+    let count = |status: CheckStatus| report.checks.iter().filter(|r| r.status == status).count();
+    out.gap(4.0);
+    out.line(
+        &format!(
+            "Checks: {} passed, {} failed, {} not applicable",
+            count(CheckStatus::Passed),
+            count(CheckStatus::Failed),
+            count(CheckStatus::NotApplicable)
+        ),
+        10.0,
+        false,
+        GREY,
+    );
+
+    out.doc.save_to_bytes().map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    fn row(check_id: &str, status: CheckStatus) -> AuditCheckRow {
+        AuditCheckRow {
+            id: Uuid::new_v4(),
+            audit_id: Uuid::nil(),
+            check_id: check_id.to_string(),
+            status,
+            notes: Some("seen in review".into()),
+            auto_detected: false,
+            evidence: None,
+            updated_at: Utc::now(),
+        }
+    }
+
+    fn failing_rows() -> Vec<AuditCheckRow> {
+        all_checks()
+            .into_iter()
+            .map(|c| row(&c.id, CheckStatus::Failed))
+            .collect()
+    }
+
+    #[test]
+    fn findings_are_grouped_most_severe_first() {
+        let meta: HashMap<String, ChecklistItem> = all_checks()
+            .into_iter()
+            .map(|c| (c.id.to_string(), c))
+            .collect();
+        let rows = failing_rows();
+        let groups = group_findings(&rows, &meta);
+
+        let severities: Vec<Severity> = groups.iter().map(|(s, _)| s.clone()).collect();
+        let mut sorted = severities.clone();
+        sorted.sort_by(|a, b| b.cmp(a));
+        assert_eq!(severities, sorted);
+
+        let total: usize = groups.iter().map(|(_, items)| items.len()).sum();
+        assert_eq!(total, rows.len());
+    }
+
+    #[test]
+    fn renders_a_pdf_document() {
+        let audit = AuditRecord {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            contract_source: None,
+            auditor: "Jane Auditor".into(),
+            audit_date: Utc::now(),
+            overall_score: 72.5,
+            summary: Some("Reviewed token contract — two issues".into()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+        };
+        let rows = failing_rows();
+        let bytes = render_audit_pdf(&AuditReport {
+            contract_name: "Token",
+            audit: &audit,
+            checks: &rows,
+            category_scores: &[],
+        })
+        .unwrap();
+        assert!(bytes.starts_with(b"%PDF"));
+    }
+
+    #[test]
+    fn distinct_colors_per_severity() {
+        let colors: Vec<_> = SEVERITY_ORDER.iter().map(severity_color).collect();
+        for (i, a) in colors.iter().enumerate() {
+            for b in &colors[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+}
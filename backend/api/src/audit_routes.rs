@@ -71,4 +71,13 @@ pub fn security_audit_routes() -> Router<AppState> {
             "/api/contracts/:id/security-audit/:audit_id/export",
             get(audit_handlers::export_audit_markdown),
         )
+
+        // Failed-check counts per severity across all audits (dashboard)
+        .route("/api/audits/summary", get(audit_handlers::get_audit_summary))
+
+        // Export a completed audit as a PDF report
+        .route(
+            "/api/audits/:id/report.pdf",
+            get(audit_handlers::export_audit_pdf),
+        )
 }
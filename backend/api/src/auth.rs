@@ -0,0 +1,621 @@
+//! API-key authentication for publisher write endpoints.
+//!
+//! Keys have the form `sr_<prefix>_<secret>`. The prefix is stored in clear
+//! and used to find the row; the full key is verified against an argon2 hash
+//! so a database leak does not expose usable credentials.
+//!
+//! `require_api_key` resolves the bearer token to a [`Caller`] and stores it
+//! in the request extensions. Handlers take `Extension<Caller>` and must use
+//! it instead of any publisher id supplied in the request body.
+
+use argon2::{
+    password_hash::{rand_core::OsRng, PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
+    Argon2,
+};
+use axum::{
+    extract::{Path, RawPathParams, Request, State},
+    http::header::AUTHORIZATION,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use shared::{Contract, ContractVisibility, OrganizationRole};
+use sqlx::PgPool;
+use subtle::ConstantTimeEq;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    admin_audit,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::db_internal_error,
+    organization_handlers::member_role,
+    state::AppState,
+};
+
+const KEY_SCHEME: &str = "sr";
+const KEY_PREFIX_LEN: usize = 8;
+const KEY_SECRET_LEN: usize = 32;
+/// Operator key that may act on behalf of any publisher (used to mint the
+/// first key for a publisher).
+const ADMIN_KEY_ENV: &str = "ADMIN_API_KEY";
+
+/// The authenticated principal behind a request
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Caller {
+    Admin,
+    Publisher(Uuid),
+}
+
+impl Caller {
+    pub fn publisher_id(&self) -> Option<Uuid> {
+        match self {
+            Caller::Admin => None,
+            Caller::Publisher(id) => Some(*id),
+        }
+    }
+
+    /// Identity recorded as `changed_by` in the contract history
+    pub fn audit_name(&self) -> String {
+        match self {
+            Caller::Admin => "admin".to_string(),
+            Caller::Publisher(id) => id.to_string(),
+        }
+    }
+
+    /// Allow the call only for the admin key.
+    pub fn require_admin(&self) -> ApiResult<()> {
+        match self {
+            Caller::Admin => Ok(()),
+            Caller::Publisher(_) => Err(forbidden()),
+        }
+    }
+
+    /// Allow the call only if it acts on `publisher_id` (admins always pass).
+    pub fn authorize_publisher(&self, publisher_id: Uuid) -> ApiResult<()> {
+        match self {
+            Caller::Admin => Ok(()),
+            Caller::Publisher(id) if *id == publisher_id => Ok(()),
+            Caller::Publisher(_) => Err(forbidden()),
+        }
+    }
+}
+
+pub fn forbidden() -> ApiError {
+    ApiError::new(
+        axum::http::StatusCode::FORBIDDEN,
+        ErrorCode::Forbidden,
+        "API key does not belong to the publisher being modified",
+    )
+}
+
+fn unauthorized(message: &str) -> ApiError {
+    ApiError::new(axum::http::StatusCode::UNAUTHORIZED, ErrorCode::Unauthorized, message)
+}
+
+/// Middleware that rejects requests without a valid `Authorization: Bearer` key
+pub async fn require_api_key(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let token = match bearer_token(&req) {
+        Some(token) => token.to_string(),
+        None => return unauthorized("Missing bearer API key").into_response(),
+    };
+
+    match authenticate(&state, &token).await {
+        Ok(Some(caller)) => run_as(caller, req, next).await,
+        Ok(None) => unauthorized("Invalid API key").into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Run the rest of the stack as `caller`. The caller is also copied onto the
+/// response so outer layers (per-publisher metrics) can attribute it.
+async fn run_as(caller: Caller, mut req: Request, next: Next) -> Response {
+    req.extensions_mut().insert(caller);
+    let mut response = next.run(req).await;
+    response.extensions_mut().insert(caller);
+    response
+}
+
+/// Like [`require_api_key`], but lets anonymous requests through.
+///
+/// A valid key still populates `Extension<Caller>` so public endpoints can
+/// personalise their response; a key that is present but invalid is rejected
+/// rather than silently ignored.
+pub async fn optional_api_key(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(token) = bearer_token(&req).map(str::to_string) else {
+        return next.run(req).await;
+    };
+
+    match authenticate(&state, &token).await {
+        Ok(Some(caller)) => run_as(caller, req, next).await,
+        Ok(None) => unauthorized("Invalid API key").into_response(),
+        Err(err) => err.into_response(),
+    }
+}
+
+/// The caller's role on the contract named by the route's `:id`, put in the
+/// request extensions by [`resolve_contract_role`]. `role` is `None` when the
+/// caller has no role there and may only read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractAccess {
+    pub contract_id: Uuid,
+    pub role: Option<OrganizationRole>,
+}
+
+impl ContractAccess {
+    /// Allow the call only with at least the `required` role on the contract.
+    pub fn require(&self, required: OrganizationRole) -> ApiResult<()> {
+        require_role(self.role, required, &format!("contract {}", self.contract_id))
+    }
+}
+
+/// 403 naming the missing role unless `role` grants `required` on `target`
+pub fn require_role(
+    role: Option<OrganizationRole>,
+    required: OrganizationRole,
+    target: &str,
+) -> ApiResult<()> {
+    match role {
+        Some(role) if role.allows(required) => Ok(()),
+        role => Err(ApiError::new(
+            axum::http::StatusCode::FORBIDDEN,
+            ErrorCode::Forbidden,
+            format!("This action requires the {} role on {}", required, target),
+        )
+        .with_details(serde_json::json!({ "required_role": required, "role": role }))),
+    }
+}
+
+/// Middleware resolving [`ContractAccess`] for `/api/contracts/:id/...`
+/// routes. It must run inside [`require_api_key`] so the caller is known.
+pub async fn resolve_contract_role(
+    State(state): State<AppState>,
+    params: RawPathParams,
+    mut req: Request,
+    next: Next,
+) -> Response {
+    let raw_id = params
+        .iter()
+        .find(|(name, _)| *name == "id")
+        .map(|(_, value)| value.to_string());
+    let caller = req.extensions().get::<Caller>().copied();
+    let (Some(raw_id), Some(caller)) = (raw_id, caller) else {
+        return next.run(req).await;
+    };
+    if !req.uri().path().starts_with("/api/contracts/") {
+        return next.run(req).await;
+    }
+
+    let Ok(contract_id) = Uuid::parse_str(&raw_id) else {
+        return ApiError::bad_request(
+            ErrorCode::InvalidContractId,
+            format!("Invalid contract ID format: {}", raw_id),
+        )
+        .into_response();
+    };
+    match contract_role(&state.db, &caller, contract_id).await {
+        Ok(role) => {
+            req.extensions_mut()
+                .insert(ContractAccess { contract_id, role });
+            next.run(req).await
+        }
+        Err(err) => err.into_response(),
+    }
+}
+
+/// Look up the caller's role on a contract (404 if it does not exist)
+pub async fn contract_role(
+    db: &PgPool,
+    caller: &Caller,
+    contract_id: Uuid,
+) -> ApiResult<Option<OrganizationRole>> {
+    let (publisher_id, organization_id): (Uuid, Option<Uuid>) =
+        sqlx::query_as("SELECT publisher_id, organization_id FROM contracts WHERE id = $1")
+            .bind(contract_id)
+            .fetch_optional(db)
+            .await
+            .map_err(|err| db_internal_error("get contract owner", err))?
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    ErrorCode::ContractNotFound,
+                    format!("No contract found with ID: {}", contract_id),
+                )
+            })?;
+
+    let membership = match (caller.publisher_id(), organization_id) {
+        (Some(publisher), Some(organization)) => member_role(db, organization, publisher)
+            .await
+            .map_err(|err| db_internal_error("get organization role", err))?,
+        _ => None,
+    };
+    Ok(effective_role(caller, publisher_id, organization_id, membership))
+}
+
+/// Admins own everything. An organization's contract goes by membership;
+/// a personal contract is owned by its publisher alone.
+fn effective_role(
+    caller: &Caller,
+    publisher_id: Uuid,
+    organization_id: Option<Uuid>,
+    membership: Option<OrganizationRole>,
+) -> Option<OrganizationRole> {
+    match (caller, organization_id) {
+        (Caller::Admin, _) => Some(OrganizationRole::Owner),
+        (Caller::Publisher(_), Some(_)) => membership,
+        (Caller::Publisher(id), None) if *id == publisher_id => Some(OrganizationRole::Owner),
+        (Caller::Publisher(_), None) => None,
+    }
+}
+
+/// Whether `caller` may see `contract`. Private contracts are visible to
+/// whoever has a role on them: the publisher, members of the owning
+/// organization, and admins.
+pub async fn can_view_contract(
+    db: &PgPool,
+    caller: Option<&Caller>,
+    contract: &Contract,
+) -> ApiResult<bool> {
+    if contract.visibility == ContractVisibility::Public {
+        return Ok(true);
+    }
+    let Some(caller) = caller else {
+        return Ok(false);
+    };
+    let membership = match (caller.publisher_id(), contract.organization_id) {
+        (Some(publisher), Some(organization)) => member_role(db, organization, publisher)
+            .await
+            .map_err(|err| db_internal_error("get organization role", err))?,
+        _ => None,
+    };
+    Ok(effective_role(
+        caller,
+        contract.publisher_id,
+        contract.organization_id,
+        membership,
+    )
+    .is_some())
+}
+
+fn bearer_token<B>(req: &axum::http::Request<B>) -> Option<&str> {
+    req.headers()
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+}
+
+/// Constant-time, so response timing can't be used to guess the admin key
+/// byte by byte. Only the length can leak, and it is not secret.
+fn is_admin_key(admin: &str, token: &str) -> bool {
+    !admin.is_empty() && bool::from(admin.as_bytes().ct_eq(token.as_bytes()))
+}
+
+async fn authenticate(state: &AppState, token: &str) -> ApiResult<Option<Caller>> {
+    if let Ok(admin) = std::env::var(ADMIN_KEY_ENV) {
+        if is_admin_key(&admin, token) {
+            return Ok(Some(Caller::Admin));
+        }
+    }
+
+    let Some(prefix) = key_prefix(token) else {
+        return Ok(None);
+    };
+
+    // Keys of a deactivated publisher are kept, so reactivating restores them
+    let row: Option<(Uuid, Uuid, String)> = sqlx::query_as(
+        "SELECT k.id, k.publisher_id, k.key_hash FROM publisher_api_keys k
+         JOIN publishers p ON p.id = k.publisher_id
+         WHERE k.key_prefix = $1 AND k.revoked_at IS NULL AND p.deactivated_at IS NULL",
+    )
+    .bind(prefix)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("look up api key", err))?;
+
+    let Some((key_id, publisher_id, key_hash)) = row else {
+        return Ok(None);
+    };
+
+    if !verify_key(token, &key_hash) {
+        return Ok(None);
+    }
+
+    let pool = state.db.clone();
+    tokio::spawn(async move {
+        if let Err(err) = sqlx::query("UPDATE publisher_api_keys SET last_used_at = NOW() WHERE id = $1")
+            .bind(key_id)
+            .execute(&pool)
+            .await
+        {
+            tracing::warn!(error = ?err, "failed to record api key usage");
+        }
+    });
+
+    Ok(Some(Caller::Publisher(publisher_id)))
+}
+
+/// Extract the lookup prefix from a raw key, or `None` if it is malformed.
+fn key_prefix(raw: &str) -> Option<&str> {
+    let mut parts = raw.splitn(3, '_');
+    let scheme = parts.next()?;
+    let prefix = parts.next()?;
+    let secret = parts.next()?;
+    if scheme != KEY_SCHEME || prefix.len() != KEY_PREFIX_LEN || secret.len() != KEY_SECRET_LEN {
+        return None;
+    }
+    Some(prefix)
+}
+
+fn random_alphanumeric(len: usize) -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(len)
+        .map(char::from)
+        .collect()
+}
+
+/// Generate a new raw key and its argon2 hash
+fn generate_key() -> Result<(String, String, String), argon2::password_hash::Error> {
+    let prefix = random_alphanumeric(KEY_PREFIX_LEN).to_lowercase();
+    let raw = format!(
+        "{}_{}_{}",
+        KEY_SCHEME,
+        prefix,
+        random_alphanumeric(KEY_SECRET_LEN)
+    );
+    let salt = SaltString::generate(&mut OsRng);
+    let hash = Argon2::default()
+        .hash_password(raw.as_bytes(), &salt)?
+        .to_string();
+    Ok((raw, prefix, hash))
+}
+
+/// Mint a key for `publisher` straight into the database
+#[cfg(test)]
+pub(crate) async fn issue_test_key(db: &PgPool, publisher_id: Uuid) -> String {
+    let (raw, prefix, hash) = generate_key().unwrap();
+    sqlx::query(
+        "INSERT INTO publisher_api_keys (publisher_id, key_prefix, key_hash) VALUES ($1, $2, $3)",
+    )
+    .bind(publisher_id)
+    .bind(prefix)
+    .bind(hash)
+    .execute(db)
+    .await
+    .expect("insert api key");
+    raw
+}
+
+fn verify_key(raw: &str, stored_hash: &str) -> bool {
+    PasswordHash::new(stored_hash)
+        .map(|parsed| {
+            Argon2::default()
+                .verify_password(raw.as_bytes(), &parsed)
+                .is_ok()
+        })
+        .unwrap_or(false)
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateApiKeyRequest {
+    pub label: Option<String>,
+}
+
+/// Returned once on creation; the raw key cannot be retrieved again
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedApiKey {
+    pub id: Uuid,
+    pub publisher_id: Uuid,
+    pub key: String,
+    pub key_prefix: String,
+    pub label: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Mint a new API key for a publisher
+#[utoipa::path(
+    post,
+    path = "/api/publishers/{id}/keys",
+    tag = "publishers",
+    params(
+        ("id" = Uuid, Path, description = "Publisher UUID"),
+    ),
+    request_body = CreateApiKeyRequest,
+    responses(
+        (status = 200, description = "New key; the raw value is only returned once", body = CreatedApiKey),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 403, description = "Key belongs to a different publisher"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(publisher_id): Path<Uuid>,
+    body: Option<Json<CreateApiKeyRequest>>,
+) -> ApiResult<Json<CreatedApiKey>> {
+    caller.authorize_publisher(publisher_id)?;
+    let label = body
+        .and_then(|Json(req)| req.label)
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM publishers WHERE id = $1)")
+        .bind(publisher_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("check publisher", err))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            ErrorCode::PublisherNotFound,
+            format!("No publisher found with ID: {}", publisher_id),
+        ));
+    }
+
+    let (raw, prefix, hash) = generate_key().map_err(|err| {
+        tracing::error!(error = %err, "failed to hash api key");
+        ApiError::internal("Failed to generate API key")
+    })?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin create api key", err))?;
+    let (id, created_at): (Uuid, DateTime<Utc>) = sqlx::query_as(
+        "INSERT INTO publisher_api_keys (publisher_id, key_prefix, key_hash, label)
+         VALUES ($1, $2, $3, $4)
+         RETURNING id, created_at",
+    )
+    .bind(publisher_id)
+    .bind(&prefix)
+    .bind(&hash)
+    .bind(&label)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("create api key", err))?;
+    admin_audit::record(
+        &mut *tx,
+        &caller,
+        admin_audit::API_KEY_CREATED,
+        "publisher",
+        &publisher_id.to_string(),
+        Some(serde_json::json!({ "key_id": id, "key_prefix": prefix })),
+    )
+    .await
+    .map_err(|err| db_internal_error("record api key in admin audit log", err))?;
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit create api key", err))?;
+
+    Ok(Json(CreatedApiKey {
+        id,
+        publisher_id,
+        key: raw,
+        key_prefix: prefix,
+        label,
+        created_at,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_key_round_trips() {
+        let (raw, prefix, hash) = generate_key().unwrap();
+        assert_eq!(key_prefix(&raw), Some(prefix.as_str()));
+        assert!(verify_key(&raw, &hash));
+        assert!(!verify_key(&format!("{}x", raw), &hash));
+    }
+
+    #[test]
+    fn only_the_exact_admin_key_matches() {
+        assert!(is_admin_key("s3cret-admin", "s3cret-admin"));
+        assert!(!is_admin_key("s3cret-admin", "s3cret-admiN"));
+        assert!(!is_admin_key("s3cret-admin", "s3cret"));
+        assert!(!is_admin_key("", ""));
+    }
+
+    #[test]
+    fn malformed_keys_have_no_prefix() {
+        assert_eq!(key_prefix("not-a-key"), None);
+        assert_eq!(key_prefix("sr_short_secret"), None);
+        assert_eq!(key_prefix(&format!("xx_abcdefgh_{}", "a".repeat(32))), None);
+    }
+
+    #[test]
+    fn publisher_callers_are_scoped() {
+        let own = Uuid::new_v4();
+        let other = Uuid::new_v4();
+        assert!(Caller::Publisher(own).authorize_publisher(own).is_ok());
+        assert!(Caller::Publisher(own).authorize_publisher(other).is_err());
+        assert!(Caller::Admin.authorize_publisher(other).is_ok());
+    }
+
+    #[test]
+    fn roles_resolve_from_ownership_and_membership() {
+        let (publisher, other, org) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+        let owner = Some(OrganizationRole::Owner);
+
+        assert_eq!(effective_role(&Caller::Publisher(publisher), publisher, None, None), owner);
+        assert_eq!(effective_role(&Caller::Publisher(other), publisher, None, None), None);
+        assert_eq!(effective_role(&Caller::Admin, publisher, Some(org), None), owner);
+        // Organization contracts go by membership, even for the original publisher
+        assert_eq!(
+            effective_role(
+                &Caller::Publisher(publisher),
+                publisher,
+                Some(org),
+                Some(OrganizationRole::Viewer)
+            ),
+            Some(OrganizationRole::Viewer)
+        );
+        assert_eq!(effective_role(&Caller::Publisher(other), publisher, Some(org), None), None);
+    }
+
+    /// Yank stands in for the owner-only endpoints
+    #[tokio::test]
+    async fn only_owners_may_yank() {
+        use crate::state::{json_body, test_request};
+        use axum::http::StatusCode;
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let mut members = Vec::new();
+        for role in ["owner", "maintainer", "viewer"] {
+            members.push((state.insert_publisher().await, role));
+        }
+        let outsider = state.insert_publisher().await;
+        let organization = state.insert_organization(&members).await;
+        let contract = state
+            .insert_contract(members[0].0, Some(organization), "public")
+            .await;
+        state.insert_version(contract, "1.0.0").await;
+        let uri = format!("/api/contracts/{}/versions/1.0.0/yank", contract);
+        let yank_as = |publisher: Uuid| {
+            let (state, uri) = (state.clone(), uri.clone());
+            async move {
+                let key = state.api_key(publisher).await;
+                state.send(test_request("POST", &uri, Some(&key), None)).await
+            }
+        };
+
+        for publisher in [members[1].0, members[2].0, outsider] {
+            assert_eq!(yank_as(publisher).await.status(), StatusCode::FORBIDDEN);
+        }
+        let json = json_body(yank_as(members[1].0).await).await;
+        assert!(json["message"].as_str().unwrap().contains("requires the owner role"));
+        assert_eq!(json["details"]["required_role"], "owner");
+        assert_eq!(json["details"]["role"], "maintainer");
+
+        assert_eq!(yank_as(members[0].0).await.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn maintainers_may_publish_but_viewers_may_not() {
+        let access = |role| ContractAccess {
+            contract_id: Uuid::nil(),
+            role: Some(role),
+        };
+        assert!(access(OrganizationRole::Owner).require(OrganizationRole::Maintainer).is_ok());
+        assert!(access(OrganizationRole::Maintainer).require(OrganizationRole::Maintainer).is_ok());
+        assert!(access(OrganizationRole::Viewer).require(OrganizationRole::Maintainer).is_err());
+        assert!(access(OrganizationRole::Viewer).require(OrganizationRole::Viewer).is_ok());
+    }
+}
@@ -0,0 +1,423 @@
+//! README badges for a contract's latest version and composite score.
+//!
+//! `version.svg` and `score.svg` render in the shields.io "flat" style, and
+//! `badge.json` answers in the shields.io endpoint schema so a badge can also
+//! be drawn by shields itself. The SVG names only common system fonts and
+//! pins each text run with `textLength`, so it lays out the same wherever the
+//! font falls back.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::header,
+    response::{IntoResponse, Response},
+    Extension, Json,
+};
+use quick_xml::escape::escape;
+use serde::{Deserialize, Serialize};
+use shared::{Contract, ContractVersion, ContractVisibility};
+use uuid::Uuid;
+
+use crate::{
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::{db_internal_error, fetch_visible_contract, highest_matching_version},
+    share_tokens::PresentedShareToken,
+    state::AppState,
+};
+
+/// How long GitHub's camo proxy and other caches may keep a badge
+pub const BADGE_MAX_AGE_SECONDS: u32 = 300;
+
+const LABEL_COLOR: &str = "#555";
+const VERSION_COLOR: &str = "#007ec6";
+const MISSING_COLOR: &str = "#9f9f9f";
+const SCORE_GREEN: &str = "#4c1";
+const SCORE_YELLOW: &str = "#dfb317";
+const SCORE_RED: &str = "#e05d44";
+
+/// Scores at or above these are green, then yellow; anything lower is red
+pub const SCORE_GREEN_AT: f64 = 80.0;
+pub const SCORE_YELLOW_AT: f64 = 50.0;
+
+/// A badge's two halves
+#[derive(Debug, Clone, PartialEq)]
+pub struct Badge {
+    pub label: String,
+    pub message: String,
+    pub color: &'static str,
+}
+
+impl Badge {
+    pub fn version(version: Option<&str>) -> Self {
+        match version {
+            Some(version) => Self {
+                label: "version".to_string(),
+                message: format!("v{}", version.trim_start_matches('v')),
+                color: VERSION_COLOR,
+            },
+            None => Self {
+                label: "version".to_string(),
+                message: "none".to_string(),
+                color: MISSING_COLOR,
+            },
+        }
+    }
+
+    pub fn score(composite: Option<f64>) -> Self {
+        match composite {
+            Some(score) => Self {
+                label: "score".to_string(),
+                message: format!("{:.0}", score),
+                color: score_color(score),
+            },
+            None => Self {
+                label: "score".to_string(),
+                message: "unscored".to_string(),
+                color: MISSING_COLOR,
+            },
+        }
+    }
+}
+
+pub fn score_color(score: f64) -> &'static str {
+    if score >= SCORE_GREEN_AT {
+        SCORE_GREEN
+    } else if score >= SCORE_YELLOW_AT {
+        SCORE_YELLOW
+    } else {
+        SCORE_RED
+    }
+}
+
+/// Approximate advance of `text` in 11px Verdana, the font shields measures with
+pub fn text_width(text: &str) -> f64 {
+    text.chars()
+        .map(|c| match c {
+            'i' | 'j' | 'l' | '.' | ',' | ':' | ';' | '|' | '!' | '\'' => 3.4,
+            ' ' | 'f' | 'r' | 't' | '(' | ')' | '[' | ']' | '-' => 4.6,
+            'm' | 'w' | 'M' | 'W' => 10.6,
+            '0'..='9' => 7.0,
+            c if c.is_ascii_uppercase() => 7.6,
+            _ => 6.6,
+        })
+        .sum::<f64>()
+        .ceil()
+}
+
+/// shields.io "flat" SVG
+pub fn render_svg(badge: &Badge) -> String {
+    let label = escape(badge.label.as_str());
+    let message = escape(badge.message.as_str());
+    let label_text = text_width(&badge.label);
+    let message_text = text_width(&badge.message);
+    let label_width = label_text + 10.0;
+    let message_width = message_text + 10.0;
+    let width = label_width + message_width;
+    // Text is drawn at 10x and scaled down, as shields does, for crisper hinting
+    let label_x = label_width * 5.0;
+    let message_x = (label_width + message_width / 2.0) * 10.0;
+
+    format!(
+        concat!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="20" role="img" aria-label="{label}: {message}">"##,
+            r##"<title>{label}: {message}</title>"##,
+            r##"<linearGradient id="s" x2="0" y2="100%"><stop offset="0" stop-color="#bbb" stop-opacity=".1"/><stop offset="1" stop-opacity=".1"/></linearGradient>"##,
+            r##"<clipPath id="r"><rect width="{width}" height="20" rx="3" fill="#fff"/></clipPath>"##,
+            r##"<g clip-path="url(#r)"><rect width="{label_width}" height="20" fill="{label_color}"/><rect x="{label_width}" width="{message_width}" height="20" fill="{color}"/><rect width="{width}" height="20" fill="url(#s)"/></g>"##,
+            r##"<g fill="#fff" text-anchor="middle" font-family="Verdana,Geneva,DejaVu Sans,sans-serif" text-rendering="geometricPrecision" font-size="110">"##,
+            r##"<text aria-hidden="true" x="{label_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)" textLength="{label_length}">{label}</text>"##,
+            r##"<text x="{label_x}" y="140" transform="scale(.1)" fill="#fff" textLength="{label_length}">{label}</text>"##,
+            r##"<text aria-hidden="true" x="{message_x}" y="150" fill="#010101" fill-opacity=".3" transform="scale(.1)" textLength="{message_length}">{message}</text>"##,
+            r##"<text x="{message_x}" y="140" transform="scale(.1)" fill="#fff" textLength="{message_length}">{message}</text>"##,
+            r##"</g></svg>"##,
+        ),
+        width = width,
+        label = label,
+        message = message,
+        label_width = label_width,
+        message_width = message_width,
+        label_color = LABEL_COLOR,
+        color = badge.color,
+        label_x = label_x,
+        message_x = message_x,
+        label_length = label_text * 10.0,
+        message_length = message_text * 10.0,
+    )
+}
+
+/// Body of a shields.io endpoint badge
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShieldsEndpoint {
+    pub schema_version: u8,
+    pub label: String,
+    pub message: String,
+    pub color: String,
+    pub cache_seconds: u32,
+}
+
+impl From<Badge> for ShieldsEndpoint {
+    fn from(badge: Badge) -> Self {
+        Self {
+            schema_version: 1,
+            label: badge.label,
+            message: badge.message,
+            color: badge.color.trim_start_matches('#').to_string(),
+            cache_seconds: BADGE_MAX_AGE_SECONDS,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BadgeJsonParams {
+    /// `version` (default) or `score`
+    pub kind: Option<String>,
+}
+
+/// Shared caches may keep a public contract's badge; a private contract's
+/// badge was served on the caller's credentials and must not be kept at all
+fn cache_control(contract: &Contract) -> String {
+    match contract.visibility {
+        ContractVisibility::Public => format!("public, max-age={}", BADGE_MAX_AGE_SECONDS),
+        ContractVisibility::Private => "private, no-store".to_string(),
+    }
+}
+
+fn svg_response(contract: &Contract, badge: &Badge) -> Response {
+    (
+        [
+            (
+                header::CONTENT_TYPE,
+                "image/svg+xml; charset=utf-8".to_string(),
+            ),
+            (header::CACHE_CONTROL, cache_control(contract)),
+        ],
+        render_svg(badge),
+    )
+        .into_response()
+}
+
+async fn version_badge(state: &AppState, id: Uuid) -> ApiResult<Badge> {
+    let versions: Vec<ContractVersion> =
+        sqlx::query_as("SELECT * FROM contract_versions WHERE contract_id = $1")
+            .bind(id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|err| db_internal_error("list versions for badge", err))?;
+    let latest = highest_matching_version(&semver::VersionReq::STAR, &versions)
+        .or_else(|| versions.iter().max_by_key(|v| v.created_at));
+    Ok(Badge::version(latest.map(|v| v.version.as_str())))
+}
+
+async fn score_badge(state: &AppState, id: Uuid) -> ApiResult<Badge> {
+    let composite: Option<f64> =
+        sqlx::query_scalar("SELECT composite FROM contract_scores WHERE contract_id = $1")
+            .bind(id)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get score for badge", err))?;
+    Ok(Badge::score(composite))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/badge/version.svg",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "SVG badge with the latest version", content_type = "image/svg+xml"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
+pub async fn version_badge_svg(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Response> {
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract =
+        fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+    Ok(svg_response(&contract, &version_badge(&state, id).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/badge/score.svg",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "SVG badge with the composite score, colored by threshold", content_type = "image/svg+xml"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
+pub async fn score_badge_svg(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Response> {
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract =
+        fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+    Ok(svg_response(&contract, &score_badge(&state, id).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/badge.json",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("kind" = Option<String>, Query, description = "`version` (default) or `score`"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "shields.io endpoint badge"),
+        (status = 400, description = "Unknown badge kind"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
+pub async fn badge_json(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+    Query(params): Query<BadgeJsonParams>,
+) -> ApiResult<Response> {
+    let score = match params.kind.as_deref() {
+        None | Some("version") => false,
+        Some("score") => true,
+        Some(other) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidBadgeKind,
+                format!("kind must be 'version' or 'score' (got '{}')", other),
+            ));
+        }
+    };
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract =
+        fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+    let badge = if score {
+        score_badge(&state, id).await?
+    } else {
+        version_badge(&state, id).await?
+    };
+    Ok((
+        [(header::CACHE_CONTROL, cache_control(&contract))],
+        Json(ShieldsEndpoint::from(badge)),
+    )
+        .into_response())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+
+    #[test]
+    fn score_color_follows_thresholds() {
+        assert_eq!(Badge::score(Some(92.4)).color, SCORE_GREEN);
+        assert_eq!(Badge::score(Some(80.0)).color, SCORE_GREEN);
+        assert_eq!(Badge::score(Some(65.0)).color, SCORE_YELLOW);
+        assert_eq!(Badge::score(Some(12.0)).color, SCORE_RED);
+        assert_eq!(Badge::score(Some(92.4)).message, "92");
+        assert_eq!(Badge::score(None).color, MISSING_COLOR);
+    }
+
+    #[test]
+    fn svg_is_escaped_and_sized_to_its_text() {
+        let short = render_svg(&Badge::version(Some("1.0.0")));
+        let long = render_svg(&Badge::version(Some("10.20.30-rc.1")));
+        let width = |svg: &str| -> f64 {
+            let start = svg.find("width=\"").unwrap() + 7;
+            let end = start + svg[start..].find('"').unwrap();
+            svg[start..end].parse().unwrap()
+        };
+        assert!(width(&long) > width(&short));
+        assert!(short.contains(">v1.0.0</text>"));
+        assert!(!short.contains("@import") && !short.contains("<style"));
+
+        let hostile = Badge {
+            label: "<script>".to_string(),
+            message: "a&b".to_string(),
+            color: VERSION_COLOR,
+        };
+        let svg = render_svg(&hostile);
+        assert!(svg.contains("&lt;script&gt;") && svg.contains("a&amp;b"));
+        assert!(!svg.contains("<script>"));
+    }
+
+    #[test]
+    fn endpoint_json_uses_the_shields_schema() {
+        let json = serde_json::to_value(ShieldsEndpoint::from(Badge::score(Some(55.0)))).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "schemaVersion": 1,
+                "label": "score",
+                "message": "55",
+                "color": "dfb317",
+                "cacheSeconds": BADGE_MAX_AGE_SECONDS,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn private_contracts_have_no_badges_for_anonymous_callers() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let id = state.insert_private_contract().await;
+
+        for uri in [
+            format!("/api/contracts/{}/badge/version.svg", id),
+            format!("/api/contracts/{}/badge/score.svg", id),
+            format!("/api/contracts/{}/badge.json?kind=score", id),
+        ] {
+            let status = state.anonymous_get(&uri).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn only_public_badges_may_be_cached() {
+        use crate::state::test_request;
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let publisher = state.insert_publisher().await;
+        let key = state.api_key(publisher).await;
+        for (visibility, expected) in [
+            (
+                "public",
+                format!("public, max-age={}", BADGE_MAX_AGE_SECONDS),
+            ),
+            ("private", "private, no-store".to_string()),
+        ] {
+            let id = state.insert_contract(publisher, None, visibility).await;
+            for uri in [
+                format!("/api/contracts/{}/badge/version.svg", id),
+                format!("/api/contracts/{}/badge.json", id),
+            ] {
+                let response = state
+                    .send(test_request("GET", &uri, Some(&key), None))
+                    .await;
+                assert_eq!(response.status(), StatusCode::OK, "{}", uri);
+                assert_eq!(
+                    response.headers()[header::CACHE_CONTROL],
+                    expected,
+                    "{}",
+                    uri
+                );
+            }
+        }
+    }
+}
@@ -3,8 +3,11 @@
 // In production this calls the actual Soroban CLI/RPC; here we simulate with
 // realistic timing so the full plumbing works end-to-end.
 
+use std::collections::BTreeMap;
 use std::time::{Duration, Instant};
 
+use crate::models::MetricDelta;
+
 /// Raw timing result from one iteration
 #[derive(Debug, Clone)]
 pub struct IterationResult {
@@ -157,6 +160,123 @@ pub fn check_regression(baseline_p95: f64, current_p95: f64, threshold_pct: f64)
     (delta_pct > threshold_pct, delta_pct)
 }
 
+/// Compare two metric sets (all "lower is better"). Returns the deltas for
+/// metrics present in both, then the names present only in `from` / `to`.
+/// `threshold_pct` gives the allowed increase for each metric.
+pub fn compare_metrics(
+    from: &BTreeMap<String, f64>,
+    to: &BTreeMap<String, f64>,
+    threshold_pct: impl Fn(&str) -> f64,
+) -> (Vec<MetricDelta>, Vec<String>, Vec<String>) {
+    let mut deltas = Vec::new();
+    let mut only_in_from = Vec::new();
+    for (metric, &from_value) in from {
+        let Some(&to_value) = to.get(metric) else {
+            only_in_from.push(metric.clone());
+            continue;
+        };
+        let delta_pct = if from_value == 0.0 {
+            None
+        } else {
+            Some((to_value - from_value) / from_value * 100.0)
+        };
+        deltas.push(MetricDelta {
+            metric: metric.clone(),
+            from_value,
+            to_value,
+            delta: to_value - from_value,
+            delta_pct,
+            is_regression: delta_pct
+                .map(|pct| pct > threshold_pct(metric))
+                .unwrap_or(false),
+        });
+    }
+    let only_in_to = to.keys().filter(|m| !from.contains_key(*m)).cloned().collect();
+    (deltas, only_in_from, only_in_to)
+}
+
+/// One method's results flattened into `<method>.<stat>` metrics, the shape
+/// `compare_metrics` works on. Resource metrics are left out when unmeasured.
+pub fn method_metrics(
+    method: &str,
+    stats: &BenchmarkStats,
+    cpu_instructions: Option<f64>,
+    memory_bytes: Option<f64>,
+) -> BTreeMap<String, f64> {
+    let mut metrics = BTreeMap::new();
+    metrics.insert(format!("{}.avg_ms", method), stats.avg_ms);
+    metrics.insert(format!("{}.p95_ms", method), stats.p95_ms);
+    metrics.insert(format!("{}.p99_ms", method), stats.p99_ms);
+    if let Some(cpu) = cpu_instructions {
+        metrics.insert(format!("{}.cpu_instructions", method), cpu);
+    }
+    if let Some(memory) = memory_bytes {
+        metrics.insert(format!("{}.memory_bytes", method), memory);
+    }
+    metrics
+}
+
+/// Mean of the measured values, `None` when no iteration measured any
+pub fn mean_of(values: impl Iterator<Item = Option<i64>>) -> Option<f64> {
+    let (sum, n) = values
+        .flatten()
+        .fold((0.0, 0usize), |(sum, n), v| (sum + v as f64, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+/// Default allowed increase before a metric counts as a regression
+pub const DEFAULT_REGRESSION_THRESHOLD_PCT: f64 = 10.0;
+
+/// Per-metric regression thresholds for one contract.
+///
+/// Overrides are keyed either by a full metric (`transfer.p95_ms`) or by a
+/// stat (`p95_ms`, applying to every method); the full name wins.
+#[derive(Debug, Clone, Default)]
+pub struct RegressionThresholds {
+    pub default_pct: Option<f64>,
+    pub overrides: BTreeMap<String, f64>,
+}
+
+impl RegressionThresholds {
+    pub fn threshold_for(&self, metric: &str) -> f64 {
+        if let Some(pct) = self.overrides.get(metric) {
+            return *pct;
+        }
+        let stat = metric.rsplit_once('.').map(|(_, stat)| stat).unwrap_or(metric);
+        self.overrides
+            .get(stat)
+            .copied()
+            .or(self.default_pct)
+            .unwrap_or_else(default_threshold_from_env)
+    }
+}
+
+/// `BENCHMARK_REGRESSION_THRESHOLD_PCT`, falling back to 10%
+fn default_threshold_from_env() -> f64 {
+    std::env::var("BENCHMARK_REGRESSION_THRESHOLD_PCT")
+        .ok()
+        .and_then(|v| v.parse::<f64>().ok())
+        .filter(|v| v.is_finite() && *v >= 0.0)
+        .unwrap_or(DEFAULT_REGRESSION_THRESHOLD_PCT)
+}
+
+/// Metrics of `current` that regressed against `previous` beyond their threshold
+pub fn find_regressions(
+    previous: &BTreeMap<String, f64>,
+    current: &BTreeMap<String, f64>,
+    thresholds: &RegressionThresholds,
+) -> Vec<(MetricDelta, f64)> {
+    let (deltas, _, _) = compare_metrics(previous, current, |m| thresholds.threshold_for(m));
+    deltas
+        .into_iter()
+        .filter(|d| d.is_regression)
+        .map(|d| {
+            let threshold = thresholds.threshold_for(&d.metric);
+            (d, threshold)
+        })
+        .collect()
+}
+
 /// Minimal LCG pseudo-random (avoids the `rand` crate dependency)
 fn rand_f64() -> f64 {
     use std::time::SystemTime;
@@ -241,6 +361,85 @@ mod tests {
         assert!(!is_reg); // 5% increase < 10% threshold
     }
 
+    #[test]
+    fn compare_reports_intersection_and_leftovers() {
+        let from: BTreeMap<String, f64> = [
+            ("transfer.p95_ms".to_string(), 10.0),
+            ("transfer.cpu_instructions".to_string(), 1000.0),
+            ("mint.p95_ms".to_string(), 5.0),
+        ]
+        .into();
+        let to: BTreeMap<String, f64> = [
+            ("transfer.p95_ms".to_string(), 10.5),
+            ("transfer.cpu_instructions".to_string(), 1300.0),
+            ("burn.p95_ms".to_string(), 2.0),
+        ]
+        .into();
+
+        let (deltas, only_from, only_to) = compare_metrics(&from, &to, |_| 10.0);
+        assert_eq!(deltas.len(), 2);
+        let cpu = deltas.iter().find(|d| d.metric == "transfer.cpu_instructions").unwrap();
+        assert!(cpu.is_regression);
+        assert!((cpu.delta_pct.unwrap() - 30.0).abs() < 1e-9);
+        let p95 = deltas.iter().find(|d| d.metric == "transfer.p95_ms").unwrap();
+        assert!(!p95.is_regression);
+        assert_eq!(only_from, vec!["mint.p95_ms".to_string()]);
+        assert_eq!(only_to, vec!["burn.p95_ms".to_string()]);
+    }
+
+    #[test]
+    fn run_compares_only_against_metrics_the_baseline_measured() {
+        let baseline: BTreeMap<String, f64> = [
+            ("transfer.avg_ms".to_string(), 8.0),
+            ("transfer.p95_ms".to_string(), 10.0),
+            ("transfer.p99_ms".to_string(), 12.0),
+            ("mint.p95_ms".to_string(), 5.0),
+        ]
+        .into();
+        let stats = BenchmarkStats::compute(vec![8.0, 9.0, 12.0]);
+        let cpu = mean_of([Some(900), None, Some(1100)].into_iter());
+        assert_eq!(cpu, Some(1000.0));
+        assert_eq!(mean_of([None, None].into_iter()), None);
+
+        let current = method_metrics("transfer", &stats, cpu, None);
+        assert_eq!(current.len(), 4);
+        let (deltas, _, _) = compare_metrics(&baseline, &current, |_| 10.0);
+        assert_eq!(deltas.len(), 3);
+        let p95 = deltas.iter().find(|d| d.metric == "transfer.p95_ms").unwrap();
+        assert!((p95.delta_pct.unwrap() - 20.0).abs() < 1e-9);
+        assert!(p95.is_regression);
+    }
+
+    #[test]
+    fn thresholds_prefer_full_metric_then_stat() {
+        let thresholds = RegressionThresholds {
+            default_pct: Some(10.0),
+            overrides: [
+                ("cpu_instructions".to_string(), 5.0),
+                ("transfer.cpu_instructions".to_string(), 50.0),
+            ]
+            .into(),
+        };
+        assert_eq!(thresholds.threshold_for("transfer.cpu_instructions"), 50.0);
+        assert_eq!(thresholds.threshold_for("mint.cpu_instructions"), 5.0);
+        assert_eq!(thresholds.threshold_for("mint.p95_ms"), 10.0);
+
+        let previous: BTreeMap<String, f64> = [
+            ("transfer.cpu_instructions".to_string(), 100.0),
+            ("mint.cpu_instructions".to_string(), 100.0),
+        ]
+        .into();
+        let current: BTreeMap<String, f64> = [
+            ("transfer.cpu_instructions".to_string(), 130.0),
+            ("mint.cpu_instructions".to_string(), 107.0),
+        ]
+        .into();
+        let regressions = find_regressions(&previous, &current, &thresholds);
+        assert_eq!(regressions.len(), 1);
+        assert_eq!(regressions[0].0.metric, "mint.cpu_instructions");
+        assert_eq!(regressions[0].1, 5.0);
+    }
+
     #[test]
     fn consistency_check() {
         // Tight distribution — should be consistent
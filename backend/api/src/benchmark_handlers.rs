@@ -4,25 +4,47 @@
 
 use axum::{
     extract::{Path, Query, State},
-    Json,
+    http::StatusCode,
+    Extension, Json,
 };
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
 use crate::{
-    benchmark_engine::{check_regression, format_cli_output, BenchmarkRunner, BenchmarkStats},
-    error::{ApiError, ApiResult},
+    auth::ContractAccess,
+    benchmark_engine::{
+        check_regression, compare_metrics, find_regressions, format_cli_output, mean_of,
+        method_metrics, BenchmarkRunner, BenchmarkStats, RegressionThresholds,
+    },
+    error::{ApiError, ApiResult, ErrorCode},
+    pagination::Paginated,
     state::AppState,
 };
 use crate::models::{
-    BenchmarkComparison, BenchmarkRecord, BenchmarkResponse, BenchmarkRun, BenchmarkStatus,
-    BenchmarkTrendPoint, ContractBenchmarkSummary, PerformanceAlert, RunBenchmarkRequest,
+    BaselineComparison, BenchmarkBaseline, BenchmarkBaselineResponse, BenchmarkComparison,
+    BenchmarkRecord, BenchmarkResponse, BenchmarkRun, BenchmarkStatus, BenchmarkTrendPoint,
+    BenchmarkVersionComparison, BenchmarkWarning, ContractBenchmarkSummary, PerformanceAlert,
+    RunBenchmarkRequest, SetBenchmarkBaselineRequest,
 };
+use crate::webhooks;
+use shared::models::OrganizationRole;
 
 // ─────────────────────────────────────────────────────────
 // POST /api/contracts/:id/benchmarks
 // Runs N iterations of a method and persists results.
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/benchmarks",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "Benchmark results"),
+    ),
+)]
 pub async fn run_benchmark(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
@@ -33,7 +55,7 @@ pub async fn run_benchmark(
         .bind(contract_id)
         .fetch_one(&state.db)
         .await
-        .map_err(|_| ApiError::not_found("ContractNotFound", format!("No contract found with ID: {}", contract_id)))?;
+        .map_err(|_| ApiError::not_found(ErrorCode::ContractNotFound, format!("No contract found with ID: {}", contract_id)))?;
 
     let iterations = req.iterations.clamp(1, 1000) as usize;
     let version = req.version.as_deref().unwrap_or("unknown");
@@ -107,19 +129,22 @@ pub async fn run_benchmark(
     .await
     .map_err(|_| ApiError::db_error("Failed to update benchmark stats"))?;
 
-    // Compare vs previous baseline for same method
+    // Compare vs the pinned baseline's latest run of this method, falling back
+    // to the previous run when there is no baseline or it never ran the method
+    let baseline = load_baseline(&state, contract_id).await?;
     let maybe_previous: Option<BenchmarkRecord> = sqlx::query_as(
         r#"SELECT * FROM benchmark_records
            WHERE contract_id = $1
              AND method_name = $2
              AND status = 'completed'
              AND id != $3
-           ORDER BY created_at DESC
+           ORDER BY contract_version = $4 DESC NULLS LAST, created_at DESC
            LIMIT 1"#,
     )
     .bind(contract_id)
     .bind(&req.method)
     .bind(benchmark.id)
+    .bind(baseline.as_ref().map(|b| b.version.as_str()))
     .fetch_optional(&state.db)
     .await
     .map_err(|_| ApiError::db_error("Failed to fetch previous benchmark for comparison"))?;
@@ -194,11 +219,31 @@ pub async fn run_benchmark(
         "Benchmark completed"
     );
 
+    let benchmark_warnings = match req.version.as_deref() {
+        Some(version) => check_version_regressions(&state, contract_id, version).await?,
+        None => Vec::new(),
+    };
+
+    let baseline = match baseline {
+        Some(baseline) => {
+            let current = method_metrics(
+                &req.method,
+                &stats,
+                mean_of(raw_results.iter().map(|r| r.cpu_instructions)),
+                mean_of(raw_results.iter().map(|r| r.memory_bytes)),
+            );
+            Some(compare_with_baseline(&state, contract_id, baseline.version, &current).await?)
+        }
+        None => None,
+    };
+
     Ok(Json(BenchmarkResponse {
         benchmark,
         runs,
         alert,
         comparison,
+        benchmark_warnings,
+        baseline,
     }))
 }
 
@@ -206,41 +251,78 @@ pub async fn run_benchmark(
 // GET /api/contracts/:id/benchmarks
 // List all benchmarks for a contract.
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/benchmarks",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("method" = Option<String>, Query, description = "Restrict to one method"),
+        ("limit" = Option<i64>, Query, description = "Page size, 1–100 (default 20)"),
+        ("offset" = Option<i64>, Query, description = "Rows to skip (default 0)"),
+    ),
+    responses(
+        (status = 200, description = "Benchmark history, newest first"),
+    ),
+)]
 pub async fn list_benchmarks(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
     Query(params): Query<ListBenchmarksParams>,
-) -> ApiResult<Json<Vec<BenchmarkRecord>>> {
+) -> ApiResult<Json<Paginated<BenchmarkRecord>>> {
     let limit = params.limit.unwrap_or(20).clamp(1, 100);
+    let offset = params.offset.unwrap_or(0).max(0);
     let method_filter = params.method.as_deref().unwrap_or("%");
 
     let records: Vec<BenchmarkRecord> = sqlx::query_as(
         r#"SELECT * FROM benchmark_records
            WHERE contract_id = $1
              AND method_name LIKE $2
-           ORDER BY created_at DESC
-           LIMIT $3"#,
+           ORDER BY created_at DESC, id DESC
+           LIMIT $3 OFFSET $4"#,
     )
     .bind(contract_id)
     .bind(method_filter)
-    .bind(limit as i64)
+    .bind(limit)
+    .bind(offset)
     .fetch_all(&state.db)
     .await
     .map_err(|_| ApiError::db_error("Failed to fetch benchmark records"))?;
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM benchmark_records WHERE contract_id = $1 AND method_name LIKE $2",
+    )
+    .bind(contract_id)
+    .bind(method_filter)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|_| ApiError::db_error("Failed to count benchmark records"))?;
 
-    Ok(Json(records))
+    Ok(Json(Paginated::new(records, total, limit, offset)))
 }
 
 #[derive(Debug, Deserialize)]
 pub struct ListBenchmarksParams {
     pub method: Option<String>,
-    pub limit: Option<usize>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
 }
 
 // ─────────────────────────────────────────────────────────
 // GET /api/contracts/:id/benchmarks/:benchmark_id
 // Get a single benchmark with all run data.
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/benchmarks/{benchmark_id}",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("benchmark_id" = Uuid, Path, description = "Benchmark UUID"),
+    ),
+    responses(
+        (status = 200, description = "Benchmark details with runs"),
+    ),
+)]
 pub async fn get_benchmark(
     State(state): State<AppState>,
     Path((contract_id, benchmark_id)): Path<(Uuid, Uuid)>,
@@ -251,7 +333,7 @@ pub async fn get_benchmark(
             .bind(contract_id)
             .fetch_one(&state.db)
             .await
-            .map_err(|_| ApiError::not_found("BenchmarkNotFound", format!("No benchmark found with ID: {}", benchmark_id)))?;
+            .map_err(|_| ApiError::not_found(ErrorCode::BenchmarkNotFound, format!("No benchmark found with ID: {}", benchmark_id)))?;
 
     let runs: Vec<BenchmarkRun> =
         sqlx::query_as("SELECT * FROM benchmark_runs WHERE benchmark_id = $1 ORDER BY iteration")
@@ -272,6 +354,8 @@ pub async fn get_benchmark(
         runs,
         alert,
         comparison: None,
+        benchmark_warnings: Vec::new(),
+        baseline: None,
     }))
 }
 
@@ -279,6 +363,18 @@ pub async fn get_benchmark(
 // GET /api/contracts/:id/benchmarks/trend?method=transfer
 // Returns time-series data for the dashboard chart.
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/benchmarks/trend",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("method" = Option<String>, Query, description = "Restrict to one method"),
+    ),
+    responses(
+        (status = 200, description = "Time series of p95/avg"),
+    ),
+)]
 pub async fn get_benchmark_trend(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
@@ -316,10 +412,403 @@ pub struct TrendParams {
     pub method: Option<String>,
 }
 
+// ─────────────────────────────────────────────────────────
+// GET /api/contracts/:id/benchmarks/compare?from=1.0.0&to=1.1.0
+// Side-by-side metrics for two versions with regression flags.
+// ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/benchmarks/compare",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("from" = String, Query, description = "Baseline version"),
+        ("to" = String, Query, description = "Version compared against the baseline"),
+        ("threshold_pct" = Option<f64>, Query, description = "Regression threshold in percent (default 10)"),
+    ),
+    responses(
+        (status = 200, description = "Per-metric deltas between the two versions"),
+        (status = 404, description = "A version has no completed benchmarks"),
+    ),
+)]
+pub async fn compare_benchmarks(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+    Query(params): Query<CompareParams>,
+) -> ApiResult<Json<BenchmarkVersionComparison>> {
+    let threshold_pct = params.threshold_pct.unwrap_or(10.0);
+    if !threshold_pct.is_finite() || threshold_pct < 0.0 {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidThreshold,
+            "threshold_pct must be a non-negative number",
+        ));
+    }
+
+    let from = version_metrics(&state, contract_id, &params.from).await?;
+    let to = version_metrics(&state, contract_id, &params.to).await?;
+    let (metrics, only_in_from, only_in_to) = compare_metrics(&from, &to, |_| threshold_pct);
+    let regressions = metrics.iter().filter(|m| m.is_regression).count();
+
+    Ok(Json(BenchmarkVersionComparison {
+        contract_id,
+        from: params.from,
+        to: params.to,
+        threshold_pct,
+        metrics,
+        only_in_from,
+        only_in_to,
+        regressions,
+    }))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CompareParams {
+    pub from: String,
+    pub to: String,
+    pub threshold_pct: Option<f64>,
+}
+
+async fn version_metrics(
+    state: &AppState,
+    contract_id: Uuid,
+    version: &str,
+) -> ApiResult<BTreeMap<String, f64>> {
+    let metrics = load_version_metrics(state, contract_id, version).await?;
+    if metrics.is_empty() {
+        return Err(ApiError::not_found(
+            ErrorCode::BenchmarksNotFound,
+            format!("No completed benchmarks for version {}", version),
+        ));
+    }
+    Ok(metrics)
+}
+
+/// Latest completed benchmark per method for a version, flattened into
+/// `<method>.<stat>` metrics. Resource metrics are averaged over the runs.
+async fn load_version_metrics(
+    state: &AppState,
+    contract_id: Uuid,
+    version: &str,
+) -> ApiResult<BTreeMap<String, f64>> {
+    let rows: Vec<(String, f64, f64, f64, Option<f64>, Option<f64>)> = sqlx::query_as(
+        r#"SELECT DISTINCT ON (r.method_name)
+               r.method_name,
+               r.avg_ms,
+               r.p95_ms,
+               r.p99_ms,
+               (SELECT AVG(cpu_instructions)::float8 FROM benchmark_runs WHERE benchmark_id = r.id),
+               (SELECT AVG(memory_bytes)::float8 FROM benchmark_runs WHERE benchmark_id = r.id)
+           FROM benchmark_records r
+           WHERE r.contract_id = $1
+             AND r.contract_version = $2
+             AND r.status = 'completed'
+           ORDER BY r.method_name, r.created_at DESC"#,
+    )
+    .bind(contract_id)
+    .bind(version)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|_| ApiError::db_error("Failed to fetch benchmarks for comparison"))?;
+
+    let mut metrics = BTreeMap::new();
+    for (method, avg_ms, p95_ms, p99_ms, cpu, memory) in rows {
+        metrics.insert(format!("{}.avg_ms", method), avg_ms);
+        metrics.insert(format!("{}.p95_ms", method), p95_ms);
+        metrics.insert(format!("{}.p99_ms", method), p99_ms);
+        if let Some(cpu) = cpu {
+            metrics.insert(format!("{}.cpu_instructions", method), cpu);
+        }
+        if let Some(memory) = memory {
+            metrics.insert(format!("{}.memory_bytes", method), memory);
+        }
+    }
+    Ok(metrics)
+}
+
+/// Per-metric thresholds configured via `PUT /api/contracts/:id/benchmark-thresholds`
+pub(crate) async fn load_thresholds(
+    state: &AppState,
+    contract_id: Uuid,
+) -> ApiResult<RegressionThresholds> {
+    let rows: Vec<(String, f64)> = sqlx::query_as(
+        "SELECT metric, threshold_pct FROM benchmark_thresholds WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|_| ApiError::db_error("Failed to load benchmark thresholds"))?;
+
+    let mut thresholds = RegressionThresholds::default();
+    for (metric, pct) in rows {
+        if metric == "*" {
+            thresholds.default_pct = Some(pct);
+        } else {
+            thresholds.overrides.insert(metric, pct);
+        }
+    }
+    Ok(thresholds)
+}
+
+/// Compare `version`'s stored results with the pinned baseline, or with the
+/// previously benchmarked version when no baseline is set.
+///
+/// New regressions are recorded in `benchmark_regression_alerts` and sent to
+/// the publisher's webhooks; the full list is returned either way so callers
+/// (publish, run_benchmark) can surface it.
+pub(crate) async fn check_version_regressions(
+    state: &AppState,
+    contract_id: Uuid,
+    version: &str,
+) -> ApiResult<Vec<BenchmarkWarning>> {
+    let current = load_version_metrics(state, contract_id, version).await?;
+    if current.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let previous_version: Option<String> = match load_baseline(state, contract_id).await? {
+        // The baseline has nothing to regress against
+        Some(baseline) if baseline.version == version => return Ok(Vec::new()),
+        Some(baseline) => Some(baseline.version),
+        None => latest_other_version(state, contract_id, version).await?,
+    };
+    let Some(previous_version) = previous_version else {
+        return Ok(Vec::new());
+    };
+
+    let previous = load_version_metrics(state, contract_id, &previous_version).await?;
+    let thresholds = load_thresholds(state, contract_id).await?;
+
+    let warnings: Vec<BenchmarkWarning> = find_regressions(&previous, &current, &thresholds)
+        .into_iter()
+        .map(|(delta, threshold_pct)| BenchmarkWarning {
+            metric: delta.metric,
+            previous_version: previous_version.clone(),
+            version: version.to_string(),
+            previous_value: delta.from_value,
+            value: delta.to_value,
+            regression_pct: delta.delta_pct.unwrap_or_default(),
+            threshold_pct,
+        })
+        .collect();
+
+    let mut newly_recorded = Vec::new();
+    for warning in &warnings {
+        let inserted = sqlx::query(
+            r#"INSERT INTO benchmark_regression_alerts
+                   (contract_id, metric, previous_version, version,
+                    previous_value, value, regression_pct, threshold_pct)
+               VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+               ON CONFLICT (contract_id, version, previous_version, metric) DO NOTHING"#,
+        )
+        .bind(contract_id)
+        .bind(&warning.metric)
+        .bind(&warning.previous_version)
+        .bind(&warning.version)
+        .bind(warning.previous_value)
+        .bind(warning.value)
+        .bind(warning.regression_pct)
+        .bind(warning.threshold_pct)
+        .execute(&state.db)
+        .await
+        .map_err(|_| ApiError::db_error("Failed to record benchmark regression"))?;
+        if inserted.rows_affected() > 0 {
+            newly_recorded.push(warning.clone());
+        }
+    }
+
+    if !newly_recorded.is_empty() {
+        tracing::warn!(
+            contract_id = %contract_id,
+            version = %version,
+            regressions = newly_recorded.len(),
+            "Benchmark regression against previous version"
+        );
+        webhooks::dispatch_benchmark_regression(state.db.clone(), contract_id, newly_recorded);
+    }
+
+    Ok(warnings)
+}
+
+/// The most recently benchmarked version other than `version`
+async fn latest_other_version(
+    state: &AppState,
+    contract_id: Uuid,
+    version: &str,
+) -> ApiResult<Option<String>> {
+    sqlx::query_scalar(
+        r#"SELECT contract_version
+           FROM benchmark_records
+           WHERE contract_id = $1
+             AND status = 'completed'
+             AND contract_version NOT IN ($2, 'unknown')
+           GROUP BY contract_version
+           ORDER BY MAX(created_at) DESC
+           LIMIT 1"#,
+    )
+    .bind(contract_id)
+    .bind(version)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|_| ApiError::db_error("Failed to find previous benchmarked version"))
+}
+
+pub(crate) async fn load_baseline(
+    state: &AppState,
+    contract_id: Uuid,
+) -> ApiResult<Option<BenchmarkBaseline>> {
+    sqlx::query_as("SELECT * FROM benchmark_baselines WHERE contract_id = $1")
+        .bind(contract_id)
+        .fetch_optional(&state.db)
+        .await
+        .map_err(|_| ApiError::db_error("Failed to load benchmark baseline"))
+}
+
+/// Deltas of a single run's metrics against the baseline version's
+async fn compare_with_baseline(
+    state: &AppState,
+    contract_id: Uuid,
+    version: String,
+    current: &BTreeMap<String, f64>,
+) -> ApiResult<BaselineComparison> {
+    let baseline = load_version_metrics(state, contract_id, &version).await?;
+    let thresholds = load_thresholds(state, contract_id).await?;
+    let (metrics, _, _) = compare_metrics(&baseline, current, |m| thresholds.threshold_for(m));
+    let regressions = metrics.iter().filter(|m| m.is_regression).count();
+    Ok(BaselineComparison {
+        version,
+        metrics,
+        regressions,
+    })
+}
+
+// ─────────────────────────────────────────────────────────
+// GET /api/contracts/:id/benchmarks/baseline
+// The pinned baseline version and its metrics.
+// ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/benchmarks/baseline",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "Baseline version with its latest metric per method"),
+        (status = 404, description = "No baseline is set"),
+    ),
+)]
+pub async fn get_benchmark_baseline(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> ApiResult<Json<BenchmarkBaselineResponse>> {
+    let baseline = load_baseline(&state, contract_id).await?.ok_or_else(|| {
+        ApiError::not_found(
+            ErrorCode::BaselineNotFound,
+            format!("No benchmark baseline set for contract {}", contract_id),
+        )
+    })?;
+    let metrics = load_version_metrics(&state, contract_id, &baseline.version).await?;
+    Ok(Json(BenchmarkBaselineResponse { baseline, metrics }))
+}
+
+// ─────────────────────────────────────────────────────────
+// POST /api/contracts/:id/benchmarks/baseline
+// Pin a benchmarked version as the baseline, replacing any previous one.
+// ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/benchmarks/baseline",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "The new baseline with its metrics"),
+        (status = 403, description = "Caller is not a maintainer of the contract"),
+        (status = 404, description = "The version has no completed benchmarks"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn set_benchmark_baseline(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Path(contract_id): Path<Uuid>,
+    Json(req): Json<SetBenchmarkBaselineRequest>,
+) -> ApiResult<Json<BenchmarkBaselineResponse>> {
+    access.require(OrganizationRole::Maintainer)?;
+    let version = req.version.trim();
+    let metrics = version_metrics(&state, contract_id, version).await?;
+
+    let baseline: BenchmarkBaseline = sqlx::query_as(
+        r#"INSERT INTO benchmark_baselines (contract_id, version)
+           VALUES ($1, $2)
+           ON CONFLICT (contract_id) DO UPDATE
+               SET version = EXCLUDED.version, set_at = NOW()
+           RETURNING *"#,
+    )
+    .bind(contract_id)
+    .bind(version)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|_| ApiError::db_error("Failed to set benchmark baseline"))?;
+
+    tracing::info!(contract_id = %contract_id, version = %version, "Benchmark baseline set");
+    Ok(Json(BenchmarkBaselineResponse { baseline, metrics }))
+}
+
+// ─────────────────────────────────────────────────────────
+// DELETE /api/contracts/:id/benchmarks/baseline
+// Unpin the baseline; runs compare with the previous one again.
+// ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    delete,
+    path = "/api/contracts/{id}/benchmarks/baseline",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 204, description = "Baseline cleared"),
+        (status = 403, description = "Caller is not a maintainer of the contract"),
+        (status = 404, description = "No baseline is set"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn clear_benchmark_baseline(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Path(contract_id): Path<Uuid>,
+) -> ApiResult<StatusCode> {
+    access.require(OrganizationRole::Maintainer)?;
+    let deleted = sqlx::query("DELETE FROM benchmark_baselines WHERE contract_id = $1")
+        .bind(contract_id)
+        .execute(&state.db)
+        .await
+        .map_err(|_| ApiError::db_error("Failed to clear benchmark baseline"))?;
+    if deleted.rows_affected() == 0 {
+        return Err(ApiError::not_found(
+            ErrorCode::BaselineNotFound,
+            format!("No benchmark baseline set for contract {}", contract_id),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
 // ─────────────────────────────────────────────────────────
 // GET /api/contracts/:id/benchmarks/summary
 // Dashboard summary: methods benchmarked, latest results, active alerts.
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/benchmarks/summary",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "Latest result per method and open alerts"),
+    ),
+)]
 pub async fn get_benchmark_summary(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
@@ -371,6 +860,18 @@ pub async fn get_benchmark_summary(
 // ─────────────────────────────────────────────────────────
 // POST /api/contracts/:id/benchmarks/alerts/:alert_id/resolve
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/benchmarks/alerts/{alert_id}/resolve",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("alert_id" = Uuid, Path, description = "Alert UUID"),
+    ),
+    responses(
+        (status = 200, description = "Alert resolved"),
+    ),
+)]
 pub async fn resolve_alert(
     State(state): State<AppState>,
     Path((contract_id, alert_id)): Path<(Uuid, Uuid)>,
@@ -387,7 +888,7 @@ pub async fn resolve_alert(
 
     if rows == 0 {
         return Err(ApiError::not_found(
-            "AlertNotFound",
+            ErrorCode::AlertNotFound,
             format!("No performance alert found with ID: {}", alert_id),
         ));
     }
@@ -399,6 +900,18 @@ pub async fn resolve_alert(
 // GET /api/contracts/:id/benchmarks/cli-output/:benchmark_id
 // Returns the CLI-style formatted output for a completed benchmark.
 // ─────────────────────────────────────────────────────────
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/benchmarks/{benchmark_id}/cli-output",
+    tag = "benchmarks",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("benchmark_id" = Uuid, Path, description = "Benchmark UUID"),
+    ),
+    responses(
+        (status = 200, description = "Plain-text CLI report"),
+    ),
+)]
 pub async fn get_cli_output(
     State(state): State<AppState>,
     Path((contract_id, benchmark_id)): Path<(Uuid, Uuid)>,
@@ -409,11 +922,11 @@ pub async fn get_cli_output(
             .bind(contract_id)
             .fetch_one(&state.db)
             .await
-            .map_err(|_| ApiError::not_found("BenchmarkNotFound", format!("No benchmark found with ID: {}", benchmark_id)))?;
+            .map_err(|_| ApiError::not_found(ErrorCode::BenchmarkNotFound, format!("No benchmark found with ID: {}", benchmark_id)))?;
 
     if benchmark.status != BenchmarkStatus::Completed {
         return Err(ApiError::unprocessable(
-            "BenchmarkNotCompleted",
+            ErrorCode::BenchmarkNotCompleted,
             format!("Benchmark {} has status {:?} and cannot produce CLI output", benchmark_id, benchmark.status),
         ));
     }
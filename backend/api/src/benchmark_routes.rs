@@ -35,6 +35,18 @@ pub fn benchmark_routes() -> Router<AppState> {
             "/api/contracts/:id/benchmarks/trend",
             get(benchmark_handlers::get_benchmark_trend),
         )
+        // ── Two versions side by side ──────────────────────────────────────
+        // ?from=1.0.0&to=1.1.0[&threshold_pct=10]
+        .route(
+            "/api/contracts/:id/benchmarks/compare",
+            get(benchmark_handlers::compare_benchmarks),
+        )
+        // ── Pinned baseline that new runs are compared against ─────────────
+        // Setting and clearing it are in routes::authenticated_routes
+        .route(
+            "/api/contracts/:id/benchmarks/baseline",
+            get(benchmark_handlers::get_benchmark_baseline),
+        )
         // ── Single benchmark detail with run-level data ────────────────────
         .route(
             "/api/contracts/:id/benchmarks/:benchmark_id",
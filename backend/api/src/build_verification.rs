@@ -0,0 +1,422 @@
+//! Reproducible-build checks: rebuild a published version from a claimed
+//! source reference and compare the resulting WASM hash with the published
+//! one.
+//!
+//! Enabled by setting `BUILD_SERVICE_URL` to a service that runs the
+//! containerized rebuild. It receives `POST {url}/builds` with the source
+//! reference and the version's `build_info`, and answers
+//! `{"wasm_hash": "<sha256 hex>"}` once the build finishes. Without it the
+//! endpoint answers 501.
+//!
+//! Rebuilds run in the background. The outcome is stored on the version
+//! (`build_verification`), so it shows up in version responses and a repeat
+//! request for the same source reference is answered from there.
+
+use std::time::Duration;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use shared::{
+    BuildInfo, BuildVerification, BuildVerificationStatus, ContractVersion, OrganizationRole,
+    VerifyBuildRequest,
+};
+use sqlx::{types::Json as DbJson, PgPool};
+use uuid::Uuid;
+
+use crate::{
+    auth::ContractAccess,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::db_internal_error,
+    state::AppState,
+    validation::ValidatedJson,
+};
+
+/// Longest a rebuild may take before it is abandoned; a pending check older
+/// than this may be restarted
+const BUILD_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Clone)]
+pub struct BuildServiceClient {
+    url: String,
+}
+
+#[derive(Serialize)]
+struct BuildJob<'a> {
+    source_url: &'a str,
+    git_ref: &'a str,
+    #[serde(flatten)]
+    build_info: &'a BuildInfo,
+}
+
+#[derive(Deserialize)]
+struct BuildOutput {
+    wasm_hash: String,
+}
+
+impl BuildServiceClient {
+    /// Returns `None` when `BUILD_SERVICE_URL` is unset, which disables
+    /// verification.
+    pub fn from_env() -> Option<Self> {
+        Self::from_url(std::env::var("BUILD_SERVICE_URL").ok())
+    }
+
+    fn from_url(url: Option<String>) -> Option<Self> {
+        url.map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .map(|url| Self { url })
+    }
+
+    /// Rebuild `git_ref` of `source_url` with the given toolchain, returning
+    /// the sha256 of the WASM produced.
+    pub async fn rebuild(
+        &self,
+        source_url: &str,
+        git_ref: &str,
+        build_info: &BuildInfo,
+    ) -> Result<String, String> {
+        let client = reqwest::Client::builder()
+            .timeout(BUILD_TIMEOUT)
+            .build()
+            .map_err(|err| err.to_string())?;
+
+        let response = client
+            .post(format!("{}/builds", self.url))
+            .json(&BuildJob {
+                source_url,
+                git_ref,
+                build_info,
+            })
+            .send()
+            .await
+            .map_err(|err| format!("build service unreachable: {}", err))?;
+
+        if !response.status().is_success() {
+            return Err(format!("build service returned HTTP {}", response.status()));
+        }
+
+        let output: BuildOutput = response
+            .json()
+            .await
+            .map_err(|err| format!("unexpected build service response: {}", err))?;
+        let hash = output.wasm_hash.trim().to_ascii_lowercase();
+        if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(format!(
+                "build service returned an invalid WASM hash '{}'",
+                output.wasm_hash
+            ));
+        }
+        Ok(hash)
+    }
+}
+
+/// A finished check of the same source reference, which a new request reuses
+fn is_cached(previous: &BuildVerification, req: &VerifyBuildRequest) -> bool {
+    matches!(
+        previous.status,
+        BuildVerificationStatus::Verified | BuildVerificationStatus::Mismatch
+    ) && previous.source_url == req.source_url
+        && previous.git_ref == req.git_ref
+}
+
+/// A rebuild still within its time budget; only one runs per version
+fn is_running(previous: &BuildVerification, now: DateTime<Utc>) -> bool {
+    previous.status == BuildVerificationStatus::Pending
+        && now - previous.requested_at < chrono::Duration::seconds(BUILD_TIMEOUT.as_secs() as i64)
+}
+
+/// The stored outcome once the build service has answered
+fn complete(
+    pending: BuildVerification,
+    rebuilt: Result<String, String>,
+    now: DateTime<Utc>,
+) -> BuildVerification {
+    let mut outcome = BuildVerification {
+        completed_at: Some(now),
+        ..pending
+    };
+    match rebuilt {
+        Ok(hash) => {
+            let reproducible = hash.eq_ignore_ascii_case(&outcome.published_wasm_hash);
+            outcome.status = if reproducible {
+                BuildVerificationStatus::Verified
+            } else {
+                BuildVerificationStatus::Mismatch
+            };
+            outcome.message = (!reproducible).then(|| {
+                format!(
+                    "Rebuilt WASM hash {} does not match published hash {}",
+                    hash, outcome.published_wasm_hash
+                )
+            });
+            outcome.reproducible = Some(reproducible);
+            outcome.rebuilt_wasm_hash = Some(hash);
+        }
+        Err(reason) => {
+            outcome.status = BuildVerificationStatus::Failed;
+            outcome.message = Some(reason);
+        }
+    }
+    outcome
+}
+
+/// Run the rebuild in the background and store the outcome on the version.
+fn spawn_rebuild(
+    client: BuildServiceClient,
+    pool: PgPool,
+    version_id: Uuid,
+    build_info: BuildInfo,
+    pending: BuildVerification,
+) {
+    tokio::spawn(async move {
+        let rebuilt = client
+            .rebuild(&pending.source_url, &pending.git_ref, &build_info)
+            .await;
+        let outcome = complete(pending, rebuilt, Utc::now());
+        match outcome.status {
+            BuildVerificationStatus::Mismatch => tracing::warn!(
+                version_id = %version_id,
+                published = %outcome.published_wasm_hash,
+                rebuilt = ?outcome.rebuilt_wasm_hash,
+                "rebuilt wasm does not match the published artifact"
+            ),
+            BuildVerificationStatus::Failed => tracing::warn!(
+                version_id = %version_id,
+                error = ?outcome.message,
+                "reproducible build failed"
+            ),
+            _ => {}
+        }
+
+        if let Err(err) =
+            sqlx::query("UPDATE contract_versions SET build_verification = $2 WHERE id = $1")
+                .bind(version_id)
+                .bind(DbJson(&outcome))
+                .execute(&pool)
+                .await
+        {
+            tracing::error!(version_id = %version_id, error = ?err, "failed to record build verification");
+        }
+    });
+}
+
+/// Rebuild a version from source and check it matches the published WASM.
+///
+/// Starts a rebuild and answers 202 with the pending check; the outcome
+/// lands on the version's `build_verification`. A finished check of the same
+/// `source_url` and `git_ref` is returned as-is with 200.
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/versions/{version}/verify-build",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("version" = String, Path, description = "Version to rebuild"),
+    ),
+    request_body = VerifyBuildRequest,
+    responses(
+        (status = 200, description = "Cached outcome for this source reference", body = BuildVerification),
+        (status = 202, description = "Rebuild started", body = BuildVerification),
+        (status = 403, description = "Caller lacks the maintainer role on the contract"),
+        (status = 404, description = "Version not found"),
+        (status = 409, description = "A rebuild of this version is already running"),
+        (status = 422, description = "The version was published without build_info"),
+        (status = 501, description = "No build service is configured"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn verify_build(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Path((id, version)): Path<(String, String)>,
+    ValidatedJson(req): ValidatedJson<VerifyBuildRequest>,
+) -> ApiResult<(StatusCode, Json<BuildVerification>)> {
+    access.require(OrganizationRole::Maintainer)?;
+    let client = BuildServiceClient::from_env().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::NOT_IMPLEMENTED,
+            ErrorCode::BuildServiceUnavailable,
+            "Reproducible-build verification is not enabled on this registry",
+        )
+    })?;
+
+    let row: ContractVersion =
+        sqlx::query_as("SELECT * FROM contract_versions WHERE contract_id = $1 AND version = $2")
+            .bind(access.contract_id)
+            .bind(&version)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get version for build verification", err))?
+            .ok_or_else(|| {
+                ApiError::not_found(
+                    ErrorCode::VersionNotFound,
+                    format!("Version {} not found for contract {}", version, id),
+                )
+            })?;
+
+    let build_info = row.build_info.map(|DbJson(info)| info).ok_or_else(|| {
+        ApiError::unprocessable(
+            ErrorCode::BuildInfoMissing,
+            format!(
+                "Version {} was published without build_info, so it cannot be rebuilt",
+                version
+            ),
+        )
+    })?;
+
+    let previous = row.build_verification.map(|DbJson(previous)| previous);
+    let now = Utc::now();
+    if let Some(previous) = &previous {
+        if is_cached(previous, &req) {
+            return Ok((StatusCode::OK, Json(previous.clone())));
+        }
+        if is_running(previous, now) {
+            return Err(in_progress(&version));
+        }
+    }
+
+    let pending = BuildVerification {
+        status: BuildVerificationStatus::Pending,
+        source_url: req.source_url,
+        git_ref: req.git_ref,
+        published_wasm_hash: row.wasm_hash,
+        rebuilt_wasm_hash: None,
+        reproducible: None,
+        message: None,
+        requested_at: now,
+        completed_at: None,
+    };
+
+    // Only the request that replaces what it read starts a rebuild
+    let claimed = sqlx::query(
+        "UPDATE contract_versions SET build_verification = $2
+         WHERE id = $1 AND build_verification IS NOT DISTINCT FROM $3",
+    )
+    .bind(row.id)
+    .bind(DbJson(&pending))
+    .bind(previous.as_ref().map(DbJson))
+    .execute(&state.db)
+    .await
+    .map_err(|err| db_internal_error("start build verification", err))?
+    .rows_affected()
+        == 1;
+    if !claimed {
+        return Err(in_progress(&version));
+    }
+
+    spawn_rebuild(
+        client,
+        state.db.clone(),
+        row.id,
+        build_info,
+        pending.clone(),
+    );
+    Ok((StatusCode::ACCEPTED, Json(pending)))
+}
+
+fn in_progress(version: &str) -> ApiError {
+    ApiError::new(
+        StatusCode::CONFLICT,
+        ErrorCode::BuildVerificationInProgress,
+        format!("A rebuild of version {} is already running", version),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PUBLISHED: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn pending(requested_at: DateTime<Utc>) -> BuildVerification {
+        BuildVerification {
+            status: BuildVerificationStatus::Pending,
+            source_url: "https://github.com/acme/token".to_string(),
+            git_ref: "v1.0.0".to_string(),
+            published_wasm_hash: PUBLISHED.to_string(),
+            rebuilt_wasm_hash: None,
+            reproducible: None,
+            message: None,
+            requested_at,
+            completed_at: None,
+        }
+    }
+
+    #[test]
+    fn matching_hashes_are_reproducible() {
+        let now = Utc::now();
+        let outcome = complete(pending(now), Ok(PUBLISHED.to_uppercase()), now);
+        assert_eq!(outcome.status, BuildVerificationStatus::Verified);
+        assert_eq!(outcome.reproducible, Some(true));
+        assert_eq!(outcome.message, None);
+        assert_eq!(outcome.completed_at, Some(now));
+    }
+
+    #[test]
+    fn mismatches_report_both_hashes() {
+        let now = Utc::now();
+        let rebuilt = "a".repeat(64);
+        let outcome = complete(pending(now), Ok(rebuilt.clone()), now);
+        assert_eq!(outcome.status, BuildVerificationStatus::Mismatch);
+        assert_eq!(outcome.reproducible, Some(false));
+        assert_eq!(outcome.rebuilt_wasm_hash.as_deref(), Some(rebuilt.as_str()));
+        let message = outcome.message.unwrap();
+        assert!(message.contains(&rebuilt) && message.contains(PUBLISHED));
+
+        let failed = complete(pending(now), Err("cargo build failed".to_string()), now);
+        assert_eq!(failed.status, BuildVerificationStatus::Failed);
+        assert_eq!(failed.reproducible, None);
+        assert_eq!(failed.message.as_deref(), Some("cargo build failed"));
+    }
+
+    #[test]
+    fn finished_checks_are_reused_for_the_same_source() {
+        let now = Utc::now();
+        let done = complete(pending(now), Ok(PUBLISHED.to_string()), now);
+        let mut req = VerifyBuildRequest {
+            source_url: done.source_url.clone(),
+            git_ref: done.git_ref.clone(),
+        };
+        assert!(is_cached(&done, &req));
+        assert!(!is_cached(&pending(now), &req));
+        req.git_ref = "v1.0.1".to_string();
+        assert!(!is_cached(&done, &req));
+
+        assert!(is_running(&pending(now), now));
+        assert!(!is_running(&pending(now - chrono::Duration::hours(1)), now));
+        assert!(!is_running(&done, now));
+    }
+
+    #[test]
+    fn disabled_without_a_service_url() {
+        assert!(BuildServiceClient::from_url(None).is_none());
+        assert!(BuildServiceClient::from_url(Some("  ".to_string())).is_none());
+        let client = BuildServiceClient::from_url(Some("http://builder:8080/".to_string()));
+        assert_eq!(client.unwrap().url, "http://builder:8080");
+    }
+
+    #[tokio::test]
+    async fn viewers_cannot_request_rebuilds() {
+        // Refused before any query
+        let state = AppState::for_tests();
+        let err = verify_build(
+            State(state),
+            Extension(ContractAccess {
+                contract_id: Uuid::new_v4(),
+                role: Some(OrganizationRole::Viewer),
+            }),
+            Path(("c".to_string(), "1.0.0".to_string())),
+            ValidatedJson(VerifyBuildRequest {
+                source_url: "https://github.com/acme/token".to_string(),
+                git_ref: "v1.0.0".to_string(),
+            }),
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+    }
+}
@@ -3,8 +3,26 @@
 
 use crate::models::{CheckCategory, ChecklistItem, DetectionMethod, Severity};
 
-/// Returns the full static checklist of 50+ security audit items
+/// Returns the full checklist: the 50+ built-in items followed by any custom
+/// rules loaded from `DETECTOR_RULES_PATH` and those added through
+/// `/api/config/detector-rules`.
 pub fn all_checks() -> Vec<ChecklistItem> {
+    let mut checks = builtin_checks();
+    checks.extend(
+        crate::detector::custom_rules()
+            .iter()
+            .map(|rule| rule.item.clone()),
+    );
+    checks.extend(
+        crate::detector::dynamic_rules()
+            .into_iter()
+            .map(|rule| rule.item.clone()),
+    );
+    checks
+}
+
+/// The static checklist of 50+ security audit items
+pub fn builtin_checks() -> Vec<ChecklistItem> {
     vec![
         // ─────────────────────────────────────────
         // INPUT VALIDATION (10 items)
@@ -249,7 +267,7 @@ pub fn all_checks() -> Vec<ChecklistItem> {
         },
 
         // ─────────────────────────────────────────
-        // NUMERICAL SAFETY (8 items)
+        // NUMERICAL SAFETY (9 items)
         // ─────────────────────────────────────────
         ChecklistItem {
             id: "NS-001".into(),
@@ -354,6 +372,21 @@ pub fn all_checks() -> Vec<ChecklistItem> {
             remediation: "Validate: `require!(slippage_bps > 0 && slippage_bps <= 10_000)`.".into(),
             references: vec![],
         },
+        ChecklistItem {
+            id: "NS-009".into(),
+            category: CheckCategory::NumericalSafety,
+            title: "Raw arithmetic operator that can overflow".into(),
+            description: "A bare `+`, `-` or `*` on a variable wraps or panics on overflow. Each \
+                          such operation is reported at its line; silence accepted ones with \
+                          `// soroban-registry:ignore NS-009 <reason>`.".into(),
+            severity: Severity::Medium,
+            detection: DetectionMethod::Automatic {
+                patterns: vec!["checked_add".into(), "checked_mul".into(), "checked_sub".into()],
+            },
+            remediation: "Use `a.checked_add(b)` / `a.checked_mul(b)` / `a.checked_sub(b)` and \
+                          map `None` to a contract error.".into(),
+            references: vec![],
+        },
 
         // ─────────────────────────────────────────
         // STATE MANAGEMENT (7 items)
@@ -454,7 +487,7 @@ pub fn all_checks() -> Vec<ChecklistItem> {
         },
 
         // ─────────────────────────────────────────
-        // REENTRANCY (5 items)
+        // REENTRANCY (6 items)
         // ─────────────────────────────────────────
         ChecklistItem {
             id: "RE-001".into(),
@@ -517,6 +550,21 @@ pub fn all_checks() -> Vec<ChecklistItem> {
             remediation: "Cache all needed state in local variables before any external call.".into(),
             references: vec![],
         },
+        ChecklistItem {
+            id: "RE-006".into(),
+            category: CheckCategory::Reentrancy,
+            title: "Persistent state written after a state-changing external call".into(),
+            description: "A function that calls another contract (e.g. a token transfer) and only \
+                          then updates its own storage lets the callee re-enter while that state \
+                          is stale. Read-only calls such as balance() are not flagged.".into(),
+            severity: Severity::High,
+            detection: DetectionMethod::Automatic {
+                patterns: vec!["_client.".into(), "invoke_contract".into(), "storage()".into()],
+            },
+            remediation: "Update storage before calling out (checks-effects-interactions), or cache \
+                          and re-validate state after the call.".into(),
+            references: vec![],
+        },
 
         // ─────────────────────────────────────────
         // AUTHENTICATION & AUTHORIZATION (5 items)
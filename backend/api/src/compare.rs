@@ -0,0 +1,106 @@
+// compare.rs
+// Side-by-side comparison of two contracts.
+//
+// Both sides are read inside one REPEATABLE READ transaction, so the payload
+// reflects a single snapshot even while scans, scores or publishes land.
+
+use serde::Serialize;
+use sqlx::PgConnection;
+use uuid::Uuid;
+
+use shared::{Contract, ContractVersion};
+
+use crate::scanner_service::{ScanFinding, SeverityCounts};
+use crate::scoring::ScoreHistoryPoint;
+
+/// Latest completed benchmark of one method
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct BenchmarkHeadline {
+    pub method_name: String,
+    pub contract_version: String,
+    pub avg_ms: f64,
+    pub p95_ms: f64,
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComparedContract {
+    pub contract: Contract,
+    /// Newest version that isn't yanked, else the newest one
+    pub latest_version: Option<ContractVersion>,
+    /// Current composite score and its components; null until first scored
+    pub score: Option<ScoreHistoryPoint>,
+    pub benchmarks: Vec<BenchmarkHeadline>,
+    /// Findings in the most recent scan, per severity; null when the
+    /// contract has never been scanned
+    pub findings: Option<SeverityCounts>,
+    pub scanned_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ComparisonResponse {
+    pub a: ComparedContract,
+    pub b: ComparedContract,
+    /// When the snapshot both sides were read from was taken
+    pub read_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Everything the comparison shows about one contract; `None` if it
+/// doesn't exist
+pub async fn load(conn: &mut PgConnection, id: Uuid) -> Result<Option<ComparedContract>, sqlx::Error> {
+    let Some(contract) = sqlx::query_as::<_, Contract>("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?
+    else {
+        return Ok(None);
+    };
+
+    let latest_version = sqlx::query_as(
+        "SELECT * FROM contract_versions WHERE contract_id = $1
+         ORDER BY yanked, created_at DESC LIMIT 1",
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let score = sqlx::query_as(
+        "SELECT composite, security, popularity, maintenance, computed_at
+         FROM contract_scores WHERE contract_id = $1",
+    )
+    .bind(id)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    let benchmarks = sqlx::query_as(
+        "SELECT DISTINCT ON (method_name) method_name, contract_version, avg_ms, p95_ms, completed_at
+         FROM benchmark_records
+         WHERE contract_id = $1 AND status = 'completed'
+         ORDER BY method_name, created_at DESC",
+    )
+    .bind(id)
+    .fetch_all(&mut *conn)
+    .await?;
+
+    let scan: Option<(sqlx::types::Json<Vec<ScanFinding>>, chrono::DateTime<chrono::Utc>)> =
+        sqlx::query_as(
+            "SELECT findings, scanned_at FROM contract_version_scans
+             WHERE contract_id = $1 ORDER BY scanned_at DESC LIMIT 1",
+        )
+        .bind(id)
+        .fetch_optional(&mut *conn)
+        .await?;
+    let (findings, scanned_at) = match scan {
+        Some((findings, scanned_at)) => (Some(SeverityCounts::from_findings(&findings)), Some(scanned_at)),
+        None => (None, None),
+    };
+
+    Ok(Some(ComparedContract {
+        contract,
+        latest_version,
+        score,
+        benchmarks,
+        findings,
+        scanned_at,
+    }))
+}
@@ -0,0 +1,203 @@
+//! Startup configuration check.
+//!
+//! Most settings are read lazily by the module that uses them, and many fall
+//! back to a default (with at most a warning) when the value does not parse.
+//! [`validate`] runs before anything else in `main` and checks every setting
+//! the API reads, so a bad deploy fails once with the full list of problems
+//! instead of one restart per typo.
+
+use std::collections::BTreeMap;
+
+use axum::http::Uri;
+
+use crate::{
+    aggregation, artifacts, cors, downloads, handlers, observability, rate_limit, scanner_service,
+    shutdown, state, transfer_handlers,
+};
+
+/// Settings that must be a positive integer when set
+const POSITIVE_INTEGERS: &[&str] = &[
+    artifacts::MAX_ARTIFACT_BYTES_ENV,
+    transfer_handlers::TRANSFER_TTL_ENV,
+    "SCAN_JOB_VISIBILITY_TIMEOUT_SECS",
+    "CACHE_MAX_CAPACITY",
+];
+
+/// Settings that must be a non-negative integer when set
+const NON_NEGATIVE_INTEGERS: &[&str] = &[state::STATS_TTL_ENV, "CACHE_TTL_SECONDS"];
+
+/// Settings that must be an absolute http(s) URL when set
+const URLS: &[&str] = &[
+    "SOROBAN_RPC_URL_MAINNET",
+    "SOROBAN_RPC_URL_TESTNET",
+    "SOROBAN_RPC_URL_FUTURENET",
+    "IPFS_API_URL",
+    "REGISTRY_SITE_URL",
+    "OTLP_ENDPOINT",
+    "OTEL_EXPORTER_OTLP_ENDPOINT",
+];
+
+/// Check the process environment. Returns every problem found.
+pub fn validate() -> Result<(), Vec<String>> {
+    let env: BTreeMap<String, String> = std::env::vars().collect();
+    let mut problems = problems(&env);
+
+    // These read the environment themselves and already reject bad values
+    problems.extend(aggregation::interval_from_env().err());
+    problems.extend(downloads::flush_interval_from_env().err());
+    problems.extend(shutdown::grace_period_from_env().err());
+
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems)
+    }
+}
+
+/// Every misconfiguration in `env`, in a stable order
+pub fn problems(env: &BTreeMap<String, String>) -> Vec<String> {
+    let mut problems = Vec::new();
+    let set = |key: &str| env.get(key).filter(|raw| !raw.trim().is_empty());
+
+    match set("DATABASE_URL") {
+        None => problems.push("DATABASE_URL must be set".to_string()),
+        Some(url) => {
+            if !url.starts_with("postgres://") && !url.starts_with("postgresql://") {
+                problems.push("DATABASE_URL must be a postgres:// URL".to_string());
+            }
+        }
+    }
+
+    if let Some(raw) = set("CORS_ALLOWED_ORIGINS") {
+        if let Err(err) = cors::parse_allowed_origins(raw) {
+            problems.push(format!("invalid CORS_ALLOWED_ORIGINS: {}", err));
+        }
+    }
+
+    problems.extend(rate_limit::config_problems(env));
+
+    for &key in URLS {
+        if let Some(raw) = set(key) {
+            if !is_http_url(raw.trim()) {
+                problems.push(format!("{} must be an http(s) URL (got '{}')", key, raw));
+            }
+        }
+    }
+
+    for &key in POSITIVE_INTEGERS {
+        if let Some(raw) = env.get(key) {
+            if !matches!(raw.trim().parse::<u64>(), Ok(n) if n > 0) {
+                problems.push(format!(
+                    "{} must be a positive integer (got '{}')",
+                    key, raw
+                ));
+            }
+        }
+    }
+    for &key in NON_NEGATIVE_INTEGERS {
+        if let Some(raw) = env.get(key) {
+            if raw.trim().parse::<u64>().is_err() {
+                problems.push(format!(
+                    "{} must be a non-negative integer (got '{}')",
+                    key, raw
+                ));
+            }
+        }
+    }
+
+    if let Some(raw) = env.get(handlers::SEARCH_SCORE_WEIGHT_ENV) {
+        if !matches!(raw.trim().parse::<f64>(), Ok(w) if (0.0..=1.0).contains(&w)) {
+            problems.push(format!(
+                "{} must be a number between 0 and 1 (got '{}')",
+                handlers::SEARCH_SCORE_WEIGHT_ENV,
+                raw
+            ));
+        }
+    }
+    if let Some(raw) = env.get("BENCHMARK_REGRESSION_THRESHOLD_PCT") {
+        if !matches!(raw.trim().parse::<f64>(), Ok(pct) if pct.is_finite() && pct >= 0.0) {
+            problems.push(format!(
+                "BENCHMARK_REGRESSION_THRESHOLD_PCT must be a non-negative number (got '{}')",
+                raw
+            ));
+        }
+    }
+    if let Some(raw) = env.get(observability::LOG_LEVEL_ENV) {
+        if observability::parse_log_level(raw).is_none() {
+            problems.push(format!(
+                "{} must be one of {} (got '{}')",
+                observability::LOG_LEVEL_ENV,
+                observability::LOG_LEVELS.join(", "),
+                raw
+            ));
+        }
+    }
+    if let Some(raw) = env.get("CACHE_ENABLED") {
+        if !matches!(raw.trim().to_ascii_lowercase().as_str(), "true" | "false") {
+            problems.push(format!(
+                "CACHE_ENABLED must be true or false (got '{}')",
+                raw
+            ));
+        }
+    }
+
+    let lookup = |name: &str| env.get(name).cloned();
+    problems.extend(scanner_service::ScannerConfig::from_lookup(lookup).err());
+    problems.extend(scanner_service::RescanConfig::from_lookup(lookup).err());
+
+    problems
+}
+
+fn is_http_url(raw: &str) -> bool {
+    raw.parse::<Uri>().is_ok_and(|uri| {
+        matches!(uri.scheme_str(), Some("http") | Some("https"))
+            && uri.host().is_some_and(|host| !host.is_empty())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env(pairs: &[(&str, &str)]) -> BTreeMap<String, String> {
+        pairs
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn a_minimal_environment_is_valid() {
+        let env = env(&[("DATABASE_URL", "postgres://registry@localhost/registry")]);
+        assert_eq!(problems(&env), Vec::<String>::new());
+    }
+
+    #[test]
+    fn every_problem_is_reported_at_once() {
+        let env = env(&[
+            ("CORS_ALLOWED_ORIGINS", "https://app.example.com/path"),
+            ("RATE_LIMIT_READ_PER_MINUTE", "-1"),
+            ("SOROBAN_RPC_URL_MAINNET", "rpc.example.com"),
+            ("MAX_ARTIFACT_BYTES", "0"),
+            ("SEARCH_SCORE_WEIGHT", "1.5"),
+            ("SCANNER_MAX_CONCURRENCY", "0"),
+        ]);
+        let problems = problems(&env);
+        assert_eq!(problems.len(), 7, "{:?}", problems);
+        assert_eq!(problems[0], "DATABASE_URL must be set");
+        for key in [
+            "CORS_ALLOWED_ORIGINS",
+            "RATE_LIMIT_READ_PER_MINUTE",
+            "SOROBAN_RPC_URL_MAINNET",
+            "MAX_ARTIFACT_BYTES",
+            "SEARCH_SCORE_WEIGHT",
+            "SCANNER_MAX_CONCURRENCY",
+        ] {
+            assert!(
+                problems.iter().any(|p| p.contains(key)),
+                "{} not reported",
+                key
+            );
+        }
+    }
+}
@@ -5,18 +5,30 @@ use aes_gcm::{
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use rand::RngCore;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeMap;
 use shared::models::{
     ConfigCreateRequest, ConfigRollbackRequest, ContractConfig, ContractConfigResponse,
+    OrganizationRole,
 };
 use uuid::Uuid;
 
-use crate::{error::ApiError, state::AppState};
+use crate::{
+    admin_audit,
+    auth::{Caller, ContractAccess},
+    detector::{self, DetectorRuleRecord},
+    error::{ApiError, ErrorCode},
+    metadata_schema,
+    pagination::{PageParams, Paginated},
+    scanner_service::{DependencyAdvisory, VersionRange},
+    scoring::{self, ScoringWeights},
+    state::AppState,
+};
 
 fn get_encryption_key() -> [u8; 32] {
     let key = std::env::var("CONFIG_SECRET_KEY")
@@ -73,7 +85,7 @@ pub async fn get_contract_config(
     .fetch_optional(&state.pool)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?
-    .ok_or_else(|| ApiError::not_found("ConfigNotFound", "Configuration not found"))?;
+    .ok_or_else(|| ApiError::not_found(ErrorCode::ConfigNotFound, "Configuration not found"))?;
 
     Ok(Json(config.into()))
 }
@@ -126,23 +138,40 @@ pub async fn get_config_history(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
     Query(query): Query<ConfigQuery>,
-) -> Result<Json<Vec<ContractConfigResponse>>, ApiError> {
+    Query(page): Query<PageParams>,
+) -> Result<Json<Paginated<ContractConfigResponse>>, ApiError> {
     let configs = sqlx::query_as::<_, ContractConfig>(
         r#"
         SELECT id, contract_id, environment, version, config_data, secrets_data, created_at, created_by
         FROM contract_configs
         WHERE contract_id = $1 AND environment = $2
         ORDER BY version DESC
+        LIMIT $3 OFFSET $4
         "#,
     )
     .bind(contract_id)
     .bind(&query.environment)
-    .fetch_all(&state.pool)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))?;
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM contract_configs WHERE contract_id = $1 AND environment = $2",
+    )
+    .bind(contract_id)
+    .bind(&query.environment)
+    .fetch_one(&state.db)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?;
 
     let responses: Vec<ContractConfigResponse> = configs.into_iter().map(|c| c.into()).collect();
-    Ok(Json(responses))
+    Ok(Json(Paginated::new(
+        responses,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
 }
 
 pub async fn rollback_config(
@@ -165,7 +194,7 @@ pub async fn rollback_config(
     .fetch_optional(&state.pool)
     .await
     .map_err(|e| ApiError::internal(e.to_string()))?
-    .ok_or_else(|| ApiError::not_found("ConfigNotFound", "Target version not found for rollback"))?;
+    .ok_or_else(|| ApiError::not_found(ErrorCode::ConfigNotFound, "Target version not found for rollback"))?;
 
     // Create a new version with target_config data
     let current_version: i32 = sqlx::query_scalar(
@@ -201,3 +230,670 @@ pub async fn rollback_config(
 
     Ok((StatusCode::CREATED, Json(new_config.into())))
 }
+
+/// Regression thresholds used when a new version is benchmarked.
+///
+/// `metrics` keys are a full metric (`transfer.p95_ms`) or a stat applied to
+/// every method (`cpu_instructions`); `default_pct` covers everything else.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BenchmarkThresholds {
+    #[serde(default)]
+    pub default_pct: Option<f64>,
+    #[serde(default)]
+    pub metrics: BTreeMap<String, f64>,
+}
+
+fn valid_threshold(pct: f64) -> bool {
+    pct.is_finite() && pct >= 0.0
+}
+
+pub async fn get_benchmark_thresholds(
+    State(state): State<AppState>,
+    Path(contract_id): Path<Uuid>,
+) -> Result<Json<BenchmarkThresholds>, ApiError> {
+    let rows: Vec<(String, f64)> = sqlx::query_as(
+        "SELECT metric, threshold_pct FROM benchmark_thresholds WHERE contract_id = $1 ORDER BY metric",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let mut thresholds = BenchmarkThresholds {
+        default_pct: None,
+        metrics: BTreeMap::new(),
+    };
+    for (metric, pct) in rows {
+        if metric == "*" {
+            thresholds.default_pct = Some(pct);
+        } else {
+            thresholds.metrics.insert(metric, pct);
+        }
+    }
+    Ok(Json(thresholds))
+}
+
+/// Replace the contract's thresholds with the ones in the body; owners only
+pub async fn put_benchmark_thresholds(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Path(contract_id): Path<Uuid>,
+    Json(payload): Json<BenchmarkThresholds>,
+) -> Result<Json<BenchmarkThresholds>, ApiError> {
+    access.require(OrganizationRole::Owner)?;
+    let invalid: Vec<&str> = payload
+        .metrics
+        .iter()
+        .filter(|(metric, pct)| metric.trim().is_empty() || metric.as_str() == "*" || !valid_threshold(**pct))
+        .map(|(metric, _)| metric.as_str())
+        .collect();
+    if !invalid.is_empty() || payload.default_pct.is_some_and(|pct| !valid_threshold(pct)) {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidThreshold,
+            "Thresholds must be non-negative percentages keyed by metric name",
+        )
+        .with_details(serde_json::json!({ "metrics": invalid })));
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    sqlx::query("DELETE FROM benchmark_thresholds WHERE contract_id = $1")
+        .bind(contract_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    let entries = payload
+        .default_pct
+        .map(|pct| ("*", pct))
+        .into_iter()
+        .chain(payload.metrics.iter().map(|(metric, pct)| (metric.as_str(), *pct)));
+    for (metric, pct) in entries {
+        sqlx::query(
+            "INSERT INTO benchmark_thresholds (contract_id, metric, threshold_pct) VALUES ($1, $2, $3)",
+        )
+        .bind(contract_id)
+        .bind(metric)
+        .bind(pct)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+
+    Ok(Json(payload))
+}
+
+// ─────────────────────────────────────────────────────────
+// Composite scoring weights (admin)
+// ─────────────────────────────────────────────────────────
+
+#[utoipa::path(
+    get,
+    path = "/api/config/scoring-weights",
+    tag = "config",
+    responses(
+        (status = 200, description = "Current scoring weights"),
+        (status = 403, description = "Not an admin key"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn get_scoring_weights(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+) -> Result<Json<ScoringWeights>, ApiError> {
+    caller.require_admin()?;
+    let weights = scoring::load_weights(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(weights))
+}
+
+/// Replace the weights; they apply from the next recompute
+#[utoipa::path(
+    put,
+    path = "/api/config/scoring-weights",
+    tag = "config",
+    responses(
+        (status = 200, description = "Stored scoring weights"),
+        (status = 403, description = "Not an admin key"),
+        (status = 422, description = "Unknown component or weights that do not sum to 1.0"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn put_scoring_weights(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Json(payload): Json<serde_json::Map<String, Value>>,
+) -> Result<Json<ScoringWeights>, ApiError> {
+    caller.require_admin()?;
+    let weights = ScoringWeights::from_json(&payload)
+        .map_err(|problem| ApiError::unprocessable(ErrorCode::InvalidScoringWeights, problem))?;
+
+    scoring::store_weights(&state.db, &weights)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    admin_audit::record_applied(
+        &state.db,
+        &caller,
+        admin_audit::CONFIG_CHANGED,
+        "scoring_weights",
+        "default",
+        serde_json::to_value(&weights).ok(),
+    )
+    .await;
+    Ok(Json(weights))
+}
+
+/// Recompute every composite score now with the stored weights
+#[utoipa::path(
+    post,
+    path = "/api/config/scoring-weights/recompute",
+    tag = "config",
+    responses(
+        (status = 200, description = "Number of contracts rescored"),
+        (status = 403, description = "Not an admin key"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn recompute_scores(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+) -> Result<Json<Value>, ApiError> {
+    caller.require_admin()?;
+    let rescored = scoring::recompute_composite_scores(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(serde_json::json!({ "rescored": rescored })))
+}
+
+// ─────────────────────────────────────────────────────────
+// Contract metadata schemas (admin)
+// ─────────────────────────────────────────────────────────
+
+/// A registered metadata schema; `category` is `_default` for the fallback
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct MetadataSchemaRecord {
+    pub category: String,
+    pub schema: Value,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/config/metadata-schemas",
+    tag = "config",
+    responses(
+        (status = 200, description = "Every registered metadata schema"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn list_metadata_schemas(
+    State(state): State<AppState>,
+) -> Result<Json<Vec<MetadataSchemaRecord>>, ApiError> {
+    let schemas = sqlx::query_as(
+        "SELECT category, schema, updated_at FROM metadata_schemas ORDER BY category",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(schemas))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/config/metadata-schemas/{category}",
+    tag = "config",
+    params(("category" = String, Path, description = "Contract category, or `_default`")),
+    responses(
+        (status = 200, description = "The category's schema"),
+        (status = 404, description = "No schema registered for the category"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn get_metadata_schema(
+    State(state): State<AppState>,
+    Path(category): Path<String>,
+) -> Result<Json<MetadataSchemaRecord>, ApiError> {
+    sqlx::query_as(
+        "SELECT category, schema, updated_at FROM metadata_schemas WHERE category = $1",
+    )
+    .bind(&category)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))?
+    .map(Json)
+    .ok_or_else(|| {
+        ApiError::not_found(
+            ErrorCode::MetadataSchemaNotFound,
+            format!("No metadata schema registered for category '{}'", category),
+        )
+    })
+}
+
+/// Register or replace a category's schema; it must compile as JSON Schema
+#[utoipa::path(
+    put,
+    path = "/api/config/metadata-schemas/{category}",
+    tag = "config",
+    params(("category" = String, Path, description = "Contract category, or `_default`")),
+    responses(
+        (status = 200, description = "Stored schema"),
+        (status = 403, description = "Not an admin key"),
+        (status = 422, description = "Body is not a valid JSON Schema"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn put_metadata_schema(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(category): Path<String>,
+    Json(schema): Json<Value>,
+) -> Result<Json<MetadataSchemaRecord>, ApiError> {
+    caller.require_admin()?;
+    metadata_schema::compile(&schema).map_err(|problem| {
+        ApiError::unprocessable(
+            ErrorCode::InvalidMetadataSchema,
+            format!("schema does not compile: {}", problem),
+        )
+    })?;
+
+    let record = sqlx::query_as(
+        "INSERT INTO metadata_schemas (category, schema) VALUES ($1, $2)
+         ON CONFLICT (category) DO UPDATE SET schema = EXCLUDED.schema, updated_at = NOW()
+         RETURNING category, schema, updated_at",
+    )
+    .bind(&category)
+    .bind(&schema)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))?;
+    admin_audit::record_applied(
+        &state.db,
+        &caller,
+        admin_audit::CONFIG_CHANGED,
+        "metadata_schema",
+        &category,
+        Some(serde_json::json!({ "operation": "put" })),
+    )
+    .await;
+    Ok(Json(record))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/config/metadata-schemas/{category}",
+    tag = "config",
+    params(("category" = String, Path, description = "Contract category, or `_default`")),
+    responses(
+        (status = 204, description = "Schema removed"),
+        (status = 403, description = "Not an admin key"),
+        (status = 404, description = "No schema registered for the category"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn delete_metadata_schema(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(category): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    caller.require_admin()?;
+    let deleted = sqlx::query("DELETE FROM metadata_schemas WHERE category = $1")
+        .bind(&category)
+        .execute(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found(
+            ErrorCode::MetadataSchemaNotFound,
+            format!("No metadata schema registered for category '{}'", category),
+        ));
+    }
+    admin_audit::record_applied(
+        &state.db,
+        &caller,
+        admin_audit::CONFIG_CHANGED,
+        "metadata_schema",
+        &category,
+        Some(serde_json::json!({ "operation": "delete" })),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ─────────────────────────────────────────────────────────
+// Dynamic detector rules (admin)
+// ─────────────────────────────────────────────────────────
+
+/// A regex rule that fails a scan when it matches a source line
+#[derive(Debug, Deserialize)]
+pub struct CreateDetectorRuleRequest {
+    pub id: String,
+    /// `info`, `low`, `medium`, `high` or `critical`
+    pub severity: String,
+    pub regex: String,
+    pub message: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/config/detector-rules",
+    tag = "config",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Rules added through the API, by id"),
+        (status = 403, description = "Not an admin key"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn list_detector_rules(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Query(page): Query<PageParams>,
+) -> Result<Json<Paginated<DetectorRuleRecord>>, ApiError> {
+    caller.require_admin()?;
+    let rules = sqlx::query_as("SELECT * FROM detector_rules ORDER BY id LIMIT $1 OFFSET $2")
+        .bind(page.limit())
+        .bind(page.offset())
+        .fetch_all(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM detector_rules")
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(Paginated::new(
+        rules,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+/// Add a rule; scans pick it up without a redeploy
+#[utoipa::path(
+    post,
+    path = "/api/config/detector-rules",
+    tag = "config",
+    responses(
+        (status = 201, description = "Stored rule"),
+        (status = 403, description = "Not an admin key"),
+        (status = 409, description = "A rule with this id already exists"),
+        (status = 422, description = "Invalid id, severity or message, or a regex that does not compile"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn create_detector_rule(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Json(payload): Json<CreateDetectorRuleRequest>,
+) -> Result<(StatusCode, Json<DetectorRuleRecord>), ApiError> {
+    caller.require_admin()?;
+    detector::validate_dynamic_rule(
+        &payload.id,
+        &payload.severity,
+        &payload.regex,
+        &payload.message,
+    )
+    .map_err(|problem| ApiError::unprocessable(ErrorCode::InvalidDetectorRule, problem))?;
+
+    let id = payload.id.trim();
+    let rule: DetectorRuleRecord = sqlx::query_as(
+        "INSERT INTO detector_rules (id, severity, pattern, message) VALUES ($1, $2, $3, $4)
+         ON CONFLICT (id) DO NOTHING
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(payload.severity.trim().to_ascii_lowercase())
+    .bind(&payload.regex)
+    .bind(payload.message.trim())
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))?
+    .ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            ErrorCode::DetectorRuleExists,
+            format!("Detector rule '{}' already exists", id),
+        )
+    })?;
+
+    detector::refresh_dynamic_rules(&state.db).await;
+    admin_audit::record_applied(
+        &state.db,
+        &caller,
+        admin_audit::CONFIG_CHANGED,
+        "detector_rule",
+        id,
+        Some(serde_json::json!({ "operation": "create" })),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(rule)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/config/detector-rules/{id}",
+    tag = "config",
+    params(("id" = String, Path, description = "Rule id")),
+    responses(
+        (status = 204, description = "Rule removed"),
+        (status = 403, description = "Not an admin key"),
+        (status = 404, description = "No rule with this id"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn delete_detector_rule(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    caller.require_admin()?;
+    let deleted = sqlx::query("DELETE FROM detector_rules WHERE id = $1")
+        .bind(&id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found(
+            ErrorCode::DetectorRuleNotFound,
+            format!("No detector rule with id '{}'", id),
+        ));
+    }
+
+    detector::refresh_dynamic_rules(&state.db).await;
+    admin_audit::record_applied(
+        &state.db,
+        &caller,
+        admin_audit::CONFIG_CHANGED,
+        "detector_rule",
+        &id,
+        Some(serde_json::json!({ "operation": "delete" })),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ─────────────────────────────────────────────────────────
+// Dependency advisories (admin)
+// ─────────────────────────────────────────────────────────
+
+/// A known-bad version range of a dependency, reported by contract scans
+#[derive(Debug, Deserialize)]
+pub struct CreateDependencyAdvisoryRequest {
+    pub id: String,
+    pub package_name: String,
+    /// Semver requirement, e.g. `>=1.0.0, <1.4.2`
+    pub affected: String,
+    /// `info`, `low`, `medium`, `high` or `critical`
+    pub severity: String,
+    #[serde(default)]
+    pub summary: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/config/dependency-advisories",
+    tag = "config",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Advisories, by id"),
+        (status = 403, description = "Not an admin key"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn list_dependency_advisories(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Query(page): Query<PageParams>,
+) -> Result<Json<Paginated<DependencyAdvisory>>, ApiError> {
+    caller.require_admin()?;
+    let advisories =
+        sqlx::query_as("SELECT * FROM dependency_advisories ORDER BY id LIMIT $1 OFFSET $2")
+            .bind(page.limit())
+            .bind(page.offset())
+            .fetch_all(&state.db)
+            .await
+            .map_err(|e| ApiError::internal(e.to_string()))?;
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM dependency_advisories")
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    Ok(Json(Paginated::new(
+        advisories,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+/// Add an advisory; it applies from the next dependency scan
+#[utoipa::path(
+    post,
+    path = "/api/config/dependency-advisories",
+    tag = "config",
+    responses(
+        (status = 201, description = "Stored advisory"),
+        (status = 403, description = "Not an admin key"),
+        (status = 409, description = "An advisory with this id already exists"),
+        (status = 422, description = "Missing id or package, unknown severity, or an unparseable range"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn create_dependency_advisory(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Json(payload): Json<CreateDependencyAdvisoryRequest>,
+) -> Result<(StatusCode, Json<DependencyAdvisory>), ApiError> {
+    caller.require_admin()?;
+    let id = payload.id.trim();
+    let package_name = payload.package_name.trim();
+    if id.is_empty() || package_name.is_empty() {
+        return Err(ApiError::unprocessable(
+            ErrorCode::InvalidAdvisory,
+            "id and package_name must not be empty",
+        ));
+    }
+    let severity = payload.severity.trim().to_ascii_lowercase();
+    if detector::parse_severity_label(&severity).is_none() {
+        return Err(ApiError::unprocessable(
+            ErrorCode::InvalidAdvisory,
+            format!(
+                "unknown severity '{}' (expected info, low, medium, high or critical)",
+                payload.severity
+            ),
+        ));
+    }
+    VersionRange::parse_req(&payload.affected).map_err(|err| {
+        ApiError::unprocessable(
+            ErrorCode::InvalidAdvisory,
+            format!(
+                "affected range '{}' is not a semver requirement: {}",
+                payload.affected, err
+            ),
+        )
+    })?;
+
+    let advisory: DependencyAdvisory = sqlx::query_as(
+        "INSERT INTO dependency_advisories (id, package_name, affected, severity, summary)
+         VALUES ($1, $2, $3, $4, $5)
+         ON CONFLICT (id) DO NOTHING
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(package_name)
+    .bind(payload.affected.trim())
+    .bind(&severity)
+    .bind(payload.summary.trim())
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|e| ApiError::internal(e.to_string()))?
+    .ok_or_else(|| {
+        ApiError::new(
+            StatusCode::CONFLICT,
+            ErrorCode::AdvisoryExists,
+            format!("Advisory '{}' already exists", id),
+        )
+    })?;
+    admin_audit::record_applied(
+        &state.db,
+        &caller,
+        admin_audit::CONFIG_CHANGED,
+        "dependency_advisory",
+        id,
+        Some(serde_json::json!({ "operation": "create" })),
+    )
+    .await;
+    Ok((StatusCode::CREATED, Json(advisory)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/config/dependency-advisories/{id}",
+    tag = "config",
+    params(("id" = String, Path, description = "Advisory id")),
+    responses(
+        (status = 204, description = "Advisory removed"),
+        (status = 403, description = "Not an admin key"),
+        (status = 404, description = "No advisory with this id"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn delete_dependency_advisory(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<String>,
+) -> Result<StatusCode, ApiError> {
+    caller.require_admin()?;
+    let deleted = sqlx::query("DELETE FROM dependency_advisories WHERE id = $1")
+        .bind(&id)
+        .execute(&state.db)
+        .await
+        .map_err(|e| ApiError::internal(e.to_string()))?
+        .rows_affected();
+    if deleted == 0 {
+        return Err(ApiError::not_found(
+            ErrorCode::AdvisoryNotFound,
+            format!("No advisory with id '{}'", id),
+        ));
+    }
+    admin_audit::record_applied(
+        &state.db,
+        &caller,
+        admin_audit::CONFIG_CHANGED,
+        "dependency_advisory",
+        &id,
+        Some(serde_json::json!({ "operation": "delete" })),
+    )
+    .await;
+    Ok(StatusCode::NO_CONTENT)
+}
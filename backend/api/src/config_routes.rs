@@ -10,4 +10,8 @@ pub fn config_routes() -> Router<AppState> {
         .route("/api/contracts/:id/config", get(config_handlers::get_contract_config).post(config_handlers::create_contract_config))
         .route("/api/contracts/:id/config/history", get(config_handlers::get_config_history))
         .route("/api/contracts/:id/config/rollback", post(config_handlers::rollback_config))
+        .route(
+            "/api/contracts/:id/benchmark-thresholds",
+            get(config_handlers::get_benchmark_thresholds),
+        )
 }
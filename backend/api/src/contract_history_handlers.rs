@@ -7,24 +7,35 @@
 //   GET  /api/contracts/:id/history/all          – paginated full history
 //   GET  /api/contracts/:id/history/export       – CSV download
 //   GET  /api/contracts/:id/versions/:v1/diff/:v2 – field-level diff
+//   GET  /api/contracts/:id/history/diff         – diff of two log entries
 //   POST /api/contracts/:id/rollback/:snapshot_id – admin rollback
+//
+// Registered with the authenticated routes in routes.rs:
+//   POST /api/contracts/:id/history/:entry_id/revert – restore an entry's state
 
 use axum::{
     extract::{Path, Query, State},
     http::{header, StatusCode},
     response::{IntoResponse, Response},
-    Json,
+    Extension, Json,
 };
+use std::collections::{BTreeMap, BTreeSet};
+
 use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
-    error::{ApiError, ApiResult},
+    auth::{Caller, ContractAccess},
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::fetch_visible_contract,
+    metadata_schema,
+    pagination::Paginated,
+    share_tokens::PresentedShareToken,
     state::AppState,
 };
 use shared::{
-    AuditActionType, AuditLogPage, ContractAuditLog, ContractSnapshot, FieldChange, RollbackRequest,
-    VersionDiff,
+    AuditActionType, Contract, ContractAuditLog, ContractSnapshot, FieldChange,
+    FieldDiff, HistoryDiff, OrganizationRole, RollbackRequest, VersionDiff,
 };
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -33,9 +44,11 @@ use shared::{
 // ─────────────────────────────────────────────────────────────────────────────
 pub async fn get_contract_history(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<Uuid>,
 ) -> ApiResult<Json<Vec<ContractAuditLog>>> {
-    verify_contract_exists(&state, contract_id).await?;
+    verify_contract_visible(&state, caller, &share_token, contract_id).await?;
 
     let entries: Vec<ContractAuditLog> = sqlx::query_as(
         "SELECT id, contract_id, action_type, old_value, new_value, changed_by, timestamp, previous_hash, hash, signature
@@ -53,8 +66,8 @@ pub async fn get_contract_history(
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
-// GET /api/contracts/:id/history/all?page=1&limit=20
-// Full paginated history.
+// GET /api/contracts/:id/history/all?limit=20&offset=0
+// Full paginated history. `page` is still accepted when `offset` is absent.
 // ─────────────────────────────────────────────────────────────────────────────
 #[derive(Debug, Deserialize)]
 pub struct PaginationParams {
@@ -62,25 +75,30 @@ pub struct PaginationParams {
     pub page: i64,
     #[serde(default = "default_limit")]
     pub limit: i64,
+    pub offset: Option<i64>,
 }
 fn default_page() -> i64 { 1 }
 fn default_limit() -> i64 { 20 }
 
 pub async fn get_full_history(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<Uuid>,
     Query(params): Query<PaginationParams>,
-) -> ApiResult<Json<AuditLogPage>> {
-    if params.page < 1 || params.limit < 1 || params.limit > 100 {
+) -> ApiResult<Json<Paginated<ContractAuditLog>>> {
+    if params.page < 1 || params.limit < 1 || params.limit > 100
+        || params.offset.is_some_and(|o| o < 0)
+    {
         return Err(ApiError::bad_request(
-            "InvalidPagination",
-            "page >= 1 and 1 <= limit <= 100",
+            ErrorCode::InvalidPagination,
+            "page >= 1, offset >= 0 and 1 <= limit <= 100",
         ));
     }
 
-    verify_contract_exists(&state, contract_id).await?;
+    verify_contract_visible(&state, caller, &share_token, contract_id).await?;
 
-    let offset = (params.page - 1) * params.limit;
+    let offset = params.offset.unwrap_or((params.page - 1).saturating_mul(params.limit));
 
     let total: i64 = sqlx::query_scalar(
         "SELECT COUNT(*) FROM contract_audit_log WHERE contract_id = $1",
@@ -104,18 +122,7 @@ pub async fn get_full_history(
     .await
     .map_err(|e| db_err("list audit log page", e))?;
 
-    let total_pages = if params.limit > 0 {
-        (total as f64 / params.limit as f64).ceil() as i64
-    } else {
-        0
-    };
-
-    Ok(Json(AuditLogPage {
-        items,
-        total,
-        page: params.page,
-        total_pages,
-    }))
+    Ok(Json(Paginated::new(items, total, params.limit, offset)))
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -124,9 +131,11 @@ pub async fn get_full_history(
 // ─────────────────────────────────────────────────────────────────────────────
 pub async fn export_history_csv(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<Uuid>,
 ) -> Result<Response, StatusCode> {
-    verify_contract_exists(&state, contract_id)
+    verify_contract_visible(&state, caller, &share_token, contract_id)
         .await
         .map_err(|_| StatusCode::NOT_FOUND)?;
 
@@ -196,9 +205,11 @@ pub async fn export_history_csv(
 // ─────────────────────────────────────────────────────────────────────────────
 pub async fn verify_contract_history(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<Uuid>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    verify_contract_exists(&state, contract_id).await?;
+    verify_contract_visible(&state, caller, &share_token, contract_id).await?;
 
     let entries: Vec<ContractAuditLog> = sqlx::query_as(
         "SELECT id, contract_id, action_type, old_value, new_value, changed_by, timestamp, previous_hash, hash, signature
@@ -266,9 +277,11 @@ pub async fn verify_contract_history(
 // ─────────────────────────────────────────────────────────────────────────────
 pub async fn diff_versions(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path((contract_id, v1, v2)): Path<(Uuid, i32, i32)>,
 ) -> ApiResult<Json<VersionDiff>> {
-    verify_contract_exists(&state, contract_id).await?;
+    verify_contract_visible(&state, caller, &share_token, contract_id).await?;
 
     let snap_a: ContractSnapshot = sqlx::query_as(
         "SELECT id, contract_id, version_number, snapshot_data, audit_log_id, created_at
@@ -281,7 +294,7 @@ pub async fn diff_versions(
     .await
     .map_err(|err| match err {
         sqlx::Error::RowNotFound => ApiError::not_found(
-            "SnapshotNotFound",
+            ErrorCode::SnapshotNotFound,
             format!("No snapshot found for version {v1}"),
         ),
         _ => db_err("fetch snapshot v1", err),
@@ -298,7 +311,7 @@ pub async fn diff_versions(
     .await
     .map_err(|err| match err {
         sqlx::Error::RowNotFound => ApiError::not_found(
-            "SnapshotNotFound",
+            ErrorCode::SnapshotNotFound,
             format!("No snapshot found for version {v2}"),
         ),
         _ => db_err("fetch snapshot v2", err),
@@ -308,6 +321,67 @@ pub async fn diff_versions(
     Ok(Json(diff))
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// GET /api/contracts/:id/history/diff?from=<entry>&to=<entry>
+// Field-level diff between the states captured at two history entries.
+// Object-valued fields such as metadata are diffed key by key.
+// ─────────────────────────────────────────────────────────────────────────────
+#[derive(Debug, Deserialize)]
+pub struct HistoryDiffParams {
+    pub from: Uuid,
+    pub to: Uuid,
+}
+
+pub async fn diff_history_entries(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(contract_id): Path<Uuid>,
+    Query(params): Query<HistoryDiffParams>,
+) -> ApiResult<Json<HistoryDiff>> {
+    verify_contract_visible(&state, caller, &share_token, contract_id).await?;
+
+    let entries: Vec<ContractAuditLog> = sqlx::query_as(
+        "SELECT id, contract_id, action_type, old_value, new_value, changed_by, timestamp, previous_hash, hash, signature
+           FROM contract_audit_log
+          WHERE id = ANY($1)",
+    )
+    .bind(vec![params.from, params.to])
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("fetch history entries for diff", e))?;
+
+    let entry = |id: Uuid| {
+        entries.iter().find(|entry| entry.id == id).ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::HistoryEntryNotFound,
+                format!("No history entry {id}"),
+            )
+        })
+    };
+    let (from, to) = (entry(params.from)?, entry(params.to)?);
+    if from.contract_id != to.contract_id || from.contract_id != contract_id {
+        return Err(ApiError::bad_request(
+            ErrorCode::HistoryContractMismatch,
+            format!(
+                "Entries {} and {} must both belong to contract {contract_id}",
+                from.id, to.id
+            ),
+        ));
+    }
+
+    let empty = serde_json::Value::Null;
+    Ok(Json(HistoryDiff {
+        contract_id,
+        from: from.id,
+        to: to.id,
+        changes: diff_fields(
+            from.new_value.as_ref().unwrap_or(&empty),
+            to.new_value.as_ref().unwrap_or(&empty),
+        ),
+    }))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // POST /api/contracts/:id/rollback/:snapshot_id
 // Admin-only: restores contract to a previous snapshot.
@@ -330,7 +404,7 @@ pub async fn rollback_contract(
     .await
     .map_err(|err| match err {
         sqlx::Error::RowNotFound => ApiError::not_found(
-            "SnapshotNotFound",
+            ErrorCode::SnapshotNotFound,
             format!("No snapshot found with id {snapshot_id} for contract {contract_id}"),
         ),
         _ => db_err("fetch rollback snapshot", err),
@@ -435,6 +509,208 @@ pub async fn rollback_contract(
     })))
 }
 
+// ─────────────────────────────────────────────────────────────────────────────
+// POST /api/contracts/:id/history/:entry_id/revert
+// Re-applies the contract state captured at a history entry as a new change.
+// Nothing is deleted: the revert is itself recorded as a `rollback` entry.
+// ─────────────────────────────────────────────────────────────────────────────
+
+/// Fields a revert restores. Publisher, verification and the WASM hash are
+/// never taken from history.
+pub const REVERTED_FIELDS: [&str; 5] = ["name", "description", "category", "tags", "metadata"];
+
+/// Publisher-editable contract state as captured in a history entry
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct RevertedFields {
+    pub name: String,
+    pub description: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+/// The snapshot's `REVERTED_FIELDS`, or the names of those it lacks or holds
+/// in a shape the current schema doesn't accept
+pub fn reverted_fields(snapshot: &serde_json::Value) -> Result<RevertedFields, Vec<String>> {
+    let fits = |field: &str, value: Option<&serde_json::Value>| match (field, value) {
+        (_, None) => false,
+        ("name", Some(value)) => value.is_string(),
+        ("description" | "category", Some(value)) => value.is_string() || value.is_null(),
+        ("tags", Some(value)) => value
+            .as_array()
+            .is_some_and(|tags| tags.iter().all(|tag| tag.is_string())),
+        (_, Some(value)) => value.is_object() || value.is_null(),
+    };
+    let unusable: Vec<String> = REVERTED_FIELDS
+        .iter()
+        .filter(|field| !fits(**field, snapshot.get(**field)))
+        .map(|field| field.to_string())
+        .collect();
+    if !unusable.is_empty() {
+        return Err(unusable);
+    }
+    let subset: serde_json::Map<String, serde_json::Value> = REVERTED_FIELDS
+        .iter()
+        .map(|field| (field.to_string(), snapshot[*field].clone()))
+        .collect();
+    serde_json::from_value(serde_json::Value::Object(subset))
+        .map_err(|_| REVERTED_FIELDS.iter().map(|field| field.to_string()).collect())
+}
+
+fn revert_incompatible(entry_id: Uuid, reason: &str) -> ApiError {
+    ApiError::new(
+        StatusCode::CONFLICT,
+        ErrorCode::RevertIncompatible,
+        format!(
+            "History entry {entry_id} predates a schema-incompatible change and cannot be reverted to: {reason}"
+        ),
+    )
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/history/{entry_id}/revert",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("entry_id" = Uuid, Path, description = "History entry whose state to restore"),
+    ),
+    responses(
+        (status = 200, description = "The restored contract", body = Contract),
+        (status = 403, description = "Caller is not a maintainer of the contract"),
+        (status = 404, description = "Contract or history entry not found"),
+        (status = 409, description = "The entry's state no longer fits the contract schema"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn revert_to_history_entry(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Extension(access): Extension<ContractAccess>,
+    Path((contract_id, entry_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<Json<Contract>> {
+    access.require(OrganizationRole::Maintainer)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_err("begin revert", e))?;
+
+    let entry: ContractAuditLog = sqlx::query_as(
+        "SELECT id, contract_id, action_type, old_value, new_value, changed_by, timestamp, previous_hash, hash, signature
+           FROM contract_audit_log
+          WHERE id = $1 AND contract_id = $2",
+    )
+    .bind(entry_id)
+    .bind(contract_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|e| db_err("fetch history entry", e))?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            ErrorCode::HistoryEntryNotFound,
+            format!("No history entry {entry_id} for contract {contract_id}"),
+        )
+    })?;
+
+    let Some(snapshot) = entry.new_value.as_ref() else {
+        return Err(revert_incompatible(entry_id, "it captured no contract state"));
+    };
+    let fields = reverted_fields(snapshot).map_err(|fields| {
+        revert_incompatible(entry_id, "fields are missing or changed type")
+            .with_details(serde_json::json!({ "fields": fields }))
+    })?;
+
+    // Metadata is checked against the schema in force now, not the one it was
+    // published under
+    if let Some(metadata) = &fields.metadata {
+        let errors = match metadata_schema::load_for_category(&state.db, fields.category.as_deref())
+            .await
+            .map_err(|e| db_err("load metadata schema", e))?
+        {
+            Some(schema) => {
+                let schema = metadata_schema::compile(&schema).map_err(|err| {
+                    tracing::error!(error = %err, "stored metadata schema does not compile");
+                    ApiError::internal("The metadata schema for this category is invalid")
+                })?;
+                metadata_schema::validate(&schema, metadata)
+            }
+            None => Vec::new(),
+        };
+        if !errors.is_empty() {
+            return Err(revert_incompatible(
+                entry_id,
+                "its metadata fails the current metadata schema",
+            )
+            .with_details(serde_json::json!({ "errors": errors })));
+        }
+    }
+
+    // Lock the row so a concurrent edit can't land between the state recorded
+    // as `old_value` and the update
+    let current_data: serde_json::Value = sqlx::query_scalar(
+        "SELECT row_to_json(contracts.*) FROM contracts WHERE id = $1 FOR UPDATE",
+    )
+    .bind(contract_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("read current contract for revert", e))?;
+
+    let contract: Contract = sqlx::query_as(
+        "UPDATE contracts
+            SET name        = $2,
+                description = $3,
+                category    = $4,
+                tags        = $5,
+                metadata    = $6,
+                updated_at  = NOW()
+          WHERE id = $1
+          RETURNING *",
+    )
+    .bind(contract_id)
+    .bind(&fields.name)
+    .bind(&fields.description)
+    .bind(&fields.category)
+    .bind(&fields.tags)
+    .bind(&fields.metadata)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("apply revert to contract", e))?;
+
+    let restored_data: serde_json::Value = sqlx::query_scalar(
+        "SELECT row_to_json(contracts.*) FROM contracts WHERE id = $1",
+    )
+    .bind(contract_id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_err("read reverted contract", e))?;
+
+    record_contract_change(
+        &mut tx,
+        contract_id,
+        AuditActionType::Rollback,
+        Some(current_data),
+        Some(restored_data),
+        &caller.audit_name(),
+    )
+    .await
+    .map_err(|e| db_err("record revert", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_err("commit revert", e))?;
+
+    tracing::info!(
+        contract_id = %contract_id,
+        reverted_to_entry = %entry_id,
+        changed_by = %caller.audit_name(),
+        "Contract reverted to history entry"
+    );
+
+    Ok(Json(contract))
+}
+
 // ─────────────────────────────────────────────────────────────────────────────
 // Shared internal helpers
 // ─────────────────────────────────────────────────────────────────────────────
@@ -449,8 +725,31 @@ pub async fn log_contract_change(
     new_value: Option<serde_json::Value>,
     changed_by: &str,
 ) -> Result<Uuid, sqlx::Error> {
-    use sha2::{Sha256, Digest};
     let mut tx = db.begin().await?;
+    let log_id = record_contract_change(
+        &mut tx,
+        contract_id,
+        action_type,
+        old_value,
+        new_value,
+        changed_by,
+    )
+    .await?;
+    tx.commit().await?;
+    Ok(log_id)
+}
+
+/// [`log_contract_change`] inside a transaction the caller owns, so the entry
+/// commits or rolls back with the change it records
+pub async fn record_contract_change(
+    tx: &mut sqlx::PgConnection,
+    contract_id: Uuid,
+    action_type: AuditActionType,
+    old_value: Option<serde_json::Value>,
+    new_value: Option<serde_json::Value>,
+    changed_by: &str,
+) -> Result<Uuid, sqlx::Error> {
+    use sha2::{Sha256, Digest};
 
     // 1. Fetch the latest hash to use as previous_hash
     let prev_hash: Option<String> = sqlx::query_scalar(
@@ -514,7 +813,6 @@ pub async fn log_contract_change(
         .await?;
     }
 
-    tx.commit().await?;
     Ok(log_id)
 }
 
@@ -578,26 +876,228 @@ fn compute_diff(
     }
 }
 
+/// Changed keys of two JSONB objects. A key present on only one side is
+/// reported with the other side left out; two objects recurse.
+pub fn diff_fields(
+    a: &serde_json::Value,
+    b: &serde_json::Value,
+) -> BTreeMap<String, FieldDiff> {
+    let empty = serde_json::Map::new();
+    let a_obj = a.as_object().unwrap_or(&empty);
+    let b_obj = b.as_object().unwrap_or(&empty);
+
+    let keys: BTreeSet<&String> = a_obj.keys().chain(b_obj.keys()).collect();
+    let mut changes = BTreeMap::new();
+    for key in keys {
+        let diff = match (a_obj.get(key), b_obj.get(key)) {
+            (Some(from), Some(to)) if from == to => continue,
+            (Some(from), Some(to)) if from.is_object() && to.is_object() => FieldDiff::Nested {
+                changes: diff_fields(from, to),
+            },
+            (from, to) => FieldDiff::Changed {
+                from: from.cloned(),
+                to: to.cloned(),
+            },
+        };
+        changes.insert(key.clone(), diff);
+    }
+    changes
+}
+
 /// Verify a contract row exists; returns 404 error if not.
-async fn verify_contract_exists(state: &AppState, contract_id: Uuid) -> ApiResult<()> {
-    sqlx::query_scalar::<_, i64>("SELECT COUNT(*) FROM contracts WHERE id = $1")
-        .bind(contract_id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|e| db_err("check contract exists", e))
-        .and_then(|count| {
-            if count == 0 {
-                Err(ApiError::not_found(
-                    "ContractNotFound",
-                    format!("No contract found with ID: {contract_id}"),
-                ))
-            } else {
-                Ok(())
-            }
-        })
+/// 404 unless the contract exists and the caller may see it
+async fn verify_contract_visible(
+    state: &AppState,
+    caller: Option<Extension<Caller>>,
+    share_token: &PresentedShareToken,
+    contract_id: Uuid,
+) -> ApiResult<()> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    )
+    .await
+    .map(|_| ())
 }
 
 fn db_err(op: &str, err: sqlx::Error) -> ApiError {
     tracing::error!(operation = op, error = ?err, "database error");
     ApiError::internal("An unexpected database error occurred")
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn contract_row(description: &str) -> serde_json::Value {
+        json!({
+            "id": Uuid::nil(),
+            "name": "Token",
+            "description": description,
+            "category": "defi",
+            "tags": ["token"],
+            "metadata": { "license": "MIT" },
+            "is_verified": true,
+        })
+    }
+
+    #[test]
+    fn revert_restores_the_field_from_the_chosen_entry() {
+        // Two edits, each captured as a history entry's new_value
+        let first = contract_row("first edit");
+        let second = contract_row("second edit");
+        assert_ne!(first["description"], second["description"]);
+
+        let restored = reverted_fields(&first).unwrap();
+        assert_eq!(restored.description.as_deref(), Some("first edit"));
+        assert_eq!(restored.tags, vec!["token".to_string()]);
+    }
+
+    #[test]
+    fn entries_from_before_a_schema_change_are_rejected() {
+        let mut old = contract_row("before metadata existed");
+        old.as_object_mut().unwrap().remove("metadata");
+        assert_eq!(reverted_fields(&old).unwrap_err(), vec!["metadata".to_string()]);
+
+        let mut retyped = contract_row("tags were a string");
+        retyped["tags"] = json!("token,defi");
+        assert_eq!(reverted_fields(&retyped).unwrap_err(), vec!["tags".to_string()]);
+
+        assert_eq!(reverted_fields(&json!(null)).unwrap_err().len(), REVERTED_FIELDS.len());
+    }
+
+    #[test]
+    fn diff_omits_unchanged_fields_and_nests_metadata() {
+        let mut from = contract_row("same");
+        let mut to = contract_row("same");
+        from["metadata"] = json!({ "license": "MIT", "audited": false, "links": { "docs": "a" } });
+        to["metadata"] = json!({ "license": "MIT", "audited": true, "links": { "docs": "b" } });
+        to["name"] = json!("Token v2");
+
+        let changes = diff_fields(&from, &to);
+        assert_eq!(changes.keys().collect::<Vec<_>>(), vec!["metadata", "name"]);
+        assert_eq!(
+            changes["name"],
+            FieldDiff::Changed { from: Some(json!("Token")), to: Some(json!("Token v2")) }
+        );
+
+        let FieldDiff::Nested { changes: metadata } = &changes["metadata"] else {
+            panic!("metadata should be diffed key by key");
+        };
+        assert!(!metadata.contains_key("license"));
+        assert_eq!(
+            metadata["audited"],
+            FieldDiff::Changed { from: Some(json!(false)), to: Some(json!(true)) }
+        );
+        assert!(matches!(metadata["links"], FieldDiff::Nested { .. }));
+    }
+
+    #[test]
+    fn diff_reports_added_and_removed_keys() {
+        let changes = diff_fields(&json!({ "category": "defi" }), &json!({ "tags": [] }));
+        assert_eq!(changes["category"], FieldDiff::Changed { from: Some(json!("defi")), to: None });
+        assert_eq!(changes["tags"], FieldDiff::Changed { from: None, to: Some(json!([])) });
+
+        let json = serde_json::to_value(&changes["category"]).unwrap();
+        assert_eq!(json, json!({ "from": "defi" }));
+    }
+
+    #[tokio::test]
+    async fn private_contract_history_is_hidden_from_anonymous_callers() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let id = state.insert_private_contract().await;
+        let entry = Uuid::new_v4();
+
+        for uri in [
+            format!("/api/contracts/{}/history", id),
+            format!("/api/contracts/{}/history/all", id),
+            format!("/api/contracts/{}/history/export", id),
+            format!("/api/contracts/{}/history/verify", id),
+            format!("/api/contracts/{id}/history/diff?from={entry}&to={entry}"),
+            format!("/api/contracts/{}/versions/1/diff/2", id),
+        ] {
+            let status = state.anonymous_get(&uri).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn revert_restores_the_entry_and_records_a_rollback() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let publisher = state.insert_publisher().await;
+        let id = state.insert_contract(publisher, None, "public").await;
+        let set_description = |description: &'static str| {
+            sqlx::query("UPDATE contracts SET description = $2 WHERE id = $1")
+                .bind(id)
+                .bind(description)
+                .execute(&state.db)
+        };
+
+        set_description("first").await.unwrap();
+        let snapshot: serde_json::Value =
+            sqlx::query_scalar("SELECT row_to_json(contracts.*) FROM contracts WHERE id = $1")
+                .bind(id)
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        let entry = log_contract_change(
+            &state.db,
+            id,
+            AuditActionType::MetadataUpdated,
+            None,
+            Some(snapshot),
+            "test",
+        )
+        .await
+        .unwrap();
+        set_description("second").await.unwrap();
+
+        let key = state.api_key(publisher).await;
+        let uri = format!("/api/contracts/{id}/history/{entry}/revert");
+        let response = state
+            .send(crate::state::test_request("POST", &uri, Some(&key), None))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = crate::state::json_body(response).await;
+        assert_eq!(body["description"], "first");
+
+        let (old_value, new_value): (serde_json::Value, serde_json::Value) = sqlx::query_as(
+            "SELECT old_value, new_value FROM contract_audit_log
+              WHERE contract_id = $1 AND action_type = 'rollback'",
+        )
+        .bind(id)
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        assert_eq!(old_value["description"], "second");
+        assert_eq!(new_value["description"], "first");
+
+        let unknown = format!("/api/contracts/{id}/history/{}/revert", Uuid::new_v4());
+        let request = crate::state::test_request("POST", &unknown, Some(&key), None);
+        let response = state.send(request).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let stranger = state.insert_publisher().await;
+        let key = state.api_key(stranger).await;
+        let response = state
+            .send(crate::state::test_request("POST", &uri, Some(&key), None))
+            .await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let rollbacks: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM contract_audit_log WHERE contract_id = $1 AND action_type = 'rollback'",
+        )
+        .bind(id)
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        assert_eq!(rollbacks, 1);
+    }
+}
@@ -33,6 +33,11 @@ pub fn contract_history_routes() -> Router<AppState> {
             "/api/contracts/:id/history/export",
             get(contract_history_handlers::export_history_csv),
         )
+        // Field-level diff between two audit log entries
+        .route(
+            "/api/contracts/:id/history/diff",
+            get(contract_history_handlers::diff_history_entries),
+        )
         // Version diff: compare any two snapshot versions
         .route(
             "/api/contracts/:id/versions/:v1/diff/:v2",
@@ -0,0 +1,204 @@
+//! CORS configuration.
+//!
+//! Allowed origins come from `CORS_ALLOWED_ORIGINS` (comma-separated) so new
+//! frontend deployments do not need a rebuild. Each entry must be a bare
+//! origin such as `https://app.example.com` or `http://localhost:3000`.
+
+use axum::http::{header, HeaderName, HeaderValue, Method, Uri};
+use tower_http::cors::CorsLayer;
+
+use crate::{observability::REQUEST_ID_HEADER, rate_limit, share_tokens::SHARE_TOKEN_HEADER};
+
+const ALLOWED_ORIGINS_ENV: &str = "CORS_ALLOWED_ORIGINS";
+
+/// Used when `CORS_ALLOWED_ORIGINS` is unset
+const DEFAULT_ALLOWED_ORIGINS: &[&str] = &[
+    "http://localhost:3000",
+    "https://soroban-registry.vercel.app",
+];
+
+/// Build the CORS layer from the environment, failing on any invalid origin.
+pub fn cors_layer_from_env() -> anyhow::Result<CorsLayer> {
+    let origins = match std::env::var(ALLOWED_ORIGINS_ENV) {
+        Ok(raw) if !raw.trim().is_empty() => parse_allowed_origins(&raw)
+            .map_err(|err| anyhow::anyhow!("invalid {}: {}", ALLOWED_ORIGINS_ENV, err))?,
+        _ => DEFAULT_ALLOWED_ORIGINS
+            .iter()
+            .map(|origin| HeaderValue::from_static(origin))
+            .collect(),
+    };
+
+    tracing::info!(origins = ?origins, "CORS allowed origins configured");
+
+    Ok(cors_layer(origins))
+}
+
+/// Request headers browser clients may send
+fn allowed_headers() -> [HeaderName; 6] {
+    [
+        header::CONTENT_TYPE,
+        header::AUTHORIZATION,
+        header::ACCEPT,
+        header::IF_NONE_MATCH,
+        HeaderName::from_static(REQUEST_ID_HEADER),
+        HeaderName::from_static(SHARE_TOKEN_HEADER),
+    ]
+}
+
+/// Response headers browser clients may read: rate-limit state, cache
+/// validators and the request id to quote in bug reports
+fn exposed_headers() -> [HeaderName; 6] {
+    [
+        rate_limit::HEADER_RATE_LIMIT_LIMIT,
+        rate_limit::HEADER_RATE_LIMIT_REMAINING,
+        rate_limit::HEADER_RATE_LIMIT_RESET,
+        header::RETRY_AFTER,
+        header::ETAG,
+        HeaderName::from_static(REQUEST_ID_HEADER),
+    ]
+}
+
+fn cors_layer(origins: Vec<HeaderValue>) -> CorsLayer {
+    CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods([
+            Method::GET,
+            Method::POST,
+            Method::PUT,
+            Method::PATCH,
+            Method::DELETE,
+            Method::OPTIONS,
+        ])
+        .allow_headers(allowed_headers())
+        .expose_headers(exposed_headers())
+}
+
+/// Parse a comma-separated origin list, rejecting anything that is not
+/// `http(s)://host[:port]`.
+pub fn parse_allowed_origins(raw: &str) -> Result<Vec<HeaderValue>, String> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(parse_origin)
+        .collect()
+}
+
+fn parse_origin(entry: &str) -> Result<HeaderValue, String> {
+    let origin = entry.trim_end_matches('/');
+    let uri: Uri = origin
+        .parse()
+        .map_err(|_| format!("`{}` is not a valid origin", entry))?;
+
+    let scheme_ok = matches!(uri.scheme_str(), Some("http") | Some("https"));
+    let has_host = uri.host().is_some_and(|host| !host.is_empty());
+    let bare = match uri.path_and_query() {
+        Some(pq) => pq.as_str().is_empty() || pq.as_str() == "/",
+        None => true,
+    };
+    if !scheme_ok || !has_host || !bare {
+        return Err(format!(
+            "`{}` is not a valid origin (expected http(s)://host[:port])",
+            entry
+        ));
+    }
+
+    HeaderValue::from_str(origin).map_err(|_| format!("`{}` is not a valid origin", entry))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_comma_separated_origins() {
+        let origins =
+            parse_allowed_origins("https://app.example.com, http://localhost:3000/ ,").unwrap();
+        assert_eq!(
+            origins,
+            vec![
+                HeaderValue::from_static("https://app.example.com"),
+                HeaderValue::from_static("http://localhost:3000"),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_origins() {
+        assert!(parse_allowed_origins("app.example.com").is_err());
+        assert!(parse_allowed_origins("ftp://example.com").is_err());
+        assert!(parse_allowed_origins("https://example.com/path").is_err());
+        assert!(parse_allowed_origins("https://ok.example.com,not an origin").is_err());
+    }
+
+    #[tokio::test]
+    async fn browsers_may_send_and_read_the_api_headers() {
+        use axum::{body::Body, http::Request, routing::get, Router};
+        use tower::ServiceExt;
+
+        let origin = "https://app.example.com";
+        let app = Router::new()
+            .route("/api/contracts", get(|| async { "[]" }))
+            .layer(cors_layer(parse_allowed_origins(origin).unwrap()));
+        let listed = |response: &axum::response::Response, name: HeaderName| {
+            response.headers()[name]
+                .to_str()
+                .unwrap()
+                .split(',')
+                .map(|value| value.trim().to_ascii_lowercase())
+                .collect::<Vec<_>>()
+        };
+
+        let preflight = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method(Method::OPTIONS)
+                    .uri("/api/contracts")
+                    .header(header::ORIGIN, origin)
+                    .header(header::ACCESS_CONTROL_REQUEST_METHOD, "GET")
+                    .header(
+                        header::ACCESS_CONTROL_REQUEST_HEADERS,
+                        "if-none-match,x-request-id",
+                    )
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let allowed = listed(&preflight, header::ACCESS_CONTROL_ALLOW_HEADERS);
+        for name in allowed_headers() {
+            let name = name.as_str();
+            assert!(allowed.iter().any(|a| a == name), "{name} not allowed");
+        }
+
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/api/contracts")
+                    .header(header::ORIGIN, origin)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let exposed = listed(&response, header::ACCESS_CONTROL_EXPOSE_HEADERS);
+        let expected = [
+            "x-ratelimit-limit",
+            "x-ratelimit-remaining",
+            "x-ratelimit-reset",
+            "retry-after",
+            "etag",
+            "x-request-id",
+        ];
+        for name in expected {
+            assert!(exposed.iter().any(|e| e == name), "{name} not exposed");
+        }
+    }
+
+    #[test]
+    fn defaults_are_valid_origins() {
+        for origin in DEFAULT_ALLOWED_ORIGINS {
+            assert!(parse_origin(origin).is_ok(), "{}", origin);
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! On-chain deployment addresses of registry entries (`contract_deployment_addresses`).
+//!
+//! A contract may record any number of addresses per network. With `verify`
+//! set, each address is checked through Soroban RPC, as `verify_contract`
+//! does, against every WASM hash the contract has registered. An address
+//! running something else, or nothing at all, is still stored with
+//! `verified: false` so operators can look into it.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use shared::{DeploymentAddress, Network, OrganizationRole, RecordDeploymentRequest};
+use uuid::Uuid;
+
+use crate::{
+    auth::{Caller, ContractAccess},
+    error::ApiResult,
+    handlers::{db_internal_error, fetch_visible_contract, rpc_api_error},
+    pagination::{PageParams, Paginated, PaginatedDeploymentAddresses},
+    share_tokens::PresentedShareToken,
+    soroban_rpc::{self, RpcError},
+    state::AppState,
+};
+
+/// Result of checking an address on chain
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DeploymentCheck {
+    pub verified: bool,
+    pub on_chain_hash: Option<String>,
+}
+
+/// Judge an RPC lookup of a deployment against the contract's registered
+/// hashes. Nothing deployed is a failed check; an RPC that could not answer
+/// is an error, since the address was not checked at all.
+pub fn deployment_check(
+    lookup: Result<String, RpcError>,
+    registered: &[String],
+) -> ApiResult<DeploymentCheck> {
+    match lookup {
+        Ok(hash) => Ok(DeploymentCheck {
+            verified: registered
+                .iter()
+                .any(|registered| registered.eq_ignore_ascii_case(&hash)),
+            on_chain_hash: Some(hash),
+        }),
+        Err(RpcError::NotDeployed) => Ok(DeploymentCheck {
+            verified: false,
+            on_chain_hash: None,
+        }),
+        Err(err) => Err(rpc_api_error(err)),
+    }
+}
+
+/// Query Soroban RPC for what `address` runs on `network`
+async fn check_deployment(
+    state: &AppState,
+    contract_id: Uuid,
+    network: &Network,
+    address: &str,
+) -> ApiResult<DeploymentCheck> {
+    let registered: Vec<String> = sqlx::query_scalar(
+        "SELECT wasm_hash FROM contracts WHERE id = $1
+         UNION
+         SELECT wasm_hash FROM contract_versions WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list registered wasm hashes", err))?;
+
+    let rpc_url = soroban_rpc::rpc_url_for(network).map_err(rpc_api_error)?;
+    let check = deployment_check(
+        soroban_rpc::fetch_wasm_hash(&rpc_url, address).await,
+        &registered,
+    )?;
+    if !check.verified {
+        tracing::warn!(
+            %contract_id,
+            %address,
+            on_chain_hash = ?check.on_chain_hash,
+            "deployment does not run a registered WASM hash"
+        );
+    }
+    Ok(check)
+}
+
+/// Record a deployment address, optionally checking it on chain. Recording
+/// an address again keeps its previous check unless `verify` is set.
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/deployments",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    request_body = RecordDeploymentRequest,
+    responses(
+        (status = 201, description = "The recorded deployment; `verified` is false when the address does not run a registered hash", body = DeploymentAddress),
+        (status = 400, description = "Not a valid contract address"),
+        (status = 403, description = "Caller is not a maintainer of the contract"),
+        (status = 404, description = "Contract not found"),
+        (status = 502, description = "Soroban RPC unreachable or returned an unexpected response"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn record_deployment(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Extension(access): Extension<ContractAccess>,
+    Json(req): Json<RecordDeploymentRequest>,
+) -> ApiResult<(StatusCode, Json<DeploymentAddress>)> {
+    access.require(OrganizationRole::Maintainer)?;
+    let id = access.contract_id;
+
+    let address = req.address.trim();
+    soroban_rpc::decode_contract_address(address).map_err(rpc_api_error)?;
+
+    let network = match req.network {
+        Some(network) => network,
+        None => sqlx::query_scalar("SELECT network FROM contracts WHERE id = $1")
+            .bind(id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get contract network", err))?,
+    };
+
+    let check = if req.verify {
+        Some(check_deployment(&state, id, &network, address).await?)
+    } else {
+        None
+    };
+
+    let deployment: DeploymentAddress = sqlx::query_as(
+        "INSERT INTO contract_deployment_addresses
+             (contract_id, network, address, verified, on_chain_hash, verified_at, created_by)
+         VALUES ($1, $2, $3, $4, $5, CASE WHEN $6 THEN NOW() END, $7)
+         ON CONFLICT (contract_id, network, address) DO UPDATE SET
+             verified = CASE WHEN $6 THEN EXCLUDED.verified ELSE contract_deployment_addresses.verified END,
+             on_chain_hash = CASE WHEN $6 THEN EXCLUDED.on_chain_hash ELSE contract_deployment_addresses.on_chain_hash END,
+             verified_at = CASE WHEN $6 THEN EXCLUDED.verified_at ELSE contract_deployment_addresses.verified_at END,
+             updated_at = NOW()
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(&network)
+    .bind(address)
+    .bind(check.as_ref().is_some_and(|check| check.verified))
+    .bind(check.as_ref().and_then(|check| check.on_chain_hash.clone()))
+    .bind(check.is_some())
+    .bind(caller.publisher_id())
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("record deployment", err))?;
+
+    Ok((StatusCode::CREATED, Json(deployment)))
+}
+
+/// Deployment addresses recorded for a contract
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/deployments",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        PageParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Deployments grouped by network, newest first", body = PaginatedDeploymentAddresses),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
+pub async fn list_deployments(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<DeploymentAddress>>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let deployments: Vec<DeploymentAddress> = sqlx::query_as(
+        "SELECT * FROM contract_deployment_addresses WHERE contract_id = $1
+         ORDER BY network, created_at DESC, id DESC
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(id)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list deployments", err))?;
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM contract_deployment_addresses WHERE contract_id = $1",
+    )
+    .bind(id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count deployments", err))?;
+
+    Ok(Json(Paginated::new(
+        deployments,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn any_registered_hash_verifies_the_address() {
+        let registered = vec!["aa11".to_string(), "bb22".to_string()];
+        let check = deployment_check(Ok("BB22".to_string()), &registered).unwrap();
+        assert!(check.verified);
+        assert_eq!(check.on_chain_hash.as_deref(), Some("BB22"));
+    }
+
+    #[test]
+    fn mismatched_or_missing_deployments_are_kept_unverified() {
+        let registered = vec!["aa11".to_string()];
+        let check = deployment_check(Ok("cc33".to_string()), &registered).unwrap();
+        assert!(!check.verified);
+        assert_eq!(check.on_chain_hash.as_deref(), Some("cc33"));
+
+        let check = deployment_check(Err(RpcError::NotDeployed), &registered).unwrap();
+        assert_eq!(
+            check,
+            DeploymentCheck {
+                verified: false,
+                on_chain_hash: None,
+            }
+        );
+    }
+
+    #[test]
+    fn unreachable_rpc_is_an_error() {
+        let err = deployment_check(Err(RpcError::Unreachable("timeout".into())), &[]).unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_GATEWAY);
+    }
+
+    #[tokio::test]
+    async fn private_contract_deployments_are_hidden_from_anonymous_callers() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let id = state.insert_private_contract().await;
+
+        let uri = format!("/api/contracts/{}/deployments", id);
+        assert_eq!(state.anonymous_get(&uri).await, StatusCode::NOT_FOUND);
+    }
+}
@@ -1,9 +1,18 @@
 // api/src/detector.rs
 // Static pattern-matching auto-detector for Soroban Rust source code.
 
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use chrono::{DateTime, Utc};
+use once_cell::sync::{Lazy, OnceCell};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+
 use crate::checklist::all_checks;
-use crate::models::{CheckStatus, DetectionMethod};
+use crate::models::{CheckCategory, CheckStatus, ChecklistItem, DetectionMethod, Severity};
 
 /// Result of running the detector on a single check
 #[derive(Debug)]
@@ -15,47 +24,53 @@ pub struct DetectionResult {
 /// Run all auto-detectable checks against the provided source code.
 /// Returns a map of check_id → DetectionResult for auto/semi-auto checks only.
 pub fn detect_all(source: &str) -> HashMap<String, DetectionResult> {
-    let checks = all_checks();
-    let mut results = HashMap::new();
     let lines: Vec<&str> = source.lines().collect();
+    all_checks()
+        .iter()
+        .filter_map(|check| Some((check.id.to_string(), detect_check(&lines, check)?)))
+        .collect()
+}
 
-    for check in checks {
-        let patterns = match &check.detection {
-            DetectionMethod::Automatic { patterns } => patterns.clone(),
-            DetectionMethod::SemiAutomatic { patterns } => patterns.clone(),
-            DetectionMethod::Manual => continue,
-        };
-
-        let result = match check.id {
-            "IV-001" => detect_unwrap(&lines),
-            "IV-002" => detect_expect(&lines),
-            "IV-006" => detect_panic_macro(&lines),
-            "IV-009" => detect_direct_index(&lines),
-            "AC-001" => detect_require_auth(&lines, &["admin", "owner", "operator"]),
-            "AC-002" => detect_transfer_without_auth(&lines),
-            "AC-007" => detect_init_guard(&lines),
-            "AC-008" => detect_upgrade_guard(&lines),
-            "NS-001" => detect_unchecked_arithmetic(&lines),
-            "NS-002" => detect_division_by_zero_guard(&lines),
-            "NS-005" => detect_truncating_cast(&lines),
-            "AA-001" => detect_require_auth_present(&lines),
-            "EH-002" => detect_silent_discard(&lines),
-            "EH-004" => detect_storage_none_handled(&lines),
-            "SM-001" => detect_ttl_extension(&lines),
-            "SM-002" => detect_instance_ttl(&lines),
-            "SM-003" => detect_state_before_call(&lines),
-            "TS-001" => detect_token_transfer_error(&lines),
-            "EL-001" => detect_events_on_transfers(&lines),
-            "DS-001" => detect_contracttype(&lines),
-            "SP-001" => detect_datakey_enum(&lines),
-            "RL-001" => detect_bounded_loops(&lines),
-            _        => detect_generic(&lines, &patterns),
-        };
-
-        results.insert(check.id.to_string(), result);
+/// Run one check against `lines`; `None` for manual checks
+pub fn detect_check(lines: &[&str], check: &ChecklistItem) -> Option<DetectionResult> {
+    if let Some(result) = detect_custom_rule(lines, &check.id) {
+        return Some(result);
     }
 
-    results
+    let patterns = match &check.detection {
+        DetectionMethod::Automatic { patterns } => patterns,
+        DetectionMethod::SemiAutomatic { patterns } => patterns,
+        DetectionMethod::Manual => return None,
+    };
+
+    let result = match check.id.as_ref() {
+        "IV-001" => detect_unwrap(lines),
+        "IV-002" => detect_expect(lines),
+        "IV-006" => detect_panic_macro(lines),
+        "IV-009" => detect_direct_index(lines),
+        "AC-001" => detect_require_auth(lines, &["admin", "owner", "operator"]),
+        "AC-002" => detect_transfer_without_auth(lines),
+        "AC-007" => detect_init_guard(lines),
+        "AC-008" => detect_upgrade_guard(lines),
+        "NS-001" => detect_unchecked_arithmetic(lines),
+        "NS-009" => detect_raw_arithmetic(lines),
+        "NS-002" => detect_division_by_zero_guard(lines),
+        "NS-005" => detect_truncating_cast(lines),
+        "AA-001" => detect_require_auth_present(lines),
+        "EH-002" => detect_silent_discard(lines),
+        "EH-004" => detect_storage_none_handled(lines),
+        "SM-001" => detect_ttl_extension(lines),
+        "SM-002" => detect_instance_ttl(lines),
+        "SM-003" => detect_state_before_call(lines),
+        "RE-006" => detect_reentrancy(lines),
+        "TS-001" => detect_token_transfer_error(lines),
+        "EL-001" => detect_events_on_transfers(lines),
+        "DS-001" => detect_contracttype(lines),
+        "SP-001" => detect_datakey_enum(lines),
+        "RL-001" => detect_bounded_loops(lines),
+        _        => detect_generic(lines, patterns),
+    };
+    Some(result)
 }
 
 // ─────────────────────────────────────────────────────────
@@ -209,6 +224,125 @@ fn detect_unchecked_arithmetic(lines: &[&str]) -> DetectionResult {
     }
 }
 
+/// `a + b`, `x * 2`, `total -= fee`: a binary operator with at least one
+/// non-literal operand. Spaces around the operator keep `->`, `&*x` and
+/// unary minus out.
+static RAW_ARITHMETIC: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"([A-Za-z_][\w.]*(?:\([^()]*\))?|\d[\d_]*)\s+([+*-])(=?)\s+([A-Za-z_(][\w.]*|\d[\d_]*)")
+        .expect("valid arithmetic pattern")
+});
+
+/// Whether a `+` match joins trait bounds (`T: Clone + Send`,
+/// `dyn Error + Sync`, `'a + Copy`) rather than values. Trait names are
+/// CamelCase; SCREAMING_CASE constants still count as values.
+fn is_trait_bound(code: &str, lhs_start: usize, lhs: &str, rhs: &str) -> bool {
+    let camel_case = |s: &str| {
+        s.starts_with(|c: char| c.is_ascii_uppercase())
+            && s.contains(|c: char| c.is_ascii_lowercase())
+    };
+    code[..lhs_start].ends_with('\'') || (camel_case(lhs) && camel_case(rhs))
+}
+
+/// NS-009: the first raw arithmetic operation in the source. Inline ignores
+/// are applied by the scanner's suppression pass, like for every other rule.
+fn detect_raw_arithmetic(lines: &[&str]) -> DetectionResult {
+    for (i, line) in lines.iter().enumerate() {
+        let code = strip_line_comment(line).trim();
+        if code.is_empty() || is_test_line(code) || code.contains('"') || code.contains("..") {
+            continue;
+        }
+        for caps in RAW_ARITHMETIC.captures_iter(code) {
+            let (lhs, op, rhs) = (&caps[1], &caps[2], &caps[4]);
+            let is_literal = |s: &str| s.starts_with(|c: char| c.is_ascii_digit());
+            if is_literal(lhs) && is_literal(rhs) {
+                continue;
+            }
+            let lhs_start = caps.get(1).map_or(0, |m| m.start());
+            if op == "+" && caps[3].is_empty() && is_trait_bound(code, lhs_start, lhs, rhs) {
+                continue;
+            }
+            let checked = match op {
+                "+" => "checked_add",
+                "-" => "checked_sub",
+                _ => "checked_mul",
+            };
+            return DetectionResult {
+                status: CheckStatus::Failed,
+                evidence: Some(format!(
+                    "Line {}: `{} {}{} {}` can overflow; use `{}.{}({})` instead: {}",
+                    i + 1,
+                    lhs,
+                    op,
+                    &caps[3],
+                    rhs,
+                    lhs,
+                    checked,
+                    rhs,
+                    code
+                )),
+            };
+        }
+    }
+    DetectionResult { status: CheckStatus::Passed, evidence: None }
+}
+
+/// Code before a trailing `//` comment
+fn strip_line_comment(line: &str) -> &str {
+    match line.find("//") {
+        Some(i) => &line[..i],
+        None => line,
+    }
+}
+
+/// Marker for an inline ignore: `// soroban-registry:ignore RULE-ID reason`
+pub const IGNORE_PRAGMA: &str = "soroban-registry:ignore";
+
+/// Rule id and (possibly empty) reason of an ignore pragma on this line
+fn ignore_pragma(line: &str) -> Option<(&str, &str)> {
+    let comment = &line[line.find("//")? + 2..];
+    let rest = comment.trim_start().strip_prefix(IGNORE_PRAGMA)?.trim();
+    let (rule, reason) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+    (!rule.is_empty()).then_some((rule, reason.trim()))
+}
+
+/// Every inline ignore in a source, keyed by the line it covers, so a scan
+/// reads the pragmas once however often it re-runs a rule
+#[derive(Debug, Default)]
+pub struct InlineSuppressions {
+    /// Line index -> (rule id, reason); a pragma on the line itself comes
+    /// before one in the comment above
+    by_line: HashMap<usize, Vec<(String, String)>>,
+}
+
+impl InlineSuppressions {
+    /// A pragma covers its own line, and the next one when it sits alone in
+    /// a comment. A pragma without a reason doesn't count.
+    pub fn new(lines: &[&str]) -> Self {
+        let mut by_line: HashMap<usize, Vec<(String, String)>> = HashMap::new();
+        for (i, line) in lines.iter().enumerate() {
+            let Some((rule, reason)) = ignore_pragma(line).filter(|(_, reason)| !reason.is_empty())
+            else {
+                continue;
+            };
+            let pragma = (rule.to_string(), reason.to_string());
+            if line.trim_start().starts_with("//") && i + 1 < lines.len() {
+                by_line.entry(i + 1).or_default().push(pragma.clone());
+            }
+            by_line.entry(i).or_default().insert(0, pragma);
+        }
+        Self { by_line }
+    }
+
+    /// Reason given for ignoring `rule_id` on line `i`
+    pub fn reason(&self, i: usize, rule_id: &str) -> Option<&str> {
+        self.by_line
+            .get(&i)?
+            .iter()
+            .find(|(rule, _)| rule == rule_id)
+            .map(|(_, reason)| reason.as_str())
+    }
+}
+
 fn detect_division_by_zero_guard(lines: &[&str]) -> DetectionResult {
     for (i, line) in lines.iter().enumerate() {
         let t = line.trim();
@@ -346,6 +480,73 @@ fn detect_state_before_call(lines: &[&str]) -> DetectionResult {
     DetectionResult { status: CheckStatus::Passed, evidence: None }
 }
 
+/// `<x>client.method(`, `Client::new(..).method(` or `invoke_contract(`
+static EXTERNAL_CALL: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?:\w*[cC]lient(?:::new\([^)]*\))?\s*\.\s*(\w+)\s*(?:::<[^>]*>)?\s*\(|\b(invoke_contract|try_invoke_contract)\b)")
+        .expect("valid external call pattern")
+});
+
+/// Persistent/instance/temporary storage writes
+static STATE_WRITE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"storage\(\)\s*\.\s*(?:persistent\(\)|instance\(\)|temporary\(\))?\s*\.?\s*(?:set|remove|update)\s*\(")
+        .expect("valid state write pattern")
+});
+
+/// Methods that only read from the callee, so calling them first is safe
+const READ_ONLY_CALLS: &[&str] = &[
+    "balance", "spendable_balance", "allowance", "decimals", "name", "symbol",
+    "total_supply", "authorized", "get", "version",
+];
+
+fn is_read_only_call(method: &str) -> bool {
+    READ_ONLY_CALLS.contains(&method)
+        || ["get_", "read_", "is_", "has_", "query_", "view_"]
+            .iter()
+            .any(|prefix| method.starts_with(prefix))
+}
+
+/// RE-006: within one function, a state-changing external call followed by
+/// a storage write. Read-only calls (`balance`, `get_*`, ...) don't count.
+fn detect_reentrancy(lines: &[&str]) -> DetectionResult {
+    // (line number, method) of the first state-changing call in the current fn
+    let mut pending_call: Option<(usize, String)> = None;
+    for (i, line) in lines.iter().enumerate() {
+        let t = line.trim();
+        if t.starts_with("//") || is_test_line(t) {
+            continue;
+        }
+        if t.starts_with("pub fn ") || t.starts_with("fn ") || t.contains(" fn ") {
+            pending_call = None;
+        }
+
+        if let Some((call_line, method)) = &pending_call {
+            if STATE_WRITE.is_match(t) {
+                return DetectionResult {
+                    status: CheckStatus::Failed,
+                    evidence: Some(format!(
+                        "Line {}: storage written after external call `{}` on line {}; the callee can re-enter while this state is stale: {}",
+                        i + 1,
+                        method,
+                        call_line,
+                        t
+                    )),
+                };
+            }
+        }
+
+        if pending_call.is_none() {
+            for caps in EXTERNAL_CALL.captures_iter(t) {
+                let method = caps.get(1).or_else(|| caps.get(2)).map(|m| m.as_str()).unwrap_or("");
+                if method != "new" && !is_read_only_call(method) {
+                    pending_call = Some((i + 1, method.to_string()));
+                    break;
+                }
+            }
+        }
+    }
+    DetectionResult { status: CheckStatus::Passed, evidence: None }
+}
+
 fn detect_token_transfer_error(lines: &[&str]) -> DetectionResult {
     let source = lines.join("\n");
     if source.contains("TokenClient") && source.contains(".transfer(") {
@@ -443,6 +644,384 @@ fn detect_generic(lines: &[&str], good_patterns: &[String]) -> DetectionResult {
     }
 }
 
+// ─────────────────────────────────────────────────────────
+// Custom rules (DETECTOR_RULES_PATH)
+// ─────────────────────────────────────────────────────────
+
+/// Env var pointing at a TOML or JSON file of extra rules
+pub const RULES_PATH_ENV: &str = "DETECTOR_RULES_PATH";
+
+/// A team-specific rule: a regex that must *not* match the source
+#[derive(Debug)]
+pub struct CustomRule {
+    pub id: String,
+    pub severity: Severity,
+    pub pattern: Regex,
+    pub message: String,
+    /// Checklist entry so audits and scoring treat the rule like a built-in
+    pub item: ChecklistItem,
+}
+
+static CUSTOM_RULES: OnceCell<Vec<CustomRule>> = OnceCell::new();
+
+/// Rules loaded at startup (empty when `DETECTOR_RULES_PATH` is unset)
+pub fn custom_rules() -> &'static [CustomRule] {
+    CUSTOM_RULES.get().map(Vec::as_slice).unwrap_or(&[])
+}
+
+/// Run the file-loaded or dynamic rule with this id, if there is one
+fn detect_custom_rule(lines: &[&str], id: &str) -> Option<DetectionResult> {
+    if let Some(rule) = custom_rules().iter().find(|rule| rule.id == id) {
+        return Some(detect_custom(lines, rule));
+    }
+    dynamic_rule(id).map(|rule| detect_custom(lines, &rule))
+}
+
+/// Load `DETECTOR_RULES_PATH` into the rule registry. Call once at startup;
+/// an invalid file is an error so rules are never silently dropped.
+pub fn init_custom_rules_from_env() -> Result<usize, RuleFileError> {
+    let Ok(path) = std::env::var(RULES_PATH_ENV) else {
+        return Ok(0);
+    };
+    let rules = load_custom_rules(Path::new(&path))?;
+    let count = rules.len();
+    if CUSTOM_RULES.set(rules).is_err() {
+        tracing::warn!("custom detector rules were already loaded; ignoring reload");
+    }
+    Ok(count)
+}
+
+/// Error in a custom rule file, pointing at the offending line when known
+#[derive(Debug)]
+pub struct RuleFileError {
+    pub path: String,
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl std::fmt::Display for RuleFileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {}", self.path, line, self.message),
+            None => write!(f, "{}: {}", self.path, self.message),
+        }
+    }
+}
+
+impl std::error::Error for RuleFileError {}
+
+#[derive(Debug, Deserialize)]
+struct RuleFile {
+    #[serde(default)]
+    rules: Vec<RawRule>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawRule {
+    id: String,
+    severity: String,
+    pattern: String,
+    message: String,
+    /// Defaults to input validation, since rules pattern-match source text
+    #[serde(default)]
+    category: Option<CheckCategory>,
+    #[serde(default)]
+    remediation: Option<String>,
+}
+
+pub fn load_custom_rules(path: &Path) -> Result<Vec<CustomRule>, RuleFileError> {
+    let display = path.display().to_string();
+    let text = std::fs::read_to_string(path).map_err(|err| RuleFileError {
+        path: display.clone(),
+        line: None,
+        message: format!("cannot read rule file: {}", err),
+    })?;
+    let is_json = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+    parse_custom_rules(&text, is_json, &display)
+}
+
+fn parse_custom_rules(text: &str, is_json: bool, path: &str) -> Result<Vec<CustomRule>, RuleFileError> {
+    let error = |line: Option<usize>, message: String| RuleFileError {
+        path: path.to_string(),
+        line,
+        message,
+    };
+
+    let file: RuleFile = if is_json {
+        serde_json::from_str(text).map_err(|err| error(Some(err.line()), err.to_string()))?
+    } else {
+        toml::from_str(text).map_err(|err| {
+            let line = err.span().map(|span| line_of_offset(text, span.start));
+            error(line, err.message().to_string())
+        })?
+    };
+
+    let builtin: HashSet<String> = crate::checklist::builtin_checks()
+        .into_iter()
+        .map(|c| c.id.to_string())
+        .collect();
+    let mut seen = HashSet::new();
+    let mut rules = Vec::with_capacity(file.rules.len());
+
+    for raw in file.rules {
+        let line = line_of_rule(text, &raw.id);
+        let id = raw.id.trim();
+        if id.is_empty() {
+            return Err(error(line, "rule id must not be empty".into()));
+        }
+        if builtin.contains(id) {
+            return Err(error(line, format!("rule '{}' clashes with a built-in check", id)));
+        }
+        if !seen.insert(id.to_string()) {
+            return Err(error(line, format!("duplicate rule id '{}'", id)));
+        }
+        if raw.message.trim().is_empty() {
+            return Err(error(line, format!("rule '{}' needs a message", id)));
+        }
+        let severity = parse_severity_label(&raw.severity).ok_or_else(|| {
+            error(
+                line,
+                format!(
+                    "rule '{}' has unknown severity '{}' (expected info, low, medium, high or critical)",
+                    id, raw.severity
+                ),
+            )
+        })?;
+        let pattern = Regex::new(&raw.pattern)
+            .map_err(|err| error(line, format!("rule '{}' has an invalid pattern: {}", id, err)))?;
+
+        rules.push(build_rule(
+            id,
+            severity,
+            pattern,
+            raw.message.trim(),
+            raw.category.unwrap_or(CheckCategory::InputValidation),
+            raw.remediation.unwrap_or_default(),
+        ));
+    }
+
+    Ok(rules)
+}
+
+fn build_rule(
+    id: &str,
+    severity: Severity,
+    pattern: Regex,
+    message: &str,
+    category: CheckCategory,
+    remediation: String,
+) -> CustomRule {
+    let item = ChecklistItem {
+        id: id.to_string().into(),
+        category,
+        title: message.to_string().into(),
+        description: message.to_string().into(),
+        severity: severity.clone(),
+        detection: DetectionMethod::Automatic {
+            patterns: vec![pattern.as_str().to_string()],
+        },
+        remediation: remediation.into(),
+        references: vec![],
+    };
+    CustomRule {
+        id: id.to_string(),
+        severity,
+        pattern,
+        message: message.to_string(),
+        item,
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Dynamic rules (/api/config/detector-rules)
+// ─────────────────────────────────────────────────────────
+
+/// A rule managed at runtime, as stored in `detector_rules`
+#[derive(Debug, Clone, Serialize, sqlx::FromRow)]
+pub struct DetectorRuleRecord {
+    pub id: String,
+    pub severity: String,
+    pub pattern: String,
+    pub message: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Compiled dynamic rules by id. A rule's regex is compiled when the rule is
+/// first seen and reused by every scan until the rule is removed, at which
+/// point it is dropped with the last scan still holding it.
+#[derive(Debug, Default)]
+struct DynamicRuleSet {
+    rules: RwLock<BTreeMap<String, Arc<CustomRule>>>,
+}
+
+impl DynamicRuleSet {
+    fn all(&self) -> Vec<Arc<CustomRule>> {
+        self.rules
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .cloned()
+            .collect()
+    }
+
+    fn get(&self, id: &str) -> Option<Arc<CustomRule>> {
+        self.rules
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(id)
+            .cloned()
+    }
+
+    /// Make `records` the rule set, reusing the compiled rule for any record
+    /// that is unchanged since the last install
+    fn install(&self, records: &[DetectorRuleRecord]) -> usize {
+        let mut registry = self.rules.write().unwrap_or_else(PoisonError::into_inner);
+        let mut next = BTreeMap::new();
+        for record in records {
+            // An id deleted and re-added elsewhere may carry a different rule
+            let cached = registry.get(&record.id).cloned().filter(|rule| {
+                rule.pattern.as_str() == record.pattern
+                    && rule.message == record.message.trim()
+                    && parse_severity_label(&record.severity).as_ref() == Some(&rule.severity)
+            });
+            if let Some(rule) = cached {
+                next.insert(record.id.clone(), rule);
+                continue;
+            }
+            match validate_dynamic_rule(
+                &record.id,
+                &record.severity,
+                &record.pattern,
+                &record.message,
+            ) {
+                Ok((severity, pattern)) => {
+                    let rule = build_rule(
+                        record.id.trim(),
+                        severity,
+                        pattern,
+                        record.message.trim(),
+                        CheckCategory::InputValidation,
+                        String::new(),
+                    );
+                    next.insert(record.id.clone(), Arc::new(rule));
+                }
+                // Only possible if a file-loaded rule took the id after it was stored
+                Err(problem) => {
+                    tracing::warn!(rule = %record.id, %problem, "skipping dynamic detector rule")
+                }
+            }
+        }
+        *registry = next;
+        registry.len()
+    }
+}
+
+static DYNAMIC_RULES: Lazy<DynamicRuleSet> = Lazy::new(Default::default);
+
+/// Rules added through the config API, as of the last refresh
+pub fn dynamic_rules() -> Vec<Arc<CustomRule>> {
+    DYNAMIC_RULES.all()
+}
+
+fn dynamic_rule(id: &str) -> Option<Arc<CustomRule>> {
+    DYNAMIC_RULES.get(id)
+}
+
+/// Check a rule submitted through the config API. The error names the
+/// problem, including the regex error for a pattern that does not compile.
+pub fn validate_dynamic_rule(
+    id: &str,
+    severity: &str,
+    pattern: &str,
+    message: &str,
+) -> Result<(Severity, Regex), String> {
+    let id = id.trim();
+    if id.is_empty() {
+        return Err("rule id must not be empty".into());
+    }
+    let builtin = crate::checklist::builtin_checks()
+        .iter()
+        .any(|check| check.id == id);
+    if builtin || custom_rules().iter().any(|rule| rule.id == id) {
+        return Err(format!(
+            "rule '{}' clashes with a built-in or file-loaded rule",
+            id
+        ));
+    }
+    if message.trim().is_empty() {
+        return Err(format!("rule '{}' needs a message", id));
+    }
+    let severity = parse_severity_label(severity).ok_or_else(|| {
+        format!(
+            "rule '{}' has unknown severity '{}' (expected info, low, medium, high or critical)",
+            id, severity
+        )
+    })?;
+    let pattern = Regex::new(pattern)
+        .map_err(|err| format!("rule '{}' has an invalid pattern: {}", id, err))?;
+    Ok((severity, pattern))
+}
+
+/// Make `records` the dynamic rule set
+pub fn install_dynamic_rules(records: &[DetectorRuleRecord]) -> usize {
+    DYNAMIC_RULES.install(records)
+}
+
+/// Reload the dynamic rules from the database
+pub async fn sync_dynamic_rules(pool: &PgPool) -> Result<usize, sqlx::Error> {
+    let records: Vec<DetectorRuleRecord> =
+        sqlx::query_as("SELECT * FROM detector_rules ORDER BY id")
+            .fetch_all(pool)
+            .await?;
+    Ok(install_dynamic_rules(&records))
+}
+
+/// Pick up rules added or removed through any API instance before a scan.
+/// If the database cannot be read, the rules from the last refresh stay.
+pub async fn refresh_dynamic_rules(pool: &PgPool) {
+    if let Err(err) = sync_dynamic_rules(pool).await {
+        tracing::warn!(error = %err, "failed to refresh dynamic detector rules");
+    }
+}
+
+/// Parse a lowercase severity label (`info`, `low`, `medium`, `high`, `critical`)
+pub fn parse_severity_label(raw: &str) -> Option<Severity> {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "info" => Some(Severity::Info),
+        "low" => Some(Severity::Low),
+        "medium" => Some(Severity::Medium),
+        "high" => Some(Severity::High),
+        "critical" => Some(Severity::Critical),
+        _ => None,
+    }
+}
+
+fn line_of_offset(text: &str, offset: usize) -> usize {
+    text[..offset.min(text.len())].matches('\n').count() + 1
+}
+
+/// Best-effort line of a rule: where its quoted id first appears
+fn line_of_rule(text: &str, id: &str) -> Option<usize> {
+    text.find(&format!("\"{}\"", id))
+        .map(|offset| line_of_offset(text, offset))
+}
+
+fn detect_custom(lines: &[&str], rule: &CustomRule) -> DetectionResult {
+    for (i, line) in lines.iter().enumerate() {
+        if is_test_line(line) || line.trim_start().starts_with("//") { continue; }
+        if rule.pattern.is_match(line) {
+            return DetectionResult {
+                status: CheckStatus::Failed,
+                evidence: Some(format!("Line {}: {} ({})", i + 1, line.trim(), rule.message)),
+            };
+        }
+    }
+    DetectionResult { status: CheckStatus::Passed, evidence: None }
+}
+
 fn is_test_line(line: &str) -> bool {
     let t = line.trim();
     t.starts_with("#[test]") || t.starts_with("#[cfg(test)]")
@@ -493,6 +1072,165 @@ pub fn transfer(env: Env, from: Address, amount: i128) {
         assert!(bad_fails > good_fails, "bad({}) should exceed good({})", bad_fails, good_fails);
     }
 
+    const REENTRANT_WITHDRAW: &str = r#"
+pub fn withdraw(env: Env, to: Address, amount: i128) {
+    to.require_auth();
+    let balance: i128 = env.storage().persistent().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+    let token_client = token::Client::new(&env, &read_token(&env));
+    token_client.transfer(&env.current_contract_address(), &to, &amount);
+    env.storage().persistent().set(&DataKey::Balance(to.clone()), &(balance - amount));
+}
+"#;
+
+    const FIXED_WITHDRAW: &str = r#"
+pub fn withdraw(env: Env, to: Address, amount: i128) {
+    to.require_auth();
+    let balance: i128 = env.storage().persistent().get(&DataKey::Balance(to.clone())).unwrap_or(0);
+    env.storage().persistent().set(&DataKey::Balance(to.clone()), &(balance - amount));
+    let token_client = token::Client::new(&env, &read_token(&env));
+    token_client.transfer(&env.current_contract_address(), &to, &amount);
+}
+"#;
+
+    const READ_ONLY_THEN_WRITE: &str = r#"
+pub fn sync(env: Env, token: Address) {
+    let held = token::Client::new(&env, &token).balance(&env.current_contract_address());
+    env.storage().instance().set(&DataKey::Reserve, &held);
+}
+"#;
+
+    #[test]
+    fn reentrancy_flags_write_after_external_call() {
+        let lines: Vec<&str> = REENTRANT_WITHDRAW.lines().collect();
+        let result = detect_reentrancy(&lines);
+        assert_eq!(result.status, CheckStatus::Failed);
+        let evidence = result.evidence.unwrap();
+        assert!(evidence.starts_with("Line 7:"), "{}", evidence);
+        assert!(evidence.contains("`transfer` on line 6"));
+
+        let item = all_checks().into_iter().find(|c| c.id == "RE-006").unwrap();
+        assert_eq!(item.severity, Severity::High);
+        assert_eq!(detect_all(REENTRANT_WITHDRAW)["RE-006"].status, CheckStatus::Failed);
+    }
+
+    #[test]
+    fn reentrancy_passes_checks_effects_interactions() {
+        let lines: Vec<&str> = FIXED_WITHDRAW.lines().collect();
+        assert_eq!(detect_reentrancy(&lines).status, CheckStatus::Passed);
+    }
+
+    #[test]
+    fn reentrancy_ignores_read_only_calls() {
+        let lines: Vec<&str> = READ_ONLY_THEN_WRITE.lines().collect();
+        assert_eq!(detect_reentrancy(&lines).status, CheckStatus::Passed);
+
+        // A call in one function doesn't taint writes in the next
+        let split = "fn pay(env: Env) {\n    client.transfer(&a, &b, &1);\n}\nfn save(env: Env) {\n    env.storage().instance().set(&K, &1);\n}\n";
+        let lines: Vec<&str> = split.lines().collect();
+        assert_eq!(detect_reentrancy(&lines).status, CheckStatus::Passed);
+    }
+
+    const RAW_ADD: &str = r#"
+pub fn deposit(env: Env, from: Address, amount: i128) {
+    from.require_auth();
+    let balance = read_balance(&env, &from);
+    write_balance(&env, &from, balance + amount);
+}
+"#;
+
+    const CHECKED_ADD: &str = r#"
+pub fn deposit(env: Env, from: Address, amount: i128) -> Result<(), Error> {
+    from.require_auth();
+    let balance = read_balance(&env, &from);
+    let updated = balance.checked_add(amount).ok_or(Error::Overflow)?;
+    write_balance(&env, &from, updated);
+    Ok(())
+}
+"#;
+
+    fn raw_arithmetic_findings(source: &str) -> usize {
+        crate::scanner_service::detector_findings(source)
+            .iter()
+            .filter(|f| f.rule_id == "NS-009")
+            .count()
+    }
+
+    #[test]
+    fn raw_addition_on_user_input_is_flagged() {
+        assert_eq!(raw_arithmetic_findings(RAW_ADD), 1);
+        let lines: Vec<&str> = RAW_ADD.lines().collect();
+        let evidence = detect_raw_arithmetic(&lines).evidence.unwrap();
+        assert!(evidence.starts_with("Line 5:"), "{}", evidence);
+        assert!(evidence.contains("balance.checked_add(amount)"));
+
+        let item = all_checks().into_iter().find(|c| c.id == "NS-009").unwrap();
+        assert_eq!(item.severity, Severity::Medium);
+    }
+
+    #[test]
+    fn checked_add_is_not_flagged() {
+        assert_eq!(raw_arithmetic_findings(CHECKED_ADD), 0);
+        // Literal-only math, ranges, return types and derefs aren't arithmetic on values
+        let lines = ["const MAX: u32 = 60 * 60;", "for i in 0..n + 1 {}", "fn f() -> i128 {", "let v = &*x;"];
+        assert_eq!(detect_raw_arithmetic(&lines).status, CheckStatus::Passed);
+    }
+
+    #[test]
+    fn trait_bounds_are_not_arithmetic() {
+        let lines = [
+            "fn run<T: Clone + Send + 'static>(task: T) {}",
+            "fn boxed() -> Box<dyn Error + Send + Sync> {",
+            "    F: Fn(u32) + Sync,",
+            "fn hold<'a, T: 'a + Copy>(v: &'a T) {}",
+            "impl<T> Store for T where T: Storage + Clone {}",
+        ];
+        assert_eq!(detect_raw_arithmetic(&lines).status, CheckStatus::Passed);
+
+        // Constants and values next to a bound-like name are still arithmetic
+        for line in [
+            "let supply = MAX_SUPPLY + amount;",
+            "let n = A + B;",
+            "let t = Total + fee;",
+        ] {
+            let status = detect_raw_arithmetic(&[line]).status;
+            assert_eq!(status, CheckStatus::Failed, "{}", line);
+        }
+    }
+
+    #[test]
+    fn raw_arithmetic_honours_inline_ignore() {
+        let trailing = RAW_ADD.replace(
+            "balance + amount);",
+            "balance + amount); // soroban-registry:ignore NS-009 amount is capped by the caller",
+        );
+        assert_eq!(raw_arithmetic_findings(&trailing), 0);
+
+        let above = RAW_ADD.replace(
+            "    write_balance(",
+            "    // soroban-registry:ignore NS-009 bounded by MAX_SUPPLY\n    write_balance(",
+        );
+        assert_eq!(raw_arithmetic_findings(&above), 0);
+
+        // Ignoring a different rule doesn't silence this one
+        let other = RAW_ADD.replace("balance + amount);", "balance + amount); // soroban-registry:ignore IV-001 n/a");
+        assert_eq!(raw_arithmetic_findings(&other), 1);
+    }
+
+    #[test]
+    fn ignore_pragma_needs_a_reason() {
+        let lines = [
+            "let a = x + y; // soroban-registry:ignore NS-009 capped above",
+            "let b = x + y; // soroban-registry:ignore NS-009",
+            "// soroban-registry:ignore NS-009   ",
+            "let c = x + y;",
+        ];
+        let pragmas = InlineSuppressions::new(&lines);
+        assert_eq!(pragmas.reason(0, "NS-009"), Some("capped above"));
+        assert_eq!(pragmas.reason(0, "IV-001"), None);
+        assert_eq!(pragmas.reason(1, "NS-009"), None);
+        assert_eq!(pragmas.reason(3, "NS-009"), None);
+    }
+
     #[test]
     fn unwrap_detection_works() {
         assert_eq!(detect_unwrap(&["let x = foo.unwrap();"]).status, CheckStatus::Failed);
@@ -503,4 +1241,101 @@ pub fn transfer(env: Env, from: Address, amount: i128) {
     fn panic_detection_works() {
         assert_eq!(detect_panic_macro(&[r#"panic!("bad");"#]).status, CheckStatus::Failed);
     }
+
+    #[test]
+    fn parses_toml_custom_rules() {
+        let text = r#"
+[[rules]]
+id = "TEAM-001"
+severity = "high"
+pattern = "env\\.invoke_contract"
+message = "Raw invoke_contract is banned; use a generated client"
+"#;
+        let rules = parse_custom_rules(text, false, "rules.toml").unwrap();
+        assert_eq!(rules.len(), 1);
+        assert_eq!(rules[0].severity, Severity::High);
+        assert_eq!(rules[0].item.id, "TEAM-001");
+
+        let hit = detect_custom(&["env.invoke_contract(&id, &fn_name, args);"], &rules[0]);
+        assert_eq!(hit.status, CheckStatus::Failed);
+        let miss = detect_custom(&["client.transfer(&from, &to, &amount);"], &rules[0]);
+        assert_eq!(miss.status, CheckStatus::Passed);
+    }
+
+    #[test]
+    fn invalid_rule_reports_its_line() {
+        let text = r#"{
+  "rules": [
+    {"id": "OK-1", "severity": "low", "pattern": "foo", "message": "ok"},
+    {"id": "BAD-1", "severity": "low", "pattern": "(unclosed", "message": "bad"}
+  ]
+}"#;
+        let err = parse_custom_rules(text, true, "rules.json").unwrap_err();
+        assert_eq!(err.line, Some(4));
+        assert!(err.to_string().starts_with("rules.json:4:"));
+    }
+
+    #[test]
+    fn rejects_unknown_severity_and_builtin_ids() {
+        let text = "[[rules]]\nid = \"X-1\"\nseverity = \"severe\"\npattern = \"x\"\nmessage = \"m\"\n";
+        let err = parse_custom_rules(text, false, "r.toml").unwrap_err();
+        assert_eq!(err.line, Some(2));
+
+        let text = "[[rules]]\nid = \"IV-001\"\nseverity = \"low\"\npattern = \"x\"\nmessage = \"m\"\n";
+        assert!(parse_custom_rules(text, false, "r.toml").is_err());
+    }
+
+    #[test]
+    fn dynamic_rules_are_compiled_once_and_detected() {
+        let record = |id: &str, pattern: &str| DetectorRuleRecord {
+            id: id.to_string(),
+            severity: "high".to_string(),
+            pattern: pattern.to_string(),
+            message: "dynamic rule".to_string(),
+            created_at: Utc::now(),
+        };
+        let records = vec![
+            record("DYN-TEST-001", r"forbidden_dynamic_call\("),
+            record("DYN-TEST-002", "(unclosed"),
+        ];
+        // A private set, so tests reading the global rules never see these
+        let rules = DynamicRuleSet::default();
+        assert_eq!(rules.install(&records), 1);
+        let first = rules.get("DYN-TEST-001").unwrap();
+        rules.install(&records);
+        assert!(Arc::ptr_eq(&first, &rules.get("DYN-TEST-001").unwrap()));
+        rules.install(&[record("DYN-TEST-001", r"other_dynamic_call\(")]);
+        assert!(!Arc::ptr_eq(&first, &rules.get("DYN-TEST-001").unwrap()));
+        rules.install(&records);
+        let rule = rules.get("DYN-TEST-001").unwrap();
+        assert_eq!(rule.item.id, "DYN-TEST-001");
+
+        let hit = detect_custom(&["    forbidden_dynamic_call(1);"], &rule);
+        assert_eq!(hit.status, CheckStatus::Failed);
+
+        rules.install(&[]);
+        assert!(rules.get("DYN-TEST-001").is_none());
+        assert!(rules.all().is_empty());
+        // Scans still holding a removed rule keep it alive until they finish
+        assert_eq!(rule.id, "DYN-TEST-001");
+        assert!(dynamic_rule("DYN-TEST-001").is_none());
+    }
+
+    #[test]
+    fn dynamic_rule_validation_reports_the_regex_error() {
+        let err = validate_dynamic_rule("DYN-1", "high", "(unclosed", "m").unwrap_err();
+        assert!(err.contains("invalid pattern") && err.contains("unclosed"));
+        assert!(validate_dynamic_rule("IV-001", "high", "x", "m").is_err());
+        assert!(validate_dynamic_rule("DYN-1", "severe", "x", "m").is_err());
+        assert!(validate_dynamic_rule("DYN-1", "low", "x", " ").is_err());
+        let (severity, _) = validate_dynamic_rule("DYN-1", "Critical", "x", "m").unwrap();
+        assert_eq!(severity, Severity::Critical);
+    }
+
+    #[test]
+    fn toml_syntax_errors_carry_a_line() {
+        let text = "[[rules]]\nid = \"X-1\"\nseverity = \n";
+        let err = parse_custom_rules(text, false, "r.toml").unwrap_err();
+        assert!(err.line.is_some());
+    }
 }
@@ -0,0 +1,183 @@
+// downloads.rs
+// Per-version download counters.
+//
+// Downloads are counted in memory and flushed to `contract_versions` in one
+// batched UPDATE per interval, so serving a WASM file never waits on a write.
+// Each flush adds its deltas (`download_count + n`), which keeps concurrent
+// flushes and API replicas from overwriting one another. The same deltas go
+// into today's `analytics_daily_aggregates` row for trending.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+pub const FLUSH_INTERVAL_ENV: &str = "DOWNLOAD_FLUSH_INTERVAL_SECS";
+pub const DEFAULT_FLUSH_INTERVAL_SECS: u64 = 10;
+
+/// Downloads seen since the last flush, keyed by version id
+#[derive(Debug, Default)]
+pub struct DownloadCounter {
+    pending: Mutex<HashMap<Uuid, u64>>,
+}
+
+impl DownloadCounter {
+    pub fn record(&self, version_id: Uuid) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        *pending.entry(version_id).or_insert(0) += 1;
+    }
+
+    /// Take every pending count, leaving the counter empty
+    pub fn drain(&self) -> HashMap<Uuid, u64> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Put counts back after a failed flush so no download is lost
+    pub fn restore(&self, counts: HashMap<Uuid, u64>) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        for (version_id, n) in counts {
+            *pending.entry(version_id).or_insert(0) += n;
+        }
+    }
+
+    /// Write pending counts to the database. Returns the number of
+    /// downloads flushed.
+    pub async fn flush(&self, pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let counts = self.drain();
+        if counts.is_empty() {
+            return Ok(0);
+        }
+        let (ids, deltas): (Vec<Uuid>, Vec<i64>) =
+            counts.iter().map(|(id, n)| (*id, *n as i64)).unzip();
+
+        match write_counts(pool, &ids, &deltas).await {
+            Ok(_) => Ok(deltas.iter().sum::<i64>() as u64),
+            Err(err) => {
+                self.restore(counts);
+                Err(err)
+            }
+        }
+    }
+}
+
+async fn write_counts(pool: &PgPool, ids: &[Uuid], deltas: &[i64]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE contract_versions v
+         SET download_count = v.download_count + d.delta
+         FROM UNNEST($1::uuid[], $2::bigint[]) AS d(id, delta)
+         WHERE v.id = d.id",
+    )
+    .bind(ids)
+    .bind(deltas)
+    .execute(&mut *tx)
+    .await?;
+    sqlx::query(
+        "INSERT INTO analytics_daily_aggregates (contract_id, date, download_count)
+         SELECT v.contract_id, CURRENT_DATE, SUM(d.delta)::int
+         FROM UNNEST($1::uuid[], $2::bigint[]) AS d(id, delta)
+         JOIN contract_versions v ON v.id = d.id
+         GROUP BY v.contract_id
+         ON CONFLICT (contract_id, date) DO UPDATE
+         SET download_count = analytics_daily_aggregates.download_count + EXCLUDED.download_count",
+    )
+    .bind(ids)
+    .bind(deltas)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await
+}
+
+/// Read `DOWNLOAD_FLUSH_INTERVAL_SECS`, defaulting to 10 seconds
+pub fn flush_interval_from_env() -> Result<Duration, String> {
+    match std::env::var(FLUSH_INTERVAL_ENV) {
+        Ok(raw) => match raw.trim().parse::<u64>() {
+            Ok(secs) if secs > 0 => Ok(Duration::from_secs(secs)),
+            _ => Err(format!(
+                "{} must be a positive number of seconds (got '{}')",
+                FLUSH_INTERVAL_ENV, raw
+            )),
+        },
+        Err(_) => Ok(Duration::from_secs(DEFAULT_FLUSH_INTERVAL_SECS)),
+    }
+}
+
+/// Spawn the batched writer. On shutdown it flushes once more so counts
+/// recorded during the grace period still land.
+pub fn spawn_flush_task(
+    pool: PgPool,
+    counter: Arc<DownloadCounter>,
+    interval: Duration,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            let stopping = tokio::select! {
+                _ = ticker.tick() => false,
+                _ = shutdown.cancelled() => true,
+            };
+            if let Err(err) = counter.flush(&pool).await {
+                tracing::warn!(error = ?err, "downloads: flush failed; will retry");
+            }
+            if stopping {
+                tracing::info!("downloads: stopped");
+                return;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn concurrent_downloads_are_all_counted() {
+        const N: u64 = 2_000;
+        let counter = Arc::new(DownloadCounter::default());
+        let version = Uuid::new_v4();
+
+        // A flusher drains while downloads are still arriving
+        let flushed = {
+            let counter = counter.clone();
+            tokio::spawn(async move {
+                let mut total = 0;
+                for _ in 0..50 {
+                    total += counter.drain().values().sum::<u64>();
+                    tokio::task::yield_now().await;
+                }
+                total
+            })
+        };
+
+        let downloads: Vec<_> = (0..N)
+            .map(|_| {
+                let counter = counter.clone();
+                tokio::spawn(async move { counter.record(version) })
+            })
+            .collect();
+        for download in downloads {
+            download.await.unwrap();
+        }
+
+        let total = flushed.await.unwrap() + counter.drain().values().sum::<u64>();
+        assert_eq!(total, N);
+    }
+
+    #[test]
+    fn restore_merges_with_new_downloads() {
+        let counter = DownloadCounter::default();
+        let version = Uuid::new_v4();
+        counter.record(version);
+        counter.record(version);
+        let taken = counter.drain();
+        counter.record(version);
+        counter.restore(taken);
+        assert_eq!(counter.drain().get(&version), Some(&3));
+    }
+}
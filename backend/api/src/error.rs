@@ -1,81 +1,397 @@
 use axum::{
-    http::{header, HeaderValue, StatusCode},
+    http::{HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
-use chrono::{SecondsFormat, Utc};
 use serde::Serialize;
-use uuid::Uuid;
+
+use crate::observability::REQUEST_ID_HEADER;
+
+/// Declares [`ErrorCode`] from one table of `Variant => "stable.code"` rows,
+/// so adding an error means adding a row, and with it choosing a code.
+macro_rules! error_codes {
+    ($($variant:ident => $code:literal,)+) => {
+        /// Every error the API can return, each with a stable, dotted `code`
+        /// that clients can match on regardless of HTTP status or message.
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub enum ErrorCode {
+            $($variant,)+
+        }
+
+        impl ErrorCode {
+            pub const ALL: &'static [ErrorCode] = &[$(ErrorCode::$variant,)+];
+
+            /// The stable code serialized in the error envelope
+            pub fn as_str(&self) -> &'static str {
+                match self {
+                    $(ErrorCode::$variant => $code,)+
+                }
+            }
+        }
+    };
+}
+
+error_codes! {
+    // Generic
+    InternalServerError => "internal",
+    DatabaseError => "internal.database",
+    InvalidRequest => "request.invalid_body",
+    InvalidQuery => "request.invalid_query",
+    ValidationError => "request.validation_failed",
+    InvalidPagination => "request.invalid_pagination",
+    InvalidCursor => "request.invalid_cursor",
+    UnsupportedCursorSort => "request.unsupported_cursor_sort",
+    InvalidSortField => "request.invalid_sort_field",
+    InvalidRange => "request.invalid_range",
+    RangeTooLarge => "request.range_too_large",
+    RouteNotFound => "route.not_found",
+    RateLimitExceeded => "rate_limit.exceeded",
+
+    // Auth
+    Unauthorized => "auth.unauthorized",
+    Forbidden => "auth.forbidden",
+
+    // Contracts and versions
+    ContractNotFound => "contract.not_found",
+    InvalidContractId => "contract.invalid_id",
+    InvalidContractAddress => "contract.invalid_address",
+    MissingContractId => "contract.missing_id",
+    MissingWasmHash => "contract.missing_wasm_hash",
+    ContractNotDeployed => "contract.not_deployed",
+    AmbiguousNetwork => "contract.ambiguous_network",
+    DuplicateVersion => "contract.duplicate_version",
+    InvalidSemver => "version.invalid_semver",
+    VersionNotFound => "version.not_found",
+    NoMatchingVersion => "version.no_match",
+    InvalidVersionRange => "version.invalid_range",
+    InvalidSuccessor => "version.invalid_successor",
+    SignatureInvalid => "version.signature_invalid",
+    AbiNotFound => "abi.not_found",
+    AbiNotParsed => "abi.not_parsed",
+    ArtifactNotFound => "artifact.not_found",
+    InvalidArtifact => "artifact.invalid",
+    ArtifactTooLarge => "artifact.too_large",
+    RangeNotSatisfiable => "artifact.range_not_satisfiable",
+    InvalidSbomFormat => "sbom.invalid_format",
+    DependencyCycle => "dependency.cycle",
+    NoSourceCode => "contract.no_source_code",
+    InvalidMetadata => "contract.invalid_metadata",
+    ReadmeNotFound => "readme.not_found",
+    InvalidRenderFormat => "readme.invalid_render",
+    InvalidChangelogFormat => "changelog.invalid_format",
+    InvalidBadgeKind => "badge.invalid_kind",
+    InvalidRankMode => "search.invalid_rank",
+    InvalidFunctionSignature => "search.invalid_signature",
+    ShareTokenNotFound => "share_token.not_found",
+    InvalidShareTokenExpiry => "share_token.invalid_expiry",
+    InvalidShareTokenLabel => "share_token.invalid_label",
+    BuildInfoMissing => "build.info_missing",
+    BuildServiceUnavailable => "build.service_unavailable",
+    BuildVerificationInProgress => "build.verification_in_progress",
+
+    // Publishers
+    PublisherNotFound => "publisher.not_found",
+    InvalidPublisherId => "publisher.invalid_id",
+    UserAddressRequired => "publisher.address_required",
+    PublisherDeactivated => "publisher.deactivated",
+
+    // Organizations
+    OrganizationNotFound => "organization.not_found",
+    InvalidOrganizationName => "organization.invalid_name",
+    OrganizationOwnerRequired => "organization.owner_required",
+    DuplicateOrganization => "organization.duplicate",
+    OrganizationMemberNotFound => "organization.member_not_found",
+    LastOrganizationOwner => "organization.last_owner",
+
+    // Ownership transfers
+    TransferNotFound => "transfer.not_found",
+    TransferExpired => "transfer.expired",
+    InvalidTransferTarget => "transfer.invalid_target",
+
+    // Analytics and scoring
+    InvalidMetric => "analytics.invalid_metric",
+    InvalidInterval => "analytics.invalid_interval",
+    InvalidWindow => "analytics.invalid_window",
+    InvalidGranularity => "score.invalid_granularity",
+    InvalidScoringWeights => "score.invalid_weights",
+
+    // Audits
+    AuditNotFound => "audit.not_found",
+    AuditIncomplete => "audit.incomplete",
+    CheckNotFound => "audit.check_not_found",
+    InvalidCheckId => "audit.invalid_check_id",
+
+    // Scans
+    ScanJobNotFound => "scan.job_not_found",
+    ScanNotComplete => "scan.not_complete",
+    ScanRequired => "scan.required",
+    InvalidFailOn => "scan.invalid_fail_on",
+    InvalidScanBatch => "scan.invalid_batch",
+    ScanBatchNotFound => "scan.batch_not_found",
+    InvalidCallbackUrl => "scan.invalid_callback_url",
+    InvalidCallbackSecret => "scan.invalid_callback_secret",
+    InvalidFingerprint => "scan.invalid_fingerprint",
+    SuppressionReasonRequired => "scan.suppression_reason_required",
+    SuppressionNotFound => "scan.suppression_not_found",
+    InvalidAlertId => "alert.invalid_id",
+    AlertNotFound => "alert.not_found",
+
+    // Benchmarks
+    BenchmarkNotFound => "benchmark.not_found",
+    BenchmarksNotFound => "benchmark.none_for_version",
+    BenchmarkNotCompleted => "benchmark.not_completed",
+    InvalidThreshold => "benchmark.invalid_threshold",
+    BaselineNotFound => "benchmark.no_baseline",
+
+    // Templates
+    TemplateNotFound => "template.not_found",
+    TemplateVersionNotFound => "template.version_not_found",
+    DuplicateTemplateVersion => "template.duplicate_version",
+    EmptyTemplateVersion => "template.empty_version",
+    InvalidTemplatePath => "template.invalid_path",
+    InvalidTemplateParameters => "template.invalid_parameters",
+    InvalidVersion => "template.invalid_version",
+    DuplicateTemplateSlug => "template.duplicate_slug",
+    InvalidTemplateSlug => "template.invalid_slug",
+    InvalidStatsWindow => "template.invalid_stats_window",
+
+    // Deployments and health
+    InvalidDeploymentId => "deployment.invalid_id",
+    InvalidDeploymentStatus => "deployment.invalid_status",
+    NoGreenDeployment => "deployment.no_green",
+    NoDeploymentToRollback => "deployment.nothing_to_roll_back",
+    InsufficientHealthChecks => "deployment.insufficient_health_checks",
+    HealthNotFound => "health.not_found",
+    RpcNotConfigured => "rpc.not_configured",
+    RpcUnavailable => "rpc.unavailable",
+
+    // Migrations, config and experiments
+    MigrationNotFound => "migration.not_found",
+    SnapshotNotFound => "snapshot.not_found",
+    HistoryEntryNotFound => "history.entry_not_found",
+    RevertIncompatible => "history.revert_incompatible",
+    HistoryContractMismatch => "history.contract_mismatch",
+    ConfigNotFound => "config.not_found",
+    MissingCreatedBy => "config.missing_created_by",
+    InvalidMetadataSchema => "config.invalid_metadata_schema",
+    MetadataSchemaNotFound => "config.metadata_schema_not_found",
+    InvalidDetectorRule => "config.invalid_detector_rule",
+    DetectorRuleExists => "config.detector_rule_exists",
+    DetectorRuleNotFound => "config.detector_rule_not_found",
+    InvalidAdvisory => "config.invalid_advisory",
+    AdvisoryExists => "config.advisory_exists",
+    AdvisoryNotFound => "config.advisory_not_found",
+    InvalidTestId => "experiment.invalid_test_id",
+    TestNotFound => "experiment.not_found",
+    InvalidSplit => "experiment.invalid_split",
+    NoWinner => "experiment.no_winner",
+    MissingRegions => "residency.missing_regions",
+    InvalidExportFormat => "export.invalid_format",
+    InvalidImport => "import.invalid_body",
+    InvalidLogLevel => "admin.invalid_log_level",
+
+    // Webhooks
+    InvalidWebhookUrl => "webhook.invalid_url",
+    InvalidWebhookSecret => "webhook.invalid_secret",
+
+    // Multisig
+    PolicyNotFound => "multisig.policy_not_found",
+    PolicyInactive => "multisig.policy_inactive",
+    InvalidSigners => "multisig.invalid_signers",
+    ThresholdExceedsSigners => "multisig.threshold_exceeds_signers",
+    UnauthorizedSigner => "multisig.unauthorized_signer",
+    MissingProposer => "multisig.missing_proposer",
+    ProposalNotFound => "multisig.proposal_not_found",
+    ProposalNotPending => "multisig.proposal_not_pending",
+    ProposalNotApproved => "multisig.proposal_not_approved",
+    ProposalExpired => "multisig.proposal_expired",
+    AlreadySigned => "multisig.already_signed",
+}
+
+impl Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
 
 #[derive(Debug)]
 pub struct ApiError {
     status: StatusCode,
-    error: String,
+    code: ErrorCode,
     message: String,
+    details: Option<serde_json::Value>,
 }
 
+/// The error envelope every failure serializes to
 #[derive(Debug, Serialize)]
-struct ErrorResponse {
-    error: String,
-    message: String,
-    code: u16,
-    timestamp: String,
-    correlation_id: String,
+pub struct ErrorResponse {
+    pub code: ErrorCode,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl ErrorResponse {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            details: None,
+            request_id: crate::observability::current_request_id(),
+        }
+    }
 }
 
 impl ApiError {
-    pub fn new(status: StatusCode, error: impl Into<String>, message: impl Into<String>) -> Self {
+    pub fn new(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> Self {
         Self {
             status,
-            error: error.into(),
+            code,
             message: message.into(),
+            details: None,
         }
     }
 
-    pub fn bad_request(error: impl Into<String>, message: impl Into<String>) -> Self {
-        Self::new(StatusCode::BAD_REQUEST, error, message)
+    /// Attach structured context that is returned alongside the message.
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    pub fn bad_request(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, code, message)
     }
 
-    pub fn not_found(error: impl Into<String>, message: impl Into<String>) -> Self {
-        Self::new(StatusCode::NOT_FOUND, error, message)
+    pub fn not_found(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, code, message)
     }
 
     pub fn internal(message: impl Into<String>) -> Self {
         Self::new(
             StatusCode::INTERNAL_SERVER_ERROR,
-            "InternalServerError",
+            ErrorCode::InternalServerError,
             message,
         )
     }
 
-    pub fn unprocessable(error: impl Into<String>, message: impl Into<String>) -> Self {
-        Self::new(StatusCode::UNPROCESSABLE_ENTITY, error, message)
+    pub fn unprocessable(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::UNPROCESSABLE_ENTITY, code, message)
+    }
+
+    pub fn bad_gateway(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_GATEWAY, code, message)
     }
 
     pub fn db_error(message: impl Into<String>) -> Self {
-        Self::new(StatusCode::INTERNAL_SERVER_ERROR, "DatabaseError", message)
+        Self::new(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::DatabaseError,
+            message,
+        )
+    }
+
+    pub fn code(&self) -> ErrorCode {
+        self.code
+    }
+
+    pub fn status(&self) -> StatusCode {
+        self.status
     }
 }
 
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
-        let correlation_id = Uuid::new_v4().to_string();
-        let payload = ErrorResponse {
-            error: self.error,
-            message: self.message,
-            code: self.status.as_u16(),
-            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
-            correlation_id: correlation_id.clone(),
-        };
+        let mut payload = ErrorResponse::new(self.code, self.message);
+        payload.details = self.details;
+        let request_id = payload.request_id.clone();
 
+        // Same header the request id middleware echoes, so it always agrees
+        // with the `request_id` in the body
         let mut response = (self.status, Json(payload)).into_response();
-        if let Ok(value) = HeaderValue::from_str(&correlation_id) {
-            response
-                .headers_mut()
-                .insert(header::HeaderName::from_static("x-correlation-id"), value);
+        if let Some(value) = request_id.and_then(|id| HeaderValue::from_str(&id).ok()) {
+            response.headers_mut().insert(REQUEST_ID_HEADER, value);
         }
         response
     }
 }
 
 pub type ApiResult<T> = std::result::Result<T, ApiError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[test]
+    fn codes_are_unique_and_dotted() {
+        let mut seen = HashSet::new();
+        for code in ErrorCode::ALL {
+            let s = code.as_str();
+            assert!(seen.insert(s), "duplicate error code {}", s);
+            assert!(
+                s.bytes().all(|b| b.is_ascii_lowercase() || b == b'.' || b == b'_'),
+                "error code {} must be lowercase dotted",
+                s
+            );
+        }
+    }
+
+    #[test]
+    fn envelope_has_code_message_and_optional_details() {
+        let mut body = ErrorResponse::new(ErrorCode::DuplicateVersion, "version 1.0.0 exists");
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["code"], "contract.duplicate_version");
+        assert_eq!(json["message"], "version 1.0.0 exists");
+        assert!(json.get("details").is_none());
+
+        body.details = Some(serde_json::json!({ "version": "1.0.0" }));
+        let json = serde_json::to_value(&body).unwrap();
+        assert_eq!(json["details"]["version"], "1.0.0");
+    }
+
+    #[test]
+    fn status_is_independent_of_code() {
+        let err = ApiError::new(
+            StatusCode::CONFLICT,
+            ErrorCode::ScanNotComplete,
+            "still running",
+        );
+        assert_eq!(err.status(), StatusCode::CONFLICT);
+        assert_eq!(err.code(), ErrorCode::ScanNotComplete);
+    }
+
+    #[tokio::test]
+    async fn request_id_header_matches_the_body() {
+        use axum::{body::Body, http::Request, routing::get, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route(
+                "/missing",
+                get(|| async {
+                    ApiError::not_found(ErrorCode::ContractNotFound, "no such contract")
+                }),
+            )
+            .layer(axum::middleware::from_fn(
+                crate::observability::request_id_middleware,
+            ));
+        let response = app
+            .oneshot(
+                Request::builder()
+                    .uri("/missing")
+                    .header(REQUEST_ID_HEADER, "req-42")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "req-42");
+        assert!(response.headers().get("x-correlation-id").is_none());
+        let json = crate::state::json_body(response).await;
+        assert_eq!(json["request_id"], "req-42");
+        assert_eq!(json["code"], "contract.not_found");
+    }
+}
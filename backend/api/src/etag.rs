@@ -0,0 +1,108 @@
+// api/src/etag.rs
+// Strong ETags and `If-None-Match` handling for cacheable GET responses.
+
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+/// Strong validator over the exact response bytes, so any change to a field
+/// in the body changes the tag
+pub fn strong_etag(body: &[u8]) -> String {
+    let digest = Sha256::digest(body);
+    format!("\"{}\"", hex::encode(&digest[..16]))
+}
+
+/// RFC 9110 §13.1.2: `If-None-Match` uses weak comparison, and `*` matches
+/// any current representation
+pub fn if_none_match(headers: &HeaderMap, etag: &str) -> bool {
+    let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+    let current = opaque(etag);
+    headers
+        .get_all(header::IF_NONE_MATCH)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .any(|tag| tag.trim() == "*" || opaque(tag) == current)
+}
+
+/// Serialize `value` as JSON with an ETag, or answer 304 with no body when
+/// the client already holds this representation
+pub fn json_with_etag<T: Serialize>(request_headers: &HeaderMap, value: &T) -> Response {
+    let body = match serde_json::to_vec(value) {
+        Ok(body) => body,
+        Err(err) => {
+            return crate::error::ApiError::internal(format!("serialize response: {}", err))
+                .into_response()
+        }
+    };
+    let etag = strong_etag(&body);
+    let etag_value = HeaderValue::from_str(&etag).expect("hex etag is a valid header value");
+    // Bodies can differ per caller (e.g. `starred_by_me`)
+    let vary = HeaderValue::from_static("Authorization");
+
+    if if_none_match(request_headers, &etag) {
+        return (
+            StatusCode::NOT_MODIFIED,
+            [(header::ETAG, etag_value), (header::VARY, vary)],
+        )
+            .into_response();
+    }
+
+    (
+        StatusCode::OK,
+        [
+            (header::CONTENT_TYPE, HeaderValue::from_static("application/json")),
+            (header::ETAG, etag_value),
+            (header::VARY, vary),
+        ],
+        Body::from(body),
+    )
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(if_none_match: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::IF_NONE_MATCH, HeaderValue::from_str(if_none_match).unwrap());
+        headers
+    }
+
+    #[test]
+    fn etag_tracks_body_changes() {
+        let a = strong_etag(br#"{"star_count":1}"#);
+        let b = strong_etag(br#"{"star_count":2}"#);
+        assert_ne!(a, b);
+        assert_eq!(a, strong_etag(br#"{"star_count":1}"#));
+        assert!(a.starts_with('"') && a.ends_with('"'));
+    }
+
+    #[test]
+    fn matches_lists_weak_tags_and_wildcard() {
+        let etag = strong_etag(b"body");
+        assert!(if_none_match(&headers(&etag), &etag));
+        assert!(if_none_match(&headers(&format!("\"other\", W/{}", etag)), &etag));
+        assert!(if_none_match(&headers("*"), &etag));
+        assert!(!if_none_match(&headers("\"other\""), &etag));
+        assert!(!if_none_match(&HeaderMap::new(), &etag));
+    }
+
+    #[test]
+    fn not_modified_has_no_body() {
+        let value = serde_json::json!({ "id": 1 });
+        let first = json_with_etag(&HeaderMap::new(), &value);
+        assert_eq!(first.status(), StatusCode::OK);
+        let etag = first.headers()[header::ETAG].to_str().unwrap().to_string();
+
+        let second = json_with_etag(&headers(&etag), &value);
+        assert_eq!(second.status(), StatusCode::NOT_MODIFIED);
+        assert_eq!(second.headers()[header::ETAG], etag.as_str());
+        assert!(second.headers().get(header::CONTENT_TYPE).is_none());
+    }
+}
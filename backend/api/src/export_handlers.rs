@@ -0,0 +1,226 @@
+//! Full catalog export for backups and mirrors.
+//!
+//! `GET /api/export` streams one JSON object per contract, with its versions
+//! nested, straight from a database cursor so the catalog is never held in
+//! memory. Private contracts are included with their `visibility`, which the
+//! import restores. `?since=` limits the dump to contracts changed after a point;
+//! each response carries `X-Export-Started-At`, which a client passes as the
+//! next `since` to catch up without gaps.
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension,
+};
+use chrono::{DateTime, SecondsFormat, Utc};
+use futures::{channel::mpsc, SinkExt, Stream, TryStreamExt};
+use serde::Deserialize;
+use sqlx::PgPool;
+
+use crate::{
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    state::AppState,
+};
+
+pub const NDJSON_CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Timestamp the export's snapshot was taken at; the `since` for the next run
+pub const EXPORT_STARTED_AT: HeaderName = HeaderName::from_static("x-export-started-at");
+
+/// Lines buffered between the database cursor and the response body
+const EXPORT_BUFFER_LINES: usize = 64;
+
+type ExportLine = std::io::Result<Vec<u8>>;
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    /// Only `ndjson` is supported, and is the default
+    pub format: Option<String>,
+    /// Only contracts created or changed after this instant
+    pub since: Option<DateTime<Utc>>,
+}
+
+pub fn check_export_format(format: Option<&str>) -> ApiResult<()> {
+    match format {
+        None => Ok(()),
+        Some(format) if format.eq_ignore_ascii_case("ndjson") => Ok(()),
+        Some(format) => Err(ApiError::bad_request(
+            ErrorCode::InvalidExportFormat,
+            format!("Unsupported export format '{}'; use ndjson", format),
+        )),
+    }
+}
+
+/// One record of the dump, terminated by a newline
+pub fn ndjson_line(record: &serde_json::Value) -> Vec<u8> {
+    // serde_json escapes newlines inside strings, so a record is one line
+    let mut line = record.to_string().into_bytes();
+    line.push(b'\n');
+    line
+}
+
+/// A contract changes with its own row, or when a version is published,
+/// deprecated or yanked
+const EXPORT_QUERY: &str = "
+    SELECT to_jsonb(c) || jsonb_build_object('versions', COALESCE(
+               (SELECT jsonb_agg(to_jsonb(v) ORDER BY v.created_at)
+                FROM contract_versions v WHERE v.contract_id = c.id),
+               '[]'::jsonb))
+    FROM contracts c
+    WHERE $1::timestamptz IS NULL
+       OR c.created_at > $1
+       OR c.updated_at > $1
+       OR EXISTS (
+           SELECT 1 FROM contract_versions v
+           WHERE v.contract_id = c.id
+             AND (v.created_at > $1 OR v.deprecated_at > $1 OR v.yanked_at > $1))
+    ORDER BY c.created_at, c.id";
+
+/// Export the registry catalog (admin only)
+#[utoipa::path(
+    get,
+    path = "/api/export",
+    tag = "admin",
+    params(
+        ("format" = Option<String>, Query, description = "Output format; only `ndjson`"),
+        ("since" = Option<String>, Query, description = "RFC 3339 timestamp; only contracts changed after it"),
+    ),
+    responses(
+        (status = 200, description = "One contract with its versions per line", content_type = "application/x-ndjson"),
+        (status = 400, description = "Unsupported format"),
+        (status = 403, description = "Caller is not an admin"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn export_catalog(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Query(params): Query<ExportParams>,
+) -> ApiResult<Response> {
+    caller.require_admin()?;
+    check_export_format(params.format.as_deref())?;
+
+    // Taken before the cursor opens, so anything changed while streaming is
+    // picked up again by the next incremental export
+    let started_at = Utc::now();
+
+    let (lines, body) = mpsc::channel(EXPORT_BUFFER_LINES);
+    let pool = state.db.clone();
+    let since = params.since;
+    tokio::spawn(async move { write_catalog(&pool, since, lines).await });
+
+    let mut response = Body::from_stream(body).into_response();
+    let headers = response.headers_mut();
+    headers.insert(
+        header::CONTENT_TYPE,
+        HeaderValue::from_static(NDJSON_CONTENT_TYPE),
+    );
+    if let Ok(value) =
+        HeaderValue::from_str(&started_at.to_rfc3339_opts(SecondsFormat::Micros, true))
+    {
+        headers.insert(EXPORT_STARTED_AT, value);
+    }
+    *response.status_mut() = StatusCode::OK;
+    Ok(response)
+}
+
+async fn write_catalog(
+    pool: &PgPool,
+    since: Option<DateTime<Utc>>,
+    lines: mpsc::Sender<ExportLine>,
+) {
+    let records = sqlx::query_scalar::<_, serde_json::Value>(EXPORT_QUERY)
+        .bind(since)
+        .fetch(pool);
+    if let Some(written) = forward_records(records, lines).await {
+        tracing::info!(contracts = written, since = ?since, "catalog export finished");
+    }
+}
+
+/// Send each record as a line, returning how many were sent. A database error
+/// is sent on as an error, which makes the server abort the response instead
+/// of ending it cleanly, so a client can't mistake a partial dump for a whole one.
+async fn forward_records<S>(mut records: S, mut lines: mpsc::Sender<ExportLine>) -> Option<u64>
+where
+    S: Stream<Item = Result<serde_json::Value, sqlx::Error>> + Unpin,
+{
+    let mut written = 0u64;
+    loop {
+        let line = match records.try_next().await {
+            Ok(Some(record)) => ndjson_line(&record),
+            Ok(None) => return Some(written),
+            Err(err) => {
+                tracing::error!(error = %err, written, "catalog export failed mid-stream");
+                let _ = lines.send(Err(std::io::Error::other(err))).await;
+                return None;
+            }
+        };
+        if lines.send(Ok(line)).await.is_err() {
+            // The client went away
+            return None;
+        }
+        written += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_ndjson_is_supported() {
+        assert!(check_export_format(None).is_ok());
+        assert!(check_export_format(Some("NDJSON")).is_ok());
+
+        let err = check_export_format(Some("csv")).unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(err.code().as_str(), "export.invalid_format");
+    }
+
+    #[test]
+    fn each_record_is_a_single_line() {
+        let record = serde_json::json!({
+            "name": "token",
+            "description": "line one\nline two",
+            "versions": [{ "version": "1.0.0" }],
+        });
+        let line = ndjson_line(&record);
+        assert_eq!(line.last(), Some(&b'\n'));
+        assert_eq!(line.iter().filter(|&&b| b == b'\n').count(), 1);
+
+        let parsed: serde_json::Value = serde_json::from_slice(&line).unwrap();
+        assert_eq!(parsed, record);
+    }
+
+    #[tokio::test]
+    async fn a_database_error_aborts_the_body() {
+        let records = futures::stream::iter(vec![
+            Ok(serde_json::json!({ "name": "token" })),
+            Err(sqlx::Error::PoolTimedOut),
+            Ok(serde_json::json!({ "name": "never sent" })),
+        ]);
+        let (lines, body) = mpsc::channel(EXPORT_BUFFER_LINES);
+        assert_eq!(forward_records(records, lines).await, None);
+
+        let body = axum::body::to_bytes(Body::from_stream(body), usize::MAX).await;
+        assert!(body.is_err());
+    }
+
+    #[tokio::test]
+    async fn a_complete_export_ends_the_body_cleanly() {
+        let records = futures::stream::iter(vec![
+            Ok(serde_json::json!({ "name": "a" })),
+            Ok(serde_json::json!({ "name": "b" })),
+        ]);
+        let (lines, body) = mpsc::channel(EXPORT_BUFFER_LINES);
+        assert_eq!(forward_records(records, lines).await, Some(2));
+
+        let body = axum::body::to_bytes(Body::from_stream(body), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"{\"name\":\"a\"}\n{\"name\":\"b\"}\n");
+    }
+}
@@ -0,0 +1,219 @@
+//! Atom 1.0 feed of recently published contracts (`GET /api/feed.atom`).
+//!
+//! The document is produced with `quick_xml`'s event writer so that text and
+//! attribute values are escaped by the library rather than by hand.
+
+use std::io::Cursor;
+
+use axum::{
+    extract::{Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use quick_xml::{
+    events::{BytesDecl, BytesEnd, BytesStart, BytesText, Event},
+    Writer,
+};
+use serde::Deserialize;
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{error::ApiError, handlers::db_internal_error, state::AppState};
+
+const FEED_ENTRY_LIMIT: i64 = 50;
+const ATOM_NAMESPACE: &str = "http://www.w3.org/2005/Atom";
+const DEFAULT_SITE_URL: &str = "http://localhost:3000";
+
+#[derive(Debug, Deserialize)]
+pub struct FeedParams {
+    pub publisher_id: Option<Uuid>,
+}
+
+/// One feed entry: a contract joined with its publisher
+#[derive(Debug, FromRow)]
+pub struct FeedEntry {
+    pub id: Uuid,
+    pub name: String,
+    pub description: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub publisher_name: String,
+}
+
+/// Base URL of the web frontend; entry links point at `<site>/contracts/<id>`
+fn site_url() -> String {
+    std::env::var("REGISTRY_SITE_URL")
+        .ok()
+        .filter(|url| !url.trim().is_empty())
+        .map(|url| url.trim_end_matches('/').to_string())
+        .unwrap_or_else(|| DEFAULT_SITE_URL.to_string())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/feed.atom",
+    tag = "contracts",
+    params(
+        ("publisher_id" = Option<Uuid>, Query, description = "Only include this publisher's contracts"),
+    ),
+    responses(
+        (status = 200, description = "Atom 1.0 feed of recent publishes"),
+    ),
+)]
+pub async fn atom_feed(
+    State(state): State<AppState>,
+    Query(params): Query<FeedParams>,
+) -> Result<Response, ApiError> {
+    let entries: Vec<FeedEntry> = sqlx::query_as(
+        "SELECT c.id, c.name, c.description, c.created_at, c.updated_at,
+                COALESCE(p.display_name, p.username, p.stellar_address) AS publisher_name
+         FROM contracts c
+         JOIN publishers p ON p.id = c.publisher_id
+         WHERE ($1::uuid IS NULL OR c.publisher_id = $1) AND c.visibility = 'public'
+         ORDER BY c.created_at DESC
+         LIMIT $2",
+    )
+    .bind(params.publisher_id)
+    .bind(FEED_ENTRY_LIMIT)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("load feed entries", err))?;
+
+    let xml = render_feed(&site_url(), params.publisher_id, &entries).map_err(|err| {
+        tracing::error!(error = %err, "failed to render atom feed");
+        ApiError::internal("Failed to render feed")
+    })?;
+
+    Ok((
+        StatusCode::OK,
+        [(header::CONTENT_TYPE, "application/atom+xml; charset=utf-8")],
+        xml,
+    )
+        .into_response())
+}
+
+/// Render the Atom document for `entries`
+pub fn render_feed(
+    site: &str,
+    publisher_id: Option<Uuid>,
+    entries: &[FeedEntry],
+) -> quick_xml::Result<String> {
+    let mut writer = Writer::new_with_indent(Cursor::new(Vec::new()), b' ', 2);
+    writer.write_event(Event::Decl(BytesDecl::new("1.0", Some("utf-8"), None)))?;
+
+    let mut feed = BytesStart::new("feed");
+    feed.push_attribute(("xmlns", ATOM_NAMESPACE));
+    writer.write_event(Event::Start(feed))?;
+
+    let feed_id = match publisher_id {
+        Some(id) => format!("{}/api/feed.atom?publisher_id={}", site, id),
+        None => format!("{}/api/feed.atom", site),
+    };
+    let title = match (publisher_id, entries.first()) {
+        (Some(_), Some(first)) => format!("Soroban Registry: {}", first.publisher_name),
+        _ => "Soroban Registry: recently published contracts".to_string(),
+    };
+    let updated = entries
+        .iter()
+        .map(|e| e.updated_at)
+        .max()
+        .unwrap_or_else(Utc::now);
+
+    text_element(&mut writer, "id", &feed_id)?;
+    text_element(&mut writer, "title", &title)?;
+    text_element(&mut writer, "updated", &updated.to_rfc3339())?;
+    link_element(&mut writer, &format!("{}/contracts", site), None)?;
+    link_element(&mut writer, &feed_id, Some("self"))?;
+
+    for entry in entries {
+        writer.write_event(Event::Start(BytesStart::new("entry")))?;
+        text_element(&mut writer, "id", &format!("urn:uuid:{}", entry.id))?;
+        text_element(&mut writer, "title", &entry.name)?;
+        link_element(&mut writer, &format!("{}/contracts/{}", site, entry.id), None)?;
+        text_element(&mut writer, "published", &entry.created_at.to_rfc3339())?;
+        text_element(&mut writer, "updated", &entry.updated_at.to_rfc3339())?;
+
+        writer.write_event(Event::Start(BytesStart::new("author")))?;
+        text_element(&mut writer, "name", &entry.publisher_name)?;
+        writer.write_event(Event::End(BytesEnd::new("author")))?;
+
+        if let Some(ref description) = entry.description {
+            text_element(&mut writer, "summary", description)?;
+        }
+        writer.write_event(Event::End(BytesEnd::new("entry")))?;
+    }
+
+    writer.write_event(Event::End(BytesEnd::new("feed")))?;
+
+    let bytes = writer.into_inner().into_inner();
+    Ok(String::from_utf8(bytes).expect("quick_xml writes utf-8"))
+}
+
+fn text_element<W: std::io::Write>(
+    writer: &mut Writer<W>,
+    name: &str,
+    text: &str,
+) -> quick_xml::Result<()> {
+    writer.write_event(Event::Start(BytesStart::new(name)))?;
+    writer.write_event(Event::Text(BytesText::new(text)))?;
+    writer.write_event(Event::End(BytesEnd::new(name)))?;
+    Ok(())
+}
+
+fn link_element<W: std::io::Write>(
+    writer: &mut Writer<W>,
+    href: &str,
+    rel: Option<&str>,
+) -> quick_xml::Result<()> {
+    let mut link = BytesStart::new("link");
+    if let Some(rel) = rel {
+        link.push_attribute(("rel", rel));
+    }
+    link.push_attribute(("href", href));
+    writer.write_event(Event::Empty(link))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(name: &str) -> FeedEntry {
+        FeedEntry {
+            id: Uuid::nil(),
+            name: name.to_string(),
+            description: Some("Swap <tokens> & more".to_string()),
+            created_at: Utc::now(),
+            updated_at: Utc::now(),
+            publisher_name: "alice".to_string(),
+        }
+    }
+
+    #[test]
+    fn escapes_names_and_descriptions() {
+        let xml = render_feed("https://example.com", None, &[entry("A & B <AMM>")]).unwrap();
+        assert!(xml.contains("<title>A &amp; B &lt;AMM&gt;</title>"));
+        assert!(xml.contains("Swap &lt;tokens&gt; &amp; more"));
+        assert!(!xml.contains("<AMM>"));
+    }
+
+    #[test]
+    fn entries_link_to_contract_pages() {
+        let xml = render_feed("https://example.com", None, &[entry("Token")]).unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains(r#"xmlns="http://www.w3.org/2005/Atom""#));
+        assert!(xml.contains(&format!(
+            r#"href="https://example.com/contracts/{}""#,
+            Uuid::nil()
+        )));
+        assert!(xml.contains("<name>alice</name>"));
+    }
+
+    #[test]
+    fn empty_feed_is_still_valid_atom() {
+        let xml = render_feed("https://example.com", None, &[]).unwrap();
+        assert!(xml.contains("<updated>"));
+        assert!(xml.trim_end().ends_with("</feed>"));
+    }
+}
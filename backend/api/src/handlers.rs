@@ -10,19 +10,37 @@ use axum::{
     },
     http::StatusCode,
     response::IntoResponse,
-    Json,
+    Extension, Json,
 };
 use shared::{
-    Contract, ContractDeployment, ContractSearchParams, ContractVersion, DeployGreenRequest,
-    DeploymentEnvironment, DeploymentStatus, DeploymentSwitch, HealthCheckRequest,
-    PaginatedResponse, PublishRequest, Publisher, SwitchDeploymentRequest, VerifyRequest,
+    Contract, ContractDeployment, ContractVersion, ContractVisibility, DeployGreenRequest,
+    DeploymentEnvironment, DeploymentStatus, DeploymentSwitch, DeprecateVersionRequest,
+    HealthCheckRequest, Network, OrganizationRole, PublishRequest, Publisher, SetVisibilityRequest,
+    SwitchDeploymentRequest, UpdatePublisherRequest, VerificationResult, VerifyRequest,
 };
+use sqlx::{Postgres, QueryBuilder};
+use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
 
 use crate::{
-    analytics,
-    error::{ApiError, ApiResult},
+    abi, admin_audit, analytics, artifacts,
+    auth::{self, Caller, ContractAccess},
+    benchmark_handlers, compare,
+    error::{ApiError, ApiResult, ErrorCode},
+    ipfs, metadata_schema, metrics,
+    models::BenchmarkWarning,
+    organization_handlers,
+    pagination::{
+        PageParams, Paginated, PaginatedContractListItems, PaginatedContractVersions,
+        PaginatedContracts,
+    },
+    readme, sbom,
+    share_tokens::{self, PresentedShareToken},
+    signing, soroban_rpc,
     state::AppState,
+    trending,
+    upload::PublishUpload,
+    webhooks,
 };
 
 pub fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
@@ -32,250 +50,1962 @@ pub fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
 
 fn map_json_rejection(err: JsonRejection) -> ApiError {
     ApiError::bad_request(
-        "InvalidRequest",
+        ErrorCode::InvalidRequest,
         format!("Invalid JSON payload: {}", err.body_text()),
     )
 }
 
 fn map_query_rejection(err: QueryRejection) -> ApiError {
     ApiError::bad_request(
-        "InvalidQuery",
+        ErrorCode::InvalidQuery,
         format!("Invalid query parameters: {}", err.body_text()),
     )
 }
 
-/// Health check ΓÇö probes DB connectivity and reports uptime.
-/// Returns 200 when everything is reachable, 503 when the database
-/// connection pool cannot satisfy a trivial query.
+/// Liveness probe: confirms the process is up and serving. Deliberately
+/// touches no dependencies so a slow database never gets the pod restarted;
+/// see [`health_ready`] for the dependency check.
 pub async fn health_check(State(state): State<AppState>) -> (StatusCode, Json<serde_json::Value>) {
-    let uptime = state.started_at.elapsed().as_secs();
-    let now = chrono::Utc::now().to_rfc3339();
+    (
+        StatusCode::OK,
+        Json(serde_json::json!({
+            "status": "ok",
+            "version": "0.1.0",
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+            "uptime_secs": state.started_at.elapsed().as_secs()
+        })),
+    )
+}
 
-    // Quick connectivity probe ΓÇö keeps the query as cheap as possible
-    // so that frequent polling from orchestrators doesn't add load.
-    let db_ok = sqlx::query_scalar::<_, i32>("SELECT 1")
-        .fetch_one(&state.db)
+/// Upper bound on each readiness probe, including the wait for a pooled
+/// connection, so probes can't queue behind request traffic
+const READY_PROBE_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+
+async fn probe_database(pool: &sqlx::PgPool) -> Result<(), String> {
+    let probe = async {
+        let mut conn = pool.acquire().await.map_err(|e| e.to_string())?;
+        sqlx::query_scalar::<_, i32>("SELECT 1")
+            .fetch_one(&mut *conn)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    };
+    tokio::time::timeout(READY_PROBE_TIMEOUT, probe)
         .await
-        .is_ok();
+        .unwrap_or_else(|_| Err("timed out".to_string()))
+}
 
-    if db_ok {
-        tracing::info!(uptime_secs = uptime, "health check passed");
+/// TCP reachability of the OTLP collector; a connect is enough to know spans
+/// have somewhere to go
+async fn probe_otlp(endpoint: &str) -> Result<(), String> {
+    let url = reqwest::Url::parse(endpoint).map_err(|e| format!("invalid endpoint: {}", e))?;
+    let host = url.host_str().ok_or("endpoint has no host")?.to_string();
+    let port = url.port_or_known_default().unwrap_or(4317);
+    match tokio::time::timeout(READY_PROBE_TIMEOUT, tokio::net::TcpStream::connect((host, port))).await {
+        Ok(Ok(_)) => Ok(()),
+        Ok(Err(err)) => Err(err.to_string()),
+        Err(_) => Err("timed out".to_string()),
+    }
+}
 
-        (
-            StatusCode::OK,
-            Json(serde_json::json!({
-                "status": "ok",
-                "version": "0.1.0",
-                "timestamp": now,
-                "uptime_secs": uptime
-            })),
-        )
-    } else {
-        tracing::warn!(
-            uptime_secs = uptime,
-            "health check degraded ΓÇö db unreachable"
-        );
+/// Readiness probe: 200 when every dependency answers, 503 naming the ones
+/// that don't. The collector is only checked when `READY_CHECK_OTLP=true`.
+pub async fn health_ready(State(state): State<AppState>) -> (StatusCode, Json<serde_json::Value>) {
+    let mut checks = serde_json::Map::new();
+    let mut failed = Vec::new();
 
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(serde_json::json!({
-                "status": "degraded",
-                "version": "0.1.0",
-                "timestamp": now,
-                "uptime_secs": uptime
-            })),
-        )
+    match probe_database(&state.db).await {
+        Ok(()) => {
+            checks.insert("database".into(), serde_json::json!({ "status": "ok" }));
+        }
+        Err(err) => {
+            tracing::warn!(error = %err, "readiness: database unreachable");
+            checks.insert(
+                "database".into(),
+                serde_json::json!({ "status": "down", "error": err }),
+            );
+            failed.push("database");
+        }
+    }
+
+    let check_otlp = std::env::var("READY_CHECK_OTLP")
+        .map(|v| v.eq_ignore_ascii_case("true") || v == "1")
+        .unwrap_or(false);
+    if check_otlp {
+        let endpoint = std::env::var("OTLP_ENDPOINT")
+            .unwrap_or_else(|_| "http://jaeger:4317".to_string());
+        match probe_otlp(&endpoint).await {
+            Ok(()) => {
+                checks.insert("otlp".into(), serde_json::json!({ "status": "ok" }));
+            }
+            Err(err) => {
+                tracing::warn!(error = %err, endpoint = %endpoint, "readiness: OTLP collector unreachable");
+                checks.insert(
+                    "otlp".into(),
+                    serde_json::json!({ "status": "down", "error": err }),
+                );
+                failed.push("otlp");
+            }
+        }
     }
+
+    let status = if failed.is_empty() {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (
+        status,
+        Json(serde_json::json!({
+            "status": if failed.is_empty() { "ready" } else { "unavailable" },
+            "failed": failed,
+            "checks": checks,
+            "timestamp": chrono::Utc::now().to_rfc3339(),
+        })),
+    )
 }
 
-/// Get registry statistics
-pub async fn get_stats(State(state): State<AppState>) -> ApiResult<Json<serde_json::Value>> {
-    let total_contracts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM contracts")
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| db_internal_error("count contracts", err))?;
+#[derive(Debug, Deserialize)]
+pub struct StatsParams {
+    /// Only count contracts on this network
+    pub network: Option<Network>,
+}
 
-    let verified_contracts: i64 =
-        sqlx::query_scalar("SELECT COUNT(*) FROM contracts WHERE is_verified = true")
+/// Contract counts on one network
+#[derive(Debug, Serialize, sqlx::FromRow)]
+struct NetworkStats {
+    network: Network,
+    total_contracts: i64,
+    verified_contracts: i64,
+    /// Publishers with at least one contract on the network
+    total_publishers: i64,
+}
+
+/// Get registry statistics, broken down by network
+///
+/// Served from `AppState::stats` for `STATS_CACHE_TTL_SECONDS`; publishes and
+/// verifications invalidate it. `?network=` narrows the counts to one network.
+pub async fn get_stats(
+    State(state): State<AppState>,
+    params: Result<Query<StatsParams>, QueryRejection>,
+) -> ApiResult<([(axum::http::HeaderName, String); 1], Json<serde_json::Value>)> {
+    let Query(params) = params.map_err(map_query_rejection)?;
+    let stats = match state.stats.get() {
+        Some(stats) => stats,
+        None => {
+            let stats = compute_stats(&state).await?;
+            state.stats.put(stats.clone());
+            stats
+        }
+    };
+    let stats = match params.network {
+        Some(network) => network_stats(&stats, &network),
+        None => stats,
+    };
+    let cache_control = format!("public, max-age={}", state.stats.ttl().as_secs());
+    Ok(([(axum::http::header::CACHE_CONTROL, cache_control)], Json(stats)))
+}
+
+/// The `by_network` entry of full stats, with zeroes for an empty network
+fn network_stats(stats: &serde_json::Value, network: &Network) -> serde_json::Value {
+    let key = serde_json::to_value(network).unwrap_or_default();
+    let entry = key
+        .as_str()
+        .and_then(|key| stats["by_network"].get(key))
+        .cloned()
+        .unwrap_or_else(|| {
+            serde_json::json!({
+                "total_contracts": 0,
+                "verified_contracts": 0,
+                "total_publishers": 0,
+            })
+        });
+    let mut entry = entry;
+    entry["network"] = key;
+    entry
+}
+
+/// Served to everyone from one cache entry, so only public contracts count
+async fn compute_stats(state: &AppState) -> ApiResult<serde_json::Value> {
+    let total_contracts: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM contracts WHERE visibility = 'public'")
             .fetch_one(&state.db)
             .await
-            .map_err(|err| db_internal_error("count verified contracts", err))?;
+            .map_err(|err| db_internal_error("count contracts", err))?;
+
+    let verified_contracts: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM contracts WHERE is_verified = true AND visibility = 'public'",
+    )
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count verified contracts", err))?;
 
     let total_publishers: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM publishers")
         .fetch_one(&state.db)
         .await
         .map_err(|err| db_internal_error("count publishers", err))?;
 
-    Ok(Json(serde_json::json!({
+    let networks: Vec<NetworkStats> = sqlx::query_as(
+        "SELECT network,
+                COUNT(*) AS total_contracts,
+                COUNT(*) FILTER (WHERE is_verified) AS verified_contracts,
+                COUNT(DISTINCT publisher_id) AS total_publishers
+         FROM contracts
+         WHERE visibility = 'public'
+         GROUP BY network",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count contracts by network", err))?;
+
+    let by_network: serde_json::Map<String, serde_json::Value> = networks
+        .into_iter()
+        .filter_map(|stats| {
+            let key = serde_json::to_value(&stats.network).ok()?.as_str()?.to_string();
+            let mut value = serde_json::to_value(&stats).ok()?;
+            value.as_object_mut()?.remove("network");
+            Some((key, value))
+        })
+        .collect();
+
+    Ok(serde_json::json!({
         "total_contracts": total_contracts,
         "verified_contracts": verified_contracts,
         "total_publishers": total_publishers,
-    })))
+        "by_network": by_network,
+    }))
 }
 
-/// List and search contracts
-pub async fn list_contracts(
-    State(state): State<AppState>,
-    params: Result<Query<ContractSearchParams>, QueryRejection>,
-) -> axum::response::Response {
-    let Query(params) = match params {
-        Ok(q) => q,
-        Err(err) => return map_query_rejection(err).into_response(),
-    };
-
-    let page = params.page.unwrap_or(1);
-    let limit = params.limit.unwrap_or(20);
+/// Default page size for `GET /api/contracts`
+const DEFAULT_CONTRACTS_LIMIT: i64 = 20;
+/// Hard upper bound for the page size of `GET /api/contracts`
+const MAX_CONTRACTS_LIMIT: i64 = 100;
+/// Lowest name similarity a `fuzzy=true` match may have. Equal to pg_trgm's
+/// default `%` threshold, so the trigram index can serve the filter.
+const FUZZY_SIMILARITY_THRESHOLD: f32 = 0.3;
+
+/// Env var setting how much the composite score counts under `rank=score`
+pub const SEARCH_SCORE_WEIGHT_ENV: &str = "SEARCH_SCORE_WEIGHT";
+pub const DEFAULT_SEARCH_SCORE_WEIGHT: f64 = 0.3;
+
+static SEARCH_SCORE_WEIGHT: once_cell::sync::Lazy<f64> = once_cell::sync::Lazy::new(|| {
+    parse_search_score_weight(std::env::var(SEARCH_SCORE_WEIGHT_ENV).ok().as_deref())
+});
+
+/// Unset, unparsable or out-of-range (0–1) values use the default
+fn parse_search_score_weight(raw: Option<&str>) -> f64 {
+    raw.and_then(|value| value.trim().parse().ok())
+        .filter(|weight: &f64| (0.0..=1.0).contains(weight))
+        .unwrap_or(DEFAULT_SEARCH_SCORE_WEIGHT)
+}
 
-    // bad input, bail early
-    if page < 1 || limit < 1 || limit > 100 {
-        return ApiError::bad_request(
-            "InvalidPagination",
-            "page must be >= 1 and limit must be between 1 and 100",
-        )
-        .into_response();
-    }
+/// `rank=score` ordering of a text match: `ts_rank` squashed into 0–1
+/// (`rank / (rank + 1)`, Postgres normalization 32) blended with the 0–100
+/// composite score. Unscored contracts count as 0. Mirrors the SQL built by
+/// [`push_score_blended_rank`].
+pub(crate) fn score_blended_rank(relevance: f64, composite: Option<f64>, weight: f64) -> f64 {
+    let relevance = relevance / (relevance + 1.0);
+    (1.0 - weight) * relevance + weight * composite.unwrap_or(0.0) / 100.0
+}
 
-    let offset = (page - 1) * limit;
+fn push_score_blended_rank(builder: &mut QueryBuilder<'_, Postgres>, tsquery: &str, weight: f64) {
+    builder
+        .push("((1 - ")
+        .push_bind(weight)
+        .push(") * ts_rank(search_vector, to_tsquery('simple', ")
+        .push_bind(tsquery.to_string())
+        .push("), 32) + ")
+        .push_bind(weight)
+        .push(
+            " * COALESCE((SELECT cs.composite FROM contract_scores cs \
+             WHERE cs.contract_id = contracts.id), 0) / 100)::real",
+        );
+}
 
-    // Build dynamic query based on filters
-    let mut query = String::from("SELECT * FROM contracts WHERE 1=1");
-    let mut count_query = String::from("SELECT COUNT(*) FROM contracts WHERE 1=1");
+/// Query parameters for `GET /api/contracts`.
+///
+/// `limit`/`offset` are clamped into range rather than rejected. The legacy
+/// `page`/`page_size` pair is still honoured when `offset` is absent.
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListContractsParams {
+    /// Full-text search over name and description, ranked by relevance
+    pub q: Option<String>,
+    /// Match `q` against names by trigram similarity instead, tolerating typos
+    pub fuzzy: Option<bool>,
+    /// `score` blends full-text relevance with the composite score; the
+    /// default `relevance` ranks by text alone
+    pub rank: Option<String>,
+    pub query: Option<String>,
+    pub network: Option<Network>,
+    pub verified_only: Option<bool>,
+    pub category: Option<String>,
+    /// Only contracts carrying this tag (case-insensitive)
+    pub tag: Option<String>,
+    /// Restrict results to a single publisher (UUID)
+    pub publisher_id: Option<String>,
+    /// One of [`SortField::ALLOWED`]; a leading `-` sorts descending
+    pub sort: Option<String>,
+    /// Opt into keyset pagination. Pass an empty value for the first page,
+    /// then the `next_cursor` of the previous response.
+    pub cursor: Option<String>,
+    #[serde(alias = "page_size")]
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+    pub page: Option<i64>,
+}
 
-    if let Some(ref q) = params.query {
-        let search_clause = format!(" AND (name ILIKE '%{}%' OR description ILIKE '%{}%')", q, q);
-        query.push_str(&search_clause);
-        count_query.push_str(&search_clause);
+impl ListContractsParams {
+    fn clamped_limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_CONTRACTS_LIMIT)
+            .clamp(1, MAX_CONTRACTS_LIMIT)
     }
 
-    if let Some(verified) = params.verified_only {
-        if verified {
-            query.push_str(" AND is_verified = true");
-            count_query.push_str(" AND is_verified = true");
+    fn clamped_offset(&self, limit: i64) -> i64 {
+        match (self.offset, self.page) {
+            (Some(offset), _) => offset.max(0),
+            (None, Some(page)) => (page.max(1) - 1).saturating_mul(limit),
+            (None, None) => 0,
         }
     }
 
-    if let Some(ref category) = params.category {
-        let category_clause = format!(" AND category = '{}'", category);
-        query.push_str(&category_clause);
-        count_query.push_str(&category_clause);
+    /// `None` means no explicit sort was requested.
+    fn sort_field(&self) -> Result<Option<SortField>, ApiError> {
+        match self.sort.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(raw) => SortField::parse(raw).map(Some),
+        }
     }
 
-    query.push_str(&format!(
-        " ORDER BY created_at DESC LIMIT {} OFFSET {}",
-        limit, offset
-    ));
+    fn search_tsquery(&self) -> Option<String> {
+        if self.fuzzy.unwrap_or(false) {
+            return None;
+        }
+        self.q.as_deref().and_then(build_prefix_tsquery)
+    }
 
-    let contracts: Vec<Contract> = match sqlx::query_as(&query).fetch_all(&state.db).await {
-        Ok(rows) => rows,
-        Err(err) => return db_internal_error("list contracts", err).into_response(),
-    };
+    /// Whether `rank=score` asks for relevance blended with the composite score
+    fn rank_by_score(&self) -> Result<bool, ApiError> {
+        match self.rank.as_deref().map(str::trim) {
+            None | Some("") | Some("relevance") => Ok(false),
+            Some("score") => Ok(true),
+            Some(other) => Err(ApiError::bad_request(
+                ErrorCode::InvalidRankMode,
+                format!("rank must be 'relevance' or 'score' (got '{}')", other),
+            )),
+        }
+    }
 
-    let total: i64 = match sqlx::query_scalar(&count_query).fetch_one(&state.db).await {
-        Ok(n) => n,
-        Err(err) => return db_internal_error("count filtered contracts", err).into_response(),
-    };
+    /// `q` when `fuzzy=true` selects trigram matching
+    fn fuzzy_term(&self) -> Option<String> {
+        if !self.fuzzy.unwrap_or(false) {
+            return None;
+        }
+        self.q
+            .as_deref()
+            .map(str::trim)
+            .filter(|q| !q.is_empty())
+            .map(str::to_string)
+    }
 
-    let paginated = PaginatedResponse::new(contracts, total, page, limit);
+    /// Parse `publisher_id`, treating an empty value as absent.
+    fn publisher_uuid(&self) -> Result<Option<Uuid>, ApiError> {
+        match self.publisher_id.as_deref().map(str::trim) {
+            None | Some("") => Ok(None),
+            Some(raw) => Uuid::parse_str(raw).map(Some).map_err(|_| {
+                ApiError::bad_request(
+                    ErrorCode::InvalidPublisherId,
+                    format!("publisher_id must be a valid UUID, got '{}'", raw),
+                )
+            }),
+        }
+    }
+}
 
-    // link headers for pagination
-    let total_pages = paginated.total_pages;
-    let mut links: Vec<String> = Vec::new();
+/// Whitelisted sort orders for `GET /api/contracts`.
+///
+/// Each variant maps to a fixed `ORDER BY` fragment so the raw `sort` value
+/// never reaches SQL. Every fragment ends with `id` as a tiebreaker to keep
+/// offset pagination stable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SortField {
+    NameAsc,
+    NameDesc,
+    CreatedAtAsc,
+    #[default]
+    CreatedAtDesc,
+    ScoreAsc,
+    ScoreDesc,
+}
 
-    if page > 1 {
-        links.push(format!(
-            "</api/contracts?page={}&limit={}>; rel=\"prev\"",
-            page - 1,
-            limit
-        ));
-    }
-    if page < total_pages {
-        links.push(format!(
-            "</api/contracts?page={}&limit={}>; rel=\"next\"",
-            page + 1,
-            limit
-        ));
+impl SortField {
+    pub const ALLOWED: &'static [&'static str] = &[
+        "name",
+        "-name",
+        "created_at",
+        "-created_at",
+        "score",
+        "-score",
+    ];
+
+    pub fn parse(raw: &str) -> Result<Self, ApiError> {
+        match raw.trim() {
+            "name" => Ok(Self::NameAsc),
+            "-name" => Ok(Self::NameDesc),
+            "created_at" => Ok(Self::CreatedAtAsc),
+            "-created_at" => Ok(Self::CreatedAtDesc),
+            "score" => Ok(Self::ScoreAsc),
+            "-score" => Ok(Self::ScoreDesc),
+            other => Err(ApiError::bad_request(
+                ErrorCode::InvalidSortField,
+                format!(
+                    "Unknown sort field '{}'. Allowed values: {}",
+                    other,
+                    Self::ALLOWED.join(", ")
+                ),
+            )),
+        }
     }
 
-    let mut response = (StatusCode::OK, Json(paginated)).into_response();
+    /// Row-value comparison that selects rows after a cursor, for the sorts
+    /// that keyset pagination supports.
+    pub fn keyset_comparator(self) -> Option<&'static str> {
+        match self {
+            Self::CreatedAtDesc => Some("<"),
+            Self::CreatedAtAsc => Some(">"),
+            _ => None,
+        }
+    }
 
-    if !links.is_empty() {
-        if let Ok(value) = axum::http::HeaderValue::from_str(&links.join(", ")) {
-            response.headers_mut().insert("link", value);
+    pub fn order_by(self) -> &'static str {
+        match self {
+            Self::NameAsc => "name ASC, id ASC",
+            Self::NameDesc => "name DESC, id DESC",
+            Self::CreatedAtAsc => "created_at ASC, id ASC",
+            Self::CreatedAtDesc => "created_at DESC, id DESC",
+            Self::ScoreAsc => "popularity_score ASC, id ASC",
+            Self::ScoreDesc => "popularity_score DESC, id DESC",
         }
     }
+}
 
-    response
+/// Opaque keyset position: the `(created_at, id)` of the last row returned.
+///
+/// Encoded as URL-safe base64 of `<created_at micros>:<uuid>`. Decoding is
+/// strict so that a tampered or truncated cursor is a 400, not a 500.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ContractCursor {
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub id: Uuid,
 }
 
-pub async fn get_contract(
-    State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> ApiResult<Json<Contract>> {
-    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
-        .bind(id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("No contract found with ID: {}", id),
-            ),
-            _ => db_internal_error("get contract by id", err),
-        })?;
+impl ContractCursor {
+    pub fn encode(&self) -> String {
+        use base64::Engine;
+        let raw = format!("{}:{}", self.created_at.timestamp_micros(), self.id);
+        base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(raw)
+    }
 
-    let active_deployment: Option<ContractDeployment> = sqlx::query_as(
-        "SELECT * FROM contract_deployments 
-         WHERE contract_id = $1 AND status = 'active'",
-    )
-    .bind(contract.id)
-    .fetch_optional(&state.db)
-    .await
-    .map_err(|err| db_internal_error("get active deployment", err))?;
+    pub fn decode(cursor: &str) -> Result<Self, ApiError> {
+        use base64::Engine;
+        let invalid = || ApiError::bad_request(ErrorCode::InvalidCursor, "cursor is malformed or has been modified");
 
-    if let Some(deployment) = active_deployment {
-        let mut contract_with_deployment = contract.clone();
-        contract_with_deployment.wasm_hash = deployment.wasm_hash;
-        Ok(Json(contract_with_deployment))
+        let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(cursor.trim())
+            .map_err(|_| invalid())?;
+        let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (micros, id) = raw.split_once(':').ok_or_else(invalid)?;
+        let micros: i64 = micros.parse().map_err(|_| invalid())?;
+        let created_at = chrono::DateTime::from_timestamp_micros(micros).ok_or_else(invalid)?;
+        let id = Uuid::parse_str(id).map_err(|_| invalid())?;
+
+        Ok(Self { created_at, id })
+    }
+}
+
+/// Turn free-form user input into a prefix-matching `to_tsquery` expression,
+/// e.g. `"Token swap"` becomes `"token:* & swap:*"`.
+///
+/// Anything other than alphanumerics and underscores is dropped so the
+/// result can never contain tsquery operators. Returns `None` when nothing
+/// searchable remains.
+fn build_prefix_tsquery(input: &str) -> Option<String> {
+    let terms: Vec<String> = input
+        .split_whitespace()
+        .map(|word| {
+            word.chars()
+                .filter(|c| c.is_alphanumeric() || *c == '_')
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|word| !word.is_empty())
+        .map(|word| format!("{}:*", word))
+        .collect();
+
+    if terms.is_empty() {
+        None
     } else {
-    Ok(Json(contract))
+        Some(terms.join(" & "))
     }
 }
 
-/// Get contract ABI
-pub async fn get_contract_abi(
-    State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> ApiResult<Json<serde_json::Value>> {
-    let abi: Option<serde_json::Value> = sqlx::query_scalar("SELECT abi FROM contracts WHERE id = $1")
-        .bind(id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|_| ApiError::not_found("ContractNotFound", format!("No contract found with ID: {}", id)))?;
+/// A contract row in a listing, with its relevance when `q` was supplied
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ContractListItem {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub contract: Contract,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<f32>,
+    /// Name similarity to `q` (0–1) in `fuzzy=true` mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[sqlx(default)]
+    pub similarity: Option<f32>,
+    pub star_count: i64,
+    /// Only present for authenticated publisher requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starred_by_me: Option<bool>,
+    /// False when the contract has no version that isn't yanked
+    pub has_installable_version: bool,
+    /// WASM downloads across all versions
+    pub download_count: i64,
+}
 
-    abi.map(Json).ok_or_else(|| ApiError::not_found("AbiNotFound", format!("No ABI available for contract: {}", id)))
+/// `GET /api/contracts/:id` body: the contract plus its star signals
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ContractDetail {
+    #[serde(flatten)]
+    pub contract: Contract,
+    pub star_count: i64,
+    /// Only present for authenticated publisher requests
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub starred_by_me: Option<bool>,
+    /// WASM downloads across all versions
+    pub download_count: i64,
 }
 
-/// Get contract version history
-pub async fn get_contract_versions(
-    State(state): State<AppState>,
-    Path(id): Path<String>,
-) -> ApiResult<Json<Vec<ContractVersion>>> {
-    let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
-        ApiError::bad_request(
-            "InvalidContractId",
-            format!("Invalid contract ID format: {}", id),
-        )
-    })?;
+/// `POST /api/contracts` body: the contract plus any benchmark regressions
+/// of the published version against the previous one
+#[derive(Debug, Serialize, ToSchema)]
+pub struct PublishResponse {
+    #[serde(flatten)]
+    pub contract: Contract,
+    #[schema(value_type = Vec<Object>)]
+    pub benchmark_warnings: Vec<BenchmarkWarning>,
+}
 
-    let versions: Vec<ContractVersion> = sqlx::query_as(
-        "SELECT * FROM contract_versions WHERE contract_id = $1 ORDER BY created_at DESC",
+/// `download_count` for the `contracts` row in scope
+fn push_download_count_column(builder: &mut QueryBuilder<'_, Postgres>) {
+    builder.push(
+        "(SELECT COALESCE(SUM(v.download_count), 0) FROM contract_versions v WHERE v.contract_id = contracts.id)::bigint AS download_count",
+    );
+}
+
+/// Select `star_count` and `starred_by_me` for rows of `contracts`
+fn push_star_columns(builder: &mut QueryBuilder<'_, Postgres>, viewer: Option<Uuid>) {
+    builder.push(
+        "(SELECT COUNT(*) FROM contract_stars s WHERE s.contract_id = contracts.id) AS star_count, ",
+    );
+    match viewer {
+        Some(publisher_id) => {
+            builder
+                .push("EXISTS(SELECT 1 FROM contract_stars s WHERE s.contract_id = contracts.id AND s.publisher_id = ")
+                .push_bind(publisher_id)
+                .push(") AS starred_by_me");
+        }
+        None => {
+            builder.push("NULL::boolean AS starred_by_me");
+        }
+    }
+}
+
+/// Append the WHERE clause shared by the list and count queries so that
+/// `total` always reflects the same filters as the returned page.
+fn push_contract_filters(
+    builder: &mut QueryBuilder<'_, Postgres>,
+    params: &ListContractsParams,
+    publisher_id: Option<Uuid>,
+    caller: Option<&Caller>,
+) {
+    builder.push(" WHERE 1=1");
+    push_visibility_filter(builder, caller);
+
+    if let Some(publisher_id) = publisher_id {
+        builder.push(" AND publisher_id = ").push_bind(publisher_id);
+    }
+
+    if let Some(tsquery) = params.search_tsquery() {
+        builder
+            .push(" AND search_vector @@ to_tsquery('simple', ")
+            .push_bind(tsquery)
+            .push(")");
+    }
+
+    if let Some(term) = params.fuzzy_term() {
+        // `%` lets idx_contracts_name_trgm narrow the rows; similarity() then
+        // holds them to our threshold whatever pg_trgm.similarity_threshold is
+        builder
+            .push(" AND name % ")
+            .push_bind(term.clone())
+            .push(" AND similarity(name, ")
+            .push_bind(term)
+            .push(") >= ")
+            .push_bind(FUZZY_SIMILARITY_THRESHOLD);
+    }
+
+    if let Some(q) = params.query.as_deref().map(str::trim).filter(|q| !q.is_empty()) {
+        let pattern = format!("%{}%", q);
+        builder
+            .push(" AND (name ILIKE ")
+            .push_bind(pattern.clone())
+            .push(" OR description ILIKE ")
+            .push_bind(pattern)
+            .push(")");
+    }
+
+    if let Some(ref network) = params.network {
+        builder.push(" AND network = ").push_bind(network.clone());
+    }
+
+    if params.verified_only.unwrap_or(false) {
+        builder.push(" AND is_verified = true");
+    }
+
+    if let Some(ref category) = params.category {
+        builder.push(" AND category = ").push_bind(category.clone());
+    }
+
+    if let Some(tag) = params.tag.as_deref().map(str::trim).filter(|t| !t.is_empty()) {
+        builder
+            .push(" AND ")
+            .push_bind(tag.to_lowercase())
+            .push(" = ANY(tags)");
+    }
+}
+
+/// Hide private contracts `caller` holds no role on. Mirrors
+/// [`auth::can_view_contract`]: admins see everything, publishers see their
+/// own personal contracts and those of organizations they belong to.
+pub(crate) fn push_visibility_filter(builder: &mut QueryBuilder<'_, Postgres>, caller: Option<&Caller>) {
+    match caller {
+        Some(Caller::Admin) => {}
+        Some(Caller::Publisher(publisher_id)) => {
+            builder
+                .push(" AND (visibility = 'public' OR (organization_id IS NULL AND publisher_id = ")
+                .push_bind(*publisher_id)
+                .push(") OR organization_id IN (SELECT organization_id FROM organization_members WHERE publisher_id = ")
+                .push_bind(*publisher_id)
+                .push("))");
+        }
+        None => {
+            builder.push(" AND visibility = 'public'");
+        }
+    }
+}
+
+/// A tag and the number of contracts carrying it
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct TagCount {
+    pub tag: String,
+    pub count: i64,
+}
+
+/// List all tags in use, most common first
+#[utoipa::path(
+    get,
+    path = "/api/tags",
+    tag = "contracts",
+    responses(
+        (status = 200, description = "Distinct tags with contract counts", body = [TagCount]),
+    ),
+)]
+pub async fn list_tags(State(state): State<AppState>) -> ApiResult<Json<Vec<TagCount>>> {
+    let tags: Vec<TagCount> = sqlx::query_as(
+        "SELECT tag, COUNT(*) AS count
+         FROM contracts, unnest(tags) AS tag
+         WHERE visibility = 'public'
+         GROUP BY tag
+         ORDER BY count DESC, tag ASC",
+    )
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list tags", err))?;
+
+    Ok(Json(tags))
+}
+
+/// Most suggestions returned by `GET /api/contracts/suggest`
+const SUGGEST_LIMIT: i64 = 10;
+/// Shorter prefixes would match most of the table
+const SUGGEST_MIN_PREFIX_CHARS: usize = 2;
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SuggestParams {
+    /// Start of the contract name, case-insensitive
+    pub prefix: Option<String>,
+}
+
+/// A typeahead suggestion
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ContractSuggestion {
+    pub id: Uuid,
+    pub name: String,
+}
+
+/// `LIKE` pattern for names starting with `prefix`, or `None` when the prefix
+/// is too short to be worth a query
+pub(crate) fn suggest_pattern(prefix: &str) -> Option<String> {
+    let prefix = prefix.trim();
+    if prefix.chars().count() < SUGGEST_MIN_PREFIX_CHARS {
+        return None;
+    }
+    let mut pattern = String::with_capacity(prefix.len() + 1);
+    for c in prefix.to_lowercase().chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+    }
+    pattern.push('%');
+    Some(pattern)
+}
+
+/// Contract names starting with a prefix, most popular first
+#[utoipa::path(
+    get,
+    path = "/api/contracts/suggest",
+    tag = "contracts",
+    params(SuggestParams),
+    responses(
+        (status = 200, description = "Up to 10 suggestions; empty for prefixes under 2 characters", body = [ContractSuggestion]),
+    ),
+)]
+pub async fn suggest_contracts(
+    State(state): State<AppState>,
+    Query(params): Query<SuggestParams>,
+) -> ApiResult<Json<Vec<ContractSuggestion>>> {
+    let Some(pattern) = params.prefix.as_deref().and_then(suggest_pattern) else {
+        return Ok(Json(Vec::new()));
+    };
+
+    // Served by idx_contracts_name_prefix (lower(name) text_pattern_ops)
+    let suggestions: Vec<ContractSuggestion> = sqlx::query_as(
+        "SELECT id, name FROM contracts
+         WHERE lower(name) LIKE $1 AND visibility = 'public'
+         ORDER BY popularity_score DESC, name ASC
+         LIMIT $2",
+    )
+    .bind(pattern)
+    .bind(SUGGEST_LIMIT)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("suggest contracts", err))?;
+
+    Ok(Json(suggestions))
+}
+
+/// List and search contracts
+#[utoipa::path(
+    get,
+    path = "/api/contracts",
+    tag = "contracts",
+    params(
+        ListContractsParams,
+    ),
+    responses(
+        (status = 200, description = "Paginated contract listing; `next_cursor` is set in `?cursor=` mode until the last page", body = PaginatedContractListItems),
+        (status = 400, description = "Invalid filter or sort value"),
+    ),
+)]
+pub async fn list_contracts(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    params: Result<Query<ListContractsParams>, QueryRejection>,
+) -> axum::response::Response {
+    let caller = caller.map(|Extension(caller)| caller);
+    let viewer = caller.as_ref().and_then(Caller::publisher_id);
+    let Query(params) = match params {
+        Ok(q) => q,
+        Err(err) => return map_query_rejection(err).into_response(),
+    };
+
+    let publisher_id = match params.publisher_uuid() {
+        Ok(id) => id,
+        Err(err) => return err.into_response(),
+    };
+    let sort = match params.sort_field() {
+        Ok(sort) => sort,
+        Err(err) => return err.into_response(),
+    };
+    let rank_by_score = match params.rank_by_score() {
+        Ok(rank_by_score) => rank_by_score,
+        Err(err) => return err.into_response(),
+    };
+    let limit = params.clamped_limit();
+
+    // Keyset mode: ignores offset and always orders by (created_at, id)
+    let cursor_mode = params.cursor.is_some();
+    let after = match params.cursor.as_deref().map(str::trim) {
+        None | Some("") => None,
+        Some(raw) => match ContractCursor::decode(raw) {
+            Ok(cursor) => Some(cursor),
+            Err(err) => return err.into_response(),
+        },
+    };
+    let keyset_cmp = if cursor_mode {
+        match sort.unwrap_or_default().keyset_comparator() {
+            Some(cmp) => Some(cmp),
+            None => {
+                return ApiError::bad_request(
+                    ErrorCode::UnsupportedCursorSort,
+                    "cursor pagination only supports sort=created_at or sort=-created_at",
+                )
+                .into_response()
+            }
+        }
+    } else {
+        None
+    };
+    let offset = if cursor_mode { 0 } else { params.clamped_offset(limit) };
+
+    let tsquery = params.search_tsquery();
+    let fuzzy_term = params.fuzzy_term();
+
+    let mut query = QueryBuilder::<Postgres>::new("SELECT *, ");
+    match tsquery {
+        Some(ref tsquery) if rank_by_score => {
+            push_score_blended_rank(&mut query, tsquery, *SEARCH_SCORE_WEIGHT);
+            query.push(" AS rank");
+        }
+        Some(ref tsquery) => {
+            query
+                .push("ts_rank(search_vector, to_tsquery('simple', ")
+                .push_bind(tsquery.clone())
+                .push(")) AS rank");
+        }
+        None => {
+            query.push("NULL::real AS rank");
+        }
+    }
+    match fuzzy_term {
+        Some(ref term) => {
+            query
+                .push(", similarity(name, ")
+                .push_bind(term.clone())
+                .push(") AS similarity");
+        }
+        None => {
+            query.push(", NULL::real AS similarity");
+        }
+    }
+    query.push(", ");
+    push_star_columns(&mut query, viewer);
+    query.push(
+        ", EXISTS(SELECT 1 FROM contract_versions v WHERE v.contract_id = contracts.id AND NOT v.yanked) AS has_installable_version",
+    );
+    query.push(", ");
+    push_download_count_column(&mut query);
+    query.push(" FROM contracts");
+    push_contract_filters(&mut query, &params, publisher_id, caller.as_ref());
+    if let (Some(cmp), Some(after)) = (keyset_cmp, after) {
+        query
+            .push(" AND (created_at, id) ")
+            .push(cmp)
+            .push(" (")
+            .push_bind(after.created_at)
+            .push(", ")
+            .push_bind(after.id)
+            .push(")");
+    }
+    query.push(" ORDER BY ");
+    match (sort, tsquery.is_some(), fuzzy_term.is_some()) {
+        // relevance wins unless the caller asked for something else
+        (None, true, _) if !cursor_mode => query.push("rank DESC, id DESC"),
+        (None, _, true) if !cursor_mode => query.push("similarity DESC, id DESC"),
+        (sort, _, _) => query.push(sort.unwrap_or_default().order_by()),
+    };
+    if cursor_mode {
+        // one extra row tells us whether another page exists
+        query.push(" LIMIT ").push_bind(limit + 1);
+    } else {
+        query
+            .push(" LIMIT ")
+            .push_bind(limit)
+            .push(" OFFSET ")
+            .push_bind(offset);
+    }
+
+    let mut count_query = QueryBuilder::<Postgres>::new("SELECT COUNT(*) FROM contracts");
+    push_contract_filters(&mut count_query, &params, publisher_id, caller.as_ref());
+
+    let mut contracts: Vec<ContractListItem> = match query.build_query_as().fetch_all(&state.db).await {
+        Ok(rows) => rows,
+        Err(err) => return db_internal_error("list contracts", err).into_response(),
+    };
+
+    let total: i64 = match count_query.build_query_scalar().fetch_one(&state.db).await {
+        Ok(n) => n,
+        Err(err) => return db_internal_error("count filtered contracts", err).into_response(),
+    };
+
+    let mut next_cursor = None;
+    if cursor_mode && contracts.len() as i64 > limit {
+        contracts.truncate(limit as usize);
+        next_cursor = contracts.last().map(|item| {
+            ContractCursor {
+                created_at: item.contract.created_at,
+                id: item.contract.id,
+            }
+            .encode()
+        });
+    }
+
+    // link headers for pagination
+    let mut links: Vec<String> = Vec::new();
+
+    if cursor_mode {
+        if let Some(ref cursor) = next_cursor {
+            links.push(format!(
+                "</api/contracts?cursor={}&limit={}>; rel=\"next\"",
+                cursor, limit
+            ));
+        }
+    } else if offset > 0 {
+        links.push(format!(
+            "</api/contracts?offset={}&limit={}>; rel=\"prev\"",
+            (offset - limit).max(0),
+            limit
+        ));
+    }
+    if !cursor_mode && offset + limit < total {
+        links.push(format!(
+            "</api/contracts?offset={}&limit={}>; rel=\"next\"",
+            offset + limit,
+            limit
+        ));
+    }
+
+    let mut body = Paginated::new(contracts, total, limit, offset);
+    if cursor_mode {
+        body = body.with_next_cursor(next_cursor);
+    }
+    let mut response = (StatusCode::OK, Json(body)).into_response();
+
+    if !links.is_empty() {
+        if let Ok(value) = axum::http::HeaderValue::from_str(&links.join(", ")) {
+            response.headers_mut().insert("link", value);
+        }
+    }
+
+    response
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Contract details; carries a strong ETag", body = ContractDetail),
+        (status = 304, description = "Unchanged since the ETag sent in If-None-Match"),
+        (status = 404, description = "Contract not found"),
+    ),
+)]
+pub async fn get_contract(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+    headers: axum::http::HeaderMap,
+) -> ApiResult<axum::response::Response> {
+    let caller = caller.map(|Extension(caller)| caller);
+    let viewer = caller.as_ref().and_then(Caller::publisher_id);
+    let contract =
+        fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let active_deployment: Option<ContractDeployment> = sqlx::query_as(
+        "SELECT * FROM contract_deployments 
+         WHERE contract_id = $1 AND status = 'active'",
+    )
+    .bind(contract.id)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("get active deployment", err))?;
+
+    let mut contract = contract;
+    if let Some(deployment) = active_deployment {
+        contract.wasm_hash = deployment.wasm_hash;
+    }
+
+    let (star_count, starred_by_me) = star_summary(&state.db, contract.id, viewer).await?;
+    let download_count: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(download_count), 0)::bigint FROM contract_versions WHERE contract_id = $1",
+    )
+    .bind(contract.id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("sum downloads", err))?;
+
+    let detail = ContractDetail {
+        contract,
+        star_count,
+        starred_by_me,
+        download_count,
+    };
+    Ok(crate::etag::json_with_etag(&headers, &detail))
+}
+
+async fn star_summary(
+    pool: &sqlx::PgPool,
+    contract_id: Uuid,
+    viewer: Option<Uuid>,
+) -> ApiResult<(i64, Option<bool>)> {
+    sqlx::query_as(
+        "SELECT
+            (SELECT COUNT(*) FROM contract_stars WHERE contract_id = $1),
+            CASE WHEN $2::uuid IS NULL THEN NULL
+                 ELSE EXISTS(SELECT 1 FROM contract_stars WHERE contract_id = $1 AND publisher_id = $2)
+            END",
+    )
+    .bind(contract_id)
+    .bind(viewer)
+    .fetch_one(pool)
+    .await
+    .map_err(|err| db_internal_error("count stars", err))
+}
+
+/// Star state returned by the star/unstar endpoints
+#[derive(Debug, Serialize, ToSchema)]
+pub struct StarStatus {
+    pub contract_id: Uuid,
+    pub starred: bool,
+    pub star_count: i64,
+}
+
+/// Load a contract `caller` may see, either through a role on it or a live
+/// share token. A private contract they cannot see is reported as missing,
+/// so its existence does not leak.
+pub(crate) async fn fetch_visible_contract(
+    pool: &sqlx::PgPool,
+    caller: Option<&Caller>,
+    share_token: Option<&str>,
+    id: Uuid,
+) -> ApiResult<Contract> {
+    let not_found = || {
+        ApiError::not_found(
+            ErrorCode::ContractNotFound,
+            format!("No contract found with ID: {}", id),
+        )
+    };
+    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|err| db_internal_error("get contract by id", err))?
+        .ok_or_else(not_found)?;
+    if !is_visible(pool, caller, share_token, &contract).await? {
+        return Err(not_found());
+    }
+    Ok(contract)
+}
+
+/// [`fetch_visible_contract`] for routes keyed by the on-chain contract ID,
+/// which may be registered once per network; the first visible one wins
+pub(crate) async fn fetch_visible_contract_by_address(
+    pool: &sqlx::PgPool,
+    caller: Option<&Caller>,
+    share_token: Option<&str>,
+    contract_id: &str,
+) -> ApiResult<Contract> {
+    let contracts: Vec<Contract> =
+        sqlx::query_as("SELECT * FROM contracts WHERE contract_id = $1 ORDER BY created_at")
+            .bind(contract_id)
+            .fetch_all(pool)
+            .await
+            .map_err(|err| db_internal_error("get contract", err))?;
+    for contract in contracts {
+        if is_visible(pool, caller, share_token, &contract).await? {
+            return Ok(contract);
+        }
+    }
+    Err(ApiError::not_found(
+        ErrorCode::ContractNotFound,
+        format!("Contract not found: {}", contract_id),
+    ))
+}
+
+/// Whether `caller` may see `contract`, through a role on it or a live share
+/// token for it
+pub(crate) async fn is_visible(
+    pool: &sqlx::PgPool,
+    caller: Option<&Caller>,
+    share_token: Option<&str>,
+    contract: &Contract,
+) -> ApiResult<bool> {
+    if auth::can_view_contract(pool, caller, contract).await? {
+        return Ok(true);
+    }
+    match share_token {
+        Some(raw) => share_tokens::grants_access(pool, contract.id, raw)
+            .await
+            .map_err(|err| db_internal_error("check share token", err)),
+        None => Ok(false),
+    }
+}
+
+pub(crate) async fn ensure_contract_exists(pool: &sqlx::PgPool, id: Uuid) -> ApiResult<()> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM contracts WHERE id = $1)")
+        .bind(id)
+        .fetch_one(pool)
+        .await
+        .map_err(|err| db_internal_error("check contract", err))?;
+    if !exists {
+        return Err(ApiError::not_found(
+            ErrorCode::ContractNotFound,
+            format!("No contract found with ID: {}", id),
+        ));
+    }
+    Ok(())
+}
+
+fn star_publisher(caller: Caller) -> ApiResult<Uuid> {
+    caller.publisher_id().ok_or_else(|| {
+        ApiError::new(
+            StatusCode::FORBIDDEN,
+            ErrorCode::Forbidden,
+            "Stars are recorded per publisher; use a publisher API key",
+        )
+    })
+}
+
+/// Star a contract (idempotent)
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/star",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "Contract is starred", body = StarStatus),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 404, description = "Contract not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn star_contract(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<StarStatus>> {
+    let publisher_id = star_publisher(caller)?;
+    ensure_contract_exists(&state.db, id).await?;
+
+    sqlx::query(
+        "INSERT INTO contract_stars (publisher_id, contract_id) VALUES ($1, $2)
+         ON CONFLICT (publisher_id, contract_id) DO NOTHING",
+    )
+    .bind(publisher_id)
+    .bind(id)
+    .execute(&state.db)
+    .await
+    .map_err(|err| db_internal_error("star contract", err))?;
+
+    let (star_count, _) = star_summary(&state.db, id, None).await?;
+    Ok(Json(StarStatus {
+        contract_id: id,
+        starred: true,
+        star_count,
+    }))
+}
+
+/// Remove a star (idempotent)
+#[utoipa::path(
+    delete,
+    path = "/api/contracts/{id}/star",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "Contract is not starred", body = StarStatus),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 404, description = "Contract not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn unstar_contract(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<StarStatus>> {
+    let publisher_id = star_publisher(caller)?;
+    ensure_contract_exists(&state.db, id).await?;
+
+    sqlx::query("DELETE FROM contract_stars WHERE publisher_id = $1 AND contract_id = $2")
+        .bind(publisher_id)
+        .bind(id)
+        .execute(&state.db)
+        .await
+        .map_err(|err| db_internal_error("unstar contract", err))?;
+
+    let (star_count, _) = star_summary(&state.db, id, None).await?;
+    Ok(Json(StarStatus {
+        contract_id: id,
+        starred: false,
+        star_count,
+    }))
+}
+
+/// Get contract ABI
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/abi",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Contract ABI as JSON"),
+        (status = 404, description = "Unknown or private contract, or no ABI"),
+    ),
+)]
+pub async fn get_contract_abi(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let abi: Option<serde_json::Value> =
+        sqlx::query_scalar("SELECT abi FROM contracts WHERE id = $1")
+            .bind(id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("get contract abi", err))?;
+
+    abi.map(Json).ok_or_else(|| ApiError::not_found(ErrorCode::AbiNotFound, format!("No ABI available for contract: {}", id)))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct SbomParams {
+    /// SBOM flavour; only `cyclonedx` (the default) is supported
+    pub format: Option<String>,
+}
+
+/// CycloneDX JSON SBOM of a version: the contract and its declared
+/// dependencies, with versions and SHA-256 hashes where known
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/versions/{version}/sbom",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("version" = String, Path, description = "Contract version"),
+        SbomParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "CycloneDX 1.5 JSON", content_type = "application/vnd.cyclonedx+json"),
+        (status = 400, description = "Unsupported format"),
+        (status = 404, description = "Unknown or private contract, or unknown version"),
+    ),
+)]
+pub async fn get_version_sbom(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path((id, version)): Path<(Uuid, String)>,
+    Query(params): Query<SbomParams>,
+) -> ApiResult<axum::response::Response> {
+    match params.format.as_deref().map(str::trim) {
+        None | Some("") | Some("cyclonedx") => {}
+        Some(other) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidSbomFormat,
+                format!("Unsupported SBOM format '{}'; use cyclonedx", other),
+            ))
+        }
+    }
+
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let bom = sbom::for_version(&state.db, id, &version)
+        .await
+        .map_err(|err| db_internal_error("build sbom", err))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::VersionNotFound,
+                format!("Version {} not found for contract {}", version, id),
+            )
+        })?;
+
+    Ok((
+        [(axum::http::header::CONTENT_TYPE, sbom::CYCLONEDX_CONTENT_TYPE)],
+        Json(bom),
+    )
+        .into_response())
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ReadmeParams {
+    /// `html` for sanitized HTML; omit for the raw markdown
+    pub render: Option<String>,
+}
+
+/// Contract README, as published or rendered to sanitized HTML
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/readme",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ReadmeParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "text/markdown, or text/html with render=html"),
+        (status = 400, description = "Unknown render format"),
+        (status = 404, description = "contract.not_found for an unknown or private contract, or readme.not_found when it has no README"),
+    ),
+)]
+pub async fn get_contract_readme(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ReadmeParams>,
+) -> ApiResult<axum::response::Response> {
+    let as_html = match params.render.as_deref() {
+        None | Some("markdown") => false,
+        Some("html") => true,
+        Some(other) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidRenderFormat,
+                format!("render must be 'html' or 'markdown' (got '{}')", other),
+            ));
+        }
+    };
+
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let markdown = readme::load(&state.db, id)
+        .await
+        .map_err(|err| db_internal_error("get readme", err))?
+        .ok_or_else(|| {
+            ApiError::not_found(ErrorCode::ReadmeNotFound, format!("Contract {} has no README", id))
+        })?;
+
+    let (content_type, body) = if as_html {
+        ("text/html; charset=utf-8", readme::render_html(&markdown))
+    } else {
+        ("text/markdown; charset=utf-8", markdown)
+    };
+    Ok((
+        [
+            (axum::http::header::CONTENT_TYPE, content_type),
+            (axum::http::header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+        ],
+        body,
+    )
+        .into_response())
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ChangelogParams {
+    /// `markdown` for a single CHANGELOG.md document; omit for JSON
+    pub format: Option<String>,
+}
+
+/// One version's change notes
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ChangelogEntry {
+    pub version: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub yanked: bool,
+    /// Empty when the version was published without notes
+    pub changelog: String,
+}
+
+/// Newest first: semver versions in descending order, then any that are not
+/// semver by publish date
+pub(crate) fn changelog_entries(mut versions: Vec<ContractVersion>) -> Vec<ChangelogEntry> {
+    let parsed =
+        |v: &ContractVersion| semver::Version::parse(v.version.trim().trim_start_matches('v')).ok();
+    versions.sort_by(|a, b| match (parsed(a), parsed(b)) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => b.created_at.cmp(&a.created_at),
+    });
+    versions
+        .into_iter()
+        .map(|v| ChangelogEntry {
+            version: v.version,
+            created_at: v.created_at,
+            yanked: v.yanked,
+            changelog: v.release_notes.unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Keep-a-Changelog style document; every version gets a section, empty or not
+pub(crate) fn render_changelog(name: &str, entries: &[ChangelogEntry]) -> String {
+    let mut doc = format!("# Changelog\n\nAll notable changes to {}.\n", name);
+    for entry in entries {
+        doc.push_str(&format!(
+            "\n## [{}] - {}{}\n",
+            entry.version,
+            entry.created_at.format("%Y-%m-%d"),
+            if entry.yanked { " [YANKED]" } else { "" }
+        ));
+        let notes = entry.changelog.trim();
+        if !notes.is_empty() {
+            doc.push('\n');
+            doc.push_str(notes);
+            doc.push('\n');
+        }
+    }
+    doc
+}
+
+/// Change notes of every version, newest first
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/changelog",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ChangelogParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Versions with their notes, or text/markdown with format=markdown", body = Vec<ChangelogEntry>),
+        (status = 400, description = "Unknown format"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
+pub async fn get_contract_changelog(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ChangelogParams>,
+) -> ApiResult<axum::response::Response> {
+    let as_markdown = match params.format.as_deref() {
+        None | Some("json") => false,
+        Some("markdown") => true,
+        Some(other) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidChangelogFormat,
+                format!("format must be 'markdown' or 'json' (got '{}')", other),
+            ));
+        }
+    };
+
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract =
+        fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let versions: Vec<ContractVersion> =
+        sqlx::query_as("SELECT * FROM contract_versions WHERE contract_id = $1")
+            .bind(id)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|err| db_internal_error("list versions for changelog", err))?;
+    let entries = changelog_entries(versions);
+
+    if as_markdown {
+        return Ok((
+            [
+                (axum::http::header::CONTENT_TYPE, "text/markdown; charset=utf-8"),
+                (axum::http::header::X_CONTENT_TYPE_OPTIONS, "nosniff"),
+            ],
+            render_changelog(&contract.name, &entries),
+        )
+            .into_response());
+    }
+    Ok(Json(entries).into_response())
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ScoreHistoryParams {
+    /// Only points computed at or after this time (RFC 3339)
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only points computed at or before this time (RFC 3339)
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    /// `daily` returns the last value per day; omit for every computation
+    pub granularity: Option<String>,
+}
+
+/// Upper bound on points returned in one response; past it the most recent
+/// points are kept
+const SCORE_HISTORY_LIMIT: i64 = 10_000;
+
+/// The newest `limit` points inside the requested window, oldest first
+async fn load_score_history(
+    db: &sqlx::PgPool,
+    id: Uuid,
+    params: &ScoreHistoryParams,
+    limit: i64,
+) -> Result<Vec<crate::scoring::ScoreHistoryPoint>, sqlx::Error> {
+    let mut points: Vec<crate::scoring::ScoreHistoryPoint> = sqlx::query_as(
+        "SELECT composite, security, popularity, maintenance, computed_at
+         FROM score_history
+         WHERE contract_id = $1
+           AND ($2::timestamptz IS NULL OR computed_at >= $2)
+           AND ($3::timestamptz IS NULL OR computed_at <= $3)
+         ORDER BY computed_at DESC, id DESC
+         LIMIT $4",
     )
     .bind(id)
+    .bind(params.from)
+    .bind(params.to)
+    .bind(limit)
+    .fetch_all(db)
+    .await?;
+    points.reverse();
+    Ok(points)
+}
+
+/// Composite score time series for a contract
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/score-history",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ScoreHistoryParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Score computations, oldest first; the most recent 10,000 when there are more"),
+        (status = 400, description = "Unknown granularity or from after to"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
+pub async fn get_score_history(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ScoreHistoryParams>,
+) -> ApiResult<Json<serde_json::Value>> {
+    let daily = match params.granularity.as_deref() {
+        None | Some("raw") => false,
+        Some("daily") => true,
+        Some(other) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidGranularity,
+                format!("granularity must be 'daily' or omitted (got '{}')", other),
+            ))
+        }
+    };
+    if let (Some(from), Some(to)) = (params.from, params.to) {
+        if from > to {
+            return Err(ApiError::bad_request(ErrorCode::InvalidRange, "from must not be after to"));
+        }
+    }
+
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
+
+    let points = load_score_history(&state.db, id, &params, SCORE_HISTORY_LIMIT)
+        .await
+        .map_err(|err| db_internal_error("load score history", err))?;
+
+    let points = if daily {
+        crate::scoring::last_point_per_day(points)
+    } else {
+        points
+    };
+
+    Ok(Json(serde_json::json!({
+        "contract_id": id,
+        "granularity": if daily { "daily" } else { "raw" },
+        "points": points,
+    })))
+}
+
+/// Get contract version history
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/versions",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        PageParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Versions of the contract, newest first", body = PaginatedContractVersions),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
+pub async fn get_contract_versions(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<String>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<ContractVersion>>> {
+    let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            ErrorCode::InvalidContractId,
+            format!("Invalid contract ID format: {}", id),
+        )
+    })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), contract_uuid)
+        .await?;
+
+    let versions: Vec<ContractVersion> = sqlx::query_as(
+        "SELECT * FROM contract_versions WHERE contract_id = $1
+         ORDER BY created_at DESC, id DESC
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(contract_uuid)
+    .bind(page.limit())
+    .bind(page.offset())
     .fetch_all(&state.db)
     .await
     .map_err(|err| db_internal_error("list versions", err))?;
+    let total: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM contract_versions WHERE contract_id = $1")
+            .bind(contract_uuid)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("count versions", err))?;
 
-    Ok(Json(versions))
+    Ok(Json(Paginated::new(
+        versions,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ResolveVersionQuery {
+    pub range: String,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ResolvedVersion {
+    pub range: String,
+    pub version: String,
+    pub wasm_hash: String,
+}
+
+/// Pick the highest stored version satisfying `req`. Versions that are not
+/// valid semver (optionally prefixed with `v`) are skipped, as are yanked
+/// versions unless `req` pins exactly that version.
+pub(crate) fn highest_matching_version<'a>(
+    req: &semver::VersionReq,
+    versions: &'a [ContractVersion],
+) -> Option<&'a ContractVersion> {
+    let best = |allow_yanked: bool| {
+        versions
+            .iter()
+            .filter(|v| allow_yanked || !v.yanked)
+            .filter_map(|v| {
+                semver::Version::parse(v.version.trim().trim_start_matches('v'))
+                    .ok()
+                    .map(|parsed| (parsed, v))
+            })
+            .filter(|(parsed, _)| req.matches(parsed))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, v)| v)
+    };
+    best(false).or_else(|| if is_exact_pin(req) { best(true) } else { None })
+}
+
+/// `=1.2.3`: the only kind of requirement that may still select a yanked
+/// version, so existing pins keep resolving
+fn is_exact_pin(req: &semver::VersionReq) -> bool {
+    match req.comparators.as_slice() {
+        [c] => c.op == semver::Op::Exact && c.minor.is_some() && c.patch.is_some(),
+        _ => false,
+    }
+}
+
+/// Set or clear `yanked` on one version; only the contract's owners may
+async fn set_version_yanked(
+    state: &AppState,
+    access: &ContractAccess,
+    version: &str,
+    yanked: bool,
+) -> ApiResult<ContractVersion> {
+    access.require(OrganizationRole::Owner)?;
+
+    // Re-yanking keeps the original timestamp
+    sqlx::query_as(
+        "UPDATE contract_versions
+         SET yanked = $3,
+             yanked_at = CASE WHEN $3 THEN COALESCE(yanked_at, NOW()) ELSE NULL END
+         WHERE contract_id = $1 AND version = $2
+         RETURNING *",
+    )
+    .bind(access.contract_id)
+    .bind(version)
+    .bind(yanked)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("update yanked flag", err))?
+    .ok_or_else(|| {
+        ApiError::not_found(
+            ErrorCode::VersionNotFound,
+            format!("Contract {} has no version {}", access.contract_id, version),
+        )
+    })
+}
+
+/// Yank a version: range resolution skips it, but it stays downloadable and
+/// exact `=x.y.z` pins still resolve to it
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/versions/{version}/yank",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("version" = String, Path, description = "Version to yank"),
+    ),
+    responses(
+        (status = 200, description = "The yanked version", body = ContractVersion),
+        (status = 403, description = "Caller lacks the owner role on the contract"),
+        (status = 404, description = "Contract or version not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn yank_contract_version(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Path((_, version)): Path<(String, String)>,
+) -> ApiResult<Json<ContractVersion>> {
+    set_version_yanked(&state, &access, &version, true)
+        .await
+        .map(Json)
+}
+
+/// Undo a yank so the version is selectable by ranges again
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/versions/{version}/unyank",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("version" = String, Path, description = "Version to restore"),
+    ),
+    responses(
+        (status = 200, description = "The restored version", body = ContractVersion),
+        (status = 403, description = "Caller lacks the owner role on the contract"),
+        (status = 404, description = "Contract or version not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn unyank_contract_version(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Path((_, version)): Path<(String, String)>,
+) -> ApiResult<Json<ContractVersion>> {
+    set_version_yanked(&state, &access, &version, false)
+        .await
+        .map(Json)
+}
+
+/// Make a contract public or private. Private contracts are only visible to
+/// their publisher, members of the owning organization and admins.
+#[utoipa::path(
+    put,
+    path = "/api/contracts/{id}/visibility",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    request_body = SetVisibilityRequest,
+    responses(
+        (status = 200, description = "The updated contract", body = Contract),
+        (status = 403, description = "Caller lacks the owner role on the contract"),
+        (status = 404, description = "Contract not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn set_contract_visibility(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Json(req): Json<SetVisibilityRequest>,
+) -> ApiResult<Json<Contract>> {
+    access.require(OrganizationRole::Owner)?;
+
+    let contract: Contract = sqlx::query_as(
+        "UPDATE contracts SET visibility = $2, updated_at = NOW() WHERE id = $1 RETURNING *",
+    )
+    .bind(access.contract_id)
+    .bind(req.visibility)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("set contract visibility", err))?;
+    state.stats.invalidate();
+
+    Ok(Json(contract))
+}
+
+/// Resolve a semver range (e.g. `^1.2.0`) to the best matching version
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/versions/resolve",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("range" = String, Query, description = "Semver range, e.g. ^1.2.0"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Highest version matching the range", body = ResolvedVersion),
+        (status = 400, description = "Invalid range"),
+        (status = 404, description = "Unknown or private contract, or no version satisfies the range"),
+    ),
+)]
+pub async fn resolve_contract_version(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<String>,
+    Query(query): Query<ResolveVersionQuery>,
+) -> ApiResult<Json<ResolvedVersion>> {
+    let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
+        ApiError::bad_request(
+            ErrorCode::InvalidContractId,
+            format!("Invalid contract ID format: {}", id),
+        )
+    })?;
+
+    let req = semver::VersionReq::parse(query.range.trim()).map_err(|err| {
+        ApiError::bad_request(
+            ErrorCode::InvalidVersionRange,
+            format!("'{}' is not a valid semver range: {}", query.range, err),
+        )
+    })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), contract_uuid)
+        .await?;
+
+    let versions: Vec<ContractVersion> =
+        sqlx::query_as("SELECT * FROM contract_versions WHERE contract_id = $1")
+            .bind(contract_uuid)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|err| db_internal_error("resolve version", err))?;
+
+    match highest_matching_version(&req, &versions) {
+        Some(best) => Ok(Json(ResolvedVersion {
+            range: query.range,
+            version: best.version.clone(),
+            wasm_hash: best.wasm_hash.clone(),
+        })),
+        None => {
+            let available: Vec<&str> = versions.iter().map(|v| v.version.as_str()).collect();
+            Err(ApiError::not_found(
+                ErrorCode::NoMatchingVersion,
+                format!("No version of contract {} satisfies '{}'", id, query.range),
+            )
+            .with_details(serde_json::json!({ "available_versions": available })))
+        }
+    }
+}
+
+/// Mark a version as deprecated. Re-deprecating updates the reason and
+/// successor; the original `deprecated_at` is kept.
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/versions/{version}/deprecate",
+    tag = "versions",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("version" = String, Path, description = "Version to deprecate"),
+    ),
+    request_body = DeprecateVersionRequest,
+    responses(
+        (status = 200, description = "The deprecated version", body = ContractVersion),
+        (status = 403, description = "Caller lacks the owner role on the contract"),
+        (status = 404, description = "Version not found"),
+        (status = 422, description = "superseded_by is not a version of this contract"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn deprecate_contract_version(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Path((id, version)): Path<(String, String)>,
+    ValidatedJson(req): ValidatedJson<DeprecateVersionRequest>,
+) -> ApiResult<Json<ContractVersion>> {
+    access.require(OrganizationRole::Owner)?;
+    let contract_uuid = access.contract_id;
+
+    if let Some(ref successor) = req.superseded_by {
+        if successor == &version {
+            return Err(ApiError::unprocessable(
+                ErrorCode::InvalidSuccessor,
+                "A version cannot supersede itself",
+            ));
+        }
+
+        let exists: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM contract_versions WHERE contract_id = $1 AND version = $2)",
+        )
+        .bind(contract_uuid)
+        .bind(successor)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("check successor version", err))?;
+
+        if !exists {
+            return Err(ApiError::unprocessable(
+                ErrorCode::InvalidSuccessor,
+                format!(
+                    "superseded_by '{}' is not a version of contract {}",
+                    successor, id
+                ),
+            ));
+        }
+    }
+
+    let updated: Option<ContractVersion> = sqlx::query_as(
+        "UPDATE contract_versions
+         SET deprecated_at = COALESCE(deprecated_at, NOW()),
+             deprecation_reason = $3,
+             superseded_by = $4
+         WHERE contract_id = $1 AND version = $2
+         RETURNING *",
+    )
+    .bind(contract_uuid)
+    .bind(&version)
+    .bind(&req.reason)
+    .bind(&req.superseded_by)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("deprecate version", err))?;
+
+    updated.map(Json).ok_or_else(|| {
+        ApiError::not_found(
+            ErrorCode::VersionNotFound,
+            format!("Version {} not found for contract {}", version, id),
+        )
+    })
 }
 
 /// Publish a new contract
@@ -286,29 +2016,147 @@ pub async fn get_contract_versions(
 /// - publisher_address: must be a valid Stellar address (56 chars starting with 'G')
 /// - source_url: if provided, must be a valid URL
 /// - tags: max 10 tags, each max 50 characters
+///
+/// Requires an API key; `publisher_address` must belong to the key's publisher.
+///
+/// The body is either this JSON, with `wasm` base64-encoded, or
+/// `multipart/form-data` with the JSON as a `metadata` part and the raw module
+/// as a `wasm` file part.
+#[utoipa::path(
+    post,
+    path = "/api/contracts",
+    tag = "contracts",
+    request_body(
+        content = PublishRequest,
+        content_type = "application/json",
+        description = "Also accepted as multipart/form-data: `metadata` (this JSON, without wasm) and `wasm` (the module file)",
+    ),
+    responses(
+        (status = 200, description = "Published contract, with benchmark_warnings for regressions", body = PublishResponse),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 403, description = "Key belongs to a different publisher, the contract is owned by another publisher, or the publisher is not a member of organization_id"),
+        (status = 409, description = "contract.duplicate_version: this version was already published"),
+        (status = 413, description = "artifact.too_large: the artifact exceeds MAX_ARTIFACT_BYTES (details holds limit and actual)"),
+        (status = 422, description = "version.invalid_semver, or metadata failed JSON Schema validation (details.errors lists each path and message)"),
+    ),
+    security(("api_key" = [])),
+)]
 pub async fn publish_contract(
     State(state): State<AppState>,
-    ValidatedJson(req): ValidatedJson<PublishRequest>,
-) -> ApiResult<Json<Contract>> {
-    // req is already validated and sanitized by ValidatedJson extractor
+    Extension(caller): Extension<Caller>,
+    upload: PublishUpload,
+) -> ApiResult<Json<PublishResponse>> {
+    // Already validated and sanitized, with the artifact decoded
+    let PublishUpload { request: req, wasm } = upload;
+
+    let publisher: Publisher = match caller.publisher_id() {
+        // The key decides who is publishing, not the request body
+        Some(publisher_id) => {
+            let publisher: Publisher = sqlx::query_as("SELECT * FROM publishers WHERE id = $1")
+                .bind(publisher_id)
+                .fetch_one(&state.db)
+                .await
+                .map_err(|err| db_internal_error("get authenticated publisher", err))?;
+            if publisher.stellar_address != req.publisher_address {
+                return Err(auth::forbidden());
+            }
+            publisher
+        }
+        // Operators may publish on behalf of any address
+        None => sqlx::query_as(
+            "INSERT INTO publishers (stellar_address) VALUES ($1)
+             ON CONFLICT (stellar_address) DO UPDATE SET stellar_address = EXCLUDED.stellar_address
+             RETURNING *",
+        )
+        .bind(&req.publisher_address)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("upsert publisher", err))?,
+    };
+    // Keys already stop working on deactivation; this covers the admin key
+    if publisher.deactivated_at.is_some() {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            ErrorCode::PublisherDeactivated,
+            format!("Publisher {} is deactivated", publisher.id),
+        ));
+    }
 
-    // First, ensure publisher exists or create one
-    let publisher: Publisher = sqlx::query_as(
-        "INSERT INTO publishers (stellar_address) VALUES ($1)
-         ON CONFLICT (stellar_address) DO UPDATE SET stellar_address = EXCLUDED.stellar_address
-         RETURNING *",
-    )
-    .bind(&req.publisher_address)
-    .fetch_one(&state.db)
-    .await
-    .map_err(|err| db_internal_error("upsert publisher", err))?;
+    if let Some(organization_id) = req.organization_id {
+        let role =
+            organization_handlers::authorize_member(&state.db, organization_id, publisher.id)
+                .await?;
+        auth::require_role(
+            Some(role),
+            OrganizationRole::Maintainer,
+            &format!("organization {}", organization_id),
+        )?;
+    }
 
-    // TODO: Fetch WASM hash from Stellar network
-    let wasm_hash = "placeholder_hash".to_string();
+    // Validation guarantees a signature always comes with its key and artifact
+    let signing_key = match (&wasm, &req.signature, &req.public_key) {
+        (Some(bytes), Some(signature), Some(public_key)) => {
+            signing::verify_artifact(bytes, signature, public_key).map_err(|reason| {
+                ApiError::unprocessable(
+                    ErrorCode::SignatureInvalid,
+                    format!("Artifact signature is invalid: {}", reason),
+                )
+            })?;
+            Some(signing::normalize_key(public_key))
+        }
+        _ => None,
+    };
+
+    let wasm_hash = match &wasm {
+        Some(bytes) => artifacts::wasm_sha256(bytes),
+        // TODO: Fetch WASM hash from Stellar network
+        None => "placeholder_hash".to_string(),
+    };
+
+    let version = req
+        .version
+        .as_deref()
+        .map(parse_publish_version)
+        .transpose()?
+        .map(|v| v.to_string());
+
+    if let Some(metadata) = &req.metadata {
+        validate_publish_metadata(&state, req.category.as_deref(), metadata).await?;
+    }
+
+    // An unreadable spec is stored as such; it never fails the publish
+    let contract_abi = wasm.as_deref().map(abi::ContractAbi::from_wasm);
+
+    // The contract row, its new version and everything stored with them
+    // (artifact, ABI, README, dependencies) commit together, so a duplicate
+    // version or a failed write leaves the existing entry untouched.
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin publish", err))?;
 
+    // Republishing the same contract adds a version to the existing entry;
+    // only its publisher, or an owner or maintainer of its organization, may
+    // do that. An existing organization is kept; moving is a transfer.
     let contract: Contract = sqlx::query_as(
-        "INSERT INTO contracts (contract_id, wasm_hash, name, description, publisher_id, network, category, tags)
-         VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+        "INSERT INTO contracts (contract_id, wasm_hash, name, description, publisher_id, network, category, tags, metadata, organization_id, visibility)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $11, COALESCE($12, 'public'))
+         ON CONFLICT (contract_id, network) DO UPDATE SET
+             wasm_hash = CASE WHEN $10 THEN EXCLUDED.wasm_hash ELSE contracts.wasm_hash END,
+             name = EXCLUDED.name,
+             description = EXCLUDED.description,
+             category = EXCLUDED.category,
+             tags = EXCLUDED.tags,
+             metadata = COALESCE(EXCLUDED.metadata, contracts.metadata),
+             organization_id = COALESCE(contracts.organization_id, EXCLUDED.organization_id),
+             visibility = COALESCE($12, contracts.visibility),
+             updated_at = NOW()
+         WHERE (contracts.organization_id IS NULL AND contracts.publisher_id = EXCLUDED.publisher_id)
+            OR contracts.organization_id IN (
+                SELECT organization_id FROM organization_members
+                WHERE publisher_id = EXCLUDED.publisher_id AND role IN ('owner', 'maintainer')
+            )
          RETURNING *",
     )
     .bind(&req.contract_id)
@@ -319,9 +2167,122 @@ pub async fn publish_contract(
     .bind(&req.network)
     .bind(&req.category)
     .bind(&req.tags)
-    .fetch_one(&state.db)
+    .bind(&req.metadata)
+    .bind(wasm.is_some())
+    .bind(req.organization_id)
+    .bind(req.visibility)
+    .fetch_optional(&mut *tx)
     .await
-    .map_err(|err| db_internal_error("create contract", err))?;
+    .map_err(|err| db_internal_error("create contract", err))?
+    .ok_or_else(auth::forbidden)?;
+
+    let ipfs = wasm.as_ref().and_then(|_| ipfs::IpfsClient::from_env());
+    let version_id: Option<Uuid> = match &version {
+        Some(version) => Some(
+            sqlx::query_scalar(
+                "INSERT INTO contract_versions
+                     (contract_id, version, wasm_hash, source_url, ipfs_status, signed, signing_key,
+                      release_notes, build_info)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)
+                 RETURNING id",
+            )
+            .bind(contract.id)
+            .bind(version)
+            .bind(&wasm_hash)
+            .bind(&req.source_url)
+            .bind(ipfs.as_ref().map(|_| ipfs::STATUS_PENDING))
+            .bind(signing_key.is_some())
+            .bind(&signing_key)
+            .bind(&req.changelog)
+            .bind(req.build_info.as_ref().map(sqlx::types::Json))
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|err| version_insert_error(err, version))?,
+        ),
+        None => None,
+    };
+
+    // Validation guarantees a version whenever there is an artifact
+    if let (Some(version), Some(contract_abi)) = (&version, &contract_abi) {
+        abi::store(&mut *tx, contract.id, version, contract_abi)
+            .await
+            .map_err(|err| db_internal_error("store abi", err))?;
+    }
+
+    if let Some(markdown) = &req.readme {
+        readme::store(&mut *tx, contract.id, markdown)
+            .await
+            .map_err(|err| db_internal_error("store readme", err))?;
+    }
+
+    sqlx::query(
+        "INSERT INTO contract_deployments (contract_id, environment, status, wasm_hash, activated_at)
+         VALUES ($1, 'blue', 'active', $2, NOW())
+         ON CONFLICT (contract_id, environment) DO NOTHING",
+    )
+    .bind(contract.id)
+    .bind(&wasm_hash)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("create initial blue deployment", err))?;
+
+    // The declared set replaces the stored one
+    let dependency_names: Vec<&str> = req
+        .dependencies
+        .iter()
+        .map(|dep| dep.name.as_str())
+        .collect();
+    sqlx::query(
+        "DELETE FROM contract_dependencies
+         WHERE contract_id = $1 AND dependency_name <> ALL($2)",
+    )
+    .bind(contract.id)
+    .bind(&dependency_names)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("drop undeclared dependencies", err))?;
+
+    // A dependency resolves by its on-chain ID, or else by name among this
+    // publisher's contracts on the same network; another publisher's
+    // same-named contract is never picked, and an ambiguous name stays
+    // unresolved.
+    for dep in &req.dependencies {
+        sqlx::query(
+            "INSERT INTO contract_dependencies
+                (contract_id, dependency_name, dependency_contract_id, version_constraint)
+             VALUES ($1, $2, CASE
+                 WHEN $5::text IS NOT NULL THEN
+                     (SELECT id FROM contracts WHERE contract_id = $5 AND network = $3)
+                 ELSE
+                     (SELECT (array_agg(id))[1] FROM contracts
+                      WHERE name = $2 AND network = $3 AND publisher_id = $6 AND id <> $1
+                      HAVING COUNT(*) = 1)
+             END, $4)
+             ON CONFLICT (contract_id, dependency_name) DO UPDATE
+             SET dependency_contract_id = EXCLUDED.dependency_contract_id,
+                 version_constraint = EXCLUDED.version_constraint",
+        )
+        .bind(contract.id)
+        .bind(&dep.name)
+        .bind(&contract.network)
+        .bind(&dep.version_constraint)
+        .bind(&dep.contract_id)
+        .bind(contract.publisher_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| db_internal_error("store dependency", err))?;
+    }
+
+    if let (Some(version_id), Some(wasm)) = (version_id, &wasm) {
+        artifacts::store_artifact(&mut *tx, version_id, wasm)
+            .await
+            .map_err(|err| db_internal_error("store artifact", err))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit publish", err))?;
+    metrics::record_publisher_publish(&caller);
 
     // Fire-and-forget analytics event
     let pool = state.db.clone();
@@ -342,28 +2303,137 @@ pub async fn publish_contract(
             tracing::warn!(error = ?err, "failed to record contract_published event");
         }
     });
-    sqlx::query(
-        "INSERT INTO contract_deployments (contract_id, environment, status, wasm_hash, activated_at)
-         VALUES ($1, 'blue', 'active', $2, NOW())
-         ON CONFLICT (contract_id, environment) DO NOTHING",
-    )
-    .bind(contract.id)
-    .bind(&wasm_hash)
-    .execute(&state.db)
-    .await
-    .map_err(|err| db_internal_error("create initial blue deployment", err))?;
 
-    Ok(Json(contract))
+    // Pinning runs after the commit, so it always finds the version row
+    if let (Some(version), Some(version_id), Some(wasm), Some(client)) =
+        (&version, version_id, wasm, ipfs)
+    {
+        let filename = artifacts::artifact_filename(&contract.name, version);
+        ipfs::spawn_pin(client, state.db.clone(), version_id, filename, wasm);
+    }
+
+    // Results benchmarked against this version (e.g. in CI before publishing)
+    // are compared with the previous version so regressions surface now. The
+    // publish is already committed, so a failed check must not turn it into
+    // an error response.
+    let benchmark_warnings = match &version {
+        Some(version) => {
+            match benchmark_handlers::check_version_regressions(&state, contract.id, version).await {
+                Ok(warnings) => warnings,
+                Err(err) => {
+                    tracing::warn!(error = ?err, contract_id = %contract.id, "benchmark regression check failed");
+                    Vec::new()
+                }
+            }
+        }
+        None => Vec::new(),
+    };
+
+    if contract.visibility == ContractVisibility::Public {
+        webhooks::dispatch_contract_published(state.db.clone(), contract.clone());
+    }
+    state.stats.invalidate();
+
+    Ok(Json(PublishResponse {
+        contract,
+        benchmark_warnings,
+    }))
+}
+
+/// Versions must be strict semver (`1.2.3`, `1.2.3-rc.1`); `1.2` or `v1.2.3`
+/// are rejected so range resolution never has to guess.
+fn parse_publish_version(raw: &str) -> ApiResult<semver::Version> {
+    semver::Version::parse(raw.trim()).map_err(|err| {
+        ApiError::unprocessable(
+            ErrorCode::InvalidSemver,
+            format!("'{}' is not a valid semantic version: {}", raw, err),
+        )
+    })
+}
+
+/// The `(contract_id, version)` unique constraint is the duplicate check, so
+/// two concurrent publishes of one version can't both succeed.
+fn version_insert_error(err: sqlx::Error, version: &str) -> ApiError {
+    match err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::new(
+            StatusCode::CONFLICT,
+            ErrorCode::DuplicateVersion,
+            format!("Version {} has already been published for this contract", version),
+        )
+        .with_details(serde_json::json!({ "version": version })),
+        err => db_internal_error("create version", err),
+    }
+}
+
+/// 422 listing every schema violation when `metadata` doesn't satisfy the
+/// schema registered for `category` (or the default schema)
+async fn validate_publish_metadata(
+    state: &AppState,
+    category: Option<&str>,
+    metadata: &serde_json::Value,
+) -> ApiResult<()> {
+    if !metadata.is_object() {
+        return Err(ApiError::unprocessable(
+            ErrorCode::InvalidMetadata,
+            "metadata must be a JSON object",
+        ));
+    }
+    let Some(schema) = metadata_schema::load_for_category(&state.db, category)
+        .await
+        .map_err(|err| db_internal_error("load metadata schema", err))?
+    else {
+        return Ok(());
+    };
+    let schema = metadata_schema::compile(&schema).map_err(|err| {
+        tracing::error!(error = %err, "stored metadata schema does not compile");
+        ApiError::internal("The metadata schema for this category is invalid")
+    })?;
+
+    let errors = metadata_schema::validate(&schema, metadata);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    Err(ApiError::unprocessable(
+        ErrorCode::InvalidMetadata,
+        format!("metadata failed schema validation ({} errors)", errors.len()),
+    )
+    .with_details(serde_json::json!({ "errors": errors })))
 }
 
 /// Verify a contract
+///
+/// `contract_id` is treated as the deployed address: the WASM hash reported
+/// by Soroban RPC for the contract's network is compared with the sha256 of
+/// the stored artifact and a matching contract is marked verified. `network`
+/// may be omitted when the address is registered on only one network.
+#[utoipa::path(
+    post,
+    path = "/api/contracts/verify",
+    tag = "contracts",
+    request_body = VerifyRequest,
+    responses(
+        (status = 200, description = "Verification result, or `pending` for an unregistered address", body = VerificationResult),
+        (status = 400, description = "contract.ambiguous_network: the address is registered on several networks and no network was given"),
+        (status = 404, description = "Contract, version or artifact not found"),
+        (status = 502, description = "Soroban RPC unreachable or returned an unexpected response"),
+    ),
+)]
 pub async fn verify_contract(
     State(state): State<AppState>,
     payload: Result<Json<VerifyRequest>, JsonRejection>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let Json(req) = payload.map_err(map_json_rejection)?;
 
-    // TODO: Implement full verification logic
+    let network = match req.network.clone() {
+        Some(network) => Some(network),
+        None => declared_network(&state, &req.contract_id).await?,
+    };
+    if let Some(network) = network {
+        let result = verify_against_chain(&state, &req, network).await?;
+        return Ok(Json(serde_json::to_value(result).map_err(|err| {
+            ApiError::internal(format!("failed to serialize verification result: {}", err))
+        })?));
+    }
 
     // Fire-and-forget analytics event
     // We parse the contract_id string as UUID for the event; if it fails we skip.
@@ -391,7 +2461,164 @@ pub async fn verify_contract(
     })))
 }
 
-/// Create a publisher
+/// The network `address` is registered on, so verification queries the RPC
+/// the publisher declared; `None` when it isn't registered at all
+async fn declared_network(state: &AppState, address: &str) -> ApiResult<Option<Network>> {
+    let networks: Vec<Network> =
+        sqlx::query_scalar("SELECT network FROM contracts WHERE contract_id = $1")
+            .bind(address)
+            .fetch_all(&state.db)
+            .await
+            .map_err(|err| db_internal_error("look up contract network", err))?;
+    match networks.len() {
+        0 => Ok(None),
+        1 => Ok(networks.into_iter().next()),
+        _ => Err(ApiError::bad_request(
+            ErrorCode::AmbiguousNetwork,
+            format!(
+                "Contract {} is registered on several networks; pass network to choose one",
+                address
+            ),
+        )
+        .with_details(serde_json::json!({ "networks": networks }))),
+    }
+}
+
+/// Map a Soroban RPC failure to the API error a verification reports
+pub(crate) fn rpc_api_error(err: soroban_rpc::RpcError) -> ApiError {
+    match err {
+        soroban_rpc::RpcError::InvalidAddress => {
+            ApiError::bad_request(ErrorCode::InvalidContractAddress, err.to_string())
+        }
+        soroban_rpc::RpcError::NotDeployed => {
+            ApiError::not_found(ErrorCode::ContractNotDeployed, err.to_string())
+        }
+        soroban_rpc::RpcError::NotConfigured(_) => ApiError::new(
+            StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::RpcNotConfigured,
+            err.to_string(),
+        ),
+        soroban_rpc::RpcError::Unreachable(_) | soroban_rpc::RpcError::Malformed(_) => {
+            ApiError::bad_gateway(ErrorCode::RpcUnavailable, err.to_string())
+        }
+    }
+}
+
+async fn verify_against_chain(
+    state: &AppState,
+    req: &VerifyRequest,
+    network: Network,
+) -> ApiResult<VerificationResult> {
+    let contract: Option<(Uuid,)> =
+        sqlx::query_as("SELECT id FROM contracts WHERE contract_id = $1 AND network = $2")
+            .bind(&req.contract_id)
+            .bind(&network)
+            .fetch_optional(&state.db)
+            .await
+            .map_err(|err| db_internal_error("look up contract for verification", err))?;
+    let Some((contract_uuid,)) = contract else {
+        return Err(ApiError::not_found(
+            ErrorCode::ContractNotFound,
+            format!("No contract {} registered on {:?}", req.contract_id, network),
+        ));
+    };
+
+    let version: Option<(Uuid, String)> = match &req.version {
+        Some(version) => sqlx::query_as(
+            "SELECT id, version FROM contract_versions WHERE contract_id = $1 AND version = $2",
+        )
+        .bind(contract_uuid)
+        .bind(version)
+        .fetch_optional(&state.db)
+        .await,
+        None => sqlx::query_as(
+            "SELECT id, version FROM contract_versions WHERE contract_id = $1
+             ORDER BY created_at DESC LIMIT 1",
+        )
+        .bind(contract_uuid)
+        .fetch_optional(&state.db)
+        .await,
+    }
+    .map_err(|err| db_internal_error("look up version for verification", err))?;
+    let Some((version_id, version)) = version else {
+        return Err(ApiError::not_found(
+            ErrorCode::VersionNotFound,
+            "No matching version found for this contract",
+        ));
+    };
+
+    let wasm = artifacts::load_artifact(&state.db, version_id)
+        .await
+        .map_err(|err| db_internal_error("load artifact for verification", err))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::ArtifactNotFound,
+                format!("Version {} has no stored WASM artifact", version),
+            )
+        })?;
+    let computed_hash = artifacts::wasm_sha256(&wasm);
+
+    let rpc_url = soroban_rpc::rpc_url_for(&network).map_err(rpc_api_error)?;
+    let on_chain_hash = soroban_rpc::fetch_wasm_hash(&rpc_url, &req.contract_id)
+        .await
+        .map_err(rpc_api_error)?;
+
+    let verified = on_chain_hash.eq_ignore_ascii_case(&computed_hash);
+    if verified {
+        sqlx::query("UPDATE contracts SET is_verified = true, updated_at = NOW() WHERE id = $1")
+            .bind(contract_uuid)
+            .execute(&state.db)
+            .await
+            .map_err(|err| db_internal_error("mark contract verified", err))?;
+        state.stats.invalidate();
+
+        let pool = state.db.clone();
+        let compiler_version = req.compiler_version.clone();
+        tokio::spawn(async move {
+            if let Err(err) = analytics::record_event(
+                &pool,
+                AnalyticsEventType::ContractVerified,
+                contract_uuid,
+                None,
+                None,
+                Some(serde_json::json!({ "compiler_version": compiler_version })),
+            )
+            .await
+            {
+                tracing::warn!(error = ?err, "failed to record contract_verified event");
+            }
+        });
+    } else {
+        tracing::warn!(
+            contract_id = %req.contract_id,
+            %on_chain_hash,
+            %computed_hash,
+            "deployed WASM does not match stored artifact"
+        );
+    }
+
+    Ok(VerificationResult {
+        verified,
+        contract_id: req.contract_id.clone(),
+        network,
+        version,
+        on_chain_hash,
+        computed_hash,
+    })
+}
+
+/// Create a publisher (requires an API key)
+#[utoipa::path(
+    post,
+    path = "/api/publishers",
+    tag = "publishers",
+    request_body = Publisher,
+    responses(
+        (status = 200, description = "Created publisher", body = Publisher),
+        (status = 401, description = "Missing or invalid API key"),
+    ),
+    security(("api_key" = [])),
+)]
 pub async fn create_publisher(
     State(state): State<AppState>,
     payload: Result<Json<Publisher>, JsonRejection>,
@@ -411,11 +2638,24 @@ pub async fn create_publisher(
     .fetch_one(&state.db)
     .await
     .map_err(|err| db_internal_error("create publisher", err))?;
+    state.stats.invalidate();
 
     Ok(Json(created))
 }
 
 /// Get publisher by ID
+#[utoipa::path(
+    get,
+    path = "/api/publishers/{id}",
+    tag = "publishers",
+    params(
+        ("id" = Uuid, Path, description = "Publisher UUID"),
+    ),
+    responses(
+        (status = 200, description = "Publisher details", body = Publisher),
+        (status = 404, description = "Publisher not found"),
+    ),
+)]
 pub async fn get_publisher(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -426,7 +2666,7 @@ pub async fn get_publisher(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "PublisherNotFound",
+                ErrorCode::PublisherNotFound,
                 format!("No publisher found with ID: {}", id),
             ),
             _ => db_internal_error("get publisher by id", err),
@@ -435,38 +2675,500 @@ pub async fn get_publisher(
     Ok(Json(publisher))
 }
 
-/// Get all contracts by a publisher
-pub async fn get_publisher_contracts(
+/// Update a publisher's profile. Fields left out of the body are unchanged;
+/// an empty string clears one. Only the publisher (or an admin) may: owners
+/// add organization members without their consent, so owning an
+/// organization someone belongs to grants nothing over their profile.
+#[utoipa::path(
+    put,
+    path = "/api/publishers/{id}",
+    tag = "publishers",
+    params(
+        ("id" = Uuid, Path, description = "Publisher UUID"),
+    ),
+    request_body = UpdatePublisherRequest,
+    responses(
+        (status = 200, description = "Updated publisher", body = Publisher),
+        (status = 400, description = "Invalid URL or field too long"),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 403, description = "Caller is not the publisher"),
+        (status = 404, description = "Publisher not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn update_publisher(
     State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
     Path(id): Path<Uuid>,
-) -> ApiResult<Json<Vec<Contract>>> {
-    let contracts: Vec<Contract> =
-        sqlx::query_as("SELECT * FROM contracts WHERE publisher_id = $1 ORDER BY created_at DESC")
+    ValidatedJson(req): ValidatedJson<UpdatePublisherRequest>,
+) -> ApiResult<Json<Publisher>> {
+    caller.authorize_publisher(id)?;
+
+    let publisher: Publisher = sqlx::query_as(
+        "UPDATE publishers SET
+             display_name = CASE WHEN $2::text IS NULL THEN display_name ELSE NULLIF($2, '') END,
+             bio = CASE WHEN $3::text IS NULL THEN bio ELSE NULLIF($3, '') END,
+             website = CASE WHEN $4::text IS NULL THEN website ELSE NULLIF($4, '') END,
+             avatar_url = CASE WHEN $5::text IS NULL THEN avatar_url ELSE NULLIF($5, '') END,
+             updated_at = NOW()
+         WHERE id = $1
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(&req.display_name)
+    .bind(&req.bio)
+    .bind(&req.website)
+    .bind(&req.avatar_url)
+    .fetch_optional(&state.db)
+    .await
+    .map_err(|err| db_internal_error("update publisher", err))?
+    .ok_or_else(|| publisher_not_found(id))?;
+
+    Ok(Json(publisher))
+}
+
+fn publisher_not_found(id: Uuid) -> ApiError {
+    ApiError::not_found(
+        ErrorCode::PublisherNotFound,
+        format!("No publisher found with ID: {}", id),
+    )
+}
+
+/// Set or clear `deactivated_at`, flag the publisher's contracts to match,
+/// and record the change. Changing to the current state is a no-op.
+async fn set_publisher_deactivated(
+    state: &AppState,
+    caller: &Caller,
+    id: Uuid,
+    deactivate: bool,
+) -> ApiResult<Publisher> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin publisher deactivation", err))?;
+
+    let changed: Option<Publisher> = sqlx::query_as(
+        "UPDATE publishers
+         SET deactivated_at = CASE WHEN $2 THEN NOW() END, updated_at = NOW()
+         WHERE id = $1 AND (deactivated_at IS NULL) = $2
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(deactivate)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("set publisher deactivated", err))?;
+
+    let Some(publisher) = changed else {
+        return sqlx::query_as("SELECT * FROM publishers WHERE id = $1")
             .bind(id)
-            .fetch_all(&state.db)
+            .fetch_optional(&state.db)
             .await
-            .map_err(|err| db_internal_error("list publisher contracts", err))?;
+            .map_err(|err| db_internal_error("get publisher by id", err))?
+            .ok_or_else(|| publisher_not_found(id));
+    };
 
-    Ok(Json(contracts))
+    // Contracts are flagged, never removed: consumers keep downloading them.
+    // Contracts that reach the publisher later are flagged by a trigger.
+    let contracts =
+        sqlx::query("UPDATE contracts SET publisher_deactivated = $2 WHERE publisher_id = $1")
+            .bind(id)
+            .bind(deactivate)
+            .execute(&mut *tx)
+            .await
+            .map_err(|err| db_internal_error("flag deactivated publisher contracts", err))?
+            .rows_affected();
+
+    let action = if deactivate {
+        admin_audit::PUBLISHER_DEACTIVATED
+    } else {
+        admin_audit::PUBLISHER_REACTIVATED
+    };
+    admin_audit::record(
+        &mut *tx,
+        caller,
+        action,
+        "publisher",
+        &id.to_string(),
+        Some(serde_json::json!({ "contracts": contracts })),
+    )
+    .await
+    .map_err(|err| db_internal_error("record publisher deactivation", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit publisher deactivation", err))?;
+    Ok(publisher)
+}
+
+/// Deactivate a publisher account. Their API keys stop authenticating and
+/// they can no longer publish; their contracts stay downloadable, flagged
+/// with `publisher_deactivated`. Nothing is deleted, so it can be undone
+/// with `/reactivate`.
+#[utoipa::path(
+    post,
+    path = "/api/publishers/{id}/deactivate",
+    tag = "publishers",
+    params(
+        ("id" = Uuid, Path, description = "Publisher UUID"),
+    ),
+    responses(
+        (status = 200, description = "Deactivated publisher", body = Publisher),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 403, description = "Key belongs to a different publisher"),
+        (status = 404, description = "Publisher not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn deactivate_publisher(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Publisher>> {
+    caller.authorize_publisher(id)?;
+    let publisher = set_publisher_deactivated(&state, &caller, id, true).await?;
+    Ok(Json(publisher))
+}
+
+/// Reactivate a deactivated publisher, restoring their API keys. Admin
+/// only, since a deactivated publisher's own keys no longer authenticate.
+#[utoipa::path(
+    post,
+    path = "/api/publishers/{id}/reactivate",
+    tag = "publishers",
+    params(
+        ("id" = Uuid, Path, description = "Publisher UUID"),
+    ),
+    responses(
+        (status = 200, description = "Reactivated publisher", body = Publisher),
+        (status = 401, description = "Missing or invalid API key"),
+        (status = 403, description = "Not the admin key"),
+        (status = 404, description = "Publisher not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn reactivate_publisher(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Publisher>> {
+    caller.require_admin()?;
+    let publisher = set_publisher_deactivated(&state, &caller, id, false).await?;
+    Ok(Json(publisher))
+}
+
+/// Get all contracts by a publisher
+#[utoipa::path(
+    get,
+    path = "/api/publishers/{id}/contracts",
+    tag = "publishers",
+    params(
+        ("id" = Uuid, Path, description = "Publisher UUID"),
+        PageParams,
+    ),
+    responses(
+        (status = 200, description = "Public contracts by this publisher, newest first", body = PaginatedContracts),
+    ),
+)]
+pub async fn get_publisher_contracts(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<Contract>>> {
+    let contracts: Vec<Contract> = sqlx::query_as(
+        "SELECT * FROM contracts WHERE publisher_id = $1 AND visibility = 'public'
+         ORDER BY created_at DESC, id DESC
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(id)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list publisher contracts", err))?;
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM contracts WHERE publisher_id = $1 AND visibility = 'public'",
+    )
+    .bind(id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count publisher contracts", err))?;
+
+    Ok(Json(Paginated::new(
+        contracts,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct CompareParams {
+    pub a: Uuid,
+    pub b: Uuid,
+}
+
+/// Two contracts side by side: metadata, latest version, score, benchmark
+/// headlines and scan findings by severity, all from one snapshot
+#[utoipa::path(
+    get,
+    path = "/api/contracts/compare",
+    tag = "contracts",
+    params(
+        CompareParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Both contracts, read at a single timestamp"),
+        (status = 404, description = "One or both contracts unknown or private; details.missing names which"),
+    ),
+)]
+pub async fn compare_contracts(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Query(params): Query<CompareParams>,
+) -> ApiResult<Json<compare::ComparisonResponse>> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin comparison", err))?;
+    sqlx::query("SET TRANSACTION ISOLATION LEVEL REPEATABLE READ, READ ONLY")
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| db_internal_error("set comparison isolation", err))?;
+
+    let read_at: chrono::DateTime<chrono::Utc> = sqlx::query_scalar("SELECT NOW()")
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|err| db_internal_error("read snapshot time", err))?;
+    let a = compare::load(&mut tx, params.a)
+        .await
+        .map_err(|err| db_internal_error("load comparison side a", err))?;
+    let b = compare::load(&mut tx, params.b)
+        .await
+        .map_err(|err| db_internal_error("load comparison side b", err))?;
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("end comparison", err))?;
+
+    // A side the caller may not see is reported as missing, like get_contract
+    let caller = caller.map(|Extension(caller)| caller);
+    let mut sides = [a, b];
+    for side in &mut sides {
+        if let Some(compared) = side.as_ref() {
+            let visible = is_visible(
+                &state.db,
+                caller.as_ref(),
+                share_token.as_deref(),
+                &compared.contract,
+            )
+            .await?;
+            if !visible {
+                *side = None;
+            }
+        }
+    }
+    let [a, b] = sides;
+
+    match (a, b) {
+        (Some(a), Some(b)) => Ok(Json(compare::ComparisonResponse { a, b, read_at })),
+        (a, b) => {
+            let missing: Vec<(&str, Uuid)> = [("a", params.a, a.is_none()), ("b", params.b, b.is_none())]
+                .into_iter()
+                .filter(|(_, _, missing)| *missing)
+                .map(|(side, id, _)| (side, id))
+                .collect();
+            let names: Vec<String> = missing
+                .iter()
+                .map(|(side, id)| format!("{} ({})", side, id))
+                .collect();
+            Err(ApiError::not_found(
+                ErrorCode::ContractNotFound,
+                format!("No contract found for {}", names.join(" or ")),
+            )
+            .with_details(serde_json::json!({
+                "missing": missing.iter().map(|(side, _)| side).collect::<Vec<_>>(),
+            })))
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TrendingParams {
+    /// `1d`, `7d` (default) or `30d`
+    pub window: Option<String>,
+    /// Number of contracts (default 20, max 100)
+    pub limit: Option<i64>,
+}
+
+/// Contracts ranked by decayed recent downloads, stars and publishes.
+/// Ties break on all-time downloads.
+#[utoipa::path(
+    get,
+    path = "/api/contracts/trending",
+    tag = "contracts",
+    params(TrendingParams),
+    responses(
+        (status = 200, description = "Trending contracts, highest score first", body = trending::TrendingResponse),
+        (status = 400, description = "Unknown window or out-of-range limit"),
+    ),
+)]
+pub async fn get_trending_contracts(
+    State(state): State<AppState>,
+    Query(params): Query<TrendingParams>,
+) -> ApiResult<Json<trending::TrendingResponse>> {
+    let window = match params.window.as_deref() {
+        None => trending::TrendWindow::Week,
+        Some(raw) => trending::TrendWindow::parse(raw).ok_or_else(|| {
+            ApiError::bad_request(
+                ErrorCode::InvalidWindow,
+                format!("window must be one of 1d, 7d, 30d (got '{}')", raw),
+            )
+        })?,
+    };
+    let limit = params.limit.unwrap_or(trending::DEFAULT_TRENDING_LIMIT);
+    if !(1..=trending::MAX_TRENDING_LIMIT).contains(&limit) {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidPagination,
+            format!("limit must be between 1 and {}", trending::MAX_TRENDING_LIMIT),
+        ));
+    }
+
+    let items = trending::trending(&state.db, window, limit)
+        .await
+        .map_err(|err| db_internal_error("list trending contracts", err))?;
+    Ok(Json(trending::TrendingResponse {
+        window: window.as_str().to_string(),
+        items,
+    }))
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct TimeseriesParams {
+    /// `publishes`, `scans` or `new_publishers`
+    pub metric: String,
+    /// Start of the window (RFC 3339); defaults to 30 days before `to`
+    pub from: Option<chrono::DateTime<chrono::Utc>>,
+    /// End of the window (RFC 3339); defaults to now
+    pub to: Option<chrono::DateTime<chrono::Utc>>,
+    /// `hour`, `day` (default), `week` or `month`
+    pub interval: Option<String>,
+}
+
+/// Registry activity bucketed over time, with empty buckets reported as zero
+#[utoipa::path(
+    get,
+    path = "/api/analytics/timeseries",
+    tag = "analytics",
+    params(TimeseriesParams),
+    responses(
+        (status = 200, description = "One point per bucket, oldest first"),
+        (status = 400, description = "Unknown metric or interval, or invalid or oversized range"),
+    ),
+)]
+pub async fn get_analytics_timeseries(
+    State(state): State<AppState>,
+    Query(params): Query<TimeseriesParams>,
+) -> ApiResult<Json<analytics::TimeseriesResponse>> {
+    let metric = analytics::TimeseriesMetric::parse(&params.metric).ok_or_else(|| {
+        let allowed: Vec<&str> = analytics::TimeseriesMetric::ALL
+            .iter()
+            .map(|m| m.as_str())
+            .collect();
+        ApiError::bad_request(
+            ErrorCode::InvalidMetric,
+            format!("metric must be one of {} (got '{}')", allowed.join(", "), params.metric),
+        )
+    })?;
+    let interval = match params.interval.as_deref() {
+        None => analytics::BucketInterval::Day,
+        Some(raw) => analytics::BucketInterval::parse(raw).ok_or_else(|| {
+            ApiError::bad_request(
+                ErrorCode::InvalidInterval,
+                format!("interval must be one of hour, day, week, month (got '{}')", raw),
+            )
+        })?,
+    };
+
+    let to = params.to.unwrap_or_else(chrono::Utc::now);
+    let from = params.from.unwrap_or(to - chrono::Duration::days(30));
+    if from > to {
+        return Err(ApiError::bad_request(ErrorCode::InvalidRange, "from must not be after to"));
+    }
+    let buckets = analytics::bucket_starts(from, to, interval).ok_or_else(|| {
+        ApiError::bad_request(
+            ErrorCode::RangeTooLarge,
+            format!(
+                "range spans more than {} {} buckets; narrow it or use a coarser interval",
+                analytics::MAX_TIMESERIES_BUCKETS,
+                interval.as_str()
+            ),
+        )
+    })?;
+
+    let points = analytics::timeseries(&state.db, metric, interval, from, to, &buckets)
+        .await
+        .map_err(|err| db_internal_error("analytics timeseries", err))?;
+
+    Ok(Json(analytics::TimeseriesResponse {
+        metric,
+        interval,
+        from,
+        to,
+        points,
+    }))
+}
+
+/// Run analytics aggregation immediately instead of waiting for the next tick
+#[utoipa::path(
+    post,
+    path = "/api/admin/aggregate",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Rows upserted, events pruned and run duration"),
+        (status = 403, description = "Caller is not an admin"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn trigger_aggregation(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+) -> ApiResult<Json<crate::aggregation::AggregationRun>> {
+    caller.require_admin()?;
+    let run = crate::aggregation::run_once(&state.db)
+        .await
+        .map_err(|err| db_internal_error("run aggregation", err))?;
+    Ok(Json(run))
 }
 
 /// Get analytics for a specific contract
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/analytics",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Deployment and interaction analytics"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_contract_analytics(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<Uuid>,
 ) -> ApiResult<Json<ContractAnalyticsResponse>> {
-    // Verify the contract exists
-    let _contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
-        .bind(id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("No contract found with ID: {}", id),
-            ),
-            _ => db_internal_error("get contract for analytics", err),
-        })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
 
     let thirty_days_ago = chrono::Utc::now() - chrono::Duration::days(30);
 
@@ -541,6 +3243,14 @@ pub async fn get_contract_analytics(
         timeline,
     }))
 }
+#[utoipa::path(
+    post,
+    path = "/api/deployments/green",
+    tag = "deployments",
+    responses(
+        (status = 200, description = "Green deployment created"),
+    ),
+)]
 pub async fn deploy_green(
     State(state): State<AppState>,
     payload: Result<Json<DeployGreenRequest>, JsonRejection>,
@@ -549,7 +3259,7 @@ pub async fn deploy_green(
 
     let contract_uuid = Uuid::parse_str(&req.contract_id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidContractId",
+            ErrorCode::InvalidContractId,
             format!("Invalid contract ID format: {}", req.contract_id),
         )
     })?;
@@ -561,7 +3271,7 @@ pub async fn deploy_green(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("No contract found with ID: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for deploy", err),
@@ -584,6 +3294,14 @@ pub async fn deploy_green(
 }
 
 
+#[utoipa::path(
+    post,
+    path = "/api/deployments/switch",
+    tag = "deployments",
+    responses(
+        (status = 200, description = "Traffic switched"),
+    ),
+)]
 pub async fn switch_deployment(
     State(state): State<AppState>,
     payload: Result<Json<SwitchDeploymentRequest>, JsonRejection>,
@@ -597,7 +3315,7 @@ pub async fn switch_deployment(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for switch", err),
@@ -638,19 +3356,19 @@ pub async fn switch_deployment(
     if let Some(ref green) = green_deployment {
         if !force && green.status != DeploymentStatus::Testing {
             return Err(ApiError::bad_request(
-                "InvalidDeploymentStatus",
+                ErrorCode::InvalidDeploymentStatus,
                 "Green deployment must be in testing status before switch",
             ));
         }
         if !force && green.health_checks_passed < 3 {
             return Err(ApiError::bad_request(
-                "InsufficientHealthChecks",
+                ErrorCode::InsufficientHealthChecks,
                 "Green deployment must pass at least 3 health checks before switch",
             ));
         }
     } else {
         return Err(ApiError::bad_request(
-            "NoGreenDeployment",
+            ErrorCode::NoGreenDeployment,
             "No green deployment found",
         ));
     }
@@ -698,6 +3416,17 @@ pub async fn switch_deployment(
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/deployments/{contract_id}/rollback",
+    tag = "deployments",
+    params(
+        ("contract_id" = String, Path, description = "On-chain contract ID"),
+    ),
+    responses(
+        (status = 200, description = "Deployment rolled back"),
+    ),
+)]
 pub async fn rollback_deployment(
     State(state): State<AppState>,
     Path(contract_id): Path<String>,
@@ -708,7 +3437,7 @@ pub async fn rollback_deployment(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", contract_id),
             ),
             _ => db_internal_error("get contract for rollback", err),
@@ -749,7 +3478,7 @@ pub async fn rollback_deployment(
 
     if target_deployment.is_none() {
         return Err(ApiError::bad_request(
-            "NoDeploymentToRollback",
+            ErrorCode::NoDeploymentToRollback,
             format!("No {} deployment found to rollback to", to_env),
         ));
     }
@@ -797,6 +3526,14 @@ pub async fn rollback_deployment(
     })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/deployments/health",
+    tag = "deployments",
+    responses(
+        (status = 200, description = "Health check recorded"),
+    ),
+)]
 pub async fn report_health_check(
     State(state): State<AppState>,
     payload: Result<Json<HealthCheckRequest>, JsonRejection>,
@@ -809,7 +3546,7 @@ pub async fn report_health_check(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for health check", err),
@@ -854,21 +3591,33 @@ pub async fn report_health_check(
     })))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/deployments/status",
+    tag = "deployments",
+    params(
+        ("id" = String, Path, description = "On-chain contract ID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Blue/green deployment status"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_deployment_status(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE contract_id = $1")
-        .bind(&contract_id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("Contract not found: {}", contract_id),
-            ),
-            _ => db_internal_error("get contract", err),
-        })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract = fetch_visible_contract_by_address(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        &contract_id,
+    )
+    .await?;
 
     let deployments: Vec<ContractDeployment> = sqlx::query_as(
         "SELECT * FROM contract_deployments 
@@ -893,22 +3642,47 @@ pub async fn get_deployment_status(
 }
 
 pub async fn route_not_found() -> ApiError {
-    ApiError::not_found("RouteNotFound", "The requested endpoint does not exist")
+    ApiError::not_found(ErrorCode::RouteNotFound, "The requested endpoint does not exist")
 }
 
 use std::time::Duration;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Deserialize)]
 pub struct CacheParams {
     pub cache: Option<String>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/state/{key}",
+    tag = "contracts",
+    params(
+        ("id" = String, Path, description = "On-chain contract ID"),
+        ("key" = String, Path, description = "Storage key"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Contract state value"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_contract_state(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path((contract_id, key)): Path<(String, String)>,
     Query(params): Query<CacheParams>,
 ) -> ApiResult<Json<serde_json::Value>> {
+    // Checked before the cache, which would otherwise serve private state
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract_by_address(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        &contract_id,
+    )
+    .await?;
     let use_cache = params.cache.as_deref() == Some("on");
 
     // Try cache first if enabled
@@ -945,6 +3719,18 @@ pub async fn get_contract_state(
     Ok(Json(value))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/state/{key}",
+    tag = "contracts",
+    params(
+        ("id" = String, Path, description = "On-chain contract ID"),
+        ("key" = String, Path, description = "Storage key"),
+    ),
+    responses(
+        (status = 200, description = "State updated and cache invalidated"),
+    ),
+)]
 pub async fn update_contract_state(
     State(state): State<AppState>,
     Path((contract_id, key)): Path<(String, String)>,
@@ -991,22 +3777,29 @@ pub async fn get_cache_stats(
 /// exactly why a contract received its score. Scores are computed on demand
 /// from live DB data (verification status, latest audit, usage stats, age,
 /// and unresolved critical vulnerabilities).
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/trust-score",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Computed trust score"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_trust_score(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<Uuid>,
 ) -> ApiResult<Json<serde_json::Value>> {
     // ── 1. Load the contract ──────────────────────────────────────────────────
-    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
-        .bind(id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("No contract found with ID: {}", id),
-            ),
-            _ => db_internal_error("get contract for trust score", err),
-        })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract =
+        fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
 
     // ── 2. Latest audit score (optional) ─────────────────────────────────────
     let latest_audit_score: Option<f64> = sqlx::query_scalar(
@@ -1143,6 +3936,17 @@ pub struct GraphParams {
     pub network: Option<Network>,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/contracts/graph",
+    tag = "contracts",
+    params(
+        ("network" = Option<String>, Query, description = "Restrict the graph to one network"),
+    ),
+    responses(
+        (status = 200, description = "Dependency graph of all contracts"),
+    ),
+)]
 pub async fn get_contract_graph(
     State(state): State<AppState>,
     Query(params): Query<GraphParams>,
@@ -1288,8 +4092,15 @@ pub async fn get_contract(
 /// Get contract version history
 pub async fn get_contract_versions(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<Uuid>,
 ) -> Result<Json<Vec<ContractVersion>>, StatusCode> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id)
+        .await
+        .map_err(|err| err.status())?;
+
     let versions: Vec<ContractVersion> = sqlx::query_as(
         "SELECT * FROM contract_versions WHERE contract_id = $1 ORDER BY created_at DESC",
     )
@@ -1423,7 +4234,7 @@ use uuid::Uuid;
 
 use crate::{
     analytics,
-    error::{ApiError, ApiResult},
+    error::{ApiError, ApiResult, ErrorCode},
     state::AppState,
 };
 
@@ -1434,14 +4245,14 @@ pub fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
 
 fn map_json_rejection(err: JsonRejection) -> ApiError {
     ApiError::bad_request(
-        "InvalidRequest",
+        ErrorCode::InvalidRequest,
         format!("Invalid JSON payload: {}", err.body_text()),
     )
 }
 
 fn map_query_rejection(err: QueryRejection) -> ApiError {
     ApiError::bad_request(
-        "InvalidQuery",
+        ErrorCode::InvalidQuery,
         format!("Invalid query parameters: {}", err.body_text()),
     )
 }
@@ -1531,7 +4342,7 @@ pub async fn list_contracts(
     // bad input, bail early
     if page < 1 || limit < 1 || limit > 100 {
         return ApiError::bad_request(
-            "InvalidPagination",
+            ErrorCode::InvalidPagination,
             "page must be >= 1 and limit must be between 1 and 100",
         )
         .into_response();
@@ -1619,7 +4430,7 @@ pub async fn get_contract(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("No contract found with ID: {}", id),
             ),
             _ => db_internal_error("get contract by id", err),
@@ -1660,14 +4471,19 @@ pub async fn get_contract_abi(
 /// Get contract version history
 pub async fn get_contract_versions(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<ContractVersion>>> {
     let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidContractId",
+            ErrorCode::InvalidContractId,
             format!("Invalid contract ID format: {}", id),
         )
     })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), contract_uuid)
+        .await?;
 
     let versions: Vec<ContractVersion> = sqlx::query_as(
         "SELECT * FROM contract_versions WHERE contract_id = $1 ORDER BY created_at DESC",
@@ -1821,7 +4637,7 @@ pub async fn get_publisher(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "PublisherNotFound",
+                ErrorCode::PublisherNotFound,
                 format!("No publisher found with ID: {}", id),
             ),
             _ => db_internal_error("get publisher by id", err),
@@ -1847,21 +4663,13 @@ pub async fn get_publisher_contracts(
 
 /// Get analytics for a specific contract
 pub async fn get_contract_analytics(
-    State(state): State<AppState>,
-    Path(id): Path<Uuid>,
-) -> ApiResult<Json<ContractAnalyticsResponse>> {
-    // Verify the contract exists
-    let _contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
-        .bind(id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("No contract found with ID: {}", id),
-            ),
-            _ => db_internal_error("get contract for analytics", err),
-        })?;
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ContractAnalyticsResponse>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
 
     let thirty_days_ago = chrono::Utc::now() - chrono::Duration::days(30);
 
@@ -1948,7 +4756,7 @@ pub async fn deploy_green(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("No contract found with ID: {}", id),
             ),
             _ => db_internal_error("get contract for analytics", err),
@@ -2079,7 +4887,7 @@ pub async fn switch_deployment(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for switch", err),
@@ -2120,19 +4928,19 @@ pub async fn switch_deployment(
     if let Some(ref green) = green_deployment {
         if !force && green.status != DeploymentStatus::Testing {
             return Err(ApiError::bad_request(
-                "InvalidDeploymentStatus",
+                ErrorCode::InvalidDeploymentStatus,
                 "Green deployment must be in testing status before switch",
             ));
         }
         if !force && green.health_checks_passed < 3 {
             return Err(ApiError::bad_request(
-                "InsufficientHealthChecks",
+                ErrorCode::InsufficientHealthChecks,
                 "Green deployment must pass at least 3 health checks before switch",
             ));
         }
     } else {
         return Err(ApiError::bad_request(
-            "NoGreenDeployment",
+            ErrorCode::NoGreenDeployment,
             "No green deployment found",
         ));
     }
@@ -2190,7 +4998,7 @@ pub async fn rollback_deployment(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", contract_id),
             ),
             _ => db_internal_error("get contract for rollback", err),
@@ -2231,7 +5039,7 @@ pub async fn rollback_deployment(
 
     if target_deployment.is_none() {
         return Err(ApiError::bad_request(
-            "NoDeploymentToRollback",
+            ErrorCode::NoDeploymentToRollback,
             format!("No {} deployment found to rollback to", to_env),
         ));
     }
@@ -2291,7 +5099,7 @@ pub async fn report_health_check(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for health check", err),
@@ -2338,19 +5146,18 @@ pub async fn report_health_check(
 
 pub async fn get_deployment_status(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE contract_id = $1")
-        .bind(&contract_id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("Contract not found: {}", contract_id),
-            ),
-            _ => db_internal_error("get contract", err),
-        })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract = fetch_visible_contract_by_address(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        &contract_id,
+    )
+    .await?;
 
     let deployments: Vec<ContractDeployment> = sqlx::query_as(
         "SELECT * FROM contract_deployments 
@@ -2375,7 +5182,7 @@ pub async fn get_deployment_status(
 }
 
 pub async fn route_not_found() -> ApiError {
-    ApiError::not_found("RouteNotFound", "The requested endpoint does not exist")
+    ApiError::not_found(ErrorCode::RouteNotFound, "The requested endpoint does not exist")
 }
 
 use std::time::Duration;
@@ -2388,9 +5195,20 @@ pub struct CacheParams {
 
 pub async fn get_contract_state(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path((contract_id, key)): Path<(String, String)>,
     Query(params): Query<CacheParams>,
 ) -> ApiResult<Json<serde_json::Value>> {
+    // Checked before the cache, which would otherwise serve private state
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract_by_address(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        &contract_id,
+    )
+    .await?;
     let use_cache = params.cache.as_deref() == Some("on");
 
     // Try cache first if enabled
@@ -2510,7 +5328,7 @@ use uuid::Uuid;
 
 use crate::{
     analytics,
-    error::{ApiError, ApiResult},
+    error::{ApiError, ApiResult, ErrorCode},
     state::AppState,
 };
 
@@ -2521,14 +5339,14 @@ pub fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
 
 fn map_json_rejection(err: JsonRejection) -> ApiError {
     ApiError::bad_request(
-        "InvalidRequest",
+        ErrorCode::InvalidRequest,
         format!("Invalid JSON payload: {}", err.body_text()),
     )
 }
 
 fn map_query_rejection(err: QueryRejection) -> ApiError {
     ApiError::bad_request(
-        "InvalidQuery",
+        ErrorCode::InvalidQuery,
         format!("Invalid query parameters: {}", err.body_text()),
     )
 }
@@ -2618,7 +5436,7 @@ pub async fn list_contracts(
     // bad input, bail early
     if page < 1 || limit < 1 || limit > 100 {
         return ApiError::bad_request(
-            "InvalidPagination",
+            ErrorCode::InvalidPagination,
             "page must be >= 1 and limit must be between 1 and 100",
         )
         .into_response();
@@ -2706,7 +5524,7 @@ pub async fn get_contract(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("No contract found with ID: {}", id),
             ),
             _ => db_internal_error("get contract by id", err),
@@ -2748,14 +5566,19 @@ pub async fn get_contract_abi(
 /// Get contract version history
 pub async fn get_contract_versions(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<ContractVersion>>> {
     let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidContractId",
+            ErrorCode::InvalidContractId,
             format!("Invalid contract ID format: {}", id),
         )
     })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), contract_uuid)
+        .await?;
 
     let versions: Vec<ContractVersion> = sqlx::query_as(
         "SELECT * FROM contract_versions WHERE contract_id = $1 ORDER BY created_at DESC",
@@ -2909,7 +5732,7 @@ pub async fn get_publisher(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "PublisherNotFound",
+                ErrorCode::PublisherNotFound,
                 format!("No publisher found with ID: {}", id),
             ),
             _ => db_internal_error("get publisher by id", err),
@@ -2936,20 +5759,12 @@ pub async fn get_publisher_contracts(
 /// Get analytics for a specific contract
 pub async fn get_contract_analytics(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<Uuid>,
 ) -> ApiResult<Json<ContractAnalyticsResponse>> {
-    // Verify the contract exists
-    let _contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
-        .bind(id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("No contract found with ID: {}", id),
-            ),
-            _ => db_internal_error("get contract for analytics", err),
-        })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), id).await?;
 
     let thirty_days_ago = chrono::Utc::now() - chrono::Duration::days(30);
 
@@ -3043,7 +5858,7 @@ pub async fn deploy_green(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("No contract found with ID: {}", id),
             ),
             _ => db_internal_error("get contract for analytics", err),
@@ -3173,7 +5988,7 @@ pub async fn switch_deployment(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for switch", err),
@@ -3216,19 +6031,19 @@ pub async fn switch_deployment(
     if let Some(ref green) = green_deployment {
         if !force && green.status != DeploymentStatus::Testing {
             return Err(ApiError::bad_request(
-                "InvalidDeploymentStatus",
+                ErrorCode::InvalidDeploymentStatus,
                 "Green deployment must be in testing status before switch",
             ));
         }
         if !force && green.health_checks_passed < 3 {
             return Err(ApiError::bad_request(
-                "InsufficientHealthChecks",
+                ErrorCode::InsufficientHealthChecks,
                 "Green deployment must pass at least 3 health checks before switch",
             ));
         }
     } else {
         return Err(ApiError::bad_request(
-            "NoGreenDeployment",
+            ErrorCode::NoGreenDeployment,
             "No green deployment found",
         ));
     }
@@ -3286,7 +6101,7 @@ pub async fn rollback_deployment(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", contract_id),
             ),
             _ => db_internal_error("get contract for rollback", err),
@@ -3329,7 +6144,7 @@ pub async fn rollback_deployment(
 
     if target_deployment.is_none() {
         return Err(ApiError::bad_request(
-            "NoDeploymentToRollback",
+            ErrorCode::NoDeploymentToRollback,
             format!("No {} deployment found to rollback to", to_env),
         ));
     }
@@ -3389,7 +6204,7 @@ pub async fn report_health_check(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for health check", err),
@@ -3436,19 +6251,18 @@ pub async fn report_health_check(
 
 pub async fn get_deployment_status(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE contract_id = $1")
-        .bind(&contract_id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("Contract not found: {}", contract_id),
-            ),
-            _ => db_internal_error("get contract", err),
-        })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract = fetch_visible_contract_by_address(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        &contract_id,
+    )
+    .await?;
 
     let deployments: Vec<ContractDeployment> = sqlx::query_as(
         "SELECT * FROM contract_deployments 
@@ -3479,7 +6293,7 @@ pub async fn get_deployment_status(
 }
 
 pub async fn route_not_found() -> ApiError {
-    ApiError::not_found("RouteNotFound", "The requested endpoint does not exist")
+    ApiError::not_found(ErrorCode::RouteNotFound, "The requested endpoint does not exist")
 }
 
 use serde::Deserialize;
@@ -3492,9 +6306,20 @@ pub struct CacheParams {
 
 pub async fn get_contract_state(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path((contract_id, key)): Path<(String, String)>,
     Query(params): Query<CacheParams>,
 ) -> ApiResult<Json<serde_json::Value>> {
+    // Checked before the cache, which would otherwise serve private state
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract_by_address(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        &contract_id,
+    )
+    .await?;
     let use_cache = params.cache.as_deref() == Some("on");
 
     // Try cache first if enabled
@@ -3590,7 +6415,7 @@ pub async fn create_ab_test(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for ab test", err),
@@ -3598,7 +6423,7 @@ pub async fn create_ab_test(
 
     let variant_a_uuid = Uuid::parse_str(&req.variant_a_deployment_id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidDeploymentId",
+            ErrorCode::InvalidDeploymentId,
             format!(
                 "Invalid variant A deployment ID: {}",
                 req.variant_a_deployment_id
@@ -3608,7 +6433,7 @@ pub async fn create_ab_test(
 
     let variant_b_uuid = Uuid::parse_str(&req.variant_b_deployment_id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidDeploymentId",
+            ErrorCode::InvalidDeploymentId,
             format!(
                 "Invalid variant B deployment ID: {}",
                 req.variant_b_deployment_id
@@ -3617,10 +6442,10 @@ pub async fn create_ab_test(
     })?;
 
     let traffic_split = Decimal::try_from(req.traffic_split.unwrap_or(50.0))
-        .map_err(|_| ApiError::bad_request("InvalidSplit", "Invalid traffic split"))?;
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidSplit, "Invalid traffic split"))?;
 
     let significance_threshold = Decimal::try_from(req.significance_threshold.unwrap_or(95.0))
-        .map_err(|_| ApiError::bad_request("InvalidThreshold", "Invalid significance threshold"))?;
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidThreshold, "Invalid significance threshold"))?;
 
     let mut tx = state
         .db
@@ -3676,7 +6501,7 @@ pub async fn start_ab_test(
     Path(test_id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let test_uuid = Uuid::parse_str(&test_id).map_err(|_| {
-        ApiError::bad_request("InvalidTestId", format!("Invalid test ID: {}", test_id))
+        ApiError::bad_request(ErrorCode::InvalidTestId, format!("Invalid test ID: {}", test_id))
     })?;
 
     sqlx::query(
@@ -3703,7 +6528,7 @@ pub async fn get_variant(
     let Json(req) = payload.map_err(map_json_rejection)?;
 
     let test_uuid = Uuid::parse_str(&req.test_id).map_err(|_| {
-        ApiError::bad_request("InvalidTestId", format!("Invalid test ID: {}", req.test_id))
+        ApiError::bad_request(ErrorCode::InvalidTestId, format!("Invalid test ID: {}", req.test_id))
     })?;
 
     let variant: Option<String> = sqlx::query_scalar("SELECT assign_variant($1, $2)")
@@ -3721,7 +6546,7 @@ pub async fn get_variant(
         })))
     } else {
         Err(ApiError::not_found(
-            "TestNotFound",
+            ErrorCode::TestNotFound,
             format!("Test not found or not running: {}", req.test_id),
         ))
     }
@@ -3734,7 +6559,7 @@ pub async fn record_ab_test_metric(
     let Json(req) = payload.map_err(map_json_rejection)?;
 
     let test_uuid = Uuid::parse_str(&req.test_id).map_err(|_| {
-        ApiError::bad_request("InvalidTestId", format!("Invalid test ID: {}", req.test_id))
+        ApiError::bad_request(ErrorCode::InvalidTestId, format!("Invalid test ID: {}", req.test_id))
     })?;
 
     let variant = if let Some(ref user_addr) = req.user_address {
@@ -3768,13 +6593,13 @@ pub async fn record_ab_test_metric(
         }
     } else {
         return Err(ApiError::bad_request(
-            "UserAddressRequired",
+            ErrorCode::UserAddressRequired,
             "User address required for metric recording",
         ));
     };
 
     let metric_value = Decimal::try_from(req.metric_value)
-        .map_err(|_| ApiError::bad_request("InvalidMetric", "Invalid metric value"))?;
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidMetric, "Invalid metric value"))?;
 
     let metric: AbTestMetric = sqlx::query_as(
         "INSERT INTO ab_test_metrics (
@@ -3800,7 +6625,7 @@ pub async fn get_ab_test_results(
     Path(test_id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let test_uuid = Uuid::parse_str(&test_id).map_err(|_| {
-        ApiError::bad_request("InvalidTestId", format!("Invalid test ID: {}", test_id))
+        ApiError::bad_request(ErrorCode::InvalidTestId, format!("Invalid test ID: {}", test_id))
     })?;
 
     let test: AbTest = sqlx::query_as("SELECT * FROM ab_tests WHERE id = $1")
@@ -3809,7 +6634,7 @@ pub async fn get_ab_test_results(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => {
-                ApiError::not_found("TestNotFound", format!("Test not found: {}", test_id))
+                ApiError::not_found(ErrorCode::TestNotFound, format!("Test not found: {}", test_id))
             }
             _ => db_internal_error("get test", err),
         })?;
@@ -4003,7 +6828,7 @@ pub async fn rollout_winning_variant(
     Path(test_id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let test_uuid = Uuid::parse_str(&test_id).map_err(|_| {
-        ApiError::bad_request("InvalidTestId", format!("Invalid test ID: {}", test_id))
+        ApiError::bad_request(ErrorCode::InvalidTestId, format!("Invalid test ID: {}", test_id))
     })?;
 
     let test: AbTest = sqlx::query_as("SELECT * FROM ab_tests WHERE id = $1")
@@ -4012,7 +6837,7 @@ pub async fn rollout_winning_variant(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => {
-                ApiError::not_found("TestNotFound", format!("Test not found: {}", test_id))
+                ApiError::not_found(ErrorCode::TestNotFound, format!("Test not found: {}", test_id))
             }
             _ => db_internal_error("get test", err),
         })?;
@@ -4022,7 +6847,7 @@ pub async fn rollout_winning_variant(
 
     if winner.is_none() {
         return Err(ApiError::bad_request(
-            "NoWinner",
+            ErrorCode::NoWinner,
             "No statistically significant winner found",
         ));
     }
@@ -4083,7 +6908,7 @@ async fn get_ab_test_results_internal(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => {
-                ApiError::not_found("TestNotFound", format!("Test not found: {}", test_uuid))
+                ApiError::not_found(ErrorCode::TestNotFound, format!("Test not found: {}", test_uuid))
             }
             _ => db_internal_error("get test", err),
         })?;
@@ -4129,14 +6954,14 @@ pub async fn record_performance_metric(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract for metric", err),
         })?;
 
     let value = Decimal::try_from(req.value)
-        .map_err(|_| ApiError::bad_request("InvalidMetric", "Invalid metric value"))?;
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidMetric, "Invalid metric value"))?;
 
     let p50 = req.p50.and_then(|v| Decimal::try_from(v).ok());
     let p95 = req.p95.and_then(|v| Decimal::try_from(v).ok());
@@ -4163,22 +6988,34 @@ pub async fn record_performance_metric(
     Ok(Json(metric))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/performance",
+    tag = "contracts",
+    params(
+        ("id" = String, Path, description = "On-chain contract ID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Performance metrics"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_contract_performance(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<String>,
     Query(params): Query<std::collections::HashMap<String, String>>,
 ) -> ApiResult<Json<serde_json::Value>> {
-    let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE contract_id = $1")
-        .bind(&contract_id)
-        .fetch_one(&state.db)
-        .await
-        .map_err(|err| match err {
-            sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
-                format!("Contract not found: {}", contract_id),
-            ),
-            _ => db_internal_error("get contract", err),
-        })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract = fetch_visible_contract_by_address(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        &contract_id,
+    )
+    .await?;
 
     let timeframe = params.get("timeframe").map(|s| s.as_str()).unwrap_or("7d");
     let start_time = parse_timeframe(timeframe);
@@ -4328,14 +7165,14 @@ pub async fn create_alert_config(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", req.contract_id),
             ),
             _ => db_internal_error("get contract", err),
         })?;
 
     let threshold_value = Decimal::try_from(req.threshold_value)
-        .map_err(|_| ApiError::bad_request("InvalidThreshold", "Invalid threshold value"))?;
+        .map_err(|_| ApiError::bad_request(ErrorCode::InvalidThreshold, "Invalid threshold value"))?;
 
     let severity = req.severity.unwrap_or(AlertSeverity::Warning);
 
@@ -4371,7 +7208,7 @@ pub async fn get_performance_anomalies(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("Contract not found: {}", contract_id),
             ),
             _ => db_internal_error("get contract", err),
@@ -4395,7 +7232,7 @@ pub async fn acknowledge_alert(
     Path(alert_id): Path<String>,
 ) -> ApiResult<Json<serde_json::Value>> {
     let alert_uuid = Uuid::parse_str(&alert_id).map_err(|_| {
-        ApiError::bad_request("InvalidAlertId", format!("Invalid alert ID: {}", alert_id))
+        ApiError::bad_request(ErrorCode::InvalidAlertId, format!("Invalid alert ID: {}", alert_id))
     })?;
 
     sqlx::query(
@@ -4416,64 +7253,59 @@ pub async fn acknowledge_alert(
 
 
 /// Get contract dependencies (recursive tree)
+///
+/// Each declared dependency is resolved to the highest registered version
+/// matching its range. A dependency that leads back onto the current path is
+/// reported as a 409 naming the cycle.
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/dependencies",
+    tag = "dependencies",
+    params(
+        ("id" = String, Path, description = "Contract identifier"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Resolved dependency tree"),
+        (status = 404, description = "Unknown or private contract"),
+        (status = 409, description = "Dependency cycle detected"),
+    ),
+)]
 pub async fn get_contract_dependencies(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<DependencyTreeNode>>> {
     let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidContractId",
+            ErrorCode::InvalidContractId,
             format!("Invalid contract ID format: {}", id),
         )
     })?;
 
-    // Helper to recursively fetch dependencies
-    // Note: In production, we'd want to limit recursion depth
+    let caller = caller.map(|Extension(caller)| caller);
+    let root_name =
+        fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), contract_uuid)
+            .await?
+            .name;
+
     async fn fetch_deps(
         pool: &sqlx::PgPool,
         contract_id: Uuid,
-        visited: &mut HashSet<Uuid>,
+        path: &mut Vec<(Uuid, String)>,
     ) -> ApiResult<Vec<DependencyTreeNode>> {
-        if visited.contains(&contract_id) {
-            return Err(ApiError::bad_request(
-                "CircularDependency",
-                "Circular dependency detected",
-            ));
-        }
-        visited.insert(contract_id);
-
-        let deps: Vec<ContractDependency> =
-            sqlx::query_as("SELECT * FROM contract_dependencies WHERE contract_id = $1")
-                .bind(contract_id)
-                .fetch_all(pool)
-                .await
-                .map_err(|err| db_internal_error("fetch dependencies", err))?;
+        let deps: Vec<ContractDependency> = sqlx::query_as(
+            "SELECT * FROM contract_dependencies WHERE contract_id = $1 ORDER BY dependency_name",
+        )
+        .bind(contract_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|err| db_internal_error("fetch dependencies", err))?;
 
         let mut nodes = Vec::new();
         for dep in deps {
-            // If dependency points to a valid contract, fetch it to get details
-            if let Some(dep_contract_id) = dep.dependency_contract_id {
-                let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
-                    .bind(dep_contract_id)
-                    .fetch_one(pool)
-                    .await
-                    .map_err(|err| db_internal_error("fetch dependent contract", err))?;
-
-                // Recursively fetch sub-dependencies
-                // We clone visited for each branch to allow shared dependencies (diamond problem)
-                // but avoid cycles in the current path.
-                let mut path_visited = visited.clone();
-                let sub_deps =
-                    Box::pin(fetch_deps(pool, dep_contract_id, &mut path_visited)).await?;
-
-                nodes.push(DependencyTreeNode {
-                    contract_id: contract.contract_id,
-                    name: contract.name,
-                    current_version: "1.0.0".to_string(), // TODO: fetch actual version
-                    constraint_to_parent: dep.version_constraint,
-                    dependencies: sub_deps,
-                });
-            } else {
+            let Some(dep_contract_id) = dep.dependency_contract_id else {
                 // Dependency not found in registry (unresolved)
                 nodes.push(DependencyTreeNode {
                     contract_id: "unknown".to_string(),
@@ -4482,41 +7314,114 @@ pub async fn get_contract_dependencies(
                     constraint_to_parent: dep.version_constraint,
                     dependencies: Vec::new(),
                 });
+                continue;
+            };
+
+            let contract: Contract = sqlx::query_as("SELECT * FROM contracts WHERE id = $1")
+                .bind(dep_contract_id)
+                .fetch_one(pool)
+                .await
+                .map_err(|err| db_internal_error("fetch dependent contract", err))?;
+
+            if let Some(cycle) = dependency_cycle(path, dep_contract_id, &contract.name) {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    ErrorCode::DependencyCycle,
+                    format!("Dependency cycle detected: {}", cycle.join(" -> ")),
+                )
+                .with_details(serde_json::json!({ "cycle": cycle })));
             }
+
+            let versions: Vec<ContractVersion> =
+                sqlx::query_as("SELECT * FROM contract_versions WHERE contract_id = $1")
+                    .bind(dep_contract_id)
+                    .fetch_all(pool)
+                    .await
+                    .map_err(|err| db_internal_error("fetch dependency versions", err))?;
+            let current_version = semver::VersionReq::parse(&dep.version_constraint)
+                .ok()
+                .and_then(|req| highest_matching_version(&req, &versions))
+                .map(|v| v.version.clone())
+                .unwrap_or_else(|| "unresolved".to_string());
+
+            // Only the current path counts towards cycles, so shared
+            // dependencies (diamonds) are still allowed.
+            path.push((dep_contract_id, contract.name.clone()));
+            let sub_deps = Box::pin(fetch_deps(pool, dep_contract_id, path)).await;
+            path.pop();
+
+            nodes.push(DependencyTreeNode {
+                contract_id: contract.contract_id,
+                name: contract.name,
+                current_version,
+                constraint_to_parent: dep.version_constraint,
+                dependencies: sub_deps?,
+            });
         }
 
         Ok(nodes)
     }
 
-    let mut visited = HashSet::new();
-    let tree = fetch_deps(&state.db, contract_uuid, &mut visited).await?;
+    let mut path = vec![(contract_uuid, root_name)];
+    let tree = fetch_deps(&state.db, contract_uuid, &mut path).await?;
 
     Ok(Json(tree))
 }
 
+/// If `next` is already on the resolution path, return the cycle as names,
+/// starting and ending with the repeated contract.
+fn dependency_cycle(path: &[(Uuid, String)], next: Uuid, next_name: &str) -> Option<Vec<String>> {
+    let start = path.iter().position(|(id, _)| *id == next)?;
+    let mut cycle: Vec<String> = path[start..].iter().map(|(_, name)| name.clone()).collect();
+    cycle.push(next_name.to_string());
+    Some(cycle)
+}
+
 /// Get contracts that depend on this one
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/dependents",
+    tag = "dependencies",
+    params(
+        ("id" = String, Path, description = "Contract identifier"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Contracts depending on this one that the caller may see"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_contract_dependents(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<serde_json::Value>>> {
     let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidContractId",
+            ErrorCode::InvalidContractId,
             format!("Invalid contract ID format: {}", id),
         )
     })?;
-
-    // Join contract_dependencies with contracts to get details of the dependent
-    let rows: Vec<(Uuid, String, String, String)> = sqlx::query_as(
-        "SELECT c.id, c.name, c.contract_id, cd.version_constraint 
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), contract_uuid)
+        .await?;
+
+    // Join contract_dependencies with contracts to get details of the
+    // dependent; private dependents stay hidden like they do in listings
+    let mut builder = QueryBuilder::<Postgres>::new(
+        "SELECT c.id, c.name, c.contract_id, cd.version_constraint
          FROM contract_dependencies cd
          JOIN contracts c ON cd.contract_id = c.id
-         WHERE cd.dependency_contract_id = $1",
-    )
-    .bind(contract_uuid)
-    .fetch_all(&state.db)
-    .await
-    .map_err(|err| db_internal_error("fetch dependents", err))?;
+         WHERE cd.dependency_contract_id = ",
+    );
+    builder.push_bind(contract_uuid);
+    push_visibility_filter(&mut builder, caller.as_ref());
+    let rows: Vec<(Uuid, String, String, String)> = builder
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("fetch dependents", err))?;
 
     let dependents = rows
         .into_iter()
@@ -4548,7 +7453,7 @@ use shared::{
 use uuid::Uuid;
 
 use crate::{
-    error::{ApiError, ApiResult},
+    error::{ApiError, ApiResult, ErrorCode},
     state::AppState,
 };
 
@@ -4558,11 +7463,11 @@ fn db_internal_error(operation: &str, err: sqlx::Error) -> ApiError {
 }
 
 fn map_json_rejection(err: JsonRejection) -> ApiError {
-    ApiError::bad_request("InvalidRequest", format!("Invalid JSON payload: {}", err.body_text()))
+    ApiError::bad_request(ErrorCode::InvalidRequest, format!("Invalid JSON payload: {}", err.body_text()))
 }
 
 fn map_query_rejection(err: QueryRejection) -> ApiError {
-    ApiError::bad_request("InvalidQuery", format!("Invalid query parameters: {}", err.body_text()))
+    ApiError::bad_request(ErrorCode::InvalidQuery, format!("Invalid query parameters: {}", err.body_text()))
 }
 
 /// Health check — probes DB connectivity and reports uptime.
@@ -4693,7 +7598,7 @@ pub async fn get_contract(
 ) -> ApiResult<Json<Contract>> {
     let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidContractId",
+            ErrorCode::InvalidContractId,
             format!("Invalid contract ID format: {}", id),
         )
     })?;
@@ -4704,7 +7609,7 @@ pub async fn get_contract(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("No contract found with ID: {}", id),
             ),
             _ => db_internal_error("get contract by id", err),
@@ -4716,14 +7621,19 @@ pub async fn get_contract(
 /// Get contract version history
 pub async fn get_contract_versions(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(id): Path<String>,
 ) -> ApiResult<Json<Vec<ContractVersion>>> {
     let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidContractId",
+            ErrorCode::InvalidContractId,
             format!("Invalid contract ID format: {}", id),
         )
     })?;
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), contract_uuid)
+        .await?;
 
     let versions: Vec<ContractVersion> = sqlx::query_as(
         "SELECT * FROM contract_versions WHERE contract_id = $1 ORDER BY created_at DESC",
@@ -4823,7 +7733,7 @@ pub async fn get_publisher(
 ) -> ApiResult<Json<Publisher>> {
     let publisher_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidPublisherId",
+            ErrorCode::InvalidPublisherId,
             format!("Invalid publisher ID format: {}", id),
         )
     })?;
@@ -4834,7 +7744,7 @@ pub async fn get_publisher(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "PublisherNotFound",
+                ErrorCode::PublisherNotFound,
                 format!("No publisher found with ID: {}", id),
             ),
             _ => db_internal_error("get publisher by id", err),
@@ -4850,7 +7760,7 @@ pub async fn get_publisher_contracts(
 ) -> ApiResult<Json<Vec<Contract>>> {
     let publisher_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidPublisherId",
+            ErrorCode::InvalidPublisherId,
             format!("Invalid publisher ID format: {}", id),
         )
     })?;
@@ -4874,7 +7784,7 @@ pub async fn get_contract_health(
 ) -> ApiResult<Json<ContractHealth>> {
     let contract_uuid = Uuid::parse_str(&id).map_err(|_| {
         ApiError::bad_request(
-            "InvalidContractId",
+            ErrorCode::InvalidContractId,
             format!("Invalid contract ID format: {}", id),
         )
     })?;
@@ -4886,7 +7796,7 @@ pub async fn get_contract_health(
         .await
         .map_err(|match_err| match match_err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ContractNotFound",
+                ErrorCode::ContractNotFound,
                 format!("No contract found with ID: {}", id),
             ),
             _ => db_internal_error("check contract existence", match_err),
@@ -4898,7 +7808,7 @@ pub async fn get_contract_health(
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "HealthNotFound",
+                ErrorCode::HealthNotFound,
                 format!("Health data not found for contract: {}", id),
             ),
             _ => db_internal_error("get contract health", err),
@@ -4909,5 +7819,846 @@ pub async fn get_contract_health(
 
 /// Fallback endpoint for unknown routes
 pub async fn route_not_found() -> ApiError {
-    ApiError::not_found("RouteNotFound", "The requested endpoint does not exist")
+    ApiError::not_found(ErrorCode::RouteNotFound, "The requested endpoint does not exist")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list_params(query: &str) -> ListContractsParams {
+        let uri: axum::http::Uri = format!("/api/contracts?{}", query).parse().unwrap();
+        Query::<ListContractsParams>::try_from_uri(&uri).unwrap().0
+    }
+
+    #[test]
+    fn huge_page_numbers_saturate_instead_of_overflowing() {
+        let params = list_params(&format!("page={}", i64::MAX));
+        let limit = params.clamped_limit();
+        assert_eq!(params.clamped_offset(limit), i64::MAX);
+        assert_eq!(list_params("page=3&limit=10").clamped_offset(10), 20);
+    }
+
+    #[test]
+    fn network_filter_excludes_other_networks() {
+        let params = list_params("network=testnet");
+        assert!(matches!(params.network, Some(Network::Testnet)));
+
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM contracts");
+        push_contract_filters(&mut builder, &params, None, None);
+        assert!(builder.sql().contains(" AND network = $1"));
+
+        let uri: axum::http::Uri = "/api/contracts?network=moonnet".parse().unwrap();
+        assert!(Query::<ListContractsParams>::try_from_uri(&uri).is_err());
+    }
+
+    #[test]
+    fn fuzzy_search_matches_by_similarity_instead_of_full_text() {
+        let params = list_params("q=tokn&fuzzy=true");
+        assert_eq!(params.search_tsquery(), None);
+        assert_eq!(params.fuzzy_term().as_deref(), Some("tokn"));
+
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM contracts");
+        push_contract_filters(&mut builder, &params, None, None);
+        assert!(builder.sql().contains("similarity(name, $2) >= $3"));
+        assert!(!builder.sql().contains("to_tsquery"));
+
+        // Full text stays the default
+        let params = list_params("q=tokn");
+        assert_eq!(params.fuzzy_term(), None);
+        assert!(params.search_tsquery().is_some());
+    }
+
+    #[test]
+    fn rank_by_score_puts_the_better_scored_of_equal_matches_first() {
+        let params = list_params("q=token&rank=score");
+        assert!(params.rank_by_score().unwrap());
+        assert!(!list_params("q=token").rank_by_score().unwrap());
+        assert!(list_params("q=token&rank=stars").rank_by_score().is_err());
+
+        let weight = DEFAULT_SEARCH_SCORE_WEIGHT;
+        let relevance = 0.06;
+        let strong = score_blended_rank(relevance, Some(90.0), weight);
+        let weak = score_blended_rank(relevance, Some(40.0), weight);
+        let unscored = score_blended_rank(relevance, None, weight);
+        assert!(strong > weak && weak > unscored);
+
+        // A far better text match can still beat a better score
+        assert!(
+            score_blended_rank(0.5, Some(10.0), weight)
+                > score_blended_rank(0.01, Some(60.0), weight)
+        );
+
+        let mut builder = QueryBuilder::<Postgres>::new("SELECT ");
+        push_score_blended_rank(&mut builder, "token:*", weight);
+        assert!(builder.sql().contains("ts_rank(search_vector, to_tsquery('simple', $2), 32)"));
+        assert!(builder.sql().contains("FROM contract_scores cs"));
+    }
+
+    #[test]
+    fn search_score_weight_falls_back_to_the_default() {
+        assert_eq!(parse_search_score_weight(None), DEFAULT_SEARCH_SCORE_WEIGHT);
+        assert_eq!(parse_search_score_weight(Some("1.5")), DEFAULT_SEARCH_SCORE_WEIGHT);
+        assert_eq!(parse_search_score_weight(Some("heavy")), DEFAULT_SEARCH_SCORE_WEIGHT);
+        assert_eq!(parse_search_score_weight(Some(" 0.5 ")), 0.5);
+    }
+
+    #[test]
+    fn stats_narrow_to_one_network() {
+        let stats = serde_json::json!({
+            "total_contracts": 5,
+            "by_network": {
+                "testnet": { "total_contracts": 3, "verified_contracts": 1, "total_publishers": 2 },
+                "mainnet": { "total_contracts": 2, "verified_contracts": 2, "total_publishers": 1 },
+            },
+        });
+        let testnet = network_stats(&stats, &Network::Testnet);
+        assert_eq!(testnet["network"], "testnet");
+        assert_eq!(testnet["total_contracts"], 3);
+
+        let futurenet = network_stats(&stats, &Network::Futurenet);
+        assert_eq!(futurenet["total_contracts"], 0);
+    }
+
+    #[tokio::test]
+    async fn stats_within_the_ttl_are_served_without_the_database() {
+        // Any query against this state's pool fails the request
+        let state = AppState::for_tests();
+        state
+            .stats
+            .put(serde_json::json!({ "total_contracts": 7, "verified_contracts": 2, "total_publishers": 3 }));
+
+        for _ in 0..2 {
+            let params = Ok(Query(StatsParams { network: None }));
+            let (headers, Json(stats)) = get_stats(State(state.clone()), params).await.unwrap();
+            assert_eq!(stats["total_contracts"], 7);
+            assert_eq!(
+                headers[0].1,
+                format!("public, max-age={}", state.stats.ttl().as_secs())
+            );
+        }
+
+        state.stats.invalidate();
+        let params = Ok(Query(StatsParams { network: None }));
+        assert!(get_stats(State(state), params).await.is_err());
+    }
+
+    #[test]
+    fn dependency_cycle_names_the_loop() {
+        let a = Uuid::new_v4();
+        let b = Uuid::new_v4();
+        let c = Uuid::new_v4();
+        let path = vec![(a, "a".to_string()), (b, "b".to_string()), (c, "c".to_string())];
+
+        assert_eq!(
+            dependency_cycle(&path, b, "b"),
+            Some(vec!["b".to_string(), "c".to_string(), "b".to_string()])
+        );
+        assert_eq!(dependency_cycle(&path, Uuid::new_v4(), "d"), None);
+    }
+
+    fn version(v: &str) -> ContractVersion {
+        ContractVersion {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::nil(),
+            version: v.to_string(),
+            wasm_hash: format!("hash-{}", v),
+            source_url: None,
+            commit_hash: None,
+            release_notes: None,
+            created_at: chrono::Utc::now(),
+            deprecated: false,
+            deprecated_at: None,
+            deprecation_reason: None,
+            superseded_by: None,
+            ipfs_cid: None,
+            ipfs_status: None,
+            yanked: false,
+            yanked_at: None,
+            download_count: 0,
+            signed: false,
+            signing_key: None,
+            build_info: None,
+            build_verification: None,
+        }
+    }
+
+    fn yanked(v: &str) -> ContractVersion {
+        ContractVersion {
+            yanked: true,
+            yanked_at: Some(chrono::Utc::now()),
+            ..version(v)
+        }
+    }
+
+    #[test]
+    fn ranges_skip_yanked_versions() {
+        let versions = vec![version("1.2.0"), yanked("1.4.1"), version("1.3.9")];
+        let req = semver::VersionReq::parse("^1.2.0").unwrap();
+        assert_eq!(highest_matching_version(&req, &versions).unwrap().version, "1.3.9");
+
+        // A range matched only by yanked versions resolves to nothing
+        let req = semver::VersionReq::parse(">=1.4.0").unwrap();
+        assert!(highest_matching_version(&req, &versions).is_none());
+    }
+
+    #[test]
+    fn exact_pin_still_resolves_a_yanked_version() {
+        let versions = vec![version("1.2.0"), yanked("1.4.1")];
+        let req = semver::VersionReq::parse("=1.4.1").unwrap();
+        assert_eq!(highest_matching_version(&req, &versions).unwrap().version, "1.4.1");
+
+        // `=1.4` is a range over 1.4.x, not a pin
+        let req = semver::VersionReq::parse("=1.4").unwrap();
+        assert!(highest_matching_version(&req, &versions).is_none());
+    }
+
+    #[test]
+    fn suggestions_need_two_characters_and_escape_wildcards() {
+        assert_eq!(suggest_pattern(""), None);
+        assert_eq!(suggest_pattern(" t "), None);
+        assert_eq!(suggest_pattern("To").as_deref(), Some("to%"));
+        assert_eq!(suggest_pattern("50%_off").as_deref(), Some("50\\%\\_off%"));
+    }
+
+    #[tokio::test]
+    async fn organization_owners_cannot_edit_their_members_profiles() {
+        use crate::state::{json_body, test_request};
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let owner = state.insert_publisher().await;
+        let member = state.insert_publisher().await;
+        // The member never agreed to join
+        state
+            .insert_organization(&[(owner, "owner"), (member, "viewer")])
+            .await;
+        let uri = format!("/api/publishers/{}", member);
+        let edit = |key: &str, name: &str| {
+            let body = serde_json::json!({ "display_name": name });
+            test_request("PUT", &uri, Some(key), Some(body))
+        };
+
+        let owner_key = state.api_key(owner).await;
+        let response = state.send(edit(&owner_key, "Hijacked")).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let member_key = state.api_key(member).await;
+        let response = state.send(edit(&member_key, "Member")).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(json_body(response).await["display_name"], "Member");
+    }
+
+    #[tokio::test]
+    async fn strangers_cannot_deactivate_and_publishers_cannot_reactivate() {
+        // Refused before any query
+        let state = AppState::for_tests();
+        let publisher = Uuid::new_v4();
+        let other = Caller::Publisher(Uuid::new_v4());
+
+        let err = deactivate_publisher(State(state.clone()), Extension(other), Path(publisher))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+
+        let own = Caller::Publisher(publisher);
+        let err = reactivate_publisher(State(state), Extension(own), Path(publisher))
+            .await
+            .unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn contracts_follow_their_current_publishers_deactivation() {
+        use crate::state::test_request;
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let leaving = state.insert_publisher().await;
+        let active = state.insert_publisher().await;
+        let kept = state.insert_contract(leaving, None, "public").await;
+        let key = state.api_key(leaving).await;
+        let uri = format!("/api/publishers/{}/deactivate", leaving);
+        let response = state
+            .send(test_request("POST", &uri, Some(&key), None))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let flagged = |id: Uuid| {
+            let db = state.db.clone();
+            async move {
+                sqlx::query_scalar::<_, bool>(
+                    "SELECT publisher_deactivated FROM contracts WHERE id = $1",
+                )
+                .bind(id)
+                .fetch_one(&db)
+                .await
+                .unwrap()
+            }
+        };
+        assert!(flagged(kept).await);
+
+        // Arriving after the deactivation, as an import would
+        let imported = state.insert_contract(leaving, None, "public").await;
+        assert!(flagged(imported).await);
+
+        // Handed to an active publisher, as a transfer would
+        sqlx::query("UPDATE contracts SET publisher_id = $2 WHERE id = $1")
+            .bind(kept)
+            .bind(active)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        assert!(!flagged(kept).await);
+    }
+
+    #[tokio::test]
+    async fn private_contracts_are_visible_only_to_their_owners() {
+        // Personal contracts need no membership lookup, so the pool is never used
+        let db = AppState::for_tests().db;
+        let owner = Uuid::new_v4();
+        let mut contract = Contract {
+            id: Uuid::new_v4(),
+            contract_id: "CPRIVATE".to_string(),
+            wasm_hash: "aa11".to_string(),
+            name: "vault".to_string(),
+            description: None,
+            publisher_id: owner,
+            organization_id: None,
+            network: Network::Testnet,
+            is_verified: false,
+            category: None,
+            tags: Vec::new(),
+            metadata: None,
+            publisher_deactivated: false,
+            visibility: ContractVisibility::Private,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        };
+        let stranger = Caller::Publisher(Uuid::new_v4());
+        let can_view = |caller: Option<Caller>, contract: Contract| {
+            let db = db.clone();
+            async move {
+                auth::can_view_contract(&db, caller.as_ref(), &contract)
+                    .await
+                    .unwrap()
+            }
+        };
+
+        assert!(!can_view(None, contract.clone()).await);
+        assert!(!can_view(Some(stranger), contract.clone()).await);
+        assert!(can_view(Some(Caller::Publisher(owner)), contract.clone()).await);
+        assert!(can_view(Some(Caller::Admin), contract.clone()).await);
+
+        contract.visibility = ContractVisibility::Public;
+        assert!(can_view(None, contract.clone()).await);
+        assert!(can_view(Some(stranger), contract).await);
+    }
+
+    #[tokio::test]
+    async fn private_contract_versions_are_hidden_from_anonymous_callers() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let id = state.insert_private_contract().await;
+
+        for uri in [
+            format!("/api/contracts/{}/versions", id),
+            format!("/api/contracts/{}/versions/resolve?range=*", id),
+            format!("/api/contracts/{}/versions/1.0.0/sbom", id),
+            format!("/api/contracts/{}/changelog", id),
+        ] {
+            let status = state.anonymous_get(&uri).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn private_contract_details_are_hidden_from_anonymous_callers() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let id = state.insert_private_contract().await;
+
+        for uri in [
+            format!("/api/contracts/{}/abi", id),
+            format!("/api/contracts/{}/readme", id),
+            format!("/api/contracts/{}/score-history", id),
+            format!("/api/contracts/{}/dependencies", id),
+        ] {
+            let status = state.anonymous_get(&uri).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn private_contract_activity_is_hidden_from_anonymous_callers() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let id = state.insert_private_contract().await;
+        let address: String = sqlx::query_scalar("SELECT contract_id FROM contracts WHERE id = $1")
+            .bind(id)
+            .fetch_one(&state.db)
+            .await
+            .unwrap();
+
+        for uri in [
+            format!("/api/contracts/{}/analytics", id),
+            format!("/api/contracts/{}/trust-score", id),
+            format!("/api/contracts/{}/dependents", id),
+            format!("/api/contracts/{}/functions", id),
+            format!("/api/contracts/{}/performance", address),
+            format!("/api/contracts/{}/deployments/status", address),
+            format!("/api/contracts/{}/state/balance", address),
+        ] {
+            let status = state.anonymous_get(&uri).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+
+    #[tokio::test]
+    async fn published_build_info_is_served_with_the_version() {
+        use crate::state::{json_body, test_request};
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let publisher = state.insert_publisher().await;
+        let address: String =
+            sqlx::query_scalar("SELECT stellar_address FROM publishers WHERE id = $1")
+                .bind(publisher)
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        let body = serde_json::json!({
+            "contract_id": format!("C{}", &address[1..]),
+            "name": "built",
+            "network": "testnet",
+            "tags": [],
+            "publisher_address": address,
+            "version": "1.0.0",
+            "build_info": {
+                "compiler_version": " rustc 1.79.0 ",
+                "target": "wasm32-unknown-unknown",
+                "optimization_flags": "opt-level=z lto=true",
+            },
+        });
+        let key = state.api_key(publisher).await;
+        let response = state
+            .send(test_request("POST", "/api/contracts", Some(&key), Some(body)))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let id = json_body(response).await["id"].as_str().unwrap().to_string();
+
+        let uri = format!("/api/contracts/{}/versions", id);
+        let response = state.send(test_request("GET", &uri, None, None)).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let versions = json_body(response).await;
+        let build_info = &versions["items"][0]["build_info"];
+        assert_eq!(build_info["compiler_version"], "rustc 1.79.0");
+        assert_eq!(build_info["target"], "wasm32-unknown-unknown");
+        assert_eq!(build_info["optimization_flags"], "opt-level=z lto=true");
+    }
+
+    #[tokio::test]
+    async fn publish_resolves_dependencies_by_id_or_own_name_and_replaces_them() {
+        use crate::state::{json_body, test_request};
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let publisher = state.insert_publisher().await;
+        let stranger = state.insert_publisher().await;
+        let mine = state.insert_contract(publisher, None, "public").await;
+        let theirs = state.insert_contract(stranger, None, "public").await;
+        let name_of = |id: Uuid| {
+            let db = state.db.clone();
+            async move {
+                sqlx::query_scalar::<_, String>("SELECT name FROM contracts WHERE id = $1")
+                    .bind(id)
+                    .fetch_one(&db)
+                    .await
+                    .unwrap()
+            }
+        };
+        let my_name = name_of(mine).await;
+        let their_name = name_of(theirs).await;
+        // Pinning needs a well-formed on-chain ID
+        let their_contract_id: String = sqlx::query_scalar(
+            "UPDATE contracts SET contract_id = 'C' || substr(p.stellar_address, 2)
+             FROM publishers p
+             WHERE contracts.id = $1 AND p.id = contracts.publisher_id
+             RETURNING contracts.contract_id",
+        )
+        .bind(theirs)
+        .fetch_one(&state.db)
+        .await
+        .unwrap();
+        let address: String =
+            sqlx::query_scalar("SELECT stellar_address FROM publishers WHERE id = $1")
+                .bind(publisher)
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        let key = state.api_key(publisher).await;
+        let publish = |dependencies: serde_json::Value| {
+            test_request(
+                "POST",
+                "/api/contracts",
+                Some(&key),
+                Some(serde_json::json!({
+                    "contract_id": format!("C{}", &address[1..]),
+                    "name": "dependent",
+                    "network": "testnet",
+                    "tags": [],
+                    "publisher_address": address,
+                    "dependencies": dependencies,
+                })),
+            )
+        };
+        let resolved = |id: Uuid| {
+            let db = state.db.clone();
+            async move {
+                sqlx::query_as::<_, (String, Option<Uuid>)>(
+                    "SELECT dependency_name, dependency_contract_id FROM contract_dependencies
+                     WHERE contract_id = $1 ORDER BY dependency_name",
+                )
+                .bind(id)
+                .fetch_all(&db)
+                .await
+                .unwrap()
+            }
+        };
+
+        let response = state
+            .send(publish(serde_json::json!([
+                { "name": my_name, "version_constraint": "^1" },
+                { "name": their_name, "version_constraint": "^1" },
+                {
+                    "name": "pinned",
+                    "version_constraint": "^1",
+                    "contract_id": their_contract_id,
+                },
+            ])))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let id: Uuid = json_body(response).await["id"]
+            .as_str()
+            .unwrap()
+            .parse()
+            .unwrap();
+        let mut expected = vec![
+            (my_name.clone(), Some(mine)),
+            (their_name, None),
+            ("pinned".to_string(), Some(theirs)),
+        ];
+        expected.sort();
+        assert_eq!(resolved(id).await, expected);
+
+        let response = state
+            .send(publish(serde_json::json!([
+                { "name": my_name, "version_constraint": "^2" },
+            ])))
+            .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(resolved(id).await, vec![(my_name, Some(mine))]);
+    }
+
+    #[tokio::test]
+    async fn capped_score_history_keeps_the_latest_points() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let publisher = state.insert_publisher().await;
+        let id = state.insert_contract(publisher, None, "public").await;
+        for (days_ago, composite) in [(3, 1.0), (2, 2.0), (1, 3.0)] {
+            sqlx::query(
+                "INSERT INTO score_history
+                    (contract_id, composite, security, popularity, maintenance, computed_at)
+                 VALUES ($1, $2, 0, 0, 0, NOW() - make_interval(days => $3))",
+            )
+            .bind(id)
+            .bind(composite)
+            .bind(days_ago)
+            .execute(&state.db)
+            .await
+            .unwrap();
+        }
+        let params = ScoreHistoryParams {
+            from: None,
+            to: None,
+            granularity: None,
+        };
+
+        let points = load_score_history(&state.db, id, &params, 2).await.unwrap();
+        let composites: Vec<f64> = points.iter().map(|point| point.composite).collect();
+        assert_eq!(composites, [2.0, 3.0]);
+    }
+
+    #[tokio::test]
+    async fn starring_is_idempotent_and_counted_per_publisher() {
+        use crate::state::{json_body, test_request};
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let owner = state.insert_publisher().await;
+        let fan = state.insert_publisher().await;
+        let other = state.insert_publisher().await;
+        let id = state.insert_contract(owner, None, "public").await;
+        let (fan_key, other_key) = (state.api_key(fan).await, state.api_key(other).await);
+        let star_uri = format!("/api/contracts/{}/star", id);
+        let detail_uri = format!("/api/contracts/{}", id);
+        let send = |method: &'static str, uri: String, key: Option<String>| {
+            let state = state.clone();
+            async move {
+                let response = state
+                    .send(test_request(method, &uri, key.as_deref(), None))
+                    .await;
+                assert_eq!(response.status(), StatusCode::OK, "{} {}", method, uri);
+                json_body(response).await
+            }
+        };
+
+        for _ in 0..2 {
+            let status = send("POST", star_uri.clone(), Some(fan_key.clone())).await;
+            assert_eq!(status["starred"], true);
+            assert_eq!(status["star_count"], 1);
+        }
+        let status = send("POST", star_uri.clone(), Some(other_key.clone())).await;
+        assert_eq!(status["star_count"], 2);
+
+        let detail = send("GET", detail_uri.clone(), Some(fan_key.clone())).await;
+        assert_eq!(detail["star_count"], 2);
+        assert_eq!(detail["starred_by_me"], true);
+        let owner_key = state.api_key(owner).await;
+        let detail = send("GET", detail_uri.clone(), Some(owner_key)).await;
+        assert_eq!(detail["starred_by_me"], false);
+        let detail = send("GET", detail_uri.clone(), None).await;
+        assert_eq!(detail["star_count"], 2);
+        assert!(detail.get("starred_by_me").is_none());
+
+        for _ in 0..2 {
+            let status = send("DELETE", star_uri.clone(), Some(fan_key.clone())).await;
+            assert_eq!(status["starred"], false);
+            assert_eq!(status["star_count"], 1);
+        }
+        let detail = send("GET", detail_uri, Some(fan_key.clone())).await;
+        assert_eq!(detail["star_count"], 1);
+        assert_eq!(detail["starred_by_me"], false);
+
+        let missing = format!("/api/contracts/{}/star", Uuid::new_v4());
+        let response = state
+            .send(test_request("POST", &missing, Some(&fan_key), None))
+            .await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn comparing_a_private_contract_reports_it_missing() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let a = state.insert_private_contract().await;
+        let b = state.insert_private_contract().await;
+
+        let uri = format!("/api/contracts/compare?a={}&b={}", a, b);
+        assert_eq!(state.anonymous_get(&uri).await, StatusCode::NOT_FOUND);
+    }
+
+    #[test]
+    fn listings_hide_private_contracts_the_caller_has_no_role_on() {
+        let params = list_params("");
+        let sql = |caller: Option<&Caller>| {
+            let mut builder = QueryBuilder::<Postgres>::new("SELECT * FROM contracts");
+            push_contract_filters(&mut builder, &params, None, caller);
+            builder.sql().to_string()
+        };
+
+        assert!(sql(None).contains(" AND visibility = 'public'"));
+        let own = sql(Some(&Caller::Publisher(Uuid::new_v4())));
+        assert!(
+            own.contains("visibility = 'public' OR (organization_id IS NULL AND publisher_id = $1)")
+        );
+        assert!(
+            own.contains("SELECT organization_id FROM organization_members WHERE publisher_id = $2")
+        );
+        assert!(!sql(Some(&Caller::Admin)).contains("visibility"));
+    }
+
+    #[test]
+    fn changelog_orders_versions_and_keeps_empty_sections() {
+        let noted = |v: &str, notes: &str| ContractVersion {
+            release_notes: Some(notes.to_string()),
+            ..version(v)
+        };
+        let versions = vec![
+            noted("1.2.0", "### Added\n- Burn"),
+            version("1.10.0"),
+            yanked("1.3.0"),
+            noted("1.0.0", "Initial release"),
+        ];
+        let entries = changelog_entries(versions);
+        let order: Vec<&str> = entries.iter().map(|e| e.version.as_str()).collect();
+        assert_eq!(order, ["1.10.0", "1.3.0", "1.2.0", "1.0.0"]);
+        assert_eq!(entries[0].changelog, "");
+
+        let doc = render_changelog("token", &entries);
+        let headings: Vec<&str> = doc.lines().filter(|l| l.starts_with("## ")).collect();
+        assert_eq!(headings.len(), 4);
+        assert!(headings[1].ends_with("[YANKED]"));
+        assert!(doc.contains("### Added\n- Burn\n"));
+    }
+
+    #[test]
+    fn publish_rejects_incomplete_semver() {
+        let err = parse_publish_version("1.2").unwrap_err();
+        assert_eq!(err.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(err.code().as_str(), "version.invalid_semver");
+        assert!(parse_publish_version("v1.2.3").is_err());
+    }
+
+    #[test]
+    fn prerelease_and_release_are_distinct_versions() {
+        let rc = parse_publish_version("1.2.3-rc.1").unwrap();
+        let release = parse_publish_version("1.2.3").unwrap();
+        assert_ne!(rc.to_string(), release.to_string());
+        assert!(rc < release);
+    }
+
+    /// Stand-in for the Postgres error raised by the versions unique index
+    #[derive(Debug)]
+    struct UniqueViolation;
+
+    impl std::fmt::Display for UniqueViolation {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.write_str("duplicate key value violates unique constraint")
+        }
+    }
+
+    impl std::error::Error for UniqueViolation {}
+
+    impl sqlx::error::DatabaseError for UniqueViolation {
+        fn message(&self) -> &str {
+            "duplicate key value violates unique constraint"
+        }
+        fn as_error(&self) -> &(dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+        fn as_error_mut(&mut self) -> &mut (dyn std::error::Error + Send + Sync + 'static) {
+            self
+        }
+        fn into_error(self: Box<Self>) -> Box<dyn std::error::Error + Send + Sync + 'static> {
+            self
+        }
+        fn kind(&self) -> sqlx::error::ErrorKind {
+            sqlx::error::ErrorKind::UniqueViolation
+        }
+    }
+
+    #[test]
+    fn republishing_a_version_is_a_conflict() {
+        let err = version_insert_error(sqlx::Error::Database(Box::new(UniqueViolation)), "1.2.3");
+        assert_eq!(err.status(), StatusCode::CONFLICT);
+        assert_eq!(err.code().as_str(), "contract.duplicate_version");
+
+        let err = version_insert_error(sqlx::Error::RowNotFound, "1.2.3");
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn resolves_highest_version_in_range() {
+        let versions = vec![version("1.2.0"), version("1.4.1"), version("2.0.0"), version("1.3.9")];
+        let req = semver::VersionReq::parse("^1.2.0").unwrap();
+        let best = highest_matching_version(&req, &versions).unwrap();
+        assert_eq!(best.version, "1.4.1");
+    }
+
+    #[test]
+    fn resolve_skips_invalid_versions() {
+        let versions = vec![version("latest"), version("v1.0.3"), version("1.0")];
+        let req = semver::VersionReq::parse("~1.0").unwrap();
+        let best = highest_matching_version(&req, &versions).unwrap();
+        assert_eq!(best.version, "v1.0.3");
+    }
+
+    #[test]
+    fn resolve_returns_none_without_match() {
+        let versions = vec![version("0.9.0")];
+        let req = semver::VersionReq::parse(">=1.0.0").unwrap();
+        assert!(highest_matching_version(&req, &versions).is_none());
+    }
+
+    #[test]
+    fn cursor_round_trips() {
+        let cursor = ContractCursor {
+            created_at: chrono::DateTime::from_timestamp_micros(1_700_000_000_123_456).unwrap(),
+            id: Uuid::new_v4(),
+        };
+        assert_eq!(ContractCursor::decode(&cursor.encode()).unwrap(), cursor);
+    }
+
+    #[test]
+    fn malformed_cursors_are_rejected() {
+        use base64::Engine;
+        let encode = |s: &str| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(s);
+
+        assert!(ContractCursor::decode("not base64!").is_err());
+        assert!(ContractCursor::decode(&encode("no-separator")).is_err());
+        assert!(ContractCursor::decode(&encode("abc:00000000-0000-0000-0000-000000000000")).is_err());
+        assert!(ContractCursor::decode(&encode("1700000000:not-a-uuid")).is_err());
+    }
+
+    #[test]
+    fn keyset_only_for_created_at_sorts() {
+        assert_eq!(SortField::CreatedAtDesc.keyset_comparator(), Some("<"));
+        assert_eq!(SortField::CreatedAtAsc.keyset_comparator(), Some(">"));
+        assert_eq!(SortField::NameAsc.keyset_comparator(), None);
+    }
+
+    #[test]
+    fn sort_field_parses_whitelisted_values() {
+        assert_eq!(SortField::parse("name").unwrap(), SortField::NameAsc);
+        assert_eq!(SortField::parse("-created_at").unwrap(), SortField::CreatedAtDesc);
+        assert_eq!(SortField::parse("score").unwrap(), SortField::ScoreAsc);
+        assert_eq!(SortField::default(), SortField::CreatedAtDesc);
+    }
+
+    #[test]
+    fn sort_field_rejects_unknown_values() {
+        assert!(SortField::parse("name; DROP TABLE contracts").is_err());
+        assert!(SortField::parse("-wasm_hash").is_err());
+    }
+
+    #[test]
+    fn sort_field_always_has_id_tiebreaker() {
+        for raw in SortField::ALLOWED {
+            let order = SortField::parse(raw).unwrap().order_by();
+            assert!(order.contains(", id "), "{} lacks tiebreaker", raw);
+        }
+    }
+
+    #[test]
+    fn prefix_tsquery_lowercases_and_joins_terms() {
+        assert_eq!(
+            build_prefix_tsquery("Token  Swap").as_deref(),
+            Some("token:* & swap:*")
+        );
+    }
+
+    #[test]
+    fn prefix_tsquery_strips_operators() {
+        assert_eq!(
+            build_prefix_tsquery("amm') | !x:*").as_deref(),
+            Some("amm:* & x:*")
+        );
+    }
+
+    #[test]
+    fn prefix_tsquery_empty_input_falls_back() {
+        assert_eq!(build_prefix_tsquery("   "), None);
+        assert_eq!(build_prefix_tsquery("&& ||"), None);
+    }
 }
@@ -4,17 +4,25 @@ use axum::{
     Json,
 };
 use shared::models::{
-    CreateMigrationRequest, Migration, MigrationStatus, PaginatedResponse,
-    UpdateMigrationStatusRequest,
+    CreateMigrationRequest, Migration, MigrationStatus, UpdateMigrationStatusRequest,
 };
 use uuid::Uuid;
 
-use crate::error::ApiError;
+use crate::error::{ApiError, ErrorCode};
+use crate::pagination::{PageParams, Paginated};
 use crate::state::AppState;
 use super::db_internal_error;
 
 
 /// Create a new migration
+#[utoipa::path(
+    post,
+    path = "/api/migrations",
+    tag = "migrations",
+    responses(
+        (status = 200, description = "Migration created"),
+    ),
+)]
 pub async fn create_migration(
     State(state): State<AppState>,
     Json(payload): Json<CreateMigrationRequest>,
@@ -34,6 +42,17 @@ pub async fn create_migration(
 }
 
 /// Update a migration status
+#[utoipa::path(
+    put,
+    path = "/api/migrations/{id}",
+    tag = "migrations",
+    params(
+        ("id" = Uuid, Path, description = "Migration UUID"),
+    ),
+    responses(
+        (status = 200, description = "Migration updated"),
+    ),
+)]
 pub async fn update_migration(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -56,27 +75,51 @@ pub async fn update_migration(
 }
 
 /// Get all migrations
+#[utoipa::path(
+    get,
+    path = "/api/migrations",
+    tag = "migrations",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Migrations, newest first"),
+    ),
+)]
 pub async fn get_migrations(
     State(state): State<AppState>,
-) -> Result<Json<PaginatedResponse<Migration>>, ApiError> {
-    // For simplicity, we'll just return the last 50 migrations
+    Query(page): Query<PageParams>,
+) -> Result<Json<Paginated<Migration>>, ApiError> {
     let migrations: Vec<Migration> = sqlx::query_as(
         "SELECT id, contract_id, status, wasm_hash, log_output, created_at, updated_at
         FROM migrations
-        ORDER BY created_at DESC
-        LIMIT 50"
+        ORDER BY created_at DESC, id DESC
+        LIMIT $1 OFFSET $2"
     )
+    .bind(page.limit())
+    .bind(page.offset())
     .fetch_all(&state.db)
     .await
     .map_err(|e| db_internal_error("get migrations", e))?;
 
-    let total = migrations.len() as i64; // In a real app we'd do a count query
-    let response = PaginatedResponse::new(migrations, total, 1, 50);
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM migrations")
+        .fetch_one(&state.db)
+        .await
+        .map_err(|e| db_internal_error("count migrations", e))?;
 
-    Ok(Json(response))
+    Ok(Json(Paginated::new(migrations, total, page.limit(), page.offset())))
 }
 
 /// Get a specific migration
+#[utoipa::path(
+    get,
+    path = "/api/migrations/{id}",
+    tag = "migrations",
+    params(
+        ("id" = Uuid, Path, description = "Migration UUID"),
+    ),
+    responses(
+        (status = 200, description = "Migration details"),
+    ),
+)]
 pub async fn get_migration(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
@@ -90,7 +133,7 @@ pub async fn get_migration(
     .fetch_optional(&state.db)
     .await
     .map_err(|e| db_internal_error("get migration", e))?
-    .ok_or(ApiError::not_found("MigrationNotFound", "Migration not found"))?;
+    .ok_or(ApiError::not_found(ErrorCode::MigrationNotFound, "Migration not found"))?;
 
     Ok(Json(migration))
 }
@@ -0,0 +1,681 @@
+//! Catalog import, the counterpart of `export_handlers`.
+//!
+//! `POST /api/import` reads the NDJSON export line by line and upserts
+//! contracts, keyed by `(contract_id, network)`, and their versions in
+//! transactions of `IMPORT_BATCH_SIZE` records. Rows that already match are
+//! left alone, so importing the same file twice changes nothing. A contract
+//! stored with a different publisher, organization or WASM hash, and a
+//! version whose WASM hash differs from the stored one, are conflicts: they
+//! are reported, and replaced only with `?on_conflict=overwrite`.
+//!
+//! Artifacts are not part of the export, so imported versions carry only
+//! their hashes. Publishers must already exist in the target registry.
+
+use axum::{
+    body::Body,
+    extract::{Query, State},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use shared::{ContractVisibility, Network};
+use sqlx::{Postgres, Transaction};
+use tokio::io::AsyncBufReadExt;
+use tokio_util::io::StreamReader;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::db_internal_error,
+    state::AppState,
+};
+
+/// Records upserted per transaction
+pub const IMPORT_BATCH_SIZE: usize = 500;
+
+/// What to do with a contract or version that conflicts with the stored one
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum OnConflict {
+    #[default]
+    Skip,
+    Overwrite,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportParams {
+    #[serde(default)]
+    pub on_conflict: OnConflict,
+}
+
+/// One line of the export: a contract with its versions
+#[derive(Debug, Deserialize)]
+pub struct ImportRecord {
+    pub contract_id: String,
+    pub network: Network,
+    pub wasm_hash: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub publisher_id: Uuid,
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+    #[serde(default)]
+    pub is_verified: bool,
+    /// Exports from before private contracts default to public
+    #[serde(default)]
+    pub visibility: ContractVisibility,
+    pub category: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub versions: Vec<ImportVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportVersion {
+    pub version: String,
+    pub wasm_hash: String,
+    pub source_url: Option<String>,
+    pub commit_hash: Option<String>,
+    pub release_notes: Option<String>,
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub deprecated: bool,
+    pub deprecated_at: Option<DateTime<Utc>>,
+    pub deprecation_reason: Option<String>,
+    pub superseded_by: Option<String>,
+    #[serde(default)]
+    pub yanked: bool,
+    pub yanked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, ToSchema)]
+pub struct ImportCounts {
+    pub created: u64,
+    pub updated: u64,
+    /// Already identical, or a conflict left in place
+    pub skipped: u64,
+}
+
+/// A version whose incoming hash differs from the stored one
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportConflict {
+    pub line: u64,
+    pub contract_id: String,
+    pub network: Network,
+    pub version: String,
+    pub existing_hash: String,
+    pub incoming_hash: String,
+    pub resolution: OnConflict,
+}
+
+/// A contract stored with a different owner or WASM hash than the import's
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportContractConflict {
+    pub line: u64,
+    pub contract_id: String,
+    pub network: Network,
+    /// Which of `publisher_id`, `organization_id` and `wasm_hash` differ
+    pub fields: Vec<String>,
+    /// With `skip`, neither the contract nor its versions were imported
+    pub resolution: OnConflict,
+}
+
+/// A line that could not be imported; the rest of the file still is
+#[derive(Debug, Clone, Serialize, ToSchema)]
+pub struct ImportLineError {
+    pub line: u64,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Clone, Serialize, ToSchema)]
+pub struct ImportSummary {
+    pub contracts: ImportCounts,
+    pub versions: ImportCounts,
+    pub contract_conflicts: Vec<ImportContractConflict>,
+    pub conflicts: Vec<ImportConflict>,
+    pub errors: Vec<ImportLineError>,
+}
+
+/// How to apply an incoming version
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VersionAction {
+    Insert,
+    /// Same hash: refresh the other fields if they changed
+    Refresh,
+    Conflict(OnConflict),
+}
+
+pub fn version_action(
+    existing_hash: Option<&str>,
+    incoming_hash: &str,
+    on_conflict: OnConflict,
+) -> VersionAction {
+    match existing_hash {
+        None => VersionAction::Insert,
+        Some(existing) if existing.eq_ignore_ascii_case(incoming_hash) => VersionAction::Refresh,
+        Some(_) => VersionAction::Conflict(on_conflict),
+    }
+}
+
+/// Ownership and identity of a stored contract, which an import only
+/// replaces with `on_conflict=overwrite`
+#[derive(Debug, Clone, PartialEq, Eq, sqlx::FromRow)]
+pub struct StoredContract {
+    pub publisher_id: Uuid,
+    pub organization_id: Option<Uuid>,
+    pub wasm_hash: String,
+}
+
+/// The fields of `stored` that `incoming` would change
+pub fn contract_conflicts(stored: &StoredContract, incoming: &StoredContract) -> Vec<String> {
+    let mut fields = Vec::new();
+    if stored.publisher_id != incoming.publisher_id {
+        fields.push("publisher_id".to_string());
+    }
+    if stored.organization_id != incoming.organization_id {
+        fields.push("organization_id".to_string());
+    }
+    if !stored.wasm_hash.eq_ignore_ascii_case(&incoming.wasm_hash) {
+        fields.push("wasm_hash".to_string());
+    }
+    fields
+}
+
+/// Parse one NDJSON line; blank lines yield `None`
+pub fn parse_import_line(line: &str) -> Result<Option<ImportRecord>, String> {
+    let line = line.trim();
+    if line.is_empty() {
+        return Ok(None);
+    }
+    serde_json::from_str(line)
+        .map(Some)
+        .map_err(|err| format!("invalid record: {}", err))
+}
+
+/// Import an NDJSON catalog export (admin only)
+#[utoipa::path(
+    post,
+    path = "/api/import",
+    tag = "admin",
+    params(
+        ("on_conflict" = Option<OnConflict>, Query, description = "`skip` (default) or `overwrite` contracts whose owner or hash differs, and versions whose hash differs"),
+    ),
+    request_body(content = String, content_type = "application/x-ndjson", description = "Output of GET /api/export"),
+    responses(
+        (status = 200, description = "What was created, updated, skipped and in conflict", body = ImportSummary),
+        (status = 403, description = "Caller is not an admin"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn import_catalog(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Query(params): Query<ImportParams>,
+    body: Body,
+) -> ApiResult<Json<ImportSummary>> {
+    caller.require_admin()?;
+
+    let stream = body.into_data_stream().map_err(std::io::Error::other);
+    let mut lines = StreamReader::new(stream).lines();
+
+    let mut summary = ImportSummary::default();
+    let mut batch: Vec<(u64, ImportRecord)> = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut line_number = 0u64;
+    loop {
+        let line = lines.next_line().await.map_err(|err| {
+            ApiError::bad_request(
+                ErrorCode::InvalidImport,
+                format!("Failed to read import body: {}", err),
+            )
+        })?;
+        let Some(line) = line else { break };
+        line_number += 1;
+        match parse_import_line(&line) {
+            Ok(Some(record)) => batch.push((line_number, record)),
+            Ok(None) => {}
+            Err(message) => summary.errors.push(ImportLineError {
+                line: line_number,
+                message,
+            }),
+        }
+        if batch.len() >= IMPORT_BATCH_SIZE {
+            import_batch(&state, &mut batch, params.on_conflict, &mut summary).await?;
+        }
+    }
+    if !batch.is_empty() {
+        import_batch(&state, &mut batch, params.on_conflict, &mut summary).await?;
+    }
+
+    let changed = summary.contracts.created
+        + summary.contracts.updated
+        + summary.versions.created
+        + summary.versions.updated;
+    if changed > 0 {
+        state.stats.invalidate();
+    }
+    tracing::info!(
+        contracts = ?summary.contracts,
+        versions = ?summary.versions,
+        contract_conflicts = summary.contract_conflicts.len(),
+        conflicts = summary.conflicts.len(),
+        errors = summary.errors.len(),
+        "catalog import finished"
+    );
+    Ok(Json(summary))
+}
+
+/// Upsert and drain `batch` in one transaction. A database failure aborts the
+/// request; batches committed before it stay, and re-running is safe.
+async fn import_batch(
+    state: &AppState,
+    batch: &mut Vec<(u64, ImportRecord)>,
+    on_conflict: OnConflict,
+    summary: &mut ImportSummary,
+) -> ApiResult<()> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin import batch", err))?;
+    for (line, record) in batch.drain(..) {
+        import_record(&mut tx, line, &record, on_conflict, summary).await?;
+    }
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit import batch", err))
+}
+
+async fn import_record(
+    tx: &mut Transaction<'_, Postgres>,
+    line: u64,
+    record: &ImportRecord,
+    on_conflict: OnConflict,
+    summary: &mut ImportSummary,
+) -> ApiResult<()> {
+    let publisher_exists: bool =
+        sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM publishers WHERE id = $1)")
+            .bind(record.publisher_id)
+            .fetch_one(&mut **tx)
+            .await
+            .map_err(|err| db_internal_error("check import publisher", err))?;
+    if !publisher_exists {
+        summary.errors.push(ImportLineError {
+            line,
+            message: format!("No publisher found with ID: {}", record.publisher_id),
+        });
+        return Ok(());
+    }
+
+    // Unknown organizations are dropped rather than failing the record
+    let organization_id: Option<Uuid> = match record.organization_id {
+        Some(id) => sqlx::query_scalar("SELECT id FROM organizations WHERE id = $1")
+            .bind(id)
+            .fetch_optional(&mut **tx)
+            .await
+            .map_err(|err| db_internal_error("check import organization", err))?,
+        None => None,
+    };
+
+    let stored: Option<StoredContract> = sqlx::query_as(
+        "SELECT publisher_id, organization_id, wasm_hash FROM contracts
+         WHERE contract_id = $1 AND network = $2
+         FOR UPDATE",
+    )
+    .bind(&record.contract_id)
+    .bind(&record.network)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|err| db_internal_error("get imported contract", err))?;
+    if let Some(stored) = stored {
+        let incoming = StoredContract {
+            publisher_id: record.publisher_id,
+            organization_id,
+            wasm_hash: record.wasm_hash.clone(),
+        };
+        let fields = contract_conflicts(&stored, &incoming);
+        if !fields.is_empty() {
+            summary.contract_conflicts.push(ImportContractConflict {
+                line,
+                contract_id: record.contract_id.clone(),
+                network: record.network.clone(),
+                fields,
+                resolution: on_conflict,
+            });
+            if on_conflict == OnConflict::Skip {
+                summary.contracts.skipped += 1;
+                summary.versions.skipped += record.versions.len() as u64;
+                return Ok(());
+            }
+        }
+    }
+
+    let upserted: Option<(Uuid, bool)> = sqlx::query_as(
+        "INSERT INTO contracts
+             (contract_id, network, wasm_hash, name, description, publisher_id,
+              organization_id, is_verified, category, tags, metadata, visibility)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
+         ON CONFLICT (contract_id, network) DO UPDATE SET
+             wasm_hash = EXCLUDED.wasm_hash,
+             name = EXCLUDED.name,
+             description = EXCLUDED.description,
+             publisher_id = EXCLUDED.publisher_id,
+             organization_id = EXCLUDED.organization_id,
+             is_verified = EXCLUDED.is_verified,
+             category = EXCLUDED.category,
+             tags = EXCLUDED.tags,
+             metadata = EXCLUDED.metadata,
+             visibility = EXCLUDED.visibility,
+             updated_at = NOW()
+         WHERE (contracts.wasm_hash, contracts.name, contracts.description,
+                contracts.publisher_id, contracts.organization_id, contracts.is_verified,
+                contracts.category, contracts.tags, contracts.metadata, contracts.visibility)
+               IS DISTINCT FROM
+               (EXCLUDED.wasm_hash, EXCLUDED.name, EXCLUDED.description,
+                EXCLUDED.publisher_id, EXCLUDED.organization_id, EXCLUDED.is_verified,
+                EXCLUDED.category, EXCLUDED.tags, EXCLUDED.metadata, EXCLUDED.visibility)
+         RETURNING id, (xmax = 0) AS inserted",
+    )
+    .bind(&record.contract_id)
+    .bind(&record.network)
+    .bind(&record.wasm_hash)
+    .bind(&record.name)
+    .bind(&record.description)
+    .bind(record.publisher_id)
+    .bind(organization_id)
+    .bind(record.is_verified)
+    .bind(&record.category)
+    .bind(&record.tags)
+    .bind(&record.metadata)
+    .bind(record.visibility)
+    .fetch_optional(&mut **tx)
+    .await
+    .map_err(|err| db_internal_error("upsert imported contract", err))?;
+
+    let contract_uuid = match upserted {
+        Some((id, true)) => {
+            summary.contracts.created += 1;
+            id
+        }
+        Some((id, false)) => {
+            summary.contracts.updated += 1;
+            id
+        }
+        None => {
+            summary.contracts.skipped += 1;
+            sqlx::query_scalar("SELECT id FROM contracts WHERE contract_id = $1 AND network = $2")
+                .bind(&record.contract_id)
+                .bind(&record.network)
+                .fetch_one(&mut **tx)
+                .await
+                .map_err(|err| db_internal_error("get imported contract", err))?
+        }
+    };
+
+    for version in &record.versions {
+        let existing_hash: Option<String> = sqlx::query_scalar(
+            "SELECT wasm_hash FROM contract_versions WHERE contract_id = $1 AND version = $2",
+        )
+        .bind(contract_uuid)
+        .bind(&version.version)
+        .fetch_optional(&mut **tx)
+        .await
+        .map_err(|err| db_internal_error("get imported version", err))?;
+
+        match version_action(existing_hash.as_deref(), &version.wasm_hash, on_conflict) {
+            VersionAction::Insert => {
+                insert_version(tx, contract_uuid, version).await?;
+                summary.versions.created += 1;
+            }
+            VersionAction::Refresh => {
+                if update_version(tx, contract_uuid, version).await? {
+                    summary.versions.updated += 1;
+                } else {
+                    summary.versions.skipped += 1;
+                }
+            }
+            VersionAction::Conflict(resolution) => {
+                if resolution == OnConflict::Overwrite {
+                    update_version(tx, contract_uuid, version).await?;
+                    summary.versions.updated += 1;
+                } else {
+                    summary.versions.skipped += 1;
+                }
+                summary.conflicts.push(ImportConflict {
+                    line,
+                    contract_id: record.contract_id.clone(),
+                    network: record.network.clone(),
+                    version: version.version.clone(),
+                    existing_hash: existing_hash.unwrap_or_default(),
+                    incoming_hash: version.wasm_hash.clone(),
+                    resolution,
+                });
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn insert_version(
+    tx: &mut Transaction<'_, Postgres>,
+    contract_uuid: Uuid,
+    version: &ImportVersion,
+) -> ApiResult<()> {
+    sqlx::query(
+        "INSERT INTO contract_versions
+             (contract_id, version, wasm_hash, source_url, commit_hash, release_notes,
+              created_at, deprecated, deprecated_at, deprecation_reason, superseded_by,
+              yanked, yanked_at)
+         VALUES ($1, $2, $3, $4, $5, $6, COALESCE($7, NOW()), $8, $9, $10, $11, $12, $13)",
+    )
+    .bind(contract_uuid)
+    .bind(&version.version)
+    .bind(&version.wasm_hash)
+    .bind(&version.source_url)
+    .bind(&version.commit_hash)
+    .bind(&version.release_notes)
+    .bind(version.created_at)
+    .bind(version.deprecated)
+    .bind(version.deprecated_at)
+    .bind(&version.deprecation_reason)
+    .bind(&version.superseded_by)
+    .bind(version.yanked)
+    .bind(version.yanked_at)
+    .execute(&mut **tx)
+    .await
+    .map_err(|err| db_internal_error("insert imported version", err))?;
+    Ok(())
+}
+
+/// Bring a stored version in line with the import; false if nothing differed
+async fn update_version(
+    tx: &mut Transaction<'_, Postgres>,
+    contract_uuid: Uuid,
+    version: &ImportVersion,
+) -> ApiResult<bool> {
+    let result = sqlx::query(
+        "UPDATE contract_versions SET
+             wasm_hash = $3, source_url = $4, commit_hash = $5, release_notes = $6,
+             deprecated = $7, deprecated_at = $8, deprecation_reason = $9,
+             superseded_by = $10, yanked = $11, yanked_at = $12
+         WHERE contract_id = $1 AND version = $2
+           AND (wasm_hash, source_url, commit_hash, release_notes, deprecated, deprecated_at,
+                deprecation_reason, superseded_by, yanked, yanked_at)
+               IS DISTINCT FROM ($3, $4, $5, $6, $7, $8, $9, $10, $11, $12)",
+    )
+    .bind(contract_uuid)
+    .bind(&version.version)
+    .bind(&version.wasm_hash)
+    .bind(&version.source_url)
+    .bind(&version.commit_hash)
+    .bind(&version.release_notes)
+    .bind(version.deprecated)
+    .bind(version.deprecated_at)
+    .bind(&version.deprecation_reason)
+    .bind(&version.superseded_by)
+    .bind(version.yanked)
+    .bind(version.yanked_at)
+    .execute(&mut **tx)
+    .await
+    .map_err(|err| db_internal_error("update imported version", err))?;
+    Ok(result.rows_affected() > 0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_export_lines() {
+        let line = serde_json::json!({
+            "id": Uuid::new_v4(),
+            "contract_id": "CABC",
+            "network": "testnet",
+            "wasm_hash": "aa11",
+            "name": "token",
+            "description": null,
+            "publisher_id": Uuid::new_v4(),
+            "organization_id": null,
+            "is_verified": true,
+            "visibility": "private",
+            "category": "defi",
+            "tags": ["token"],
+            "metadata": null,
+            "created_at": "2026-01-01T00:00:00Z",
+            "updated_at": "2026-01-02T00:00:00Z",
+            "versions": [{
+                "id": Uuid::new_v4(),
+                "contract_id": Uuid::new_v4(),
+                "version": "1.0.0",
+                "wasm_hash": "aa11",
+                "source_url": null,
+                "commit_hash": null,
+                "release_notes": "first",
+                "created_at": "2026-01-01T00:00:00Z",
+                "deprecated": false,
+                "yanked": false,
+                "download_count": 3,
+            }],
+        })
+        .to_string();
+
+        let record = parse_import_line(&line).unwrap().unwrap();
+        assert_eq!(record.contract_id, "CABC");
+        assert!(matches!(record.network, Network::Testnet));
+        assert_eq!(record.visibility, ContractVisibility::Private);
+        assert_eq!(record.versions.len(), 1);
+        assert_eq!(record.versions[0].release_notes.as_deref(), Some("first"));
+
+        assert!(parse_import_line("   ").unwrap().is_none());
+        assert!(parse_import_line("{\"name\": 1}").is_err());
+    }
+
+    #[test]
+    fn differing_hashes_are_conflicts() {
+        assert_eq!(
+            version_action(None, "aa", OnConflict::Skip),
+            VersionAction::Insert
+        );
+        assert_eq!(
+            version_action(Some("AA"), "aa", OnConflict::Skip),
+            VersionAction::Refresh
+        );
+        assert_eq!(
+            version_action(Some("bb"), "aa", OnConflict::Skip),
+            VersionAction::Conflict(OnConflict::Skip)
+        );
+        assert_eq!(
+            version_action(Some("bb"), "aa", OnConflict::Overwrite),
+            VersionAction::Conflict(OnConflict::Overwrite)
+        );
+    }
+
+    #[test]
+    fn ownership_and_hash_changes_are_contract_conflicts() {
+        let stored = StoredContract {
+            publisher_id: Uuid::new_v4(),
+            organization_id: None,
+            wasm_hash: "AA11".into(),
+        };
+        let same = StoredContract {
+            wasm_hash: "aa11".into(),
+            ..stored.clone()
+        };
+        assert!(contract_conflicts(&stored, &same).is_empty());
+
+        let moved = StoredContract {
+            publisher_id: Uuid::new_v4(),
+            organization_id: Some(Uuid::new_v4()),
+            wasm_hash: "bb22".into(),
+        };
+        assert_eq!(
+            contract_conflicts(&stored, &moved),
+            ["publisher_id", "organization_id", "wasm_hash"]
+        );
+    }
+
+    #[tokio::test]
+    async fn ownership_changes_are_imported_only_on_overwrite() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let owner = state.insert_publisher().await;
+        let other = state.insert_publisher().await;
+        let id = state.insert_contract(owner, None, "public").await;
+        let (address, hash): (String, String) =
+            sqlx::query_as("SELECT contract_id, wasm_hash FROM contracts WHERE id = $1")
+                .bind(id)
+                .fetch_one(&state.db)
+                .await
+                .unwrap();
+        let line = serde_json::json!({
+            "contract_id": address,
+            "network": "testnet",
+            "wasm_hash": hash,
+            "name": "taken",
+            "description": null,
+            "publisher_id": other,
+            "category": null,
+        });
+        let import = |on_conflict| {
+            import_catalog(
+                State(state.clone()),
+                Extension(Caller::Admin),
+                Query(ImportParams { on_conflict }),
+                Body::from(line.to_string()),
+            )
+        };
+        let publisher = || async {
+            sqlx::query_scalar::<_, Uuid>("SELECT publisher_id FROM contracts WHERE id = $1")
+                .bind(id)
+                .fetch_one(&state.db)
+                .await
+                .unwrap()
+        };
+
+        let Json(skipped) = import(OnConflict::Skip).await.unwrap();
+        assert_eq!(skipped.contract_conflicts[0].fields, ["publisher_id"]);
+        assert_eq!(skipped.contracts.skipped, 1);
+        assert_eq!(publisher().await, owner);
+
+        let Json(overwritten) = import(OnConflict::Overwrite).await.unwrap();
+        assert_eq!(overwritten.contract_conflicts.len(), 1);
+        assert_eq!(overwritten.contracts.updated, 1);
+        assert_eq!(publisher().await, other);
+    }
+
+    #[test]
+    fn on_conflict_defaults_to_skip() {
+        let uri: axum::http::Uri = "/api/import".parse().unwrap();
+        let Query(params) = Query::<ImportParams>::try_from_uri(&uri).unwrap();
+        assert_eq!(params.on_conflict, OnConflict::Skip);
+
+        let uri: axum::http::Uri = "/api/import?on_conflict=clobber".parse().unwrap();
+        assert!(Query::<ImportParams>::try_from_uri(&uri).is_err());
+    }
+}
@@ -0,0 +1,145 @@
+//! Optional IPFS pinning of published artifacts.
+//!
+//! Enabled by setting `IPFS_API_URL` to a Kubo-compatible HTTP API (for
+//! example `http://127.0.0.1:5001`). Pinning runs in the background after
+//! publish; the version row tracks `pending` → `pinned`/`failed` so clients can
+//! tell whether the CID is live yet.
+
+use std::time::Duration;
+
+use serde::Deserialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+pub const STATUS_PENDING: &str = "pending";
+pub const STATUS_PINNED: &str = "pinned";
+pub const STATUS_FAILED: &str = "failed";
+
+const PIN_TIMEOUT: Duration = Duration::from_secs(60);
+const MULTIPART_BOUNDARY: &str = "soroban-registry-ipfs-boundary";
+
+#[derive(Debug, Clone)]
+pub struct IpfsClient {
+    api_url: String,
+}
+
+#[derive(Deserialize)]
+struct AddResponse {
+    #[serde(rename = "Hash")]
+    hash: String,
+}
+
+impl IpfsClient {
+    /// Returns `None` when `IPFS_API_URL` is unset, which disables pinning.
+    pub fn from_env() -> Option<Self> {
+        std::env::var("IPFS_API_URL")
+            .ok()
+            .map(|url| url.trim().trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty())
+            .map(|api_url| Self { api_url })
+    }
+
+    /// Add and pin `wasm`, returning its CID.
+    pub async fn pin(&self, filename: &str, wasm: &[u8]) -> Result<String, String> {
+        let client = reqwest::Client::builder()
+            .timeout(PIN_TIMEOUT)
+            .build()
+            .map_err(|err| err.to_string())?;
+
+        let response = client
+            .post(format!("{}/api/v0/add?pin=true&cid-version=1", self.api_url))
+            .header(
+                reqwest::header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", MULTIPART_BOUNDARY),
+            )
+            .body(multipart_file_body(filename, wasm))
+            .send()
+            .await
+            .map_err(|err| err.to_string())?;
+
+        if !response.status().is_success() {
+            return Err(format!("IPFS API returned HTTP {}", response.status()));
+        }
+
+        let body = response.text().await.map_err(|err| err.to_string())?;
+        parse_add_response(&body)
+    }
+}
+
+/// Pin a version's artifact in the background and record the outcome.
+///
+/// The caller is expected to have already set `ipfs_status = 'pending'`.
+pub fn spawn_pin(client: IpfsClient, pool: PgPool, version_id: Uuid, filename: String, wasm: Vec<u8>) {
+    tokio::spawn(async move {
+        let result = client.pin(&filename, &wasm).await;
+
+        let update = match &result {
+            Ok(cid) => sqlx::query(
+                "UPDATE contract_versions SET ipfs_cid = $2, ipfs_status = $3 WHERE id = $1",
+            )
+            .bind(version_id)
+            .bind(cid)
+            .bind(STATUS_PINNED),
+            Err(err) => {
+                tracing::warn!(version_id = %version_id, error = %err, "ipfs pinning failed");
+                sqlx::query("UPDATE contract_versions SET ipfs_status = $2 WHERE id = $1")
+                    .bind(version_id)
+                    .bind(STATUS_FAILED)
+            }
+        };
+
+        if let Err(err) = update.execute(&pool).await {
+            tracing::error!(version_id = %version_id, error = ?err, "failed to record ipfs pin status");
+        }
+    });
+}
+
+fn multipart_file_body(filename: &str, wasm: &[u8]) -> Vec<u8> {
+    let mut body = Vec::with_capacity(wasm.len() + 256);
+    body.extend_from_slice(
+        format!(
+            "--{}\r\nContent-Disposition: form-data; name=\"file\"; filename=\"{}\"\r\nContent-Type: application/wasm\r\n\r\n",
+            MULTIPART_BOUNDARY, filename
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(wasm);
+    body.extend_from_slice(format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY).as_bytes());
+    body
+}
+
+/// `/api/v0/add` streams one JSON object per added file; the last one is ours.
+fn parse_add_response(body: &str) -> Result<String, String> {
+    body.lines()
+        .filter(|line| !line.trim().is_empty())
+        .last()
+        .ok_or_else(|| "empty IPFS add response".to_string())
+        .and_then(|line| {
+            serde_json::from_str::<AddResponse>(line)
+                .map(|resp| resp.hash)
+                .map_err(|err| format!("unexpected IPFS add response: {}", err))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_cid_from_add_response() {
+        let body = r#"{"Name":"token-1.0.0.wasm","Hash":"bafkreigh2akiscaildc","Size":"1024"}
+"#;
+        assert_eq!(parse_add_response(body).unwrap(), "bafkreigh2akiscaildc");
+        assert!(parse_add_response("").is_err());
+        assert!(parse_add_response("not json").is_err());
+    }
+
+    #[test]
+    fn multipart_body_wraps_file_part() {
+        let body = multipart_file_body("a.wasm", b"\0asm");
+        let text = String::from_utf8_lossy(&body);
+        assert!(text.starts_with(&format!("--{}\r\n", MULTIPART_BOUNDARY)));
+        assert!(text.contains("filename=\"a.wasm\""));
+        assert!(text.ends_with(&format!("\r\n--{}--\r\n", MULTIPART_BOUNDARY)));
+    }
+}
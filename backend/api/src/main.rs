@@ -8,56 +8,90 @@ mod patch;
 mod profiler;
 mod test_framework;
 mod wizard;
+mod abi;
+mod abi_compat;
+mod admin_audit;
 mod aggregation;
 mod analytics;
+mod artifacts;
+mod auth;
+mod badge;
 mod audit_handlers;
+mod audit_pdf;
 mod audit_routes;
 mod benchmark_engine;
 mod benchmark_handlers;
 mod benchmark_routes;
+mod build_verification;
 mod cache;
 mod cache_benchmark;
 mod checklist;
+mod compare;
 mod config_handlers;
 mod config_routes;
 mod contract_history_handlers;
 mod contract_history_routes;
+mod cors;
+mod deployment_handlers;
 mod detector;
+mod downloads;
+mod export_handlers;
+mod import_handlers;
 mod error;
+mod etag;
+mod feed;
 mod handlers;
+mod ipfs;
+mod metadata_schema;
 mod metrics;
 mod observability;
 mod metrics_handler;
+mod openapi;
+mod organization_handlers;
+mod pagination;
 mod models;
 mod multisig_handlers;
 mod multisig_routes;
 mod observability;
 mod popularity;
 mod rate_limit;
+mod readme;
 mod residency_handlers;
 mod residency_routes;
 mod routes;
+mod scoring;
+mod share_tokens;
+mod shutdown;
+mod signing;
+mod sarif;
+mod sbom;
+mod soroban_rpc;
 mod state;
 mod template_handlers;
 mod template_routes;
+mod template_usage;
+mod transfer_handlers;
 mod scanner_service;
 mod scan_handlers;
 mod scan_routes;
+mod trending;
 mod trust;
+mod upload;
 mod health_monitor;
 mod migration_cli;
 mod validation;
+mod webhook_handlers;
+mod webhook_routes;
+mod webhooks;
 mod type_safety;
 mod type_safety_handlers;
 mod type_safety_routes;
 
 use anyhow::Result;
-use axum::http::{header, HeaderValue, Method};
 use axum::{middleware, routing::get, Router};
 use dotenv::dotenv;
 use sqlx::postgres::PgPoolOptions;
 use std::net::SocketAddr;
-use tower_http::cors::CorsLayer;
 
 use crate::observability::Observability;
 use crate::rate_limit::RateLimitState;
@@ -67,11 +101,24 @@ use crate::state::AppState;
 async fn main() -> Result<()> {
     dotenv().ok();
 
+    if let Err(problems) = config::validate() {
+        eprintln!("invalid configuration ({} problems):", problems.len());
+        for problem in &problems {
+            eprintln!("  - {}", problem);
+        }
+        std::process::exit(1);
+    }
+
     let otlp_endpoint = std::env::var("OTLP_ENDPOINT")
         .unwrap_or_else(|_| "http://jaeger:4317".to_string());
     observability::init(&otlp_endpoint);
     metrics::init_metrics();
 
+    let custom_rules = detector::init_custom_rules_from_env()?;
+    if custom_rules > 0 {
+        tracing::info!(count = custom_rules, "loaded custom detector rules");
+    }
+
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
     let pool = PgPoolOptions::new()
         .max_connections(5)
@@ -81,10 +128,47 @@ async fn main() -> Result<()> {
     sqlx::migrate!("../../database/migrations").run(&pool).await?;
     tracing::info!("database connected and migrations applied");
 
-    aggregation::spawn_aggregation_task(pool.clone());
+    let dynamic_rules = detector::sync_dynamic_rules(&pool).await?;
+    if dynamic_rules > 0 {
+        tracing::info!(count = dynamic_rules, "loaded dynamic detector rules");
+    }
 
+    let aggregation_interval = aggregation::interval_from_env().map_err(anyhow::Error::msg)?;
+    let grace_period = shutdown::grace_period_from_env().map_err(anyhow::Error::msg)?;
+    let shutdown_token = tokio_util::sync::CancellationToken::new();
+    shutdown::spawn_signal_listener(shutdown_token.clone());
+
+    let aggregation_task = aggregation::spawn_aggregation_task(
+        pool.clone(),
+        aggregation_interval,
+        shutdown_token.clone(),
+    );
+    scanner_service::spawn_scan_worker(
+        pool.clone(),
+        scanner_service::ScannerConfig::from_env().map_err(anyhow::Error::msg)?,
+    );
+    scanner_service::spawn_rescan_scheduler(
+        pool.clone(),
+        scanner_service::RescanConfig::from_env().map_err(anyhow::Error::msg)?,
+    );
+
+    let db = pool.clone();
     let state = AppState::new(pool);
+    let flush_interval = downloads::flush_interval_from_env().map_err(anyhow::Error::msg)?;
+    let download_flush_task = downloads::spawn_flush_task(
+        db.clone(),
+        state.downloads.clone(),
+        flush_interval,
+        shutdown_token.clone(),
+    );
+    let template_usage_task = template_usage::spawn_flush_task(
+        db.clone(),
+        state.template_usage.clone(),
+        flush_interval,
+        shutdown_token.clone(),
+    );
     let obs = Observability::init()?;
+    let cors = cors::cors_layer_from_env()?;
 
     /// Enable verbose output (shows HTTP requests, responses, and debug info)
     #[arg(long, short = 'v', global = true)]
@@ -515,7 +599,7 @@ async fn main() -> Result<()> {
 }
     // Create app state
     let state = AppState::new(pool, obs.registry);
-    let rate_limit_state = RateLimitState::from_env();
+    let rate_limit_state = RateLimitState::from_env().map_err(anyhow::Error::msg)?;
 
         /// Output JSON file
         #[arg(long)]
@@ -835,44 +919,50 @@ async fn main() -> Result<()> {
     Ok(())
 }
     // Build router
-    let app = Router::new()
-        .merge(routes::contract_routes())
-        .merge(routes::publisher_routes())
-        .merge(routes::health_routes())
-        .merge(routes::migration_routes())
-        .merge(routes::canary_routes())
-        .merge(routes::ab_test_routes())
-        .merge(routes::performance_routes())
-        .merge(multisig_routes::multisig_routes())
-        .merge(audit_routes::security_audit_routes())
-        .merge(benchmark_routes::benchmark_routes())
-        .merge(config_routes::config_routes())
-        .merge(contract_history_routes::contract_history_routes())
-        .merge(template_routes::template_routes())
-        .merge(scan_routes::scan_routes())
+    let app = routes::api_routes(&state)
         .route("/metrics", get(observability::metrics_handler))
         .merge(routes::observability_routes())
-        .merge(residency_routes::residency_routes())
-        .merge(type_safety_routes::type_safety_routes())
-        .fallback(handlers::route_not_found)
+        .merge(openapi::openapi_routes())
         .layer(middleware::from_fn(metrics_middleware))
         .layer(middleware::from_fn_with_state(
             rate_limit_state,
             rate_limit::rate_limit_middleware,
         ))
-        .layer(CorsLayer::permissive())
         .layer(cors)
+        .layer(middleware::from_fn(observability::request_id_middleware))
         .with_state(state);
 
     let addr = SocketAddr::from(([0, 0, 0, 0], 3001));
     tracing::info!(addr = %addr, "API server listening");
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
-    axum::serve(
+    let server = axum::serve(
         listener,
         app.into_make_service_with_connect_info::<SocketAddr>(),
     )
-    .await?;
+    .with_graceful_shutdown(shutdown_token.clone().cancelled_owned());
+
+    tokio::select! {
+        result = server => result?,
+        _ = shutdown::grace_deadline(shutdown_token.clone(), grace_period) => {
+            tracing::warn!(
+                grace_secs = grace_period.as_secs(),
+                "shutdown grace period elapsed; dropping remaining requests"
+            );
+        }
+    }
+
+    if let Err(err) = aggregation_task.await {
+        tracing::error!(error = ?err, "aggregation task panicked during shutdown");
+    }
+    if let Err(err) = download_flush_task.await {
+        tracing::error!(error = ?err, "download flush task panicked during shutdown");
+    }
+    if let Err(err) = template_usage_task.await {
+        tracing::error!(error = ?err, "template usage task panicked during shutdown");
+    }
+    db.close().await;
+    tracing::info!("shutdown complete");
 
     Ok(())
 }
@@ -882,13 +972,12 @@ async fn metrics_middleware(
     next: middleware::Next,
 ) -> axum::response::Response {
     let method = req.method().to_string();
-    let path = req
-        .uri()
-        .path()
-        .to_string()
-        .replace(|c: char| c.is_ascii_alphanumeric() || c == '/' || c == '-' || c == '_', |c: char| c)
-        .trim_end_matches(|c: char| c.is_ascii_digit())
-        .to_string();
+    let path = metrics::route_label(&req);
+    let request_id = req
+        .extensions()
+        .get::<observability::RequestId>()
+        .map(|id| id.0.clone())
+        .unwrap_or_default();
     let timer = std::time::Instant::now();
 
     let response = next.run(req).await;
@@ -902,8 +991,12 @@ async fn metrics_middleware(
     metrics::HTTP_REQUEST_DURATION
         .with_label_values(&[&method, &path])
         .observe(elapsed);
+    metrics::observe_publisher_status(
+        response.extensions().get::<auth::Caller>(),
+        response.status().as_u16(),
+    );
 
-    tracing::info!(method = %method, path = %path, status = %status, latency_ms = %(elapsed * 1000.0) as u64);
+    tracing::info!(request_id = %request_id, method = %method, path = %path, status = %status, latency_ms = %(elapsed * 1000.0) as u64);
 
     response
 }
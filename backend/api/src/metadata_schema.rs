@@ -0,0 +1,106 @@
+// api/src/metadata_schema.rs
+// JSON Schema validation of publisher-supplied contract metadata.
+//
+// Schemas are registered per category, with `DEFAULT_CATEGORY` applying to
+// contracts whose category has none. With no schema registered at all,
+// metadata is only required to be a JSON object.
+
+use jsonschema::JSONSchema;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::PgPool;
+
+/// Category key of the fallback schema
+pub const DEFAULT_CATEGORY: &str = "_default";
+
+/// One schema violation, located by JSON pointer into the metadata
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetadataError {
+    pub path: String,
+    pub message: String,
+}
+
+/// Compile `schema`, reporting why it isn't a usable JSON Schema
+pub fn compile(schema: &Value) -> Result<JSONSchema, String> {
+    JSONSchema::compile(schema).map_err(|err| err.to_string())
+}
+
+/// Every violation of `schema` in `metadata`, not just the first
+pub fn validate(schema: &JSONSchema, metadata: &Value) -> Vec<MetadataError> {
+    let mut errors: Vec<MetadataError> = match schema.validate(metadata) {
+        Ok(()) => Vec::new(),
+        Err(errors) => errors
+            .map(|err| {
+                let path = err.instance_path.to_string();
+                MetadataError {
+                    path: if path.is_empty() { "/".to_string() } else { path },
+                    message: err.to_string(),
+                }
+            })
+            .collect(),
+    };
+    errors.sort_by(|a, b| a.path.cmp(&b.path));
+    errors
+}
+
+/// Schema for `category`, falling back to the default one
+pub async fn load_for_category(
+    pool: &PgPool,
+    category: Option<&str>,
+) -> Result<Option<Value>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT schema FROM metadata_schemas
+         WHERE category = $1 OR category = $2
+         ORDER BY (category = $2)
+         LIMIT 1",
+    )
+    .bind(category.unwrap_or(DEFAULT_CATEGORY))
+    .bind(DEFAULT_CATEGORY)
+    .fetch_optional(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn schema() -> JSONSchema {
+        compile(&json!({
+            "type": "object",
+            "required": ["license", "audited"],
+            "properties": {
+                "license": { "type": "string" },
+                "audited": { "type": "boolean" },
+                "homepage": { "type": "string", "format": "uri" },
+                "maintainers": { "type": "array", "items": { "type": "string" } }
+            },
+            "additionalProperties": false
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn valid_metadata_has_no_errors() {
+        let metadata = json!({ "license": "MIT", "audited": true, "maintainers": ["a"] });
+        assert!(validate(&schema(), &metadata).is_empty());
+    }
+
+    #[test]
+    fn reports_every_violation_with_its_path() {
+        let metadata = json!({ "license": 5, "maintainers": ["a", 2], "extra": 1 });
+        let errors = validate(&schema(), &metadata);
+        let paths: Vec<&str> = errors.iter().map(|e| e.path.as_str()).collect();
+
+        assert!(errors.len() >= 4, "expected all violations, got {:?}", errors);
+        assert!(paths.contains(&"/license"));
+        assert!(paths.contains(&"/maintainers/1"));
+        // Missing required and unexpected properties are reported at the root
+        assert!(paths.contains(&"/"));
+    }
+
+    #[test]
+    fn rejects_invalid_schemas() {
+        assert!(compile(&json!({ "type": "not-a-type" })).is_err());
+    }
+}
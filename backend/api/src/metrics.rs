@@ -1,4 +1,10 @@
+use std::collections::HashSet;
+use std::sync::Mutex;
+
 use once_cell::sync::Lazy;
+use uuid::Uuid;
+
+use crate::auth::Caller;
 use prometheus::{
     histogram_opts, opts, GaugeVec, HistogramVec, IntCounterVec, Registry,
 };
@@ -260,6 +266,38 @@ pub static PUBLISHERS_TOTAL: Lazy<IntGauge> = gauge!("publishers_total", "Total
 pub static PUBLISHER_REGISTRATIONS: Lazy<IntCounter> =
     counter!("publisher_registrations_total", "Publisher registrations");
 
+// ── Scans and audits ────────────────────────────────────────────────────────
+pub static SCANS_COMPLETED: Lazy<IntCounterVec> =
+    counter_vec!("scans_completed_total", "Scans finished, by kind", &["kind"]);
+pub static AUDITS_COMPLETED: Lazy<IntCounter> =
+    counter!("audits_completed_total", "Security audits created and scored");
+
+// ── Rate limiting ───────────────────────────────────────────────────────────
+pub static RATE_LIMIT_DECISIONS: Lazy<IntCounterVec> = counter_vec!(
+    "rate_limit_decisions_total",
+    "Rate-limit checks, by route template and outcome (allowed/rejected)",
+    &["route", "outcome"]
+);
+// Memory backend only: with RATE_LIMIT_BACKEND=redis buckets live in Redis
+// and expire there, so this gauge is never set.
+pub static RATE_LIMIT_ACTIVE_BUCKETS: Lazy<IntGauge> = gauge!(
+    "rate_limit_active_buckets",
+    "Client/endpoint buckets tracked by the in-memory rate limiter; expired ones are dropped by a periodic sweep (memory backend only)"
+);
+
+// ── Per publisher ───────────────────────────────────────────────────────────
+// The `publisher` label is bounded by PublisherLabels: never more than
+// PUBLISHER_LABEL_LIMIT publisher ids plus the three shared labels.
+pub static PUBLISHER_PUBLISHES: Lazy<IntCounterVec> =
+    counter_vec!("publisher_publishes_total", "Contracts published per publisher", &["publisher"]);
+pub static PUBLISHER_SCANS: Lazy<IntCounterVec> =
+    counter_vec!("publisher_scans_total", "Scans submitted per publisher", &["publisher"]);
+pub static PUBLISHER_HTTP_ERRORS: Lazy<IntCounterVec> = counter_vec!(
+    "publisher_http_errors_total",
+    "4xx/5xx responses per publisher",
+    &["publisher", "class"]
+);
+
 pub fn register_all(r: &Registry) -> prometheus::Result<()> {
     r.register(Box::new(HTTP_REQUESTS_TOTAL.clone()))?;
     r.register(Box::new(HTTP_REQUEST_DURATION.clone()))?;
@@ -314,6 +352,13 @@ pub fn register_all(r: &Registry) -> prometheus::Result<()> {
     r.register(Box::new(PATCHES_FAILED.clone()))?;
     r.register(Box::new(PUBLISHERS_TOTAL.clone()))?;
     r.register(Box::new(PUBLISHER_REGISTRATIONS.clone()))?;
+    r.register(Box::new(SCANS_COMPLETED.clone()))?;
+    r.register(Box::new(AUDITS_COMPLETED.clone()))?;
+    r.register(Box::new(RATE_LIMIT_DECISIONS.clone()))?;
+    r.register(Box::new(RATE_LIMIT_ACTIVE_BUCKETS.clone()))?;
+    r.register(Box::new(PUBLISHER_PUBLISHES.clone()))?;
+    r.register(Box::new(PUBLISHER_SCANS.clone()))?;
+    r.register(Box::new(PUBLISHER_HTTP_ERRORS.clone()))?;
     Ok(())
 }
 
@@ -325,6 +370,21 @@ pub fn gather_metrics(r: &Registry) -> String {
     String::from_utf8(buf).unwrap_or_default()
 }
 
+/// `path` label for requests that matched no route (404s)
+pub const UNMATCHED_ROUTE_LABEL: &str = "unmatched";
+
+/// Label used for the `path` dimension of the HTTP metrics.
+///
+/// Uses the route template axum matched (`/api/contracts/:id/versions`) so
+/// concrete ids never become label values. Requests that matched no route
+/// share a single label.
+pub fn route_label<B>(req: &axum::http::Request<B>) -> String {
+    req.extensions()
+        .get::<axum::extract::MatchedPath>()
+        .map(|matched| matched.as_str().to_string())
+        .unwrap_or_else(|| UNMATCHED_ROUTE_LABEL.to_string())
+}
+
 pub fn observe_http(method: &str, path: &str, status: u16, duration_secs: f64) {
     HTTP_REQUESTS_TOTAL
         .with_label_values(&[method, path, &status.to_string()])
@@ -357,6 +417,110 @@ pub fn observe_db_query(query: &str, duration_secs: f64) {
     DB_TRANSACTIONS_TOTAL.inc();
 }
 
+/// Most publisher ids that get a label of their own
+pub const PUBLISHER_LABEL_LIMIT: usize = 100;
+/// Publishers seen after the limit was reached
+pub const OTHER_PUBLISHERS_LABEL: &str = "other";
+pub const ANONYMOUS_LABEL: &str = "anonymous";
+pub const ADMIN_LABEL: &str = "admin";
+
+/// Hands out `publisher` label values with a hard cardinality bound.
+///
+/// The first `limit` publishers seen keep their id as the label for the life
+/// of the process; everyone after that shares `other`. Together with the
+/// anonymous and admin labels a metric never carries more than `limit + 3`
+/// publisher values, however many publishers exist.
+pub struct PublisherLabels {
+    limit: usize,
+    seen: Mutex<HashSet<Uuid>>,
+}
+
+impl PublisherLabels {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            seen: Mutex::new(HashSet::new()),
+        }
+    }
+
+    pub fn label(&self, caller: Option<&Caller>) -> String {
+        let id = match caller {
+            None => return ANONYMOUS_LABEL.to_string(),
+            Some(Caller::Admin) => return ADMIN_LABEL.to_string(),
+            Some(Caller::Publisher(id)) => *id,
+        };
+        let mut seen = self.seen.lock().unwrap_or_else(|e| e.into_inner());
+        if seen.contains(&id) || (seen.len() < self.limit && seen.insert(id)) {
+            id.to_string()
+        } else {
+            OTHER_PUBLISHERS_LABEL.to_string()
+        }
+    }
+}
+
+static PUBLISHER_LABELS: Lazy<PublisherLabels> =
+    Lazy::new(|| PublisherLabels::new(PUBLISHER_LABEL_LIMIT));
+
+/// Bounded `publisher` label for the request's auth context
+pub fn publisher_label(caller: Option<&Caller>) -> String {
+    PUBLISHER_LABELS.label(caller)
+}
+
+/// Count a publish, in total and against the publisher
+pub fn record_publisher_publish(caller: &Caller) {
+    CONTRACTS_PUBLISHED.inc();
+    PUBLISHER_PUBLISHES
+        .with_label_values(&[&publisher_label(Some(caller))])
+        .inc();
+}
+
+pub fn record_publisher_scan(caller: Option<&Caller>) {
+    PUBLISHER_SCANS
+        .with_label_values(&[&publisher_label(caller)])
+        .inc();
+}
+
+/// Scan kinds counted by `scans_completed_total`
+pub const SCAN_KIND_DETECTOR: &str = "detector";
+pub const SCAN_KIND_CACHED: &str = "cached";
+pub const SCAN_KIND_DEPENDENCY: &str = "dependency";
+
+pub fn record_scan_completed(kind: &str) {
+    SCANS_COMPLETED.with_label_values(&[kind]).inc();
+}
+
+/// Outcomes counted by `rate_limit_decisions_total`
+pub const RATE_LIMIT_ALLOWED: &str = "allowed";
+pub const RATE_LIMIT_REJECTED: &str = "rejected";
+
+pub fn record_rate_limit_decision(route: &str, allowed: bool) {
+    let outcome = if allowed {
+        RATE_LIMIT_ALLOWED
+    } else {
+        RATE_LIMIT_REJECTED
+    };
+    RATE_LIMIT_DECISIONS.with_label_values(&[route, outcome]).inc();
+}
+
+/// Registry-wide totals, refreshed by the aggregation task rather than
+/// queried on every scrape
+pub fn set_registry_totals(contracts: i64, publishers: i64) {
+    CONTRACTS_TOTAL.set(contracts);
+    PUBLISHERS_TOTAL.set(publishers);
+}
+
+/// Count a 4xx/5xx response against the caller; other statuses are ignored
+pub fn observe_publisher_status(caller: Option<&Caller>, status: u16) {
+    let class = match status {
+        400..=499 => "4xx",
+        500..=599 => "5xx",
+        _ => return,
+    };
+    PUBLISHER_HTTP_ERRORS
+        .with_label_values(&[&publisher_label(caller), class])
+        .inc();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -433,6 +597,117 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_route_label_uses_matched_template() {
+        use axum::{
+            body::Body,
+            http::{HeaderValue, Request},
+            middleware::{self, Next},
+            response::Response,
+            routing::get,
+            Router,
+        };
+        use tower::Service;
+
+        async fn capture_label(req: Request<Body>, next: Next) -> Response {
+            let label = route_label(&req);
+            let mut response = next.run(req).await;
+            response
+                .headers_mut()
+                .insert("x-route-label", HeaderValue::from_str(&label).unwrap());
+            response
+        }
+
+        let mut app: Router<()> = Router::new()
+            .route("/api/contracts", get(|| async { "list" }))
+            .route("/api/contracts/:id", get(|| async { "one" }))
+            .route("/api/contracts/:id/versions", get(|| async { "versions" }))
+            .route(
+                "/api/contracts/:id/security-audit/:audit_id",
+                get(|| async { "audit" }),
+            )
+            .layer(middleware::from_fn(capture_label));
+
+        let cases = [
+            ("/api/contracts", "/api/contracts"),
+            ("/api/contracts/123", "/api/contracts/:id"),
+            (
+                "/api/contracts/7f9c2b1e-0d4a-4c8e-9a57-3e1b2c4d5e6f/versions",
+                "/api/contracts/:id/versions",
+            ),
+            ("/api/contracts/42/versions", "/api/contracts/:id/versions"),
+            (
+                "/api/contracts/abc/security-audit/def",
+                "/api/contracts/:id/security-audit/:audit_id",
+            ),
+            ("/does/not/exist/99", UNMATCHED_ROUTE_LABEL),
+        ];
+
+        for (path, expected) in cases {
+            let response = app
+                .call(Request::get(path).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(
+                response.headers()["x-route-label"],
+                expected,
+                "label for {}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn publisher_labels_are_bounded() {
+        let labels = PublisherLabels::new(2);
+        let (a, b, c) = (Uuid::new_v4(), Uuid::new_v4(), Uuid::new_v4());
+
+        assert_eq!(labels.label(Some(&Caller::Publisher(a))), a.to_string());
+        assert_eq!(labels.label(Some(&Caller::Publisher(b))), b.to_string());
+        assert_eq!(labels.label(Some(&Caller::Publisher(c))), OTHER_PUBLISHERS_LABEL);
+        // Publishers that already have a label keep it
+        assert_eq!(labels.label(Some(&Caller::Publisher(a))), a.to_string());
+        assert_eq!(labels.label(None), ANONYMOUS_LABEL);
+        assert_eq!(labels.label(Some(&Caller::Admin)), ADMIN_LABEL);
+
+        let distinct: HashSet<String> = (0..50)
+            .map(|_| labels.label(Some(&Caller::Publisher(Uuid::new_v4()))))
+            .collect();
+        assert_eq!(distinct.len(), 1);
+    }
+
+    #[test]
+    fn publisher_errors_are_counted_by_class() {
+        let r = fresh_registry();
+        let caller = Caller::Publisher(Uuid::new_v4());
+        let label = publisher_label(Some(&caller));
+        observe_publisher_status(Some(&caller), 404);
+        observe_publisher_status(Some(&caller), 503);
+        observe_publisher_status(Some(&caller), 200);
+
+        let count = |class: &str| PUBLISHER_HTTP_ERRORS.with_label_values(&[&label, class]).get();
+        assert_eq!(count("4xx"), 1);
+        assert_eq!(count("5xx"), 1);
+        assert!(gather_metrics(&r).contains("publisher_http_errors_total"));
+    }
+
+    #[test]
+    fn publishing_increments_the_published_counter() {
+        let r = fresh_registry();
+        let before = CONTRACTS_PUBLISHED.get();
+        record_publisher_publish(&Caller::Publisher(Uuid::new_v4()));
+        assert!(CONTRACTS_PUBLISHED.get() > before);
+
+        record_scan_completed(SCAN_KIND_CACHED);
+        AUDITS_COMPLETED.inc();
+        set_registry_totals(12, 3);
+        let out = gather_metrics(&r);
+        assert!(out.contains("contracts_published_total"));
+        assert!(out.contains("scans_completed_total{kind=\"cached\"}"));
+        assert!(out.contains("audits_completed_total"));
+        assert!(out.contains("t_contracts_total 12") && out.contains("t_publishers_total 3"));
+    }
+
     #[test]
     fn test_observe_http_records_duration() {
         let _r = fresh_registry();
@@ -22,28 +22,16 @@ mod tests {
     use http::Request;
     use tower::ServiceExt;
     use prometheus::Registry;
-    use std::sync::Arc;
-    use std::time::Instant;
-    use crate::cache::{CacheLayer, CacheConfig};
 
     fn test_state() -> AppState {
         let registry = Registry::new_custom(Some("test".into()), None).unwrap();
         metrics::register_all(&registry).unwrap();
         AppState {
-            db: create_test_pool(),
-            started_at: Instant::now(),
-            cache: Arc::new(CacheLayer::new(CacheConfig::default())),
             registry,
+            ..AppState::for_tests()
         }
     }
 
-    fn create_test_pool() -> sqlx::PgPool {
-        sqlx::pool::PoolOptions::new()
-            .max_connections(1)
-            .connect_lazy("postgres://localhost/test")
-            .expect("lazy pool")
-    }
-
     #[tokio::test]
     async fn test_metrics_endpoint_returns_200() {
         let state = test_state();
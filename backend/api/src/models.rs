@@ -1,11 +1,16 @@
 // src/models.rs
 // Shared data types for the Soroban Security Audit system
 
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use uuid::Uuid;
 
+use crate::scanner_service::SeverityCounts;
+
 // ─────────────────────────────────────────────────────────
 // Checklist definition types (static / compile-time)
 // ─────────────────────────────────────────────────────────
@@ -74,16 +79,17 @@ pub enum DetectionMethod {
     SemiAutomatic { patterns: Vec<String> },
 }
 
-/// A static checklist item definition
+/// A checklist item definition. Built-in items borrow their text; custom
+/// rules loaded at runtime own theirs.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChecklistItem {
-    pub id: &'static str,
+    pub id: Cow<'static, str>,
     pub category: CheckCategory,
-    pub title: &'static str,
-    pub description: &'static str,
+    pub title: Cow<'static, str>,
+    pub description: Cow<'static, str>,
     pub severity: Severity,
     pub detection: DetectionMethod,
-    pub remediation: &'static str,
+    pub remediation: Cow<'static, str>,
     pub references: Vec<&'static str>,
 }
 
@@ -160,20 +166,31 @@ pub struct AuditResponse {
     pub checks: Vec<CheckWithStatus>,
     pub category_scores: Vec<CategoryScore>,
     pub auto_detected_count: usize,
+    /// Failed checks per severity; all zero when nothing failed
+    pub severity_summary: SeverityCounts,
+}
+
+/// Failed checks per severity across every audit
+#[derive(Debug, Serialize)]
+pub struct AuditSeveritySummary {
+    pub audits: i64,
+    /// Audits with at least one failed check
+    pub audits_with_findings: i64,
+    pub severity_summary: SeverityCounts,
 }
 
 /// A checklist item merged with its current audit status
 #[derive(Debug, Serialize)]
 pub struct CheckWithStatus {
     // ── static metadata ──
-    pub id: &'static str,
+    pub id: Cow<'static, str>,
     pub category: String,
-    pub title: &'static str,
-    pub description: &'static str,
+    pub title: Cow<'static, str>,
+    pub description: Cow<'static, str>,
     pub severity: String,
     pub detection_type: &'static str,
     pub auto_patterns: Vec<String>,
-    pub remediation: &'static str,
+    pub remediation: Cow<'static, str>,
     pub references: Vec<&'static str>,
     // ── live audit status ──
     pub status: CheckStatus,
@@ -311,6 +328,56 @@ pub struct BenchmarkResponse {
     pub runs: Vec<BenchmarkRun>,
     pub alert: Option<PerformanceAlert>,
     pub comparison: Option<BenchmarkComparison>,
+    /// Version-level regressions against the baseline, or the previous
+    /// benchmarked version when none is pinned
+    pub benchmark_warnings: Vec<BenchmarkWarning>,
+    /// This run against the pinned baseline; absent when none is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub baseline: Option<BaselineComparison>,
+}
+
+/// One row in `benchmark_baselines`
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BenchmarkBaseline {
+    pub contract_id: Uuid,
+    pub version: String,
+    pub set_at: DateTime<Utc>,
+}
+
+/// `POST /api/contracts/:id/benchmarks/baseline` body
+#[derive(Debug, Deserialize)]
+pub struct SetBenchmarkBaselineRequest {
+    pub version: String,
+}
+
+/// `GET /api/contracts/:id/benchmarks/baseline` body
+#[derive(Debug, Serialize)]
+pub struct BenchmarkBaselineResponse {
+    #[serde(flatten)]
+    pub baseline: BenchmarkBaseline,
+    /// The baseline version's metrics, as `<method>.<stat>`
+    pub metrics: BTreeMap<String, f64>,
+}
+
+/// A run's metrics against the baseline version's
+#[derive(Debug, Clone, Serialize)]
+pub struct BaselineComparison {
+    pub version: String,
+    /// Only metrics the baseline also measured
+    pub metrics: Vec<MetricDelta>,
+    pub regressions: usize,
+}
+
+/// A metric that regressed between two versions beyond its threshold
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+pub struct BenchmarkWarning {
+    pub metric: String,
+    pub previous_version: String,
+    pub version: String,
+    pub previous_value: f64,
+    pub value: f64,
+    pub regression_pct: f64,
+    pub threshold_pct: f64,
 }
 
 /// Point in a benchmark trend time-series
@@ -325,6 +392,34 @@ pub struct BenchmarkTrendPoint {
     pub max_ms: f64,
 }
 
+/// One metric measured in both versions of a comparison
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct MetricDelta {
+    /// `<method>.<stat>`, e.g. `transfer.p95_ms`
+    pub metric: String,
+    pub from_value: f64,
+    pub to_value: f64,
+    pub delta: f64,
+    /// `None` when the baseline is zero
+    pub delta_pct: Option<f64>,
+    pub is_regression: bool,
+}
+
+/// `GET /api/contracts/:id/benchmarks/compare` body
+#[derive(Debug, Serialize)]
+pub struct BenchmarkVersionComparison {
+    pub contract_id: Uuid,
+    pub from: String,
+    pub to: String,
+    pub threshold_pct: f64,
+    pub metrics: Vec<MetricDelta>,
+    /// Metrics measured only in `from`
+    pub only_in_from: Vec<String>,
+    /// Metrics measured only in `to`
+    pub only_in_to: Vec<String>,
+    pub regressions: usize,
+}
+
 /// Dashboard summary for a contract's benchmarks
 #[derive(Debug, Serialize)]
 pub struct ContractBenchmarkSummary {
@@ -16,7 +16,7 @@ use shared::{
 use uuid::Uuid;
 
 use crate::{
-    error::{ApiError, ApiResult},
+    error::{ApiError, ApiResult, ErrorCode},
     handlers::db_internal_error,
     state::AppState,
 };
@@ -27,7 +27,7 @@ use crate::{
 
 fn map_json_rejection(err: axum::extract::rejection::JsonRejection) -> ApiError {
     ApiError::bad_request(
-        "InvalidRequest",
+        ErrorCode::InvalidRequest,
         format!("Invalid JSON payload: {}", err.body_text()),
     )
 }
@@ -40,7 +40,7 @@ async fn fetch_proposal(state: &AppState, id: Uuid) -> ApiResult<DeployProposal>
         .await
         .map_err(|err| match err {
             sqlx::Error::RowNotFound => ApiError::not_found(
-                "ProposalNotFound",
+                ErrorCode::ProposalNotFound,
                 format!("No proposal found with ID: {}", id),
             ),
             _ => db_internal_error("fetch proposal", err),
@@ -73,19 +73,19 @@ pub async fn create_policy(
     // Validation
     if req.threshold < 1 {
         return Err(ApiError::bad_request(
-            "InvalidThreshold",
+            ErrorCode::InvalidThreshold,
             "threshold must be at least 1",
         ));
     }
     if req.signer_addresses.is_empty() {
         return Err(ApiError::bad_request(
-            "InvalidSigners",
+            ErrorCode::InvalidSigners,
             "signer_addresses must not be empty",
         ));
     }
     if req.threshold as usize > req.signer_addresses.len() {
         return Err(ApiError::bad_request(
-            "ThresholdExceedsSigners",
+            ErrorCode::ThresholdExceedsSigners,
             format!(
                 "threshold ({}) cannot exceed the number of signers ({})",
                 req.threshold,
@@ -95,7 +95,7 @@ pub async fn create_policy(
     }
     if req.created_by.is_empty() {
         return Err(ApiError::bad_request(
-            "MissingProposer",
+            ErrorCode::MissingProposer,
             "created_by field is required",
         ));
     }
@@ -135,13 +135,13 @@ pub async fn create_proposal(
 
     // Validate required fields
     if req.contract_id.is_empty() {
-        return Err(ApiError::bad_request("MissingContractId", "contract_id is required"));
+        return Err(ApiError::bad_request(ErrorCode::MissingContractId, "contract_id is required"));
     }
     if req.wasm_hash.is_empty() {
-        return Err(ApiError::bad_request("MissingWasmHash", "wasm_hash is required"));
+        return Err(ApiError::bad_request(ErrorCode::MissingWasmHash, "wasm_hash is required"));
     }
     if req.proposer.is_empty() {
-        return Err(ApiError::bad_request("MissingProposer", "proposer is required"));
+        return Err(ApiError::bad_request(ErrorCode::MissingProposer, "proposer is required"));
     }
 
     // Look up the policy to compute expires_at
@@ -152,7 +152,7 @@ pub async fn create_proposal(
             .await
             .map_err(|err| match err {
                 sqlx::Error::RowNotFound => ApiError::not_found(
-                    "PolicyNotFound",
+                    ErrorCode::PolicyNotFound,
                     format!("No policy found with ID: {}", req.policy_id),
                 ),
                 _ => db_internal_error("fetch policy for proposal", err),
@@ -217,7 +217,7 @@ pub async fn sign_proposal(
         }
         return Err(ApiError::new(
             StatusCode::GONE,
-            "ProposalExpired",
+            ErrorCode::ProposalExpired,
             "This proposal has expired and can no longer be signed",
         ));
     }
@@ -225,7 +225,7 @@ pub async fn sign_proposal(
     // Only pending proposals can be signed
     if proposal.status != ProposalStatus::Pending {
         return Err(ApiError::bad_request(
-            "ProposalNotPending",
+            ErrorCode::ProposalNotPending,
             format!(
                 "Proposal is in '{}' status and cannot be signed",
                 proposal.status
@@ -243,7 +243,7 @@ pub async fn sign_proposal(
 
     if !policy.signer_addresses.contains(&req.signer_address) {
         return Err(ApiError::bad_request(
-            "UnauthorizedSigner",
+            ErrorCode::UnauthorizedSigner,
             format!(
                 "'{}' is not an authorized signer for this proposal",
                 req.signer_address
@@ -267,7 +267,7 @@ pub async fn sign_proposal(
             if db_err.constraint() == Some("proposal_signatures_proposal_id_signer_address_key") =>
         {
             ApiError::bad_request(
-                "AlreadySigned",
+                ErrorCode::AlreadySigned,
                 format!("'{}' has already signed this proposal", req.signer_address),
             )
         }
@@ -334,14 +334,14 @@ pub async fn execute_proposal(
         }
         return Err(ApiError::new(
             StatusCode::GONE,
-            "ProposalExpired",
+            ErrorCode::ProposalExpired,
             "This proposal has expired and cannot be executed",
         ));
     }
 
     if proposal.status != ProposalStatus::Approved {
         return Err(ApiError::bad_request(
-            "ProposalNotApproved",
+            ErrorCode::ProposalNotApproved,
             format!(
                 "Proposal must be in 'approved' status to execute. Current status: '{}'",
                 proposal.status
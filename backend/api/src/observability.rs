@@ -1,12 +1,20 @@
-use axum::{http::StatusCode, response::IntoResponse};
+use axum::{extract::State, http::StatusCode, response::IntoResponse, Extension, Json};
+use once_cell::sync::OnceCell;
 use opentelemetry::trace::TracerProvider as _;
 use opentelemetry_otlp::WithExportConfig;
 use opentelemetry_sdk::{runtime, trace as sdktrace};
 use prometheus::Encoder;
 use tracing_opentelemetry::OpenTelemetryLayer;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use serde::{Deserialize, Serialize};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
 
-use crate::metrics::REGISTRY;
+use crate::{
+    admin_audit,
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    metrics::REGISTRY,
+    state::AppState,
+};
 
 pub fn init(otlp_endpoint: &str) {
     let exporter = opentelemetry_otlp::new_exporter()
@@ -27,16 +35,124 @@ pub fn init(otlp_endpoint: &str) {
 
     let tracer = tracer_provider.tracer("soroban-registry");
 
-    let filter = EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| "api=debug,tower_http=debug".into());
-
     tracing_subscriber::registry()
-        .with(filter)
+        .with(reloadable_filter())
         .with(tracing_subscriber::fmt::layer().json())
         .with(OpenTelemetryLayer::new(tracer))
         .init();
 }
 
+// ─────────────────────────────────────────────────────────
+// Log level
+// ─────────────────────────────────────────────────────────
+
+/// One level for every target; `RUST_LOG` takes precedence when both are set
+pub const LOG_LEVEL_ENV: &str = "LOG_LEVEL";
+const DEFAULT_LOG_FILTER: &str = "api=debug,tower_http=debug";
+/// Levels accepted by `LOG_LEVEL` and `POST /api/admin/log-level`
+pub const LOG_LEVELS: &[&str] = &["trace", "debug", "info", "warn", "error", "off"];
+
+type FilterHandle = reload::Handle<EnvFilter, tracing_subscriber::Registry>;
+
+/// Handle to the installed filter, set once by `init`
+static LOG_FILTER: OnceCell<FilterHandle> = OnceCell::new();
+
+pub fn parse_log_level(raw: &str) -> Option<&'static str> {
+    let level = raw.trim().to_ascii_lowercase();
+    LOG_LEVELS.iter().copied().find(|allowed| *allowed == level)
+}
+
+fn initial_filter() -> EnvFilter {
+    if let Ok(filter) = EnvFilter::try_from_default_env() {
+        return filter;
+    }
+    match std::env::var(LOG_LEVEL_ENV)
+        .ok()
+        .as_deref()
+        .and_then(parse_log_level)
+    {
+        Some(level) => EnvFilter::new(level),
+        None => EnvFilter::new(DEFAULT_LOG_FILTER),
+    }
+}
+
+/// The filter layer, wrapped so [`set_log_level`] can swap it at runtime
+fn reloadable_filter() -> reload::Layer<EnvFilter, tracing_subscriber::Registry> {
+    let (layer, handle) = reload::Layer::new(initial_filter());
+    let _ = LOG_FILTER.set(handle);
+    layer
+}
+
+/// Replace the filter behind `handle` with `level`. Returns the previous
+/// filter's directives.
+fn apply_log_level(handle: &FilterHandle, level: &str) -> Result<String, reload::Error> {
+    let previous = handle.with_current(|filter| filter.to_string())?;
+    handle.reload(EnvFilter::new(level))?;
+    Ok(previous)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SetLogLevelRequest {
+    pub level: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LogLevelResponse {
+    /// Filter directives in effect before the change
+    pub previous: String,
+    pub level: String,
+}
+
+/// Change the log level of the running process. Applies to every event and
+/// span from the next one on; a restart goes back to `RUST_LOG`/`LOG_LEVEL`.
+#[utoipa::path(
+    post,
+    path = "/api/admin/log-level",
+    tag = "admin",
+    responses(
+        (status = 200, description = "Previous filter and the level now in effect"),
+        (status = 403, description = "Caller is not an admin"),
+        (status = 422, description = "Not one of trace, debug, info, warn, error, off"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn set_log_level(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Json(req): Json<SetLogLevelRequest>,
+) -> ApiResult<Json<LogLevelResponse>> {
+    caller.require_admin()?;
+    let Some(level) = parse_log_level(&req.level) else {
+        return Err(ApiError::unprocessable(
+            ErrorCode::InvalidLogLevel,
+            format!(
+                "level must be one of {} (got '{}')",
+                LOG_LEVELS.join(", "),
+                req.level
+            ),
+        ));
+    };
+    let handle = LOG_FILTER
+        .get()
+        .ok_or_else(|| ApiError::internal("Log filter was not installed"))?;
+    let previous = apply_log_level(handle, level).map_err(|e| ApiError::internal(e.to_string()))?;
+
+    tracing::warn!(%previous, level, "log level changed at runtime");
+    admin_audit::record_applied(
+        &state.db,
+        &caller,
+        admin_audit::LOG_LEVEL_CHANGED,
+        "log_filter",
+        "process",
+        Some(serde_json::json!({ "previous": previous, "level": level })),
+    )
+    .await;
+    Ok(Json(LogLevelResponse {
+        previous,
+        level: level.to_string(),
+    }))
+}
+
 pub async fn metrics_handler() -> impl IntoResponse {
     let encoder = prometheus::TextEncoder::new();
     let mut buf = Vec::new();
@@ -56,6 +172,72 @@ pub async fn metrics_handler() -> impl IntoResponse {
         buf,
     )
         .into_response()
+// ─────────────────────────────────────────────────────────
+// Request ids
+// ─────────────────────────────────────────────────────────
+
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Longest client-supplied id we echo back; anything longer is replaced
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// The id of the request being handled, stored in request extensions
+#[derive(Debug, Clone)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// Request id of the current task, if it runs inside [`request_id_middleware`].
+/// Lets `ApiError` include the id without threading it through every handler.
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Reuse a caller's id when it is short printable ASCII, so it can't be used
+/// to inject into headers or logs
+fn accept_request_id(raw: Option<&str>) -> Option<String> {
+    let id = raw?.trim();
+    let valid = !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.bytes().all(|b| b.is_ascii_graphic());
+    valid.then(|| id.to_string())
+}
+
+/// Tag every request with an id: reuse `X-Request-Id` or generate a UUID,
+/// record it on the request span (exported to OTLP as a span attribute) and
+/// echo it on the response.
+pub async fn request_id_middleware(
+    mut req: axum::http::Request<axum::body::Body>,
+    next: axum::middleware::Next,
+) -> axum::response::Response {
+    use tracing::Instrument;
+
+    let id = accept_request_id(
+        req.headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|v| v.to_str().ok()),
+    )
+    .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
+    req.extensions_mut().insert(RequestId(id.clone()));
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %id,
+        method = %req.method(),
+        uri = %req.uri().path(),
+    );
+    let mut response = CURRENT_REQUEST_ID
+        .scope(id.clone(), next.run(req).instrument(span))
+        .await;
+
+    if let Ok(value) = axum::http::HeaderValue::from_str(&id) {
+        response.headers_mut().insert(REQUEST_ID_HEADER, value);
+    }
+    response
+}
+
 use anyhow::Result;
 use opentelemetry::trace::TracerProvider;
 use opentelemetry::KeyValue;
@@ -93,11 +275,8 @@ impl Observability {
         let tracer = tracer_provider.tracer("soroban-registry");
         let otel_layer = tracing_opentelemetry::layer().with_tracer(tracer);
 
-        let env_filter = tracing_subscriber::EnvFilter::try_from_default_env()
-            .unwrap_or_else(|_| "api=debug,tower_http=debug".into());
-
         tracing_subscriber::registry()
-            .with(env_filter)
+            .with(reloadable_filter())
             .with(tracing_subscriber::fmt::layer())
             .with(otel_layer)
             .init();
@@ -115,6 +294,36 @@ impl Observability {
 mod tests {
     use super::*;
 
+    #[test]
+    fn reuses_only_well_formed_request_ids() {
+        assert_eq!(accept_request_id(Some(" abc-123 ")), Some("abc-123".to_string()));
+        assert_eq!(accept_request_id(Some("")), None);
+        assert_eq!(accept_request_id(Some("has space")), None);
+        assert_eq!(accept_request_id(Some(&"x".repeat(MAX_REQUEST_ID_LEN + 1))), None);
+        assert_eq!(accept_request_id(None), None);
+    }
+
+    #[test]
+    fn log_levels_are_validated() {
+        assert_eq!(parse_log_level(" WARN "), Some("warn"));
+        assert_eq!(parse_log_level("off"), Some("off"));
+        assert_eq!(parse_log_level("verbose"), None);
+        assert_eq!(parse_log_level("api=debug"), None);
+    }
+
+    #[test]
+    fn reloading_the_filter_takes_effect_immediately() {
+        let (layer, handle) = reload::Layer::new(EnvFilter::new("error"));
+        let subscriber = tracing_subscriber::registry().with(layer);
+        tracing::subscriber::with_default(subscriber, || {
+            assert!(!tracing::enabled!(tracing::Level::INFO));
+            let previous = apply_log_level(&handle, "info").unwrap();
+            assert_eq!(previous, "error");
+            assert!(tracing::enabled!(tracing::Level::INFO));
+            assert!(!tracing::enabled!(tracing::Level::DEBUG));
+        });
+    }
+
     #[test]
     fn test_registry_creation() {
         let registry = Registry::new_custom(Some("test".into()), None).unwrap();
@@ -0,0 +1,348 @@
+//! OpenAPI document for the registry API.
+//!
+//! Operations are described by `#[utoipa::path]` attributes next to each
+//! handler; this module only collects them. When adding a route to one of the
+//! documented routers, annotate the handler and list it in [`ApiDoc`] — the
+//! test below fails for any registered path missing from the document.
+
+use axum::Router;
+use utoipa::{
+    openapi::security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+    Modify, OpenApi,
+};
+use utoipa_swagger_ui::SwaggerUi;
+
+use crate::{
+    abi, abi_compat, admin_audit, artifacts, audit_handlers, auth, badge, benchmark_handlers,
+    build_verification, config_handlers, contract_history_handlers, deployment_handlers,
+    export_handlers, feed, handlers, import_handlers, observability, organization_handlers,
+    pagination, scan_handlers, share_tokens, template_handlers, transfer_handlers,
+};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "Soroban Registry API"),
+    paths(
+        handlers::list_contracts,
+        handlers::get_contract_graph,
+        feed::atom_feed,
+        handlers::get_contract,
+        handlers::get_contract_abi,
+        handlers::get_contract_readme,
+        handlers::get_contract_changelog,
+        handlers::suggest_contracts,
+        badge::version_badge_svg,
+        badge::score_badge_svg,
+        badge::badge_json,
+        handlers::get_version_sbom,
+        handlers::get_trending_contracts,
+        handlers::compare_contracts,
+        handlers::get_score_history,
+        handlers::get_contract_versions,
+        handlers::resolve_contract_version,
+        handlers::deprecate_contract_version,
+        build_verification::verify_build,
+        artifacts::download_version_wasm,
+        abi::get_version_abi,
+        abi::search_by_function,
+        abi_compat::get_abi_compat,
+        abi_compat::get_abi_diff,
+        handlers::list_tags,
+        handlers::star_contract,
+        handlers::unstar_contract,
+        config_handlers::get_scoring_weights,
+        config_handlers::put_scoring_weights,
+        config_handlers::recompute_scores,
+        handlers::get_contract_analytics,
+        handlers::get_analytics_timeseries,
+        handlers::trigger_aggregation,
+        observability::set_log_level,
+        admin_audit::list_audit_log,
+        handlers::yank_contract_version,
+        handlers::unyank_contract_version,
+        handlers::set_contract_visibility,
+        share_tokens::create_share_token,
+        share_tokens::list_share_tokens,
+        share_tokens::revoke_share_token,
+        config_handlers::list_metadata_schemas,
+        config_handlers::get_metadata_schema,
+        config_handlers::put_metadata_schema,
+        config_handlers::delete_metadata_schema,
+        config_handlers::list_detector_rules,
+        config_handlers::create_detector_rule,
+        config_handlers::delete_detector_rule,
+        config_handlers::list_dependency_advisories,
+        config_handlers::create_dependency_advisory,
+        config_handlers::delete_dependency_advisory,
+        handlers::get_trust_score,
+        handlers::get_contract_dependencies,
+        handlers::get_contract_dependents,
+        handlers::verify_contract,
+        handlers::get_deployment_status,
+        handlers::deploy_green,
+        handlers::switch_deployment,
+        handlers::rollback_deployment,
+        handlers::report_health_check,
+        handlers::get_contract_state,
+        handlers::update_contract_state,
+        handlers::get_contract_performance,
+        handlers::publish_contract,
+        handlers::get_publisher,
+        handlers::get_publisher_contracts,
+        handlers::create_publisher,
+        handlers::update_publisher,
+        handlers::deactivate_publisher,
+        handlers::reactivate_publisher,
+        organization_handlers::create_organization,
+        organization_handlers::get_organization,
+        organization_handlers::get_organization_contracts,
+        organization_handlers::set_organization_member,
+        organization_handlers::remove_organization_member,
+        transfer_handlers::initiate_transfer,
+        transfer_handlers::accept_transfer,
+        transfer_handlers::list_transfers,
+        contract_history_handlers::revert_to_history_entry,
+        deployment_handlers::record_deployment,
+        deployment_handlers::list_deployments,
+        export_handlers::export_catalog,
+        import_handlers::import_catalog,
+        auth::create_api_key,
+        handlers::migrations::create_migration,
+        handlers::migrations::get_migrations,
+        handlers::migrations::update_migration,
+        handlers::migrations::get_migration,
+        audit_handlers::get_checklist_definition,
+        audit_handlers::get_security_score,
+        audit_handlers::list_security_audits,
+        audit_handlers::get_security_audit,
+        audit_handlers::create_security_audit,
+        audit_handlers::get_security_audit_by_id,
+        audit_handlers::update_check,
+        audit_handlers::run_autocheck,
+        audit_handlers::export_audit_markdown,
+        audit_handlers::export_audit_pdf,
+        audit_handlers::get_audit_summary,
+        benchmark_handlers::run_benchmark,
+        benchmark_handlers::list_benchmarks,
+        benchmark_handlers::get_benchmark_summary,
+        benchmark_handlers::get_benchmark_trend,
+        benchmark_handlers::compare_benchmarks,
+        benchmark_handlers::get_benchmark_baseline,
+        benchmark_handlers::set_benchmark_baseline,
+        benchmark_handlers::clear_benchmark_baseline,
+        benchmark_handlers::get_benchmark,
+        benchmark_handlers::get_cli_output,
+        benchmark_handlers::resolve_alert,
+        template_handlers::list_templates,
+        template_handlers::get_template,
+        template_handlers::clone_template,
+        template_handlers::instantiate_template,
+        template_handlers::list_template_versions,
+        template_handlers::create_template_version,
+        template_handlers::fork_template,
+        template_handlers::list_template_forks,
+        template_handlers::get_template_stats,
+        scan_handlers::ingest_cves,
+        scan_handlers::scan_contract,
+        scan_handlers::get_scan_report,
+        scan_handlers::scan_diff,
+        scan_handlers::submit_scan,
+        scan_handlers::submit_scan_batch,
+        scan_handlers::get_scan_batch,
+        scan_handlers::get_scan_job,
+        scan_handlers::get_scan_sarif,
+        scan_handlers::list_suppressions,
+        scan_handlers::create_suppression,
+        scan_handlers::delete_suppression,
+    ),
+    components(schemas(
+        shared::Contract,
+        shared::Network,
+        shared::ContractVersion,
+        shared::BuildInfo,
+        shared::Publisher,
+        shared::UpdatePublisherRequest,
+        shared::ContractVisibility,
+        shared::SetVisibilityRequest,
+        shared::Organization,
+        shared::OrganizationMember,
+        shared::OrganizationRole,
+        shared::CreateOrganizationRequest,
+        shared::SetOrganizationMemberRequest,
+        organization_handlers::OrganizationDetail,
+        shared::TransferStatus,
+        shared::ContractTransfer,
+        shared::CreateTransferRequest,
+        shared::DeploymentAddress,
+        shared::RecordDeploymentRequest,
+        import_handlers::OnConflict,
+        import_handlers::ImportCounts,
+        import_handlers::ImportConflict,
+        import_handlers::ImportContractConflict,
+        import_handlers::ImportLineError,
+        import_handlers::ImportSummary,
+        shared::PublishRequest,
+        shared::DependencyDeclaration,
+        shared::VerifyRequest,
+        shared::VerificationResult,
+        shared::DeprecateVersionRequest,
+        shared::VerifyBuildRequest,
+        shared::BuildVerification,
+        shared::BuildVerificationStatus,
+        abi::ContractAbi,
+        abi::AbiFunction,
+        abi::AbiParam,
+        abi::AbiType,
+        abi::AbiTypeKind,
+        abi::AbiCase,
+        abi::FunctionMatch,
+        pagination::PaginatedFunctionMatches,
+        pagination::PaginatedShareTokens,
+        pagination::PaginatedContractTransfers,
+        pagination::PaginatedDeploymentAddresses,
+        abi_compat::AbiCompatReport,
+        abi_compat::AbiChange,
+        abi_compat::AbiChangeKind,
+        abi_compat::SemverBump,
+        abi_compat::AbiDiff,
+        abi_compat::FunctionDiff,
+        abi_compat::ParamChange,
+        abi_compat::ReturnChange,
+        handlers::ContractListItem,
+        handlers::ResolvedVersion,
+        handlers::ChangelogEntry,
+        handlers::TagCount,
+        handlers::ContractSuggestion,
+        handlers::ContractDetail,
+        handlers::PublishResponse,
+        handlers::StarStatus,
+        auth::CreateApiKeyRequest,
+        auth::CreatedApiKey,
+        share_tokens::CreateShareTokenRequest,
+        share_tokens::ShareToken,
+        share_tokens::CreatedShareToken,
+        admin_audit::AdminAuditEntry,
+        pagination::PaginatedContracts,
+        pagination::PaginatedContractListItems,
+        pagination::PaginatedContractVersions,
+        pagination::PaginatedAdminAuditEntries,
+    )),
+    modifiers(&ApiKeyAuth),
+)]
+pub struct ApiDoc;
+
+/// Registers the bearer API-key scheme referenced by `security(("api_key" = []))`
+struct ApiKeyAuth;
+
+impl Modify for ApiKeyAuth {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "api_key",
+            SecurityScheme::Http(HttpBuilder::new().scheme(HttpAuthScheme::Bearer).build()),
+        );
+    }
+}
+
+/// `GET /api/openapi.json` and the Swagger UI at `/docs`
+pub fn openapi_routes<S>() -> Router<S>
+where
+    S: Clone + Send + Sync + 'static,
+{
+    Router::from(SwaggerUi::new("/docs").url("/api/openapi.json", ApiDoc::openapi()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::Service;
+
+    /// Route sources covered by the document, as (file contents, router fns).
+    /// An empty fn list means every route in the file.
+    const DOCUMENTED: &[(&str, &[&str])] = &[
+        (
+            include_str!("routes.rs"),
+            &[
+                "contract_routes",
+                "publisher_routes",
+                "authenticated_routes",
+                "migration_routes",
+            ],
+        ),
+        (include_str!("audit_routes.rs"), &[]),
+        (include_str!("benchmark_routes.rs"), &[]),
+        (include_str!("template_routes.rs"), &[]),
+        (include_str!("scan_routes.rs"), &[]),
+    ];
+
+    fn router_body<'a>(source: &'a str, router_fn: &str) -> &'a str {
+        let start = source
+            .find(&format!("pub fn {}(", router_fn))
+            .unwrap_or_else(|| panic!("router fn {} not found", router_fn));
+        let rest = &source[start + 1..];
+        let end = rest.find("\npub fn ").map(|i| i + 1).unwrap_or(rest.len());
+        &source[start..start + end]
+    }
+
+    /// Every `.route("...")` path in `source`, converted to OpenAPI syntax
+    fn route_paths(source: &str) -> Vec<String> {
+        let mut paths = Vec::new();
+        let mut rest = source;
+        while let Some(i) = rest.find(".route(") {
+            rest = &rest[i + ".route(".len()..];
+            let Some(open) = rest.find('"') else { break };
+            let Some(len) = rest[open + 1..].find('"') else { break };
+            let raw = &rest[open + 1..open + 1 + len];
+            let path = raw
+                .split('/')
+                .map(|seg| match seg.strip_prefix(':') {
+                    Some(name) => format!("{{{}}}", name),
+                    None => seg.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("/");
+            paths.push(path);
+        }
+        paths
+    }
+
+    #[tokio::test]
+    async fn served_document_is_valid_and_complete() {
+        let mut app = openapi_routes::<()>();
+        let response = app
+            .call(Request::get("/api/openapi.json").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert!(response.status().is_success());
+
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let doc: utoipa::openapi::OpenApi =
+            serde_json::from_slice(&bytes).expect("served JSON is not an OpenAPI document");
+        let raw: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert!(raw["openapi"].as_str().unwrap().starts_with("3."));
+
+        let mut missing = Vec::new();
+        for (source, fns) in DOCUMENTED {
+            let sections: Vec<&str> = if fns.is_empty() {
+                vec![*source]
+            } else {
+                fns.iter().map(|f| router_body(source, f)).collect()
+            };
+            for path in sections.into_iter().flat_map(route_paths) {
+                if !doc.paths.paths.contains_key(&path) {
+                    missing.push(path);
+                }
+            }
+        }
+        assert!(missing.is_empty(), "routes missing from OpenAPI: {:?}", missing);
+    }
+
+    #[test]
+    fn route_paths_are_converted_to_openapi_syntax() {
+        let paths = route_paths(r#".route("/api/contracts/:id/versions/:version", get(x))"#);
+        assert_eq!(paths, vec!["/api/contracts/{id}/versions/{version}"]);
+    }
+}
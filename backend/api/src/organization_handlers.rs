@@ -0,0 +1,515 @@
+//! Publisher organizations (`organizations`, `organization_members`).
+//!
+//! An organization is owned by one or more `owner` members, who manage the
+//! membership. Owners and maintainers publish contracts on its behalf;
+//! viewers only read. Roles on an organization's contracts come from here
+//! (see `auth::ContractAccess`).
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use serde::Serialize;
+use shared::{
+    Contract, CreateOrganizationRequest, Organization, OrganizationMember, OrganizationRole,
+    SetOrganizationMemberRequest,
+};
+use sqlx::{PgPool, Postgres, QueryBuilder};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    auth::{self, Caller},
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::{db_internal_error, push_visibility_filter},
+    pagination::{PageParams, Paginated, PaginatedContracts},
+    state::AppState,
+};
+
+const MAX_ORGANIZATION_NAME_LENGTH: usize = 100;
+
+/// An organization with its members
+#[derive(Debug, Serialize, ToSchema)]
+pub struct OrganizationDetail {
+    #[serde(flatten)]
+    pub organization: Organization,
+    pub members: Vec<OrganizationMember>,
+}
+
+fn organization_not_found(id: Uuid) -> ApiError {
+    ApiError::not_found(
+        ErrorCode::OrganizationNotFound,
+        format!("No organization found with ID: {}", id),
+    )
+}
+
+fn not_a_member(id: Uuid) -> ApiError {
+    ApiError::new(
+        StatusCode::FORBIDDEN,
+        ErrorCode::Forbidden,
+        format!("Publisher is not a member of organization {}", id),
+    )
+}
+
+async fn fetch_organization(db: &PgPool, id: Uuid) -> ApiResult<Organization> {
+    sqlx::query_as("SELECT * FROM organizations WHERE id = $1")
+        .bind(id)
+        .fetch_optional(db)
+        .await
+        .map_err(|err| db_internal_error("get organization", err))?
+        .ok_or_else(|| organization_not_found(id))
+}
+
+async fn fetch_members(db: &PgPool, id: Uuid) -> ApiResult<Vec<OrganizationMember>> {
+    sqlx::query_as(
+        "SELECT * FROM organization_members WHERE organization_id = $1
+         ORDER BY created_at, publisher_id",
+    )
+    .bind(id)
+    .fetch_all(db)
+    .await
+    .map_err(|err| db_internal_error("list organization members", err))
+}
+
+/// Role of `publisher_id` in `organization_id`, if they are a member
+pub async fn member_role(
+    db: &PgPool,
+    organization_id: Uuid,
+    publisher_id: Uuid,
+) -> Result<Option<OrganizationRole>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT role FROM organization_members WHERE organization_id = $1 AND publisher_id = $2",
+    )
+    .bind(organization_id)
+    .bind(publisher_id)
+    .fetch_optional(db)
+    .await
+}
+
+/// 404 for an unknown organization, 403 unless `publisher_id` is a member
+pub async fn authorize_member(
+    db: &PgPool,
+    organization_id: Uuid,
+    publisher_id: Uuid,
+) -> ApiResult<OrganizationRole> {
+    fetch_organization(db, organization_id).await?;
+    member_role(db, organization_id, publisher_id)
+        .await
+        .map_err(|err| db_internal_error("get organization role", err))?
+        .ok_or_else(|| not_a_member(organization_id))
+}
+
+/// Membership changes are for organization owners (and admins)
+async fn authorize_owner(db: &PgPool, caller: &Caller, organization_id: Uuid) -> ApiResult<()> {
+    let Some(publisher_id) = caller.publisher_id() else {
+        fetch_organization(db, organization_id).await?;
+        return Ok(());
+    };
+    let role = authorize_member(db, organization_id, publisher_id).await?;
+    auth::require_role(
+        Some(role),
+        OrganizationRole::Owner,
+        &format!("organization {}", organization_id),
+    )
+}
+
+/// Whether giving `target` the role `new_role` (`None`: removing them)
+/// would take away the organization's last owner
+pub fn removes_last_owner(
+    members: &[(Uuid, OrganizationRole)],
+    target: Uuid,
+    new_role: Option<OrganizationRole>,
+) -> bool {
+    let target_is_owner = members
+        .iter()
+        .any(|(id, role)| *id == target && *role == OrganizationRole::Owner);
+    let owners_after = members
+        .iter()
+        .filter(|(id, role)| {
+            let role = if *id == target { new_role } else { Some(*role) };
+            role == Some(OrganizationRole::Owner)
+        })
+        .count();
+    target_is_owner && owners_after == 0
+}
+
+fn last_owner_conflict(organization_id: Uuid) -> ApiError {
+    ApiError::new(
+        StatusCode::CONFLICT,
+        ErrorCode::LastOrganizationOwner,
+        format!(
+            "Organization {} must keep at least one owner; add another owner first",
+            organization_id
+        ),
+    )
+}
+
+/// Lock the membership rows so concurrent changes can't both remove an owner
+async fn lock_members(
+    tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+    organization_id: Uuid,
+) -> ApiResult<Vec<(Uuid, OrganizationRole)>> {
+    sqlx::query_as(
+        "SELECT publisher_id, role FROM organization_members
+         WHERE organization_id = $1 FOR UPDATE",
+    )
+    .bind(organization_id)
+    .fetch_all(&mut **tx)
+    .await
+    .map_err(|err| db_internal_error("lock organization members", err))
+}
+
+/// Create an organization. A publisher key becomes its first owner; an
+/// admin key must name the owner in `owner_id`.
+#[utoipa::path(
+    post,
+    path = "/api/organizations",
+    tag = "organizations",
+    request_body = CreateOrganizationRequest,
+    responses(
+        (status = 201, description = "The new organization", body = OrganizationDetail),
+        (status = 400, description = "Invalid name, or an admin key without owner_id"),
+        (status = 404, description = "owner_id is not a known publisher"),
+        (status = 409, description = "An organization with this name already exists"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn create_organization(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Json(req): Json<CreateOrganizationRequest>,
+) -> ApiResult<(StatusCode, Json<OrganizationDetail>)> {
+    let name = req.name.trim();
+    if name.is_empty() || name.chars().count() > MAX_ORGANIZATION_NAME_LENGTH {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidOrganizationName,
+            format!("name must be 1-{} characters", MAX_ORGANIZATION_NAME_LENGTH),
+        ));
+    }
+    let owner_id = match (caller.publisher_id(), req.owner_id) {
+        (Some(publisher_id), _) => publisher_id,
+        (None, Some(owner_id)) => owner_id,
+        (None, None) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::OrganizationOwnerRequired,
+                "owner_id is required when creating an organization with an admin key",
+            ))
+        }
+    };
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin create organization", err))?;
+
+    let organization: Organization =
+        sqlx::query_as("INSERT INTO organizations (name) VALUES ($1) RETURNING *")
+            .bind(name)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|err| match err {
+                sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::new(
+                    StatusCode::CONFLICT,
+                    ErrorCode::DuplicateOrganization,
+                    format!("An organization named '{}' already exists", name),
+                ),
+                err => db_internal_error("create organization", err),
+            })?;
+
+    let owner: OrganizationMember = sqlx::query_as(
+        "INSERT INTO organization_members (organization_id, publisher_id, role)
+         VALUES ($1, $2, $3)
+         RETURNING *",
+    )
+    .bind(organization.id)
+    .bind(owner_id)
+    .bind(OrganizationRole::Owner)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => ApiError::not_found(
+            ErrorCode::PublisherNotFound,
+            format!("No publisher found with ID: {}", owner_id),
+        ),
+        err => db_internal_error("add organization owner", err),
+    })?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit create organization", err))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(OrganizationDetail {
+            organization,
+            members: vec![owner],
+        }),
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{id}",
+    tag = "organizations",
+    params(
+        ("id" = Uuid, Path, description = "Organization UUID"),
+    ),
+    responses(
+        (status = 200, description = "The organization and its members", body = OrganizationDetail),
+        (status = 404, description = "Organization not found"),
+    ),
+)]
+pub async fn get_organization(
+    State(state): State<AppState>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<OrganizationDetail>> {
+    let organization = fetch_organization(&state.db, id).await?;
+    let members = fetch_members(&state.db, id).await?;
+    Ok(Json(OrganizationDetail {
+        organization,
+        members,
+    }))
+}
+
+/// The organization's contracts; private ones only for its members
+#[utoipa::path(
+    get,
+    path = "/api/organizations/{id}/contracts",
+    tag = "organizations",
+    params(
+        ("id" = Uuid, Path, description = "Organization UUID"),
+        PageParams,
+    ),
+    responses(
+        (status = 200, description = "Contracts owned by the organization that the caller may see", body = PaginatedContracts),
+        (status = 404, description = "Organization not found"),
+    ),
+)]
+pub async fn get_organization_contracts(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(id): Path<Uuid>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<Contract>>> {
+    fetch_organization(&state.db, id).await?;
+    let caller = caller.map(|Extension(caller)| caller);
+    let visible = |select: &str| {
+        let mut builder = QueryBuilder::<Postgres>::new(select);
+        builder.push(" FROM contracts WHERE organization_id = ");
+        builder.push_bind(id);
+        push_visibility_filter(&mut builder, caller.as_ref());
+        builder
+    };
+
+    let mut builder = visible("SELECT *");
+    builder.push(" ORDER BY created_at DESC, id DESC LIMIT ");
+    builder.push_bind(page.limit());
+    builder.push(" OFFSET ");
+    builder.push_bind(page.offset());
+    let contracts: Vec<Contract> = builder
+        .build_query_as()
+        .fetch_all(&state.db)
+        .await
+        .map_err(|err| db_internal_error("list organization contracts", err))?;
+    let total: i64 = visible("SELECT COUNT(*)")
+        .build_query_scalar()
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("count organization contracts", err))?;
+
+    Ok(Json(Paginated::new(
+        contracts,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+/// Add a publisher to the organization, or change their role
+#[utoipa::path(
+    post,
+    path = "/api/organizations/{id}/members",
+    tag = "organizations",
+    params(
+        ("id" = Uuid, Path, description = "Organization UUID"),
+    ),
+    request_body = SetOrganizationMemberRequest,
+    responses(
+        (status = 200, description = "The membership", body = OrganizationMember),
+        (status = 403, description = "Caller is not an owner of the organization"),
+        (status = 404, description = "Organization or publisher not found"),
+        (status = 409, description = "The change would leave the organization without an owner"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn set_organization_member(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<SetOrganizationMemberRequest>,
+) -> ApiResult<Json<OrganizationMember>> {
+    authorize_owner(&state.db, &caller, id).await?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin set organization member", err))?;
+    let members = lock_members(&mut tx, id).await?;
+    if removes_last_owner(&members, req.publisher_id, Some(req.role)) {
+        return Err(last_owner_conflict(id));
+    }
+
+    let member: OrganizationMember = sqlx::query_as(
+        "INSERT INTO organization_members (organization_id, publisher_id, role)
+         VALUES ($1, $2, $3)
+         ON CONFLICT (organization_id, publisher_id) DO UPDATE SET role = EXCLUDED.role
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(req.publisher_id)
+    .bind(req.role)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => ApiError::not_found(
+            ErrorCode::PublisherNotFound,
+            format!("No publisher found with ID: {}", req.publisher_id),
+        ),
+        err => db_internal_error("set organization member", err),
+    })?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit set organization member", err))?;
+    Ok(Json(member))
+}
+
+/// Remove a member. Owners may remove anyone; members may remove themselves.
+#[utoipa::path(
+    delete,
+    path = "/api/organizations/{id}/members/{publisher_id}",
+    tag = "organizations",
+    params(
+        ("id" = Uuid, Path, description = "Organization UUID"),
+        ("publisher_id" = Uuid, Path, description = "Member to remove"),
+    ),
+    responses(
+        (status = 204, description = "Member removed"),
+        (status = 403, description = "Caller is neither an owner nor the member being removed"),
+        (status = 404, description = "Organization or membership not found"),
+        (status = 409, description = "The member is the organization's last owner"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn remove_organization_member(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path((id, publisher_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    if caller.publisher_id() == Some(publisher_id) {
+        authorize_member(&state.db, id, publisher_id).await?;
+    } else {
+        authorize_owner(&state.db, &caller, id).await?;
+    }
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin remove organization member", err))?;
+    let members = lock_members(&mut tx, id).await?;
+    if !members.iter().any(|(member, _)| *member == publisher_id) {
+        return Err(ApiError::not_found(
+            ErrorCode::OrganizationMemberNotFound,
+            format!("Publisher {} is not a member of organization {}", publisher_id, id),
+        ));
+    }
+    if removes_last_owner(&members, publisher_id, None) {
+        return Err(last_owner_conflict(id));
+    }
+
+    sqlx::query("DELETE FROM organization_members WHERE organization_id = $1 AND publisher_id = $2")
+        .bind(id)
+        .bind(publisher_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| db_internal_error("remove organization member", err))?;
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit remove organization member", err))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn removing_or_demoting_the_last_owner_is_blocked() {
+        let (owner, member) = (Uuid::new_v4(), Uuid::new_v4());
+        let members = vec![
+            (owner, OrganizationRole::Owner),
+            (member, OrganizationRole::Maintainer),
+        ];
+
+        assert!(removes_last_owner(&members, owner, None));
+        assert!(removes_last_owner(&members, owner, Some(OrganizationRole::Viewer)));
+        assert!(!removes_last_owner(&members, owner, Some(OrganizationRole::Owner)));
+        assert!(!removes_last_owner(&members, member, None));
+    }
+
+    #[test]
+    fn an_owner_can_leave_once_another_owner_exists() {
+        let (first, second) = (Uuid::new_v4(), Uuid::new_v4());
+        let members = vec![
+            (first, OrganizationRole::Owner),
+            (second, OrganizationRole::Owner),
+        ];
+        assert!(!removes_last_owner(&members, first, None));
+        // Adding a new member never removes an owner
+        assert!(!removes_last_owner(&members, Uuid::new_v4(), Some(OrganizationRole::Viewer)));
+    }
+
+    #[test]
+    fn roles_serialize_lowercase() {
+        assert_eq!(serde_json::to_value(OrganizationRole::Owner).unwrap(), "owner");
+        let req: SetOrganizationMemberRequest = serde_json::from_value(serde_json::json!({
+            "publisher_id": Uuid::nil(),
+            "role": "maintainer",
+        }))
+        .unwrap();
+        assert_eq!(req.role, OrganizationRole::Maintainer);
+    }
+
+    #[tokio::test]
+    async fn private_organization_contracts_are_listed_for_members_only() {
+        use crate::state::{json_body, test_request};
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let member = state.insert_publisher().await;
+        let org = state.insert_organization(&[(member, "viewer")]).await;
+        let public = state.insert_contract(member, Some(org), "public").await;
+        let private = state.insert_contract(member, Some(org), "private").await;
+        let list = |key: Option<&str>| {
+            let uri = format!("/api/organizations/{}/contracts", org);
+            state.send(test_request("GET", &uri, key, None))
+        };
+        let listed = |body: serde_json::Value| -> Vec<String> {
+            let contracts = body["items"].as_array().unwrap().iter();
+            let ids = contracts.map(|c| c["id"].as_str().unwrap().to_string());
+            ids.collect()
+        };
+
+        let response = list(None).await;
+        assert_eq!(listed(json_body(response).await), vec![public.to_string()]);
+
+        let key = state.api_key(member).await;
+        let ids = listed(json_body(list(Some(&key)).await).await);
+        assert!(ids.contains(&private.to_string()) && ids.contains(&public.to_string()));
+    }
+}
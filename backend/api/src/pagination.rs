@@ -0,0 +1,127 @@
+//! The response envelope shared by every list endpoint.
+//!
+//! Lists answer `{ items, total, limit, offset, has_more }` whatever they
+//! list, so clients page through all of them the same way. Endpoints that
+//! take no other query parameters accept [`PageParams`]; the rest clamp their
+//! own `limit`/`offset` the same way and build a [`Paginated`] from them.
+
+use serde::{Deserialize, Serialize};
+use shared::{Contract, ContractTransfer, ContractVersion, DeploymentAddress};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    abi::FunctionMatch, admin_audit::AdminAuditEntry, handlers::ContractListItem,
+    share_tokens::ShareToken,
+};
+
+/// Page size when `limit` is absent
+pub const DEFAULT_PAGE_LIMIT: i64 = 20;
+/// Hard upper bound for `limit`
+pub const MAX_PAGE_LIMIT: i64 = 100;
+
+/// A page of results
+#[derive(Debug, Serialize, ToSchema)]
+#[aliases(
+    PaginatedContracts = Paginated<Contract>,
+    PaginatedContractListItems = Paginated<ContractListItem>,
+    PaginatedContractVersions = Paginated<ContractVersion>,
+    PaginatedAdminAuditEntries = Paginated<AdminAuditEntry>,
+    PaginatedFunctionMatches = Paginated<FunctionMatch>,
+    PaginatedShareTokens = Paginated<ShareToken>,
+    PaginatedContractTransfers = Paginated<ContractTransfer>,
+    PaginatedDeploymentAddresses = Paginated<DeploymentAddress>,
+)]
+pub struct Paginated<T> {
+    pub items: Vec<T>,
+    /// Rows matching the request's filters (ignores limit/offset)
+    pub total: i64,
+    pub limit: i64,
+    pub offset: i64,
+    /// Whether another page follows this one
+    pub has_more: bool,
+    /// Cursor for the following page, on endpoints paginated by `?cursor=`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_cursor: Option<String>,
+}
+
+impl<T> Paginated<T> {
+    pub fn new(items: Vec<T>, total: i64, limit: i64, offset: i64) -> Self {
+        let has_more = offset + (items.len() as i64) < total;
+        Self {
+            items,
+            total,
+            limit,
+            offset,
+            has_more,
+            next_cursor: None,
+        }
+    }
+
+    /// A keyset page: more follow exactly when there is a cursor to them
+    pub fn with_next_cursor(mut self, next_cursor: Option<String>) -> Self {
+        self.has_more = next_cursor.is_some();
+        self.next_cursor = next_cursor;
+        self
+    }
+}
+
+/// `?limit=&offset=`, clamped into range rather than rejected
+#[derive(Debug, Default, Clone, Copy, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct PageParams {
+    /// Page size, 1–100 (default 20)
+    pub limit: Option<i64>,
+    /// Rows to skip (default 0)
+    pub offset: Option<i64>,
+}
+
+impl PageParams {
+    pub fn limit(&self) -> i64 {
+        self.limit
+            .unwrap_or(DEFAULT_PAGE_LIMIT)
+            .clamp(1, MAX_PAGE_LIMIT)
+    }
+
+    pub fn offset(&self) -> i64 {
+        self.offset.unwrap_or(0).max(0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn has_more_until_the_last_row_is_served() {
+        assert!(Paginated::new(vec![1, 2], 5, 2, 0).has_more);
+        assert!(Paginated::new(vec![3, 4], 5, 2, 2).has_more);
+        assert!(!Paginated::new(vec![5], 5, 2, 4).has_more);
+        assert!(!Paginated::<i32>::new(vec![], 5, 2, 10).has_more);
+    }
+
+    #[test]
+    fn cursor_pages_follow_the_cursor() {
+        let page = Paginated::new(vec![1, 2], 2, 2, 0).with_next_cursor(Some("c".into()));
+        assert!(page.has_more);
+        let json = serde_json::to_value(Paginated::new(vec![1], 1, 20, 0)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "items": [1],
+                "total": 1,
+                "limit": 20,
+                "offset": 0,
+                "has_more": false,
+            })
+        );
+    }
+
+    #[test]
+    fn page_params_are_clamped() {
+        let params = |limit, offset| PageParams { limit, offset };
+        assert_eq!(params(None, None).limit(), DEFAULT_PAGE_LIMIT);
+        assert_eq!(params(Some(0), None).limit(), 1);
+        assert_eq!(params(Some(500), None).limit(), MAX_PAGE_LIMIT);
+        assert_eq!(params(None, Some(-4)).offset(), 0);
+    }
+}
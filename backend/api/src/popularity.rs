@@ -15,6 +15,12 @@ pub fn spawn_popularity_task(pool: PgPool) {
 
             if let Err(err) = recalculate_scores(&pool, "7d").await {
                 tracing::error!(error = ?err, "popularity: recalculation failed");
+                continue;
+            }
+
+            // The composite score builds on popularity, so refresh it too
+            if let Err(err) = crate::scoring::recompute_composite_scores(&pool).await {
+                tracing::error!(error = ?err, "scoring: composite recompute failed");
             }
         }
     });
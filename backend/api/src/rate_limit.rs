@@ -1,9 +1,9 @@
 use std::{
-    collections::HashMap,
+    collections::{BTreeMap, HashMap},
     env,
     net::{IpAddr, SocketAddr},
     sync::{Arc, Mutex},
-    time::{Duration, Instant},
+    time::Duration,
 };
 
 use axum::{
@@ -17,7 +17,8 @@ use axum::{
     response::{IntoResponse, Response},
     Json,
 };
-use serde_json::json;
+// Tokio's clock rather than std's, so tests can pause and advance it
+use tokio::time::Instant;
 
 const DEFAULT_READ_LIMIT_PER_MINUTE: u32 = 100;
 const DEFAULT_WRITE_LIMIT_PER_MINUTE: u32 = 20;
@@ -25,74 +26,384 @@ const DEFAULT_AUTH_LIMIT_PER_MINUTE: u32 = 1_000;
 const DEFAULT_HEALTH_LIMIT_PER_MINUTE: u32 = 10_000;
 const DEFAULT_WINDOW_SECONDS: u64 = 60;
 const ENDPOINT_LIMIT_ENV_PREFIX: &str = "RATE_LIMIT_ENDPOINT_";
-
-const HEADER_RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
-const HEADER_RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
-const HEADER_RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+const ROUTE_OVERRIDES_ENV: &str = "RATE_LIMIT_OVERRIDES";
+const ALLOWLIST_ENV: &str = "RATE_LIMIT_ALLOWLIST";
+
+/// Built-in per-route limits for expensive endpoints. `RATE_LIMIT_OVERRIDES`
+/// entries for the same route replace these.
+const DEFAULT_ROUTE_OVERRIDES: &[(&str, u32, u64)] = &[
+    ("POST /api/contracts", 10, 60),
+    ("POST /api/contracts/:id/scan", 5, 60),
+    ("POST /api/scan", 5, 60),
+];
+
+pub(crate) const HEADER_RATE_LIMIT_LIMIT: HeaderName = HeaderName::from_static("x-ratelimit-limit");
+pub(crate) const HEADER_RATE_LIMIT_REMAINING: HeaderName = HeaderName::from_static("x-ratelimit-remaining");
+pub(crate) const HEADER_RATE_LIMIT_RESET: HeaderName = HeaderName::from_static("x-ratelimit-reset");
+
+const ALGORITHM_ENV: &str = "RATE_LIMIT_ALGORITHM";
+const BACKEND_ENV: &str = "RATE_LIMIT_BACKEND";
+const REDIS_URL_ENV: &str = "REDIS_URL";
+const REDIS_KEY_PREFIX: &str = "soroban:ratelimit";
+/// A Redis round trip slower than this counts as Redis being unavailable
+const REDIS_TIMEOUT: Duration = Duration::from_millis(250);
+
+/// Same fixed-window bucket as the in-memory backend, done atomically so
+/// every replica shares one counter. A denied request doesn't consume a slot.
+/// Returns `{allowed, count, ttl_ms}`.
+const REDIS_BUCKET_SCRIPT: &str = r#"
+local limit = tonumber(ARGV[1])
+local window_ms = tonumber(ARGV[2])
+local count = tonumber(redis.call('GET', KEYS[1]) or '0')
+local allowed = 0
+if count < limit then
+  count = redis.call('INCR', KEYS[1])
+  allowed = 1
+end
+local ttl = redis.call('PTTL', KEYS[1])
+if ttl < 0 then
+  redis.call('PEXPIRE', KEYS[1], window_ms)
+  ttl = window_ms
+end
+return {allowed, count, ttl}
+"#;
+
+/// Sliding-window counter: `KEYS[1]` counts the current aligned window and
+/// `KEYS[2]` the one before it, which is weighted by how much of it still
+/// overlaps the trailing window. Returns `{allowed, ceil(estimate), ttl_ms}`.
+const REDIS_SLIDING_SCRIPT: &str = r#"
+local limit = tonumber(ARGV[1])
+local window_ms = tonumber(ARGV[2])
+local elapsed_ms = tonumber(ARGV[3])
+local previous = tonumber(redis.call('GET', KEYS[2]) or '0')
+local count = tonumber(redis.call('GET', KEYS[1]) or '0')
+local estimate = previous * (window_ms - elapsed_ms) / window_ms + count
+local allowed = 0
+if estimate < limit then
+  redis.call('INCR', KEYS[1])
+  redis.call('PEXPIRE', KEYS[1], window_ms * 2)
+  estimate = estimate + 1
+  allowed = 1
+end
+return {allowed, math.ceil(estimate), window_ms - elapsed_ms}
+"#;
 
 #[derive(Clone)]
 pub struct RateLimitState {
     config: Arc<RateLimitConfig>,
-    buckets: Arc<Mutex<HashMap<BucketKey, BucketState>>>,
+    backend: Backend,
+    algorithm: Algorithm,
+    /// Peers that are never limited (`RATE_LIMIT_ALLOWLIST`)
+    allowlist: Arc<[IpNet]>,
 }
 
-impl RateLimitState {
-    pub fn from_env() -> Self {
-        Self::new(RateLimitConfig::from_env())
+/// A CIDR range such as `10.0.0.0/8` or `2001:db8::/32`. A bare address is
+/// a single-host range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct IpNet {
+    network: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    fn parse(raw: &str) -> Result<Self, String> {
+        let raw = raw.trim();
+        let (addr, prefix) = match raw.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (raw, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|_| format!("`{}` is not an IP address or CIDR range", raw))?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            None => max,
+            Some(prefix) => prefix
+                .parse::<u8>()
+                .ok()
+                .filter(|prefix| *prefix <= max)
+                .ok_or_else(|| format!("`{}` has an invalid prefix length", raw))?,
+        };
+        Ok(Self {
+            network: addr,
+            prefix,
+        })
     }
 
-    fn new(config: RateLimitConfig) -> Self {
-        Self {
-            config: Arc::new(config),
-            buckets: Arc::new(Mutex::new(HashMap::new())),
+    fn contains(&self, ip: IpAddr) -> bool {
+        // IPv4 peers on a dual-stack socket show up as `::ffff:a.b.c.d`
+        match (self.network, ip.to_canonical()) {
+            (IpAddr::V4(network), IpAddr::V4(ip)) => {
+                let mask = u32::MAX.checked_shl(32 - self.prefix as u32).unwrap_or(0);
+                u32::from(network) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(network), IpAddr::V6(ip)) => {
+                let mask = u128::MAX.checked_shl(128 - self.prefix as u32).unwrap_or(0);
+                u128::from(network) & mask == u128::from(ip) & mask
+            }
+            _ => false,
         }
     }
+}
 
-    fn check_request<B>(&self, request: &Request<B>) -> RateLimitDecision {
-        let (limit, endpoint_key) = self.select_limit(request);
-        let ip = extract_client_ip(request);
-        let key = BucketKey { ip, endpoint_key };
-        let now = Instant::now();
+/// Parse `RATE_LIMIT_ALLOWLIST`: comma-separated CIDR ranges or addresses,
+/// e.g. `10.0.0.0/8,2001:db8::/32,192.0.2.7`. Unlike the limits, a bad entry
+/// is an error, since silently dropping it would throttle a trusted peer.
+fn parse_allowlist(raw: &str) -> Result<Vec<IpNet>, Vec<String>> {
+    let mut ranges = Vec::new();
+    let mut problems = Vec::new();
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        match IpNet::parse(entry) {
+            Ok(range) => ranges.push(range),
+            Err(err) => problems.push(format!("{} entry {}", ALLOWLIST_ENV, err)),
+        }
+    }
+    if problems.is_empty() {
+        Ok(ranges)
+    } else {
+        Err(problems)
+    }
+}
 
-        let mut buckets = self.buckets.lock().expect("rate limiter mutex poisoned");
+/// How a bucket's requests are counted against its limit
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    /// Counter reset at each window boundary. Cheap, but a client can spend
+    /// a full window's limit on each side of a boundary.
+    Fixed,
+    /// The previous window's count, weighted by how much of it still falls
+    /// inside the trailing window, plus the current count
+    Sliding,
+}
 
-        let bucket = buckets.entry(key).or_insert_with(|| BucketState {
-            window_start: now,
-            count: 0,
-        });
+impl Algorithm {
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let name = lookup(ALGORITHM_ENV).map(|raw| raw.trim().to_ascii_lowercase());
+        match name.as_deref() {
+            None | Some("") | Some("fixed") => Ok(Algorithm::Fixed),
+            Some("sliding") => Ok(Algorithm::Sliding),
+            Some(other) => Err(format!(
+                "{} must be `fixed` or `sliding` (got `{}`)",
+                ALGORITHM_ENV, other
+            )),
+        }
+    }
 
-        if now.duration_since(bucket.window_start) >= self.config.window {
-            bucket.window_start = now;
-            bucket.count = 0;
+    fn name(&self) -> &'static str {
+        match self {
+            Algorithm::Fixed => "fixed",
+            Algorithm::Sliding => "sliding",
         }
+    }
+}
 
-        let remaining_window = self
-            .config
-            .window
-            .saturating_sub(now.duration_since(bucket.window_start));
-        let reset_seconds = ceil_duration_to_seconds(remaining_window).max(1);
+/// Where bucket counters live
+#[derive(Clone)]
+enum Backend {
+    /// Per process; the default for single-node deploys
+    Memory(Arc<Mutex<MemoryBuckets>>),
+    /// Shared by every replica (`RATE_LIMIT_BACKEND=redis`)
+    Redis(Arc<RedisBackend>),
+}
 
-        if bucket.count >= limit {
-            return RateLimitDecision {
-                allowed: false,
-                limit,
-                remaining: 0,
-                reset_seconds,
-            };
+impl Backend {
+    fn memory() -> Self {
+        let buckets = MemoryBuckets::new(MEMORY_SWEEP_INTERVAL);
+        Backend::Memory(Arc::new(Mutex::new(buckets)))
+    }
+
+    fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let name = lookup(BACKEND_ENV).map(|raw| raw.trim().to_ascii_lowercase());
+        match name.as_deref() {
+            None | Some("") | Some("memory") => Ok(Self::memory()),
+            Some("redis") => {
+                let url = lookup(REDIS_URL_ENV)
+                    .filter(|url| !url.trim().is_empty())
+                    .ok_or_else(|| format!("{}=redis requires {}", BACKEND_ENV, REDIS_URL_ENV))?;
+                RedisBackend::new(url.trim()).map(|redis| Backend::Redis(Arc::new(redis)))
+            }
+            Some(other) => Err(format!(
+                "{} must be `memory` or `redis` (got `{}`)",
+                BACKEND_ENV, other
+            )),
         }
+    }
 
-        bucket.count += 1;
-        let remaining = limit.saturating_sub(bucket.count);
+    fn name(&self) -> &'static str {
+        match self {
+            Backend::Memory(_) => "memory",
+            Backend::Redis(_) => "redis",
+        }
+    }
+}
 
-        RateLimitDecision {
-            allowed: true,
+struct RedisBackend {
+    client: redis::Client,
+    /// Connected on first use and reconnecting on its own afterwards, so the
+    /// API can start (failing open) while Redis is still down
+    connection: tokio::sync::OnceCell<redis::aio::ConnectionManager>,
+    script: redis::Script,
+    sliding_script: redis::Script,
+}
+
+impl RedisBackend {
+    fn new(url: &str) -> Result<Self, String> {
+        let client = redis::Client::open(url)
+            .map_err(|err| format!("invalid {}: {}", REDIS_URL_ENV, err))?;
+        Ok(Self {
+            client,
+            connection: tokio::sync::OnceCell::new(),
+            script: redis::Script::new(REDIS_BUCKET_SCRIPT),
+            sliding_script: redis::Script::new(REDIS_SLIDING_SCRIPT),
+        })
+    }
+
+    async fn check(
+        &self,
+        key: &BucketKey,
+        limit: u32,
+        window: Duration,
+        algorithm: Algorithm,
+    ) -> redis::RedisResult<RateLimitDecision> {
+        let mut connection = self
+            .connection
+            .get_or_try_init(|| redis::aio::ConnectionManager::new(self.client.clone()))
+            .await?
+            .clone();
+        let redis_key = format!("{}:{}:{}", REDIS_KEY_PREFIX, key.endpoint_key, key.ip);
+        let window_ms = window.as_millis().max(1) as u64;
+        let (allowed, count, ttl_ms): (u8, u32, u64) = match algorithm {
+            Algorithm::Fixed => {
+                self.script
+                    .key(redis_key)
+                    .arg(limit)
+                    .arg(window_ms)
+                    .invoke_async(&mut connection)
+                    .await?
+            }
+            Algorithm::Sliding => {
+                // Windows are aligned to the epoch so every replica agrees on
+                // where they start
+                let now_ms = std::time::SystemTime::now()
+                    .duration_since(std::time::UNIX_EPOCH)
+                    .unwrap_or_default()
+                    .as_millis() as u64;
+                let index = now_ms / window_ms;
+                self.sliding_script
+                    .key(format!("{}:{}", redis_key, index))
+                    .key(format!("{}:{}", redis_key, index.saturating_sub(1)))
+                    .arg(limit)
+                    .arg(window_ms)
+                    .arg(now_ms % window_ms)
+                    .invoke_async(&mut connection)
+                    .await?
+            }
+        };
+
+        Ok(RateLimitDecision {
+            allowed: allowed == 1,
             limit,
-            remaining,
-            reset_seconds,
+            remaining: limit.saturating_sub(count),
+            reset_seconds: ceil_duration_to_seconds(Duration::from_millis(ttl_ms)).max(1),
+        })
+    }
+}
+
+impl RateLimitState {
+    /// Reads the limits, `RATE_LIMIT_ALLOWLIST`, `RATE_LIMIT_ALGORITHM`
+    /// (`fixed`, the default, or `sliding`) and `RATE_LIMIT_BACKEND`
+    /// (`memory`, the default, or `redis` with `REDIS_URL`)
+    pub fn from_env() -> Result<Self, String> {
+        let allowlist = match env::var(ALLOWLIST_ENV) {
+            Ok(raw) => parse_allowlist(&raw).map_err(|problems| problems.join("; "))?,
+            Err(_) => Vec::new(),
+        };
+        let algorithm = Algorithm::from_lookup(|name| env::var(name).ok())?;
+        let backend = Backend::from_lookup(|name| env::var(name).ok())?;
+        tracing::info!(
+            backend = backend.name(),
+            algorithm = algorithm.name(),
+            allowlisted_ranges = allowlist.len(),
+            "Rate limiter backend selected"
+        );
+        Ok(Self {
+            config: Arc::new(RateLimitConfig::from_env()),
+            backend,
+            algorithm,
+            allowlist: allowlist.into(),
+        })
+    }
+
+    fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config: Arc::new(config),
+            backend: Backend::memory(),
+            algorithm: Algorithm::Fixed,
+            allowlist: Arc::from([]),
         }
     }
 
-    fn select_limit<B>(&self, request: &Request<B>) -> (u32, String) {
+    /// Whether the connecting peer is allowlisted. Only the socket address
+    /// counts: `X-Forwarded-For` is client-controlled and would let anyone
+    /// opt out of limiting.
+    fn is_allowlisted<B>(&self, request: &Request<B>) -> bool {
+        if self.allowlist.is_empty() {
+            return false;
+        }
+        request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .is_some_and(|ConnectInfo(peer)| {
+                self.allowlist.iter().any(|range| range.contains(peer.ip()))
+            })
+    }
+
+    /// Bucket and limit that apply to `request`. Kept apart from [`Self::check`]
+    /// so the request isn't held across the Redis round trip.
+    fn bucket_for<B>(&self, request: &Request<B>) -> (BucketKey, u32, Duration) {
+        let (limit, window, endpoint_key) = self.select_limit(request);
+        let ip = extract_client_ip(request);
+        (BucketKey { ip, endpoint_key }, limit, window)
+    }
+
+    async fn check(&self, key: BucketKey, limit: u32, window: Duration) -> RateLimitDecision {
+        match &self.backend {
+            Backend::Memory(buckets) => check_memory(
+                buckets,
+                key,
+                limit,
+                window,
+                self.algorithm,
+                &crate::metrics::RATE_LIMIT_ACTIVE_BUCKETS,
+            ),
+            Backend::Redis(redis) => {
+                let outcome = tokio::time::timeout(
+                    REDIS_TIMEOUT,
+                    redis.check(&key, limit, window, self.algorithm),
+                )
+                .await;
+                let error = match outcome {
+                    Ok(Ok(decision)) => return decision,
+                    Ok(Err(err)) => err.to_string(),
+                    Err(_) => format!("no reply within {}ms", REDIS_TIMEOUT.as_millis()),
+                };
+                // Fail open: a Redis outage must not take the API down with it
+                tracing::error!(
+                    error = %error,
+                    endpoint = %key.endpoint_key,
+                    "RATE LIMITING DISABLED: Redis backend unavailable, allowing request unthrottled"
+                );
+                RateLimitDecision {
+                    allowed: true,
+                    limit,
+                    remaining: limit,
+                    reset_seconds: ceil_duration_to_seconds(window).max(1),
+                }
+            }
+        }
+    }
+
+    fn select_limit<B>(&self, request: &Request<B>) -> (u32, Duration, String) {
         let method = request.method();
         let matched_path = request
             .extensions()
@@ -100,24 +411,150 @@ impl RateLimitState {
             .map(|p| p.as_str())
             .unwrap_or_else(|| request.uri().path());
         let endpoint_key = endpoint_key(method, matched_path);
+        let window = self.config.window;
+
+        if let Some(route) = self
+            .config
+            .route_overrides
+            .get(&route_override_key(method, matched_path))
+        {
+            return (route.limit, route.window, endpoint_key);
+        }
 
         if let Some(limit) = self.config.endpoint_limits.get(&endpoint_key) {
-            return (*limit, endpoint_key);
+            return (*limit, window, endpoint_key);
         }
 
         if matched_path == "/health" || method == Method::OPTIONS {
-            return (self.config.health_limit, endpoint_key);
+            return (self.config.health_limit, window, endpoint_key);
         }
 
         if request.headers().contains_key(AUTHORIZATION) {
-            return (self.config.auth_limit, endpoint_key);
+            return (self.config.auth_limit, window, endpoint_key);
         }
 
         if is_write_method(method) {
-            return (self.config.write_limit, endpoint_key);
+            return (self.config.write_limit, window, endpoint_key);
         }
 
-        (self.config.read_limit, endpoint_key)
+        (self.config.read_limit, window, endpoint_key)
+    }
+}
+
+/// How often the memory backend drops buckets that can no longer limit anyone
+const MEMORY_SWEEP_INTERVAL: Duration = Duration::from_secs(10);
+
+/// The memory backend's buckets. Expired ones are swept at most once per
+/// `sweep_every` rather than on every request; one found before the sweep
+/// starts over from zero when it is next used, so keeping it is harmless.
+struct MemoryBuckets {
+    buckets: HashMap<BucketKey, BucketState>,
+    sweep_every: Duration,
+    next_sweep: Instant,
+}
+
+impl MemoryBuckets {
+    fn new(sweep_every: Duration) -> Self {
+        Self {
+            buckets: HashMap::new(),
+            sweep_every,
+            next_sweep: Instant::now() + sweep_every,
+        }
+    }
+
+    fn sweep_if_due(&mut self, now: Instant, algorithm: Algorithm) {
+        if now < self.next_sweep {
+            return;
+        }
+        self.buckets
+            .retain(|_, bucket| !bucket.expired(now, algorithm));
+        self.next_sweep = now + self.sweep_every;
+    }
+}
+
+/// `active_buckets` is set to the number of tracked buckets, counting `key`;
+/// expired ones count until the next sweep
+fn check_memory(
+    buckets: &Mutex<MemoryBuckets>,
+    key: BucketKey,
+    limit: u32,
+    window: Duration,
+    algorithm: Algorithm,
+    active_buckets: &prometheus::IntGauge,
+) -> RateLimitDecision {
+    let now = Instant::now();
+    let mut memory = buckets.lock().expect("rate limiter mutex poisoned");
+    memory.sweep_if_due(now, algorithm);
+    let buckets = &mut memory.buckets;
+    let tracked = buckets.len() + usize::from(!buckets.contains_key(&key));
+    active_buckets.set(tracked as i64);
+
+    let bucket = buckets.entry(key).or_insert_with(|| BucketState {
+        window_start: now,
+        window,
+        count: 0,
+        previous_count: 0,
+    });
+    bucket.window = window;
+
+    let elapsed = now.duration_since(bucket.window_start);
+    if elapsed >= window {
+        match algorithm {
+            Algorithm::Fixed => bucket.window_start = now,
+            Algorithm::Sliding => {
+                // Stay aligned to the first window so the previous count is
+                // weighted by real overlap; more than one window idle means
+                // nothing from before still overlaps
+                let windows_passed = elapsed.as_nanos() / window.as_nanos();
+                bucket.previous_count = if windows_passed == 1 { bucket.count } else { 0 };
+                bucket.window_start =
+                    now - Duration::from_nanos((elapsed.as_nanos() % window.as_nanos()) as u64);
+            }
+        }
+        bucket.count = 0;
+    }
+
+    let into_window = now.duration_since(bucket.window_start);
+    let remaining_window = window.saturating_sub(into_window);
+    let reset_seconds = ceil_duration_to_seconds(remaining_window).max(1);
+
+    if algorithm == Algorithm::Sliding {
+        let overlap = remaining_window.as_secs_f64() / window.as_secs_f64();
+        let estimate = bucket.previous_count as f64 * overlap + bucket.count as f64;
+        if estimate >= limit as f64 {
+            return RateLimitDecision {
+                allowed: false,
+                limit,
+                remaining: 0,
+                reset_seconds,
+            };
+        }
+        bucket.count += 1;
+        return RateLimitDecision {
+            allowed: true,
+            limit,
+            remaining: limit.saturating_sub((estimate + 1.0).ceil() as u32),
+            reset_seconds,
+        };
+    }
+
+    if bucket.count >= limit {
+        return RateLimitDecision {
+            allowed: false,
+            limit,
+            remaining: 0,
+            reset_seconds,
+        };
+    }
+
+    bucket.count += 1;
+    let remaining = limit.saturating_sub(bucket.count);
+
+    RateLimitDecision {
+        allowed: true,
+        limit,
+        remaining,
+        reset_seconds,
     }
 }
 
@@ -128,6 +565,14 @@ struct RateLimitConfig {
     health_limit: u32,
     window: Duration,
     endpoint_limits: HashMap<String, u32>,
+    /// Keyed by `"<METHOD> <route template>"`, e.g. `"POST /api/contracts"`
+    route_overrides: HashMap<String, RouteOverride>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct RouteOverride {
+    limit: u32,
+    window: Duration,
 }
 
 impl RateLimitConfig {
@@ -162,6 +607,11 @@ impl RateLimitConfig {
             endpoint_limits.insert(endpoint_key.to_string(), limit);
         }
 
+        let mut route_overrides = default_route_overrides();
+        if let Ok(raw) = env::var(ROUTE_OVERRIDES_ENV) {
+            route_overrides.extend(parse_route_overrides(&raw));
+        }
+
         tracing::info!(
             read_limit,
             write_limit,
@@ -169,6 +619,7 @@ impl RateLimitConfig {
             health_limit,
             window_seconds,
             endpoint_overrides = endpoint_limits.len(),
+            route_overrides = route_overrides.len(),
             "Rate limiter configured"
         );
 
@@ -179,6 +630,7 @@ impl RateLimitConfig {
             health_limit,
             window: Duration::from_secs(window_seconds),
             endpoint_limits,
+            route_overrides,
         }
     }
 
@@ -191,8 +643,137 @@ impl RateLimitConfig {
             health_limit,
             window,
             endpoint_limits: HashMap::new(),
+            route_overrides: HashMap::new(),
+        }
+    }
+
+    #[cfg(test)]
+    fn with_route_override(mut self, route: &str, limit: u32, window: Duration) -> Self {
+        self.route_overrides
+            .insert(route.to_string(), RouteOverride { limit, window });
+        self
+    }
+}
+
+/// [`DEFAULT_ROUTE_OVERRIDES`] keyed the way [`RateLimitState::select_limit`] looks them up
+fn default_route_overrides() -> HashMap<String, RouteOverride> {
+    DEFAULT_ROUTE_OVERRIDES
+        .iter()
+        .map(|(route, limit, secs)| {
+            (
+                route.to_string(),
+                RouteOverride {
+                    limit: *limit,
+                    window: Duration::from_secs(*secs),
+                },
+            )
+        })
+        .collect()
+}
+
+/// Parse `RATE_LIMIT_OVERRIDES`: comma-separated `METHOD /route=requests/seconds`
+/// entries, e.g. `POST /api/contracts=5/60,POST /api/contracts/:id/scan=2/60`.
+/// Invalid entries are logged and skipped.
+fn parse_route_overrides(raw: &str) -> HashMap<String, RouteOverride> {
+    let mut overrides = HashMap::new();
+
+    for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+        match parse_route_override(entry) {
+            Some((key, value)) => {
+                overrides.insert(key, value);
+            }
+            None => tracing::warn!("Ignoring invalid {ROUTE_OVERRIDES_ENV} entry `{entry}`"),
+        }
+    }
+
+    overrides
+}
+
+/// One `METHOD /path=limit/seconds` entry of `RATE_LIMIT_OVERRIDES`
+fn parse_route_override(entry: &str) -> Option<(String, RouteOverride)> {
+    let (route, spec) = entry.rsplit_once('=')?;
+    let (method, path) = route.trim().split_once(char::is_whitespace)?;
+    let method = Method::from_bytes(method.to_ascii_uppercase().as_bytes()).ok()?;
+    let path = path.trim();
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    let (limit, secs) = spec.trim().split_once('/')?;
+    let limit = limit.trim().parse::<u32>().ok().filter(|l| *l > 0)?;
+    let secs = secs.trim().parse::<u64>().ok().filter(|s| *s > 0)?;
+
+    Some((
+        route_override_key(&method, path),
+        RouteOverride {
+            limit,
+            window: Duration::from_secs(secs),
+        },
+    ))
+}
+
+/// Every rate-limit setting in `env` that would otherwise be ignored with a
+/// warning (or, for the backend, fail at startup), for `config::validate`
+pub fn config_problems(env: &BTreeMap<String, String>) -> Vec<String> {
+    let mut problems = Vec::new();
+    let positive_u32 = |key: &str, raw: &str| match raw.trim().parse::<u32>() {
+        Ok(value) if value > 0 => None,
+        _ => Some(format!(
+            "{} must be a positive integer (got '{}')",
+            key, raw
+        )),
+    };
+
+    for key in [
+        "RATE_LIMIT_READ_PER_MINUTE",
+        "RATE_LIMIT_WRITE_PER_MINUTE",
+        "RATE_LIMIT_AUTH_PER_MINUTE",
+        "RATE_LIMIT_HEALTH_PER_MINUTE",
+    ] {
+        if let Some(raw) = env.get(key) {
+            problems.extend(positive_u32(key, raw));
         }
     }
+    if let Some(raw) = env.get("RATE_LIMIT_WINDOW_SECONDS") {
+        if !matches!(raw.trim().parse::<u64>(), Ok(secs) if secs > 0) {
+            problems.push(format!(
+                "RATE_LIMIT_WINDOW_SECONDS must be a positive integer (got '{}')",
+                raw
+            ));
+        }
+    }
+    for (key, raw) in env.range(ENDPOINT_LIMIT_ENV_PREFIX.to_string()..) {
+        if !key.starts_with(ENDPOINT_LIMIT_ENV_PREFIX) {
+            break;
+        }
+        problems.extend(positive_u32(key, raw));
+    }
+    if let Some(raw) = env.get(ROUTE_OVERRIDES_ENV) {
+        for entry in raw.split(',').map(str::trim).filter(|e| !e.is_empty()) {
+            if parse_route_override(entry).is_none() {
+                problems.push(format!(
+                    "{} entry `{}` is not `METHOD /path=limit/seconds`",
+                    ROUTE_OVERRIDES_ENV, entry
+                ));
+            }
+        }
+    }
+    if let Some(raw) = env.get(ALLOWLIST_ENV) {
+        if let Err(errs) = parse_allowlist(raw) {
+            problems.extend(errs);
+        }
+    }
+    if let Err(err) = Algorithm::from_lookup(|name| env.get(name).cloned()) {
+        problems.push(err);
+    }
+    if let Err(err) = Backend::from_lookup(|name| env.get(name).cloned()) {
+        problems.push(err);
+    }
+    problems
+}
+
+fn route_override_key(method: &Method, route: &str) -> String {
+    format!("{} {}", method.as_str(), route)
 }
 
 #[derive(Hash, Eq, PartialEq)]
@@ -203,7 +784,22 @@ struct BucketKey {
 
 struct BucketState {
     window_start: Instant,
+    window: Duration,
     count: u32,
+    /// Count of the window before `window_start`; only used when sliding
+    previous_count: u32,
+}
+
+impl BucketState {
+    /// Whether nothing counted here can limit a request any more: a fixed
+    /// window once it ends, a sliding one once the window after it ends too
+    fn expired(&self, now: Instant, algorithm: Algorithm) -> bool {
+        let lifetime = match algorithm {
+            Algorithm::Fixed => self.window,
+            Algorithm::Sliding => self.window * 2,
+        };
+        now.duration_since(self.window_start) >= lifetime
+    }
 }
 
 struct RateLimitDecision {
@@ -218,26 +814,25 @@ pub async fn rate_limit_middleware(
     request: Request<Body>,
     next: Next,
 ) -> Response {
-    let decision = rate_limiter.check_request(&request);
+    if rate_limiter.is_allowlisted(&request) {
+        return next.run(request).await;
+    }
+
+    let route = crate::metrics::route_label(&request);
+    let (key, limit, window) = rate_limiter.bucket_for(&request);
+    let decision = rate_limiter.check(key, limit, window).await;
+    crate::metrics::record_rate_limit_decision(&route, decision.allowed);
 
     if !decision.allowed {
         let mut response = (
             StatusCode::TOO_MANY_REQUESTS,
-            Json(json!({
-                "error": "RateLimitExceeded",
-                "message": "Too many requests. Please retry after the indicated time.",
-                "code": 429,
-                "timestamp": chrono::Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Secs, true),
-                "correlation_id": uuid::Uuid::new_v4().to_string()
-            })),
+            Json(crate::error::ErrorResponse::new(
+                crate::error::ErrorCode::RateLimitExceeded,
+                "Too many requests. Please retry after the indicated time.",
+            )),
         )
             .into_response();
         attach_rate_limit_headers(&mut response, &decision);
-        response.headers_mut().insert(
-            RETRY_AFTER,
-            HeaderValue::from_str(&decision.reset_seconds.to_string())
-                .unwrap_or_else(|_| HeaderValue::from_static("1")),
-        );
         return response;
     }
 
@@ -246,6 +841,8 @@ pub async fn rate_limit_middleware(
     response
 }
 
+/// Limit/remaining/reset on every response, plus `Retry-After` (seconds until
+/// the window resets) so clients can pace themselves before hitting a 429.
 fn attach_rate_limit_headers(response: &mut Response, decision: &RateLimitDecision) {
     response.headers_mut().insert(
         HEADER_RATE_LIMIT_LIMIT,
@@ -257,11 +854,12 @@ fn attach_rate_limit_headers(response: &mut Response, decision: &RateLimitDecisi
         HeaderValue::from_str(&decision.remaining.to_string())
             .unwrap_or_else(|_| HeaderValue::from_static("0")),
     );
-    response.headers_mut().insert(
-        HEADER_RATE_LIMIT_RESET,
-        HeaderValue::from_str(&decision.reset_seconds.to_string())
-            .unwrap_or_else(|_| HeaderValue::from_static("1")),
-    );
+    let reset = HeaderValue::from_str(&decision.reset_seconds.to_string())
+        .unwrap_or_else(|_| HeaderValue::from_static("1"));
+    response
+        .headers_mut()
+        .insert(HEADER_RATE_LIMIT_RESET, reset.clone());
+    response.headers_mut().insert(RETRY_AFTER, reset);
 }
 
 fn extract_client_ip<B>(request: &Request<B>) -> String {
@@ -384,13 +982,19 @@ mod tests {
         health_limit: u32,
         window: Duration,
     ) -> Router<()> {
-        let limiter = RateLimitState::new(RateLimitConfig::for_tests(
+        app_with_config(RateLimitConfig::for_tests(
             read_limit,
             write_limit,
             health_limit,
             window,
-        ));
+        ))
+    }
 
+    fn app_with_config(config: RateLimitConfig) -> Router<()> {
+        app_with_limiter(RateLimitState::new(config))
+    }
+
+    fn app_with_limiter(limiter: RateLimitState) -> Router<()> {
         Router::new()
             .route("/health", get(|| async { "ok" }))
             .route("/read", get(|| async { "read" }))
@@ -486,6 +1090,155 @@ mod tests {
         assert!(limited_response.headers().contains_key(RETRY_AFTER));
     }
 
+    #[tokio::test]
+    async fn remaining_decrements_and_429_carries_retry_after() {
+        let app = test_app(3, 1, 10_000, Duration::from_secs(60));
+        let header = |response: &Response, name: &HeaderName| -> u64 {
+            response.headers()[name].to_str().unwrap().parse().unwrap()
+        };
+
+        let mut remaining = Vec::new();
+        for _ in 0..3 {
+            let response = call(
+                &app,
+                Request::builder()
+                    .uri("/read")
+                    .method("GET")
+                    .header("x-forwarded-for", "192.0.2.44")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(header(&response, &HEADER_RATE_LIMIT_LIMIT), 3);
+            assert!(header(&response, &RETRY_AFTER) > 0);
+            remaining.push(header(&response, &HEADER_RATE_LIMIT_REMAINING));
+        }
+        assert_eq!(remaining, vec![2, 1, 0]);
+
+        let limited = call(
+            &app,
+            Request::builder()
+                .uri("/read")
+                .method("GET")
+                .header("x-forwarded-for", "192.0.2.44")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(header(&limited, &HEADER_RATE_LIMIT_REMAINING), 0);
+        let retry_after = header(&limited, &RETRY_AFTER);
+        assert!(retry_after > 0 && retry_after <= 60);
+    }
+
+    #[tokio::test]
+    async fn rejects_are_counted_once_per_429() {
+        use crate::metrics::{RATE_LIMIT_ACTIVE_BUCKETS, RATE_LIMIT_ALLOWED, RATE_LIMIT_REJECTED};
+
+        // A route of its own, so tests running alongside don't share labels
+        let app = Router::new()
+            .route("/metered", get(|| async { "metered" }))
+            .layer(middleware::from_fn_with_state(
+                RateLimitState::new(RateLimitConfig::for_tests(
+                    2,
+                    2,
+                    10_000,
+                    Duration::from_secs(60),
+                )),
+                rate_limit_middleware,
+            ));
+        let count = |outcome: &str| {
+            crate::metrics::RATE_LIMIT_DECISIONS
+                .with_label_values(&["/metered", outcome])
+                .get()
+        };
+        let allowed_before = count(RATE_LIMIT_ALLOWED);
+        let rejected_before = count(RATE_LIMIT_REJECTED);
+
+        let mut too_many = 0;
+        for _ in 0..5 {
+            let response = call(
+                &app,
+                Request::builder()
+                    .uri("/metered")
+                    .method("GET")
+                    .header("x-forwarded-for", "198.51.100.77")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+            if response.status() == StatusCode::TOO_MANY_REQUESTS {
+                too_many += 1;
+            }
+        }
+
+        assert_eq!(too_many, 3);
+        assert_eq!(count(RATE_LIMIT_REJECTED) - rejected_before, 3);
+        assert_eq!(count(RATE_LIMIT_ALLOWED) - allowed_before, 2);
+        assert!(RATE_LIMIT_ACTIVE_BUCKETS.get() >= 1);
+    }
+
+    #[test]
+    fn expired_buckets_stop_counting_as_active() {
+        let gauge = prometheus::IntGauge::new("active_buckets", "test").unwrap();
+        let window = Duration::from_millis(200);
+        let check = |buckets: &Mutex<MemoryBuckets>, ip: &str, algorithm| {
+            let key = BucketKey {
+                ip: ip.to_string(),
+                endpoint_key: "GET /read".to_string(),
+            };
+            check_memory(buckets, key, 10, window, algorithm, &gauge);
+        };
+
+        // Sweeping on every request, so expiry shows up immediately
+        let fixed = Mutex::new(MemoryBuckets::new(Duration::ZERO));
+        check(&fixed, "203.0.113.1", Algorithm::Fixed);
+        check(&fixed, "203.0.113.2", Algorithm::Fixed);
+        check(&fixed, "203.0.113.3", Algorithm::Fixed);
+        assert_eq!(gauge.get(), 3);
+
+        std::thread::sleep(window + window / 4);
+        check(&fixed, "203.0.113.4", Algorithm::Fixed);
+        assert_eq!(gauge.get(), 1);
+        assert_eq!(fixed.lock().unwrap().buckets.len(), 1);
+
+        // A sliding bucket still weighs its last window until the next ends
+        let sliding = Mutex::new(MemoryBuckets::new(Duration::ZERO));
+        check(&sliding, "203.0.113.1", Algorithm::Sliding);
+        std::thread::sleep(window + window / 4);
+        check(&sliding, "203.0.113.2", Algorithm::Sliding);
+        assert_eq!(gauge.get(), 2);
+        std::thread::sleep(window);
+        check(&sliding, "203.0.113.3", Algorithm::Sliding);
+        assert_eq!(gauge.get(), 2);
+        std::thread::sleep(window * 2);
+        check(&sliding, "203.0.113.4", Algorithm::Sliding);
+        assert_eq!(gauge.get(), 1);
+    }
+
+    #[test]
+    fn expired_buckets_are_kept_until_the_sweep_is_due() {
+        let gauge = prometheus::IntGauge::new("swept_buckets", "test").unwrap();
+        let window = Duration::from_millis(200);
+        let buckets = Mutex::new(MemoryBuckets::new(Duration::from_secs(3600)));
+        let key = BucketKey {
+            ip: "203.0.113.1".to_string(),
+            endpoint_key: "GET /read".to_string(),
+        };
+        check_memory(&buckets, key, 10, window, Algorithm::Fixed, &gauge);
+
+        let mut memory = buckets.lock().unwrap();
+        let expired = Instant::now() + window * 3;
+        memory.sweep_if_due(expired, Algorithm::Fixed);
+        assert_eq!(memory.buckets.len(), 1);
+
+        let due = Instant::now() + Duration::from_secs(3601);
+        memory.sweep_if_due(due, Algorithm::Fixed);
+        assert!(memory.buckets.is_empty());
+        assert_eq!(memory.next_sweep, due + Duration::from_secs(3600));
+    }
+
     #[tokio::test]
     async fn allows_requests_again_after_window_reset() {
         let app = test_app(1, 1, 10_000, Duration::from_secs(1));
@@ -571,6 +1324,299 @@ mod tests {
         assert_eq!(read_ok.status(), StatusCode::OK);
     }
 
+    #[tokio::test]
+    async fn route_override_throttles_write_before_read() {
+        let config = RateLimitConfig::for_tests(5, 5, 10_000, Duration::from_secs(60))
+            .with_route_override("POST /write", 2, Duration::from_secs(60));
+        let app = app_with_config(config);
+        let ip = "203.0.113.77";
+
+        let request = |method: &str, uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .method(method)
+                .header("x-forwarded-for", ip)
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..2 {
+            let response = call(&app, request("POST", "/write")).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[HEADER_RATE_LIMIT_LIMIT], "2");
+        }
+        let limited = call(&app, request("POST", "/write")).await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        for _ in 0..5 {
+            let response = call(&app, request("GET", "/read")).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let read_limited = call(&app, request("GET", "/read")).await;
+        assert_eq!(read_limited.status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn scan_submissions_have_their_own_lower_limit() {
+        let mut config = RateLimitConfig::for_tests(100, 20, 10_000, Duration::from_secs(60));
+        config.route_overrides = default_route_overrides();
+        let scan_limit = config.route_overrides["POST /api/scan"].limit;
+        assert!(scan_limit < config.write_limit);
+
+        let app = Router::new()
+            .route("/api/scan", post(|| async { "queued" }))
+            .route("/write", post(|| async { "write" }))
+            .layer(middleware::from_fn_with_state(
+                RateLimitState::new(config),
+                rate_limit_middleware,
+            ));
+        let request = |uri: &str| {
+            Request::builder()
+                .uri(uri)
+                .method("POST")
+                .header("x-forwarded-for", "203.0.113.91")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..scan_limit {
+            let response = call(&app, request("/api/scan")).await;
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+        let limited = call(&app, request("/api/scan")).await;
+        assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Other writes from the same client are still on the generic limit
+        let write = call(&app, request("/write")).await;
+        assert_eq!(write.status(), StatusCode::OK);
+        assert_eq!(write.headers()[HEADER_RATE_LIMIT_LIMIT], "20");
+    }
+
+    #[test]
+    fn parses_route_overrides() {
+        let overrides = parse_route_overrides(
+            "POST /api/contracts=5/60, post /api/contracts/:id/scan = 2/30,bogus,GET /x=0/60",
+        );
+
+        assert_eq!(overrides.len(), 2);
+        assert_eq!(
+            overrides["POST /api/contracts"],
+            RouteOverride {
+                limit: 5,
+                window: Duration::from_secs(60)
+            }
+        );
+        assert_eq!(
+            overrides["POST /api/contracts/:id/scan"],
+            RouteOverride {
+                limit: 2,
+                window: Duration::from_secs(30)
+            }
+        );
+    }
+
+    #[test]
+    fn parses_ipv4_and_ipv6_allowlist_ranges() {
+        let ranges = parse_allowlist("10.0.0.0/8, 192.0.2.7,2001:db8::/32,").unwrap();
+        assert_eq!(ranges.len(), 3);
+
+        let ip = |raw: &str| raw.parse::<IpAddr>().unwrap();
+        assert!(ranges[0].contains(ip("10.255.1.2")));
+        assert!(!ranges[0].contains(ip("11.0.0.1")));
+        assert!(ranges[1].contains(ip("192.0.2.7")));
+        assert!(!ranges[1].contains(ip("192.0.2.8")));
+        assert!(ranges[2].contains(ip("2001:db8:ffff::1")));
+        assert!(!ranges[2].contains(ip("2001:db9::1")));
+        assert!(ranges[0].contains(ip("::ffff:10.1.2.3")));
+        assert!(IpNet::parse("0.0.0.0/0")
+            .unwrap()
+            .contains(ip("203.0.113.1")));
+        assert!(!IpNet::parse("::/0").unwrap().contains(ip("203.0.113.1")));
+
+        let problems = parse_allowlist("10.0.0.0/33,not-an-ip,::1/129,10.0.0.0/x").unwrap_err();
+        assert_eq!(problems.len(), 4, "{:?}", problems);
+        assert!(problems[0].contains("10.0.0.0/33"));
+    }
+
+    #[tokio::test]
+    async fn allowlisted_peers_are_never_limited() {
+        let limiter = RateLimitState {
+            allowlist: parse_allowlist("10.0.0.0/8").unwrap().into(),
+            ..RateLimitState::new(RateLimitConfig::for_tests(
+                2,
+                2,
+                10_000,
+                Duration::from_secs(60),
+            ))
+        };
+        let app = app_with_limiter(limiter.clone());
+        let request = |peer: &str| {
+            let mut request = Request::builder()
+                .uri("/read")
+                .method("GET")
+                .body(Body::empty())
+                .unwrap();
+            request
+                .extensions_mut()
+                .insert(ConnectInfo(peer.parse::<SocketAddr>().unwrap()));
+            request
+        };
+
+        for _ in 0..10 {
+            let response = call(&app, request("10.1.2.3:40000")).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert!(response.headers().get(HEADER_RATE_LIMIT_LIMIT).is_none());
+        }
+        let Backend::Memory(buckets) = &limiter.backend else {
+            unreachable!()
+        };
+        assert!(buckets.lock().unwrap().buckets.is_empty());
+
+        for expected in [
+            StatusCode::OK,
+            StatusCode::OK,
+            StatusCode::TOO_MANY_REQUESTS,
+        ] {
+            let response = call(&app, request("203.0.113.5:40000")).await;
+            assert_eq!(response.status(), expected);
+        }
+    }
+
+    /// Spend the whole limit at the end of one window, then burst again just
+    /// after the boundary. Returns how many of the second burst got a 429.
+    /// Needs a paused clock.
+    async fn rejections_across_boundary(algorithm: Algorithm) -> usize {
+        let window = Duration::from_secs(1);
+        let app = app_with_limiter(RateLimitState {
+            algorithm,
+            ..RateLimitState::new(RateLimitConfig::for_tests(4, 4, 10_000, window))
+        });
+        let request = || {
+            Request::builder()
+                .uri("/read")
+                .method("GET")
+                .header("x-forwarded-for", "198.51.100.7")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for _ in 0..4 {
+            assert_eq!(call(&app, request()).await.status(), StatusCode::OK);
+        }
+        tokio::time::advance(window + Duration::from_millis(50)).await;
+
+        let mut rejected = 0;
+        for _ in 0..4 {
+            if call(&app, request()).await.status() == StatusCode::TOO_MANY_REQUESTS {
+                rejected += 1;
+            }
+        }
+        rejected
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sliding_window_rejects_a_burst_straddling_the_boundary() {
+        assert_eq!(rejections_across_boundary(Algorithm::Fixed).await, 0);
+        // ~95% of the previous window still overlaps, so only one more fits
+        assert_eq!(rejections_across_boundary(Algorithm::Sliding).await, 3);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn sliding_window_forgets_counts_after_two_windows() {
+        let window = Duration::from_millis(200);
+        let app = app_with_limiter(RateLimitState {
+            algorithm: Algorithm::Sliding,
+            ..RateLimitState::new(RateLimitConfig::for_tests(2, 2, 10_000, window))
+        });
+        let request = || {
+            Request::builder()
+                .uri("/read")
+                .method("GET")
+                .header("x-forwarded-for", "198.51.100.8")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        for expected in [
+            StatusCode::OK,
+            StatusCode::OK,
+            StatusCode::TOO_MANY_REQUESTS,
+        ] {
+            assert_eq!(call(&app, request()).await.status(), expected);
+        }
+        tokio::time::advance(window * 2 + Duration::from_millis(20)).await;
+        assert_eq!(call(&app, request()).await.status(), StatusCode::OK);
+        assert_eq!(call(&app, request()).await.status(), StatusCode::OK);
+    }
+
+    #[test]
+    fn algorithm_defaults_to_fixed() {
+        let lookup = |value: &'static str| {
+            move |name: &str| (name == ALGORITHM_ENV).then(|| value.to_string())
+        };
+        assert_eq!(Algorithm::from_lookup(|_| None), Ok(Algorithm::Fixed));
+        assert_eq!(
+            Algorithm::from_lookup(lookup(" Sliding ")),
+            Ok(Algorithm::Sliding)
+        );
+        assert!(Algorithm::from_lookup(lookup("leaky")).is_err());
+    }
+
+    #[tokio::test]
+    async fn forwarded_for_header_does_not_grant_allowlisting() {
+        let limiter = RateLimitState {
+            allowlist: parse_allowlist("10.0.0.0/8").unwrap().into(),
+            ..RateLimitState::new(RateLimitConfig::for_tests(
+                1,
+                1,
+                10_000,
+                Duration::from_secs(60),
+            ))
+        };
+        let app = app_with_limiter(limiter);
+        let request = || {
+            Request::builder()
+                .uri("/read")
+                .method("GET")
+                .header("x-forwarded-for", "10.0.0.1")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        assert_eq!(call(&app, request()).await.status(), StatusCode::OK);
+        assert_eq!(
+            call(&app, request()).await.status(),
+            StatusCode::TOO_MANY_REQUESTS
+        );
+    }
+
+    #[test]
+    fn config_problems_lists_every_bad_setting() {
+        let env: BTreeMap<String, String> = [
+            ("RATE_LIMIT_READ_PER_MINUTE", "0"),
+            ("RATE_LIMIT_WRITE_PER_MINUTE", "50"),
+            ("RATE_LIMIT_WINDOW_SECONDS", "1m"),
+            ("RATE_LIMIT_ENDPOINT_GET_API_STATS", "many"),
+            (
+                "RATE_LIMIT_OVERRIDES",
+                "POST /api/contracts=5/60, GET api=1/1",
+            ),
+            ("RATE_LIMIT_BACKEND", "redis"),
+            ("RATE_LIMIT_ALLOWLIST", "10.0.0.0/8,10.0.0.300"),
+        ]
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+
+        let problems = config_problems(&env);
+        assert_eq!(problems.len(), 6, "{:?}", problems);
+        assert!(problems[0].starts_with("RATE_LIMIT_READ_PER_MINUTE"));
+        assert!(problems.iter().any(|p| p.contains("`10.0.0.300`")));
+        assert!(problems.iter().any(|p| p.contains("`GET api=1/1`")));
+        assert!(problems.iter().any(|p| p.contains(REDIS_URL_ENV)));
+        assert!(config_problems(&BTreeMap::new()).is_empty());
+    }
+
     #[tokio::test]
     async fn health_checks_have_high_dedicated_limit() {
         let app = test_app(1, 1, 10, Duration::from_secs(60));
@@ -604,4 +1650,49 @@ mod tests {
 
         assert_eq!(limited.status(), StatusCode::TOO_MANY_REQUESTS);
     }
+
+    #[test]
+    fn backend_defaults_to_memory_and_validates_redis_config() {
+        let backend = Backend::from_lookup(|_| None).unwrap();
+        assert_eq!(backend.name(), "memory");
+
+        let redis = Backend::from_lookup(|name| match name {
+            BACKEND_ENV => Some("Redis".into()),
+            REDIS_URL_ENV => Some("redis://127.0.0.1:6379".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(redis.name(), "redis");
+
+        assert!(Backend::from_lookup(|name| (name == BACKEND_ENV).then(|| "redis".into())).is_err());
+        assert!(Backend::from_lookup(|name| (name == BACKEND_ENV).then(|| "memcached".into())).is_err());
+    }
+
+    #[tokio::test]
+    async fn unreachable_redis_fails_open() {
+        // Nothing listens on port 1, so every Redis call fails
+        let limiter = RateLimitState {
+            config: Arc::new(RateLimitConfig::for_tests(1, 1, 10_000, Duration::from_secs(60))),
+            backend: Backend::Redis(Arc::new(RedisBackend::new("redis://127.0.0.1:1").unwrap())),
+            algorithm: Algorithm::Fixed,
+            allowlist: Arc::from([]),
+        };
+        let app = Router::new()
+            .route("/read", get(|| async { "read" }))
+            .layer(middleware::from_fn_with_state(limiter, rate_limit_middleware));
+
+        for _ in 0..3 {
+            let response = call(
+                &app,
+                Request::builder()
+                    .uri("/read")
+                    .header("x-forwarded-for", "192.0.2.5")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[HEADER_RATE_LIMIT_REMAINING], "1");
+        }
+    }
 }
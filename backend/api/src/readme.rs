@@ -0,0 +1,95 @@
+// readme.rs
+// Contract README storage and rendering.
+//
+// Markdown is stored exactly as published. HTML is rendered on request and
+// always passed through an allowlist sanitizer, so raw HTML embedded in a
+// README (scripts, event handlers, `javascript:` links) never reaches the
+// frontend.
+
+use pulldown_cmark::{html, Options, Parser};
+use uuid::Uuid;
+
+/// Largest README accepted on publish (256 KiB of markdown)
+pub const MAX_README_BYTES: usize = 256 * 1024;
+
+/// Render markdown to HTML and strip anything outside the sanitizer's
+/// allowlist
+pub fn render_html(markdown: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+    let mut unsafe_html = String::with_capacity(markdown.len() * 3 / 2);
+    html::push_html(&mut unsafe_html, Parser::new_ext(markdown, options));
+    sanitize(&unsafe_html)
+}
+
+fn sanitize(unsafe_html: &str) -> String {
+    ammonia::Builder::default()
+        .link_rel(Some("nofollow noopener noreferrer"))
+        .url_schemes(["http", "https", "mailto"].into_iter().collect())
+        .clean(unsafe_html)
+        .to_string()
+}
+
+/// The stored README for a contract, if it has one
+pub async fn load(pool: &sqlx::PgPool, contract_id: Uuid) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar("SELECT markdown FROM contract_readmes WHERE contract_id = $1")
+        .bind(contract_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// Replace the contract's README; publishing without one leaves it unchanged
+pub async fn store<'e, E>(executor: E, contract_id: Uuid, markdown: &str) -> Result<(), sqlx::Error>
+where
+    E: sqlx::PgExecutor<'e>,
+{
+    sqlx::query(
+        "INSERT INTO contract_readmes (contract_id, markdown)
+         VALUES ($1, $2)
+         ON CONFLICT (contract_id) DO UPDATE
+         SET markdown = EXCLUDED.markdown, updated_at = NOW()",
+    )
+    .bind(contract_id)
+    .bind(markdown)
+    .execute(executor)
+    .await
+    .map(|_| ())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_markdown() {
+        let html = render_html("# Token\n\nA **fungible** token.\n\n| a | b |\n|---|---|\n| 1 | 2 |\n");
+        assert!(html.contains("<h1>Token</h1>"));
+        assert!(html.contains("<strong>fungible</strong>"));
+        assert!(html.contains("<table>"));
+    }
+
+    #[test]
+    fn strips_scripts_and_event_handlers() {
+        let html = render_html(
+            "Hello <script>alert(1)</script>\n\n<img src=\"https://x.test/a.png\" onerror=\"alert(2)\">\n\n<iframe src=\"https://evil.test\"></iframe>\n",
+        );
+        assert!(!html.contains("<script"));
+        assert!(!html.contains("alert(1)"));
+        assert!(!html.contains("onerror"));
+        assert!(!html.contains("<iframe"));
+        assert!(html.contains("<img src=\"https://x.test/a.png\">"));
+    }
+
+    #[test]
+    fn drops_javascript_links() {
+        let html = render_html("[click](javascript:alert(1)) and <a href=\"data:text/html,x\">data</a>");
+        assert!(!html.contains("javascript:"));
+        assert!(!html.contains("data:text/html"));
+
+        let html = render_html("[docs](https://example.com)");
+        assert!(html.contains("href=\"https://example.com\""));
+        assert!(html.contains("rel=\"nofollow noopener noreferrer\""));
+    }
+}
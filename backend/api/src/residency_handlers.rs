@@ -7,7 +7,8 @@ use serde::Deserialize;
 use uuid::Uuid;
 
 use crate::{
-    error::{ApiError, ApiResult},
+    error::{ApiError, ApiResult, ErrorCode},
+    pagination::{PageParams, Paginated},
     state::AppState,
 };
 use shared::models::{
@@ -22,7 +23,7 @@ fn db_err(ctx: &str, err: sqlx::Error) -> ApiError {
 }
 
 fn not_found(id: Uuid) -> ApiError {
-    ApiError::not_found("PolicyNotFound", format!("No residency policy found with ID: {}", id))
+    ApiError::not_found(ErrorCode::PolicyNotFound, format!("No residency policy found with ID: {}", id))
 }
 
 async fn fetch_policy(state: &AppState, id: Uuid) -> ApiResult<ResidencyPolicy> {
@@ -41,13 +42,13 @@ pub async fn create_policy(
     Json(req): Json<CreateResidencyPolicyRequest>,
 ) -> ApiResult<(StatusCode, Json<ResidencyPolicy>)> {
     if req.contract_id.is_empty() {
-        return Err(ApiError::bad_request("MissingContractId", "contract_id is required"));
+        return Err(ApiError::bad_request(ErrorCode::MissingContractId, "contract_id is required"));
     }
     if req.allowed_regions.is_empty() {
-        return Err(ApiError::bad_request("MissingRegions", "allowed_regions must not be empty"));
+        return Err(ApiError::bad_request(ErrorCode::MissingRegions, "allowed_regions must not be empty"));
     }
     if req.created_by.is_empty() {
-        return Err(ApiError::bad_request("MissingCreatedBy", "created_by is required"));
+        return Err(ApiError::bad_request(ErrorCode::MissingCreatedBy, "created_by is required"));
     }
 
     let policy: ResidencyPolicy = sqlx::query_as(
@@ -76,14 +77,30 @@ pub async fn get_policy(
 
 pub async fn list_policies(
     State(state): State<AppState>,
-) -> ApiResult<Json<Vec<ResidencyPolicy>>> {
-    let policies: Vec<ResidencyPolicy> =
-        sqlx::query_as("SELECT * FROM residency_policies WHERE is_active = TRUE ORDER BY created_at DESC")
-            .fetch_all(&state.db)
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<ResidencyPolicy>>> {
+    let policies: Vec<ResidencyPolicy> = sqlx::query_as(
+        "SELECT * FROM residency_policies WHERE is_active = TRUE
+         ORDER BY created_at DESC, id DESC
+         LIMIT $1 OFFSET $2",
+    )
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_err("list residency policies", e))?;
+    let total: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM residency_policies WHERE is_active = TRUE")
+            .fetch_one(&state.db)
             .await
-            .map_err(|e| db_err("list residency policies", e))?;
-
-    Ok(Json(policies))
+            .map_err(|e| db_err("count residency policies", e))?;
+
+    Ok(Json(Paginated::new(
+        policies,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
 }
 
 pub async fn update_policy(
@@ -93,7 +110,7 @@ pub async fn update_policy(
 ) -> ApiResult<Json<ResidencyPolicy>> {
     if let Some(ref regions) = req.allowed_regions {
         if regions.is_empty() {
-            return Err(ApiError::bad_request("MissingRegions", "allowed_regions must not be empty"));
+            return Err(ApiError::bad_request(ErrorCode::MissingRegions, "allowed_regions must not be empty"));
         }
     }
 
@@ -129,7 +146,7 @@ pub async fn check_residency(
     let policy = fetch_policy(&state, req.policy_id).await?;
 
     if !policy.is_active {
-        return Err(ApiError::bad_request("PolicyInactive", "The referenced residency policy is not active"));
+        return Err(ApiError::bad_request(ErrorCode::PolicyInactive, "The referenced residency policy is not active"));
     }
 
     let is_allowed = policy.allowed_regions.iter().any(|r| r.eq_ignore_ascii_case(&req.requested_region));
@@ -1,9 +1,75 @@
 use axum::{
-    routing::{get, post, put},
+    extract::DefaultBodyLimit,
+    middleware,
+    routing::{delete, get, post, put},
     Router,
 };
 
-use crate::{handlers, metrics_handler, state::AppState};
+use crate::{
+    abi, abi_compat, admin_audit, artifacts, audit_routes, auth, badge, benchmark_handlers, benchmark_routes, build_verification, config_handlers, config_routes, contract_history_handlers, contract_history_routes, deployment_handlers,
+    export_handlers, feed, handlers, import_handlers, metrics_handler, multisig_routes, observability,
+    organization_handlers, residency_routes, scan_handlers, scan_routes, share_tokens, state::AppState, template_routes, transfer_handlers,
+    type_safety_routes, webhook_routes,
+};
+
+/// Every API route, with the authentication layers each group needs. The
+/// server adds the metrics, rate-limit, CORS and request-id layers on top.
+pub fn api_routes(state: &AppState) -> Router<AppState> {
+    Router::new()
+        .merge(contract_routes().route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::optional_api_key,
+        )))
+        .merge(publisher_routes().route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::optional_api_key,
+        )))
+        .merge(
+            authenticated_routes()
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    auth::resolve_contract_role,
+                ))
+                .route_layer(middleware::from_fn_with_state(
+                    state.clone(),
+                    auth::require_api_key,
+                )),
+        )
+        .merge(webhook_routes::webhook_routes().route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::require_api_key,
+        )))
+        .merge(health_routes())
+        .merge(migration_routes())
+        .merge(canary_routes())
+        .merge(ab_test_routes())
+        .merge(performance_routes())
+        .merge(multisig_routes::multisig_routes())
+        .merge(audit_routes::security_audit_routes().route_layer(
+            middleware::from_fn_with_state(state.clone(), auth::optional_api_key),
+        ))
+        .merge(benchmark_routes::benchmark_routes())
+        .merge(config_routes::config_routes())
+        .merge(contract_history_routes::contract_history_routes().route_layer(
+            middleware::from_fn_with_state(state.clone(), auth::optional_api_key),
+        ))
+        .merge(template_routes::template_routes().route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::optional_api_key,
+        )))
+        .merge(template_routes::template_owner_routes().route_layer(
+            middleware::from_fn_with_state(state.clone(), auth::require_api_key),
+        ))
+        .merge(scan_routes::scan_routes().route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            auth::optional_api_key,
+        )))
+        .merge(residency_routes::residency_routes())
+        .merge(type_safety_routes::type_safety_routes().route_layer(
+            middleware::from_fn_with_state(state.clone(), auth::optional_api_key),
+        ))
+        .fallback(handlers::route_not_found)
+}
 
 pub fn observability_routes() -> Router<AppState> {
     Router::new().route("/metrics", get(metrics_handler::metrics_endpoint))
@@ -13,20 +79,73 @@ pub fn contract_routes() -> Router<AppState> {
     Router::new()
         .route("/api/contracts", get(handlers::list_contracts))
         .route("/api/contracts/graph", get(handlers::get_contract_graph))
-        .route("/api/contracts", post(handlers::publish_contract))
+        .route("/api/feed.atom", get(feed::atom_feed))
         .route(
             "/api/contracts/trending",
             get(handlers::get_trending_contracts),
         )
+        .route("/api/contracts/compare", get(handlers::compare_contracts))
+        .route("/api/contracts/suggest", get(handlers::suggest_contracts))
+        .route(
+            "/api/contracts/search/by-function",
+            get(abi::search_by_function),
+        )
         .route("/api/contracts/:id", get(handlers::get_contract))
         .route("/api/contracts/:id/abi", get(handlers::get_contract_abi))
+        .route("/api/contracts/:id/readme", get(handlers::get_contract_readme))
+        .route(
+            "/api/contracts/:id/changelog",
+            get(handlers::get_contract_changelog),
+        )
+        .route(
+            "/api/contracts/:id/badge/version.svg",
+            get(badge::version_badge_svg),
+        )
+        .route(
+            "/api/contracts/:id/badge/score.svg",
+            get(badge::score_badge_svg),
+        )
+        .route("/api/contracts/:id/badge.json", get(badge::badge_json))
+        .route(
+            "/api/contracts/:id/score-history",
+            get(handlers::get_score_history),
+        )
         .route(
             "/api/contracts/:id/versions",
             get(handlers::get_contract_versions),
         )
+        .route(
+            "/api/contracts/:id/versions/resolve",
+            get(handlers::resolve_contract_version),
+        )
+        .route("/api/tags", get(handlers::list_tags))
+        .route(
+            "/api/contracts/:id/versions/:version/download",
+            get(artifacts::download_version_wasm),
+        )
+        .route(
+            "/api/contracts/:id/versions/:version/abi",
+            get(abi::get_version_abi),
+        )
+        .route(
+            "/api/contracts/:id/abi-compat",
+            get(abi_compat::get_abi_compat),
+        )
+        .route(
+            "/api/contracts/:id/versions/:version/abi-diff",
+            get(abi_compat::get_abi_diff),
+        )
+        .route(
+            "/api/contracts/:id/versions/:version/sbom",
+            get(handlers::get_version_sbom),
+        )
         .route(
             "/api/contracts/:id/analytics",
             get(handlers::get_contract_analytics),
+        )
+        .route(
+            "/api/analytics/timeseries",
+            get(handlers::get_analytics_timeseries),
         )
 		  .route("/api/contracts/:id/trust-score", get(handlers::get_trust_score))
         .route(
@@ -39,6 +158,10 @@ pub fn contract_routes() -> Router<AppState> {
         )
         )
         .route("/api/contracts/verify", post(handlers::verify_contract))
+        .route(
+            "/api/contracts/:id/deployments",
+            get(deployment_handlers::list_deployments),
+        )
         .route(
             "/api/contracts/:id/deployments/status",
             get(handlers::get_deployment_status),
@@ -66,18 +189,174 @@ pub fn contract_routes() -> Router<AppState> {
 /// Publisher-related routes
 pub fn publisher_routes() -> Router<AppState> {
     Router::new()
-        .route("/api/publishers", post(handlers::create_publisher))
         .route("/api/publishers/:id", get(handlers::get_publisher))
         .route(
             "/api/publishers/:id/contracts",
             get(handlers::get_publisher_contracts),
         )
+        .route(
+            "/api/organizations/:id",
+            get(organization_handlers::get_organization),
+        )
+        .route(
+            "/api/organizations/:id/contracts",
+            get(organization_handlers::get_organization_contracts),
+        )
+}
+
+/// Write routes that require `Authorization: Bearer <api key>`.
+///
+/// The caller is expected to wrap these in `auth::require_api_key`, and inside
+/// it `auth::resolve_contract_role` for the `/api/contracts/:id` routes.
+pub fn authenticated_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/contracts",
+            // MAX_ARTIFACT_BYTES replaces axum's default body limit here
+            post(handlers::publish_contract)
+                .layer(middleware::from_fn(artifacts::limit_artifact_upload))
+                .layer(DefaultBodyLimit::disable()),
+        )
+        .route("/api/publishers", post(handlers::create_publisher))
+        .route(
+            "/api/organizations",
+            post(organization_handlers::create_organization),
+        )
+        .route(
+            "/api/organizations/:id/members",
+            post(organization_handlers::set_organization_member),
+        )
+        .route(
+            "/api/organizations/:id/members/:publisher_id",
+            delete(organization_handlers::remove_organization_member),
+        )
+        .route("/api/publishers/:id", put(handlers::update_publisher))
+        .route(
+            "/api/publishers/:id/deactivate",
+            post(handlers::deactivate_publisher),
+        )
+        .route(
+            "/api/publishers/:id/reactivate",
+            post(handlers::reactivate_publisher),
+        )
+        .route("/api/publishers/:id/keys", post(auth::create_api_key))
+        .route(
+            "/api/contracts/:id/star",
+            post(handlers::star_contract).delete(handlers::unstar_contract),
+        )
+        .route(
+            "/api/config/scoring-weights",
+            get(config_handlers::get_scoring_weights).put(config_handlers::put_scoring_weights),
+        )
+        .route(
+            "/api/config/scoring-weights/recompute",
+            post(config_handlers::recompute_scores),
+        )
+        .route("/api/admin/aggregate", post(handlers::trigger_aggregation))
+        .route("/api/admin/log-level", post(observability::set_log_level))
+        .route("/api/admin/audit-log", get(admin_audit::list_audit_log))
+        .route("/api/export", get(export_handlers::export_catalog))
+        .route(
+            "/api/import",
+            // Imports are whole catalogs, read line by line
+            post(import_handlers::import_catalog).layer(DefaultBodyLimit::disable()),
+        )
+        .route(
+            "/api/contracts/:id/versions/:version/yank",
+            post(handlers::yank_contract_version),
+        )
+        .route(
+            "/api/contracts/:id/versions/:version/unyank",
+            post(handlers::unyank_contract_version),
+        )
+        .route(
+            "/api/contracts/:id/versions/:version/deprecate",
+            post(handlers::deprecate_contract_version),
+        )
+        .route(
+            "/api/contracts/:id/versions/:version/verify-build",
+            post(build_verification::verify_build),
+        )
+        .route(
+            "/api/contracts/:id/suppressions",
+            post(scan_handlers::create_suppression),
+        )
+        .route(
+            "/api/contracts/:id/suppressions/:suppression_id",
+            delete(scan_handlers::delete_suppression),
+        )
+        .route(
+            "/api/contracts/:id/visibility",
+            put(handlers::set_contract_visibility),
+        )
+        .route(
+            "/api/contracts/:id/share-tokens",
+            get(share_tokens::list_share_tokens).post(share_tokens::create_share_token),
+        )
+        .route(
+            "/api/contracts/:id/share-tokens/:token_id",
+            delete(share_tokens::revoke_share_token),
+        )
+        .route(
+            "/api/contracts/:id/transfer",
+            post(transfer_handlers::initiate_transfer),
+        )
+        .route(
+            "/api/contracts/:id/transfer/accept",
+            post(transfer_handlers::accept_transfer),
+        )
+        .route("/api/transfers", get(transfer_handlers::list_transfers))
+        .route(
+            "/api/contracts/:id/deployments",
+            post(deployment_handlers::record_deployment),
+        )
+        .route(
+            "/api/contracts/:id/benchmark-thresholds",
+            put(config_handlers::put_benchmark_thresholds),
+        )
+        .route(
+            "/api/contracts/:id/benchmarks/baseline",
+            post(benchmark_handlers::set_benchmark_baseline)
+                .delete(benchmark_handlers::clear_benchmark_baseline),
+        )
+        .route(
+            "/api/contracts/:id/history/:entry_id/revert",
+            post(contract_history_handlers::revert_to_history_entry),
+        )
+        .route(
+            "/api/config/metadata-schemas",
+            get(config_handlers::list_metadata_schemas),
+        )
+        .route(
+            "/api/config/metadata-schemas/:category",
+            get(config_handlers::get_metadata_schema)
+                .put(config_handlers::put_metadata_schema)
+                .delete(config_handlers::delete_metadata_schema),
+        )
+        .route(
+            "/api/config/detector-rules",
+            get(config_handlers::list_detector_rules).post(config_handlers::create_detector_rule),
+        )
+        .route(
+            "/api/config/detector-rules/:id",
+            delete(config_handlers::delete_detector_rule),
+        )
+        .route(
+            "/api/config/dependency-advisories",
+            get(config_handlers::list_dependency_advisories)
+                .post(config_handlers::create_dependency_advisory),
+        )
+        .route(
+            "/api/config/dependency-advisories/:id",
+            delete(config_handlers::delete_dependency_advisory),
+        )
 }
 
 /// Health check routes
 pub fn health_routes() -> Router<AppState> {
     Router::new()
         .route("/health", get(handlers::health_check))
+        .route("/health/ready", get(handlers::health_ready))
         .route("/api/stats", get(handlers::get_stats))
         .route("/api/cache/stats", get(handlers::get_cache_stats))
 }
@@ -0,0 +1,262 @@
+// sarif.rs
+// SARIF 2.1.0 export of scan findings for code-scanning dashboards.
+//
+// Only the subset of the spec we emit is modelled; field names follow the
+// schema (camelCase) so the structs serialize straight to a valid log.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::checklist::all_checks;
+use crate::models::Severity;
+use crate::scanner_service::ScanFinding;
+
+pub const SARIF_VERSION: &str = "2.1.0";
+pub const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+pub const SARIF_CONTENT_TYPE: &str = "application/sarif+json";
+
+/// Artifact the detector scanned; submitted source is treated as the crate root
+const SOURCE_URI: &str = "src/lib.rs";
+
+#[derive(Debug, Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    pub schema: &'static str,
+    pub version: &'static str,
+    pub runs: Vec<Run>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Run {
+    pub tool: Tool,
+    pub results: Vec<SarifResult>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tool {
+    pub driver: Driver,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Driver {
+    pub name: &'static str,
+    pub version: &'static str,
+    pub information_uri: &'static str,
+    pub rules: Vec<ReportingDescriptor>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportingDescriptor {
+    pub id: String,
+    pub short_description: Message,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub help: Option<Message>,
+    pub default_configuration: ReportingConfiguration,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReportingConfiguration {
+    pub level: Level,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Message {
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Level {
+    Error,
+    Warning,
+    Note,
+}
+
+impl From<&Severity> for Level {
+    fn from(severity: &Severity) -> Self {
+        match severity {
+            Severity::Critical | Severity::High => Level::Error,
+            Severity::Medium => Level::Warning,
+            Severity::Low | Severity::Info => Level::Note,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SarifResult {
+    pub rule_id: String,
+    pub rule_index: usize,
+    pub level: Level,
+    pub message: Message,
+    pub locations: Vec<Location>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Location {
+    pub physical_location: PhysicalLocation,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PhysicalLocation {
+    pub artifact_location: ArtifactLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<Region>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArtifactLocation {
+    pub uri: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Region {
+    pub start_line: usize,
+}
+
+/// Build a single-run SARIF log from detector findings
+pub fn to_sarif(findings: &[ScanFinding]) -> SarifLog {
+    let checks: BTreeMap<String, _> = all_checks()
+        .into_iter()
+        .map(|c| (c.id.to_string(), c))
+        .collect();
+
+    let mut rules: Vec<ReportingDescriptor> = Vec::new();
+    let mut rule_index: BTreeMap<&str, usize> = BTreeMap::new();
+    let mut results = Vec::with_capacity(findings.len());
+
+    for finding in findings {
+        let index = *rule_index.entry(finding.rule_id.as_str()).or_insert_with(|| {
+            let check = checks.get(finding.rule_id.as_str());
+            rules.push(ReportingDescriptor {
+                id: finding.rule_id.clone(),
+                short_description: Message {
+                    text: check
+                        .map(|c| c.title.to_string())
+                        .unwrap_or_else(|| finding.message.clone()),
+                },
+                help: check
+                    .filter(|c| !c.remediation.is_empty())
+                    .map(|c| Message {
+                        text: c.remediation.to_string(),
+                    }),
+                default_configuration: ReportingConfiguration {
+                    level: Level::from(&finding.severity),
+                },
+            });
+            rules.len() - 1
+        });
+
+        results.push(SarifResult {
+            rule_id: finding.rule_id.clone(),
+            rule_index: index,
+            level: Level::from(&finding.severity),
+            message: Message {
+                text: finding.message.clone(),
+            },
+            locations: vec![physical_location(&finding.location)],
+        });
+    }
+
+    SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![Run {
+            tool: Tool {
+                driver: Driver {
+                    name: "soroban-registry-scanner",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: "https://github.com/Macnelson9/Soroban-Registry",
+                    rules,
+                },
+            },
+            results,
+        }],
+    }
+}
+
+/// Finding locations are `line N`, `path:N`, or a bare artifact name
+fn physical_location(location: &str) -> Location {
+    let (uri, start_line) = if let Some(line) = location.strip_prefix("line ") {
+        (SOURCE_URI.to_string(), line.trim().parse().ok())
+    } else if let Some((path, line)) = location.rsplit_once(':') {
+        match line.trim().parse() {
+            Ok(line) => (path.to_string(), Some(line)),
+            Err(_) => (location.to_string(), None),
+        }
+    } else if location == "source" {
+        (SOURCE_URI.to_string(), None)
+    } else {
+        (location.to_string(), None)
+    };
+
+    Location {
+        physical_location: PhysicalLocation {
+            artifact_location: ArtifactLocation { uri },
+            region: start_line.map(|start_line| Region { start_line }),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(rule: &str, location: &str, severity: Severity) -> ScanFinding {
+        ScanFinding {
+            rule_id: rule.to_string(),
+            severity,
+            location: location.to_string(),
+            message: "message".to_string(),
+        }
+    }
+
+    #[test]
+    fn emits_required_top_level_keys() {
+        let log = to_sarif(&[
+            finding("IV-001", "line 2", Severity::Critical),
+            finding("IV-001", "line 9", Severity::Critical),
+            finding("EL-001", "source", Severity::Low),
+        ]);
+        let json = serde_json::to_value(&log).unwrap();
+
+        assert_eq!(json["version"], "2.1.0");
+        assert!(json["$schema"].as_str().unwrap().contains("sarif-2.1.0"));
+        let run = &json["runs"][0];
+        assert!(run["tool"]["driver"]["name"].is_string());
+
+        let rules = run["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), 2);
+        assert_eq!(rules[0]["id"], "IV-001");
+
+        let results = run["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[1]["ruleIndex"], 0);
+        assert_eq!(results[1]["level"], "error");
+        assert_eq!(
+            results[1]["locations"][0]["physicalLocation"]["region"]["startLine"],
+            9
+        );
+        assert_eq!(results[2]["level"], "note");
+        assert!(results[2]["locations"][0]["physicalLocation"]
+            .get("region")
+            .is_none());
+    }
+
+    #[test]
+    fn parses_path_and_line_locations() {
+        let location = physical_location("src/token.rs:42");
+        assert_eq!(location.physical_location.artifact_location.uri, "src/token.rs");
+        assert_eq!(location.physical_location.region.unwrap().start_line, 42);
+
+        let location = physical_location("soroban-sdk@20.0.0");
+        assert_eq!(location.physical_location.artifact_location.uri, "soroban-sdk@20.0.0");
+        assert!(location.physical_location.region.is_none());
+    }
+}
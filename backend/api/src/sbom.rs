@@ -0,0 +1,388 @@
+// sbom.rs
+// CycloneDX software bill of materials for a contract version.
+//
+// The version is the root component; each declared dependency is listed as
+// a library, resolved to the highest registered version matching its range
+// where possible. Unresolved dependencies keep their name and range but
+// carry no version or hash.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+use shared::{ContractDependency, ContractVersion};
+
+pub const BOM_FORMAT: &str = "CycloneDX";
+pub const SPEC_VERSION: &str = "1.5";
+pub const CYCLONEDX_CONTENT_TYPE: &str = "application/vnd.cyclonedx+json; version=1.5";
+
+/// Property names under our own namespace, per the CycloneDX taxonomy rules
+const PROP_CONTRACT_ID: &str = "soroban-registry:contract_id";
+const PROP_NETWORK: &str = "soroban-registry:network";
+const PROP_CONSTRAINT: &str = "soroban-registry:version_constraint";
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Bom {
+    pub bom_format: &'static str,
+    pub spec_version: &'static str,
+    pub serial_number: String,
+    pub version: u32,
+    pub metadata: BomMetadata,
+    pub components: Vec<Component>,
+    pub dependencies: Vec<Dependency>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BomMetadata {
+    pub timestamp: DateTime<Utc>,
+    pub tools: Tools,
+    pub component: Component,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Tools {
+    pub components: Vec<Component>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ComponentType {
+    Application,
+    Library,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Component {
+    #[serde(rename = "type")]
+    pub kind: ComponentType,
+    #[serde(rename = "bom-ref", skip_serializing_if = "Option::is_none")]
+    pub bom_ref: Option<String>,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hashes: Vec<Hash>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub properties: Vec<Property>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Hash {
+    pub alg: &'static str,
+    pub content: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct Property {
+    pub name: &'static str,
+    pub value: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Dependency {
+    #[serde(rename = "ref")]
+    pub reference: String,
+    pub depends_on: Vec<String>,
+}
+
+/// The contract version an SBOM is generated for
+pub struct RootInfo<'a> {
+    pub contract_id: Uuid,
+    pub name: &'a str,
+    pub network: &'a str,
+    pub version: &'a ContractVersion,
+}
+
+/// A declared dependency and, when it is in the registry and its range
+/// matched, the version it resolves to
+pub struct ResolvedDependency<'a> {
+    pub declaration: &'a ContractDependency,
+    pub resolved: Option<(Uuid, &'a ContractVersion)>,
+}
+
+/// `wasm_hash` is hex SHA-256 once an artifact was uploaded; anything else
+/// (e.g. a placeholder) is left out of the SBOM
+fn sha256_hashes(wasm_hash: &str) -> Vec<Hash> {
+    if wasm_hash.len() == 64 && wasm_hash.bytes().all(|b| b.is_ascii_hexdigit()) {
+        vec![Hash {
+            alg: "SHA-256",
+            content: wasm_hash.to_ascii_lowercase(),
+        }]
+    } else {
+        Vec::new()
+    }
+}
+
+fn bom_ref(contract_id: Uuid, version: &str) -> String {
+    format!("{}@{}", contract_id, version)
+}
+
+pub fn build(root: &RootInfo<'_>, dependencies: &[ResolvedDependency<'_>]) -> Bom {
+    let root_ref = bom_ref(root.contract_id, &root.version.version);
+
+    let components: Vec<Component> = dependencies
+        .iter()
+        .map(|dep| {
+            let mut properties = vec![Property {
+                name: PROP_CONSTRAINT,
+                value: dep.declaration.version_constraint.clone(),
+            }];
+            match dep.resolved {
+                Some((contract_id, version)) => {
+                    properties.push(Property {
+                        name: PROP_CONTRACT_ID,
+                        value: contract_id.to_string(),
+                    });
+                    Component {
+                        kind: ComponentType::Library,
+                        bom_ref: Some(bom_ref(contract_id, &version.version)),
+                        name: dep.declaration.dependency_name.clone(),
+                        version: Some(version.version.clone()),
+                        hashes: sha256_hashes(&version.wasm_hash),
+                        properties,
+                    }
+                }
+                None => Component {
+                    kind: ComponentType::Library,
+                    bom_ref: Some(format!("unresolved:{}", dep.declaration.dependency_name)),
+                    name: dep.declaration.dependency_name.clone(),
+                    version: None,
+                    hashes: Vec::new(),
+                    properties,
+                },
+            }
+        })
+        .collect();
+
+    let mut graph = vec![Dependency {
+        reference: root_ref.clone(),
+        depends_on: components.iter().filter_map(|c| c.bom_ref.clone()).collect(),
+    }];
+    graph.extend(components.iter().filter_map(|c| {
+        c.bom_ref.clone().map(|reference| Dependency {
+            reference,
+            depends_on: Vec::new(),
+        })
+    }));
+
+    Bom {
+        bom_format: BOM_FORMAT,
+        spec_version: SPEC_VERSION,
+        serial_number: format!("urn:uuid:{}", Uuid::new_v4()),
+        version: 1,
+        metadata: BomMetadata {
+            timestamp: Utc::now(),
+            tools: Tools {
+                components: vec![Component {
+                    kind: ComponentType::Application,
+                    bom_ref: None,
+                    name: "soroban-registry".to_string(),
+                    version: Some(env!("CARGO_PKG_VERSION").to_string()),
+                    hashes: Vec::new(),
+                    properties: Vec::new(),
+                }],
+            },
+            component: Component {
+                kind: ComponentType::Application,
+                bom_ref: Some(root_ref),
+                name: root.name.to_string(),
+                version: Some(root.version.version.clone()),
+                hashes: sha256_hashes(&root.version.wasm_hash),
+                properties: vec![
+                    Property {
+                        name: PROP_CONTRACT_ID,
+                        value: root.contract_id.to_string(),
+                    },
+                    Property {
+                        name: PROP_NETWORK,
+                        value: root.network.to_string(),
+                    },
+                ],
+            },
+        },
+        components,
+        dependencies: graph,
+    }
+}
+
+/// SBOM for `version` of a contract; `None` when the contract or version
+/// doesn't exist
+pub async fn for_version(
+    pool: &PgPool,
+    contract_id: Uuid,
+    version: &str,
+) -> Result<Option<Bom>, sqlx::Error> {
+    let contract: Option<(String, String)> =
+        sqlx::query_as("SELECT name, network::text FROM contracts WHERE id = $1")
+            .bind(contract_id)
+            .fetch_optional(pool)
+            .await?;
+    let Some((name, network)) = contract else {
+        return Ok(None);
+    };
+    let root_version: Option<ContractVersion> =
+        sqlx::query_as("SELECT * FROM contract_versions WHERE contract_id = $1 AND version = $2")
+            .bind(contract_id)
+            .bind(version)
+            .fetch_optional(pool)
+            .await?;
+    let Some(root_version) = root_version else {
+        return Ok(None);
+    };
+
+    let declarations: Vec<ContractDependency> = sqlx::query_as(
+        "SELECT * FROM contract_dependencies WHERE contract_id = $1 ORDER BY dependency_name",
+    )
+    .bind(contract_id)
+    .fetch_all(pool)
+    .await?;
+
+    let mut candidates: Vec<(Uuid, Vec<ContractVersion>)> = Vec::new();
+    for dep_id in declarations.iter().filter_map(|d| d.dependency_contract_id) {
+        if candidates.iter().any(|(id, _)| *id == dep_id) {
+            continue;
+        }
+        let versions = sqlx::query_as("SELECT * FROM contract_versions WHERE contract_id = $1")
+            .bind(dep_id)
+            .fetch_all(pool)
+            .await?;
+        candidates.push((dep_id, versions));
+    }
+
+    let resolved: Vec<ResolvedDependency<'_>> = declarations
+        .iter()
+        .map(|declaration| {
+            let resolved = declaration.dependency_contract_id.and_then(|dep_id| {
+                let req = semver::VersionReq::parse(declaration.version_constraint.trim()).ok()?;
+                let (_, versions) = candidates.iter().find(|(id, _)| *id == dep_id)?;
+                crate::handlers::highest_matching_version(&req, versions).map(|v| (dep_id, v))
+            });
+            ResolvedDependency {
+                declaration,
+                resolved,
+            }
+        })
+        .collect();
+
+    Ok(Some(build(
+        &RootInfo {
+            contract_id,
+            name: &name,
+            network: &network,
+            version: &root_version,
+        },
+        &resolved,
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HASH: &str = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08";
+
+    fn version(v: &str, wasm_hash: &str) -> ContractVersion {
+        ContractVersion {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::nil(),
+            version: v.to_string(),
+            wasm_hash: wasm_hash.to_string(),
+            source_url: None,
+            commit_hash: None,
+            release_notes: None,
+            created_at: Utc::now(),
+            deprecated: false,
+            deprecated_at: None,
+            deprecation_reason: None,
+            superseded_by: None,
+            ipfs_cid: None,
+            ipfs_status: None,
+            yanked: false,
+            yanked_at: None,
+            download_count: 0,
+            signed: false,
+            signing_key: None,
+            build_info: None,
+            build_verification: None,
+        }
+    }
+
+    fn declaration(name: &str, constraint: &str) -> ContractDependency {
+        ContractDependency {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::nil(),
+            dependency_name: name.to_string(),
+            dependency_contract_id: None,
+            version_constraint: constraint.to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn version_without_dependencies_is_just_the_root() {
+        let v = version("1.2.0", HASH);
+        let root = RootInfo {
+            contract_id: Uuid::new_v4(),
+            name: "token",
+            network: "testnet",
+            version: &v,
+        };
+        let json = serde_json::to_value(build(&root, &[])).unwrap();
+
+        assert_eq!(json["bomFormat"], "CycloneDX");
+        assert_eq!(json["specVersion"], "1.5");
+        assert!(json["serialNumber"].as_str().unwrap().starts_with("urn:uuid:"));
+        let component = &json["metadata"]["component"];
+        assert_eq!(component["name"], "token");
+        assert_eq!(component["version"], "1.2.0");
+        assert_eq!(component["hashes"][0]["alg"], "SHA-256");
+        assert_eq!(component["hashes"][0]["content"], HASH);
+        assert_eq!(json["components"].as_array().unwrap().len(), 0);
+        assert_eq!(json["dependencies"][0]["dependsOn"].as_array().unwrap().len(), 0);
+    }
+
+    #[test]
+    fn dependencies_carry_versions_and_hashes() {
+        let v = version("2.0.0", "placeholder_hash");
+        let dep_version = version("1.4.1", HASH);
+        let dep_id = Uuid::new_v4();
+        let resolved = declaration("oracle", "^1.4");
+        let missing = declaration("unlisted", "*");
+        let root = RootInfo {
+            contract_id: Uuid::new_v4(),
+            name: "vault",
+            network: "mainnet",
+            version: &v,
+        };
+        let bom = build(
+            &root,
+            &[
+                ResolvedDependency {
+                    declaration: &resolved,
+                    resolved: Some((dep_id, &dep_version)),
+                },
+                ResolvedDependency {
+                    declaration: &missing,
+                    resolved: None,
+                },
+            ],
+        );
+        let json = serde_json::to_value(&bom).unwrap();
+
+        // No real hash recorded for the root, so none is claimed
+        assert!(json["metadata"]["component"].get("hashes").is_none());
+
+        let components = json["components"].as_array().unwrap();
+        assert_eq!(components[0]["type"], "library");
+        assert_eq!(components[0]["version"], "1.4.1");
+        assert_eq!(components[0]["hashes"][0]["content"], HASH);
+        assert_eq!(components[0]["bom-ref"], format!("{}@1.4.1", dep_id));
+        assert!(components[1].get("version").is_none());
+
+        let depends_on = json["dependencies"][0]["dependsOn"].as_array().unwrap();
+        assert_eq!(depends_on.len(), 2);
+    }
+}
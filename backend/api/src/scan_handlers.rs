@@ -1,19 +1,46 @@
+use std::collections::HashSet;
+
 use axum::{
-    extract::{Path, State},
-    http::StatusCode,
-    response::IntoResponse,
-    Json,
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
+use serde::Deserialize;
+use shared::OrganizationRole;
+use utoipa::IntoParams;
 use uuid::Uuid;
 
+use crate::auth::{Caller, ContractAccess};
+use crate::error::{ApiError, ApiResult, ErrorCode};
+use crate::handlers::{db_internal_error, fetch_visible_contract};
+use crate::metrics;
+use crate::pagination::{PageParams, Paginated};
 use crate::state::AppState;
-use crate::scanner_service::{self, VulnerabilityPayload, ScanRequest};
+use crate::webhook_handlers::MIN_SECRET_LENGTH;
+use crate::webhooks;
+use crate::detector::parse_severity_label;
+use crate::sarif;
+use crate::share_tokens::PresentedShareToken;
+use crate::scanner_service::{
+    self, BatchScanRequest, CreateSuppressionRequest, FindingSuppression, ScanBatchReport,
+    ScanBatchSubmission, ScanDiff, ScanJob, ScanJobStatus, ScanRequest, SourceScanRequest,
+    VulnerabilityPayload,
+};
 
+#[utoipa::path(
+    post,
+    path = "/api/vulnerabilities/sync",
+    tag = "scans",
+    responses(
+        (status = 200, description = "CVE records ingested"),
+    ),
+)]
 pub async fn ingest_cves(
     State(state): State<AppState>,
     Json(payload): Json<Vec<VulnerabilityPayload>>,
 ) -> impl IntoResponse {
-    match scanner_service::sync_cves(&state.pool, payload).await {
+    match scanner_service::sync_cves(&state.db, payload).await {
         Ok(count) => {
             let msg = format!("Ingested {} CVEs successfully", count);
             (StatusCode::OK, Json(msg)).into_response()
@@ -25,12 +52,23 @@ pub async fn ingest_cves(
     }
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/scan",
+    tag = "scans",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "Scan report"),
+    ),
+)]
 pub async fn scan_contract(
     State(state): State<AppState>,
     Path(contract_id): Path<Uuid>,
     Json(payload): Json<ScanRequest>,
 ) -> impl IntoResponse {
-    match scanner_service::perform_scan(&state.pool, contract_id, payload).await {
+    match scanner_service::perform_scan(&state.db, contract_id, payload).await {
         Ok(report) => (StatusCode::OK, Json(report)).into_response(),
         Err(e) => {
             let err = format!("Failed to run contract scan: {}", e);
@@ -39,11 +77,37 @@ pub async fn scan_contract(
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/scan",
+    tag = "scans",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Scan history"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
 pub async fn get_scan_report(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<Uuid>,
 ) -> impl IntoResponse {
-    match scanner_service::get_history(&state.pool, contract_id).await {
+    let caller = caller.map(|Extension(caller)| caller);
+    let visible = fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    );
+    if let Err(err) = visible.await {
+        return err.into_response();
+    }
+
+    match scanner_service::get_history(&state.db, contract_id).await {
         Ok(report) => (StatusCode::OK, Json(report)).into_response(),
         Err(e) => {
             let err = format!("Failed to retrieve scan history: {}", e);
@@ -51,3 +115,713 @@ pub async fn get_scan_report(
         }
     }
 }
+
+/// Lowercased `fail_on`, with blank treated as absent; unknown levels are a 400
+fn normalize_fail_on(fail_on: Option<String>) -> ApiResult<Option<String>> {
+    let fail_on = fail_on
+        .map(|raw| raw.trim().to_ascii_lowercase())
+        .filter(|raw| !raw.is_empty());
+    if let Some(raw) = fail_on.as_deref() {
+        if parse_severity_label(raw).is_none() {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidFailOn,
+                format!("fail_on must be one of low, medium, high, critical (got '{}')", raw),
+            ));
+        }
+    }
+    Ok(fail_on)
+}
+
+/// Blank callback fields count as absent. A callback needs a secret to sign
+/// with and a public http(s) URL: one resolving to an internal address is
+/// refused so the registry can't be used to reach its own network.
+async fn check_callback(req: &mut SourceScanRequest) -> ApiResult<()> {
+    req.callback_url = req
+        .callback_url
+        .take()
+        .map(|url| url.trim().to_string())
+        .filter(|url| !url.is_empty());
+    req.callback_secret = req
+        .callback_secret
+        .take()
+        .filter(|secret| !secret.is_empty());
+    let Some(url) = req.callback_url.as_deref() else {
+        if req.callback_secret.is_some() {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidCallbackUrl,
+                "callback_secret was given without a callback_url",
+            ));
+        }
+        return Ok(());
+    };
+
+    let invalid_url = |msg: String| {
+        ApiError::bad_request(
+            ErrorCode::InvalidCallbackUrl,
+            format!("callback_url {}", msg),
+        )
+    };
+    let parsed = webhooks::check_callback_url(url).map_err(invalid_url)?;
+    if req
+        .callback_secret
+        .as_ref()
+        .map_or(true, |secret| secret.len() < MIN_SECRET_LENGTH)
+    {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidCallbackSecret,
+            format!(
+                "callback_secret of at least {} characters is required with a callback_url",
+                MIN_SECRET_LENGTH
+            ),
+        ));
+    }
+    webhooks::resolve_callback(&parsed)
+        .await
+        .map_err(invalid_url)?;
+    Ok(())
+}
+
+/// Queue a detector scan of contract source.
+///
+/// Returns 202 with a `job_id` to poll. Submitting the same contract+version
+/// while a scan is queued or running returns the existing job. With a
+/// `callback_url`, the finished job is also POSTed there, signed with
+/// `callback_secret` like a webhook.
+#[utoipa::path(
+    post,
+    path = "/api/scan",
+    tag = "scans",
+    responses(
+        (status = 202, description = "Scan queued (or already in flight)"),
+        (status = 400, description = "Unknown fail_on severity, or an invalid or internal callback"),
+    ),
+)]
+pub async fn submit_scan(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Json(mut req): Json<SourceScanRequest>,
+) -> ApiResult<(StatusCode, Json<serde_json::Value>)> {
+    req.fail_on = normalize_fail_on(req.fail_on)?;
+    check_callback(&mut req).await?;
+
+    let (job, created) = scanner_service::enqueue_scan_job(&state.db, &req)
+        .await
+        .map_err(|err| db_internal_error("enqueue scan job", err))?;
+    if created {
+        metrics::record_publisher_scan(caller.as_ref().map(|Extension(caller)| caller));
+    }
+
+    let mut body = serde_json::json!({
+        "job_id": job.id,
+        "status": job.status,
+        "deduplicated": !created,
+    });
+    if req.callback_url.is_some() {
+        // A job already in flight keeps the callback it was first given
+        body["callback_registered"] = (job.callback_url == req.callback_url).into();
+    }
+    Ok((StatusCode::ACCEPTED, Json(body)))
+}
+
+/// Queue a detector scan for each contract+version in the request.
+///
+/// Returns 202 with a `batch_id` to poll and the job queued for each item.
+/// An item that cannot be queued (unknown or private contract, no source) is
+/// reported with its reason; the rest of the batch still runs.
+#[utoipa::path(
+    post,
+    path = "/api/scan/batch",
+    tag = "scans",
+    params(
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 202, description = "Batch queued, with the job id of each item"),
+        (status = 400, description = "No items, too many items, or unknown fail_on severity"),
+    ),
+)]
+pub async fn submit_scan_batch(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Json(mut req): Json<BatchScanRequest>,
+) -> ApiResult<(StatusCode, Json<ScanBatchSubmission>)> {
+    if req.items.is_empty() || req.items.len() > scanner_service::MAX_BATCH_SCAN_ITEMS {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidScanBatch,
+            format!(
+                "A batch must list between 1 and {} items (got {})",
+                scanner_service::MAX_BATCH_SCAN_ITEMS,
+                req.items.len()
+            ),
+        ));
+    }
+    req.fail_on = normalize_fail_on(req.fail_on)?;
+
+    let caller = caller.as_ref().map(|Extension(caller)| caller);
+    let mut visible = HashSet::new();
+    for id in req.items.iter().map(|item| item.contract_id) {
+        if visible.contains(&id) {
+            continue;
+        }
+        match fetch_visible_contract(&state.db, caller, share_token.as_deref(), id).await {
+            Ok(_) => {
+                visible.insert(id);
+            }
+            Err(err) if err.status() == StatusCode::NOT_FOUND => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    let submission = scanner_service::enqueue_scan_batch(&state.db, &req, &visible)
+        .await
+        .map_err(|err| db_internal_error("enqueue scan batch", err))?;
+    let created = submission
+        .items
+        .iter()
+        .filter(|item| item.job_id.is_some() && !item.deduplicated);
+    for _ in created {
+        metrics::record_publisher_scan(caller);
+    }
+
+    Ok((StatusCode::ACCEPTED, Json(submission)))
+}
+
+/// Aggregate status of a batch, with findings rolled up across its items
+#[utoipa::path(
+    get,
+    path = "/api/scan/batch/{batch_id}",
+    tag = "scans",
+    params(
+        ("batch_id" = Uuid, Path, description = "Batch id returned by POST /api/scan/batch"),
+    ),
+    responses(
+        (status = 200, description = "Batch status, per-item results and the findings roll-up"),
+        (status = 404, description = "Unknown batch"),
+        (status = 422, description = "Finished, and a finding reached the fail_on severity"),
+    ),
+)]
+pub async fn get_scan_batch(
+    State(state): State<AppState>,
+    Path(batch_id): Path<Uuid>,
+) -> ApiResult<(StatusCode, Json<ScanBatchReport>)> {
+    let (batch, items) = scanner_service::get_scan_batch(&state.db, batch_id)
+        .await
+        .map_err(|err| db_internal_error("load scan batch", err))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::ScanBatchNotFound,
+                format!("No scan batch {}", batch_id),
+            )
+        })?;
+    let report = scanner_service::summarize_batch(batch, items);
+    let status = match report.passed {
+        Some(false) => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::OK,
+    };
+
+    Ok((status, Json(report)))
+}
+
+/// Status code for a job poll: a completed scan that tripped its `fail_on`
+/// gate answers 422 (body still carries the findings) so CI can gate on it.
+fn job_status_code(job: &ScanJob) -> StatusCode {
+    match (job.status, job.passed) {
+        (ScanJobStatus::Completed, Some(false)) => StatusCode::UNPROCESSABLE_ENTITY,
+        _ => StatusCode::OK,
+    }
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ScanJobParams {
+    /// Also return the findings silenced by a suppression
+    #[serde(default)]
+    pub include_suppressed: bool,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/scan/{job_id}",
+    tag = "scans",
+    params(
+        ("job_id" = Uuid, Path, description = "Scan job id returned by POST /api/scan"),
+        ScanJobParams,
+    ),
+    responses(
+        (status = 200, description = "Job status, with findings once completed"),
+        (status = 404, description = "Unknown job"),
+        (status = 422, description = "Completed, and a finding reached the fail_on severity"),
+    ),
+)]
+pub async fn get_scan_job(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+    Query(params): Query<ScanJobParams>,
+) -> ApiResult<(StatusCode, Json<ScanJob>)> {
+    let mut job = scanner_service::get_scan_job(&state.db, job_id)
+        .await
+        .map_err(|err| db_internal_error("load scan job", err))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::ScanJobNotFound,
+                format!("No scan job {}", job_id),
+            )
+        })?;
+    if !params.include_suppressed {
+        job.suppressed = None;
+    }
+
+    Ok((job_status_code(&job), Json(job)))
+}
+
+/// Completed job findings as a SARIF 2.1.0 log
+#[utoipa::path(
+    get,
+    path = "/api/scan/{job_id}/sarif",
+    tag = "scans",
+    params(
+        ("job_id" = Uuid, Path, description = "Scan job id returned by POST /api/scan"),
+    ),
+    responses(
+        (status = 200, description = "SARIF 2.1.0 log", content_type = "application/sarif+json"),
+        (status = 404, description = "Unknown job"),
+        (status = 409, description = "The job has not completed"),
+    ),
+)]
+pub async fn get_scan_sarif(
+    State(state): State<AppState>,
+    Path(job_id): Path<Uuid>,
+) -> ApiResult<Response> {
+    let job = scanner_service::get_scan_job(&state.db, job_id)
+        .await
+        .map_err(|err| db_internal_error("load scan job", err))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::ScanJobNotFound,
+                format!("No scan job {}", job_id),
+            )
+        })?;
+
+    let findings = match (job.status, job.findings) {
+        (ScanJobStatus::Completed, Some(findings)) => findings.0,
+        (status, _) => {
+            return Err(ApiError::new(
+                StatusCode::CONFLICT,
+                ErrorCode::ScanNotComplete,
+                format!("Scan job {} has not completed", job_id),
+            )
+            .with_details(serde_json::json!({ "status": status })))
+        }
+    };
+
+    Ok((
+        [(header::CONTENT_TYPE, sarif::SARIF_CONTENT_TYPE)],
+        Json(sarif::to_sarif(&findings)),
+    )
+        .into_response())
+}
+
+/// Stored suppressions for a contract's scan findings
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/suppressions",
+    tag = "scans",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        PageParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Suppressed finding fingerprints with their reasons, oldest first"),
+        (status = 404, description = "Unknown or private contract"),
+    ),
+)]
+pub async fn list_suppressions(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(contract_id): Path<Uuid>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<FindingSuppression>>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    )
+    .await?;
+
+    let (suppressions, total) =
+        scanner_service::list_suppressions(&state.db, contract_id, page.limit(), page.offset())
+            .await
+            .map_err(|err| db_internal_error("list suppressions", err))?;
+    Ok(Json(Paginated::new(
+        suppressions,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+/// Silence a finding by fingerprint (`RULE-ID@location`) from the next scan
+/// onwards. A reason is required; re-adding a fingerprint replaces its reason.
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/suppressions",
+    tag = "scans",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 201, description = "The stored suppression"),
+        (status = 400, description = "Missing fingerprint"),
+        (status = 403, description = "Caller lacks the maintainer role on the contract"),
+        (status = 404, description = "Contract not found"),
+        (status = 422, description = "Missing reason"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn create_suppression(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Extension(access): Extension<ContractAccess>,
+    Path(contract_id): Path<Uuid>,
+    Json(req): Json<CreateSuppressionRequest>,
+) -> ApiResult<(StatusCode, Json<FindingSuppression>)> {
+    let fingerprint = req.fingerprint.trim();
+    if fingerprint.is_empty() {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidFingerprint,
+            "fingerprint must be a finding fingerprint such as IV-001@line 12",
+        ));
+    }
+    let reason = req.reason.trim();
+    if reason.is_empty() {
+        return Err(ApiError::unprocessable(
+            ErrorCode::SuppressionReasonRequired,
+            "A suppression needs a non-empty reason",
+        ));
+    }
+    access.require(OrganizationRole::Maintainer)?;
+
+    let suppression = scanner_service::upsert_suppression(
+        &state.db,
+        contract_id,
+        fingerprint,
+        reason,
+        caller.publisher_id(),
+    )
+    .await
+    .map_err(|err| db_internal_error("store suppression", err))?;
+    Ok((StatusCode::CREATED, Json(suppression)))
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/contracts/{id}/suppressions/{suppression_id}",
+    tag = "scans",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("suppression_id" = Uuid, Path, description = "Suppression to remove"),
+    ),
+    responses(
+        (status = 204, description = "Suppression removed"),
+        (status = 403, description = "Caller lacks the maintainer role on the contract"),
+        (status = 404, description = "Contract or suppression not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn delete_suppression(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Path((contract_id, suppression_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    access.require(OrganizationRole::Maintainer)?;
+
+    let deleted = scanner_service::delete_suppression(&state.db, contract_id, suppression_id)
+        .await
+        .map_err(|err| db_internal_error("delete suppression", err))?;
+    if !deleted {
+        return Err(ApiError::not_found(
+            ErrorCode::SuppressionNotFound,
+            format!("Contract {} has no suppression {}", contract_id, suppression_id),
+        ));
+    }
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize, IntoParams)]
+pub struct ScanDiffParams {
+    /// Baseline version
+    pub from: String,
+    /// Version being compared against the baseline
+    pub to: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/scan-diff",
+    tag = "scans",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ScanDiffParams,
+        ("token" = Option<String>, Query, description = "Share token for a private contract; also accepted as X-Share-Token"),
+    ),
+    responses(
+        (status = 200, description = "Findings added, removed and unchanged between the two versions"),
+        (status = 404, description = "Unknown or private contract"),
+        (status = 409, description = "One of the versions has not been scanned yet"),
+    ),
+)]
+pub async fn scan_diff(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
+    Path(contract_id): Path<Uuid>,
+    Query(params): Query<ScanDiffParams>,
+) -> ApiResult<Json<ScanDiff>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    fetch_visible_contract(
+        &state.db,
+        caller.as_ref(),
+        share_token.as_deref(),
+        contract_id,
+    )
+    .await?;
+
+    let mut findings = Vec::with_capacity(2);
+    for version in [&params.from, &params.to] {
+        let recorded = scanner_service::latest_version_findings(&state.db, contract_id, version)
+            .await
+            .map_err(|err| db_internal_error("load version scan", err))?;
+        match recorded {
+            Some(recorded) => findings.push(recorded),
+            None => {
+                return Err(ApiError::new(
+                    StatusCode::CONFLICT,
+                    ErrorCode::ScanRequired,
+                    format!("Version {} has no scan on record; scan it first", version),
+                )
+                .with_details(serde_json::json!({ "version": version })))
+            }
+        }
+    }
+
+    let to = findings.pop().unwrap_or_default();
+    let from = findings.pop().unwrap_or_default();
+    let (added, removed, unchanged) = scanner_service::diff_findings(from, to);
+
+    Ok(Json(ScanDiff {
+        contract_id,
+        from: params.from,
+        to: params.to,
+        added,
+        removed,
+        unchanged,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Severity;
+    use crate::scanner_service::ScanFinding;
+
+    fn job(status: ScanJobStatus, passed: Option<bool>) -> ScanJob {
+        ScanJob {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            version: Some("1.0.0".into()),
+            rescan: false,
+            source: String::new(),
+            fail_on: Some("high".into()),
+            status,
+            findings: Some(sqlx::types::Json(vec![ScanFinding {
+                rule_id: "IV-001".into(),
+                severity: Severity::High,
+                location: "line 1".into(),
+                message: "unwrap".into(),
+            }])),
+            suppressed: None,
+            passed,
+            error: None,
+            callback_url: None,
+            callback_secret: None,
+            created_at: chrono::Utc::now(),
+            started_at: None,
+            finished_at: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn batches_are_validated_before_queueing() {
+        // Rejected before any query
+        let state = AppState::for_tests();
+        let item = scanner_service::BatchScanItem {
+            contract_id: Uuid::new_v4(),
+            version: None,
+            source: Some("fn main() {}".into()),
+        };
+        let submit = |items: Vec<scanner_service::BatchScanItem>, fail_on: Option<&str>| {
+            submit_scan_batch(
+                State(state.clone()),
+                None,
+                PresentedShareToken::default(),
+                Json(BatchScanRequest {
+                    items,
+                    fail_on: fail_on.map(str::to_string),
+                }),
+            )
+        };
+
+        let err = submit(vec![], None).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        let too_many = vec![item.clone(); scanner_service::MAX_BATCH_SCAN_ITEMS + 1];
+        let err = submit(too_many, None).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        let err = submit(vec![item], Some("severe")).await.unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn batches_report_private_contracts_as_not_found() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let owner = state.insert_publisher().await;
+        let private = state.insert_contract(owner, None, "private").await;
+        let public = state.insert_contract(owner, None, "public").await;
+        let unknown = Uuid::new_v4();
+        let item = |contract_id| scanner_service::BatchScanItem {
+            contract_id,
+            version: None,
+            source: Some("fn main() {}".into()),
+        };
+        let submit = |caller: Option<Caller>| {
+            submit_scan_batch(
+                State(state.clone()),
+                caller.map(Extension),
+                PresentedShareToken::default(),
+                Json(BatchScanRequest {
+                    items: vec![item(private), item(unknown), item(public)],
+                    fail_on: None,
+                }),
+            )
+        };
+
+        let (_, Json(anonymous)) = submit(None).await.unwrap();
+        for queued in &anonymous.items[..2] {
+            assert_eq!(queued.job_id, None);
+            let expected = scanner_service::contract_not_found(queued.contract_id);
+            assert_eq!(queued.error.as_deref(), Some(expected.as_str()));
+        }
+        assert!(anonymous.items[2].job_id.is_some());
+
+        let (_, Json(owned)) = submit(Some(Caller::Publisher(owner))).await.unwrap();
+        assert!(owned.items[0].job_id.is_some());
+        assert!(owned.items[1].job_id.is_none());
+    }
+
+    #[tokio::test]
+    async fn callbacks_must_be_signed_and_public() {
+        let request = |url: Option<&str>, secret: Option<&str>| SourceScanRequest {
+            contract_id: Uuid::new_v4(),
+            version: None,
+            source: String::new(),
+            fail_on: None,
+            callback_url: url.map(str::to_string),
+            callback_secret: secret.map(str::to_string),
+        };
+        let secret = Some("0123456789abcdef");
+
+        let mut none = request(Some("  "), Some(""));
+        check_callback(&mut none).await.unwrap();
+        assert_eq!(none.callback_url, None);
+
+        for (url, secret) in [
+            (None, secret),
+            (Some("ftp://example.com/done"), secret),
+            (Some("http://169.254.169.254/latest"), secret),
+            (Some("http://[::1]:3001/"), secret),
+            (Some("https://ci.example.com/done"), None),
+            (Some("https://ci.example.com/done"), Some("short")),
+        ] {
+            let mut req = request(url, secret);
+            let err = check_callback(&mut req).await.unwrap_err();
+            assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+        }
+    }
+
+    #[test]
+    fn failed_gate_polls_as_422() {
+        assert_eq!(
+            job_status_code(&job(ScanJobStatus::Completed, Some(false))),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
+        assert_eq!(job_status_code(&job(ScanJobStatus::Completed, Some(true))), StatusCode::OK);
+        assert_eq!(job_status_code(&job(ScanJobStatus::Running, None)), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn polling_a_scan_answers_422_only_when_the_gate_fails() {
+        use crate::state::{json_body, test_request};
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let publisher = state.insert_publisher().await;
+        let contract = state.insert_contract(publisher, None, "public").await;
+        // IV-001 is a critical finding, so any gate trips on it
+        let source = "pub fn read(input: Option<u64>) -> u64 {\n    input.unwrap()\n}\n";
+
+        for (fail_on, expected) in [
+            (Some("critical"), StatusCode::UNPROCESSABLE_ENTITY),
+            (None, StatusCode::OK),
+        ] {
+            let body = serde_json::json!({
+                "contract_id": contract,
+                "source": source,
+                "fail_on": fail_on,
+            });
+            let response = state
+                .send(test_request("POST", "/api/scan", None, Some(body)))
+                .await;
+            assert_eq!(response.status(), StatusCode::ACCEPTED);
+            let job_id = json_body(response).await["job_id"].clone();
+            let job_id: Uuid = serde_json::from_value(job_id).unwrap();
+            let uri = format!("/api/scan/{}", job_id);
+
+            // Nothing to gate on until the scan has run
+            let response = state.send(test_request("GET", &uri, None, None)).await;
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(json_body(response).await["status"], "queued");
+
+            scanner_service::run_queued_job(&state.db, job_id).await;
+            let response = state.send(test_request("GET", &uri, None, None)).await;
+            assert_eq!(response.status(), expected, "fail_on={:?}", fail_on);
+            let job = json_body(response).await;
+            assert_eq!(job["status"], "completed");
+            assert_eq!(job["passed"], expected == StatusCode::OK);
+            let findings = job["findings"].as_array().unwrap();
+            assert!(findings.iter().any(|f| f["rule_id"] == "IV-001"));
+        }
+    }
+
+    #[tokio::test]
+    async fn private_contract_scans_are_hidden_from_anonymous_callers() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let id = state.insert_private_contract().await;
+
+        for uri in [
+            format!("/api/contracts/{}/scan", id),
+            format!("/api/contracts/{}/scan-diff?from=1.0.0&to=1.1.0", id),
+            format!("/api/contracts/{}/suppressions", id),
+        ] {
+            let status = state.anonymous_get(&uri).await;
+            assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+        }
+    }
+}
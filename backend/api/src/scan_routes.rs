@@ -7,6 +7,13 @@ use crate::{scan_handlers, state::AppState};
 pub fn scan_routes() -> Router<AppState> {
     Router::new()
         .route("/api/vulnerabilities/sync", post(scan_handlers::ingest_cves))
+        .route("/api/scan", post(scan_handlers::submit_scan))
+        .route("/api/scan/batch", post(scan_handlers::submit_scan_batch))
+        .route("/api/scan/batch/:batch_id", get(scan_handlers::get_scan_batch))
+        .route("/api/scan/:job_id", get(scan_handlers::get_scan_job))
+        .route("/api/scan/:job_id/sarif", get(scan_handlers::get_scan_sarif))
         .route("/api/contracts/:id/scan", post(scan_handlers::scan_contract))
         .route("/api/contracts/:id/scan", get(scan_handlers::get_scan_report))
+        .route("/api/contracts/:id/scan-diff", get(scan_handlers::scan_diff))
+        .route("/api/contracts/:id/suppressions", get(scan_handlers::list_suppressions))
 }
@@ -1,8 +1,21 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ops::Bound;
+use std::sync::Arc;
+use std::time::Duration;
+
 use chrono::{DateTime, Utc};
+use semver::{Comparator, Op, Version, VersionReq};
 use serde::{Deserialize, Serialize};
-use sqlx::{FromRow, PgPool};
+use sha2::{Digest, Sha256};
+use sqlx::{FromRow, PgConnection, PgPool};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
+use crate::checklist::all_checks;
+use crate::detector::{detect_all, detect_check, DetectionResult, InlineSuppressions};
+use crate::models::{CheckStatus, ChecklistItem, Severity};
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct VulnerabilityPayload {
     pub cve_id: String,
@@ -21,6 +34,68 @@ pub struct DependencyDescriptor {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ScanRequest {
     pub dependencies: Vec<DependencyDescriptor>,
+    /// When set, the findings are also recorded against this version so they
+    /// can be compared with other versions later.
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// A single finding as recorded against a contract version
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ScanFinding {
+    pub rule_id: String,
+    pub severity: Severity,
+    /// Where the finding was raised (package, file/line, ...)
+    pub location: String,
+    pub message: String,
+}
+
+impl ScanFinding {
+    /// Stable identity used to match findings across scans
+    pub fn fingerprint(&self) -> String {
+        format!("{}@{}", self.rule_id, self.location)
+    }
+}
+
+/// Findings per severity
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, FromRow)]
+pub struct SeverityCounts {
+    pub critical: i64,
+    pub high: i64,
+    pub medium: i64,
+    pub low: i64,
+    pub info: i64,
+}
+
+impl SeverityCounts {
+    pub fn from_findings(findings: &[ScanFinding]) -> Self {
+        let mut counts = Self::default();
+        for finding in findings {
+            counts.add(&finding.severity, 1);
+        }
+        counts
+    }
+
+    pub fn add(&mut self, severity: &Severity, n: i64) {
+        match severity {
+            Severity::Critical => self.critical += n,
+            Severity::High => self.high += n,
+            Severity::Medium => self.medium += n,
+            Severity::Low => self.low += n,
+            Severity::Info => self.info += n,
+        }
+    }
+}
+
+/// Findings that appeared, disappeared or stayed between two versions
+#[derive(Debug, Serialize)]
+pub struct ScanDiff {
+    pub contract_id: Uuid,
+    pub from: String,
+    pub to: String,
+    pub added: Vec<ScanFinding>,
+    pub removed: Vec<ScanFinding>,
+    pub unchanged: Vec<ScanFinding>,
 }
 
 #[derive(Debug, Serialize, FromRow)]
@@ -37,6 +112,8 @@ pub struct ScanResultRow {
 pub struct ScanReport {
     pub contract_id: Uuid,
     pub findings: Vec<ScanResultRow>,
+    /// Dependencies whose declared range overlaps a registry advisory
+    pub advisories: Vec<ScanFinding>,
     pub scanned_dependencies_count: usize,
 }
 
@@ -135,13 +212,1610 @@ pub async fn perform_scan(pool: &PgPool, contract_id: Uuid, request: ScanRequest
         }
     }
 
+    let dependencies: Vec<(String, String)> = request
+        .dependencies
+        .iter()
+        .map(|dep| (dep.package_name.clone(), dep.version.clone()))
+        .collect();
+    let advisories = advisory_findings(pool, &dependencies).await?;
+
+    if let Some(version) = &request.version {
+        let recorded: Vec<ScanFinding> = findings
+            .iter()
+            .map(|f| ScanFinding {
+                rule_id: f.cve_id.clone(),
+                severity: parse_severity(&f.severity),
+                location: format!("{}@{}", f.package_name, f.current_version),
+                message: match &f.recommended_version {
+                    Some(fixed) => format!("{} is vulnerable; upgrade to {}", f.package_name, fixed),
+                    None => format!("{} is vulnerable", f.package_name),
+                },
+            })
+            .chain(advisories.iter().cloned())
+            .collect();
+        record_version_scan(pool, contract_id, version, &recorded).await?;
+    }
+    crate::metrics::record_scan_completed(crate::metrics::SCAN_KIND_DEPENDENCY);
+
     Ok(ScanReport {
         contract_id,
         findings,
+        advisories,
         scanned_dependencies_count: request.dependencies.len(),
     })
 }
 
+// ─────────────────────────────────────────────────────────
+// Dependency advisories (`dependency_advisories`)
+// ─────────────────────────────────────────────────────────
+
+/// A known-bad range of a dependency, managed via `/api/config/dependency-advisories`
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct DependencyAdvisory {
+    /// Advisory identifier, e.g. `RUSTSEC-2024-0001`; reported as the rule id
+    pub id: String,
+    pub package_name: String,
+    /// Affected versions as a semver requirement, e.g. `>=1.0.0, <1.4.2`
+    pub affected: String,
+    pub severity: String,
+    pub summary: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A contiguous span of versions. Pre-release tags are ignored: ranges are
+/// compared on major.minor.patch only.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionRange {
+    pub lower: Bound<Version>,
+    pub upper: Bound<Version>,
+}
+
+impl VersionRange {
+    const ALL: VersionRange = VersionRange {
+        lower: Bound::Unbounded,
+        upper: Bound::Unbounded,
+    };
+
+    /// Parse a declared dependency version. A bare version (`1.2.3`) is the
+    /// resolved version itself; anything else is read as a requirement.
+    pub fn parse_declared(raw: &str) -> Result<Self, semver::Error> {
+        match Version::parse(raw.trim()) {
+            Ok(version) => Ok(Self {
+                lower: Bound::Included(version.clone()),
+                upper: Bound::Included(version),
+            }),
+            Err(_) => Self::parse_req(raw),
+        }
+    }
+
+    /// The versions matched by a semver requirement; its comparators all
+    /// have to hold, so their spans are intersected
+    pub fn parse_req(raw: &str) -> Result<Self, semver::Error> {
+        let req = VersionReq::parse(raw.trim())?;
+        Ok(req
+            .comparators
+            .iter()
+            .map(comparator_range)
+            .fold(Self::ALL, |acc, range| acc.intersect(&range)))
+    }
+
+    pub fn intersect(&self, other: &Self) -> Self {
+        let lower = match (&self.lower, &other.lower) {
+            (Bound::Unbounded, b) | (b, Bound::Unbounded) => b.clone(),
+            (a, b) => {
+                if lower_key(a) >= lower_key(b) {
+                    a.clone()
+                } else {
+                    b.clone()
+                }
+            }
+        };
+        let upper = match (&self.upper, &other.upper) {
+            (Bound::Unbounded, b) | (b, Bound::Unbounded) => b.clone(),
+            (a, b) => {
+                if upper_key(a) <= upper_key(b) {
+                    a.clone()
+                } else {
+                    b.clone()
+                }
+            }
+        };
+        Self { lower, upper }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        match (&self.lower, &self.upper) {
+            (Bound::Unbounded, _) | (_, Bound::Unbounded) => false,
+            (Bound::Included(lo), Bound::Included(hi)) => lo > hi,
+            (
+                Bound::Included(lo) | Bound::Excluded(lo),
+                Bound::Included(hi) | Bound::Excluded(hi),
+            ) => lo >= hi,
+        }
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersect(other).is_empty()
+    }
+}
+
+/// Orders lower bounds: at the same version, an exclusive bound starts later
+fn lower_key(bound: &Bound<Version>) -> (Option<&Version>, bool) {
+    match bound {
+        Bound::Included(v) => (Some(v), false),
+        Bound::Excluded(v) => (Some(v), true),
+        Bound::Unbounded => (None, false),
+    }
+}
+
+/// Orders upper bounds: at the same version, an exclusive bound ends earlier
+fn upper_key(bound: &Bound<Version>) -> (Option<&Version>, bool) {
+    match bound {
+        Bound::Included(v) => (Some(v), true),
+        Bound::Excluded(v) => (Some(v), false),
+        Bound::Unbounded => (None, true),
+    }
+}
+
+fn comparator_range(c: &Comparator) -> VersionRange {
+    let v = |major: u64, minor: u64, patch: u64| Version::new(major, minor, patch);
+    let floor = v(c.major, c.minor.unwrap_or(0), c.patch.unwrap_or(0));
+    // First version past the precision the comparator was written with
+    let past = match (c.minor, c.patch) {
+        (None, _) => v(c.major + 1, 0, 0),
+        (Some(minor), None) => v(c.major, minor + 1, 0),
+        (Some(minor), Some(patch)) => v(c.major, minor, patch + 1),
+    };
+    let (lower, upper) = match c.op {
+        Op::Exact | Op::Wildcard => (Bound::Included(floor), Bound::Excluded(past)),
+        Op::Greater => (Bound::Included(past), Bound::Unbounded),
+        Op::GreaterEq => (Bound::Included(floor), Bound::Unbounded),
+        Op::Less => (Bound::Unbounded, Bound::Excluded(floor)),
+        Op::LessEq => (Bound::Unbounded, Bound::Excluded(past)),
+        Op::Tilde => {
+            let end = match c.minor {
+                None => v(c.major + 1, 0, 0),
+                Some(minor) => v(c.major, minor + 1, 0),
+            };
+            (Bound::Included(floor), Bound::Excluded(end))
+        }
+        Op::Caret => {
+            // The left-most non-zero part may not change
+            let end = match (c.major, c.minor, c.patch) {
+                (0, None, _) => v(1, 0, 0),
+                (0, Some(0), None) => v(0, 1, 0),
+                (0, Some(0), Some(patch)) => v(0, 0, patch + 1),
+                (0, Some(minor), _) => v(0, minor + 1, 0),
+                (major, _, _) => v(major + 1, 0, 0),
+            };
+            (Bound::Included(floor), Bound::Excluded(end))
+        }
+        // Operators added to semver later: don't rule anything out
+        _ => (Bound::Unbounded, Bound::Unbounded),
+    };
+    VersionRange { lower, upper }
+}
+
+/// Findings for every advisory whose affected range overlaps the declared
+/// version of the same package. Unparseable versions are skipped rather
+/// than guessed at.
+pub fn match_advisories(
+    dependencies: &[(String, String)],
+    advisories: &[DependencyAdvisory],
+) -> Vec<ScanFinding> {
+    let mut findings = Vec::new();
+    for (package, declared) in dependencies {
+        let Ok(declared_range) = VersionRange::parse_declared(declared) else {
+            continue;
+        };
+        for advisory in advisories.iter().filter(|a| &a.package_name == package) {
+            let Ok(affected) = VersionRange::parse_req(&advisory.affected) else {
+                continue;
+            };
+            if declared_range.overlaps(&affected) {
+                findings.push(ScanFinding {
+                    rule_id: advisory.id.clone(),
+                    severity: parse_severity(&advisory.severity),
+                    location: format!("{}@{}", package, declared),
+                    message: format!(
+                        "{} {} overlaps affected range {} ({}): {}",
+                        package, declared, advisory.affected, advisory.id, advisory.summary
+                    ),
+                });
+            }
+        }
+    }
+    findings
+}
+
+async fn advisory_findings(
+    pool: &PgPool,
+    dependencies: &[(String, String)],
+) -> Result<Vec<ScanFinding>, sqlx::Error> {
+    let packages: Vec<&str> = dependencies.iter().map(|(name, _)| name.as_str()).collect();
+    let advisories: Vec<DependencyAdvisory> = sqlx::query_as(
+        "SELECT * FROM dependency_advisories WHERE package_name = ANY($1) ORDER BY id",
+    )
+    .bind(&packages)
+    .fetch_all(pool)
+    .await?;
+    Ok(match_advisories(dependencies, &advisories))
+}
+
+/// Request body for `POST /api/scan`: queue a detector run over contract source
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SourceScanRequest {
+    pub contract_id: Uuid,
+    /// Record the findings against this version (enables scan-diff)
+    #[serde(default)]
+    pub version: Option<String>,
+    pub source: String,
+    /// `low|medium|high|critical`: fail the request when any finding is at or
+    /// above this level
+    #[serde(default)]
+    pub fail_on: Option<String>,
+    /// Public http(s) URL the finished job is POSTed to, completed or failed
+    #[serde(default)]
+    pub callback_url: Option<String>,
+    /// Key for the callback's `X-Registry-Signature`; required with a URL
+    #[serde(default, skip_serializing)]
+    pub callback_secret: Option<String>,
+}
+
+/// Where a suppression came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SuppressionSource {
+    /// A `// soroban-registry:ignore RULE-ID reason` pragma in the source
+    Inline,
+    /// An entry in the contract's stored suppression list
+    Stored,
+}
+
+/// A finding silenced by a suppression; kept for the record but excluded from
+/// `fail_on` gating and the security score
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SuppressedFinding {
+    #[serde(flatten)]
+    pub finding: ScanFinding,
+    pub suppressed_by: SuppressionSource,
+    pub reason: String,
+}
+
+/// Detector output split into the findings that count and the suppressed ones
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DetectorScan {
+    pub findings: Vec<ScanFinding>,
+    pub suppressed: Vec<SuppressedFinding>,
+}
+
+/// Run every detector rule over `source` and return the unsuppressed findings
+pub fn detector_findings(source: &str) -> Vec<ScanFinding> {
+    scan_with_suppressions(source, &HashMap::new()).findings
+}
+
+/// Run the detector, honouring inline ignore pragmas and `stored` suppressions
+/// (fingerprint -> reason).
+///
+/// Rules report their first hit only, so when a finding on line N is
+/// suppressed that line is blanked and only that rule re-run: a later,
+/// unsuppressed occurrence of the same problem still surfaces. Each re-run
+/// consumes one suppressed line, so the work grows with the number of
+/// suppressions hit rather than with the length of the source.
+pub fn scan_with_suppressions(source: &str, stored: &HashMap<String, String>) -> DetectorScan {
+    let lines: Vec<&str> = source.lines().collect();
+    let pragmas = InlineSuppressions::new(&lines);
+    let mut scan = DetectorScan::default();
+
+    for first in raw_detector_findings(source) {
+        // Blanked copy of the source, made once the rule needs a re-run
+        let mut view: Option<Vec<&str>> = None;
+        let mut next = Some(first);
+        while let Some(finding) = next.take() {
+            let line = finding_line(&finding.location);
+            let suppression = match stored.get(&finding.fingerprint()) {
+                Some(reason) => Some((SuppressionSource::Stored, reason.clone())),
+                None => line
+                    .and_then(|n| pragmas.reason(n - 1, &finding.rule_id))
+                    .map(|reason| (SuppressionSource::Inline, reason.to_string())),
+            };
+            let Some((suppressed_by, reason)) = suppression else {
+                scan.findings.push(finding);
+                break;
+            };
+            let rule_id = finding.rule_id.clone();
+            scan.suppressed.push(SuppressedFinding {
+                finding,
+                suppressed_by,
+                reason,
+            });
+
+            let Some(n) = line else {
+                continue;
+            };
+            let view = view.get_or_insert_with(|| lines.clone());
+            if let Some(hit) = view.get_mut(n - 1).filter(|hit| !hit.is_empty()) {
+                *hit = "";
+                next = rule_finding(view, &rule_id);
+            }
+        }
+    }
+
+    scan.findings
+        .sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.rule_id.cmp(&b.rule_id)));
+    scan
+}
+
+/// Line number of a `line N` location
+fn finding_line(location: &str) -> Option<usize> {
+    location
+        .strip_prefix("line ")?
+        .trim()
+        .parse()
+        .ok()
+        .filter(|&n| n > 0)
+}
+
+fn raw_detector_findings(source: &str) -> Vec<ScanFinding> {
+    let results = detect_all(source);
+    let mut findings: Vec<ScanFinding> = all_checks()
+        .into_iter()
+        .filter_map(|check| finding_from(&check, results.get(check.id.as_ref())?))
+        .collect();
+    findings.sort_by(|a, b| b.severity.cmp(&a.severity).then_with(|| a.rule_id.cmp(&b.rule_id)));
+    findings
+}
+
+/// `rule_id`'s first finding in `lines`, for re-running one rule
+fn rule_finding(lines: &[&str], rule_id: &str) -> Option<ScanFinding> {
+    let check = all_checks().into_iter().find(|check| check.id == rule_id)?;
+    finding_from(&check, &detect_check(lines, &check)?)
+}
+
+fn finding_from(check: &ChecklistItem, result: &DetectionResult) -> Option<ScanFinding> {
+    if result.status != CheckStatus::Failed {
+        return None;
+    }
+    Some(ScanFinding {
+        rule_id: check.id.to_string(),
+        severity: check.severity.clone(),
+        location: result
+            .evidence
+            .as_deref()
+            .and_then(evidence_location)
+            .unwrap_or_else(|| "source".to_string()),
+        message: check.title.to_string(),
+    })
+}
+
+/// Detector evidence starts with `Line N:` when it points at a line
+fn evidence_location(evidence: &str) -> Option<String> {
+    let rest = evidence.strip_prefix("Line ")?;
+    let line: usize = rest.split(':').next()?.trim().parse().ok()?;
+    Some(format!("line {}", line))
+}
+
+/// True when no finding reaches the `fail_on` threshold
+pub fn passes_gate(findings: &[ScanFinding], fail_on: Option<&Severity>) -> bool {
+    match fail_on {
+        Some(threshold) => findings.iter().all(|f| f.severity < *threshold),
+        None => true,
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Background scan jobs (`scan_jobs`)
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ScanJobStatus {
+    Queued,
+    Running,
+    Completed,
+    Failed,
+}
+
+impl ScanJobStatus {
+    pub fn is_terminal(self) -> bool {
+        matches!(self, ScanJobStatus::Completed | ScanJobStatus::Failed)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ScanJob {
+    #[serde(rename = "job_id")]
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub version: Option<String>,
+    /// Queued by the re-scan scheduler rather than a client
+    #[sqlx(default)]
+    pub rescan: bool,
+    #[serde(skip)]
+    pub source: String,
+    pub fail_on: Option<String>,
+    pub status: ScanJobStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub findings: Option<sqlx::types::Json<Vec<ScanFinding>>>,
+    /// Findings silenced by a suppression; only returned on request
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub suppressed: Option<sqlx::types::Json<Vec<SuppressedFinding>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passed: Option<bool>,
+    /// The same bytes were already scanned with the same rules; the findings
+    /// were reused rather than recomputed
+    #[sqlx(default)]
+    pub from_cache: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Notified when the job finishes
+    #[sqlx(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+    #[sqlx(default)]
+    #[serde(skip)]
+    pub callback_secret: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub finished_at: Option<DateTime<Utc>>,
+}
+
+/// Queue a scan, or return the in-flight job for the same contract+version.
+/// Returns the job and whether it was newly created. A callback joins an
+/// in-flight job only if that job has none yet.
+pub async fn enqueue_scan_job(
+    pool: &PgPool,
+    request: &SourceScanRequest,
+) -> Result<(ScanJob, bool), sqlx::Error> {
+    let mut conn = pool.acquire().await?;
+    queue_scan_job(&mut conn, request).await
+}
+
+/// [`enqueue_scan_job`] on a given connection, so a batch can queue its jobs
+/// in the transaction that records it
+async fn queue_scan_job(
+    conn: &mut PgConnection,
+    request: &SourceScanRequest,
+) -> Result<(ScanJob, bool), sqlx::Error> {
+    // The partial unique index makes this race-safe: concurrent submissions
+    // for the same target collapse onto one queued/running row.
+    let created: Option<ScanJob> = sqlx::query_as(
+        "INSERT INTO scan_jobs (contract_id, version, source, fail_on, callback_url, callback_secret)
+         VALUES ($1, $2, $3, $4, $5, $6)
+         ON CONFLICT (contract_id, (COALESCE(version, ''))) WHERE status IN ('queued', 'running')
+         DO NOTHING
+         RETURNING *",
+    )
+    .bind(request.contract_id)
+    .bind(&request.version)
+    .bind(&request.source)
+    .bind(&request.fail_on)
+    .bind(&request.callback_url)
+    .bind(&request.callback_secret)
+    .fetch_optional(&mut *conn)
+    .await?;
+
+    if let Some(job) = created {
+        return Ok((job, true));
+    }
+
+    let existing: ScanJob = sqlx::query_as(
+        "UPDATE scan_jobs
+         SET callback_url = COALESCE(callback_url, $3),
+             callback_secret = CASE WHEN callback_url IS NULL THEN $4 ELSE callback_secret END
+         WHERE id = (
+             SELECT id FROM scan_jobs
+             WHERE contract_id = $1 AND COALESCE(version, '') = COALESCE($2, '')
+               AND status IN ('queued', 'running')
+             ORDER BY created_at DESC
+             LIMIT 1
+         )
+         RETURNING *",
+    )
+    .bind(request.contract_id)
+    .bind(&request.version)
+    .bind(&request.callback_url)
+    .bind(&request.callback_secret)
+    .fetch_one(&mut *conn)
+    .await?;
+    Ok((existing, false))
+}
+
+pub async fn get_scan_job(pool: &PgPool, job_id: Uuid) -> Result<Option<ScanJob>, sqlx::Error> {
+    sqlx::query_as("SELECT * FROM scan_jobs WHERE id = $1")
+        .bind(job_id)
+        .fetch_optional(pool)
+        .await
+}
+
+/// How long a running job may go without a heartbeat before it is failed
+fn visibility_timeout() -> Duration {
+    let secs = std::env::var("SCAN_JOB_VISIBILITY_TIMEOUT_SECS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(300);
+    Duration::from_secs(secs)
+}
+
+const WORKER_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub const SCANNER_TIMEOUT_ENV: &str = "SCANNER_TIMEOUT_SECS";
+pub const SCANNER_CONCURRENCY_ENV: &str = "SCANNER_MAX_CONCURRENCY";
+
+/// How long a scan may run when `SCANNER_TIMEOUT_SECS` is unset
+pub const DEFAULT_SCAN_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Limits on detector runs. By default one scan runs at a time and gives up
+/// after [`DEFAULT_SCAN_TIMEOUT`], so a pathological source can't hold the
+/// only slot forever.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ScannerConfig {
+    /// A scan running longer than this fails the job; `None` never times out
+    pub timeout: Option<Duration>,
+    pub max_concurrency: usize,
+}
+
+impl Default for ScannerConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Some(DEFAULT_SCAN_TIMEOUT),
+            max_concurrency: 1,
+        }
+    }
+}
+
+impl ScannerConfig {
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    /// `SCANNER_TIMEOUT_SECS=0` disables the timeout
+    pub(crate) fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let parse = |name: &str| -> Result<Option<u64>, String> {
+            lookup(name)
+                .map(|raw| {
+                    raw.trim()
+                        .parse::<u64>()
+                        .map_err(|_| format!("{} must be a non-negative integer (got '{}')", name, raw))
+                })
+                .transpose()
+        };
+        let timeout = match parse(SCANNER_TIMEOUT_ENV)? {
+            None => Self::default().timeout,
+            Some(0) => None,
+            Some(secs) => Some(Duration::from_secs(secs)),
+        };
+        let max_concurrency = match parse(SCANNER_CONCURRENCY_ENV)? {
+            None => Self::default().max_concurrency,
+            Some(0) => return Err(format!("{} must be at least 1", SCANNER_CONCURRENCY_ENV)),
+            Some(n) => usize::try_from(n)
+                .ok()
+                .filter(|n| *n <= Semaphore::MAX_PERMITS)
+                .ok_or_else(|| format!("{} is too large", SCANNER_CONCURRENCY_ENV))?,
+        };
+        Ok(Self {
+            timeout,
+            max_concurrency,
+        })
+    }
+}
+
+/// Why a scan produced no result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScanFailure {
+    TimedOut(Duration),
+    Panicked(String),
+}
+
+impl std::fmt::Display for ScanFailure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ScanFailure::TimedOut(limit) => write!(f, "scan timed out after {}s", limit.as_secs()),
+            ScanFailure::Panicked(err) => write!(f, "scan panicked: {}", err),
+        }
+    }
+}
+
+/// Run `scan` on the blocking pool, holding `permit` until it returns.
+///
+/// The permit travels with the detector thread rather than the job: a scan
+/// that times out keeps its slot until it actually finishes, so runaway scans
+/// can't stack up past `SCANNER_MAX_CONCURRENCY`.
+fn spawn_scan<T: Send + 'static>(
+    permit: OwnedSemaphorePermit,
+    scan: impl FnOnce() -> T + Send + 'static,
+) -> JoinHandle<T> {
+    tokio::task::spawn_blocking(move || {
+        let _permit = permit;
+        scan()
+    })
+}
+
+/// Wait for a spawned scan, giving up after `timeout`
+async fn await_scan<T>(scan: JoinHandle<T>, timeout: Option<Duration>) -> Result<T, ScanFailure> {
+    let joined = match timeout {
+        Some(limit) => tokio::time::timeout(limit, scan)
+            .await
+            .map_err(|_| ScanFailure::TimedOut(limit))?,
+        None => scan.await,
+    };
+    joined.map_err(|err| ScanFailure::Panicked(err.to_string()))
+}
+
+/// Spawn the background worker that drains `scan_jobs`, running up to
+/// `config.max_concurrency` scans at once.
+pub fn spawn_scan_worker(pool: PgPool, config: ScannerConfig) {
+    tokio::spawn(async move {
+        let timeout = visibility_timeout();
+        let slots = Arc::new(Semaphore::new(config.max_concurrency));
+        let mut interval = tokio::time::interval(WORKER_POLL_INTERVAL);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = fail_stale_jobs(&pool, timeout).await {
+                tracing::error!(error = ?err, "scan worker: failed to reap stale jobs");
+            }
+
+            // Drain everything that is queued before waiting again. A slot is
+            // taken before claiming, so jobs beyond the limit stay queued.
+            loop {
+                let permit = slots
+                    .clone()
+                    .acquire_owned()
+                    .await
+                    .expect("scan semaphore is never closed");
+                match claim_next_job(&pool).await {
+                    Ok(Some(job)) => {
+                        let pool = pool.clone();
+                        tokio::spawn(async move {
+                            run_job(&pool, job, timeout, config.timeout, permit).await
+                        });
+                    }
+                    Ok(None) => break,
+                    Err(err) => {
+                        tracing::error!(error = ?err, "scan worker: failed to claim job");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Jobs whose worker stopped heartbeating (crash, restart) are marked failed
+/// instead of staying `running` forever.
+async fn fail_stale_jobs(pool: &PgPool, timeout: Duration) -> Result<u64, sqlx::Error> {
+    let failed: Vec<ScanJob> = sqlx::query_as(
+        "UPDATE scan_jobs
+         SET status = 'failed', error = 'scan worker stopped responding', finished_at = NOW()
+         WHERE status = 'running' AND heartbeat_at < NOW() - make_interval(secs => $1)
+         RETURNING *",
+    )
+    .bind(timeout.as_secs_f64())
+    .fetch_all(pool)
+    .await?;
+    if !failed.is_empty() {
+        tracing::warn!(count = failed.len(), "scan worker: failed stale jobs");
+    }
+    let count = failed.len() as u64;
+    for job in failed {
+        crate::webhooks::dispatch_scan_callback(pool.clone(), job);
+    }
+    Ok(count)
+}
+
+async fn claim_next_job(pool: &PgPool) -> Result<Option<ScanJob>, sqlx::Error> {
+    sqlx::query_as(
+        "UPDATE scan_jobs
+         SET status = 'running', started_at = NOW(), heartbeat_at = NOW()
+         WHERE id = (
+             SELECT id FROM scan_jobs
+             WHERE status = 'queued'
+             ORDER BY created_at
+             FOR UPDATE SKIP LOCKED
+             LIMIT 1
+         )
+         RETURNING *",
+    )
+    .fetch_optional(pool)
+    .await
+}
+
+async fn run_job(
+    pool: &PgPool,
+    job: ScanJob,
+    timeout: Duration,
+    scan_timeout: Option<Duration>,
+    permit: OwnedSemaphorePermit,
+) {
+    let job_id = job.id;
+    let source = job.source.clone();
+    let stored = match suppression_reasons(pool, job.contract_id).await {
+        Ok(stored) => stored,
+        Err(err) => {
+            tracing::error!(job_id = %job_id, error = ?err, "scan worker: failed to load suppressions");
+            if let Err(err) = fail_job(pool, job_id, "could not load finding suppressions").await {
+                tracing::error!(job_id = %job_id, error = ?err, "scan worker: failed to record job result");
+            }
+            return;
+        }
+    };
+    crate::detector::refresh_dynamic_rules(pool).await;
+    let cache_key = ScanCacheKey::new(&source, &rule_set_version(), &stored);
+    match cached_scan(pool, &cache_key).await {
+        Ok(Some(scan)) => {
+            drop(permit);
+            tracing::debug!(job_id = %job_id, artifact = %cache_key.artifact_sha256, "scan worker: reusing cached scan");
+            if let Err(err) = complete_job(pool, &job, scan, true).await {
+                tracing::error!(job_id = %job_id, error = ?err, "scan worker: failed to record job result");
+            }
+            return;
+        }
+        Ok(None) => {}
+        // The cache only saves work; scan as if it were empty
+        Err(err) => {
+            tracing::warn!(job_id = %job_id, error = ?err, "scan worker: failed to read scan cache")
+        }
+    }
+
+    let scan = await_scan(
+        spawn_scan(permit, move || scan_with_suppressions(&source, &stored)),
+        scan_timeout,
+    );
+    tokio::pin!(scan);
+
+    // Heartbeat well inside the visibility timeout while the scan runs
+    let mut heartbeat = tokio::time::interval(timeout / 3);
+    heartbeat.tick().await;
+    let outcome = loop {
+        tokio::select! {
+            result = &mut scan => break result,
+            _ = heartbeat.tick() => {
+                if let Err(err) = sqlx::query("UPDATE scan_jobs SET heartbeat_at = NOW() WHERE id = $1")
+                    .bind(job_id)
+                    .execute(pool)
+                    .await
+                {
+                    tracing::warn!(job_id = %job_id, error = ?err, "scan worker: heartbeat failed");
+                }
+            }
+        }
+    };
+
+    let result = match outcome {
+        Ok(scan) => {
+            if let Err(err) = store_cached_scan(pool, &cache_key, &scan).await {
+                tracing::warn!(job_id = %job_id, error = ?err, "scan worker: failed to cache scan");
+            }
+            complete_job(pool, &job, scan, false).await
+        }
+        Err(failure) => {
+            tracing::warn!(job_id = %job_id, reason = %failure, "scan worker: scan failed");
+            fail_job(pool, job_id, &failure.to_string()).await
+        }
+    };
+    if let Err(err) = result {
+        tracing::error!(job_id = %job_id, error = ?err, "scan worker: failed to record job result");
+    }
+}
+
+/// Claim `job_id` and run it to completion the way the worker would
+#[cfg(test)]
+pub(crate) async fn run_queued_job(pool: &PgPool, job_id: Uuid) {
+    let job: ScanJob = sqlx::query_as(
+        "UPDATE scan_jobs
+         SET status = 'running', started_at = NOW(), heartbeat_at = NOW()
+         WHERE id = $1 AND status = 'queued'
+         RETURNING *",
+    )
+    .bind(job_id)
+    .fetch_one(pool)
+    .await
+    .expect("claim scan job");
+    let permit = Arc::new(Semaphore::new(1))
+        .acquire_owned()
+        .await
+        .expect("fresh semaphore has a permit");
+    run_job(pool, job, visibility_timeout(), None, permit).await;
+}
+
+async fn complete_job(
+    pool: &PgPool,
+    job: &ScanJob,
+    scan: DetectorScan,
+    from_cache: bool,
+) -> Result<(), sqlx::Error> {
+    let DetectorScan { findings, suppressed } = scan;
+    let fail_on = job.fail_on.as_deref().and_then(crate::detector::parse_severity_label);
+    let passed = passes_gate(&findings, fail_on.as_ref());
+
+    if let Some(version) = &job.version {
+        let previous = latest_version_findings(pool, job.contract_id, version).await?;
+        record_version_scan(pool, job.contract_id, version, &findings).await?;
+
+        if job.rescan {
+            let new_critical = new_critical_findings(previous.as_deref().unwrap_or(&[]), &findings);
+            if !new_critical.is_empty() {
+                tracing::warn!(
+                    contract_id = %job.contract_id,
+                    version = %version,
+                    count = new_critical.len(),
+                    "re-scan found new critical findings"
+                );
+                crate::webhooks::dispatch_new_critical_findings(
+                    pool.clone(),
+                    job.contract_id,
+                    version.clone(),
+                    new_critical,
+                );
+            }
+            crate::scoring::recompute_contract_score(pool, job.contract_id).await?;
+        }
+    }
+
+    let completed: Option<ScanJob> = sqlx::query_as(
+        "UPDATE scan_jobs
+         SET status = 'completed', findings = $2, suppressed = $3, passed = $4,
+             from_cache = $5, finished_at = NOW()
+         WHERE id = $1 AND status = 'running'
+         RETURNING *",
+    )
+    .bind(job.id)
+    .bind(sqlx::types::Json(&findings))
+    .bind(sqlx::types::Json(&suppressed))
+    .bind(passed)
+    .bind(from_cache)
+    .fetch_optional(pool)
+    .await?;
+    if let Some(completed) = completed {
+        crate::webhooks::dispatch_scan_callback(pool.clone(), completed);
+    }
+    crate::metrics::record_scan_completed(if from_cache {
+        crate::metrics::SCAN_KIND_CACHED
+    } else {
+        crate::metrics::SCAN_KIND_DETECTOR
+    });
+    Ok(())
+}
+
+async fn fail_job(pool: &PgPool, job_id: Uuid, reason: &str) -> Result<(), sqlx::Error> {
+    let failed: Option<ScanJob> = sqlx::query_as(
+        "UPDATE scan_jobs SET status = 'failed', error = $2, finished_at = NOW()
+         WHERE id = $1 AND status = 'running'
+         RETURNING *",
+    )
+    .bind(job_id)
+    .bind(reason)
+    .fetch_optional(pool)
+    .await?;
+    if let Some(failed) = failed {
+        crate::webhooks::dispatch_scan_callback(pool.clone(), failed);
+    }
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Batch scans (`scan_batches`)
+// ─────────────────────────────────────────────────────────
+
+/// Most contracts one `POST /api/scan/batch` may list
+pub const MAX_BATCH_SCAN_ITEMS: usize = 50;
+
+/// One contract+version of a batch
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchScanItem {
+    pub contract_id: Uuid,
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Source to scan; when absent, the source of the latest scan of this
+    /// contract+version is scanned again
+    #[serde(default)]
+    pub source: Option<String>,
+}
+
+/// Request body for `POST /api/scan/batch`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct BatchScanRequest {
+    pub items: Vec<BatchScanItem>,
+    /// `low|medium|high|critical`: the batch fails when any finding of any
+    /// item is at or above this level
+    #[serde(default)]
+    pub fail_on: Option<String>,
+}
+
+/// A batch item as queued: the job scanning it, or why none was queued
+#[derive(Debug, Clone, Serialize)]
+pub struct QueuedBatchItem {
+    pub contract_id: Uuid,
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<Uuid>,
+    /// The contract+version already had a scan in flight, which is reused
+    pub deduplicated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response to `POST /api/scan/batch`
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanBatchSubmission {
+    pub batch_id: Uuid,
+    pub items: Vec<QueuedBatchItem>,
+}
+
+/// Source of the most recent scan of a contract+version, if any
+async fn latest_scan_source(
+    conn: &mut PgConnection,
+    contract_id: Uuid,
+    version: Option<&str>,
+) -> Result<Option<String>, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT source FROM scan_jobs
+         WHERE contract_id = $1 AND COALESCE(version, '') = COALESCE($2, '')
+         ORDER BY created_at DESC
+         LIMIT 1",
+    )
+    .bind(contract_id)
+    .bind(version)
+    .fetch_optional(conn)
+    .await
+}
+
+/// Why an item naming a contract the caller can't see was not queued. Unknown
+/// and private contracts read the same, so a batch can't probe for either.
+pub fn contract_not_found(contract_id: Uuid) -> String {
+    format!("contract {} not found", contract_id)
+}
+
+async fn queue_batch_item(
+    conn: &mut PgConnection,
+    item: &BatchScanItem,
+    fail_on: Option<&String>,
+    visible: &HashSet<Uuid>,
+) -> Result<QueuedBatchItem, sqlx::Error> {
+    let mut queued = QueuedBatchItem {
+        contract_id: item.contract_id,
+        version: item.version.clone(),
+        job_id: None,
+        deduplicated: false,
+        error: None,
+    };
+    if !visible.contains(&item.contract_id) {
+        queued.error = Some(contract_not_found(item.contract_id));
+        return Ok(queued);
+    }
+    let source = match &item.source {
+        Some(source) => Some(source.clone()),
+        None => latest_scan_source(conn, item.contract_id, item.version.as_deref()).await?,
+    };
+    let Some(source) = source else {
+        queued.error = Some("no source given and none on record from an earlier scan".into());
+        return Ok(queued);
+    };
+
+    let request = SourceScanRequest {
+        contract_id: item.contract_id,
+        version: item.version.clone(),
+        source,
+        fail_on: fail_on.cloned(),
+        callback_url: None,
+        callback_secret: None,
+    };
+    let (job, created) = queue_scan_job(conn, &request).await?;
+    queued.job_id = Some(job.id);
+    queued.deduplicated = !created;
+    Ok(queued)
+}
+
+/// Queue one job per item and record the batch, all in one transaction so a
+/// failed submission leaves no orphaned jobs behind. Items whose contract is
+/// not in `visible` are recorded as not found; an item that cannot be queued
+/// (no source) is kept with its reason rather than failing the batch.
+pub async fn enqueue_scan_batch(
+    pool: &PgPool,
+    request: &BatchScanRequest,
+    visible: &HashSet<Uuid>,
+) -> Result<ScanBatchSubmission, sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    let mut items = Vec::with_capacity(request.items.len());
+    for item in &request.items {
+        let fail_on = request.fail_on.as_ref();
+        items.push(queue_batch_item(&mut *tx, item, fail_on, visible).await?);
+    }
+
+    let batch_id: Uuid =
+        sqlx::query_scalar("INSERT INTO scan_batches (fail_on) VALUES ($1) RETURNING id")
+            .bind(&request.fail_on)
+            .fetch_one(&mut *tx)
+            .await?;
+    for (position, item) in items.iter().enumerate() {
+        sqlx::query(
+            "INSERT INTO scan_batch_items (batch_id, position, contract_id, version, job_id, error)
+             VALUES ($1, $2, $3, $4, $5, $6)",
+        )
+        .bind(batch_id)
+        .bind(position as i32)
+        .bind(item.contract_id)
+        .bind(&item.version)
+        .bind(item.job_id)
+        .bind(&item.error)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    Ok(ScanBatchSubmission { batch_id, items })
+}
+
+#[derive(Debug, Clone, FromRow)]
+pub struct ScanBatchRow {
+    pub id: Uuid,
+    pub fail_on: Option<String>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// A batch item joined with the current state of its job
+#[derive(Debug, Clone, FromRow)]
+pub struct BatchItemRow {
+    pub contract_id: Uuid,
+    pub version: Option<String>,
+    pub job_id: Option<Uuid>,
+    /// Why the item was never queued
+    pub queue_error: Option<String>,
+    pub status: Option<ScanJobStatus>,
+    pub findings: Option<sqlx::types::Json<Vec<ScanFinding>>>,
+    pub job_error: Option<String>,
+}
+
+pub async fn get_scan_batch(
+    pool: &PgPool,
+    batch_id: Uuid,
+) -> Result<Option<(ScanBatchRow, Vec<BatchItemRow>)>, sqlx::Error> {
+    let batch: Option<ScanBatchRow> = sqlx::query_as("SELECT * FROM scan_batches WHERE id = $1")
+        .bind(batch_id)
+        .fetch_optional(pool)
+        .await?;
+    let Some(batch) = batch else {
+        return Ok(None);
+    };
+    let items = sqlx::query_as(
+        "SELECT i.contract_id, i.version, i.job_id, i.error AS queue_error,
+                j.status, j.findings, j.error AS job_error
+         FROM scan_batch_items i
+         LEFT JOIN scan_jobs j ON j.id = i.job_id
+         WHERE i.batch_id = $1
+         ORDER BY i.position",
+    )
+    .bind(batch_id)
+    .fetch_all(pool)
+    .await?;
+    Ok(Some((batch, items)))
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ScanBatchStatus {
+    /// At least one job is queued or running
+    Running,
+    /// Every item has completed or failed
+    Completed,
+}
+
+/// One item of a batch report
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanBatchItemReport {
+    pub contract_id: Uuid,
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub job_id: Option<Uuid>,
+    pub status: ScanJobStatus,
+    /// Findings per severity, once the scan has completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub findings: Option<SeverityCounts>,
+    /// Against the batch's `fail_on`, once the scan has completed
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passed: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// Response to `GET /api/scan/batch/:batch_id`
+#[derive(Debug, Clone, Serialize)]
+pub struct ScanBatchReport {
+    pub batch_id: Uuid,
+    pub fail_on: Option<String>,
+    pub status: ScanBatchStatus,
+    pub total: usize,
+    pub completed: usize,
+    pub failed: usize,
+    pub pending: usize,
+    /// Findings per severity across every completed item
+    pub findings: SeverityCounts,
+    /// Set once the batch has finished: true when at least one item was
+    /// scanned and none reached `fail_on`. Failed items are reported per item
+    /// and do not fail the batch.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub passed: Option<bool>,
+    pub created_at: DateTime<Utc>,
+    pub items: Vec<ScanBatchItemReport>,
+}
+
+/// Roll a batch's items up into its report
+pub fn summarize_batch(batch: ScanBatchRow, rows: Vec<BatchItemRow>) -> ScanBatchReport {
+    let fail_on = batch
+        .fail_on
+        .as_deref()
+        .and_then(crate::detector::parse_severity_label);
+    let mut findings = SeverityCounts::default();
+    let (mut completed, mut failed, mut pending) = (0, 0, 0);
+    let mut all_passed = true;
+
+    let items: Vec<ScanBatchItemReport> = rows
+        .into_iter()
+        .map(|row| {
+            let mut item = ScanBatchItemReport {
+                contract_id: row.contract_id,
+                version: row.version,
+                job_id: row.job_id,
+                status: ScanJobStatus::Failed,
+                findings: None,
+                passed: None,
+                error: None,
+            };
+            match (row.status, row.findings) {
+                (Some(ScanJobStatus::Completed), job_findings) => {
+                    let job_findings = job_findings.map(|f| f.0).unwrap_or_default();
+                    let counts = SeverityCounts::from_findings(&job_findings);
+                    for finding in &job_findings {
+                        findings.add(&finding.severity, 1);
+                    }
+                    let passed = passes_gate(&job_findings, fail_on.as_ref());
+                    all_passed &= passed;
+                    completed += 1;
+                    item.status = ScanJobStatus::Completed;
+                    item.findings = Some(counts);
+                    item.passed = Some(passed);
+                }
+                (Some(status @ (ScanJobStatus::Queued | ScanJobStatus::Running)), _) => {
+                    pending += 1;
+                    item.status = status;
+                }
+                (Some(ScanJobStatus::Failed), _) => {
+                    failed += 1;
+                    item.error = row.job_error;
+                }
+                (None, _) => {
+                    failed += 1;
+                    item.error = Some(
+                        row.queue_error
+                            .unwrap_or_else(|| "scan job no longer exists".to_string()),
+                    );
+                }
+            }
+            item
+        })
+        .collect();
+
+    let status = if pending > 0 {
+        ScanBatchStatus::Running
+    } else {
+        ScanBatchStatus::Completed
+    };
+    ScanBatchReport {
+        batch_id: batch.id,
+        fail_on: batch.fail_on,
+        status,
+        total: items.len(),
+        completed,
+        failed,
+        pending,
+        findings,
+        passed: (status == ScanBatchStatus::Completed).then_some(completed > 0 && all_passed),
+        created_at: batch.created_at,
+        items,
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Scan result cache (`scan_result_cache`)
+// ─────────────────────────────────────────────────────────
+
+/// Bump when a built-in detector's logic changes without its checklist
+/// entry changing, so cached results from the old logic stop matching
+pub const DETECTOR_LOGIC_VERSION: u32 = 1;
+
+/// Cached scans older than this are ignored, and pruned as new ones are
+/// stored. Rule changes already stop old entries matching; the age limit
+/// keeps the table bounded for artifacts that are never scanned again.
+pub const SCAN_CACHE_TTL_DAYS: i32 = 30;
+
+/// Identifies the rules a scan runs with: every checklist entry, built-in,
+/// file-loaded or dynamic, with its severity and patterns
+pub fn rule_set_version() -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(DETECTOR_LOGIC_VERSION.to_be_bytes());
+    for check in all_checks() {
+        // Serializing a checklist entry cannot fail
+        hasher.update(serde_json::to_vec(&check).unwrap_or_default());
+        hasher.update(b"\n");
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// What a scan's result depends on. Stored suppressions are part of it
+/// because they decide which findings are active.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ScanCacheKey {
+    pub artifact_sha256: String,
+    pub rule_set_version: String,
+    pub suppressions_sha256: String,
+}
+
+impl ScanCacheKey {
+    pub fn new(source: &str, rule_set_version: &str, stored: &HashMap<String, String>) -> Self {
+        let mut suppressions = Sha256::new();
+        for (fingerprint, reason) in stored.iter().collect::<BTreeMap<_, _>>() {
+            suppressions.update(fingerprint.as_bytes());
+            suppressions.update([0]);
+            suppressions.update(reason.as_bytes());
+            suppressions.update([0]);
+        }
+        Self {
+            artifact_sha256: hex::encode(Sha256::digest(source.as_bytes())),
+            rule_set_version: rule_set_version.to_string(),
+            suppressions_sha256: hex::encode(suppressions.finalize()),
+        }
+    }
+}
+
+async fn cached_scan(
+    pool: &PgPool,
+    key: &ScanCacheKey,
+) -> Result<Option<DetectorScan>, sqlx::Error> {
+    let cached: Option<sqlx::types::Json<DetectorScan>> = sqlx::query_scalar(
+        "SELECT scan FROM scan_result_cache
+         WHERE artifact_sha256 = $1 AND rule_set_version = $2 AND suppressions_sha256 = $3
+           AND created_at > NOW() - make_interval(days => $4)",
+    )
+    .bind(&key.artifact_sha256)
+    .bind(&key.rule_set_version)
+    .bind(&key.suppressions_sha256)
+    .bind(SCAN_CACHE_TTL_DAYS)
+    .fetch_optional(pool)
+    .await?;
+    Ok(cached.map(|scan| scan.0))
+}
+
+async fn store_cached_scan(
+    pool: &PgPool,
+    key: &ScanCacheKey,
+    scan: &DetectorScan,
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO scan_result_cache (artifact_sha256, rule_set_version, suppressions_sha256, scan)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (artifact_sha256, rule_set_version, suppressions_sha256)
+         DO UPDATE SET scan = EXCLUDED.scan, created_at = NOW()",
+    )
+    .bind(&key.artifact_sha256)
+    .bind(&key.rule_set_version)
+    .bind(&key.suppressions_sha256)
+    .bind(sqlx::types::Json(scan))
+    .execute(pool)
+    .await?;
+    sqlx::query(
+        "DELETE FROM scan_result_cache WHERE created_at < NOW() - make_interval(days => $1)",
+    )
+    .bind(SCAN_CACHE_TTL_DAYS)
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+// ─────────────────────────────────────────────────────────
+// Stored suppressions
+// ─────────────────────────────────────────────────────────
+
+/// A finding fingerprint the contract owner has chosen to silence
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FindingSuppression {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    /// `RULE-ID@location`, as reported on scan findings
+    pub fingerprint: String,
+    pub reason: String,
+    /// Publisher that added it; `None` for the admin key
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Request body for adding a stored suppression
+#[derive(Debug, Deserialize)]
+pub struct CreateSuppressionRequest {
+    pub fingerprint: String,
+    pub reason: String,
+}
+
+/// Stored suppressions for a contract as fingerprint -> reason
+pub async fn suppression_reasons(
+    pool: &PgPool,
+    contract_id: Uuid,
+) -> Result<HashMap<String, String>, sqlx::Error> {
+    let rows: Vec<(String, String)> =
+        sqlx::query_as("SELECT fingerprint, reason FROM finding_suppressions WHERE contract_id = $1")
+            .bind(contract_id)
+            .fetch_all(pool)
+            .await?;
+    Ok(rows.into_iter().collect())
+}
+
+/// A page of a contract's suppressions, oldest first, with their total count
+pub async fn list_suppressions(
+    pool: &PgPool,
+    contract_id: Uuid,
+    limit: i64,
+    offset: i64,
+) -> Result<(Vec<FindingSuppression>, i64), sqlx::Error> {
+    let suppressions = sqlx::query_as(
+        "SELECT * FROM finding_suppressions WHERE contract_id = $1
+         ORDER BY created_at, id
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(contract_id)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(pool)
+    .await?;
+    let total =
+        sqlx::query_scalar("SELECT COUNT(*) FROM finding_suppressions WHERE contract_id = $1")
+            .bind(contract_id)
+            .fetch_one(pool)
+            .await?;
+    Ok((suppressions, total))
+}
+
+/// Add a suppression, or replace the reason of an existing one for the same fingerprint
+pub async fn upsert_suppression(
+    pool: &PgPool,
+    contract_id: Uuid,
+    fingerprint: &str,
+    reason: &str,
+    created_by: Option<Uuid>,
+) -> Result<FindingSuppression, sqlx::Error> {
+    sqlx::query_as(
+        "INSERT INTO finding_suppressions (contract_id, fingerprint, reason, created_by)
+         VALUES ($1, $2, $3, $4)
+         ON CONFLICT (contract_id, fingerprint)
+         DO UPDATE SET reason = EXCLUDED.reason, created_by = EXCLUDED.created_by
+         RETURNING *",
+    )
+    .bind(contract_id)
+    .bind(fingerprint)
+    .bind(reason)
+    .bind(created_by)
+    .fetch_one(pool)
+    .await
+}
+
+/// Remove a suppression; false when the contract has no such suppression
+pub async fn delete_suppression(
+    pool: &PgPool,
+    contract_id: Uuid,
+    suppression_id: Uuid,
+) -> Result<bool, sqlx::Error> {
+    let result = sqlx::query("DELETE FROM finding_suppressions WHERE id = $1 AND contract_id = $2")
+        .bind(suppression_id)
+        .bind(contract_id)
+        .execute(pool)
+        .await?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Critical findings in `current` that `previous` didn't have
+pub fn new_critical_findings(previous: &[ScanFinding], current: &[ScanFinding]) -> Vec<ScanFinding> {
+    let known: std::collections::HashSet<String> =
+        previous.iter().map(ScanFinding::fingerprint).collect();
+    current
+        .iter()
+        .filter(|f| f.severity == Severity::Critical && !known.contains(&f.fingerprint()))
+        .cloned()
+        .collect()
+}
+
+// ─────────────────────────────────────────────────────────
+// Scheduled re-scans
+// ─────────────────────────────────────────────────────────
+
+pub const RESCAN_INTERVAL_ENV: &str = "RESCAN_INTERVAL_SECS";
+pub const RESCAN_MAX_AGE_ENV: &str = "RESCAN_MAX_AGE_DAYS";
+pub const RESCAN_BATCH_ENV: &str = "RESCAN_BATCH_SIZE";
+
+/// How often stale scans are re-queued, and how many at a time
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RescanConfig {
+    pub interval: Duration,
+    /// Scans older than this are re-run
+    pub max_age_days: u32,
+    /// Most re-scans queued at once; also caps how many may wait in the
+    /// queue, so the worker is never flooded
+    pub batch_size: u32,
+}
+
+impl Default for RescanConfig {
+    fn default() -> Self {
+        Self {
+            interval: Duration::from_secs(6 * 3600),
+            max_age_days: 30,
+            batch_size: 20,
+        }
+    }
+}
+
+impl RescanConfig {
+    pub fn from_env() -> Result<Self, String> {
+        Self::from_lookup(|name| std::env::var(name).ok())
+    }
+
+    pub(crate) fn from_lookup(lookup: impl Fn(&str) -> Option<String>) -> Result<Self, String> {
+        let defaults = Self::default();
+        let positive = |name: &str, default: u64| -> Result<u64, String> {
+            match lookup(name) {
+                None => Ok(default),
+                Some(raw) => match raw.trim().parse::<u64>() {
+                    Ok(value) if value > 0 => Ok(value),
+                    _ => Err(format!("{} must be a positive integer (got '{}')", name, raw)),
+                },
+            }
+        };
+        let to_u32 = |name: &str, value: u64| {
+            u32::try_from(value).map_err(|_| format!("{} is too large", name))
+        };
+        Ok(Self {
+            interval: Duration::from_secs(positive(RESCAN_INTERVAL_ENV, defaults.interval.as_secs())?),
+            max_age_days: to_u32(
+                RESCAN_MAX_AGE_ENV,
+                positive(RESCAN_MAX_AGE_ENV, defaults.max_age_days as u64)?,
+            )?,
+            batch_size: to_u32(
+                RESCAN_BATCH_ENV,
+                positive(RESCAN_BATCH_ENV, defaults.batch_size as u64)?,
+            )?,
+        })
+    }
+}
+
+/// Spawn the scheduler that re-queues versions whose last scan has gone
+/// stale, so improved rules reach contracts scanned long ago.
+pub fn spawn_rescan_scheduler(pool: PgPool, config: RescanConfig) {
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(config.interval);
+        loop {
+            interval.tick().await;
+            match enqueue_stale_rescans(&pool, &config).await {
+                Ok(0) => {}
+                Ok(queued) => tracing::info!(queued, "rescan: queued stale scans"),
+                Err(err) => tracing::error!(error = ?err, "rescan: failed to queue stale scans"),
+            }
+        }
+    });
+}
+
+/// Queue re-scans for versions whose latest scan finished more than
+/// `max_age_days` ago, oldest first. Only versioned scans are re-run: those
+/// are what scan-diff and scoring read. Returns the number queued.
+///
+/// A version whose source and rules are unchanged is answered from
+/// `scan_result_cache`, so re-queuing it costs no detector run.
+pub async fn enqueue_stale_rescans(pool: &PgPool, config: &RescanConfig) -> Result<u64, sqlx::Error> {
+    let waiting: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM scan_jobs WHERE rescan AND status IN ('queued', 'running')",
+    )
+    .fetch_one(pool)
+    .await?;
+    let budget = config.batch_size as i64 - waiting;
+    if budget <= 0 {
+        return Ok(0);
+    }
+
+    let result = sqlx::query(
+        "WITH latest AS (
+             SELECT DISTINCT ON (contract_id, version) contract_id, version, source, status, finished_at
+             FROM scan_jobs
+             WHERE version IS NOT NULL
+             ORDER BY contract_id, version, created_at DESC
+         )
+         INSERT INTO scan_jobs (contract_id, version, source, rescan)
+         SELECT contract_id, version, source, TRUE
+         FROM latest
+         WHERE status = 'completed' AND finished_at < NOW() - make_interval(days => $1)
+         ORDER BY finished_at
+         LIMIT $2
+         ON CONFLICT (contract_id, (COALESCE(version, ''))) WHERE status IN ('queued', 'running')
+         DO NOTHING",
+    )
+    .bind(config.max_age_days as i32)
+    .bind(budget)
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+/// Map a free-form severity label (CVE feeds use `HIGH`, `moderate`, ...)
+pub fn parse_severity(raw: &str) -> Severity {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "critical" => Severity::Critical,
+        "high" => Severity::High,
+        "medium" | "moderate" => Severity::Medium,
+        "low" => Severity::Low,
+        _ => Severity::Info,
+    }
+}
+
+pub async fn record_version_scan(
+    pool: &PgPool,
+    contract_id: Uuid,
+    version: &str,
+    findings: &[ScanFinding],
+) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO contract_version_scans (contract_id, version, findings) VALUES ($1, $2, $3)",
+    )
+    .bind(contract_id)
+    .bind(version)
+    .bind(sqlx::types::Json(findings))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Findings of the most recent scan of a version, or `None` if never scanned
+pub async fn latest_version_findings(
+    pool: &PgPool,
+    contract_id: Uuid,
+    version: &str,
+) -> Result<Option<Vec<ScanFinding>>, sqlx::Error> {
+    let row: Option<(sqlx::types::Json<Vec<ScanFinding>>,)> = sqlx::query_as(
+        "SELECT findings FROM contract_version_scans
+         WHERE contract_id = $1 AND version = $2
+         ORDER BY scanned_at DESC
+         LIMIT 1",
+    )
+    .bind(contract_id)
+    .bind(version)
+    .fetch_optional(pool)
+    .await?;
+    Ok(row.map(|(findings,)| findings.0))
+}
+
+/// Split findings into (added, removed, unchanged) by fingerprint.
+/// Unchanged findings are reported as they appear in `to`.
+pub fn diff_findings(
+    from: Vec<ScanFinding>,
+    to: Vec<ScanFinding>,
+) -> (Vec<ScanFinding>, Vec<ScanFinding>, Vec<ScanFinding>) {
+    let mut before: BTreeMap<String, ScanFinding> =
+        from.into_iter().map(|f| (f.fingerprint(), f)).collect();
+    let after: BTreeMap<String, ScanFinding> =
+        to.into_iter().map(|f| (f.fingerprint(), f)).collect();
+
+    let mut added = Vec::new();
+    let mut unchanged = Vec::new();
+    for (fingerprint, finding) in after {
+        if before.remove(&fingerprint).is_some() {
+            unchanged.push(finding);
+        } else {
+            added.push(finding);
+        }
+    }
+    let removed = before.into_values().collect();
+
+    (added, removed, unchanged)
+}
+
 pub async fn get_history(pool: &PgPool, contract_id: Uuid) -> Result<ScanReport, sqlx::Error> {
     let rows = sqlx::query_as!(
         ScanResultRow,
@@ -165,9 +1839,480 @@ pub async fn get_history(pool: &PgPool, contract_id: Uuid) -> Result<ScanReport,
     .await?
     .unwrap_or(0);
 
+    let dependencies: Vec<(String, String)> = sqlx::query_as(
+        "SELECT package_name, version FROM contract_dependencies WHERE contract_id = $1",
+    )
+    .bind(contract_id)
+    .fetch_all(pool)
+    .await?;
+    let advisories = advisory_findings(pool, &dependencies).await?;
+
     Ok(ScanReport {
         contract_id,
         findings: rows,
+        advisories,
         scanned_dependencies_count: dep_count as usize,
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn finding(rule: &str, location: &str, severity: Severity) -> ScanFinding {
+        ScanFinding {
+            rule_id: rule.to_string(),
+            severity,
+            location: location.to_string(),
+            message: String::new(),
+        }
+    }
+
+    fn batch_item(status: Option<ScanJobStatus>, findings: Vec<ScanFinding>) -> BatchItemRow {
+        BatchItemRow {
+            contract_id: Uuid::new_v4(),
+            version: Some("1.0.0".into()),
+            job_id: status.map(|_| Uuid::new_v4()),
+            queue_error: status.is_none().then(|| "contract not found".into()),
+            status,
+            findings: Some(sqlx::types::Json(findings)),
+            job_error: None,
+        }
+    }
+
+    #[test]
+    fn batch_rolls_up_findings_and_reports_failures_per_item() {
+        let batch = ScanBatchRow {
+            id: Uuid::new_v4(),
+            fail_on: Some("high".into()),
+            created_at: Utc::now(),
+        };
+        let rows = vec![
+            batch_item(
+                Some(ScanJobStatus::Completed),
+                vec![finding("EL-001", "line 3", Severity::Low)],
+            ),
+            batch_item(
+                Some(ScanJobStatus::Completed),
+                vec![
+                    finding("IV-001", "line 2", Severity::Critical),
+                    finding("EL-001", "line 8", Severity::Low),
+                ],
+            ),
+            batch_item(Some(ScanJobStatus::Failed), vec![]),
+            batch_item(None, vec![]),
+        ];
+
+        let report = summarize_batch(batch.clone(), rows.clone());
+        assert_eq!(report.status, ScanBatchStatus::Completed);
+        assert_eq!((report.total, report.completed, report.failed), (4, 2, 2));
+        assert_eq!((report.findings.critical, report.findings.low), (1, 2));
+        assert_eq!(report.passed, Some(false));
+        assert_eq!(report.items[0].passed, Some(true));
+        assert_eq!(report.items[3].error.as_deref(), Some("contract not found"));
+
+        // Failed items alone do not fail the batch
+        let report = summarize_batch(batch.clone(), vec![rows[0].clone(), rows[2].clone()]);
+        assert_eq!(report.passed, Some(true));
+
+        let mut running = rows;
+        running[1].status = Some(ScanJobStatus::Running);
+        let report = summarize_batch(batch, running);
+        assert_eq!(report.status, ScanBatchStatus::Running);
+        assert_eq!(report.pending, 1);
+        assert_eq!(report.passed, None);
+    }
+
+    #[test]
+    fn only_unseen_critical_findings_are_new() {
+        let previous = vec![
+            finding("IV-001", "line 2", Severity::Critical),
+            finding("EL-001", "source", Severity::Low),
+        ];
+        let current = vec![
+            finding("IV-001", "line 2", Severity::Critical),
+            finding("IV-001", "line 9", Severity::Critical),
+            finding("AC-002", "line 4", Severity::High),
+        ];
+        let new = new_critical_findings(&previous, &current);
+        assert_eq!(new, vec![finding("IV-001", "line 9", Severity::Critical)]);
+        assert!(new_critical_findings(&current, &current).is_empty());
+    }
+
+    #[test]
+    fn rescan_config_reads_env_with_defaults() {
+        let config = RescanConfig::from_lookup(|_| None).unwrap();
+        assert_eq!(config, RescanConfig::default());
+
+        let config = RescanConfig::from_lookup(|name| match name {
+            RESCAN_MAX_AGE_ENV => Some("7".into()),
+            RESCAN_BATCH_ENV => Some("5".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(config.max_age_days, 7);
+        assert_eq!(config.batch_size, 5);
+
+        assert!(RescanConfig::from_lookup(|name| (name == RESCAN_INTERVAL_ENV).then(|| "0".into())).is_err());
+        assert!(RescanConfig::from_lookup(|name| (name == RESCAN_BATCH_ENV).then(|| "lots".into())).is_err());
+    }
+
+    #[test]
+    fn scanner_config_defaults_to_one_timed_scan() {
+        let config = ScannerConfig::from_lookup(|_| None).unwrap();
+        assert_eq!(config, ScannerConfig::default());
+        assert_eq!(config.timeout, Some(DEFAULT_SCAN_TIMEOUT));
+        assert_eq!(config.max_concurrency, 1);
+
+        let config = ScannerConfig::from_lookup(|name| match name {
+            SCANNER_TIMEOUT_ENV => Some("30".into()),
+            SCANNER_CONCURRENCY_ENV => Some("4".into()),
+            _ => None,
+        })
+        .unwrap();
+        assert_eq!(config.timeout, Some(Duration::from_secs(30)));
+        assert_eq!(config.max_concurrency, 4);
+
+        let config = ScannerConfig::from_lookup(|name| (name == SCANNER_TIMEOUT_ENV).then(|| "0".into())).unwrap();
+        assert_eq!(config.timeout, None);
+        assert!(ScannerConfig::from_lookup(|name| (name == SCANNER_CONCURRENCY_ENV).then(|| "0".into())).is_err());
+        assert!(ScannerConfig::from_lookup(|name| (name == SCANNER_TIMEOUT_ENV).then(|| "soon".into())).is_err());
+    }
+
+    #[tokio::test]
+    async fn scan_exceeding_the_timeout_fails() {
+        let slots = Arc::new(Semaphore::new(1));
+        let permit = slots.clone().acquire_owned().await.unwrap();
+        let scan = spawn_scan(permit, || std::thread::sleep(Duration::from_millis(300)));
+
+        let failure = await_scan(scan, Some(Duration::from_millis(20))).await.unwrap_err();
+        assert!(matches!(failure, ScanFailure::TimedOut(_)));
+        assert!(failure.to_string().contains("timed out"), "{}", failure);
+
+        // The runaway detector thread still holds its slot
+        assert!(slots.clone().try_acquire_owned().is_err());
+
+        let permit = Arc::new(Semaphore::new(1)).acquire_owned().await.unwrap();
+        let quick = spawn_scan(permit, || 7);
+        assert_eq!(await_scan(quick, Some(Duration::from_secs(5))).await, Ok(7));
+    }
+
+    #[tokio::test]
+    async fn scan_beyond_max_concurrency_waits_for_a_slot() {
+        let config = ScannerConfig {
+            timeout: None,
+            max_concurrency: 2,
+        };
+        let slots = Arc::new(Semaphore::new(config.max_concurrency));
+        let (release, wait) = std::sync::mpsc::channel::<()>();
+        let wait = Arc::new(std::sync::Mutex::new(wait));
+
+        let mut running = Vec::new();
+        for _ in 0..config.max_concurrency {
+            let permit = slots.clone().acquire_owned().await.unwrap();
+            let wait = wait.clone();
+            running.push(spawn_scan(permit, move || wait.lock().unwrap().recv().unwrap()));
+        }
+
+        // The N+1th scan can't get a slot while N are running
+        let next = tokio::time::timeout(Duration::from_millis(50), slots.clone().acquire_owned()).await;
+        assert!(next.is_err());
+
+        release.send(()).unwrap();
+        let permit = tokio::time::timeout(Duration::from_secs(5), slots.clone().acquire_owned())
+            .await
+            .expect("a slot frees up once a scan finishes")
+            .unwrap();
+        drop(permit);
+
+        release.send(()).unwrap();
+        for scan in running {
+            await_scan(scan, None).await.unwrap();
+        }
+        assert_eq!(slots.available_permits(), config.max_concurrency);
+    }
+
+    #[test]
+    fn counts_findings_per_severity() {
+        let counts = SeverityCounts::from_findings(&[
+            finding("A", "x", Severity::Critical),
+            finding("B", "x", Severity::High),
+            finding("C", "x", Severity::High),
+            finding("D", "x", Severity::Info),
+        ]);
+        assert_eq!(
+            counts,
+            SeverityCounts { critical: 1, high: 2, medium: 0, low: 0, info: 1 }
+        );
+        assert_eq!(SeverityCounts::from_findings(&[]), SeverityCounts::default());
+    }
+
+    #[test]
+    fn diff_matches_by_rule_and_location() {
+        let from = vec![
+            finding("CVE-1", "soroban-sdk@20.0.0", Severity::High),
+            finding("CVE-2", "serde@1.0.0", Severity::Low),
+        ];
+        let to = vec![
+            finding("CVE-2", "serde@1.0.0", Severity::Low),
+            finding("CVE-3", "soroban-sdk@21.0.0", Severity::Critical),
+        ];
+
+        let (added, removed, unchanged) = diff_findings(from, to);
+        assert_eq!(added, vec![finding("CVE-3", "soroban-sdk@21.0.0", Severity::Critical)]);
+        assert_eq!(removed, vec![finding("CVE-1", "soroban-sdk@20.0.0", Severity::High)]);
+        assert_eq!(unchanged, vec![finding("CVE-2", "serde@1.0.0", Severity::Low)]);
+    }
+
+    #[test]
+    fn same_rule_at_new_location_is_a_new_finding() {
+        let from = vec![finding("IV-001", "src/lib.rs:10", Severity::Critical)];
+        let to = vec![finding("IV-001", "src/lib.rs:42", Severity::Critical)];
+
+        let (added, removed, unchanged) = diff_findings(from, to);
+        assert_eq!(added.len(), 1);
+        assert_eq!(removed.len(), 1);
+        assert!(unchanged.is_empty());
+    }
+
+    #[test]
+    fn fail_on_gates_at_or_above_threshold() {
+        let findings = vec![
+            finding("IV-001", "line 3", Severity::High),
+            finding("EL-001", "source", Severity::Low),
+        ];
+        assert!(!passes_gate(&findings, Some(&Severity::High)));
+        assert!(!passes_gate(&findings, Some(&Severity::Medium)));
+        assert!(passes_gate(&findings, Some(&Severity::Critical)));
+        assert!(passes_gate(&findings, None));
+    }
+
+    #[test]
+    fn detector_findings_carry_line_locations() {
+        let findings = detector_findings("pub fn f(env: Env) {\n    let x = foo.unwrap();\n}\n");
+        let unwrap = findings.iter().find(|f| f.rule_id == "IV-001").unwrap();
+        assert_eq!(unwrap.location, "line 2");
+        assert_eq!(unwrap.severity, Severity::Critical);
+    }
+
+    const TWO_UNWRAPS: &str = "pub fn f(env: Env) {\n    let a = foo.unwrap();\n    let b = bar.unwrap();\n}\n";
+
+    #[test]
+    fn inline_suppression_excludes_the_finding_and_surfaces_the_next() {
+        let source = TWO_UNWRAPS.replace(
+            "foo.unwrap();",
+            "foo.unwrap(); // soroban-registry:ignore IV-001 set in the constructor",
+        );
+        let scan = scan_with_suppressions(&source, &HashMap::new());
+
+        let active: Vec<&str> = scan
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == "IV-001")
+            .map(|f| f.location.as_str())
+            .collect();
+        assert_eq!(active, ["line 3"]);
+        assert_eq!(scan.suppressed.len(), 1);
+        assert_eq!(scan.suppressed[0].finding.location, "line 2");
+        assert_eq!(scan.suppressed[0].suppressed_by, SuppressionSource::Inline);
+        assert_eq!(scan.suppressed[0].reason, "set in the constructor");
+    }
+
+    #[test]
+    fn each_suppressed_line_is_rescanned_once() {
+        // Every unwrap but the last is ignored inline
+        let mut source = String::from("pub fn f(env: Env) {\n");
+        for i in 0..500 {
+            source.push_str(&format!(
+                "    let v{} = foo.unwrap(); // soroban-registry:ignore IV-001 checked above\n",
+                i
+            ));
+        }
+        source.push_str("    let last = foo.unwrap();\n}\n");
+        let scan = scan_with_suppressions(&source, &HashMap::new());
+
+        let active: Vec<&str> = scan
+            .findings
+            .iter()
+            .filter(|f| f.rule_id == "IV-001")
+            .map(|f| f.location.as_str())
+            .collect();
+        assert_eq!(active, ["line 502"]);
+        let suppressed = scan
+            .suppressed
+            .iter()
+            .filter(|s| s.finding.rule_id == "IV-001")
+            .count();
+        assert_eq!(suppressed, 500);
+    }
+
+    #[test]
+    fn pragma_without_reason_does_not_suppress() {
+        let source = TWO_UNWRAPS.replace("foo.unwrap();", "foo.unwrap(); // soroban-registry:ignore IV-001");
+        let scan = scan_with_suppressions(&source, &HashMap::new());
+        assert!(scan.suppressed.is_empty());
+        assert!(scan.findings.iter().any(|f| f.fingerprint() == "IV-001@line 2"));
+    }
+
+    #[test]
+    fn stored_fingerprints_suppress() {
+        let stored = HashMap::from([("IV-001@line 2".to_string(), "audited".to_string())]);
+        let scan = scan_with_suppressions(TWO_UNWRAPS, &stored);
+        assert_eq!(scan.suppressed.len(), 1);
+        assert_eq!(scan.suppressed[0].suppressed_by, SuppressionSource::Stored);
+        assert!(scan.findings.iter().all(|f| f.fingerprint() != "IV-001@line 2"));
+        assert!(scan.findings.iter().any(|f| f.fingerprint() == "IV-001@line 3"));
+
+        // Gating and scoring only ever see the active findings
+        let gate = Some(&Severity::Critical);
+        assert!(!passes_gate(&scan.findings, gate));
+        let all_stored = HashMap::from([
+            ("IV-001@line 2".to_string(), "audited".to_string()),
+            ("IV-001@line 3".to_string(), "audited".to_string()),
+        ]);
+        let scan = scan_with_suppressions(TWO_UNWRAPS, &all_stored);
+        assert!(scan.findings.iter().all(|f| f.rule_id != "IV-001"));
+        assert_eq!(scan.suppressed.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn identical_bytes_are_served_from_cache_and_changes_rescan() {
+        let Some(state) = crate::state::AppState::for_database_tests().await else {
+            return;
+        };
+        let pool = &state.db;
+        let none = HashMap::new();
+        let rules = rule_set_version();
+        assert_eq!(rules.len(), 64);
+        // Unique per run, so entries left by earlier runs are never hits
+        let source = format!("{}// run {}\n", TWO_UNWRAPS, Uuid::new_v4());
+
+        let key = ScanCacheKey::new(&source, &rules, &none);
+        assert_eq!(cached_scan(pool, &key).await.unwrap(), None);
+        let first = scan_with_suppressions(&source, &none);
+        store_cached_scan(pool, &key, &first).await.unwrap();
+        assert_eq!(cached_scan(pool, &key).await.unwrap(), Some(first.clone()));
+
+        let changed = source.replace("foo.unwrap()", "foo.unwrap_or(0)");
+        let stored = HashMap::from([("IV-001@line 2".to_string(), "audited".to_string())]);
+        let misses = [
+            ScanCacheKey::new(&changed, &rules, &none),
+            ScanCacheKey::new(&source, &"0".repeat(64), &none),
+            ScanCacheKey::new(&source, &rules, &stored),
+        ];
+        for other in &misses {
+            assert_eq!(cached_scan(pool, other).await.unwrap(), None);
+        }
+        assert_ne!(scan_with_suppressions(&changed, &none), first);
+    }
+
+    #[tokio::test]
+    async fn cached_scans_expire_and_are_pruned() {
+        let Some(state) = crate::state::AppState::for_database_tests().await else {
+            return;
+        };
+        let pool = &state.db;
+        let none = HashMap::new();
+        let rules = rule_set_version();
+        let source = format!("{}// run {}\n", TWO_UNWRAPS, Uuid::new_v4());
+        let key = ScanCacheKey::new(&source, &rules, &none);
+        let scan = scan_with_suppressions(&source, &none);
+        store_cached_scan(pool, &key, &scan).await.unwrap();
+
+        sqlx::query(
+            "UPDATE scan_result_cache SET created_at = NOW() - make_interval(days => $2)
+             WHERE artifact_sha256 = $1",
+        )
+        .bind(&key.artifact_sha256)
+        .bind(SCAN_CACHE_TTL_DAYS + 1)
+        .execute(pool)
+        .await
+        .unwrap();
+        assert_eq!(cached_scan(pool, &key).await.unwrap(), None);
+
+        // Storing any other scan prunes the expired entry
+        let other = format!("{}// run {}\n", TWO_UNWRAPS, Uuid::new_v4());
+        let other_key = ScanCacheKey::new(&other, &rules, &none);
+        store_cached_scan(pool, &other_key, &scan).await.unwrap();
+        let left: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM scan_result_cache WHERE artifact_sha256 = $1")
+                .bind(&key.artifact_sha256)
+                .fetch_one(pool)
+                .await
+                .unwrap();
+        assert_eq!(left, 0);
+    }
+
+    fn advisory(id: &str, affected: &str) -> DependencyAdvisory {
+        DependencyAdvisory {
+            id: id.to_string(),
+            package_name: "soroban-sdk".to_string(),
+            affected: affected.to_string(),
+            severity: "high".to_string(),
+            summary: "storage keys can collide".to_string(),
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn overlapping_dependency_range_names_the_advisory() {
+        let advisories = [advisory("RSA-1", ">=20.0.0, <20.3.1")];
+        for declared in ["20.3.0", "^20.1", "~20.3", ">=19, <20.0.1", "20"] {
+            let deps = [("soroban-sdk".to_string(), declared.to_string())];
+            let findings = match_advisories(&deps, &advisories);
+            assert_eq!(findings.len(), 1, "{} should overlap", declared);
+            assert_eq!(findings[0].rule_id, "RSA-1");
+            assert_eq!(findings[0].severity, Severity::High);
+            assert!(findings[0].message.contains("RSA-1"));
+        }
+    }
+
+    #[test]
+    fn disjoint_dependency_range_is_not_reported() {
+        let advisories = [advisory("RSA-1", ">=20.0.0, <20.3.1")];
+        for declared in ["20.3.1", "^21", "~20.4", "<20", ">20.3.0, <21", "^0.9"] {
+            let deps = [("soroban-sdk".to_string(), declared.to_string())];
+            assert!(
+                match_advisories(&deps, &advisories).is_empty(),
+                "{} should not overlap",
+                declared
+            );
+        }
+
+        // Same range, other package
+        let deps = [("serde".to_string(), "20.1.0".to_string())];
+        assert!(match_advisories(&deps, &advisories).is_empty());
+    }
+
+    #[test]
+    fn caret_and_tilde_follow_cargo_bounds() {
+        let range = |raw: &str| VersionRange::parse_req(raw).unwrap();
+        assert!(range("^0.2.3").overlaps(&range("=0.2.9")));
+        assert!(!range("^0.2.3").overlaps(&range("=0.3.0")));
+        assert!(!range("^0.0.3").overlaps(&range("=0.0.4")));
+        assert!(range("~1.2.3").overlaps(&range("=1.2.9")));
+        assert!(!range("~1.2.3").overlaps(&range("=1.3.0")));
+        assert!(!range("<=1.2").overlaps(&range(">=1.3.0")));
+        assert!(range(">1.2.3").overlaps(&range("=1.2.4")));
+        assert!(!range(">1.2.3").overlaps(&range("=1.2.3")));
+    }
+
+    #[test]
+    fn only_completed_and_failed_are_terminal() {
+        assert!(!ScanJobStatus::Queued.is_terminal());
+        assert!(!ScanJobStatus::Running.is_terminal());
+        assert!(ScanJobStatus::Completed.is_terminal());
+        assert!(ScanJobStatus::Failed.is_terminal());
+        assert_eq!(
+            serde_json::to_value(ScanJobStatus::Running).unwrap(),
+            serde_json::json!("running")
+        );
+    }
+
+    #[test]
+    fn parses_feed_severities() {
+        assert_eq!(parse_severity("HIGH"), Severity::High);
+        assert_eq!(parse_severity("moderate"), Severity::Medium);
+        assert_eq!(parse_severity("unknown"), Severity::Info);
+    }
+}
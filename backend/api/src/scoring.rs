@@ -2,8 +2,12 @@
 // Scoring engine: weighted category scoring, badge assignment, and report generation
 
 use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use uuid::Uuid;
 use crate::checklist::all_checks;
 use crate::models::{AuditCheckRow, CategoryScore, CheckStatus, ChecklistItem, DetectionMethod, Severity};
+use crate::scanner_service::SeverityCounts;
 
 pub fn severity_weight(sev: &Severity) -> f64 {
     match sev {
@@ -28,7 +32,7 @@ fn score_category(
 
     for item in checks {
         let status = statuses
-            .get(item.id)
+            .get(item.id.as_ref())
             .map(|r| &r.status)
             .unwrap_or(&CheckStatus::Pending);
 
@@ -79,7 +83,7 @@ pub fn calculate_scores(check_rows: &[AuditCheckRow]) -> (f64, Vec<CategoryScore
             score_category(items, &status_map);
 
         for item in items {
-            let s = status_map.get(item.id).map(|r| &r.status).unwrap_or(&CheckStatus::Pending);
+            let s = status_map.get(item.id.as_ref()).map(|r| &r.status).unwrap_or(&CheckStatus::Pending);
             if *s == CheckStatus::NotApplicable { continue; }
             let w = severity_weight(&item.severity);
             total_weighted_total += w;
@@ -130,7 +134,7 @@ pub fn build_markdown_report(
 ) -> String {
     let all = all_checks();
     // index by id as &str for lookup
-    let meta: HashMap<&str, &ChecklistItem> = all.iter().map(|c| (c.id, c)).collect();
+    let meta: HashMap<&str, &ChecklistItem> = all.iter().map(|c| (c.id.as_ref(), c)).collect();
     let status_map: HashMap<&str, &AuditCheckRow> =
         check_rows.iter().map(|r| (r.check_id.as_str(), r)).collect();
 
@@ -174,7 +178,7 @@ pub fn build_markdown_report(
             .iter()
             .filter(|item| item.severity == severity)
             .filter_map(|item| {
-                let row = status_map.get(item.id)?;
+                let row = status_map.get(item.id.as_ref())?;
                 if failures_only && row.status == CheckStatus::Passed { return None; }
                 Some((item, *row))
             })
@@ -242,6 +246,298 @@ pub fn build_markdown_report(
     md
 }
 
+// ─────────────────────────────────────────────────────────
+// Composite contract score (security + popularity + maintenance)
+// ─────────────────────────────────────────────────────────
+
+/// Components of the composite score, each on a 0–100 scale
+pub const SCORE_COMPONENTS: [&str; 3] = ["security", "popularity", "maintenance"];
+
+/// Share of the popularity component driven by downloads; the rest comes
+/// from the activity-based `popularity_score`
+pub const DOWNLOAD_POPULARITY_SHARE: f64 = 0.5;
+
+/// Blend relative activity (already 0–100) with downloads, log-scaled
+/// against the most downloaded contract so one runaway contract doesn't
+/// flatten everyone else to zero
+pub fn popularity_component(activity: f64, downloads: i64, max_downloads: i64) -> f64 {
+    let downloads_pct = if max_downloads > 0 {
+        100.0 * (1.0 + downloads.max(0) as f64).ln() / (1.0 + max_downloads as f64).ln()
+    } else {
+        0.0
+    };
+    activity * (1.0 - DOWNLOAD_POPULARITY_SHARE) + downloads_pct * DOWNLOAD_POPULARITY_SHARE
+}
+
+/// Allowed drift from 1.0 when weights are summed
+pub const WEIGHT_SUM_EPSILON: f64 = 1e-6;
+
+/// How the components combine; always sums to 1.0
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ScoringWeights {
+    pub security: f64,
+    pub popularity: f64,
+    pub maintenance: f64,
+}
+
+impl Default for ScoringWeights {
+    fn default() -> Self {
+        Self { security: 0.5, popularity: 0.3, maintenance: 0.2 }
+    }
+}
+
+/// Component values for one contract
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, sqlx::FromRow)]
+pub struct ScoreComponents {
+    pub security: f64,
+    pub popularity: f64,
+    pub maintenance: f64,
+}
+
+impl ScoringWeights {
+    /// Validate a raw `{component: weight}` object. Components left out
+    /// weigh 0. The error names the unknown component or the bad sum.
+    pub fn from_json(raw: &serde_json::Map<String, serde_json::Value>) -> Result<Self, String> {
+        let unknown: Vec<&str> = raw
+            .keys()
+            .map(String::as_str)
+            .filter(|key| !SCORE_COMPONENTS.contains(key))
+            .collect();
+        if !unknown.is_empty() {
+            return Err(format!(
+                "unknown scoring components: {} (expected {})",
+                unknown.join(", "),
+                SCORE_COMPONENTS.join(", ")
+            ));
+        }
+
+        let mut weight = |name: &str| -> Result<f64, String> {
+            match raw.get(name) {
+                None => Ok(0.0),
+                Some(value) => match value.as_f64() {
+                    Some(w) if w.is_finite() && w >= 0.0 => Ok(w),
+                    _ => Err(format!("weight for '{}' must be a non-negative number", name)),
+                },
+            }
+        };
+        let weights = Self {
+            security: weight("security")?,
+            popularity: weight("popularity")?,
+            maintenance: weight("maintenance")?,
+        };
+
+        let sum = weights.security + weights.popularity + weights.maintenance;
+        if (sum - 1.0).abs() > WEIGHT_SUM_EPSILON {
+            return Err(format!("weights must sum to 1.0, got {}", sum));
+        }
+        Ok(weights)
+    }
+
+    pub fn composite(&self, c: &ScoreComponents) -> f64 {
+        c.security * self.security + c.popularity * self.popularity + c.maintenance * self.maintenance
+    }
+}
+
+/// Weights currently stored, or the defaults if none were set
+pub async fn load_weights(pool: &PgPool) -> Result<ScoringWeights, sqlx::Error> {
+    let stored: Option<sqlx::types::Json<ScoringWeights>> =
+        sqlx::query_scalar("SELECT weights FROM scoring_weights WHERE id = TRUE")
+            .fetch_optional(pool)
+            .await?;
+    Ok(stored.map(|w| w.0).unwrap_or_default())
+}
+
+pub async fn store_weights(pool: &PgPool, weights: &ScoringWeights) -> Result<(), sqlx::Error> {
+    sqlx::query(
+        "INSERT INTO scoring_weights (id, weights, updated_at) VALUES (TRUE, $1, NOW())
+         ON CONFLICT (id) DO UPDATE SET weights = EXCLUDED.weights, updated_at = NOW()",
+    )
+    .bind(sqlx::types::Json(weights))
+    .execute(pool)
+    .await?;
+    Ok(())
+}
+
+/// Points deducted from the security component per finding in the latest
+/// scan, by severity; the total is capped at 100
+pub fn scan_penalty(counts: &SeverityCounts) -> f64 {
+    let penalty = counts.critical as f64 * 25.0
+        + counts.high as f64 * 10.0
+        + counts.medium as f64 * 3.0
+        + counts.low as f64;
+    penalty.min(100.0)
+}
+
+/// Security component: the latest audit score, or 100 for a scanned but
+/// unaudited contract, less the latest scan's penalty. Zero when there's
+/// neither.
+pub fn security_component(audit: Option<f64>, scan: Option<&SeverityCounts>) -> f64 {
+    let base = match (audit, scan) {
+        (Some(audit), _) => audit,
+        (None, Some(_)) => 100.0,
+        (None, None) => return 0.0,
+    };
+    (base - scan.map(scan_penalty).unwrap_or(0.0)).max(0.0)
+}
+
+/// Per-contract inputs to the composite score
+#[derive(Debug, sqlx::FromRow)]
+struct ScoreInputs {
+    id: Uuid,
+    audit: Option<f64>,
+    scanned: bool,
+    #[sqlx(flatten)]
+    findings: SeverityCounts,
+    activity: f64,
+    downloads: i64,
+    max_downloads: i64,
+    maintenance: f64,
+}
+
+/// Recompute every contract's composite score with the stored weights.
+/// Returns the number of contracts scored.
+pub async fn recompute_composite_scores(pool: &PgPool) -> Result<u64, sqlx::Error> {
+    recompute_scores(pool, None).await
+}
+
+/// Recompute one contract's score, e.g. after a re-scan changed its findings
+pub async fn recompute_contract_score(pool: &PgPool, contract_id: Uuid) -> Result<u64, sqlx::Error> {
+    recompute_scores(pool, Some(contract_id)).await
+}
+
+async fn recompute_scores(pool: &PgPool, only: Option<Uuid>) -> Result<u64, sqlx::Error> {
+    let weights = load_weights(pool).await?;
+
+    // security: latest audit score less the latest scan's findings;
+    // popularity: activity and downloads, each relative to the top contract
+    // (so the window functions run over every contract before filtering);
+    // maintenance: decays with time since the last update/version
+    let rows: Vec<ScoreInputs> = sqlx::query_as(
+        r#"
+        WITH downloads AS (
+            SELECT contract_id, SUM(download_count)::bigint AS total
+            FROM contract_versions GROUP BY contract_id
+        ),
+        latest_scans AS (
+            SELECT DISTINCT ON (contract_id) contract_id, findings
+            FROM contract_version_scans
+            ORDER BY contract_id, scanned_at DESC
+        ),
+        scan_counts AS (
+            SELECT
+                s.contract_id,
+                COUNT(f) FILTER (WHERE f->>'severity' = 'Critical') AS critical,
+                COUNT(f) FILTER (WHERE f->>'severity' = 'High') AS high,
+                COUNT(f) FILTER (WHERE f->>'severity' = 'Medium') AS medium,
+                COUNT(f) FILTER (WHERE f->>'severity' = 'Low') AS low,
+                COUNT(f) FILTER (WHERE f->>'severity' = 'Info') AS info
+            FROM latest_scans s
+            LEFT JOIN LATERAL jsonb_array_elements(s.findings) f ON true
+            GROUP BY s.contract_id
+        ),
+        inputs AS (
+            SELECT
+                c.id,
+                (
+                    SELECT sa.overall_score FROM security_audits sa
+                    WHERE sa.contract_id = c.id
+                    ORDER BY sa.audit_date DESC LIMIT 1
+                )::float8 AS audit,
+                (sc.contract_id IS NOT NULL) AS scanned,
+                COALESCE(sc.critical, 0) AS critical,
+                COALESCE(sc.high, 0) AS high,
+                COALESCE(sc.medium, 0) AS medium,
+                COALESCE(sc.low, 0) AS low,
+                COALESCE(sc.info, 0) AS info,
+                CASE WHEN MAX(c.popularity_score) OVER () > 0
+                     THEN 100.0 * c.popularity_score / MAX(c.popularity_score) OVER ()
+                     ELSE 0.0 END::float8 AS activity,
+                COALESCE(d.total, 0) AS downloads,
+                COALESCE(MAX(d.total) OVER (), 0)::bigint AS max_downloads,
+                (100.0 * EXP(-EXTRACT(EPOCH FROM (NOW() - GREATEST(
+                    c.updated_at,
+                    COALESCE((SELECT MAX(v.created_at) FROM contract_versions v WHERE v.contract_id = c.id), c.updated_at)
+                ))) / 86400.0 / 180.0))::float8 AS maintenance
+            FROM contracts c
+            LEFT JOIN downloads d ON d.contract_id = c.id
+            LEFT JOIN scan_counts sc ON sc.contract_id = c.id
+        )
+        SELECT * FROM inputs WHERE $1::uuid IS NULL OR id = $1
+        "#,
+    )
+    .bind(only)
+    .fetch_all(pool)
+    .await?;
+
+    let mut tx = pool.begin().await?;
+    for row in &rows {
+        let components = ScoreComponents {
+            security: security_component(row.audit, row.scanned.then_some(&row.findings)),
+            popularity: popularity_component(row.activity, row.downloads, row.max_downloads),
+            maintenance: row.maintenance,
+        };
+        let contract_id = &row.id;
+        let composite = weights.composite(&components);
+        sqlx::query(
+            "INSERT INTO contract_scores (contract_id, composite, security, popularity, maintenance, computed_at)
+             VALUES ($1, $2, $3, $4, $5, NOW())
+             ON CONFLICT (contract_id) DO UPDATE
+             SET composite = EXCLUDED.composite, security = EXCLUDED.security,
+                 popularity = EXCLUDED.popularity, maintenance = EXCLUDED.maintenance,
+                 computed_at = EXCLUDED.computed_at",
+        )
+        .bind(contract_id)
+        .bind(composite)
+        .bind(components.security)
+        .bind(components.popularity)
+        .bind(components.maintenance)
+        .execute(&mut *tx)
+        .await?;
+
+        // Every computation is recorded, changed or not, so a gap in the
+        // history means "not computed" rather than "unchanged"
+        sqlx::query(
+            "INSERT INTO score_history (contract_id, composite, security, popularity, maintenance)
+             VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(contract_id)
+        .bind(composite)
+        .bind(components.security)
+        .bind(components.popularity)
+        .bind(components.maintenance)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+
+    tracing::info!(contracts = rows.len(), "scoring: composite scores recomputed");
+    Ok(rows.len() as u64)
+}
+
+/// One recorded computation of a contract's composite score
+#[derive(Debug, Clone, PartialEq, Serialize, sqlx::FromRow)]
+pub struct ScoreHistoryPoint {
+    pub composite: f64,
+    pub security: f64,
+    pub popularity: f64,
+    pub maintenance: f64,
+    pub computed_at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Keep the last point of each UTC day. `points` must be sorted by time.
+pub fn last_point_per_day(points: Vec<ScoreHistoryPoint>) -> Vec<ScoreHistoryPoint> {
+    let mut daily: Vec<ScoreHistoryPoint> = Vec::new();
+    for point in points {
+        match daily.last_mut() {
+            Some(last) if last.computed_at.date_naive() == point.computed_at.date_naive() => {
+                *last = point;
+            }
+            _ => daily.push(point),
+        }
+    }
+    daily
+}
+
 // Suppress unused-import warning: meta is used in build_markdown_report via the HashMap
 // but only indirectly — keep it as documentation of available lookup.
 #[allow(dead_code)]
@@ -251,6 +547,80 @@ fn _use_meta() {}
 mod tests {
     use super::*;
 
+    fn weights(value: serde_json::Value) -> Result<ScoringWeights, String> {
+        ScoringWeights::from_json(value.as_object().unwrap())
+    }
+
+    #[test]
+    fn scoring_weights_must_sum_to_one() {
+        let w = weights(serde_json::json!({"security": 0.6, "popularity": 0.1, "maintenance": 0.3})).unwrap();
+        assert_eq!(w.security, 0.6);
+        // 0.7 + 0.2 + 0.1 sums to 0.9999999999999999 in floating point; epsilon absorbs it
+        assert_ne!(0.7 + 0.2 + 0.1, 1.0);
+        assert!(weights(serde_json::json!({"security": 0.7, "popularity": 0.2, "maintenance": 0.1})).is_ok());
+
+        let err = weights(serde_json::json!({"security": 0.5, "popularity": 0.3})).unwrap_err();
+        assert!(err.contains("sum to 1.0"));
+    }
+
+    #[test]
+    fn scoring_weights_reject_unknown_components() {
+        let err = weights(serde_json::json!({"security": 0.5, "hype": 0.5})).unwrap_err();
+        assert!(err.contains("hype"));
+        assert!(weights(serde_json::json!({"security": -0.5, "popularity": 1.5})).is_err());
+    }
+
+    #[test]
+    fn composite_applies_weights() {
+        let w = ScoringWeights { security: 0.5, popularity: 0.25, maintenance: 0.25 };
+        let c = ScoreComponents { security: 80.0, popularity: 40.0, maintenance: 100.0 };
+        assert!((w.composite(&c) - 75.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn scan_findings_lower_security() {
+        let clean = SeverityCounts::default();
+        let critical = SeverityCounts { critical: 1, ..Default::default() };
+        assert_eq!(security_component(None, None), 0.0);
+        assert_eq!(security_component(None, Some(&clean)), 100.0);
+        assert_eq!(security_component(Some(80.0), None), 80.0);
+        assert_eq!(security_component(Some(80.0), Some(&critical)), 55.0);
+
+        let many = SeverityCounts { critical: 10, ..Default::default() };
+        assert_eq!(scan_penalty(&many), 100.0);
+        assert_eq!(security_component(Some(80.0), Some(&many)), 0.0);
+    }
+
+    #[test]
+    fn downloads_feed_popularity() {
+        assert_eq!(popularity_component(60.0, 0, 0), 30.0);
+        assert!((popularity_component(0.0, 1000, 1000) - 50.0).abs() < 1e-9);
+        let some = popularity_component(0.0, 10, 1000);
+        assert!(some > 0.0 && some < 50.0);
+        assert!(popularity_component(40.0, 10, 1000) > popularity_component(40.0, 5, 1000));
+    }
+
+    #[test]
+    fn daily_granularity_keeps_last_value_per_day() {
+        use chrono::TimeZone;
+        let point = |day: u32, hour: u32, composite: f64| ScoreHistoryPoint {
+            composite,
+            security: 0.0,
+            popularity: 0.0,
+            maintenance: 0.0,
+            computed_at: chrono::Utc.with_ymd_and_hms(2026, 3, day, hour, 0, 0).unwrap(),
+        };
+        let daily = last_point_per_day(vec![
+            point(1, 1, 10.0),
+            point(1, 23, 12.0),
+            point(2, 5, 12.0),
+            point(4, 0, 20.0),
+            point(4, 6, 19.0),
+        ]);
+        let values: Vec<f64> = daily.iter().map(|p| p.composite).collect();
+        assert_eq!(values, vec![12.0, 12.0, 19.0]);
+    }
+
     #[test]
     fn severity_weights_ordered() {
         assert!(severity_weight(&Severity::Critical) > severity_weight(&Severity::High));
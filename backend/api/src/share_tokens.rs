@@ -0,0 +1,583 @@
+//! Share tokens for private contracts (`contract_share_tokens`).
+//!
+//! An owner mints a token to let someone read one private contract, and its
+//! artifacts, without making it public. The token is presented as
+//! `?token=` or in the `X-Share-Token` header. Tokens are long random
+//! strings, so a plain sha256 is enough to store them: the hash is the
+//! lookup key and the raw value is returned only once. Expired and revoked
+//! tokens are ignored, which leaves the contract reading as missing.
+
+use axum::{
+    async_trait,
+    extract::{FromRequestParts, Path, Query, State},
+    http::{request::Parts, StatusCode},
+    Extension, Json,
+};
+use chrono::{DateTime, Utc};
+use rand::{distributions::Alphanumeric, Rng};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use shared::OrganizationRole;
+use sqlx::PgPool;
+use std::convert::Infallible;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::{
+    admin_audit,
+    auth::{Caller, ContractAccess},
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::db_internal_error,
+    pagination::{PageParams, Paginated, PaginatedShareTokens},
+    state::AppState,
+};
+
+/// Header carrying a share token, as an alternative to `?token=`
+pub const SHARE_TOKEN_HEADER: &str = "x-share-token";
+
+const TOKEN_SCHEME: &str = "shr";
+const TOKEN_SECRET_LEN: usize = 40;
+/// Matches the `label` column's `VARCHAR(255)`
+const MAX_LABEL_CHARS: usize = 255;
+
+/// Generate a new raw token and its stored hash
+fn generate_token() -> (String, String) {
+    let secret: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_SECRET_LEN)
+        .map(char::from)
+        .collect();
+    let raw = format!("{}_{}", TOKEN_SCHEME, secret);
+    let hash = hash_token(&raw);
+    (raw, hash)
+}
+
+fn hash_token(raw: &str) -> String {
+    hex::encode(Sha256::digest(raw.trim().as_bytes()))
+}
+
+/// Whether `raw` is a live token for `contract_id`
+pub async fn grants_access(
+    pool: &PgPool,
+    contract_id: Uuid,
+    raw: &str,
+) -> Result<bool, sqlx::Error> {
+    sqlx::query_scalar(
+        "SELECT EXISTS(
+             SELECT 1 FROM contract_share_tokens
+             WHERE token_hash = $1 AND contract_id = $2 AND revoked_at IS NULL
+               AND (expires_at IS NULL OR expires_at > NOW())
+         )",
+    )
+    .bind(hash_token(raw))
+    .bind(contract_id)
+    .fetch_one(pool)
+    .await
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// The share token sent with a request, if any. The header wins over the
+/// query string when both are present.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PresentedShareToken(pub Option<String>);
+
+impl PresentedShareToken {
+    pub fn as_deref(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+}
+
+#[async_trait]
+impl<S> FromRequestParts<S> for PresentedShareToken
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = parts
+            .headers
+            .get(SHARE_TOKEN_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+        let token = header.or_else(|| {
+            Query::<TokenQuery>::try_from_uri(&parts.uri)
+                .ok()
+                .and_then(|Query(query)| query.token)
+        });
+        Ok(Self(
+            token
+                .map(|token| token.trim().to_string())
+                .filter(|token| !token.is_empty()),
+        ))
+    }
+}
+
+#[derive(Debug, Default, Deserialize, ToSchema)]
+pub struct CreateShareTokenRequest {
+    pub label: Option<String>,
+    /// When the token stops working; it never expires when unset
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// A share token as listed to owners; the raw value is never shown again
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct ShareToken {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub label: Option<String>,
+    pub created_by: Option<Uuid>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Returned once on creation; the raw token cannot be retrieved again
+#[derive(Debug, Serialize, ToSchema)]
+pub struct CreatedShareToken {
+    #[serde(flatten)]
+    pub share_token: ShareToken,
+    pub token: String,
+}
+
+/// An expiry must lie in the future
+fn check_expiry(expires_at: Option<DateTime<Utc>>, now: DateTime<Utc>) -> ApiResult<()> {
+    match expires_at {
+        Some(expires_at) if expires_at <= now => Err(ApiError::bad_request(
+            ErrorCode::InvalidShareTokenExpiry,
+            format!(
+                "expires_at must be in the future (got {})",
+                expires_at.to_rfc3339()
+            ),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// The trimmed label, dropped when blank; longer ones than the column holds
+/// are refused rather than cut
+fn check_label(label: Option<String>) -> ApiResult<Option<String>> {
+    let Some(label) = label
+        .map(|label| label.trim().to_string())
+        .filter(|label| !label.is_empty())
+    else {
+        return Ok(None);
+    };
+    let chars = label.chars().count();
+    if chars > MAX_LABEL_CHARS {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidShareTokenLabel,
+            format!(
+                "label must be at most {} characters (got {})",
+                MAX_LABEL_CHARS, chars
+            ),
+        ));
+    }
+    Ok(Some(label))
+}
+
+/// Mint a token granting read access to this contract
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/share-tokens",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    request_body = CreateShareTokenRequest,
+    responses(
+        (status = 201, description = "New token; the raw value is only returned once", body = CreatedShareToken),
+        (status = 400, description = "expires_at is not in the future, or the label is too long"),
+        (status = 403, description = "Caller lacks the owner role on the contract"),
+        (status = 404, description = "Contract not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn create_share_token(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Extension(access): Extension<ContractAccess>,
+    body: Option<Json<CreateShareTokenRequest>>,
+) -> ApiResult<(StatusCode, Json<CreatedShareToken>)> {
+    access.require(OrganizationRole::Owner)?;
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+    check_expiry(req.expires_at, Utc::now())?;
+    let label = check_label(req.label)?;
+
+    let (raw, hash) = generate_token();
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin create share token", err))?;
+    let share_token: ShareToken = sqlx::query_as(
+        "INSERT INTO contract_share_tokens (contract_id, token_hash, label, created_by, expires_at)
+         VALUES ($1, $2, $3, $4, $5)
+         RETURNING id, contract_id, label, created_by, expires_at, revoked_at, created_at",
+    )
+    .bind(access.contract_id)
+    .bind(&hash)
+    .bind(&label)
+    .bind(caller.publisher_id())
+    .bind(req.expires_at)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("create share token", err))?;
+    admin_audit::record(
+        &mut *tx,
+        &caller,
+        admin_audit::SHARE_TOKEN_CREATED,
+        "contract",
+        &access.contract_id.to_string(),
+        Some(serde_json::json!({
+            "share_token_id": share_token.id,
+            "expires_at": share_token.expires_at,
+        })),
+    )
+    .await
+    .map_err(|err| db_internal_error("record share token in admin audit log", err))?;
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit create share token", err))?;
+
+    Ok((
+        StatusCode::CREATED,
+        Json(CreatedShareToken {
+            share_token,
+            token: raw,
+        }),
+    ))
+}
+
+/// Share tokens minted for this contract, newest first
+#[utoipa::path(
+    get,
+    path = "/api/contracts/{id}/share-tokens",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        PageParams,
+    ),
+    responses(
+        (status = 200, description = "Tokens, including revoked and expired ones", body = PaginatedShareTokens),
+        (status = 403, description = "Caller lacks the owner role on the contract"),
+        (status = 404, description = "Contract not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn list_share_tokens(
+    State(state): State<AppState>,
+    Extension(access): Extension<ContractAccess>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<ShareToken>>> {
+    access.require(OrganizationRole::Owner)?;
+
+    let tokens: Vec<ShareToken> = sqlx::query_as(
+        "SELECT id, contract_id, label, created_by, expires_at, revoked_at, created_at
+         FROM contract_share_tokens WHERE contract_id = $1
+         ORDER BY created_at DESC, id DESC
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(access.contract_id)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list share tokens", err))?;
+    let total: i64 =
+        sqlx::query_scalar("SELECT COUNT(*) FROM contract_share_tokens WHERE contract_id = $1")
+            .bind(access.contract_id)
+            .fetch_one(&state.db)
+            .await
+            .map_err(|err| db_internal_error("count share tokens", err))?;
+
+    Ok(Json(Paginated::new(
+        tokens,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+/// Revoke a share token. Revoking it again is a no-op.
+#[utoipa::path(
+    delete,
+    path = "/api/contracts/{id}/share-tokens/{token_id}",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+        ("token_id" = Uuid, Path, description = "Share token to revoke"),
+    ),
+    responses(
+        (status = 204, description = "Token revoked"),
+        (status = 403, description = "Caller lacks the owner role on the contract"),
+        (status = 404, description = "Contract or share token not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn revoke_share_token(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Extension(access): Extension<ContractAccess>,
+    Path((contract_id, token_id)): Path<(Uuid, Uuid)>,
+) -> ApiResult<StatusCode> {
+    access.require(OrganizationRole::Owner)?;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin revoke share token", err))?;
+    // Only a live token is revoked, so revoking twice logs one entry
+    let revoked: Option<bool> = sqlx::query_scalar(
+        "UPDATE contract_share_tokens SET revoked_at = COALESCE(revoked_at, NOW())
+         WHERE id = $1 AND contract_id = $2
+         RETURNING revoked_at = NOW()",
+    )
+    .bind(token_id)
+    .bind(contract_id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("revoke share token", err))?;
+    match revoked {
+        None => {
+            return Err(ApiError::not_found(
+                ErrorCode::ShareTokenNotFound,
+                format!("Contract {} has no share token {}", contract_id, token_id),
+            ))
+        }
+        Some(true) => admin_audit::record(
+            &mut *tx,
+            &caller,
+            admin_audit::SHARE_TOKEN_REVOKED,
+            "contract",
+            &contract_id.to_string(),
+            Some(serde_json::json!({ "share_token_id": token_id })),
+        )
+        .await
+        .map_err(|err| db_internal_error("record share token in admin audit log", err))?,
+        Some(false) => {}
+    }
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit revoke share token", err))?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{json_body, test_request};
+    use axum::http::Request;
+
+    async fn presented(uri: &str, header: Option<&str>) -> Option<String> {
+        let mut req = Request::builder().uri(uri);
+        if let Some(header) = header {
+            req = req.header(SHARE_TOKEN_HEADER, header);
+        }
+        let (mut parts, ()) = req.body(()).unwrap().into_parts();
+        PresentedShareToken::from_request_parts(&mut parts, &())
+            .await
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn only_the_hash_of_a_token_is_kept() {
+        let (raw, hash) = generate_token();
+        assert!(raw.starts_with("shr_"));
+        assert_eq!(raw.len(), TOKEN_SCHEME.len() + 1 + TOKEN_SECRET_LEN);
+        assert_eq!(hash_token(&raw), hash);
+        assert_ne!(generate_token().1, hash);
+    }
+
+    #[tokio::test]
+    async fn tokens_are_read_from_the_header_or_the_query() {
+        assert_eq!(presented("/api/contracts/x", None).await, None);
+        assert_eq!(
+            presented("/api/contracts/x?token=shr_q", None)
+                .await
+                .as_deref(),
+            Some("shr_q")
+        );
+        assert_eq!(
+            presented("/api/contracts/x?token=shr_q", Some("shr_h"))
+                .await
+                .as_deref(),
+            Some("shr_h")
+        );
+        assert_eq!(presented("/api/contracts/x?token=", None).await, None);
+    }
+
+    #[test]
+    fn expiry_must_be_in_the_future() {
+        let now = Utc::now();
+        assert!(check_expiry(None, now).is_ok());
+        assert!(check_expiry(Some(now + chrono::Duration::hours(1)), now).is_ok());
+        let err = check_expiry(Some(now), now).unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[test]
+    fn labels_are_trimmed_and_capped() {
+        assert_eq!(check_label(None).unwrap(), None);
+        assert_eq!(check_label(Some("  ".into())).unwrap(), None);
+        assert_eq!(
+            check_label(Some(" ci ".into())).unwrap().as_deref(),
+            Some("ci")
+        );
+        let longest = "é".repeat(MAX_LABEL_CHARS);
+        assert_eq!(check_label(Some(longest.clone())).unwrap(), Some(longest));
+        let err = check_label(Some("x".repeat(MAX_LABEL_CHARS + 1))).unwrap_err();
+        assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn maintainers_cannot_mint_tokens() {
+        // Refused before any query
+        let state = AppState::for_tests();
+        let access = ContractAccess {
+            contract_id: Uuid::new_v4(),
+            role: Some(OrganizationRole::Maintainer),
+        };
+        let err = create_share_token(
+            State(state),
+            Extension(Caller::Publisher(Uuid::new_v4())),
+            Extension(access),
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+    }
+
+    /// Mint a token for `contract` as `owner` through the API; returns its
+    /// id and raw value
+    async fn mint(state: &AppState, owner: Uuid, contract: Uuid) -> (String, String) {
+        let key = state.api_key(owner).await;
+        let uri = format!("/api/contracts/{}/share-tokens", contract);
+        let body = serde_json::json!({ "label": "reviewer" });
+        let request = test_request("POST", &uri, Some(&key), Some(body));
+        let response = state.send(request).await;
+        assert_eq!(response.status(), StatusCode::CREATED);
+        let created = json_body(response).await;
+        (
+            created["id"].as_str().unwrap().to_string(),
+            created["token"].as_str().unwrap().to_string(),
+        )
+    }
+
+    /// Status of an anonymous GET presenting `token` in the header
+    async fn get_with_token(state: &AppState, uri: &str, token: &str) -> StatusCode {
+        let mut request = test_request("GET", uri, None, None);
+        request
+            .headers_mut()
+            .insert(SHARE_TOKEN_HEADER, token.parse().unwrap());
+        state.send(request).await.status()
+    }
+
+    #[tokio::test]
+    async fn a_live_token_reads_and_downloads_a_private_contract() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let owner = state.insert_publisher().await;
+        let contract = state.insert_contract(owner, None, "private").await;
+        let version = state.insert_version(contract, "1.0.0").await;
+        let wasm = b"\0asm\x01\0\0\0";
+        crate::artifacts::store_artifact(&state.db, version, wasm)
+            .await
+            .unwrap();
+        let (_, token) = mint(&state, owner, contract).await;
+        let detail = format!("/api/contracts/{}", contract);
+        let download = format!("/api/contracts/{}/versions/1.0.0/download", contract);
+
+        assert_eq!(state.anonymous_get(&detail).await, StatusCode::NOT_FOUND);
+        let with_query = format!("{}?token={}", detail, token);
+        assert_eq!(state.anonymous_get(&with_query).await, StatusCode::OK);
+        assert_eq!(
+            get_with_token(&state, &detail, &token).await,
+            StatusCode::OK
+        );
+
+        let mut request = test_request("GET", &download, None, None);
+        request
+            .headers_mut()
+            .insert(SHARE_TOKEN_HEADER, token.parse().unwrap());
+        let response = state.send(request).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], &wasm[..]);
+
+        // A token only opens the contract it was minted for
+        let other = state.insert_contract(owner, None, "private").await;
+        let other = format!("/api/contracts/{}", other);
+        assert_eq!(
+            get_with_token(&state, &other, &token).await,
+            StatusCode::NOT_FOUND
+        );
+    }
+
+    #[tokio::test]
+    async fn expired_and_revoked_tokens_read_as_missing() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let owner = state.insert_publisher().await;
+        let contract = state.insert_contract(owner, None, "private").await;
+        state.insert_version(contract, "1.0.0").await;
+        let detail = format!("/api/contracts/{}", contract);
+        let download = format!("/api/contracts/{}/versions/1.0.0/download", contract);
+
+        let (expired_id, expired) = mint(&state, owner, contract).await;
+        sqlx::query(
+            "UPDATE contract_share_tokens SET expires_at = NOW() - INTERVAL '1 minute'
+             WHERE id = $1::uuid",
+        )
+        .bind(&expired_id)
+        .execute(&state.db)
+        .await
+        .unwrap();
+
+        let (revoked_id, revoked) = mint(&state, owner, contract).await;
+        let key = state.api_key(owner).await;
+        let uri = format!("/api/contracts/{}/share-tokens/{}", contract, revoked_id);
+        let response = state
+            .send(test_request("DELETE", &uri, Some(&key), None))
+            .await;
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        for token in [&expired, &revoked] {
+            for uri in [&detail, &download] {
+                let status = get_with_token(&state, uri, token).await;
+                assert_eq!(status, StatusCode::NOT_FOUND, "{}", uri);
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn overlong_labels_are_rejected() {
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let owner = state.insert_publisher().await;
+        let contract = state.insert_contract(owner, None, "private").await;
+        let key = state.api_key(owner).await;
+        let uri = format!("/api/contracts/{}/share-tokens", contract);
+        let body = serde_json::json!({ "label": "x".repeat(MAX_LABEL_CHARS + 1) });
+        let response = state
+            .send(test_request("POST", &uri, Some(&key), Some(body)))
+            .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            json_body(response).await["code"],
+            "share_token.invalid_label"
+        );
+    }
+}
@@ -0,0 +1,90 @@
+// api/src/shutdown.rs
+// Graceful shutdown: wait for SIGTERM/SIGINT, stop background tasks, and give
+// in-flight requests a bounded grace period to finish.
+
+use std::time::Duration;
+
+use tokio_util::sync::CancellationToken;
+
+pub const GRACE_PERIOD_ENV: &str = "SHUTDOWN_GRACE_PERIOD_SECS";
+pub const DEFAULT_GRACE_PERIOD_SECS: u64 = 30;
+
+/// Read `SHUTDOWN_GRACE_PERIOD_SECS` (default 30). Zero means "exit as soon
+/// as the signal arrives", which is valid for local runs.
+pub fn grace_period_from_env() -> Result<Duration, String> {
+    match std::env::var(GRACE_PERIOD_ENV) {
+        Ok(raw) => parse_grace_period(&raw),
+        Err(_) => Ok(Duration::from_secs(DEFAULT_GRACE_PERIOD_SECS)),
+    }
+}
+
+fn parse_grace_period(raw: &str) -> Result<Duration, String> {
+    raw.trim()
+        .parse::<u64>()
+        .map(Duration::from_secs)
+        .map_err(|_| {
+            format!(
+                "{} must be a whole number of seconds (got '{}')",
+                GRACE_PERIOD_ENV, raw
+            )
+        })
+}
+
+/// Resolves on the first SIGTERM or SIGINT (Ctrl-C)
+pub async fn signal() {
+    let ctrl_c = async {
+        if let Err(err) = tokio::signal::ctrl_c().await {
+            tracing::error!(error = ?err, "failed to listen for SIGINT");
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut sigterm) => {
+                sigterm.recv().await;
+            }
+            Err(err) => {
+                tracing::error!(error = ?err, "failed to listen for SIGTERM");
+                std::future::pending::<()>().await;
+            }
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {},
+        _ = terminate => {},
+    }
+}
+
+/// Cancel `token` when a shutdown signal arrives
+pub fn spawn_signal_listener(token: CancellationToken) {
+    tokio::spawn(async move {
+        signal().await;
+        tracing::info!("shutdown initiated");
+        token.cancel();
+    });
+}
+
+/// Resolves `grace` after `token` is cancelled; race the server against it to
+/// cap how long in-flight requests may keep the process alive
+pub async fn grace_deadline(token: CancellationToken, grace: Duration) {
+    token.cancelled().await;
+    tokio::time::sleep(grace).await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_grace_period() {
+        assert_eq!(parse_grace_period("10").unwrap(), Duration::from_secs(10));
+        assert_eq!(parse_grace_period("0").unwrap(), Duration::ZERO);
+        assert!(parse_grace_period("-1").is_err());
+        assert!(parse_grace_period("30s").is_err());
+    }
+}
@@ -0,0 +1,79 @@
+// signing.rs
+// Publisher signatures over contract artifacts.
+//
+// Publishers sign the raw WASM bytes with ed25519 and send the signature and
+// their public key, both hex encoded, alongside the artifact. The registry
+// only records a version as signed once the signature checks out.
+
+use ed25519_dalek::{Signature, VerifyingKey, PUBLIC_KEY_LENGTH, SIGNATURE_LENGTH};
+
+/// Verify `signature_hex` over `wasm` with `public_key_hex`.
+/// The error explains what was wrong and is safe to return to the caller.
+pub fn verify_artifact(wasm: &[u8], signature_hex: &str, public_key_hex: &str) -> Result<(), String> {
+    let key: [u8; PUBLIC_KEY_LENGTH] = decode_hex(public_key_hex, "public_key")?;
+    let signature: [u8; SIGNATURE_LENGTH] = decode_hex(signature_hex, "signature")?;
+
+    let key = VerifyingKey::from_bytes(&key)
+        .map_err(|_| "public_key is not a valid ed25519 key".to_string())?;
+    key.verify_strict(wasm, &Signature::from_bytes(&signature))
+        .map_err(|_| "signature does not match the artifact and public key".to_string())
+}
+
+/// Canonical (lowercase hex) form of a public key, as stored on the version
+pub fn normalize_key(public_key_hex: &str) -> String {
+    public_key_hex.trim().to_ascii_lowercase()
+}
+
+fn decode_hex<const N: usize>(raw: &str, field: &str) -> Result<[u8; N], String> {
+    let bytes = hex::decode(raw.trim()).map_err(|_| format!("{} must be hex encoded", field))?;
+    bytes
+        .try_into()
+        .map_err(|bytes: Vec<u8>| format!("{} must be {} bytes (got {})", field, N, bytes.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    const WASM: &[u8] = b"\0asm\x01\0\0\0 contract body";
+
+    fn signed(key: &SigningKey, wasm: &[u8]) -> (String, String) {
+        (
+            hex::encode(key.sign(wasm).to_bytes()),
+            hex::encode(key.verifying_key().to_bytes()),
+        )
+    }
+
+    #[test]
+    fn valid_signature_verifies() {
+        let key = SigningKey::from_bytes(&[7; 32]);
+        let (signature, public_key) = signed(&key, WASM);
+        assert_eq!(verify_artifact(WASM, &signature, &public_key), Ok(()));
+        // Key casing doesn't matter
+        assert_eq!(verify_artifact(WASM, &signature, &public_key.to_uppercase()), Ok(()));
+    }
+
+    #[test]
+    fn wrong_key_is_rejected() {
+        let (signature, _) = signed(&SigningKey::from_bytes(&[7; 32]), WASM);
+        let other = hex::encode(SigningKey::from_bytes(&[9; 32]).verifying_key().to_bytes());
+        let err = verify_artifact(WASM, &signature, &other).unwrap_err();
+        assert!(err.contains("does not match"), "{}", err);
+    }
+
+    #[test]
+    fn corrupted_artifact_is_rejected() {
+        let (signature, public_key) = signed(&SigningKey::from_bytes(&[7; 32]), WASM);
+        let mut tampered = WASM.to_vec();
+        tampered[9] ^= 0xff;
+        assert!(verify_artifact(&tampered, &signature, &public_key).is_err());
+    }
+
+    #[test]
+    fn malformed_inputs_are_explained() {
+        let (signature, public_key) = signed(&SigningKey::from_bytes(&[7; 32]), WASM);
+        assert!(verify_artifact(WASM, "zz", &public_key).unwrap_err().contains("hex"));
+        assert!(verify_artifact(WASM, &signature, "abcd").unwrap_err().contains("32 bytes"));
+    }
+}
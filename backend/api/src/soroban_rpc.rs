@@ -0,0 +1,357 @@
+//! Minimal Soroban RPC client used to confirm what is actually deployed.
+//!
+//! Only one lookup is needed: the WASM hash behind a contract address. That is
+//! a `getLedgerEntries` call for the contract instance key, so the key and the
+//! returned entry are encoded/decoded by hand instead of pulling in the full
+//! XDR crate.
+
+use std::time::Duration;
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::Deserialize;
+use shared::Network;
+
+const RPC_TIMEOUT: Duration = Duration::from_secs(10);
+
+const DEFAULT_TESTNET_RPC_URL: &str = "https://soroban-testnet.stellar.org";
+const DEFAULT_FUTURENET_RPC_URL: &str = "https://rpc-futurenet.stellar.org";
+
+// XDR discriminants used below
+const LEDGER_ENTRY_CONTRACT_DATA: u32 = 6;
+const SC_ADDRESS_ACCOUNT: u32 = 0;
+const SC_ADDRESS_CONTRACT: u32 = 1;
+const SCV_CONTRACT_INSTANCE: u32 = 19;
+const SCV_LEDGER_KEY_CONTRACT_INSTANCE: u32 = 20;
+const DURABILITY_PERSISTENT: u32 = 1;
+const CONTRACT_EXECUTABLE_WASM: u32 = 0;
+
+/// strkey version byte for contract addresses (`C...`)
+const STRKEY_CONTRACT_VERSION: u8 = 2 << 3;
+
+#[derive(Debug)]
+pub enum RpcError {
+    /// No RPC endpoint configured for the network
+    NotConfigured(&'static str),
+    /// The address is not a valid `C...` contract strkey
+    InvalidAddress,
+    /// The RPC could not be reached or answered with a transport-level error
+    Unreachable(String),
+    /// The RPC answered but no contract instance exists at the address
+    NotDeployed,
+    /// The RPC answered with something we could not decode
+    Malformed(String),
+}
+
+impl std::fmt::Display for RpcError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RpcError::NotConfigured(var) => write!(f, "no Soroban RPC configured ({} is unset)", var),
+            RpcError::InvalidAddress => write!(f, "not a valid contract address"),
+            RpcError::Unreachable(msg) => write!(f, "Soroban RPC unreachable: {}", msg),
+            RpcError::NotDeployed => write!(f, "no contract is deployed at this address"),
+            RpcError::Malformed(msg) => write!(f, "unexpected Soroban RPC response: {}", msg),
+        }
+    }
+}
+
+/// RPC endpoint for a network, from `SOROBAN_RPC_URL_<NETWORK>`.
+///
+/// Testnet and futurenet fall back to the public SDF endpoints; mainnet has no
+/// default and must be configured explicitly.
+pub fn rpc_url_for(network: &Network) -> Result<String, RpcError> {
+    let (var, default) = match network {
+        Network::Mainnet => ("SOROBAN_RPC_URL_MAINNET", None),
+        Network::Testnet => ("SOROBAN_RPC_URL_TESTNET", Some(DEFAULT_TESTNET_RPC_URL)),
+        Network::Futurenet => ("SOROBAN_RPC_URL_FUTURENET", Some(DEFAULT_FUTURENET_RPC_URL)),
+    };
+
+    match std::env::var(var) {
+        Ok(url) if !url.trim().is_empty() => Ok(url.trim().to_string()),
+        _ => default
+            .map(str::to_string)
+            .ok_or(RpcError::NotConfigured(var)),
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    result: Option<LedgerEntriesResult>,
+    error: Option<serde_json::Value>,
+}
+
+#[derive(Deserialize)]
+struct LedgerEntriesResult {
+    #[serde(default)]
+    entries: Vec<LedgerEntry>,
+}
+
+#[derive(Deserialize)]
+struct LedgerEntry {
+    xdr: String,
+}
+
+/// Fetch the hex WASM hash currently deployed at `address`.
+pub async fn fetch_wasm_hash(rpc_url: &str, address: &str) -> Result<String, RpcError> {
+    let contract = decode_contract_address(address)?;
+    let key = STANDARD.encode(instance_ledger_key(&contract));
+
+    let client = reqwest::Client::builder()
+        .timeout(RPC_TIMEOUT)
+        .build()
+        .map_err(|err| RpcError::Unreachable(err.to_string()))?;
+
+    let response = client
+        .post(rpc_url)
+        .json(&serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "getLedgerEntries",
+            "params": { "keys": [key] },
+        }))
+        .send()
+        .await
+        .map_err(|err| RpcError::Unreachable(err.to_string()))?;
+
+    if !response.status().is_success() {
+        return Err(RpcError::Unreachable(format!("HTTP {}", response.status())));
+    }
+
+    let body: RpcResponse = response
+        .json()
+        .await
+        .map_err(|err| RpcError::Malformed(err.to_string()))?;
+
+    if let Some(error) = body.error {
+        return Err(RpcError::Malformed(error.to_string()));
+    }
+
+    let entry = body
+        .result
+        .and_then(|result| result.entries.into_iter().next())
+        .ok_or(RpcError::NotDeployed)?;
+
+    let xdr = STANDARD
+        .decode(entry.xdr.trim())
+        .map_err(|err| RpcError::Malformed(err.to_string()))?;
+
+    parse_instance_wasm_hash(&xdr).map(hex::encode)
+}
+
+/// Decode a `C...` strkey into the 32-byte contract id.
+pub fn decode_contract_address(address: &str) -> Result<[u8; 32], RpcError> {
+    let raw = base32_decode(address.trim()).ok_or(RpcError::InvalidAddress)?;
+    // version byte + 32-byte payload + 2-byte checksum
+    if raw.len() != 35 || raw[0] != STRKEY_CONTRACT_VERSION {
+        return Err(RpcError::InvalidAddress);
+    }
+
+    let (body, checksum) = raw.split_at(33);
+    let expected = crc16_xmodem(body).to_le_bytes();
+    if checksum != expected {
+        return Err(RpcError::InvalidAddress);
+    }
+
+    let mut contract = [0u8; 32];
+    contract.copy_from_slice(&body[1..]);
+    Ok(contract)
+}
+
+/// XDR `LedgerKey::ContractData` for the contract's instance entry
+fn instance_ledger_key(contract: &[u8; 32]) -> Vec<u8> {
+    let mut key = Vec::with_capacity(48);
+    key.extend_from_slice(&LEDGER_ENTRY_CONTRACT_DATA.to_be_bytes());
+    key.extend_from_slice(&SC_ADDRESS_CONTRACT.to_be_bytes());
+    key.extend_from_slice(contract);
+    key.extend_from_slice(&SCV_LEDGER_KEY_CONTRACT_INSTANCE.to_be_bytes());
+    key.extend_from_slice(&DURABILITY_PERSISTENT.to_be_bytes());
+    key
+}
+
+/// Pull the WASM hash out of an XDR `LedgerEntryData` for a contract instance.
+fn parse_instance_wasm_hash(xdr: &[u8]) -> Result<[u8; 32], RpcError> {
+    let mut reader = XdrReader { buf: xdr, pos: 0 };
+    let malformed = |what: &str| RpcError::Malformed(format!("ledger entry: {}", what));
+
+    if reader.u32()? != LEDGER_ENTRY_CONTRACT_DATA {
+        return Err(malformed("not contract data"));
+    }
+    // ContractDataEntry.ext (ExtensionPoint v0 has no body)
+    if reader.u32()? != 0 {
+        return Err(malformed("unsupported extension"));
+    }
+    match reader.u32()? {
+        SC_ADDRESS_CONTRACT => {
+            reader.bytes(32)?;
+        }
+        SC_ADDRESS_ACCOUNT => {
+            // PublicKey type + ed25519 key
+            reader.u32()?;
+            reader.bytes(32)?;
+        }
+        _ => return Err(malformed("unknown address type")),
+    }
+    if reader.u32()? != SCV_LEDGER_KEY_CONTRACT_INSTANCE {
+        return Err(malformed("not an instance key"));
+    }
+    // durability
+    reader.u32()?;
+    if reader.u32()? != SCV_CONTRACT_INSTANCE {
+        return Err(malformed("value is not a contract instance"));
+    }
+    if reader.u32()? != CONTRACT_EXECUTABLE_WASM {
+        return Err(malformed("contract is not backed by WASM"));
+    }
+
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(reader.bytes(32)?);
+    Ok(hash)
+}
+
+struct XdrReader<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> XdrReader<'a> {
+    fn bytes(&mut self, len: usize) -> Result<&'a [u8], RpcError> {
+        let end = self.pos + len;
+        let slice = self
+            .buf
+            .get(self.pos..end)
+            .ok_or_else(|| RpcError::Malformed("ledger entry: truncated".to_string()))?;
+        self.pos = end;
+        Ok(slice)
+    }
+
+    fn u32(&mut self) -> Result<u32, RpcError> {
+        let bytes = self.bytes(4)?;
+        Ok(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+}
+
+/// RFC 4648 base32 without padding, as used by Stellar strkeys
+fn base32_decode(input: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(input.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+
+    for c in input.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'2'..=b'7' => c - b'2' + 26,
+            _ => return None,
+        };
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+
+    Some(out)
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc: u16 = 0;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn base32_encode(data: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+        let mut out = String::new();
+        let mut buffer: u32 = 0;
+        let mut bits = 0;
+        for byte in data {
+            buffer = (buffer << 8) | *byte as u32;
+            bits += 8;
+            while bits >= 5 {
+                bits -= 5;
+                out.push(ALPHABET[((buffer >> bits) & 31) as usize] as char);
+            }
+            buffer &= (1 << bits) - 1;
+        }
+        if bits > 0 {
+            out.push(ALPHABET[((buffer << (5 - bits)) & 31) as usize] as char);
+        }
+        out
+    }
+
+    fn contract_strkey(id: [u8; 32]) -> String {
+        let mut raw = vec![STRKEY_CONTRACT_VERSION];
+        raw.extend_from_slice(&id);
+        let crc = crc16_xmodem(&raw);
+        raw.extend_from_slice(&crc.to_le_bytes());
+        base32_encode(&raw)
+    }
+
+    #[test]
+    fn decodes_contract_strkey() {
+        let id = [7u8; 32];
+        let address = contract_strkey(id);
+        assert!(address.starts_with('C'));
+        assert_eq!(address.len(), 56);
+        assert_eq!(decode_contract_address(&address).unwrap(), id);
+    }
+
+    #[test]
+    fn rejects_bad_checksum_and_account_keys() {
+        let mut address = contract_strkey([7u8; 32]);
+        address.replace_range(10..11, if &address[10..11] == "A" { "B" } else { "A" });
+        assert!(matches!(
+            decode_contract_address(&address),
+            Err(RpcError::InvalidAddress)
+        ));
+        assert!(matches!(
+            decode_contract_address("GAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA"),
+            Err(RpcError::InvalidAddress)
+        ));
+    }
+
+    #[test]
+    fn parses_wasm_hash_from_instance_entry() {
+        let contract = [1u8; 32];
+        let wasm_hash = [9u8; 32];
+
+        let mut entry = Vec::new();
+        entry.extend_from_slice(&LEDGER_ENTRY_CONTRACT_DATA.to_be_bytes());
+        entry.extend_from_slice(&0u32.to_be_bytes());
+        entry.extend_from_slice(&SC_ADDRESS_CONTRACT.to_be_bytes());
+        entry.extend_from_slice(&contract);
+        entry.extend_from_slice(&SCV_LEDGER_KEY_CONTRACT_INSTANCE.to_be_bytes());
+        entry.extend_from_slice(&DURABILITY_PERSISTENT.to_be_bytes());
+        entry.extend_from_slice(&SCV_CONTRACT_INSTANCE.to_be_bytes());
+        entry.extend_from_slice(&CONTRACT_EXECUTABLE_WASM.to_be_bytes());
+        entry.extend_from_slice(&wasm_hash);
+        // trailing storage map is ignored
+        entry.extend_from_slice(&0u32.to_be_bytes());
+
+        assert_eq!(parse_instance_wasm_hash(&entry).unwrap(), wasm_hash);
+        assert!(parse_instance_wasm_hash(&entry[..40]).is_err());
+    }
+
+    #[test]
+    fn mainnet_requires_explicit_rpc_url() {
+        std::env::remove_var("SOROBAN_RPC_URL_MAINNET");
+        assert!(matches!(
+            rpc_url_for(&Network::Mainnet),
+            Err(RpcError::NotConfigured("SOROBAN_RPC_URL_MAINNET"))
+        ));
+        assert!(rpc_url_for(&Network::Futurenet).is_ok());
+    }
+}
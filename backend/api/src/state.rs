@@ -1,8 +1,10 @@
-use std::time::Instant;
-use std::sync::Arc;
+use std::time::{Duration, Instant};
+use std::sync::{Arc, RwLock};
 use sqlx::PgPool;
 use prometheus::Registry;
 use crate::cache::{CacheLayer, CacheConfig};
+use crate::downloads::DownloadCounter;
+use crate::template_usage::InstantiationCounter;
 
 /// Application state shared across handlers
 #[derive(Clone)]
@@ -11,6 +13,9 @@ pub struct AppState {
     pub started_at: Instant,
     pub cache: Arc<CacheLayer>,
     pub registry: Registry,
+    pub downloads: Arc<DownloadCounter>,
+    pub template_usage: Arc<InstantiationCounter>,
+    pub stats: Arc<StatsCache>,
 }
 
 impl AppState {
@@ -21,6 +26,274 @@ impl AppState {
             started_at: Instant::now(),
             cache: Arc::new(CacheLayer::new(config)),
             registry,
+            downloads: Arc::new(DownloadCounter::default()),
+            template_usage: Arc::new(InstantiationCounter::default()),
+            stats: Arc::new(StatsCache::new(stats_ttl_from_env())),
         }
     }
 }
+
+/// Env var naming the database that database-backed tests run against
+#[cfg(test)]
+pub const TEST_DATABASE_URL_ENV: &str = "TEST_DATABASE_URL";
+
+#[cfg(test)]
+impl AppState {
+    /// State over a pool that never connects, for tests of paths that refuse
+    /// or answer before touching the database
+    pub(crate) fn for_tests() -> Self {
+        let db = sqlx::pool::PoolOptions::new()
+            .max_connections(1)
+            .acquire_timeout(Duration::from_millis(50))
+            .connect_lazy("postgres://registry@127.0.0.1:1/unreachable")
+            .expect("lazy pool");
+        Self::new(db, Registry::new())
+    }
+
+    /// State over the migrated database in `TEST_DATABASE_URL`; `None` when
+    /// it is unset, so database-backed tests skip
+    pub(crate) async fn for_database_tests() -> Option<Self> {
+        let url = std::env::var(TEST_DATABASE_URL_ENV).ok()?;
+        let db = PgPool::connect(&url)
+            .await
+            .expect("connect to the test database");
+        sqlx::migrate!("../../database/migrations")
+            .run(&db)
+            .await
+            .expect("migrate the test database");
+        Some(Self::new(db, Registry::new()))
+    }
+
+    /// Insert a publisher with a unique address that passes publish
+    /// validation; returns its id
+    pub(crate) async fn insert_publisher(&self) -> uuid::Uuid {
+        let (a, b) = (uuid::Uuid::new_v4(), uuid::Uuid::new_v4());
+        let suffix = format!("{}{}", a.simple(), b.simple()).to_uppercase();
+        let address = format!("G{}", &suffix[..55]);
+        sqlx::query_scalar("INSERT INTO publishers (stellar_address) VALUES ($1) RETURNING id")
+            .bind(address)
+            .fetch_one(&self.db)
+            .await
+            .expect("insert publisher")
+    }
+
+    /// Insert an organization with `members` as `(publisher, role)`; returns its id
+    pub(crate) async fn insert_organization(&self, members: &[(uuid::Uuid, &str)]) -> uuid::Uuid {
+        let id: uuid::Uuid =
+            sqlx::query_scalar("INSERT INTO organizations (name) VALUES ($1) RETURNING id")
+                .bind(format!("org-{}", uuid::Uuid::new_v4()))
+                .fetch_one(&self.db)
+                .await
+                .expect("insert organization");
+        for (publisher, role) in members {
+            sqlx::query(
+                "INSERT INTO organization_members (organization_id, publisher_id, role)
+                 VALUES ($1, $2, $3)",
+            )
+            .bind(id)
+            .bind(publisher)
+            .bind(role)
+            .execute(&self.db)
+            .await
+            .expect("insert organization member");
+        }
+        id
+    }
+
+    /// Insert a contract published by `publisher`, `visibility` being
+    /// `public` or `private`; returns its id
+    pub(crate) async fn insert_contract(
+        &self,
+        publisher: uuid::Uuid,
+        organization: Option<uuid::Uuid>,
+        visibility: &str,
+    ) -> uuid::Uuid {
+        let suffix = uuid::Uuid::new_v4().simple().to_string().to_uppercase();
+        sqlx::query_scalar(
+            "INSERT INTO contracts
+                (contract_id, wasm_hash, name, publisher_id, organization_id, network, visibility)
+             VALUES ($1, $2, $3, $4, $5, 'testnet', $6)
+             RETURNING id",
+        )
+        .bind(format!("C{}", suffix))
+        .bind("ab".repeat(32))
+        .bind(format!("contract-{}", suffix.to_lowercase()))
+        .bind(publisher)
+        .bind(organization)
+        .bind(visibility)
+        .fetch_one(&self.db)
+        .await
+        .expect("insert contract")
+    }
+
+    /// Insert a private contract owned by a fresh publisher; returns its id
+    pub(crate) async fn insert_private_contract(&self) -> uuid::Uuid {
+        let publisher = self.insert_publisher().await;
+        self.insert_contract(publisher, None, "private").await
+    }
+
+    /// Insert a version of `contract`; returns the version row's id
+    pub(crate) async fn insert_version(&self, contract: uuid::Uuid, version: &str) -> uuid::Uuid {
+        sqlx::query_scalar(
+            "INSERT INTO contract_versions (contract_id, version, wasm_hash)
+             VALUES ($1, $2, $3)
+             RETURNING id",
+        )
+        .bind(contract)
+        .bind(version)
+        .bind("cd".repeat(32))
+        .fetch_one(&self.db)
+        .await
+        .expect("insert version")
+    }
+
+    /// Insert a template at version 1.0.0 owned by `owner` (`None` for a
+    /// registry template); returns its slug
+    pub(crate) async fn insert_template(&self, owner: Option<uuid::Uuid>, private: bool) -> String {
+        let slug = format!("template-{}", uuid::Uuid::new_v4().simple());
+        sqlx::query(
+            "INSERT INTO contract_templates
+                (slug, name, category, source_code, owner_publisher_id, is_private)
+             VALUES ($1, $1, 'token', '// v1', $2, $3)",
+        )
+        .bind(&slug)
+        .bind(owner)
+        .bind(private)
+        .execute(&self.db)
+        .await
+        .expect("insert template");
+        slug
+    }
+
+    /// A working API key for `publisher`
+    pub(crate) async fn api_key(&self, publisher: uuid::Uuid) -> String {
+        crate::auth::issue_test_key(&self.db, publisher).await
+    }
+
+    /// Send `request` through the API routes, wired as the server wires them
+    pub(crate) async fn send(
+        &self,
+        request: axum::http::Request<axum::body::Body>,
+    ) -> axum::response::Response {
+        use tower::ServiceExt;
+
+        crate::routes::api_routes(self)
+            .with_state(self.clone())
+            .oneshot(request)
+            .await
+            .unwrap()
+    }
+
+    /// Status of an unauthenticated GET
+    pub(crate) async fn anonymous_get(&self, uri: &str) -> axum::http::StatusCode {
+        let response = self.send(test_request("GET", uri, None, None)).await;
+        response.status()
+    }
+}
+
+/// A request, authenticated with `key` and carrying `body` as JSON when given
+#[cfg(test)]
+pub(crate) fn test_request(
+    method: &str,
+    uri: &str,
+    key: Option<&str>,
+    body: Option<serde_json::Value>,
+) -> axum::http::Request<axum::body::Body> {
+    use axum::http::header;
+
+    let mut request = axum::http::Request::builder().method(method).uri(uri);
+    if let Some(key) = key {
+        request = request.header(header::AUTHORIZATION, format!("Bearer {}", key));
+    }
+    match body {
+        Some(body) => request
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(axum::body::Body::from(body.to_string())),
+        None => request.body(axum::body::Body::empty()),
+    }
+    .unwrap()
+}
+
+/// A response body parsed as JSON
+#[cfg(test)]
+pub(crate) async fn json_body(response: axum::response::Response) -> serde_json::Value {
+    let body = response.into_body();
+    let bytes = axum::body::to_bytes(body, usize::MAX).await.unwrap();
+    serde_json::from_slice(&bytes).unwrap()
+}
+
+/// Env var setting how long `GET /api/stats` results are reused, in seconds
+pub const STATS_TTL_ENV: &str = "STATS_CACHE_TTL_SECONDS";
+pub const DEFAULT_STATS_TTL_SECS: u64 = 30;
+
+/// Configured stats TTL; `0` disables the cache, unparsable values use the default
+pub fn stats_ttl_from_env() -> Duration {
+    let secs = std::env::var(STATS_TTL_ENV)
+        .ok()
+        .and_then(|value| value.trim().parse().ok())
+        .unwrap_or(DEFAULT_STATS_TTL_SECS);
+    Duration::from_secs(secs)
+}
+
+/// The last registry stats computed, reused until they are `ttl` old or a
+/// write invalidates them
+pub struct StatsCache {
+    ttl: Duration,
+    entry: RwLock<Option<(Instant, serde_json::Value)>>,
+}
+
+impl StatsCache {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entry: RwLock::new(None),
+        }
+    }
+
+    pub fn ttl(&self) -> Duration {
+        self.ttl
+    }
+
+    /// The cached stats, if they are still fresh
+    pub fn get(&self) -> Option<serde_json::Value> {
+        let entry = self.entry.read().unwrap_or_else(|poisoned| poisoned.into_inner());
+        entry
+            .as_ref()
+            .filter(|(computed_at, _)| computed_at.elapsed() < self.ttl)
+            .map(|(_, stats)| stats.clone())
+    }
+
+    pub fn put(&self, stats: serde_json::Value) {
+        *self.entry.write().unwrap_or_else(|poisoned| poisoned.into_inner()) =
+            Some((Instant::now(), stats));
+    }
+
+    /// Drop the cached stats so the next request recomputes them
+    pub fn invalidate(&self) {
+        *self.entry.write().unwrap_or_else(|poisoned| poisoned.into_inner()) = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stats_are_reused_until_invalidated() {
+        let cache = StatsCache::new(Duration::from_secs(30));
+        assert!(cache.get().is_none());
+
+        cache.put(serde_json::json!({ "total_contracts": 3 }));
+        assert_eq!(cache.get().unwrap()["total_contracts"], 3);
+
+        cache.invalidate();
+        assert!(cache.get().is_none());
+    }
+
+    #[test]
+    fn zero_ttl_disables_the_cache() {
+        let cache = StatsCache::new(Duration::ZERO);
+        cache.put(serde_json::json!({ "total_contracts": 3 }));
+        assert!(cache.get().is_none());
+    }
+}
@@ -1,15 +1,25 @@
+use std::collections::BTreeMap;
+
+use async_zip::{tokio::write::ZipFileWriter, Compression, ZipEntryBuilder};
 use axum::{
+    body::Body,
     extract::{Path, Query, State},
-    response::IntoResponse,
-    Json,
+    http::{header, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    Extension, Json,
 };
+use futures::TryStreamExt;
 use serde::{Deserialize, Serialize};
-use sqlx::FromRow;
+use sqlx::{FromRow, PgPool};
+use tokio_util::io::ReaderStream;
 use uuid::Uuid;
 
 use crate::{
-    error::{ApiError, ApiResult},
+    artifacts,
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
     handlers::db_internal_error,
+    pagination::{PageParams, Paginated},
     state::AppState,
 };
 
@@ -24,15 +34,39 @@ pub struct ContractTemplate {
     pub source_code: String,
     pub parameters: serde_json::Value,
     pub install_count: i64,
+    /// Times the template was instantiated, flushed in batches
+    pub instantiations: i64,
+    /// Publisher that forked this template; `None` for registry templates
+    pub owner_publisher_id: Option<Uuid>,
+    /// Visible only to its owner (and admins)
+    pub is_private: bool,
+    /// Template this one was forked from
+    pub forked_from: Option<Uuid>,
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+impl ContractTemplate {
+    /// Whether `caller` may see this template
+    pub fn readable_by(&self, caller: Option<Caller>) -> bool {
+        match caller {
+            _ if !self.is_private => true,
+            Some(Caller::Admin) => true,
+            Some(Caller::Publisher(id)) => self.owner_publisher_id == Some(id),
+            None => false,
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct TemplateListParams {
     pub category: Option<String>,
 }
 
+/// SQL condition matching [`ContractTemplate::readable_by`]: `$1` is whether
+/// the caller is an admin, `$2` the calling publisher, if any
+const READABLE_TEMPLATE: &str = "($1 OR NOT is_private OR owner_publisher_id = $2)";
+
 #[derive(Debug, Deserialize)]
 pub struct CloneRequest {
     pub name: String,
@@ -48,29 +82,80 @@ pub struct CloneResponse {
     pub version: String,
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/templates",
+    tag = "templates",
+    params(
+        ("category" = Option<String>, Query, description = "Filter by category"),
+        PageParams,
+    ),
+    responses(
+        (status = 200, description = "Available templates, most installed first"),
+    ),
+)]
 pub async fn list_templates(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
     Query(params): Query<TemplateListParams>,
-) -> ApiResult<Json<Vec<ContractTemplate>>> {
-    let templates: Vec<ContractTemplate> = match params.category {
-        Some(cat) => sqlx::query_as(
-                "SELECT * FROM contract_templates WHERE category = $1 ORDER BY install_count DESC",
-            )
-            .bind(cat)
-            .fetch_all(&state.db)
-            .await
-            .map_err(|e| db_internal_error("list templates by category", e))?,
-        None => sqlx::query_as("SELECT * FROM contract_templates ORDER BY install_count DESC")
-            .fetch_all(&state.db)
-            .await
-            .map_err(|e| db_internal_error("list all templates", e))?,
-    };
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<ContractTemplate>>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    let admin = matches!(caller, Some(Caller::Admin));
+    let publisher = caller.and_then(|caller| caller.publisher_id());
+    let condition = format!(
+        "{} AND ($3::text IS NULL OR category = $3)",
+        READABLE_TEMPLATE
+    );
+
+    let templates: Vec<ContractTemplate> = sqlx::query_as(&format!(
+        "SELECT * FROM contract_templates WHERE {}
+         ORDER BY install_count DESC, id
+         LIMIT $4 OFFSET $5",
+        condition
+    ))
+    .bind(admin)
+    .bind(publisher)
+    .bind(&params.category)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_internal_error("list templates", e))?;
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM contract_templates WHERE {}",
+        condition
+    ))
+    .bind(admin)
+    .bind(publisher)
+    .bind(&params.category)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_internal_error("count templates", e))?;
 
-    Ok(Json(templates))
+    Ok(Json(Paginated::new(
+        templates,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
 }
 
+#[utoipa::path(
+    get,
+    path = "/api/templates/{slug}",
+    tag = "templates",
+    params(
+        ("slug" = String, Path, description = "Template slug"),
+    ),
+    responses(
+        (status = 200, description = "Template details"),
+        (status = 403, description = "Template is private"),
+    ),
+)]
 pub async fn get_template(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
     Path(slug): Path<String>,
 ) -> ApiResult<Json<ContractTemplate>> {
     let template: ContractTemplate =
@@ -80,17 +165,31 @@ pub async fn get_template(
             .await
             .map_err(|err| match err {
                 sqlx::Error::RowNotFound => ApiError::not_found(
-                    "TemplateNotFound",
+                    ErrorCode::TemplateNotFound,
                     format!("No template found with slug: {}", slug),
                 ),
                 _ => db_internal_error("get template by slug", err),
             })?;
+    ensure_readable(&template, caller.map(|Extension(caller)| caller))?;
 
     Ok(Json(template))
 }
 
+#[utoipa::path(
+    post,
+    path = "/api/templates/{slug}/clone",
+    tag = "templates",
+    params(
+        ("slug" = String, Path, description = "Template slug"),
+    ),
+    responses(
+        (status = 200, description = "Rendered template source"),
+        (status = 403, description = "Template is private"),
+    ),
+)]
 pub async fn clone_template(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
     Path(slug): Path<String>,
     Json(req): Json<CloneRequest>,
 ) -> impl IntoResponse {
@@ -103,13 +202,16 @@ pub async fn clone_template(
             Ok(t) => t,
             Err(sqlx::Error::RowNotFound) => {
                 return ApiError::not_found(
-                    "TemplateNotFound",
+                    ErrorCode::TemplateNotFound,
                     format!("No template found with slug: {}", slug),
                 )
                 .into_response()
             }
             Err(e) => return db_internal_error("get template for clone", e).into_response(),
         };
+    if let Err(err) = ensure_readable(&template, caller.map(|Extension(caller)| caller)) {
+        return err.into_response();
+    }
 
     let source = apply_parameters(&template.source_code, &req.name, &req.parameters);
 
@@ -165,3 +267,1173 @@ fn apply_parameters(
 
     out
 }
+
+// ─────────────────────────────────────────────────────────
+// POST /api/templates/:slug/instantiate
+// ─────────────────────────────────────────────────────────
+
+/// One entry of a template's `parameters` array
+#[derive(Debug, Clone, PartialEq)]
+pub struct TemplateParameter {
+    pub name: String,
+    pub required: bool,
+    pub default: Option<String>,
+}
+
+/// Accepts `["name", ...]` (all required) or
+/// `[{"name": ..., "required": bool, "default": ...}, ...]`
+pub fn parse_template_parameters(raw: &serde_json::Value) -> Vec<TemplateParameter> {
+    let Some(items) = raw.as_array() else {
+        return Vec::new();
+    };
+    items
+        .iter()
+        .filter_map(|item| match item {
+            serde_json::Value::String(name) => Some(TemplateParameter {
+                name: name.clone(),
+                required: true,
+                default: None,
+            }),
+            serde_json::Value::Object(obj) => {
+                let name = obj.get("name")?.as_str()?.to_string();
+                let default = obj.get("default").filter(|v| !v.is_null()).map(value_to_string);
+                let required = obj
+                    .get("required")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(default.is_none());
+                Some(TemplateParameter {
+                    name,
+                    required,
+                    default,
+                })
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+fn value_to_string(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+/// Check the supplied values against the template's declared parameters and
+/// fill in defaults. On failure returns `(unknown, missing)` parameter names.
+pub fn resolve_parameters(
+    declared: &[TemplateParameter],
+    supplied: &serde_json::Map<String, serde_json::Value>,
+) -> Result<BTreeMap<String, String>, (Vec<String>, Vec<String>)> {
+    let unknown: Vec<String> = supplied
+        .keys()
+        .filter(|key| !declared.iter().any(|p| &p.name == *key))
+        .cloned()
+        .collect();
+
+    let mut values = BTreeMap::new();
+    let mut missing = Vec::new();
+    for param in declared {
+        match supplied.get(&param.name).filter(|v| !v.is_null()) {
+            Some(value) => {
+                values.insert(param.name.clone(), value_to_string(value));
+            }
+            None => match &param.default {
+                Some(default) => {
+                    values.insert(param.name.clone(), default.clone());
+                }
+                None if param.required => missing.push(param.name.clone()),
+                None => {
+                    values.insert(param.name.clone(), String::new());
+                }
+            },
+        }
+    }
+
+    if unknown.is_empty() && missing.is_empty() {
+        Ok(values)
+    } else {
+        Err((unknown, missing))
+    }
+}
+
+/// Replace `{{param}}` placeholders in one pass, so substituted values are
+/// never re-expanded. Unknown placeholders are left untouched.
+pub fn render_template(content: &str, values: &BTreeMap<String, String>) -> String {
+    let mut out = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        out.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after.find("}}") {
+            Some(end) => {
+                let key = after[..end].trim();
+                match values.get(key) {
+                    Some(value) => out.push_str(value),
+                    None => out.push_str(&rest[start..start + 2 + end + 2]),
+                }
+                rest = &after[end + 2..];
+            }
+            None => {
+                out.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Keep archive entries relative and inside the project root
+fn archive_path(root: &str, path: &str) -> Option<String> {
+    let path = path.replace('\\', "/");
+    let parts: Vec<&str> = path
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect();
+    if parts.is_empty() || parts.contains(&"..") {
+        return None;
+    }
+    Some(format!("{}/{}", root, parts.join("/")))
+}
+
+#[derive(Debug, FromRow)]
+struct TemplateFile {
+    path: String,
+    content: String,
+}
+
+// ─────────────────────────────────────────────────────────
+// Template versions
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct TemplateVersion {
+    pub id: Uuid,
+    pub template_id: Uuid,
+    pub version: String,
+    #[serde(skip_serializing)]
+    pub source_code: String,
+    pub parameters: serde_json::Value,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateFileInput {
+    pub path: String,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTemplateVersionRequest {
+    pub version: String,
+    /// Used as `src/lib.rs` when no `files` are given
+    #[serde(default)]
+    pub source_code: Option<String>,
+    #[serde(default)]
+    pub parameters: Option<serde_json::Value>,
+    #[serde(default)]
+    pub files: Vec<TemplateFileInput>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct InstantiateParams {
+    /// Defaults to the latest version
+    pub version: Option<String>,
+}
+
+fn parse_version(raw: &str) -> Option<semver::Version> {
+    semver::Version::parse(raw.trim().trim_start_matches('v')).ok()
+}
+
+/// The requested version, or the highest semver when none is requested
+pub fn select_version<'a>(
+    versions: &'a [TemplateVersion],
+    requested: Option<&str>,
+) -> Option<&'a TemplateVersion> {
+    match requested {
+        Some(requested) => versions.iter().find(|v| v.version == requested),
+        None => versions
+            .iter()
+            .filter_map(|v| parse_version(&v.version).map(|parsed| (parsed, v)))
+            .max_by(|(a, _), (b, _)| a.cmp(b))
+            .map(|(_, v)| v),
+    }
+}
+
+async fn load_versions(pool: &PgPool, template_id: Uuid) -> ApiResult<Vec<TemplateVersion>> {
+    sqlx::query_as("SELECT * FROM template_versions WHERE template_id = $1")
+        .bind(template_id)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| db_internal_error("list template versions", e))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/templates/{slug}/versions",
+    tag = "templates",
+    params(
+        ("slug" = String, Path, description = "Template slug or id"),
+        PageParams,
+    ),
+    responses(
+        (status = 200, description = "Template versions, newest first"),
+        (status = 403, description = "Template is private"),
+        (status = 404, description = "Template not found"),
+    ),
+)]
+pub async fn list_template_versions(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(key): Path<String>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<TemplateVersion>>> {
+    let template = find_template(&state.db, &key).await?;
+    ensure_readable(&template, caller.map(|Extension(caller)| caller))?;
+    let mut versions = load_versions(&state.db, template.id).await?;
+    // Semver order can't be expressed in SQL, so the page is cut after sorting
+    versions.sort_by(|a, b| match (parse_version(&a.version), parse_version(&b.version)) {
+        (Some(a), Some(b)) => b.cmp(&a),
+        _ => b.created_at.cmp(&a.created_at),
+    });
+    let total = versions.len() as i64;
+    let versions = versions
+        .into_iter()
+        .skip(page.offset() as usize)
+        .take(page.limit() as usize)
+        .collect();
+    Ok(Json(Paginated::new(
+        versions,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+/// Publish a new template version. Earlier versions stay instantiable.
+/// Only the template's owner may publish, and only admins may publish to the
+/// registry's own templates.
+#[utoipa::path(
+    post,
+    path = "/api/templates/{slug}/versions",
+    tag = "templates",
+    params(
+        ("slug" = String, Path, description = "Template slug or id"),
+    ),
+    responses(
+        (status = 201, description = "Version created"),
+        (status = 401, description = "No API key"),
+        (status = 403, description = "Caller does not own the template"),
+        (status = 404, description = "Template not found, or private to someone else"),
+        (status = 409, description = "Version already exists"),
+        (status = 422, description = "Version is not valid semver or has no content"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn create_template_version(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(key): Path<String>,
+    Json(req): Json<CreateTemplateVersionRequest>,
+) -> ApiResult<(StatusCode, Json<TemplateVersion>)> {
+    let template = find_template(&state.db, &key).await?;
+    ensure_owned(&template, &key, caller)?;
+
+    let version = req.version.trim().to_string();
+    let Some(parsed) = parse_version(&version) else {
+        return Err(ApiError::unprocessable(
+            ErrorCode::InvalidVersion,
+            format!("'{}' is not a valid semver version", req.version),
+        ));
+    };
+    if req.files.is_empty() && req.source_code.is_none() {
+        return Err(ApiError::unprocessable(
+            ErrorCode::EmptyTemplateVersion,
+            "Provide source_code or at least one file",
+        ));
+    }
+    if let Some(bad) = req.files.iter().find(|f| archive_path("root", &f.path).is_none()) {
+        return Err(ApiError::unprocessable(
+            ErrorCode::InvalidTemplatePath,
+            format!("'{}' is not a relative path inside the project", bad.path),
+        ));
+    }
+
+    let source_code = req
+        .source_code
+        .clone()
+        .or_else(|| {
+            req.files
+                .iter()
+                .find(|f| f.path.trim_start_matches('/') == "src/lib.rs")
+                .map(|f| f.content.clone())
+        })
+        .unwrap_or_default();
+    let parameters = req.parameters.clone().unwrap_or_else(|| template.parameters.clone());
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_internal_error("begin template version", e))?;
+
+    let created: TemplateVersion = sqlx::query_as(
+        "INSERT INTO template_versions (template_id, version, source_code, parameters)
+         VALUES ($1, $2, $3, $4)
+         RETURNING *",
+    )
+    .bind(template.id)
+    .bind(&version)
+    .bind(&source_code)
+    .bind(&parameters)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::new(
+            StatusCode::CONFLICT,
+            ErrorCode::DuplicateTemplateVersion,
+            format!("Version {} of '{}' already exists", version, template.slug),
+        ),
+        _ => db_internal_error("create template version", err),
+    })?;
+
+    for file in &req.files {
+        sqlx::query(
+            "INSERT INTO template_version_files (version_id, path, content) VALUES ($1, $2, $3)
+             ON CONFLICT (version_id, path) DO UPDATE SET content = EXCLUDED.content",
+        )
+        .bind(created.id)
+        .bind(file.path.trim_start_matches('/'))
+        .bind(&file.content)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_internal_error("store template version file", e))?;
+    }
+
+    // Keep the template row describing its latest version
+    let is_latest = match parse_version(&template.version) {
+        Some(current) => parsed > current,
+        None => true,
+    };
+    if is_latest {
+        sqlx::query(
+            "UPDATE contract_templates
+             SET version = $2, source_code = $3, parameters = $4, updated_at = NOW()
+             WHERE id = $1",
+        )
+        .bind(template.id)
+        .bind(&version)
+        .bind(&source_code)
+        .bind(&parameters)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_internal_error("update template latest version", e))?;
+    }
+
+    tx.commit()
+        .await
+        .map_err(|e| db_internal_error("commit template version", e))?;
+
+    Ok((StatusCode::CREATED, Json(created)))
+}
+
+async fn find_template(pool: &PgPool, key: &str) -> ApiResult<ContractTemplate> {
+    let query = match Uuid::parse_str(key) {
+        Ok(id) => sqlx::query_as("SELECT * FROM contract_templates WHERE id = $1").bind(id),
+        Err(_) => sqlx::query_as("SELECT * FROM contract_templates WHERE slug = $1").bind(key),
+    };
+    query
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| db_internal_error("get template", e))?
+        .ok_or_else(|| {
+            ApiError::not_found(
+                ErrorCode::TemplateNotFound,
+                format!("No template found with slug or id: {}", key),
+            )
+        })
+}
+
+fn ensure_readable(template: &ContractTemplate, caller: Option<Caller>) -> ApiResult<()> {
+    if template.readable_by(caller) {
+        return Ok(());
+    }
+    Err(ApiError::new(
+        StatusCode::FORBIDDEN,
+        ErrorCode::Forbidden,
+        format!("Template '{}' is private", template.slug),
+    ))
+}
+
+/// Allow owners and admins. A private template stays hidden from everyone
+/// else, so they get the same 404 as for a missing one.
+fn ensure_owned(template: &ContractTemplate, key: &str, caller: Caller) -> ApiResult<()> {
+    if !template.readable_by(Some(caller)) {
+        return Err(ApiError::not_found(
+            ErrorCode::TemplateNotFound,
+            format!("No template found with slug or id: {}", key),
+        ));
+    }
+    match caller {
+        Caller::Admin => Ok(()),
+        Caller::Publisher(id) if template.owner_publisher_id == Some(id) => Ok(()),
+        Caller::Publisher(_) => Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            ErrorCode::Forbidden,
+            format!("Only the owner of '{}' may publish versions", template.slug),
+        )),
+    }
+}
+
+// ─────────────────────────────────────────────────────────
+// Forks
+// ─────────────────────────────────────────────────────────
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ForkTemplateRequest {
+    /// Defaults to the origin's slug with a random suffix
+    #[serde(default)]
+    pub slug: Option<String>,
+    /// Defaults to the origin's name
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub private: bool,
+}
+
+/// Lowercase letters, digits and inner hyphens, as the seeded templates use
+pub fn valid_template_slug(slug: &str) -> bool {
+    (1..=64).contains(&slug.len())
+        && !slug.starts_with('-')
+        && !slug.ends_with('-')
+        && slug
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+}
+
+fn fork_slug(origin: &str, requested: Option<&str>) -> String {
+    match requested.map(str::trim).filter(|slug| !slug.is_empty()) {
+        Some(slug) => slug.to_string(),
+        None => {
+            let suffix = Uuid::new_v4().simple().to_string();
+            let base = origin.get(..55).unwrap_or(origin).trim_end_matches('-');
+            format!("{}-{}", base, &suffix[..8])
+        }
+    }
+}
+
+/// Copy a template's current version into a new template owned by the
+/// caller. The fork keeps its own rows, so later versions of the origin do
+/// not reach it.
+#[utoipa::path(
+    post,
+    path = "/api/templates/{slug}/fork",
+    tag = "templates",
+    params(
+        ("slug" = String, Path, description = "Template slug or id"),
+    ),
+    responses(
+        (status = 201, description = "The new template, with `forked_from` set"),
+        (status = 401, description = "No API key"),
+        (status = 403, description = "Template is private"),
+        (status = 404, description = "Template not found"),
+        (status = 409, description = "Slug already taken"),
+        (status = 422, description = "Invalid slug"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn fork_template(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(key): Path<String>,
+    body: Option<Json<ForkTemplateRequest>>,
+) -> ApiResult<(StatusCode, Json<ContractTemplate>)> {
+    let Some(Extension(caller)) = caller else {
+        return Err(ApiError::new(
+            StatusCode::UNAUTHORIZED,
+            ErrorCode::Unauthorized,
+            "Forking a template requires an API key",
+        ));
+    };
+    let req = body.map(|Json(req)| req).unwrap_or_default();
+    let origin = find_template(&state.db, &key).await?;
+    ensure_readable(&origin, Some(caller))?;
+
+    let slug = fork_slug(&origin.slug, req.slug.as_deref());
+    if !valid_template_slug(&slug) {
+        return Err(ApiError::unprocessable(
+            ErrorCode::InvalidTemplateSlug,
+            format!("'{}' is not a valid template slug", slug),
+        ));
+    }
+    let name = req
+        .name
+        .as_deref()
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .unwrap_or(&origin.name)
+        .to_string();
+
+    let versions = load_versions(&state.db, origin.id).await?;
+    let current = versions
+        .iter()
+        .find(|v| v.version == origin.version)
+        .or_else(|| select_version(&versions, None));
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|e| db_internal_error("begin template fork", e))?;
+
+    let fork: ContractTemplate = sqlx::query_as(
+        "INSERT INTO contract_templates
+             (slug, name, description, category, version, source_code, parameters,
+              owner_publisher_id, is_private, forked_from)
+         VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+         RETURNING *",
+    )
+    .bind(&slug)
+    .bind(&name)
+    .bind(&origin.description)
+    .bind(&origin.category)
+    .bind(current.map_or(&origin.version, |v| &v.version))
+    .bind(current.map_or(&origin.source_code, |v| &v.source_code))
+    .bind(current.map_or(&origin.parameters, |v| &v.parameters))
+    .bind(caller.publisher_id())
+    .bind(req.private)
+    .bind(origin.id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match &err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => ApiError::new(
+            StatusCode::CONFLICT,
+            ErrorCode::DuplicateTemplateSlug,
+            format!("A template with slug '{}' already exists", slug),
+        ),
+        _ => db_internal_error("create template fork", err),
+    })?;
+
+    let version_id: Uuid = sqlx::query_scalar(
+        "INSERT INTO template_versions (template_id, version, source_code, parameters)
+         VALUES ($1, $2, $3, $4)
+         RETURNING id",
+    )
+    .bind(fork.id)
+    .bind(&fork.version)
+    .bind(&fork.source_code)
+    .bind(&fork.parameters)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|e| db_internal_error("create fork version", e))?;
+
+    // Templates created before versioning keep their files in template_files
+    let copy_files = match current {
+        Some(current) => sqlx::query(
+            "INSERT INTO template_version_files (version_id, path, content)
+             SELECT $1, path, content FROM template_version_files WHERE version_id = $2",
+        )
+        .bind(version_id)
+        .bind(current.id),
+        None => sqlx::query(
+            "INSERT INTO template_version_files (version_id, path, content)
+             SELECT $1, path, content FROM template_files WHERE template_id = $2",
+        )
+        .bind(version_id)
+        .bind(origin.id),
+    };
+    copy_files
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| db_internal_error("copy fork files", e))?;
+
+    tx.commit()
+        .await
+        .map_err(|e| db_internal_error("commit template fork", e))?;
+
+    tracing::info!(origin = %origin.slug, fork = %fork.slug, "template forked");
+    Ok((StatusCode::CREATED, Json(fork)))
+}
+
+/// Templates forked directly from this one that the caller can see
+#[utoipa::path(
+    get,
+    path = "/api/templates/{slug}/forks",
+    tag = "templates",
+    params(
+        ("slug" = String, Path, description = "Template slug or id"),
+        PageParams,
+    ),
+    responses(
+        (status = 200, description = "Forks, newest first"),
+        (status = 403, description = "Template is private"),
+        (status = 404, description = "Template not found"),
+    ),
+)]
+pub async fn list_template_forks(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(key): Path<String>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<ContractTemplate>>> {
+    let caller = caller.map(|Extension(caller)| caller);
+    let template = find_template(&state.db, &key).await?;
+    ensure_readable(&template, caller)?;
+    let admin = matches!(caller, Some(Caller::Admin));
+    let publisher = caller.and_then(|caller| caller.publisher_id());
+    let condition = format!("{} AND forked_from = $3", READABLE_TEMPLATE);
+
+    let forks: Vec<ContractTemplate> = sqlx::query_as(&format!(
+        "SELECT * FROM contract_templates WHERE {}
+         ORDER BY created_at DESC, id DESC
+         LIMIT $4 OFFSET $5",
+        condition
+    ))
+    .bind(admin)
+    .bind(publisher)
+    .bind(template.id)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_internal_error("list template forks", e))?;
+    let total: i64 = sqlx::query_scalar(&format!(
+        "SELECT COUNT(*) FROM contract_templates WHERE {}",
+        condition
+    ))
+    .bind(admin)
+    .bind(publisher)
+    .bind(template.id)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|e| db_internal_error("count template forks", e))?;
+
+    Ok(Json(Paginated::new(
+        forks,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+// ─────────────────────────────────────────────────────────
+// Usage statistics
+// ─────────────────────────────────────────────────────────
+
+pub const DEFAULT_STATS_DAYS: i64 = 30;
+pub const MAX_STATS_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize)]
+pub struct TemplateStatsParams {
+    /// `day` (default), `week` or `month`
+    pub bucket: Option<String>,
+    /// How far back the buckets go, in days
+    pub days: Option<i64>,
+}
+
+/// Validated `bucket` and `days`; the bucket is a `date_trunc` unit
+pub fn stats_window(params: &TemplateStatsParams) -> ApiResult<(&'static str, i64)> {
+    let bucket = match params.bucket.as_deref().map(str::trim) {
+        None | Some("") | Some("day") => "day",
+        Some("week") => "week",
+        Some("month") => "month",
+        Some(other) => {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidStatsWindow,
+                format!("bucket must be day, week or month (got '{}')", other),
+            ))
+        }
+    };
+    let days = params.days.unwrap_or(DEFAULT_STATS_DAYS);
+    if !(1..=MAX_STATS_DAYS).contains(&days) {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidStatsWindow,
+            format!("days must be between 1 and {} (got {})", MAX_STATS_DAYS, days),
+        ));
+    }
+    Ok((bucket, days))
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct UsageBucket {
+    pub period_start: chrono::NaiveDate,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TemplateStats {
+    pub template_id: Uuid,
+    pub slug: String,
+    /// All-time instantiations
+    pub total: i64,
+    pub bucket: &'static str,
+    pub since: chrono::NaiveDate,
+    /// Buckets with at least one instantiation, oldest first
+    pub buckets: Vec<UsageBucket>,
+}
+
+/// Instantiation counts for a template. Counts are flushed in batches, so
+/// the latest few seconds may not be included yet.
+#[utoipa::path(
+    get,
+    path = "/api/templates/{slug}/stats",
+    tag = "templates",
+    params(
+        ("slug" = String, Path, description = "Template slug or id"),
+        ("bucket" = Option<String>, Query, description = "`day` (default), `week` or `month`"),
+        ("days" = Option<i64>, Query, description = "Days of history, 1-365 (default 30)"),
+    ),
+    responses(
+        (status = 200, description = "Total and bucketed instantiation counts"),
+        (status = 400, description = "Unknown bucket or out-of-range days"),
+        (status = 403, description = "Template is private"),
+        (status = 404, description = "Template not found"),
+    ),
+)]
+pub async fn get_template_stats(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(key): Path<String>,
+    Query(params): Query<TemplateStatsParams>,
+) -> ApiResult<Json<TemplateStats>> {
+    let (bucket, days) = stats_window(&params)?;
+    let template = find_template(&state.db, &key).await?;
+    ensure_readable(&template, caller.map(|Extension(caller)| caller))?;
+    let since = chrono::Utc::now().date_naive() - chrono::Duration::days(days - 1);
+
+    // Yesterday and today are read from the raw events, which the hourly
+    // rollup has not necessarily caught up with yet
+    let buckets: Vec<UsageBucket> = sqlx::query_as(
+        "SELECT date_trunc($2, u.day)::date AS period_start, SUM(u.n)::bigint AS count
+         FROM (
+             SELECT date AS day, count AS n FROM template_instantiation_daily
+             WHERE template_id = $1 AND date >= $3 AND date < CURRENT_DATE - 1
+             UNION ALL
+             SELECT DATE(created_at), count FROM template_instantiation_events
+             WHERE template_id = $1 AND created_at >= GREATEST($3, CURRENT_DATE - 1)
+         ) u
+         GROUP BY 1
+         ORDER BY 1",
+    )
+    .bind(template.id)
+    .bind(bucket)
+    .bind(since)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|e| db_internal_error("get template usage", e))?;
+
+    Ok(Json(TemplateStats {
+        template_id: template.id,
+        slug: template.slug,
+        total: template.instantiations,
+        bucket,
+        since,
+        buckets,
+    }))
+}
+
+/// Render the template with the given parameter values and download it as
+/// a zip. The archive is written while it is sent, one file at a time.
+#[utoipa::path(
+    post,
+    path = "/api/templates/{slug}/instantiate",
+    tag = "templates",
+    params(
+        ("slug" = String, Path, description = "Template slug or id"),
+        ("version" = Option<String>, Query, description = "Template version (defaults to latest)"),
+    ),
+    responses(
+        (status = 200, description = "Zip archive of the rendered project", content_type = "application/zip"),
+        (status = 403, description = "Template is private"),
+        (status = 404, description = "Template or version not found"),
+        (status = 422, description = "Unknown or missing parameters"),
+    ),
+)]
+pub async fn instantiate_template(
+    State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    Path(key): Path<String>,
+    Query(params): Query<InstantiateParams>,
+    Json(supplied): Json<serde_json::Map<String, serde_json::Value>>,
+) -> ApiResult<Response> {
+    let template = find_template(&state.db, &key).await?;
+    ensure_readable(&template, caller.map(|Extension(caller)| caller))?;
+    let versions = load_versions(&state.db, template.id).await?;
+    let selected = select_version(&versions, params.version.as_deref());
+    if let (Some(requested), None) = (&params.version, selected) {
+        return Err(ApiError::not_found(
+            ErrorCode::TemplateVersionNotFound,
+            format!("Template '{}' has no version {}", template.slug, requested),
+        ));
+    }
+
+    // Templates created before versioning have no version rows
+    let (version_id, parameters, fallback_source) = match selected {
+        Some(v) => (Some(v.id), v.parameters.clone(), v.source_code.clone()),
+        None => (None, template.parameters.clone(), template.source_code.clone()),
+    };
+    let declared = parse_template_parameters(&parameters);
+
+    let values = resolve_parameters(&declared, &supplied).map_err(|(unknown, missing)| {
+        let mut problems = Vec::new();
+        if !unknown.is_empty() {
+            problems.push(format!("unknown parameters: {}", unknown.join(", ")));
+        }
+        if !missing.is_empty() {
+            problems.push(format!("missing required parameters: {}", missing.join(", ")));
+        }
+        ApiError::unprocessable(ErrorCode::InvalidTemplateParameters, problems.join("; "))
+            .with_details(serde_json::json!({ "unknown": unknown, "missing": missing }))
+    })?;
+
+    let root = artifacts::sanitize_filename_part(&template.slug, "template");
+    let filename = format!("{}.zip", root);
+
+    let (reader, writer) = tokio::io::duplex(64 * 1024);
+    let pool = state.db.clone();
+    let usage = state.template_usage.clone();
+    let template_id = template.id;
+    tokio::spawn(async move {
+        let files = ZipSource {
+            template_id,
+            version_id,
+            fallback_source: &fallback_source,
+        };
+        if let Err(err) = write_template_zip(&pool, files, &root, &values, writer).await {
+            // The client sees a truncated archive; nothing else can be done mid-stream
+            tracing::error!(template_id = %template_id, error = %err, "failed to stream template archive");
+            return;
+        }
+        usage.record(template_id);
+        let _ = sqlx::query("INSERT INTO template_installs (template_id) VALUES ($1)")
+            .bind(template_id)
+            .execute(&pool)
+            .await;
+        let _ = sqlx::query(
+            "UPDATE contract_templates SET install_count = install_count + 1 WHERE id = $1",
+        )
+        .bind(template_id)
+        .execute(&pool)
+        .await;
+    });
+
+    let mut response = Body::from_stream(ReaderStream::new(reader)).into_response();
+    let headers = response.headers_mut();
+    headers.insert(header::CONTENT_TYPE, HeaderValue::from_static("application/zip"));
+    if let Ok(value) = HeaderValue::from_str(&format!("attachment; filename=\"{}\"", filename)) {
+        headers.insert(header::CONTENT_DISPOSITION, value);
+    }
+    *response.status_mut() = StatusCode::OK;
+    Ok(response)
+}
+
+/// Where the instantiated files come from
+struct ZipSource<'a> {
+    template_id: Uuid,
+    version_id: Option<Uuid>,
+    fallback_source: &'a str,
+}
+
+async fn write_template_zip(
+    pool: &PgPool,
+    source: ZipSource<'_>,
+    root: &str,
+    values: &BTreeMap<String, String>,
+    writer: tokio::io::DuplexStream,
+) -> Result<(), String> {
+    let ZipSource {
+        template_id,
+        version_id,
+        fallback_source,
+    } = source;
+    let mut zip = ZipFileWriter::with_tokio(writer);
+    let mut written = 0usize;
+
+    {
+        let query = match version_id {
+            Some(version_id) => sqlx::query_as::<_, TemplateFile>(
+                "SELECT path, content FROM template_version_files WHERE version_id = $1 ORDER BY path",
+            )
+            .bind(version_id),
+            None => sqlx::query_as::<_, TemplateFile>(
+                "SELECT path, content FROM template_files WHERE template_id = $1 ORDER BY path",
+            )
+            .bind(template_id),
+        };
+        let mut files = query.fetch(pool);
+
+        while let Some(file) = files.try_next().await.map_err(|e| e.to_string())? {
+            let Some(path) = archive_path(root, &render_template(&file.path, values)) else {
+                tracing::warn!(template_id = %template_id, path = %file.path, "skipping unsafe template path");
+                continue;
+            };
+            let rendered = render_template(&file.content, values);
+            zip.write_entry_whole(
+                ZipEntryBuilder::new(path.into(), Compression::Deflate),
+                rendered.as_bytes(),
+            )
+            .await
+            .map_err(|e| e.to_string())?;
+            written += 1;
+        }
+    }
+
+    // Single-file templates only have `source_code`
+    if written == 0 {
+        zip.write_entry_whole(
+            ZipEntryBuilder::new(format!("{}/src/lib.rs", root).into(), Compression::Deflate),
+            render_template(fallback_source, values).as_bytes(),
+        )
+        .await
+        .map_err(|e| e.to_string())?;
+    }
+
+    zip.close().await.map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn supplied(value: serde_json::Value) -> serde_json::Map<String, serde_json::Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn parses_both_parameter_shapes() {
+        let params = parse_template_parameters(&serde_json::json!([
+            "token_name",
+            {"name": "decimals", "default": 7},
+            {"name": "admin", "required": true},
+        ]));
+        assert_eq!(params.len(), 3);
+        assert!(params[0].required);
+        assert!(!params[1].required);
+        assert_eq!(params[1].default.as_deref(), Some("7"));
+        assert!(params[2].required);
+    }
+
+    #[test]
+    fn reports_unknown_and_missing_parameters() {
+        let declared = parse_template_parameters(&serde_json::json!([
+            "token_name",
+            "admin",
+            {"name": "decimals", "default": 7},
+        ]));
+
+        let err = resolve_parameters(
+            &declared,
+            &supplied(serde_json::json!({"token_name": "USD", "colour": "red"})),
+        )
+        .unwrap_err();
+        assert_eq!(err.0, vec!["colour".to_string()]);
+        assert_eq!(err.1, vec!["admin".to_string()]);
+
+        let values = resolve_parameters(
+            &declared,
+            &supplied(serde_json::json!({"token_name": "USD", "admin": "GABC"})),
+        )
+        .unwrap();
+        assert_eq!(values["decimals"], "7");
+    }
+
+    #[test]
+    fn renders_placeholders_in_one_pass() {
+        let values: BTreeMap<String, String> = [
+            ("name".to_string(), "{{evil}}".to_string()),
+            ("evil".to_string(), "boom".to_string()),
+        ]
+        .into();
+        assert_eq!(
+            render_template("pub struct {{name}}; // {{ evil }} {{other}} {{", &values),
+            "pub struct {{evil}}; // boom {{other}} {{"
+        );
+    }
+
+    fn version(version: &str, source: &str) -> TemplateVersion {
+        TemplateVersion {
+            id: Uuid::new_v4(),
+            template_id: Uuid::nil(),
+            version: version.to_string(),
+            source_code: source.to_string(),
+            parameters: serde_json::json!(["name"]),
+            created_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn explicit_older_version_renders_its_own_source() {
+        let versions = vec![
+            version("1.0.0", "// v1 {{name}}"),
+            version("1.10.0", "// v1.10 {{name}}"),
+            version("1.2.0", "// v1.2 {{name}}"),
+        ];
+        let values: BTreeMap<String, String> = [("name".to_string(), "Token".to_string())].into();
+
+        let latest = select_version(&versions, None).unwrap();
+        assert_eq!(latest.version, "1.10.0");
+
+        let older = select_version(&versions, Some("1.0.0")).unwrap();
+        assert_eq!(render_template(&older.source_code, &values), "// v1 Token");
+        assert!(select_version(&versions, Some("9.9.9")).is_none());
+    }
+
+    fn template(owner: Option<Uuid>, is_private: bool) -> ContractTemplate {
+        ContractTemplate {
+            id: Uuid::new_v4(),
+            slug: "token".to_string(),
+            name: "Token".to_string(),
+            description: None,
+            category: "token".to_string(),
+            version: "1.0.0".to_string(),
+            source_code: String::new(),
+            parameters: serde_json::json!([]),
+            install_count: 0,
+            instantiations: 0,
+            owner_publisher_id: owner,
+            is_private,
+            forked_from: None,
+            created_at: chrono::Utc::now(),
+            updated_at: chrono::Utc::now(),
+        }
+    }
+
+    #[test]
+    fn private_templates_are_readable_only_by_owner_and_admin() {
+        let owner = Uuid::new_v4();
+        let public = template(Some(owner), false);
+        assert!(public.readable_by(None));
+        assert!(public.readable_by(Some(Caller::Publisher(Uuid::new_v4()))));
+
+        let private = template(Some(owner), true);
+        assert!(private.readable_by(Some(Caller::Publisher(owner))));
+        assert!(private.readable_by(Some(Caller::Admin)));
+        assert!(!private.readable_by(None));
+        assert!(!private.readable_by(Some(Caller::Publisher(Uuid::new_v4()))));
+
+        let err = ensure_readable(&private, None).unwrap_err();
+        assert_eq!(err.status(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn only_owners_may_publish_template_versions() {
+        use crate::state::test_request;
+
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let owner = state.insert_publisher().await;
+        let stranger = state.insert_publisher().await;
+        let public = state.insert_template(Some(owner), false).await;
+        let private = state.insert_template(Some(owner), true).await;
+        let registry = state.insert_template(None, false).await;
+        let (owner_key, stranger_key) = (state.api_key(owner).await, state.api_key(stranger).await);
+        let body = || Some(serde_json::json!({ "version": "2.0.0", "source_code": "// v2" }));
+        let publish = |slug: &str, key: Option<&str>| {
+            let uri = format!("/api/templates/{}/versions", slug);
+            test_request("POST", &uri, key, body())
+        };
+
+        let response = state.send(publish(&public, None)).await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let response = state.send(publish(&public, Some(&stranger_key))).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = state.send(publish(&private, Some(&stranger_key))).await;
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        // Registry templates belong to no publisher
+        let response = state.send(publish(&registry, Some(&owner_key))).await;
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        for slug in [&public, &private] {
+            let response = state.send(publish(slug, Some(&owner_key))).await;
+            assert_eq!(response.status(), StatusCode::CREATED);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn each_instantiation_is_counted_once_in_the_stats() {
+        use crate::state::{json_body, test_request};
+
+        const N: usize = 20;
+        let Some(state) = AppState::for_database_tests().await else {
+            return;
+        };
+        let slug = state.insert_template(None, false).await;
+        let uri = format!("/api/templates/{}/instantiate", slug);
+
+        let instantiations: Vec<_> = (0..N)
+            .map(|_| {
+                let (state, uri) = (state.clone(), uri.clone());
+                tokio::spawn(async move {
+                    let request = test_request("POST", &uri, None, Some(serde_json::json!({})));
+                    let response = state.send(request).await;
+                    assert_eq!(response.status(), StatusCode::OK);
+                    axum::body::to_bytes(response.into_body(), usize::MAX)
+                        .await
+                        .unwrap();
+                })
+            })
+            .collect();
+        for instantiation in instantiations {
+            instantiation.await.unwrap();
+        }
+
+        // Counted once the archive is fully written, just after the body ends
+        let mut flushed = 0;
+        for _ in 0..100 {
+            flushed += state.template_usage.flush(&state.db).await.unwrap();
+            if flushed >= N as u64 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        assert_eq!(flushed, N as u64);
+
+        let uri = format!("/api/templates/{}/stats", slug);
+        let stats = json_body(state.send(test_request("GET", &uri, None, None)).await).await;
+        assert_eq!(stats["total"], N);
+        assert_eq!(stats["buckets"][0]["count"], N);
+    }
+
+    #[test]
+    fn fork_slugs_default_to_a_suffixed_origin() {
+        let slug = fork_slug("token", None);
+        assert!(slug.starts_with("token-") && slug.len() == "token-".len() + 8);
+        assert!(valid_template_slug(&slug));
+        assert_eq!(fork_slug("token", Some(" my-token ")), "my-token");
+        assert!(valid_template_slug(&fork_slug(&"a".repeat(64), None)));
+
+        assert!(!valid_template_slug("My Token"));
+        assert!(!valid_template_slug("-token"));
+        assert!(!valid_template_slug(""));
+    }
+
+    #[test]
+    fn stats_window_defaults_and_bounds() {
+        let params = |bucket: Option<&str>, days: Option<i64>| TemplateStatsParams {
+            bucket: bucket.map(str::to_string),
+            days,
+        };
+        assert_eq!(
+            stats_window(&params(None, None)).unwrap(),
+            ("day", DEFAULT_STATS_DAYS)
+        );
+        assert_eq!(
+            stats_window(&params(Some("month"), Some(365))).unwrap(),
+            ("month", 365)
+        );
+        assert!(stats_window(&params(Some("hour"), None)).is_err());
+        assert!(stats_window(&params(None, Some(0))).is_err());
+        assert!(stats_window(&params(None, Some(366))).is_err());
+    }
+
+    #[test]
+    fn archive_paths_stay_inside_root() {
+        assert_eq!(archive_path("token", "src/lib.rs").as_deref(), Some("token/src/lib.rs"));
+        assert_eq!(archive_path("token", "/Cargo.toml").as_deref(), Some("token/Cargo.toml"));
+        assert_eq!(archive_path("token", "../etc/passwd"), None);
+        assert_eq!(archive_path("token", ""), None);
+    }
+}
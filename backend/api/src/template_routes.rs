@@ -6,4 +6,17 @@ pub fn template_routes() -> Router<AppState> {
         .route("/api/templates", get(template_handlers::list_templates))
         .route("/api/templates/:slug", get(template_handlers::get_template))
         .route("/api/templates/:slug/clone", post(template_handlers::clone_template))
+        .route("/api/templates/:slug/instantiate", post(template_handlers::instantiate_template))
+        .route("/api/templates/:slug/versions", get(template_handlers::list_template_versions))
+        .route("/api/templates/:slug/fork", post(template_handlers::fork_template))
+        .route("/api/templates/:slug/forks", get(template_handlers::list_template_forks))
+        .route("/api/templates/:slug/stats", get(template_handlers::get_template_stats))
+}
+
+/// Template writes; mounted behind `require_api_key`
+pub fn template_owner_routes() -> Router<AppState> {
+    Router::new().route(
+        "/api/templates/:slug/versions",
+        post(template_handlers::create_template_version),
+    )
 }
@@ -0,0 +1,154 @@
+// template_usage.rs
+// Per-template instantiation counters.
+//
+// Works like the download counters: instantiations are counted in memory and
+// flushed in one batch per interval, so rendering a template never waits on
+// a write. Each flush adds its deltas to `contract_templates.instantiations`
+// and records one raw `template_instantiation_events` row per template, which
+// the aggregation task rolls up into `template_instantiation_daily`.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use sqlx::PgPool;
+use tokio::task::JoinHandle;
+use tokio_util::sync::CancellationToken;
+use uuid::Uuid;
+
+/// Instantiations seen since the last flush, keyed by template id
+#[derive(Debug, Default)]
+pub struct InstantiationCounter {
+    pending: Mutex<HashMap<Uuid, u64>>,
+}
+
+impl InstantiationCounter {
+    pub fn record(&self, template_id: Uuid) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        *pending.entry(template_id).or_insert(0) += 1;
+    }
+
+    /// Take every pending count, leaving the counter empty
+    pub fn drain(&self) -> HashMap<Uuid, u64> {
+        std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()))
+    }
+
+    /// Put counts back after a failed flush so no instantiation is lost
+    pub fn restore(&self, counts: HashMap<Uuid, u64>) {
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        for (template_id, n) in counts {
+            *pending.entry(template_id).or_insert(0) += n;
+        }
+    }
+
+    /// Write pending counts to the database. Returns the number of
+    /// instantiations flushed.
+    pub async fn flush(&self, pool: &PgPool) -> Result<u64, sqlx::Error> {
+        let counts = self.drain();
+        if counts.is_empty() {
+            return Ok(0);
+        }
+        let (ids, deltas): (Vec<Uuid>, Vec<i64>) =
+            counts.iter().map(|(id, n)| (*id, *n as i64)).unzip();
+
+        match write_counts(pool, &ids, &deltas).await {
+            Ok(_) => Ok(deltas.iter().sum::<i64>() as u64),
+            Err(err) => {
+                self.restore(counts);
+                Err(err)
+            }
+        }
+    }
+}
+
+async fn write_counts(pool: &PgPool, ids: &[Uuid], deltas: &[i64]) -> Result<(), sqlx::Error> {
+    let mut tx = pool.begin().await?;
+    sqlx::query(
+        "UPDATE contract_templates t
+         SET instantiations = t.instantiations + d.delta
+         FROM UNNEST($1::uuid[], $2::bigint[]) AS d(id, delta)
+         WHERE t.id = d.id",
+    )
+    .bind(ids)
+    .bind(deltas)
+    .execute(&mut *tx)
+    .await?;
+    // Templates deleted since they were counted are skipped by the join
+    sqlx::query(
+        "INSERT INTO template_instantiation_events (template_id, count)
+         SELECT d.id, d.delta
+         FROM UNNEST($1::uuid[], $2::bigint[]) AS d(id, delta)
+         JOIN contract_templates t ON t.id = d.id",
+    )
+    .bind(ids)
+    .bind(deltas)
+    .execute(&mut *tx)
+    .await?;
+    tx.commit().await
+}
+
+/// Spawn the batched writer. On shutdown it flushes once more so counts
+/// recorded during the grace period still land.
+pub fn spawn_flush_task(
+    pool: PgPool,
+    counter: Arc<InstantiationCounter>,
+    interval: Duration,
+    shutdown: CancellationToken,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        loop {
+            let stopping = tokio::select! {
+                _ = ticker.tick() => false,
+                _ = shutdown.cancelled() => true,
+            };
+            if let Err(err) = counter.flush(&pool).await {
+                tracing::warn!(error = ?err, "template usage: flush failed; will retry");
+            }
+            if stopping {
+                tracing::info!("template usage: stopped");
+                return;
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn n_instantiations_count_as_n() {
+        const N: u64 = 500;
+        let counter = Arc::new(InstantiationCounter::default());
+        let template = Uuid::new_v4();
+        let other = Uuid::new_v4();
+
+        let instantiations: Vec<_> = (0..N)
+            .map(|_| {
+                let counter = counter.clone();
+                tokio::spawn(async move { counter.record(template) })
+            })
+            .collect();
+        for instantiation in instantiations {
+            instantiation.await.unwrap();
+        }
+        counter.record(other);
+
+        let counts = counter.drain();
+        assert_eq!(counts.get(&template), Some(&N));
+        assert_eq!(counts.get(&other), Some(&1));
+        assert!(counter.drain().is_empty());
+    }
+
+    #[test]
+    fn restore_merges_with_new_instantiations() {
+        let counter = InstantiationCounter::default();
+        let template = Uuid::new_v4();
+        counter.record(template);
+        let taken = counter.drain();
+        counter.record(template);
+        counter.restore(taken);
+        assert_eq!(counter.drain().get(&template), Some(&2));
+    }
+}
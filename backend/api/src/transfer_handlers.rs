@@ -0,0 +1,491 @@
+//! Two-step contract ownership transfers (`contract_transfers`).
+//!
+//! An owner of a contract proposes a new owner, either a publisher or an
+//! organization; nothing changes until the recipient accepts. A contract has
+//! at most one pending transfer, and proposing another replaces it. Pending
+//! transfers lapse after `CONTRACT_TRANSFER_TTL_HOURS`. Accepted transfers
+//! are recorded in the contract's history as `publisher_changed`.
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Extension, Json,
+};
+use chrono::{DateTime, Duration, Utc};
+use once_cell::sync::Lazy;
+use shared::{
+    AuditActionType, Contract, ContractTransfer, CreateTransferRequest, OrganizationRole,
+    TransferStatus,
+};
+use uuid::Uuid;
+
+use crate::{
+    admin_audit,
+    auth::{self, Caller, ContractAccess},
+    contract_history_handlers::log_contract_change,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::db_internal_error,
+    organization_handlers::authorize_member,
+    pagination::{PageParams, Paginated, PaginatedContractTransfers},
+    state::AppState,
+};
+
+/// Env var setting how long a transfer stays open, in hours
+pub const TRANSFER_TTL_ENV: &str = "CONTRACT_TRANSFER_TTL_HOURS";
+pub const DEFAULT_TRANSFER_TTL_HOURS: i64 = 7 * 24;
+
+static TRANSFER_TTL_HOURS: Lazy<i64> =
+    Lazy::new(|| parse_transfer_ttl_hours(std::env::var(TRANSFER_TTL_ENV).ok().as_deref()));
+
+/// Configured transfer window; unset, non-positive or unparsable values use the default
+pub fn transfer_ttl() -> Duration {
+    Duration::hours(*TRANSFER_TTL_HOURS)
+}
+
+fn parse_transfer_ttl_hours(raw: Option<&str>) -> i64 {
+    raw.and_then(|value| value.trim().parse().ok())
+        .filter(|hours| *hours > 0)
+        .unwrap_or(DEFAULT_TRANSFER_TTL_HOURS)
+}
+
+/// Who a transfer hands the contract to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TransferTarget {
+    Publisher(Uuid),
+    Organization(Uuid),
+}
+
+/// The contract's current owner: its publisher, and its organization if any
+#[derive(Debug, Clone, Copy, sqlx::FromRow)]
+struct CurrentOwner {
+    publisher_id: Uuid,
+    organization_id: Option<Uuid>,
+}
+
+/// Exactly one target, and not the contract's current owner
+pub fn transfer_target(
+    req: &CreateTransferRequest,
+    publisher_id: Uuid,
+    organization_id: Option<Uuid>,
+) -> ApiResult<TransferTarget> {
+    let target = match (req.to_publisher_id, req.to_organization_id) {
+        (Some(id), None) => TransferTarget::Publisher(id),
+        (None, Some(id)) => TransferTarget::Organization(id),
+        _ => {
+            return Err(ApiError::bad_request(
+                ErrorCode::InvalidTransferTarget,
+                "Set exactly one of to_publisher_id and to_organization_id",
+            ))
+        }
+    };
+    let already_owner = match target {
+        TransferTarget::Publisher(id) => organization_id.is_none() && id == publisher_id,
+        TransferTarget::Organization(id) => organization_id == Some(id),
+    };
+    if already_owner {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidTransferTarget,
+            "The contract already belongs to this owner",
+        ));
+    }
+    Ok(target)
+}
+
+pub fn is_expired(transfer: &ContractTransfer, now: DateTime<Utc>) -> bool {
+    transfer.expires_at <= now
+}
+
+fn no_pending_transfer(contract_id: Uuid) -> ApiError {
+    ApiError::not_found(
+        ErrorCode::TransferNotFound,
+        format!("No pending transfer for contract {}", contract_id),
+    )
+}
+
+/// Only the recipient may accept: the target publisher, or an owner of the
+/// target organization (admins always pass)
+async fn authorize_recipient(
+    state: &AppState,
+    caller: &Caller,
+    transfer: &ContractTransfer,
+) -> ApiResult<()> {
+    if let Some(publisher_id) = transfer.to_publisher_id {
+        return caller.authorize_publisher(publisher_id);
+    }
+    let (Some(organization_id), Some(publisher_id)) =
+        (transfer.to_organization_id, caller.publisher_id())
+    else {
+        return Ok(());
+    };
+    let role = authorize_member(&state.db, organization_id, publisher_id).await?;
+    auth::require_role(
+        Some(role),
+        OrganizationRole::Owner,
+        &format!("organization {}", organization_id),
+    )
+}
+
+/// Propose a new owner. Replaces any transfer already pending for the contract.
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/transfer",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    request_body = CreateTransferRequest,
+    responses(
+        (status = 201, description = "The pending transfer", body = ContractTransfer),
+        (status = 400, description = "Missing, ambiguous or unchanged target"),
+        (status = 403, description = "Caller is not an owner of the contract"),
+        (status = 404, description = "Contract, publisher or organization not found"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn initiate_transfer(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Extension(access): Extension<ContractAccess>,
+    Json(req): Json<CreateTransferRequest>,
+) -> ApiResult<(StatusCode, Json<ContractTransfer>)> {
+    access.require(OrganizationRole::Owner)?;
+    let id = access.contract_id;
+
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin initiate transfer", err))?;
+
+    let owner: CurrentOwner = sqlx::query_as(
+        "SELECT publisher_id, organization_id FROM contracts WHERE id = $1 FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("lock contract for transfer", err))?;
+    let target = transfer_target(&req, owner.publisher_id, owner.organization_id)?;
+
+    sqlx::query(
+        "UPDATE contract_transfers SET status = 'cancelled', resolved_at = NOW()
+         WHERE contract_id = $1 AND status = 'pending'",
+    )
+    .bind(id)
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("cancel pending transfer", err))?;
+
+    let (to_publisher_id, to_organization_id) = match target {
+        TransferTarget::Publisher(id) => (Some(id), None),
+        TransferTarget::Organization(id) => (None, Some(id)),
+    };
+    let transfer: ContractTransfer = sqlx::query_as(
+        "INSERT INTO contract_transfers
+             (contract_id, from_publisher_id, from_organization_id,
+              to_publisher_id, to_organization_id, initiated_by, expires_at)
+         VALUES ($1, $2, $3, $4, $5, $6, $7)
+         RETURNING *",
+    )
+    .bind(id)
+    .bind(owner.publisher_id)
+    .bind(owner.organization_id)
+    .bind(to_publisher_id)
+    .bind(to_organization_id)
+    .bind(caller.publisher_id())
+    .bind(Utc::now() + transfer_ttl())
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(db) if db.is_foreign_key_violation() => match target {
+            TransferTarget::Publisher(id) => ApiError::not_found(
+                ErrorCode::PublisherNotFound,
+                format!("No publisher found with ID: {}", id),
+            ),
+            TransferTarget::Organization(id) => ApiError::not_found(
+                ErrorCode::OrganizationNotFound,
+                format!("No organization found with ID: {}", id),
+            ),
+        },
+        err => db_internal_error("create transfer", err),
+    })?;
+    admin_audit::record(
+        &mut *tx,
+        &caller,
+        admin_audit::TRANSFER_INITIATED,
+        "contract",
+        &id.to_string(),
+        Some(serde_json::json!({
+            "transfer_id": transfer.id,
+            "to_publisher_id": to_publisher_id,
+            "to_organization_id": to_organization_id,
+        })),
+    )
+    .await
+    .map_err(|err| db_internal_error("record transfer in admin audit log", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit initiate transfer", err))?;
+    Ok((StatusCode::CREATED, Json(transfer)))
+}
+
+/// Accept the contract's pending transfer and take ownership
+#[utoipa::path(
+    post,
+    path = "/api/contracts/{id}/transfer/accept",
+    tag = "contracts",
+    params(
+        ("id" = Uuid, Path, description = "Contract UUID"),
+    ),
+    responses(
+        (status = 200, description = "The contract under its new owner", body = Contract),
+        (status = 403, description = "Caller is not the recipient of the transfer"),
+        (status = 404, description = "Contract not found, or no transfer is pending"),
+        (status = 410, description = "The transfer has expired"),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn accept_transfer(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<Contract>> {
+    let mut tx = state
+        .db
+        .begin()
+        .await
+        .map_err(|err| db_internal_error("begin accept transfer", err))?;
+
+    let transfer: ContractTransfer = sqlx::query_as(
+        "SELECT * FROM contract_transfers
+         WHERE contract_id = $1 AND status = 'pending' FOR UPDATE",
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("get pending transfer", err))?
+    .ok_or_else(|| no_pending_transfer(id))?;
+
+    authorize_recipient(&state, &caller, &transfer).await?;
+
+    if is_expired(&transfer, Utc::now()) {
+        sqlx::query(
+            "UPDATE contract_transfers SET status = 'expired', resolved_at = NOW() WHERE id = $1",
+        )
+        .bind(transfer.id)
+        .execute(&mut *tx)
+        .await
+        .map_err(|err| db_internal_error("expire transfer", err))?;
+        tx.commit()
+            .await
+            .map_err(|err| db_internal_error("commit expire transfer", err))?;
+        return Err(ApiError::new(
+            StatusCode::GONE,
+            ErrorCode::TransferExpired,
+            format!("The transfer of contract {} expired at {}", id, transfer.expires_at),
+        ));
+    }
+
+    let old_value: serde_json::Value =
+        sqlx::query_scalar("SELECT row_to_json(c) FROM contracts c WHERE id = $1")
+            .bind(id)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(|err| db_internal_error("snapshot contract", err))?;
+
+    let contract: Contract = match (transfer.to_publisher_id, transfer.to_organization_id) {
+        (Some(publisher_id), _) => sqlx::query_as(
+            "UPDATE contracts SET publisher_id = $2, organization_id = NULL, updated_at = NOW()
+             WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .bind(publisher_id),
+        (None, organization_id) => sqlx::query_as(
+            "UPDATE contracts SET organization_id = $2, updated_at = NOW()
+             WHERE id = $1 RETURNING *",
+        )
+        .bind(id)
+        .bind(organization_id),
+    }
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("transfer contract", err))?;
+
+    sqlx::query(
+        "UPDATE contract_transfers
+         SET status = 'accepted', accepted_by = $2, resolved_at = NOW()
+         WHERE id = $1",
+    )
+    .bind(transfer.id)
+    .bind(caller.publisher_id())
+    .execute(&mut *tx)
+    .await
+    .map_err(|err| db_internal_error("accept transfer", err))?;
+    admin_audit::record(
+        &mut *tx,
+        &caller,
+        admin_audit::TRANSFER_ACCEPTED,
+        "contract",
+        &id.to_string(),
+        Some(serde_json::json!({
+            "transfer_id": transfer.id,
+            "from_publisher_id": transfer.from_publisher_id,
+            "from_organization_id": transfer.from_organization_id,
+        })),
+    )
+    .await
+    .map_err(|err| db_internal_error("record transfer in admin audit log", err))?;
+
+    tx.commit()
+        .await
+        .map_err(|err| db_internal_error("commit accept transfer", err))?;
+
+    let new_value = serde_json::to_value(&contract).ok();
+    if let Err(err) = log_contract_change(
+        &state.db,
+        id,
+        AuditActionType::PublisherChanged,
+        Some(old_value),
+        new_value,
+        &caller.audit_name(),
+    )
+    .await
+    {
+        tracing::error!(contract_id = %id, error = ?err, "failed to record ownership transfer");
+    }
+
+    Ok(Json(contract))
+}
+
+/// Open transfers `$1` is party to (every one when `$1` is null), as the
+/// tail of a query selecting from `contract_transfers t`
+const VISIBLE_TRANSFERS: &str = "
+    FROM contract_transfers t
+    JOIN contracts c ON c.id = t.contract_id
+    WHERE t.status = $2 AND t.expires_at > NOW()
+      AND ($1::uuid IS NULL
+           OR t.to_publisher_id = $1
+           OR t.to_organization_id IN (
+               SELECT organization_id FROM organization_members
+               WHERE publisher_id = $1 AND role = 'owner')
+           OR (c.organization_id IS NULL AND c.publisher_id = $1)
+           OR c.organization_id IN (
+               SELECT organization_id FROM organization_members
+               WHERE publisher_id = $1 AND role = 'owner'))";
+
+/// Pending transfers the caller is party to, sent or received. Admin keys
+/// see every pending transfer.
+#[utoipa::path(
+    get,
+    path = "/api/transfers",
+    tag = "contracts",
+    params(PageParams),
+    responses(
+        (status = 200, description = "Open transfers, newest first", body = PaginatedContractTransfers),
+    ),
+    security(("api_key" = [])),
+)]
+pub async fn list_transfers(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<ContractTransfer>>> {
+    let transfers: Vec<ContractTransfer> = sqlx::query_as(&format!(
+        "SELECT t.* {} ORDER BY t.created_at DESC, t.id DESC LIMIT $3 OFFSET $4",
+        VISIBLE_TRANSFERS
+    ))
+    .bind(caller.publisher_id())
+    .bind(TransferStatus::Pending)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list transfers", err))?;
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) {}", VISIBLE_TRANSFERS))
+        .bind(caller.publisher_id())
+        .bind(TransferStatus::Pending)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("count transfers", err))?;
+
+    Ok(Json(Paginated::new(
+        transfers,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(to_publisher_id: Option<Uuid>, to_organization_id: Option<Uuid>) -> CreateTransferRequest {
+        CreateTransferRequest {
+            to_publisher_id,
+            to_organization_id,
+        }
+    }
+
+    #[test]
+    fn target_must_be_exactly_one_owner() {
+        let owner = Uuid::new_v4();
+        for req in [request(None, None), request(Some(Uuid::new_v4()), Some(Uuid::new_v4()))] {
+            let err = transfer_target(&req, owner, None).unwrap_err();
+            assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+            assert_eq!(err.code().as_str(), "transfer.invalid_target");
+        }
+
+        let recipient = Uuid::new_v4();
+        assert_eq!(
+            transfer_target(&request(Some(recipient), None), owner, None).unwrap(),
+            TransferTarget::Publisher(recipient)
+        );
+    }
+
+    #[test]
+    fn target_cannot_be_the_current_owner() {
+        let publisher = Uuid::new_v4();
+        let organization = Uuid::new_v4();
+        assert!(transfer_target(&request(Some(publisher), None), publisher, None).is_err());
+        assert!(
+            transfer_target(&request(None, Some(organization)), publisher, Some(organization))
+                .is_err()
+        );
+        // Moving an organization's contract to its publisher personally is a change
+        assert_eq!(
+            transfer_target(&request(Some(publisher), None), publisher, Some(organization))
+                .unwrap(),
+            TransferTarget::Publisher(publisher)
+        );
+    }
+
+    #[test]
+    fn transfers_lapse_at_their_expiry() {
+        let now = Utc::now();
+        let transfer = ContractTransfer {
+            id: Uuid::new_v4(),
+            contract_id: Uuid::new_v4(),
+            from_publisher_id: Uuid::new_v4(),
+            from_organization_id: None,
+            to_publisher_id: Some(Uuid::new_v4()),
+            to_organization_id: None,
+            status: TransferStatus::Pending,
+            initiated_by: None,
+            accepted_by: None,
+            created_at: now - transfer_ttl(),
+            expires_at: now,
+            resolved_at: None,
+        };
+        assert!(is_expired(&transfer, now));
+        assert!(!is_expired(&transfer, now - Duration::seconds(1)));
+    }
+
+    #[test]
+    fn ttl_falls_back_to_the_default() {
+        assert_eq!(parse_transfer_ttl_hours(None), DEFAULT_TRANSFER_TTL_HOURS);
+        assert_eq!(parse_transfer_ttl_hours(Some("0")), DEFAULT_TRANSFER_TTL_HOURS);
+        assert_eq!(parse_transfer_ttl_hours(Some("soon")), DEFAULT_TRANSFER_TTL_HOURS);
+        assert_eq!(parse_transfer_ttl_hours(Some(" 48 ")), 48);
+    }
+}
@@ -0,0 +1,188 @@
+// trending.rs
+// "Trending this week": contracts ranked by recent activity.
+//
+// Downloads and publishes come from the daily rollups in
+// `analytics_daily_aggregates`, stars from `contract_stars`. Each day's
+// activity is weighted by exp(-age / (window / 2)), so yesterday counts more
+// than six days ago and the list turns over quickly. This is deliberately
+// separate from the composite score in `scoring`, which moves slowly.
+
+use serde::Serialize;
+use sqlx::PgPool;
+use utoipa::ToSchema;
+
+use shared::Contract;
+
+/// Weight of one download, star and publish in the trending score
+pub const DOWNLOAD_WEIGHT: f64 = 1.0;
+pub const STAR_WEIGHT: f64 = 5.0;
+pub const PUBLISH_WEIGHT: f64 = 10.0;
+
+pub const MAX_TRENDING_LIMIT: i64 = 100;
+pub const DEFAULT_TRENDING_LIMIT: i64 = 20;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrendWindow {
+    Day,
+    Week,
+    Month,
+}
+
+impl TrendWindow {
+    pub const ALL: [TrendWindow; 3] = [TrendWindow::Day, TrendWindow::Week, TrendWindow::Month];
+
+    pub fn parse(raw: &str) -> Option<Self> {
+        match raw.trim() {
+            "1d" => Some(TrendWindow::Day),
+            "7d" => Some(TrendWindow::Week),
+            "30d" => Some(TrendWindow::Month),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TrendWindow::Day => "1d",
+            TrendWindow::Week => "7d",
+            TrendWindow::Month => "30d",
+        }
+    }
+
+    pub fn days(&self) -> i32 {
+        match self {
+            TrendWindow::Day => 1,
+            TrendWindow::Week => 7,
+            TrendWindow::Month => 30,
+        }
+    }
+
+    /// Decay constant in days: activity half a window old counts ~37%
+    pub fn decay_days(&self) -> f64 {
+        self.days() as f64 / 2.0
+    }
+}
+
+/// Decayed weight of activity `age_days` old
+pub fn decay(age_days: f64, window: TrendWindow) -> f64 {
+    (-age_days.max(0.0) / window.decay_days()).exp()
+}
+
+/// Trending score for one day's activity
+pub fn activity_score(downloads: f64, stars: f64, publishes: f64, age_days: f64, window: TrendWindow) -> f64 {
+    decay(age_days, window)
+        * (downloads * DOWNLOAD_WEIGHT + stars * STAR_WEIGHT + publishes * PUBLISH_WEIGHT)
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow, ToSchema)]
+pub struct TrendingContract {
+    #[serde(flatten)]
+    #[sqlx(flatten)]
+    pub contract: Contract,
+    pub trending_score: f64,
+    pub recent_downloads: i64,
+    pub recent_stars: i64,
+    pub recent_publishes: i64,
+    /// All-time downloads; breaks ties in `trending_score`
+    pub download_count: i64,
+}
+
+#[derive(Debug, Serialize, ToSchema)]
+pub struct TrendingResponse {
+    pub window: String,
+    pub items: Vec<TrendingContract>,
+}
+
+/// Top `limit` contracts by decayed recent activity. Contracts with no
+/// activity in the window are left out.
+pub async fn trending(
+    pool: &PgPool,
+    window: TrendWindow,
+    limit: i64,
+) -> Result<Vec<TrendingContract>, sqlx::Error> {
+    // $1 window days, $2 decay days, $3..$5 weights; the SQL decay mirrors
+    // `activity_score`
+    sqlx::query_as(
+        r#"
+        WITH rollups AS (
+            SELECT
+                a.contract_id,
+                SUM(EXP(-(CURRENT_DATE - a.date)::float8 / $2)
+                    * (a.download_count * $3 + (a.publish_count + a.version_count) * $5)) AS score,
+                SUM(a.download_count)::bigint AS downloads,
+                SUM(a.publish_count + a.version_count)::bigint AS publishes
+            FROM analytics_daily_aggregates a
+            WHERE a.date > CURRENT_DATE - $1
+            GROUP BY a.contract_id
+        ),
+        stars AS (
+            SELECT
+                s.contract_id,
+                SUM(EXP(-EXTRACT(EPOCH FROM (NOW() - s.created_at)) / 86400.0 / $2) * $4) AS score,
+                COUNT(*) AS stars
+            FROM contract_stars s
+            WHERE s.created_at > NOW() - make_interval(days => $1)
+            GROUP BY s.contract_id
+        ),
+        totals AS (
+            SELECT contract_id, SUM(download_count)::bigint AS total
+            FROM contract_versions GROUP BY contract_id
+        )
+        SELECT
+            c.*,
+            (COALESCE(r.score, 0) + COALESCE(st.score, 0))::float8 AS trending_score,
+            COALESCE(r.downloads, 0) AS recent_downloads,
+            COALESCE(st.stars, 0) AS recent_stars,
+            COALESCE(r.publishes, 0) AS recent_publishes,
+            COALESCE(t.total, 0) AS download_count
+        FROM contracts c
+        LEFT JOIN rollups r ON r.contract_id = c.id
+        LEFT JOIN stars st ON st.contract_id = c.id
+        LEFT JOIN totals t ON t.contract_id = c.id
+        WHERE (r.contract_id IS NOT NULL OR st.contract_id IS NOT NULL)
+            AND c.visibility = 'public'
+        ORDER BY trending_score DESC, download_count DESC, c.id
+        LIMIT $6
+        "#,
+    )
+    .bind(window.days())
+    .bind(window.decay_days())
+    .bind(DOWNLOAD_WEIGHT)
+    .bind(STAR_WEIGHT)
+    .bind(PUBLISH_WEIGHT)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_supported_windows_only() {
+        for window in TrendWindow::ALL {
+            assert_eq!(TrendWindow::parse(window.as_str()), Some(window));
+        }
+        assert_eq!(TrendWindow::parse("14d"), None);
+        assert_eq!(TrendWindow::parse("week"), None);
+    }
+
+    #[test]
+    fn recent_activity_outweighs_older_activity() {
+        let window = TrendWindow::Week;
+        let today = activity_score(10.0, 0.0, 0.0, 0.0, window);
+        let last_week = activity_score(10.0, 0.0, 0.0, 6.0, window);
+        assert_eq!(today, 10.0);
+        assert!(last_week < today / 5.0);
+
+        // A shorter window decays faster
+        assert!(decay(1.0, TrendWindow::Day) < decay(1.0, TrendWindow::Month));
+    }
+
+    #[test]
+    fn stars_and_publishes_carry_more_weight_than_downloads() {
+        let window = TrendWindow::Week;
+        assert!(activity_score(0.0, 1.0, 0.0, 0.0, window) > activity_score(1.0, 0.0, 0.0, 0.0, window));
+        assert!(activity_score(0.0, 0.0, 1.0, 0.0, window) > activity_score(0.0, 1.0, 0.0, 0.0, window));
+    }
+}
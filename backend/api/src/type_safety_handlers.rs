@@ -6,11 +6,14 @@
 use axum::{
     extract::{Path, Query, State},
     http::StatusCode,
-    Json,
+    Extension, Json,
 };
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::auth::Caller;
+use crate::handlers;
+use crate::share_tokens::PresentedShareToken;
 use crate::state::AppState;
 use crate::type_safety::{
     bindings::{generate_bindings, BindingLanguage},
@@ -150,11 +153,13 @@ impl ApiError {
 /// Validate a contract function call for type safety
 pub async fn validate_call(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<String>,
     Json(body): Json<ValidateCallBody>,
 ) -> Result<Json<ValidateCallResponse>, (StatusCode, Json<ApiError>)> {
     // 1. Fetch contract ABI from database
-    let abi_json = fetch_contract_abi(&state, &contract_id)
+    let abi_json = fetch_contract_abi(&state, caller, &share_token, &contract_id)
         .await
         .map_err(|e| ApiError::not_found(e))?;
 
@@ -181,10 +186,12 @@ pub async fn validate_call(
 /// List all functions available on a contract
 pub async fn list_contract_functions(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<String>,
 ) -> Result<Json<ContractFunctionsResponse>, (StatusCode, Json<ApiError>)> {
     // Fetch and parse ABI
-    let abi_json = fetch_contract_abi(&state, &contract_id)
+    let abi_json = fetch_contract_abi(&state, caller, &share_token, &contract_id)
         .await
         .map_err(|e| ApiError::not_found(e))?;
 
@@ -206,9 +213,11 @@ pub async fn list_contract_functions(
 /// Get information about a specific function
 pub async fn get_function_info(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path((contract_id, method_name)): Path<(String, String)>,
 ) -> Result<Json<FunctionInfoDto>, (StatusCode, Json<ApiError>)> {
-    let abi_json = fetch_contract_abi(&state, &contract_id)
+    let abi_json = fetch_contract_abi(&state, caller, &share_token, &contract_id)
         .await
         .map_err(|e| ApiError::not_found(e))?;
 
@@ -228,6 +237,8 @@ pub async fn get_function_info(
 /// Generate type-safe bindings for a contract
 pub async fn generate_contract_bindings(
     State(state): State<AppState>,
+    caller: Option<Extension<Caller>>,
+    share_token: PresentedShareToken,
     Path(contract_id): Path<String>,
     Query(query): Query<GenerateBindingsQuery>,
 ) -> Result<(StatusCode, String), (StatusCode, Json<ApiError>)> {
@@ -238,7 +249,7 @@ pub async fn generate_contract_bindings(
         .map_err(|e: String| ApiError::bad_request(e))?;
 
     // Fetch and parse ABI
-    let abi_json = fetch_contract_abi(&state, &contract_id)
+    let abi_json = fetch_contract_abi(&state, caller, &share_token, &contract_id)
         .await
         .map_err(|e| ApiError::not_found(e))?;
 
@@ -258,24 +269,43 @@ pub async fn generate_contract_bindings(
 }
 
 /// Helper: Fetch contract ABI from database
-async fn fetch_contract_abi(state: &AppState, contract_id: &str) -> Result<String, String> {
-    // Try to parse as UUID first, then fall back to contract_id string lookup
-    let query = if let Ok(uuid) = Uuid::parse_str(contract_id) {
-        sqlx::query_scalar::<_, Option<String>>(
-            "SELECT abi FROM contracts WHERE id = $1"
-        )
-        .bind(uuid)
-        .fetch_optional(&state.db)
-        .await
-    } else {
-        sqlx::query_scalar::<_, Option<String>>(
-            "SELECT abi FROM contracts WHERE contract_id = $1"
-        )
-        .bind(contract_id)
-        .fetch_optional(&state.db)
-        .await
+async fn fetch_contract_abi(
+    state: &AppState,
+    caller: Option<Extension<Caller>>,
+    share_token: &PresentedShareToken,
+    contract_id: &str,
+) -> Result<String, String> {
+    // Accept the registry UUID or the on-chain contract ID; a private
+    // contract the caller may not see is reported as missing
+    let caller = caller.map(|Extension(caller)| caller);
+    let contract = match Uuid::parse_str(contract_id) {
+        Ok(uuid) => {
+            handlers::fetch_visible_contract(&state.db, caller.as_ref(), share_token.as_deref(), uuid)
+                .await
+        }
+        Err(_) => {
+            handlers::fetch_visible_contract_by_address(
+                &state.db,
+                caller.as_ref(),
+                share_token.as_deref(),
+                contract_id,
+            )
+            .await
+        }
+    };
+    let contract = match contract {
+        Ok(contract) => contract,
+        Err(err) if err.status() == StatusCode::NOT_FOUND => {
+            return Err(format!("Contract '{}' not found", contract_id))
+        }
+        Err(err) => return Err(format!("Database error: {:?}", err)),
     };
 
+    let query = sqlx::query_scalar::<_, Option<String>>("SELECT abi FROM contracts WHERE id = $1")
+        .bind(contract.id)
+        .fetch_optional(&state.db)
+        .await;
+
     match query {
         Ok(Some(Some(abi))) => Ok(abi),
         Ok(Some(None)) => Err(format!("Contract '{}' has no ABI", contract_id)),
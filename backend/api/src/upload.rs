@@ -0,0 +1,281 @@
+//! Publish request bodies, negotiated by `Content-Type`.
+//!
+//! `POST /api/contracts` accepts JSON with the artifact base64-encoded in
+//! `wasm`, or `multipart/form-data` with a `metadata` JSON part and the raw
+//! artifact as a `wasm` file part, which avoids the base64 overhead. Both
+//! arrive in the handler as a [`PublishUpload`].
+
+use axum::{
+    async_trait,
+    extract::{multipart::Field, FromRequest, Multipart, Request},
+    http::{header, HeaderMap},
+    response::{IntoResponse, Response},
+};
+use shared::PublishRequest;
+
+use crate::{
+    artifacts::{artifact_too_large, check_artifact_size, max_artifact_bytes, METADATA_ALLOWANCE_BYTES},
+    error::{ApiError, ErrorCode},
+    validation::{requests::validate_publish_request, Validatable, ValidatedJson, ValidationError},
+};
+
+/// Multipart part carrying the `PublishRequest` JSON
+pub const METADATA_PART: &str = "metadata";
+/// Multipart part carrying the raw WASM bytes
+pub const WASM_PART: &str = "wasm";
+
+/// A sanitized, validated publish request and its decoded artifact
+#[derive(Debug)]
+pub struct PublishUpload {
+    pub request: PublishRequest,
+    pub wasm: Option<Vec<u8>>,
+}
+
+#[async_trait]
+impl<S> FromRequest<S> for PublishUpload
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        if is_multipart(req.headers()) {
+            let multipart = Multipart::from_request(req, state).await.map_err(|rejection| {
+                ApiError::bad_request(ErrorCode::InvalidRequest, rejection.body_text()).into_response()
+            })?;
+            return from_multipart(multipart, max_artifact_bytes()).await;
+        }
+
+        let ValidatedJson(request) = ValidatedJson::<PublishRequest>::from_request(req, state)
+            .await
+            .map_err(IntoResponse::into_response)?;
+        let wasm = match request.wasm.as_deref() {
+            Some(encoded) => {
+                use base64::Engine;
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(encoded)
+                    .map_err(|_| {
+                        ApiError::bad_request(ErrorCode::InvalidArtifact, "wasm must be base64")
+                            .into_response()
+                    })?;
+                // The body limit is approximate; this is the exact check
+                check_artifact_size(&bytes, max_artifact_bytes())
+                    .map_err(IntoResponse::into_response)?;
+                Some(bytes)
+            }
+            None => None,
+        };
+        Ok(PublishUpload { request, wasm })
+    }
+}
+
+fn is_multipart(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.trim_start().to_ascii_lowercase().starts_with("multipart/form-data"))
+        .unwrap_or(false)
+}
+
+async fn from_multipart(mut multipart: Multipart, limit: usize) -> Result<PublishUpload, Response> {
+    let mut request: Option<PublishRequest> = None;
+    let mut wasm: Option<Vec<u8>> = None;
+
+    while let Some(mut field) = multipart.next_field().await.map_err(multipart_error)? {
+        let name = field.name().unwrap_or_default().to_string();
+        match name.as_str() {
+            METADATA_PART if request.is_none() => {
+                let bytes = read_part(&mut field, METADATA_ALLOWANCE_BYTES).await?.map_err(|_| {
+                    part_error(
+                        METADATA_PART,
+                        format!("must be at most {} KiB", METADATA_ALLOWANCE_BYTES / 1024),
+                    )
+                })?;
+                let parsed = serde_json::from_slice(&bytes).map_err(|err| {
+                    part_error(METADATA_PART, format!("Invalid JSON data: {}", err))
+                })?;
+                request = Some(parsed);
+            }
+            WASM_PART if wasm.is_none() => {
+                let bytes = read_part(&mut field, limit)
+                    .await?
+                    .map_err(|actual| artifact_too_large(limit, actual).into_response())?;
+                wasm = Some(bytes);
+            }
+            METADATA_PART | WASM_PART => {
+                return Err(part_error(&name, "part was sent more than once".to_string()));
+            }
+            _ => {
+                return Err(part_error(
+                    &name,
+                    format!("unexpected part; expected '{}' and '{}'", METADATA_PART, WASM_PART),
+                ));
+            }
+        }
+    }
+
+    let Some(mut request) = request else {
+        return Err(part_error(METADATA_PART, "part is required".to_string()));
+    };
+    if request.wasm.is_some() {
+        return Err(part_error(
+            WASM_PART,
+            "send the artifact as the wasm file part, not base64 in metadata".to_string(),
+        ));
+    }
+
+    request.sanitize();
+    validate_publish_request(&request, wasm.is_some())
+        .map_err(|errors| ValidationError::new(errors).into_response())?;
+
+    Ok(PublishUpload { request, wasm })
+}
+
+/// Read a part chunk by chunk; `Ok(Err(actual))` once it grows past `limit`,
+/// without reading the rest of it.
+async fn read_part(field: &mut Field<'_>, limit: usize) -> Result<Result<Vec<u8>, usize>, Response> {
+    let mut buffer = Vec::new();
+    while let Some(chunk) = field.chunk().await.map_err(multipart_error)? {
+        if buffer.len() + chunk.len() > limit {
+            return Ok(Err(buffer.len() + chunk.len()));
+        }
+        buffer.extend_from_slice(&chunk);
+    }
+    Ok(Ok(buffer))
+}
+
+fn multipart_error(err: axum::extract::multipart::MultipartError) -> Response {
+    ApiError::bad_request(ErrorCode::InvalidRequest, format!("Invalid multipart body: {}", err.body_text()))
+        .into_response()
+}
+
+fn part_error(part: &str, message: String) -> Response {
+    ValidationError::single(part, message).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode};
+
+    const BOUNDARY: &str = "registry-test-boundary";
+
+    fn metadata() -> serde_json::Value {
+        serde_json::json!({
+            "contract_id": "CDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC",
+            "name": "Token",
+            "network": "testnet",
+            "tags": [],
+            "publisher_address": "GDLZFC3SYJYDZT7K67VZ75HPJVIEUVNIXF47ZG2FB2RMQQVU2HHGCYSC",
+            "version": "1.0.0",
+        })
+    }
+
+    fn multipart_body(parts: &[(&str, Option<&str>, &[u8])]) -> Vec<u8> {
+        let mut body = Vec::new();
+        for (name, filename, bytes) in parts {
+            body.extend_from_slice(format!("--{}\r\n", BOUNDARY).as_bytes());
+            match filename {
+                Some(filename) => body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"; filename=\"{}\"\r\n\
+                         Content-Type: application/wasm\r\n\r\n",
+                        name, filename
+                    )
+                    .as_bytes(),
+                ),
+                None => body.extend_from_slice(
+                    format!(
+                        "Content-Disposition: form-data; name=\"{}\"\r\n\
+                         Content-Type: application/json\r\n\r\n",
+                        name
+                    )
+                    .as_bytes(),
+                ),
+            }
+            body.extend_from_slice(bytes);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(format!("--{}--\r\n", BOUNDARY).as_bytes());
+        body
+    }
+
+    fn multipart_request(body: Vec<u8>) -> Request {
+        axum::http::Request::post("/api/contracts")
+            .header(
+                header::CONTENT_TYPE,
+                format!("multipart/form-data; boundary={}", BOUNDARY),
+            )
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    async fn multipart(body: Vec<u8>) -> Multipart {
+        Multipart::from_request(multipart_request(body), &()).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn multipart_upload_keeps_the_artifact_bytes() {
+        let wasm: Vec<u8> = b"\0asm\x01\0\0\0"
+            .iter()
+            .copied()
+            .chain((0..=255u8).cycle().take(10_000))
+            .collect();
+        let metadata = metadata().to_string();
+        let body = multipart_body(&[
+            (METADATA_PART, None, metadata.as_bytes()),
+            (WASM_PART, Some("token.wasm"), &wasm),
+        ]);
+
+        let upload = PublishUpload::from_request(multipart_request(body), &())
+            .await
+            .unwrap();
+        assert_eq!(upload.wasm.as_deref(), Some(wasm.as_slice()));
+        assert_eq!(upload.request.name, "Token");
+        assert_eq!(upload.request.version.as_deref(), Some("1.0.0"));
+    }
+
+    #[tokio::test]
+    async fn json_upload_still_decodes_base64() {
+        use base64::Engine;
+
+        let mut metadata = metadata();
+        metadata["wasm"] = base64::engine::general_purpose::STANDARD
+            .encode(b"\0asm\x01\0\0\0")
+            .into();
+        let request = axum::http::Request::post("/api/contracts")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(metadata.to_string()))
+            .unwrap();
+
+        let upload = PublishUpload::from_request(request, &()).await.unwrap();
+        assert_eq!(upload.wasm.as_deref(), Some(&b"\0asm\x01\0\0\0"[..]));
+    }
+
+    #[tokio::test]
+    async fn oversized_wasm_part_is_rejected() {
+        let metadata = metadata().to_string();
+        let body = multipart_body(&[
+            (METADATA_PART, None, metadata.as_bytes()),
+            (WASM_PART, Some("token.wasm"), &[0u8; 65]),
+        ]);
+
+        let response = from_multipart(multipart(body).await, 64).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    #[tokio::test]
+    async fn multipart_requires_metadata_and_known_parts() {
+        let body = multipart_body(&[(WASM_PART, Some("token.wasm"), b"\0asm")]);
+        let response = from_multipart(multipart(body).await, 64).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let metadata = metadata().to_string();
+        let body = multipart_body(&[
+            (METADATA_PART, None, metadata.as_bytes()),
+            ("readme", None, b"# Token"),
+        ]);
+        let response = from_multipart(multipart(body).await, 64).await.unwrap_err();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+}
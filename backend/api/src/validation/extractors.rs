@@ -9,9 +9,9 @@ use axum::{
     http::StatusCode,
     Json,
 };
-use chrono::{SecondsFormat, Utc};
 use serde::{de::DeserializeOwned, Serialize};
-use uuid::Uuid;
+
+use crate::error::ErrorCode;
 
 /// A field-level validation error
 #[derive(Debug, Clone, Serialize)]
@@ -29,15 +29,20 @@ impl FieldError {
     }
 }
 
-/// Validation error response body
+/// Validation error response body, in the shared `{ code, message, details }`
+/// envelope with the per-field errors under `details`
 #[derive(Debug, Serialize)]
 pub struct ValidationErrorResponse {
-    pub error: String,
+    pub code: ErrorCode,
     pub message: String,
+    pub details: ValidationDetails,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ValidationDetails {
     pub errors: Vec<FieldError>,
-    pub code: u16,
-    pub timestamp: String,
-    pub correlation_id: String,
 }
 
 impl ValidationErrorResponse {
@@ -49,12 +54,10 @@ impl ValidationErrorResponse {
         };
 
         Self {
-            error: "ValidationError".to_string(),
+            code: ErrorCode::ValidationError,
             message: error_summary,
-            errors,
-            code: 400,
-            timestamp: Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true),
-            correlation_id: Uuid::new_v4().to_string(),
+            details: ValidationDetails { errors },
+            request_id: crate::observability::current_request_id(),
         }
     }
 }
@@ -277,9 +280,9 @@ mod tests {
         
         let response = ValidationErrorResponse::new(errors);
         
-        assert_eq!(response.error, "ValidationError");
-        assert_eq!(response.code, 400);
-        assert_eq!(response.errors.len(), 2);
+        assert_eq!(response.code, ErrorCode::ValidationError);
+        assert_eq!(response.code.as_str(), "request.validation_failed");
+        assert_eq!(response.details.errors.len(), 2);
         assert!(response.message.contains("2 fields"));
     }
 
@@ -4,18 +4,19 @@
 //! that need validation when received from clients.
 
 use shared::models::{
-    CreateMigrationRequest, DependencyDeclaration, PublishRequest, UpdateMigrationStatusRequest,
-    VerifyRequest,
+    CreateMigrationRequest, DependencyDeclaration, DeprecateVersionRequest, PublishRequest,
+    UpdateMigrationStatusRequest, UpdatePublisherRequest, VerifyBuildRequest, VerifyRequest,
 };
 
 use super::extractors::{FieldError, Validatable, ValidationBuilder};
 use super::sanitizers::{
     normalize_contract_id, normalize_stellar_address, sanitize_description_optional, sanitize_name,
-    sanitize_tags, sanitize_url_optional, trim,
+    sanitize_tags, sanitize_url_optional, trim, trim_optional,
 };
 use super::validators::{
     validate_contract_id, validate_json_depth, validate_length, validate_no_xss, validate_semver,
-    validate_source_code_size, validate_stellar_address, validate_tags, validate_url_optional,
+    validate_source_code_size, validate_stellar_address, validate_tags, validate_url,
+    validate_url_optional,
 };
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -46,6 +47,30 @@ const MAX_DEPENDENCY_NAME_LENGTH: usize = 255;
 const MAX_VERSION_CONSTRAINT_LENGTH: usize = 100;
 /// Maximum number of dependencies
 const MAX_DEPENDENCIES_COUNT: usize = 50;
+/// Maximum length for a version string (matches `contract_versions.version`)
+const MAX_VERSION_LENGTH: usize = 50;
+/// Maximum length for a deprecation reason
+const MAX_DEPRECATION_REASON_LENGTH: usize = 1000;
+/// Maximum README size
+const MAX_README_BYTES: usize = crate::readme::MAX_README_BYTES;
+/// Maximum size of one version's changelog
+pub(crate) const MAX_CHANGELOG_BYTES: usize = 64 * 1024;
+/// Maximum length for a publisher display name (matches `publishers.display_name`)
+const MAX_DISPLAY_NAME_LENGTH: usize = 100;
+/// Maximum length for a publisher bio
+const MAX_BIO_LENGTH: usize = 2000;
+/// Maximum length for profile URLs (matches `publishers.website`)
+const MAX_PROFILE_URL_LENGTH: usize = 500;
+/// Maximum length for a build's compiler version string
+const MAX_COMPILER_VERSION_LENGTH: usize = 200;
+/// Maximum length for a build target triple
+const MAX_BUILD_TARGET_LENGTH: usize = 100;
+/// Maximum length for a build's optimization flags
+const MAX_OPTIMIZATION_FLAGS_LENGTH: usize = 500;
+/// Maximum length for a source repository URL
+const MAX_SOURCE_URL_LENGTH: usize = 500;
+/// Maximum length for a git ref (commit, tag or branch)
+const MAX_GIT_REF_LENGTH: usize = 255;
 
 // ─────────────────────────────────────────────────────────────────────────────
 // PublishRequest validation
@@ -81,93 +106,222 @@ impl Validatable for PublishRequest {
 
         // Sanitize dependencies
         for dep in &mut self.dependencies {
-            dep.name = trim(&dep.name);
-            dep.version_constraint = trim(&dep.version_constraint);
+            dep.sanitize();
+        }
+
+        trim_optional(&mut self.version);
+        trim_optional(&mut self.wasm);
+        trim_optional(&mut self.signature);
+        trim_optional(&mut self.public_key);
+
+        if let Some(ref mut build) = self.build_info {
+            build.compiler_version = trim(&build.compiler_version);
+            build.target = trim(&build.target);
+            build.optimization_flags = trim(&build.optimization_flags);
         }
     }
 
     fn validate(&self) -> Result<(), Vec<FieldError>> {
-        let mut builder = ValidationBuilder::new();
+        validate_publish_request(self, self.wasm.is_some())
+    }
+}
 
-        // contract_id: required, valid Stellar contract ID format
-        builder.check("contract_id", || validate_contract_id(&self.contract_id));
+/// Validate a publish request whose artifact may arrive outside the JSON
+/// (`has_artifact`), as with multipart uploads
+pub fn validate_publish_request(
+    req: &PublishRequest,
+    has_artifact: bool,
+) -> Result<(), Vec<FieldError>> {
+    let mut builder = ValidationBuilder::new();
+
+    // contract_id: required, valid Stellar contract ID format
+    builder.check("contract_id", || validate_contract_id(&req.contract_id));
+
+    // name: required, 1-255 characters
+    builder.check("name", || {
+        if req.name.is_empty() {
+            return Err("name is required".to_string());
+        }
+        validate_length(&req.name, MIN_NAME_LENGTH, MAX_NAME_LENGTH)
+    });
 
-        // name: required, 1-255 characters
-        builder.check("name", || {
-            if self.name.is_empty() {
-                return Err("name is required".to_string());
-            }
-            validate_length(&self.name, MIN_NAME_LENGTH, MAX_NAME_LENGTH)
+    // name: no XSS patterns
+    builder.check("name", || validate_no_xss(&req.name));
+
+    // description: optional, max 5000 characters
+    if let Some(ref desc) = req.description {
+        builder.check("description", || {
+            validate_length(desc, 0, MAX_DESCRIPTION_LENGTH)
         });
+        builder.check("description", || validate_no_xss(desc));
+    }
 
-        // name: no XSS patterns
-        builder.check("name", || validate_no_xss(&self.name));
+    // publisher_address: required, valid Stellar address
+    builder.check("publisher_address", || {
+        validate_stellar_address(&req.publisher_address)
+    });
 
-        // description: optional, max 5000 characters
-        if let Some(ref desc) = self.description {
-            builder.check("description", || {
-                validate_length(desc, 0, MAX_DESCRIPTION_LENGTH)
-            });
-            builder.check("description", || validate_no_xss(desc));
-        }
+    // source_url: optional, valid URL format
+    builder.check("source_url", || validate_url_optional(&req.source_url));
 
-        // publisher_address: required, valid Stellar address
-        builder.check("publisher_address", || {
-            validate_stellar_address(&self.publisher_address)
-        });
+    // category: optional, max length
+    if let Some(ref cat) = req.category {
+        builder.check("category", || validate_length(cat, 1, MAX_CATEGORY_LENGTH));
+        builder.check("category", || validate_no_xss(cat));
+    }
 
-        // source_url: optional, valid URL format
-        builder.check("source_url", || validate_url_optional(&self.source_url));
+    // tags: max count, each max length
+    builder.check("tags", || {
+        validate_tags(&req.tags, MAX_TAGS_COUNT, MAX_TAG_LENGTH)
+    });
+
+    // dependencies: validate each
+    builder.check("dependencies", || {
+        if req.dependencies.len() > MAX_DEPENDENCIES_COUNT {
+            return Err(format!(
+                "at most {} dependencies are allowed",
+                MAX_DEPENDENCIES_COUNT
+            ));
+        }
+        Ok(())
+    });
+
+    for (i, dep) in req.dependencies.iter().enumerate() {
+        let field_name = format!("dependencies[{}].name", i);
+        if dep.name.is_empty() {
+            builder.add_error(&field_name, "dependency name is required");
+        } else if dep.name.len() > MAX_DEPENDENCY_NAME_LENGTH {
+            builder.add_error(
+                &field_name,
+                format!("must be at most {} characters", MAX_DEPENDENCY_NAME_LENGTH),
+            );
+        }
 
-        // category: optional, max length
-        if let Some(ref cat) = self.category {
-            builder.check("category", || validate_length(cat, 1, MAX_CATEGORY_LENGTH));
-            builder.check("category", || validate_no_xss(cat));
+        let constraint_field = format!("dependencies[{}].version_constraint", i);
+        if dep.version_constraint.is_empty() {
+            builder.add_error(&constraint_field, "version constraint is required");
+        } else if dep.version_constraint.len() > MAX_VERSION_CONSTRAINT_LENGTH {
+            builder.add_error(
+                &constraint_field,
+                format!(
+                    "must be at most {} characters",
+                    MAX_VERSION_CONSTRAINT_LENGTH
+                ),
+            );
+        } else if semver::VersionReq::parse(&dep.version_constraint).is_err() {
+            builder.add_error(&constraint_field, "must be a valid semver range");
         }
 
-        // tags: max count, each max length
-        builder.check("tags", || {
-            validate_tags(&self.tags, MAX_TAGS_COUNT, MAX_TAG_LENGTH)
+        if let Some(Err(err)) = dep.contract_id.as_deref().map(validate_contract_id) {
+            builder.add_error(&format!("dependencies[{}].contract_id", i), err);
+        }
+    }
+
+    // version: optional; strict semver is enforced by the publish
+    // handler so it can answer 422 version.invalid_semver
+    if let Some(ref version) = req.version {
+        builder.check("version", || validate_length(version, 1, MAX_VERSION_LENGTH));
+    }
+
+    // wasm: optional (base64 in JSON, a file part in multipart), only
+    // meaningful with a version
+    if has_artifact {
+        builder.check("wasm", || {
+            if req.version.is_none() {
+                return Err("version is required when wasm is provided".to_string());
+            }
+            match req.wasm {
+                Some(ref wasm) => {
+                    use base64::Engine;
+                    base64::engine::general_purpose::STANDARD
+                        .decode(wasm)
+                        .map(|_| ())
+                        .map_err(|_| "must be base64-encoded WASM".to_string())
+                }
+                None => Ok(()),
+            }
+        });
+    }
+
+    // signature/public_key: given together, over a wasm artifact; the
+    // signature itself is verified on publish
+    if req.signature.is_some() || req.public_key.is_some() {
+        builder.check("signature", || {
+            if !has_artifact {
+                return Err("wasm is required when signing".to_string());
+            }
+            if req.signature.is_none() || req.public_key.is_none() {
+                return Err("signature and public_key must be provided together".to_string());
+            }
+            Ok(())
         });
+    }
+
+    // metadata: bounded nesting; the schema itself is checked on publish
+    if let Some(ref metadata) = req.metadata {
+        builder.check("metadata", || validate_json_depth(metadata, MAX_JSON_DEPTH));
+    }
 
-        // dependencies: validate each
-        builder.check("dependencies", || {
-            if self.dependencies.len() > MAX_DEPENDENCIES_COUNT {
+    // readme: size-capped markdown; HTML in it is sanitized on render,
+    // not rejected here
+    if let Some(ref readme) = req.readme {
+        builder.check("readme", || {
+            if readme.len() > MAX_README_BYTES {
                 return Err(format!(
-                    "at most {} dependencies are allowed",
-                    MAX_DEPENDENCIES_COUNT
+                    "must be at most {} KiB (got {} bytes)",
+                    MAX_README_BYTES / 1024,
+                    readme.len()
                 ));
             }
             Ok(())
         });
+    }
 
-        for (i, dep) in self.dependencies.iter().enumerate() {
-            let field_name = format!("dependencies[{}].name", i);
-            if dep.name.is_empty() {
-                builder.add_error(&field_name, "dependency name is required");
-            } else if dep.name.len() > MAX_DEPENDENCY_NAME_LENGTH {
-                builder.add_error(
-                    &field_name,
-                    format!("must be at most {} characters", MAX_DEPENDENCY_NAME_LENGTH),
-                );
+    // changelog: notes for the version being created, so it needs one
+    if let Some(ref changelog) = req.changelog {
+        builder.check("changelog", || {
+            if req.version.is_none() {
+                return Err("changelog requires version".to_string());
             }
+            if changelog.len() > MAX_CHANGELOG_BYTES {
+                return Err(format!(
+                    "must be at most {} KiB (got {} bytes)",
+                    MAX_CHANGELOG_BYTES / 1024,
+                    changelog.len()
+                ));
+            }
+            Ok(())
+        });
+    }
 
-            let constraint_field = format!("dependencies[{}].version_constraint", i);
-            if dep.version_constraint.is_empty() {
-                builder.add_error(&constraint_field, "version constraint is required");
-            } else if dep.version_constraint.len() > MAX_VERSION_CONSTRAINT_LENGTH {
-                builder.add_error(
-                    &constraint_field,
-                    format!(
-                        "must be at most {} characters",
-                        MAX_VERSION_CONSTRAINT_LENGTH
-                    ),
-                );
+    // build_info: describes the version being created, so it needs one;
+    // every field is required once the object is given
+    if let Some(ref build) = req.build_info {
+        builder.check("build_info", || {
+            if req.version.is_none() {
+                return Err("build_info requires version".to_string());
             }
+            Ok(())
+        });
+        for (field, value, max) in [
+            (
+                "build_info.compiler_version",
+                &build.compiler_version,
+                MAX_COMPILER_VERSION_LENGTH,
+            ),
+            ("build_info.target", &build.target, MAX_BUILD_TARGET_LENGTH),
+            (
+                "build_info.optimization_flags",
+                &build.optimization_flags,
+                MAX_OPTIMIZATION_FLAGS_LENGTH,
+            ),
+        ] {
+            builder.check(field, || validate_length(value, 1, max));
+            builder.check(field, || validate_no_xss(value));
         }
-
-        builder.build()
     }
+
+    builder.build()
 }
 
 // ─────────────────────────────────────────────────────────────────────────────
@@ -275,6 +429,8 @@ impl Validatable for DependencyDeclaration {
     fn sanitize(&mut self) {
         self.name = trim(&self.name);
         self.version_constraint = trim(&self.version_constraint);
+        trim_optional(&mut self.contract_id);
+        self.contract_id = self.contract_id.as_deref().map(normalize_contract_id);
     }
 
     fn validate(&self) -> Result<(), Vec<FieldError>> {
@@ -294,6 +450,121 @@ impl Validatable for DependencyDeclaration {
             validate_length(&self.version_constraint, 1, MAX_VERSION_CONSTRAINT_LENGTH)
         });
 
+        if let Some(contract_id) = &self.contract_id {
+            builder.check("contract_id", || validate_contract_id(contract_id));
+        }
+
+        builder.build()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// DeprecateVersionRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for DeprecateVersionRequest {
+    fn sanitize(&mut self) {
+        self.reason = super::sanitizers::sanitize_description(&self.reason);
+        trim_optional(&mut self.superseded_by);
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        builder.check("reason", || {
+            if self.reason.is_empty() {
+                return Err("reason is required".to_string());
+            }
+            validate_length(&self.reason, 1, MAX_DEPRECATION_REASON_LENGTH)
+        });
+
+        // existence of the successor is checked against the database
+        if let Some(ref successor) = self.superseded_by {
+            builder.check("superseded_by", || {
+                validate_length(successor, 1, MAX_VERSION_LENGTH)
+            });
+        }
+
+        builder.build()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// VerifyBuildRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for VerifyBuildRequest {
+    fn sanitize(&mut self) {
+        self.source_url = trim(&self.source_url);
+        self.git_ref = trim(&self.git_ref);
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        builder.check("source_url", || {
+            if self.source_url.is_empty() {
+                return Err("source_url is required".to_string());
+            }
+            validate_length(&self.source_url, 1, MAX_SOURCE_URL_LENGTH)?;
+            validate_url(&self.source_url)
+        });
+
+        builder.check("git_ref", || {
+            if self.git_ref.is_empty() {
+                return Err("git_ref is required".to_string());
+            }
+            if self.git_ref.chars().any(char::is_whitespace) {
+                return Err("must not contain whitespace".to_string());
+            }
+            validate_length(&self.git_ref, 1, MAX_GIT_REF_LENGTH)
+        });
+
+        builder.build()
+    }
+}
+
+// ─────────────────────────────────────────────────────────────────────────────
+// UpdatePublisherRequest validation
+// ─────────────────────────────────────────────────────────────────────────────
+
+impl Validatable for UpdatePublisherRequest {
+    // Empty strings are kept: they clear the field, while `None` leaves it
+    fn sanitize(&mut self) {
+        if let Some(ref mut name) = self.display_name {
+            *name = sanitize_name(name);
+        }
+        if let Some(ref mut bio) = self.bio {
+            *bio = super::sanitizers::sanitize_description(bio);
+        }
+        if let Some(ref mut website) = self.website {
+            *website = trim(website);
+        }
+        if let Some(ref mut avatar_url) = self.avatar_url {
+            *avatar_url = trim(avatar_url);
+        }
+    }
+
+    fn validate(&self) -> Result<(), Vec<FieldError>> {
+        let mut builder = ValidationBuilder::new();
+
+        if let Some(ref name) = self.display_name {
+            builder.check("display_name", || {
+                validate_length(name, 0, MAX_DISPLAY_NAME_LENGTH)
+            });
+            builder.check("display_name", || validate_no_xss(name));
+        }
+        if let Some(ref bio) = self.bio {
+            builder.check("bio", || validate_length(bio, 0, MAX_BIO_LENGTH));
+            builder.check("bio", || validate_no_xss(bio));
+        }
+        for (field, url) in [("website", &self.website), ("avatar_url", &self.avatar_url)] {
+            builder.check(field, || validate_url_optional(url));
+            if let Some(url) = url {
+                builder.check(field, || validate_length(url, 0, MAX_PROFILE_URL_LENGTH));
+            }
+        }
+
         builder.build()
     }
 }
@@ -323,6 +594,16 @@ mod tests {
             source_url: Some("https://github.com/user/repo".to_string()),
             publisher_address: valid_stellar_address(),
             dependencies: vec![],
+            version: None,
+            wasm: None,
+            metadata: None,
+            readme: None,
+            changelog: None,
+            signature: None,
+            public_key: None,
+            organization_id: None,
+            visibility: None,
+            build_info: None,
         };
 
         assert!(req.validate().is_ok());
@@ -340,6 +621,16 @@ mod tests {
             source_url: None,
             publisher_address: valid_stellar_address(),
             dependencies: vec![],
+            version: None,
+            wasm: None,
+            metadata: None,
+            readme: None,
+            changelog: None,
+            signature: None,
+            public_key: None,
+            organization_id: None,
+            visibility: None,
+            build_info: None,
         };
 
         let result = req.validate();
@@ -360,6 +651,16 @@ mod tests {
             source_url: None,
             publisher_address: valid_stellar_address(),
             dependencies: vec![],
+            version: None,
+            wasm: None,
+            metadata: None,
+            readme: None,
+            changelog: None,
+            signature: None,
+            public_key: None,
+            organization_id: None,
+            visibility: None,
+            build_info: None,
         };
 
         let result = req.validate();
@@ -368,6 +669,71 @@ mod tests {
         assert!(errors.iter().any(|e| e.field == "name"));
     }
 
+    #[test]
+    fn test_publish_request_changelog_needs_version() {
+        let mut req = PublishRequest {
+            contract_id: valid_contract_id(),
+            name: "My Contract".to_string(),
+            description: None,
+            network: Network::Testnet,
+            category: None,
+            tags: vec![],
+            source_url: None,
+            publisher_address: valid_stellar_address(),
+            dependencies: vec![],
+            version: None,
+            wasm: None,
+            metadata: None,
+            readme: None,
+            changelog: Some("### Fixed\n- Overflow in `transfer`".to_string()),
+            signature: None,
+            public_key: None,
+            organization_id: None,
+            visibility: None,
+            build_info: None,
+        };
+
+        let errors = req.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "changelog"));
+
+        req.version = Some("1.1.0".to_string());
+        assert!(req.validate().is_ok());
+
+        req.changelog = Some("x".repeat(MAX_CHANGELOG_BYTES + 1));
+        assert!(req.validate().is_err());
+    }
+
+    #[test]
+    fn test_publish_request_build_info_needs_fields_and_a_version() {
+        let mut req: PublishRequest = serde_json::from_value(serde_json::json!({
+            "contract_id": valid_contract_id(),
+            "name": "My Contract",
+            "network": "testnet",
+            "tags": [],
+            "publisher_address": valid_stellar_address(),
+            "version": "1.0.0",
+            "build_info": {
+                "compiler_version": " rustc 1.79.0 ",
+                "target": "wasm32-unknown-unknown",
+                "optimization_flags": "opt-level=z lto=true",
+            },
+        }))
+        .unwrap();
+        req.sanitize();
+        assert!(req.validate().is_ok());
+        let build = req.build_info.as_ref().unwrap();
+        assert_eq!(build.compiler_version, "rustc 1.79.0");
+
+        req.build_info.as_mut().unwrap().target = String::new();
+        let errors = req.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "build_info.target"));
+
+        req.build_info.as_mut().unwrap().target = "wasm32-unknown-unknown".to_string();
+        req.version = None;
+        let errors = req.validate().unwrap_err();
+        assert!(errors.iter().any(|e| e.field == "build_info"));
+    }
+
     #[test]
     fn test_publish_request_sanitization() {
         let mut req = PublishRequest {
@@ -381,6 +747,16 @@ mod tests {
             publisher_address: "  gdlzfc3syjydzt7k67vz75hpjvieuvnixf47zg2fb2rmqqvu2hhgcysc  "
                 .to_string(),
             dependencies: vec![],
+            version: None,
+            wasm: None,
+            metadata: None,
+            readme: None,
+            changelog: None,
+            signature: None,
+            public_key: None,
+            organization_id: None,
+            visibility: None,
+            build_info: None,
         };
 
         req.sanitize();
@@ -414,6 +790,8 @@ mod tests {
             source_code: "fn main() {}".to_string(),
             build_params: serde_json::json!({"optimize": true}),
             compiler_version: "1.0.0".to_string(),
+            network: None,
+            version: None,
         };
 
         assert!(req.validate().is_ok());
@@ -426,6 +804,8 @@ mod tests {
             source_code: "".to_string(),
             build_params: serde_json::json!({}),
             compiler_version: "1.0.0".to_string(),
+            network: None,
+            version: None,
         };
 
         let result = req.validate();
@@ -441,6 +821,8 @@ mod tests {
             source_code: "fn main() {}".to_string(),
             build_params: serde_json::json!({}),
             compiler_version: "not-a-version".to_string(),
+            network: None,
+            version: None,
         };
 
         let result = req.validate();
@@ -461,6 +843,16 @@ mod tests {
             source_url: None,
             publisher_address: valid_stellar_address(),
             dependencies: vec![],
+            version: None,
+            wasm: None,
+            metadata: None,
+            readme: None,
+            changelog: None,
+            signature: None,
+            public_key: None,
+            organization_id: None,
+            visibility: None,
+            build_info: None,
         };
 
         let result = req.validate();
@@ -468,4 +860,34 @@ mod tests {
         let errors = result.unwrap_err();
         assert!(errors.iter().any(|e| e.field == "tags"));
     }
+
+    #[test]
+    fn test_update_publisher_request_checks_urls_and_lengths() {
+        let mut req = UpdatePublisherRequest {
+            display_name: Some("  Alice   <b>Labs</b> ".to_string()),
+            bio: Some(String::new()),
+            website: Some(" https://alice.example.com ".to_string()),
+            avatar_url: None,
+        };
+        req.sanitize();
+        assert_eq!(req.display_name.as_deref(), Some("Alice Labs"));
+        assert_eq!(req.bio.as_deref(), Some(""));
+        assert_eq!(req.website.as_deref(), Some("https://alice.example.com"));
+        assert!(req.validate().is_ok());
+
+        let req = UpdatePublisherRequest {
+            display_name: Some("x".repeat(MAX_DISPLAY_NAME_LENGTH + 1)),
+            bio: None,
+            website: Some("alice.example.com".to_string()),
+            avatar_url: Some("javascript:alert(1)".to_string()),
+        };
+        let errors = req.validate().unwrap_err();
+        for field in ["display_name", "website", "avatar_url"] {
+            assert!(
+                errors.iter().any(|e| e.field == field),
+                "{} accepted",
+                field
+            );
+        }
+    }
 }
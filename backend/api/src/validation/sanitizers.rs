@@ -109,9 +109,11 @@ pub fn sanitize_url_optional(url: &mut Option<String>) {
 
 /// Sanitize a vector of tags: trim each, remove empty, strip HTML
 pub fn sanitize_tags(tags: &[String]) -> Vec<String> {
+    let mut seen = std::collections::HashSet::new();
     tags.iter()
-        .map(|t| sanitize_name(t))
+        .map(|t| sanitize_name(t).to_lowercase())
         .filter(|t| !t.is_empty())
+        .filter(|t| seen.insert(t.clone()))
         .collect()
 }
 
@@ -205,6 +207,12 @@ mod tests {
         assert_eq!(sanitized, vec!["defi", "bad", "token"]);
     }
 
+    #[test]
+    fn test_sanitize_tags_lowercases_and_dedupes() {
+        let tags = vec!["DeFi".to_string(), "defi".to_string(), "NFT".to_string()];
+        assert_eq!(sanitize_tags(&tags), vec!["defi", "nft"]);
+    }
+
     #[test]
     fn test_trim_optional() {
         let mut some_value = Some("  hello  ".to_string());
@@ -23,6 +23,9 @@ lazy_static! {
         r"^https?://[^\s/$.?#].[^\s]*$"
     ).unwrap();
     
+    /// Tag slug pattern: lowercase alphanumerics separated by single hyphens
+    static ref TAG_SLUG_REGEX: Regex = Regex::new(r"^[a-z0-9]+(?:-[a-z0-9]+)*$").unwrap();
+
     /// HTML tag detection pattern
     static ref HTML_TAG_REGEX: Regex = Regex::new(r"<[^>]+>").unwrap();
     
@@ -187,6 +190,12 @@ pub fn validate_tags(tags: &[String], max_tags: usize, max_tag_length: usize) ->
         if let Err(e) = validate_no_xss(trimmed) {
             return Err(format!("tag '{}': {}", trimmed, e));
         }
+        if !TAG_SLUG_REGEX.is_match(trimmed) {
+            return Err(format!(
+                "tag '{}' must be a lowercase slug (letters, digits and hyphens)",
+                trimmed
+            ));
+        }
     }
     
     Ok(())
@@ -292,6 +301,11 @@ mod tests {
         // Too many tags
         let many_tags: Vec<String> = (0..15).map(|i| format!("tag{}", i)).collect();
         assert!(validate_tags(&many_tags, 10, 50).is_err());
+
+        // Not slugs
+        assert!(validate_tags(&["smart contracts".to_string()], 10, 50).is_err());
+        assert!(validate_tags(&["-defi".to_string()], 10, 50).is_err());
+        assert!(validate_tags(&["cross-chain".to_string()], 10, 50).is_ok());
     }
 
     #[test]
@@ -0,0 +1,174 @@
+use axum::{
+    extract::{Path, Query, State},
+    Extension, Json,
+};
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use uuid::Uuid;
+
+use crate::{
+    auth::Caller,
+    error::{ApiError, ApiResult, ErrorCode},
+    handlers::db_internal_error,
+    pagination::{PageParams, Paginated},
+    state::AppState,
+    validation::validate_url,
+};
+
+pub(crate) const MIN_SECRET_LENGTH: usize = 16;
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+const MAX_DELIVERY_LIMIT: i64 = 200;
+
+/// A registered webhook. The secret is never echoed back.
+#[derive(Debug, Serialize, FromRow)]
+pub struct Webhook {
+    pub id: Uuid,
+    pub publisher_id: Uuid,
+    pub url: String,
+    pub active: bool,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub secret: String,
+}
+
+#[derive(Debug, Serialize, FromRow)]
+pub struct WebhookDelivery {
+    pub id: Uuid,
+    pub webhook_id: Uuid,
+    pub event: String,
+    pub payload: serde_json::Value,
+    pub succeeded: bool,
+    pub attempts: i32,
+    pub status_code: Option<i32>,
+    pub error: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryListParams {
+    /// Only return failed deliveries
+    pub failed_only: Option<bool>,
+    pub limit: Option<i64>,
+    pub offset: Option<i64>,
+}
+
+pub async fn create_webhook(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(publisher_id): Path<Uuid>,
+    Json(req): Json<CreateWebhookRequest>,
+) -> ApiResult<Json<Webhook>> {
+    caller.authorize_publisher(publisher_id)?;
+
+    let url = req.url.trim();
+    if url.is_empty() {
+        return Err(ApiError::bad_request(ErrorCode::InvalidWebhookUrl, "url is required"));
+    }
+    validate_url(url).map_err(|msg| ApiError::bad_request(ErrorCode::InvalidWebhookUrl, format!("url {}", msg)))?;
+    if req.secret.len() < MIN_SECRET_LENGTH {
+        return Err(ApiError::bad_request(
+            ErrorCode::InvalidWebhookSecret,
+            format!("secret must be at least {} characters", MIN_SECRET_LENGTH),
+        ));
+    }
+
+    let webhook: Webhook = sqlx::query_as(
+        "INSERT INTO webhooks (publisher_id, url, secret)
+         VALUES ($1, $2, $3)
+         RETURNING id, publisher_id, url, active, created_at",
+    )
+    .bind(publisher_id)
+    .bind(url)
+    .bind(&req.secret)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| match err {
+        sqlx::Error::Database(ref db) if db.is_foreign_key_violation() => ApiError::not_found(
+            ErrorCode::PublisherNotFound,
+            format!("No publisher found with ID: {}", publisher_id),
+        ),
+        _ => db_internal_error("create webhook", err),
+    })?;
+
+    Ok(Json(webhook))
+}
+
+pub async fn list_webhooks(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(publisher_id): Path<Uuid>,
+    Query(page): Query<PageParams>,
+) -> ApiResult<Json<Paginated<Webhook>>> {
+    caller.authorize_publisher(publisher_id)?;
+
+    let webhooks: Vec<Webhook> = sqlx::query_as(
+        "SELECT id, publisher_id, url, active, created_at FROM webhooks
+         WHERE publisher_id = $1 ORDER BY created_at DESC, id DESC
+         LIMIT $2 OFFSET $3",
+    )
+    .bind(publisher_id)
+    .bind(page.limit())
+    .bind(page.offset())
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list webhooks", err))?;
+    let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM webhooks WHERE publisher_id = $1")
+        .bind(publisher_id)
+        .fetch_one(&state.db)
+        .await
+        .map_err(|err| db_internal_error("count webhooks", err))?;
+
+    Ok(Json(Paginated::new(
+        webhooks,
+        total,
+        page.limit(),
+        page.offset(),
+    )))
+}
+
+pub async fn list_webhook_deliveries(
+    State(state): State<AppState>,
+    Extension(caller): Extension<Caller>,
+    Path(publisher_id): Path<Uuid>,
+    Query(params): Query<DeliveryListParams>,
+) -> ApiResult<Json<Paginated<WebhookDelivery>>> {
+    caller.authorize_publisher(publisher_id)?;
+
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+        .clamp(1, MAX_DELIVERY_LIMIT);
+    let offset = params.offset.unwrap_or(0).max(0);
+    let failed_only = params.failed_only.unwrap_or(false);
+
+    let deliveries: Vec<WebhookDelivery> = sqlx::query_as(
+        "SELECT d.* FROM webhook_deliveries d
+         JOIN webhooks w ON w.id = d.webhook_id
+         WHERE w.publisher_id = $1 AND ($2 = FALSE OR d.succeeded = FALSE)
+         ORDER BY d.created_at DESC, d.id DESC
+         LIMIT $3 OFFSET $4",
+    )
+    .bind(publisher_id)
+    .bind(failed_only)
+    .bind(limit)
+    .bind(offset)
+    .fetch_all(&state.db)
+    .await
+    .map_err(|err| db_internal_error("list webhook deliveries", err))?;
+    let total: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM webhook_deliveries d
+         JOIN webhooks w ON w.id = d.webhook_id
+         WHERE w.publisher_id = $1 AND ($2 = FALSE OR d.succeeded = FALSE)",
+    )
+    .bind(publisher_id)
+    .bind(failed_only)
+    .fetch_one(&state.db)
+    .await
+    .map_err(|err| db_internal_error("count webhook deliveries", err))?;
+
+    Ok(Json(Paginated::new(deliveries, total, limit, offset)))
+}
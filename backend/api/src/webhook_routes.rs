@@ -0,0 +1,17 @@
+use axum::{routing::get, Router};
+
+use crate::{state::AppState, webhook_handlers};
+
+/// Webhook management routes. All of them act on a publisher and must be
+/// wrapped in `auth::require_api_key`.
+pub fn webhook_routes() -> Router<AppState> {
+    Router::new()
+        .route(
+            "/api/publishers/:id/webhooks",
+            get(webhook_handlers::list_webhooks).post(webhook_handlers::create_webhook),
+        )
+        .route(
+            "/api/publishers/:id/webhooks/deliveries",
+            get(webhook_handlers::list_webhook_deliveries),
+        )
+}
@@ -2,6 +2,7 @@ use chrono::{DateTime, Utc};
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
+use utoipa::ToSchema;
 use uuid::Uuid;
 
 // ═══════════════════════════════════════════════════════════════════════════
@@ -9,7 +10,7 @@ use uuid::Uuid;
 // ═══════════════════════════════════════════════════════════════════════════
 
 /// Represents a smart contract in the registry
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Contract {
     pub id: Uuid,
     pub contract_id: String,
@@ -17,16 +18,54 @@ pub struct Contract {
     pub name: String,
     pub description: Option<String>,
     pub publisher_id: Uuid,
+    /// Owning organization; the contract belongs to `publisher_id` alone when unset
+    #[serde(default)]
+    #[sqlx(default)]
+    pub organization_id: Option<Uuid>,
     pub network: Network,
     pub is_verified: bool,
     pub category: Option<String>,
     pub tags: Vec<String>,
+    /// Publisher-supplied metadata, validated against the category's schema
+    #[serde(default)]
+    #[sqlx(default)]
+    #[schema(value_type = Option<Object>)]
+    pub metadata: Option<serde_json::Value>,
+    /// The publisher has deactivated their account; the contract stays
+    /// available but is no longer maintained through the registry
+    #[serde(default)]
+    #[sqlx(default)]
+    pub publisher_deactivated: bool,
+    /// Private contracts are only visible to their owners
+    #[serde(default)]
+    #[sqlx(default)]
+    pub visibility: ContractVisibility,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
 
+/// Who may see a contract. Private contracts are left out of listings,
+/// search, feeds and stats, and read as missing to anyone but the
+/// publisher, members of the owning organization, and admins.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema,
+)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum ContractVisibility {
+    #[default]
+    Public,
+    Private,
+}
+
+/// Change a contract's visibility
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetVisibilityRequest {
+    pub visibility: ContractVisibility,
+}
+
 /// Network where the contract is deployed
-#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type)]
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::Type, ToSchema)]
 #[sqlx(type_name = "network_type", rename_all = "lowercase")]
 #[serde(rename_all = "lowercase")]
 pub enum Network {
@@ -36,7 +75,7 @@ pub enum Network {
 }
 
 /// Contract version information
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct ContractVersion {
     pub id: Uuid,
     pub contract_id: Uuid,
@@ -46,6 +85,103 @@ pub struct ContractVersion {
     pub commit_hash: Option<String>,
     pub release_notes: Option<String>,
     pub created_at: DateTime<Utc>,
+    pub deprecated: bool,
+    pub deprecated_at: Option<DateTime<Utc>>,
+    pub deprecation_reason: Option<String>,
+    pub superseded_by: Option<String>,
+    /// CID of the pinned artifact, once pinning succeeds
+    pub ipfs_cid: Option<String>,
+    /// `pending`, `pinned` or `failed`; null when IPFS is not configured
+    pub ipfs_status: Option<String>,
+    /// Yanked versions stay downloadable but are skipped by range resolution
+    #[serde(default)]
+    #[sqlx(default)]
+    pub yanked: bool,
+    #[serde(default)]
+    #[sqlx(default)]
+    pub yanked_at: Option<DateTime<Utc>>,
+    /// WASM downloads of this version; flushed in batches, so it can trail
+    /// live traffic by a few seconds
+    #[serde(default)]
+    #[sqlx(default)]
+    pub download_count: i64,
+    /// The artifact carried a publisher ed25519 signature that verified
+    #[serde(default)]
+    #[sqlx(default)]
+    pub signed: bool,
+    /// Hex public key the artifact was signed with
+    #[serde(default)]
+    #[sqlx(default)]
+    pub signing_key: Option<String>,
+    /// How the WASM was built; null when the publisher did not say
+    #[serde(default)]
+    #[sqlx(default)]
+    #[schema(value_type = Option<BuildInfo>)]
+    pub build_info: Option<sqlx::types::Json<BuildInfo>>,
+    /// Latest reproducible-build check; null until one is requested
+    #[serde(default)]
+    #[sqlx(default)]
+    #[schema(value_type = Option<BuildVerification>)]
+    pub build_verification: Option<sqlx::types::Json<BuildVerification>>,
+}
+
+/// Toolchain that produced a version's WASM, as reported on publish
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+pub struct BuildInfo {
+    /// e.g. `rustc 1.79.0 (129f3b996 2024-06-10)`
+    pub compiler_version: String,
+    /// e.g. `wasm32-unknown-unknown`
+    pub target: String,
+    /// e.g. `opt-level=z lto=true`
+    pub optimization_flags: String,
+}
+
+/// Request body for POST /api/contracts/:id/versions/:version/verify-build
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VerifyBuildRequest {
+    /// Repository the version is claimed to be built from
+    pub source_url: String,
+    /// Commit, tag or branch within `source_url`
+    pub git_ref: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum BuildVerificationStatus {
+    /// The rebuild is still running
+    Pending,
+    /// The rebuilt WASM hashes the same as the published one
+    Verified,
+    /// The rebuild succeeded but produced different WASM
+    Mismatch,
+    /// The rebuild itself failed; see `message`
+    Failed,
+}
+
+/// Outcome of rebuilding a version from a claimed source reference
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct BuildVerification {
+    pub status: BuildVerificationStatus,
+    pub source_url: String,
+    pub git_ref: String,
+    /// sha256 of the published WASM
+    pub published_wasm_hash: String,
+    /// sha256 of the WASM the build service produced
+    pub rebuilt_wasm_hash: Option<String>,
+    /// Whether the two hashes match; null until the rebuild succeeds
+    pub reproducible: Option<bool>,
+    /// Why the rebuild failed, or which hashes differ
+    pub message: Option<String>,
+    pub requested_at: DateTime<Utc>,
+    pub completed_at: Option<DateTime<Utc>>,
+}
+
+/// Request to deprecate a published version
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct DeprecateVersionRequest {
+    pub reason: String,
+    /// Version of the same contract that replaces this one
+    pub superseded_by: Option<String>,
 }
 
 /// Verification status and details
@@ -72,7 +208,7 @@ pub enum VerificationStatus {
 }
 
 /// Publisher/developer information
-#[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
 pub struct Publisher {
     pub id: Uuid,
     pub stellar_address: String,
@@ -80,9 +216,168 @@ pub struct Publisher {
     pub email: Option<String>,
     pub github_url: Option<String>,
     pub website: Option<String>,
+    /// Shown in place of `username` where set
+    #[serde(default)]
+    pub display_name: Option<String>,
+    #[serde(default)]
+    pub bio: Option<String>,
+    #[serde(default)]
+    pub avatar_url: Option<String>,
+    pub created_at: DateTime<Utc>,
+    #[serde(default)]
+    pub updated_at: Option<DateTime<Utc>>,
+    /// Set while the account is deactivated
+    #[serde(default)]
+    pub deactivated_at: Option<DateTime<Utc>>,
+}
+
+/// Profile fields a publisher may edit. Omitted fields are left as they
+/// are and an empty string clears one; anything else in the body, such as
+/// `id` or `created_at`, is ignored.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UpdatePublisherRequest {
+    pub display_name: Option<String>,
+    pub bio: Option<String>,
+    pub website: Option<String>,
+    pub avatar_url: Option<String>,
+}
+
+/// Role of a publisher within an organization, and so on its contracts.
+/// Owners manage members and may deprecate, yank or transfer; maintainers
+/// publish versions; viewers only read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum OrganizationRole {
+    Owner,
+    Maintainer,
+    Viewer,
+}
+
+impl OrganizationRole {
+    fn rank(self) -> u8 {
+        match self {
+            OrganizationRole::Viewer => 0,
+            OrganizationRole::Maintainer => 1,
+            OrganizationRole::Owner => 2,
+        }
+    }
+
+    /// Whether this role grants everything `required` does
+    pub fn allows(self, required: OrganizationRole) -> bool {
+        self.rank() >= required.rank()
+    }
+}
+
+impl std::fmt::Display for OrganizationRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrganizationRole::Owner => write!(f, "owner"),
+            OrganizationRole::Maintainer => write!(f, "maintainer"),
+            OrganizationRole::Viewer => write!(f, "viewer"),
+        }
+    }
+}
+
+/// A team of publishers that owns contracts together
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct Organization {
+    pub id: Uuid,
+    pub name: String,
+    pub created_at: DateTime<Utc>,
+}
+
+/// Membership of a publisher in an organization
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct OrganizationMember {
+    pub organization_id: Uuid,
+    pub publisher_id: Uuid,
+    pub role: OrganizationRole,
     pub created_at: DateTime<Utc>,
 }
 
+/// Request to create an organization; the creator becomes its first owner
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateOrganizationRequest {
+    pub name: String,
+    /// First owner; required when an admin key creates the organization,
+    /// ignored for publisher keys
+    #[serde(default)]
+    pub owner_id: Option<Uuid>,
+}
+
+/// Add a publisher to an organization, or change their role
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct SetOrganizationMemberRequest {
+    pub publisher_id: Uuid,
+    pub role: OrganizationRole,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type, ToSchema)]
+#[sqlx(type_name = "text", rename_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum TransferStatus {
+    Pending,
+    Accepted,
+    Cancelled,
+    Expired,
+}
+
+/// A proposed handover of a contract to another publisher or organization
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct ContractTransfer {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub from_publisher_id: Uuid,
+    pub from_organization_id: Option<Uuid>,
+    pub to_publisher_id: Option<Uuid>,
+    pub to_organization_id: Option<Uuid>,
+    pub status: TransferStatus,
+    pub initiated_by: Option<Uuid>,
+    pub accepted_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+    pub resolved_at: Option<DateTime<Utc>>,
+}
+
+/// Start a transfer; exactly one of the targets must be set
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct CreateTransferRequest {
+    #[serde(default)]
+    pub to_publisher_id: Option<Uuid>,
+    #[serde(default)]
+    pub to_organization_id: Option<Uuid>,
+}
+
+/// An on-chain address a registry entry is deployed at
+#[derive(Debug, Clone, Serialize, Deserialize, FromRow, ToSchema)]
+pub struct DeploymentAddress {
+    pub id: Uuid,
+    pub contract_id: Uuid,
+    pub network: Network,
+    pub address: String,
+    /// The address was checked and runs one of the contract's registered WASM hashes
+    pub verified: bool,
+    /// Hex WASM hash Soroban RPC reported at the last check
+    pub on_chain_hash: Option<String>,
+    pub verified_at: Option<DateTime<Utc>>,
+    pub created_by: Option<Uuid>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+/// Record a deployment address; recording one again replaces its check result
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct RecordDeploymentRequest {
+    pub address: String,
+    /// Defaults to the contract's own network
+    #[serde(default)]
+    pub network: Option<Network>,
+    /// Check the address against the registered WASM hash via Soroban RPC
+    #[serde(default)]
+    pub verify: bool,
+}
+
 /// Contract interaction statistics
 #[derive(Debug, Clone, Serialize, Deserialize, FromRow)]
 pub struct ContractStats {
@@ -131,7 +426,7 @@ pub struct GraphResponse {
 }
 
 /// Request to publish a new contract
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct PublishRequest {
     pub contract_id: String,
     pub name: String,
@@ -144,13 +439,50 @@ pub struct PublishRequest {
     // Dependencies (new field)
     #[serde(default)]
     pub dependencies: Vec<DependencyDeclaration>,
+    /// Version to create alongside the contract; required when `wasm` is set
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Compiled WASM, base64 encoded
+    #[serde(default)]
+    pub wasm: Option<String>,
+    /// Free-form metadata object; must satisfy the registered JSON Schema
+    #[serde(default)]
+    #[schema(value_type = Option<Object>)]
+    pub metadata: Option<serde_json::Value>,
+    /// README markdown; replaces the stored README when present
+    #[serde(default)]
+    pub readme: Option<String>,
+    /// Markdown change notes for `version`, stored as its release notes
+    #[serde(default)]
+    pub changelog: Option<String>,
+    /// Hex ed25519 signature over the raw (decoded) `wasm` bytes
+    #[serde(default)]
+    pub signature: Option<String>,
+    /// Hex ed25519 public key that produced `signature`
+    #[serde(default)]
+    pub public_key: Option<String>,
+    /// Publish on behalf of this organization; the publisher must be a member
+    #[serde(default)]
+    pub organization_id: Option<Uuid>,
+    /// Visibility of a new contract (public by default). Republishing only
+    /// changes it when set.
+    #[serde(default)]
+    pub visibility: Option<ContractVisibility>,
+    /// Toolchain used to build `wasm`, stored on the new version
+    #[serde(default)]
+    pub build_info: Option<BuildInfo>,
 }
 
 /// Dependency declaration in publish request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct DependencyDeclaration {
     pub name: String,
     pub version_constraint: String,
+    /// On-chain ID of the dependency, on the publishing contract's network.
+    /// Without it, `name` is only matched against the publisher's own
+    /// contracts.
+    #[serde(default)]
+    pub contract_id: Option<String>,
 }
 
 /// Contract dependency record (database row)
@@ -175,12 +507,32 @@ pub struct DependencyTreeNode {
 }
 
 /// Request to verify a contract
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct VerifyRequest {
     pub contract_id: String,
     pub source_code: String,
+    #[schema(value_type = Object)]
     pub build_params: serde_json::Value,
     pub compiler_version: String,
+    /// When set, `contract_id` is checked against what is deployed on this network
+    #[serde(default)]
+    pub network: Option<Network>,
+    /// Version whose artifact is compared; defaults to the latest
+    #[serde(default)]
+    pub version: Option<String>,
+}
+
+/// Outcome of comparing a stored artifact with the deployed contract
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct VerificationResult {
+    pub verified: bool,
+    pub contract_id: String,
+    pub network: Network,
+    pub version: String,
+    /// Hex WASM hash reported by Soroban RPC
+    pub on_chain_hash: String,
+    /// Hex sha256 of the artifact stored in the registry
+    pub computed_hash: String,
 }
 
 /// Search/filter parameters for contracts
@@ -883,6 +1235,33 @@ pub struct VersionDiff {
     pub modified:     Vec<FieldChange>,
 }
 
+/// How one field differs between two history entries. Two JSON objects are
+/// compared key by key; anything else is reported as a whole value, with the
+/// side where the field is absent left out.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum FieldDiff {
+    Nested {
+        changes: std::collections::BTreeMap<String, FieldDiff>,
+    },
+    Changed {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<serde_json::Value>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        to: Option<serde_json::Value>,
+    },
+}
+
+/// Response for GET /api/contracts/:id/history/diff?from=&to=
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryDiff {
+    pub contract_id: Uuid,
+    pub from:        Uuid,
+    pub to:          Uuid,
+    /// Changed fields only, keyed by field name
+    pub changes:     std::collections::BTreeMap<String, FieldDiff>,
+}
+
 /// Request body for POST /api/contracts/:id/rollback/:snapshot_id
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RollbackRequest {
@@ -890,7 +1269,6 @@ pub struct RollbackRequest {
     pub changed_by: String,
 }
 
-/// Paginated response for audit log
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProposalWithSignatures {
     pub proposal: DeployProposal,
@@ -899,12 +1277,6 @@ pub struct ProposalWithSignatures {
     /// How many more signatures are needed to reach the threshold
     pub signatures_needed: i32,
 }
-pub struct AuditLogPage {
-    pub items:       Vec<ContractAuditLog>,
-    pub total:       i64,
-    pub page:        i64,
-    pub total_pages: i64,
-}
 
 // ════════════════════════════════════════════════════════════════════════════
 // Config Management types
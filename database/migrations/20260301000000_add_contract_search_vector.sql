@@ -0,0 +1,12 @@
+-- Full-text search over contract name and description.
+-- Names are weighted above descriptions so ts_rank favours title matches.
+
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS search_vector tsvector
+    GENERATED ALWAYS AS (
+        setweight(to_tsvector('simple', coalesce(name, '')), 'A') ||
+        setweight(to_tsvector('simple', coalesce(description, '')), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS idx_contracts_search_vector
+    ON contracts USING GIN (search_vector);
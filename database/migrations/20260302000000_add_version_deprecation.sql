@@ -0,0 +1,8 @@
+-- Allow publishers to deprecate a version without deleting it.
+
+ALTER TABLE contract_versions
+    ADD COLUMN IF NOT EXISTS deprecated_at TIMESTAMPTZ,
+    ADD COLUMN IF NOT EXISTS deprecation_reason TEXT,
+    ADD COLUMN IF NOT EXISTS superseded_by VARCHAR(50),
+    ADD COLUMN IF NOT EXISTS deprecated BOOLEAN
+        GENERATED ALWAYS AS (deprecated_at IS NOT NULL) STORED;
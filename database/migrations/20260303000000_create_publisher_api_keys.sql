@@ -0,0 +1,17 @@
+-- API keys used to authenticate publishers on write endpoints.
+-- Only an argon2 hash of the secret is stored; key_prefix is the public
+-- lookup handle embedded in the raw key (sr_<prefix>_<secret>).
+
+CREATE TABLE IF NOT EXISTS publisher_api_keys (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    publisher_id UUID NOT NULL REFERENCES publishers(id) ON DELETE CASCADE,
+    key_prefix VARCHAR(16) NOT NULL UNIQUE,
+    key_hash TEXT NOT NULL,
+    label VARCHAR(255),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_used_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ
+);
+
+CREATE INDEX IF NOT EXISTS idx_publisher_api_keys_publisher
+    ON publisher_api_keys (publisher_id);
@@ -0,0 +1,10 @@
+-- Compiled WASM for each published version. Kept out of contract_versions
+-- so listing versions never drags the bytecode along.
+
+CREATE TABLE IF NOT EXISTS contract_artifacts (
+    version_id UUID PRIMARY KEY REFERENCES contract_versions(id) ON DELETE CASCADE,
+    wasm BYTEA NOT NULL,
+    size_bytes BIGINT NOT NULL,
+    sha256 VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
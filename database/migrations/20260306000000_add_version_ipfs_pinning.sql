@@ -0,0 +1,7 @@
+-- IPFS pinning state for published artifacts. Both columns stay NULL when the
+-- registry runs without IPFS_API_URL.
+
+ALTER TABLE contract_versions
+    ADD COLUMN IF NOT EXISTS ipfs_cid VARCHAR(128),
+    ADD COLUMN IF NOT EXISTS ipfs_status VARCHAR(10)
+        CHECK (ipfs_status IN ('pending', 'pinned', 'failed'));
@@ -0,0 +1,17 @@
+-- Tags are compared case-insensitively: store them lowercased and without
+-- duplicates, and index them for `?tag=` filtering and the tag cloud.
+
+UPDATE contracts
+SET tags = ARRAY(
+    SELECT DISTINCT lower(btrim(t))
+    FROM unnest(tags) AS t
+    WHERE btrim(t) <> ''
+)
+WHERE tags IS NOT NULL;
+
+UPDATE contracts SET tags = '{}' WHERE tags IS NULL;
+
+ALTER TABLE contracts ALTER COLUMN tags SET DEFAULT '{}';
+ALTER TABLE contracts ALTER COLUMN tags SET NOT NULL;
+
+CREATE INDEX IF NOT EXISTS idx_contracts_tags ON contracts USING GIN (tags);
@@ -0,0 +1,10 @@
+-- Publisher bookmarks ("stars") on contracts
+
+CREATE TABLE IF NOT EXISTS contract_stars (
+    publisher_id UUID NOT NULL REFERENCES publishers(id) ON DELETE CASCADE,
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (publisher_id, contract_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_stars_contract_id ON contract_stars(contract_id);
@@ -0,0 +1,13 @@
+-- Scan findings recorded per contract version, so findings can be compared
+-- between releases. Each scan appends a row; the latest one wins.
+
+CREATE TABLE IF NOT EXISTS contract_version_scans (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    version VARCHAR(50) NOT NULL,
+    findings JSONB NOT NULL DEFAULT '[]',
+    scanned_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_version_scans_lookup
+    ON contract_version_scans(contract_id, version, scanned_at DESC);
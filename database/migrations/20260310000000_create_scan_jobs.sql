@@ -0,0 +1,26 @@
+-- Background detector scans submitted via POST /api/scan
+
+CREATE TABLE IF NOT EXISTS scan_jobs (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    version VARCHAR(50),
+    source TEXT NOT NULL,
+    fail_on VARCHAR(10),
+    status TEXT NOT NULL DEFAULT 'queued'
+        CHECK (status IN ('queued', 'running', 'completed', 'failed')),
+    findings JSONB,
+    passed BOOLEAN,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    started_at TIMESTAMPTZ,
+    heartbeat_at TIMESTAMPTZ,
+    finished_at TIMESTAMPTZ
+);
+
+-- At most one in-flight job per contract+version; duplicate submissions
+-- resolve to the existing row.
+CREATE UNIQUE INDEX IF NOT EXISTS idx_scan_jobs_in_flight
+    ON scan_jobs (contract_id, (COALESCE(version, '')))
+    WHERE status IN ('queued', 'running');
+
+CREATE INDEX IF NOT EXISTS idx_scan_jobs_queue ON scan_jobs (created_at) WHERE status = 'queued';
@@ -0,0 +1,30 @@
+-- Version-level benchmark regression tracking
+
+-- Per-contract regression thresholds. `metric` is a full metric name
+-- (`transfer.p95_ms`), a stat applied to every method (`p95_ms`), or `*`
+-- for the contract-wide default.
+CREATE TABLE IF NOT EXISTS benchmark_thresholds (
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    metric VARCHAR(255) NOT NULL,
+    threshold_pct DOUBLE PRECISION NOT NULL CHECK (threshold_pct >= 0),
+    PRIMARY KEY (contract_id, metric)
+);
+
+-- One row per metric that regressed between two versions
+CREATE TABLE IF NOT EXISTS benchmark_regression_alerts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    metric VARCHAR(255) NOT NULL,
+    previous_version VARCHAR(50) NOT NULL,
+    version VARCHAR(50) NOT NULL,
+    previous_value DOUBLE PRECISION NOT NULL,
+    value DOUBLE PRECISION NOT NULL,
+    regression_pct DOUBLE PRECISION NOT NULL,
+    threshold_pct DOUBLE PRECISION NOT NULL,
+    resolved BOOLEAN NOT NULL DEFAULT FALSE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (contract_id, version, previous_version, metric)
+);
+
+CREATE INDEX IF NOT EXISTS idx_benchmark_regression_alerts_contract
+    ON benchmark_regression_alerts (contract_id, created_at DESC);
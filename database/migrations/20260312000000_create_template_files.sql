@@ -0,0 +1,9 @@
+-- Multi-file templates rendered by POST /api/templates/:slug/instantiate.
+-- Templates without rows here instantiate to `src/lib.rs` from `source_code`.
+
+CREATE TABLE IF NOT EXISTS template_files (
+    template_id UUID NOT NULL REFERENCES contract_templates(id) ON DELETE CASCADE,
+    path TEXT NOT NULL,
+    content TEXT NOT NULL,
+    PRIMARY KEY (template_id, path)
+);
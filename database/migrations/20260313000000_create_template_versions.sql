@@ -0,0 +1,32 @@
+-- Versioned templates: every published version keeps its own source,
+-- parameters and files so older versions stay instantiable.
+
+CREATE TABLE IF NOT EXISTS template_versions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_id UUID NOT NULL REFERENCES contract_templates(id) ON DELETE CASCADE,
+    version TEXT NOT NULL,
+    source_code TEXT NOT NULL,
+    parameters JSONB NOT NULL DEFAULT '[]',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (template_id, version)
+);
+
+CREATE TABLE IF NOT EXISTS template_version_files (
+    version_id UUID NOT NULL REFERENCES template_versions(id) ON DELETE CASCADE,
+    path TEXT NOT NULL,
+    content TEXT NOT NULL,
+    PRIMARY KEY (version_id, path)
+);
+
+-- Existing templates become their current version
+INSERT INTO template_versions (template_id, version, source_code, parameters, created_at)
+SELECT id, version, source_code, parameters, updated_at
+FROM contract_templates
+ON CONFLICT (template_id, version) DO NOTHING;
+
+INSERT INTO template_version_files (version_id, path, content)
+SELECT tv.id, tf.path, tf.content
+FROM template_files tf
+JOIN contract_templates t ON t.id = tf.template_id
+JOIN template_versions tv ON tv.template_id = t.id AND tv.version = t.version
+ON CONFLICT (version_id, path) DO NOTHING;
@@ -0,0 +1,18 @@
+-- Composite contract scores and the admin-configurable weights behind them
+
+CREATE TABLE IF NOT EXISTS scoring_weights (
+    id BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),
+    weights JSONB NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS contract_scores (
+    contract_id UUID PRIMARY KEY REFERENCES contracts(id) ON DELETE CASCADE,
+    composite DOUBLE PRECISION NOT NULL,
+    security DOUBLE PRECISION NOT NULL,
+    popularity DOUBLE PRECISION NOT NULL,
+    maintenance DOUBLE PRECISION NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_scores_composite ON contract_scores (composite DESC);
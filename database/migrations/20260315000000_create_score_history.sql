@@ -0,0 +1,14 @@
+-- One row per composite score computation, including unchanged recomputes
+
+CREATE TABLE IF NOT EXISTS score_history (
+    id BIGSERIAL PRIMARY KEY,
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    composite DOUBLE PRECISION NOT NULL,
+    security DOUBLE PRECISION NOT NULL,
+    popularity DOUBLE PRECISION NOT NULL,
+    maintenance DOUBLE PRECISION NOT NULL,
+    computed_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_score_history_contract_time
+    ON score_history (contract_id, computed_at);
@@ -0,0 +1,11 @@
+-- JSON Schemas for publisher-supplied contract metadata, one per category.
+-- The '_default' row applies to categories without their own schema.
+
+CREATE TABLE IF NOT EXISTS metadata_schemas (
+    category TEXT PRIMARY KEY,
+    schema JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+ALTER TABLE contracts ADD COLUMN IF NOT EXISTS metadata JSONB;
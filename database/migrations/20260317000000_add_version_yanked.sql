@@ -0,0 +1,5 @@
+-- npm-style yank: the version stays downloadable but range resolution skips it
+
+ALTER TABLE contract_versions
+    ADD COLUMN IF NOT EXISTS yanked BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS yanked_at TIMESTAMPTZ;
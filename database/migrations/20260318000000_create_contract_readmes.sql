@@ -0,0 +1,7 @@
+-- Markdown README per contract, replaced on each publish that includes one
+
+CREATE TABLE IF NOT EXISTS contract_readmes (
+    contract_id UUID PRIMARY KEY REFERENCES contracts(id) ON DELETE CASCADE,
+    markdown TEXT NOT NULL,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,4 @@
+-- Per-version download counter, incremented in batches by the API
+
+ALTER TABLE contract_versions
+    ADD COLUMN IF NOT EXISTS download_count BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Daily download rollup, written by the API's batched download flush and
+-- read by the trending endpoint
+
+ALTER TABLE analytics_daily_aggregates
+    ADD COLUMN IF NOT EXISTS download_count INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,9 @@
+-- Marks jobs queued by the scheduled re-scan rather than a client, so their
+-- number can be capped and new critical findings alerted on
+
+ALTER TABLE scan_jobs
+    ADD COLUMN IF NOT EXISTS rescan BOOLEAN NOT NULL DEFAULT FALSE;
+
+CREATE INDEX IF NOT EXISTS idx_scan_jobs_version_latest
+    ON scan_jobs (contract_id, version, created_at DESC)
+    WHERE version IS NOT NULL;
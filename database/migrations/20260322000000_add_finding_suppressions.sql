@@ -0,0 +1,16 @@
+-- Stored suppressions: finding fingerprints a contract owner has silenced,
+-- each with the reason it was accepted
+
+CREATE TABLE IF NOT EXISTS finding_suppressions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    fingerprint TEXT NOT NULL,
+    reason TEXT NOT NULL CHECK (length(trim(reason)) > 0),
+    created_by UUID REFERENCES publishers(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (contract_id, fingerprint)
+);
+
+-- Findings silenced during a scan, kept apart from the ones that count
+ALTER TABLE scan_jobs
+    ADD COLUMN IF NOT EXISTS suppressed JSONB;
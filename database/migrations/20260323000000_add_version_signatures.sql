@@ -0,0 +1,6 @@
+-- Publisher ed25519 signatures over version artifacts. `signed` is only set
+-- once the signature verified against the stored key.
+
+ALTER TABLE contract_versions
+    ADD COLUMN IF NOT EXISTS signed BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS signing_key TEXT;
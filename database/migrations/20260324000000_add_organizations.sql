@@ -0,0 +1,26 @@
+-- Organizations: teams of publishers that own contracts together
+
+CREATE TABLE IF NOT EXISTS organizations (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    name TEXT NOT NULL CHECK (length(trim(name)) > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_organizations_name ON organizations (LOWER(name));
+
+CREATE TABLE IF NOT EXISTS organization_members (
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    publisher_id UUID NOT NULL REFERENCES publishers(id) ON DELETE CASCADE,
+    role TEXT NOT NULL CHECK (role IN ('owner', 'member')),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (organization_id, publisher_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_organization_members_publisher
+    ON organization_members (publisher_id);
+
+-- A contract belongs to its publisher, or to an organization when set
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_contracts_organization ON contracts (organization_id);
@@ -0,0 +1,10 @@
+-- Organization roles become owner / maintainer / viewer; existing members
+-- keep publish rights as maintainers
+
+ALTER TABLE organization_members DROP CONSTRAINT IF EXISTS organization_members_role_check;
+
+UPDATE organization_members SET role = 'maintainer' WHERE role = 'member';
+
+ALTER TABLE organization_members
+    ADD CONSTRAINT organization_members_role_check
+    CHECK (role IN ('owner', 'maintainer', 'viewer'));
@@ -0,0 +1,28 @@
+-- Two-step ownership transfers: an owner proposes, the recipient accepts.
+-- Rows are kept after they resolve as the record of each handover.
+
+CREATE TABLE IF NOT EXISTS contract_transfers (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    from_publisher_id UUID NOT NULL REFERENCES publishers(id) ON DELETE CASCADE,
+    from_organization_id UUID REFERENCES organizations(id) ON DELETE SET NULL,
+    to_publisher_id UUID REFERENCES publishers(id) ON DELETE CASCADE,
+    to_organization_id UUID REFERENCES organizations(id) ON DELETE CASCADE,
+    status TEXT NOT NULL DEFAULT 'pending'
+        CHECK (status IN ('pending', 'accepted', 'cancelled', 'expired')),
+    initiated_by UUID REFERENCES publishers(id) ON DELETE SET NULL,
+    accepted_by UUID REFERENCES publishers(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL,
+    resolved_at TIMESTAMPTZ,
+    CHECK ((to_publisher_id IS NULL) <> (to_organization_id IS NULL))
+);
+
+-- At most one open transfer per contract
+CREATE UNIQUE INDEX IF NOT EXISTS idx_contract_transfers_pending
+    ON contract_transfers (contract_id) WHERE status = 'pending';
+
+CREATE INDEX IF NOT EXISTS idx_contract_transfers_to_publisher
+    ON contract_transfers (to_publisher_id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_contract_transfers_to_organization
+    ON contract_transfers (to_organization_id) WHERE status = 'pending';
@@ -0,0 +1,22 @@
+-- On-chain addresses a registry entry is deployed at, any number per network.
+-- Addresses whose WASM does not match the registry are kept unverified so
+-- operators can look into them.
+
+CREATE TABLE IF NOT EXISTS contract_deployment_addresses (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    network network_type NOT NULL,
+    address TEXT NOT NULL,
+    verified BOOLEAN NOT NULL DEFAULT FALSE,
+    -- WASM hash Soroban RPC reported at the last check; NULL when unchecked
+    -- or when nothing was deployed at the address
+    on_chain_hash TEXT,
+    verified_at TIMESTAMPTZ,
+    created_by UUID REFERENCES publishers(id) ON DELETE SET NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE (contract_id, network, address)
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_deployment_addresses_lookup
+    ON contract_deployment_addresses (network, address);
@@ -0,0 +1,6 @@
+-- Case-insensitive prefix lookups for search typeahead
+-- (`lower(name) LIKE 'abc%'`). text_pattern_ops lets the planner use the
+-- index whatever the database collation.
+
+CREATE INDEX IF NOT EXISTS idx_contracts_name_prefix
+    ON contracts (lower(name) text_pattern_ops);
@@ -0,0 +1,7 @@
+-- Typo-tolerant search: `?q=...&fuzzy=true` matches names by trigram
+-- similarity.
+
+CREATE EXTENSION IF NOT EXISTS pg_trgm;
+
+CREATE INDEX IF NOT EXISTS idx_contracts_name_trgm
+    ON contracts USING GIN (name gin_trgm_ops);
@@ -0,0 +1,7 @@
+-- Pinned benchmark baseline per contract. While set, new runs and version
+-- regression checks compare against this version instead of the previous one.
+CREATE TABLE IF NOT EXISTS benchmark_baselines (
+    contract_id UUID PRIMARY KEY REFERENCES contracts(id) ON DELETE CASCADE,
+    version VARCHAR(50) NOT NULL,
+    set_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
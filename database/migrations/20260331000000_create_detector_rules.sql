@@ -0,0 +1,9 @@
+-- Detector rules managed at runtime through /api/config/detector-rules,
+-- checked on every scan alongside the built-ins and DETECTOR_RULES_PATH
+CREATE TABLE IF NOT EXISTS detector_rules (
+    id VARCHAR(100) PRIMARY KEY,
+    severity VARCHAR(20) NOT NULL,
+    pattern TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
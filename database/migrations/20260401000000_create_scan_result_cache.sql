@@ -0,0 +1,14 @@
+-- Detector results keyed by what they depend on, so re-scanning unchanged
+-- source with unchanged rules reuses the stored findings
+
+CREATE TABLE IF NOT EXISTS scan_result_cache (
+    artifact_sha256 CHAR(64) NOT NULL,
+    rule_set_version CHAR(64) NOT NULL,
+    suppressions_sha256 CHAR(64) NOT NULL,
+    scan JSONB NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (artifact_sha256, rule_set_version, suppressions_sha256)
+);
+
+ALTER TABLE scan_jobs
+    ADD COLUMN IF NOT EXISTS from_cache BOOLEAN NOT NULL DEFAULT FALSE;
@@ -0,0 +1,14 @@
+-- Known-bad dependency versions, managed via /api/config/dependency-advisories.
+-- Dependency scans report every advisory whose range overlaps a declared version.
+
+CREATE TABLE IF NOT EXISTS dependency_advisories (
+    id VARCHAR(100) PRIMARY KEY,
+    package_name VARCHAR(255) NOT NULL,
+    affected VARCHAR(255) NOT NULL,
+    severity VARCHAR(20) NOT NULL,
+    summary TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_dependency_advisories_package
+    ON dependency_advisories (package_name);
@@ -0,0 +1,10 @@
+-- Forks: POST /api/templates/:slug/fork copies a template into a new one
+-- owned by the caller. Private templates are only visible to their owner.
+
+ALTER TABLE contract_templates
+    ADD COLUMN IF NOT EXISTS owner_publisher_id UUID REFERENCES publishers(id) ON DELETE SET NULL,
+    ADD COLUMN IF NOT EXISTS is_private BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS forked_from UUID REFERENCES contract_templates(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_contract_templates_forked_from
+    ON contract_templates(forked_from) WHERE forked_from IS NOT NULL;
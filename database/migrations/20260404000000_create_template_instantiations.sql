@@ -0,0 +1,26 @@
+-- Template usage. Instantiations are counted in memory and flushed in
+-- batches: each flush adds to `contract_templates.instantiations` and writes
+-- one raw event per template, which the aggregation task rolls up per day.
+
+ALTER TABLE contract_templates
+    ADD COLUMN IF NOT EXISTS instantiations BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS template_instantiation_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    template_id UUID NOT NULL REFERENCES contract_templates(id) ON DELETE CASCADE,
+    count BIGINT NOT NULL CHECK (count > 0),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_template_instantiation_events_template_created
+    ON template_instantiation_events(template_id, created_at);
+CREATE INDEX IF NOT EXISTS idx_template_instantiation_events_created_at
+    ON template_instantiation_events(created_at);
+
+-- Daily rollups (permanent retention)
+CREATE TABLE IF NOT EXISTS template_instantiation_daily (
+    template_id UUID NOT NULL REFERENCES contract_templates(id) ON DELETE CASCADE,
+    date DATE NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (template_id, date)
+);
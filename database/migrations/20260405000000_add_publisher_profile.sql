@@ -0,0 +1,8 @@
+-- Publisher profiles: PUT /api/publishers/:id edits these, and the feed
+-- shows `display_name` in place of `username` where it is set.
+
+ALTER TABLE publishers
+    ADD COLUMN IF NOT EXISTS display_name VARCHAR(100),
+    ADD COLUMN IF NOT EXISTS bio TEXT,
+    ADD COLUMN IF NOT EXISTS avatar_url VARCHAR(500),
+    ADD COLUMN IF NOT EXISTS updated_at TIMESTAMPTZ;
@@ -0,0 +1,33 @@
+-- Publisher deactivation: a deactivated publisher's API keys stop
+-- authenticating and they cannot publish. Nothing is deleted, so
+-- reactivating restores everything. Their contracts stay readable and are
+-- flagged so consumers can tell.
+
+ALTER TABLE publishers
+    ADD COLUMN IF NOT EXISTS deactivated_at TIMESTAMPTZ;
+
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS publisher_deactivated BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- A contract follows its current publisher, however it came to them:
+-- publish, import or ownership transfer. Deactivating or reactivating a
+-- publisher updates their existing contracts directly.
+CREATE OR REPLACE FUNCTION sync_publisher_deactivated()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.publisher_deactivated := EXISTS (
+        SELECT 1 FROM publishers
+        WHERE id = NEW.publisher_id AND deactivated_at IS NOT NULL
+    );
+    RETURN NEW;
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS sync_contracts_publisher_deactivated ON contracts;
+CREATE TRIGGER sync_contracts_publisher_deactivated
+    BEFORE INSERT OR UPDATE OF publisher_id ON contracts
+    FOR EACH ROW EXECUTE FUNCTION sync_publisher_deactivated();
+
+UPDATE contracts c SET publisher_deactivated = (p.deactivated_at IS NOT NULL)
+FROM publishers p
+WHERE p.id = c.publisher_id;
@@ -0,0 +1,9 @@
+-- Contract visibility: private contracts are hidden from listings, search,
+-- feeds and stats, and only readable by their publisher or organization.
+
+ALTER TABLE contracts
+    ADD COLUMN IF NOT EXISTS visibility TEXT NOT NULL DEFAULT 'public'
+        CHECK (visibility IN ('public', 'private'));
+
+CREATE INDEX IF NOT EXISTS idx_contracts_private
+    ON contracts (publisher_id) WHERE visibility = 'private';
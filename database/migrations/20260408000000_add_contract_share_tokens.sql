@@ -0,0 +1,17 @@
+-- Share tokens grant read access to one private contract without making it
+-- public. Only a sha256 hash of the token is stored; the raw value is shown
+-- once when it is minted.
+
+CREATE TABLE IF NOT EXISTS contract_share_tokens (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    contract_id UUID NOT NULL REFERENCES contracts(id) ON DELETE CASCADE,
+    token_hash TEXT NOT NULL UNIQUE,
+    label VARCHAR(255),
+    created_by UUID REFERENCES publishers(id) ON DELETE SET NULL,
+    expires_at TIMESTAMPTZ,
+    revoked_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_contract_share_tokens_contract
+    ON contract_share_tokens (contract_id, created_at DESC);
@@ -0,0 +1,40 @@
+-- Admin audit log: privileged operator and account actions, each tied to
+-- the request that made it. Indexed for the filters of
+-- GET /api/admin/audit-log, and append-only.
+
+CREATE TABLE IF NOT EXISTS admin_audit_log (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    -- `admin` or the acting publisher's id
+    actor TEXT NOT NULL,
+    action TEXT NOT NULL,
+    target_type TEXT NOT NULL,
+    target_id TEXT NOT NULL,
+    details JSONB,
+    request_id TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_created_at
+    ON admin_audit_log (created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_actor
+    ON admin_audit_log (actor, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS idx_admin_audit_log_action
+    ON admin_audit_log (action, created_at DESC);
+
+-- Entries are never edited or removed, not even by the API's own role
+CREATE OR REPLACE FUNCTION admin_audit_log_append_only()
+RETURNS TRIGGER AS $$
+BEGIN
+    RAISE EXCEPTION 'admin_audit_log is append-only';
+END;
+$$ language 'plpgsql';
+
+DROP TRIGGER IF EXISTS admin_audit_log_append_only ON admin_audit_log;
+CREATE TRIGGER admin_audit_log_append_only BEFORE UPDATE OR DELETE ON admin_audit_log
+    FOR EACH ROW EXECUTE FUNCTION admin_audit_log_append_only();
+
+DROP TRIGGER IF EXISTS admin_audit_log_no_truncate ON admin_audit_log;
+CREATE TRIGGER admin_audit_log_no_truncate BEFORE TRUNCATE ON admin_audit_log
+    FOR EACH STATEMENT EXECUTE FUNCTION admin_audit_log_append_only();
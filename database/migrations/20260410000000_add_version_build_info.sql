@@ -0,0 +1,6 @@
+-- Toolchain metadata reported when a version is published: compiler
+-- version, target and optimization flags. NULL for versions published
+-- without it.
+
+ALTER TABLE contract_versions
+    ADD COLUMN IF NOT EXISTS build_info JSONB;
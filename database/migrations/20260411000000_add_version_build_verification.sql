@@ -0,0 +1,6 @@
+-- Latest reproducible-build check of each version: the claimed source
+-- reference, both WASM hashes and whether they matched. NULL until a
+-- rebuild is requested.
+
+ALTER TABLE contract_versions
+    ADD COLUMN IF NOT EXISTS build_verification JSONB;
@@ -0,0 +1,5 @@
+-- Function search matches `abi -> 'functions' @> '[{"name": ...}]'`, which
+-- this index serves.
+
+CREATE INDEX IF NOT EXISTS idx_contract_abis_functions
+    ON contract_abis USING GIN ((abi -> 'functions') jsonb_path_ops);
@@ -0,0 +1,19 @@
+-- Batches of scan jobs submitted together via POST /api/scan/batch. Each
+-- item points at the job scanning it; items that could not be queued keep
+-- the reason instead.
+
+CREATE TABLE IF NOT EXISTS scan_batches (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    fail_on VARCHAR(10),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE TABLE IF NOT EXISTS scan_batch_items (
+    batch_id UUID NOT NULL REFERENCES scan_batches(id) ON DELETE CASCADE,
+    position INTEGER NOT NULL,
+    contract_id UUID NOT NULL,
+    version VARCHAR(50),
+    job_id UUID REFERENCES scan_jobs(id) ON DELETE SET NULL,
+    error TEXT,
+    PRIMARY KEY (batch_id, position)
+);
@@ -0,0 +1,22 @@
+-- Optional completion callback for scan jobs: the finished job is POSTed to
+-- callback_url, signed with callback_secret, and each delivery is recorded.
+
+ALTER TABLE scan_jobs
+    ADD COLUMN IF NOT EXISTS callback_url VARCHAR(2048),
+    ADD COLUMN IF NOT EXISTS callback_secret TEXT;
+
+CREATE TABLE IF NOT EXISTS scan_callback_deliveries (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    job_id UUID NOT NULL REFERENCES scan_jobs(id) ON DELETE CASCADE,
+    url VARCHAR(2048) NOT NULL,
+    event VARCHAR(64) NOT NULL,
+    payload JSONB NOT NULL,
+    succeeded BOOLEAN NOT NULL,
+    attempts INTEGER NOT NULL,
+    status_code INTEGER,
+    error TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_scan_callback_deliveries_job
+    ON scan_callback_deliveries (job_id, created_at DESC);
@@ -0,0 +1,4 @@
+-- Cached scans are pruned by age as new ones are stored
+
+CREATE INDEX IF NOT EXISTS idx_scan_result_cache_created_at
+    ON scan_result_cache (created_at);
@@ -62,6 +62,42 @@ export interface PaginatedResponse<T> {
   total_pages: number;
 }
 
+/** Envelope of the API's limit/offset list endpoints */
+export interface Paginated<T> {
+  items: T[];
+  /** Rows matching the request, across every page */
+  total: number;
+  limit: number;
+  offset: number;
+  has_more: boolean;
+}
+
+export interface PageParams {
+  limit?: number;
+  offset?: number;
+}
+
+function pageQuery(params?: PageParams): string {
+  const queryParams = new URLSearchParams();
+  if (params?.limit) queryParams.append("limit", String(params.limit));
+  if (params?.offset) queryParams.append("offset", String(params.offset));
+  const query = queryParams.toString();
+  return query ? `?${query}` : "";
+}
+
+function mockPage<T>(all: T[], params?: PageParams): Paginated<T> {
+  const limit = params?.limit || 20;
+  const offset = params?.offset || 0;
+  const items = all.slice(offset, offset + limit);
+  return {
+    items,
+    total: all.length,
+    limit,
+    offset,
+    has_more: offset + items.length < all.length,
+  };
+}
+
 export interface DependencyTreeNode {
   contract_id: string;
   name: string;
@@ -294,16 +330,21 @@ export const api = {
     return response.json();
   },
 
-  async getContractVersions(id: string): Promise<ContractVersion[]> {
+  async getContractVersions(
+    id: string,
+    params?: PageParams,
+  ): Promise<Paginated<ContractVersion>> {
     if (USE_MOCKS) {
       return new Promise((resolve) => {
         setTimeout(() => {
-          resolve(MOCK_VERSIONS[id] || []);
+          resolve(mockPage(MOCK_VERSIONS[id] || [], params));
         }, 300);
       });
     }
 
-    const response = await fetch(`${API_URL}/api/contracts/${id}/versions`);
+    const response = await fetch(
+      `${API_URL}/api/contracts/${id}/versions${pageQuery(params)}`,
+    );
     if (!response.ok) throw new Error("Failed to fetch contract versions");
     return response.json();
   },
@@ -344,14 +385,22 @@ export const api = {
     return response.json();
   },
 
-  async getPublisherContracts(id: string): Promise<Contract[]> {
+  async getPublisherContracts(
+    id: string,
+    params?: PageParams,
+  ): Promise<Paginated<Contract>> {
     if (USE_MOCKS) {
       return Promise.resolve(
-        MOCK_CONTRACTS.filter((c) => c.publisher_id === id),
+        mockPage(
+          MOCK_CONTRACTS.filter((c) => c.publisher_id === id),
+          params,
+        ),
       );
     }
 
-    const response = await fetch(`${API_URL}/api/publishers/${id}/contracts`);
+    const response = await fetch(
+      `${API_URL}/api/publishers/${id}/contracts${pageQuery(params)}`,
+    );
     if (!response.ok) throw new Error("Failed to fetch publisher contracts");
     return response.json();
   },